@@ -1,6 +1,9 @@
 //! Solana Arbitrage API Server
 //!
-//! REST and WebSocket API for the Arbitrage Dashboard
+//! REST and WebSocket API for the Arbitrage Dashboard. REST endpoints live
+//! under `/api/v1` (the unversioned `/api` paths remain as aliases) and are
+//! described by the OpenAPI document at `/api/v1/openapi.json`, which
+//! `api --openapi` also prints.
 
 use axum::{
     extract::{Path, Query, State},
@@ -15,19 +18,26 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, Level};
+use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 use solana_arb_core::{
     arbitrage::ArbitrageDetector,
     config::Config,
     dex::{jupiter::JupiterProvider, orca::OrcaProvider, raydium::RaydiumProvider, DexProvider},
-    ArbitrageConfig, DexType, PriceData, TokenPair,
+    rate_limiter::{shared::SharedBudget, RateLimiter},
+    ArbitrageConfig, ArbitrageOpportunity, DexType, PriceData, TokenPair,
 };
 use tokio::sync::broadcast;
 
+mod openapi;
 mod ws;
-use solana_arb_core::history::HistoryAnalyzer;
+use solana_arb_core::history::{
+    rotation::DateRange, AnalysisReport, DailyPnl, HistoryAnalyzer, TradeRecord,
+};
+use solana_arb_core::fx::{self, FxRate};
+use solana_arb_core::ledger::{Ledger, MonthlyStatement};
 use ws::WebSocketMessage;
 
 /// Application state shared across handlers
@@ -49,7 +59,7 @@ pub struct AppState {
 }
 
 /// DEX health status for monitoring
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 struct DexHealthStatus {
     name: String,
     last_success_at: Option<DateTime<Utc>>,
@@ -57,7 +67,8 @@ struct DexHealthStatus {
     status: String, // "green", "yellow", "red"
 }
 
-#[derive(Debug, Serialize)]
+/// Envelope of every successful REST response
+#[derive(Debug, Serialize, ToSchema)]
 struct ApiResponse<T> {
     success: bool,
     data: Option<T>,
@@ -72,26 +83,154 @@ impl<T: Serialize> ApiResponse<T> {
             error: None,
         }
     }
+}
+
+/// Envelope of every failed REST response: `success` is false and `data`
+/// null
+#[derive(Debug, Serialize, ToSchema)]
+struct ApiError {
+    success: bool,
+    #[schema(value_type = Option<Object>)]
+    data: Option<()>,
+    error: String,
+}
 
-    fn error(message: impl Into<String>) -> ApiResponse<()> {
-        ApiResponse {
+impl ApiError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
             success: false,
             data: None,
-            error: Some(message.into()),
+            error: message.into(),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// `GET /health` data
+#[derive(Debug, Serialize, ToSchema)]
+struct HealthData {
+    status: String,
+    version: String,
+}
+
+/// `GET /api/v1/config` data
+#[derive(Debug, Serialize, ToSchema)]
+struct ConfigData {
+    min_profit_threshold: f64,
+    api_port: u16,
+    log_level: String,
+}
+
+/// `GET /api/v1/status` data
+#[derive(Debug, Serialize, ToSchema)]
+struct StatusData {
+    dry_run: bool,
+    bot_running: bool,
+    simulated_pnl: f64,
+    simulated_trades: u32,
+    heartbeat_count: u64,
+    last_scan_at: DateTime<Utc>,
+    dex_health: Vec<DexHealthStatus>,
+    max_price_age_seconds: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct PairQuery {
+    /// Only prices with this base token
     base: Option<String>,
+    /// Only prices with this quote token
     quote: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct HistoryQuery {
+    /// RFC 3339, inclusive
+    from: Option<DateTime<Utc>>,
+    /// RFC 3339, inclusive
+    to: Option<DateTime<Utc>>,
+}
+
+/// Trades returned per page unless `limit` says otherwise
+const DEFAULT_TRADES_LIMIT: usize = 50;
+/// Most trades returned per page
+const MAX_TRADES_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TradesQuery {
+    /// RFC 3339, inclusive
+    from: Option<DateTime<Utc>>,
+    /// RFC 3339, inclusive
+    to: Option<DateTime<Utc>>,
+    /// Trades per page, at most 500 (default 50)
+    limit: Option<usize>,
+    /// Trades to skip, newest first
+    offset: Option<usize>,
+}
+
+/// `GET /api/v1/trades` data
+#[derive(Debug, Serialize, ToSchema)]
+struct TradePage {
+    trades: Vec<TradeRecord>,
+    /// Trades in the range, across all pages
+    total: usize,
+    limit: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StatementsQuery {
+    /// "YYYY-MM"
+    month: Option<String>,
+    /// Capital account id
+    account: Option<String>,
+}
+
+/// Investor capital accounts and their deposits/withdrawals
+const CAPITAL_ACCOUNTS_PATH: &str = "config/capital_accounts.json";
+
+/// History file of the mode the server runs in
+fn history_file(state: &AppState) -> &'static str {
+    if state.dry_run {
+        "data/history-sim.jsonl"
+    } else {
+        "data/history-live.jsonl"
+    }
+}
+
+/// Rate the bot last converted amounts at, when accounting in a currency
+/// other than USD. Reports keep their USD amounts without one.
+fn accounting_rate(config: &Config) -> Option<FxRate> {
+    if config.accounting_currency == fx::USD {
+        return None;
+    }
+    match FxRate::load(fx::FX_RATE_PATH) {
+        Ok(rate) if rate.currency == config.accounting_currency => Some(rate),
+        Ok(rate) => {
+            warn!(
+                "{} holds a {} rate, not {} - reports stay in USD",
+                fx::FX_RATE_PATH, rate.currency, config.accounting_currency
+            );
+            None
+        }
+        Err(e) => {
+            warn!("No {} rate in {}: {} - reports stay in USD", config.accounting_currency, fx::FX_RATE_PATH, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct OpportunitiesQuery {
+    /// Minimum net profit (%)
     min_profit: Option<f64>,
+    /// Maximum opportunities returned (default 50)
     limit: Option<usize>,
+    /// Only opportunities from this source ("detector" or a strategy's name)
+    source: Option<String>,
 }
 
 /// Default trading pairs
@@ -106,8 +245,56 @@ fn default_pairs() -> Vec<TokenPair> {
     ]
 }
 
+/// REST endpoints, versioned under `/api/v1`
+fn api_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        // Opportunities endpoints
+        .route("/opportunities", get(get_opportunities))
+        .route("/opportunities/:id", get(get_opportunity))
+        // Price endpoints
+        .route("/prices", get(get_prices))
+        .route("/prices/:pair", get(get_pair_prices))
+        // Config endpoints
+        .route("/config", get(get_config))
+        // Status endpoint (DRY_RUN visibility)
+        .route("/status", get(get_status))
+        // History analysis endpoint
+        .route("/history/analysis", get(get_history_analysis))
+        // Trade history endpoints
+        .route("/trades", get(get_trades))
+        .route("/trades/:id", get(get_trade))
+        .route("/pnl/daily", get(get_daily_pnl))
+        // Investor statements endpoint
+        .route("/ledger/statements", get(get_ledger_statements))
+}
+
+fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        // WebSocket endpoint
+        .route("/ws", get(ws::ws_handler))
+        // Health check
+        .route("/health", get(health_check))
+        .route("/api/v1/openapi.json", get(|| async { Json(openapi::ApiDoc::openapi()) }))
+        .nest("/api/v1", api_routes())
+        // Unversioned aliases for clients from before /api/v1
+        .nest("/api", api_routes())
+        // Add CORS for frontend
+        .layer(
+            CorsLayer::new()
+                .allow_origin(Any)
+                .allow_methods(Any)
+                .allow_headers(Any),
+        )
+        .with_state(state)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--openapi") {
+        println!("{}", openapi::ApiDoc::openapi().to_pretty_json()?);
+        return Ok(());
+    }
+
     // Load environment
     dotenvy::dotenv().ok();
 
@@ -123,9 +310,17 @@ async fn main() -> anyhow::Result<()> {
     // Load configuration
     let config = Config::from_env().unwrap_or_default();
 
+    // Jupiter requests count against the same budget as the bot's when
+    // SHARED_RATE_LIMITS is on
+    let jupiter_limiter = RateLimiter::per_second(config.jupiter_rate_limit);
+    let jupiter_limiter = match SharedBudget::from_config(&config, "jupiter") {
+        Some(budget) => jupiter_limiter.with_shared_budget(budget),
+        None => jupiter_limiter,
+    };
+
     // Initialize DEX providers
     let providers: Vec<Box<dyn DexProvider>> = vec![
-        Box::new(JupiterProvider::new()),
+        Box::new(JupiterProvider::new().with_rate_limiter(Arc::new(jupiter_limiter))),
         Box::new(RaydiumProvider::new()),
         Box::new(OrcaProvider::new()),
     ];
@@ -134,6 +329,7 @@ async fn main() -> anyhow::Result<()> {
     let arb_config = ArbitrageConfig {
         min_profit_threshold: rust_decimal::Decimal::try_from(config.min_profit_threshold)
             .unwrap_or_default(),
+        max_quote_skew_ms: config.max_quote_skew_ms,
         ..Default::default()
     };
     let detector = RwLock::new(ArbitrageDetector::new(arb_config));
@@ -162,6 +358,12 @@ async fn main() -> anyhow::Result<()> {
         tx: tx.clone(),
     });
 
+    // Relay the bot's trading events to WebSocket clients
+    #[cfg(unix)]
+    if let Some(path) = config.event_socket_path.clone() {
+        tokio::spawn(ws::relay_events(path, tx.clone()));
+    }
+
     // Spawn background price collector
     let collector_state = state.clone();
     tokio::spawn(async move {
@@ -216,7 +418,7 @@ async fn main() -> anyhow::Result<()> {
                             for opp in new_opps {
                                 let _ = collector_state
                                     .tx
-                                    .send(WebSocketMessage::NewOpportunity(opp));
+                                    .send(WebSocketMessage::NewOpportunity(Box::new(opp)));
                             }
                         }
                     }
@@ -249,32 +451,7 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    // Build router
-    let app = Router::new()
-        // WebSocket endpoint
-        .route("/ws", get(ws::ws_handler))
-        // Health check
-        .route("/health", get(health_check))
-        // Opportunities endpoints
-        .route("/api/opportunities", get(get_opportunities))
-        .route("/api/opportunities/:id", get(get_opportunity))
-        // Price endpoints
-        .route("/api/prices", get(get_prices))
-        .route("/api/prices/:pair", get(get_pair_prices))
-        // Config endpoints
-        .route("/api/config", get(get_config))
-        // Status endpoint (DRY_RUN visibility)
-        .route("/api/status", get(get_status))
-        // History analysis endpoint
-        .route("/api/history/analysis", get(get_history_analysis))
-        // Add CORS for frontend
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any),
-        )
-        .with_state(state);
+    let app = router(state);
 
     // Start server
     let addr = format!("0.0.0.0:{}", config.api_port);
@@ -316,18 +493,31 @@ async fn validate_dex_coverage(state: &Arc<AppState>) {
 }
 
 /// Health check endpoint
-async fn health_check() -> impl IntoResponse {
-    Json(ApiResponse::success(serde_json::json!({
-        "status": "healthy",
-        "version": env!("CARGO_PKG_VERSION"),
-    })))
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses((status = 200, description = "Server is up", body = ApiResponse<HealthData>))
+)]
+async fn health_check() -> Json<ApiResponse<HealthData>> {
+    Json(ApiResponse::success(HealthData {
+        status: "healthy".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    }))
 }
 
 /// Get current arbitrage opportunities
+#[utoipa::path(
+    get,
+    path = "/api/v1/opportunities",
+    tag = "opportunities",
+    params(OpportunitiesQuery),
+    responses((status = 200, description = "Opportunities, best first", body = ApiResponse<Vec<ArbitrageOpportunity>>))
+)]
 async fn get_opportunities(
     State(state): State<Arc<AppState>>,
     Query(params): Query<OpportunitiesQuery>,
-) -> impl IntoResponse {
+) -> Json<ApiResponse<Vec<ArbitrageOpportunity>>> {
     let detector = state.detector.read().await;
     let mut opportunities = detector.find_all_opportunities();
 
@@ -338,6 +528,10 @@ async fn get_opportunities(
         });
     }
 
+    if let Some(source) = &params.source {
+        opportunities.retain(|o| o.source_label().eq_ignore_ascii_case(source));
+    }
+
     // Limit results
     let limit = params.limit.unwrap_or(50);
     opportunities.truncate(limit);
@@ -346,6 +540,17 @@ async fn get_opportunities(
 }
 
 /// Get a specific opportunity by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/opportunities/{id}",
+    tag = "opportunities",
+    params(("id" = String, Path, description = "Opportunity UUID")),
+    responses(
+        (status = 200, description = "The opportunity", body = ApiResponse<ArbitrageOpportunity>),
+        (status = 400, description = "Invalid UUID", body = ApiError),
+        (status = 404, description = "No current opportunity with this id", body = ApiError),
+    )
+)]
 async fn get_opportunity(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
@@ -360,24 +565,31 @@ async fn get_opportunity(
             } else {
                 (
                     StatusCode::NOT_FOUND,
-                    Json(ApiResponse::<()>::error("Opportunity not found")),
+                    Json(ApiError::new("Opportunity not found")),
                 )
                     .into_response()
             }
         }
         Err(_) => (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error("Invalid UUID")),
+            Json(ApiError::new("Invalid UUID")),
         )
             .into_response(),
     }
 }
 
 /// Get current prices from all DEXs
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices",
+    tag = "prices",
+    params(PairQuery),
+    responses((status = 200, description = "Latest price per pair and DEX", body = ApiResponse<Vec<PriceData>>))
+)]
 async fn get_prices(
     State(state): State<Arc<AppState>>,
     Query(params): Query<PairQuery>,
-) -> impl IntoResponse {
+) -> Json<ApiResponse<Vec<PriceData>>> {
     let detector = state.detector.read().await;
     let prices = detector.get_prices();
 
@@ -401,6 +613,16 @@ async fn get_prices(
 }
 
 /// Get prices for a specific pair
+#[utoipa::path(
+    get,
+    path = "/api/v1/prices/{pair}",
+    tag = "prices",
+    params(("pair" = String, Path, description = "BASE-QUOTE or BASE/QUOTE, e.g. SOL-USDC")),
+    responses(
+        (status = 200, description = "Latest price of the pair on each DEX", body = ApiResponse<Vec<PriceData>>),
+        (status = 400, description = "Malformed pair", body = ApiError),
+    )
+)]
 async fn get_pair_prices(
     State(state): State<Arc<AppState>>,
     Path(pair_str): Path<String>,
@@ -411,38 +633,52 @@ async fn get_pair_prices(
     if parts.len() != 2 {
         return (
             StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<()>::error(
+            Json(ApiError::new(
                 "Invalid pair format. Use BASE-QUOTE or BASE/QUOTE",
             )),
         )
             .into_response();
     }
 
+    // Prices are kept per canonical pair; SOL-BONK is answered by inverting BONK/SOL
     let pair = TokenPair::new(parts[0], parts[1]);
+    let canonical = pair.canonical();
 
     let detector = state.detector.read().await;
     let prices = detector.get_prices();
 
     let result: Vec<_> = prices
         .values()
-        .filter(|p| p.pair == pair)
-        .cloned()
+        .filter(|p| p.pair == canonical)
+        .map(|p| if p.pair == pair { p.clone() } else { p.inverted() })
         .collect();
 
     Json(ApiResponse::success(result)).into_response()
 }
 
 /// Get current configuration
-async fn get_config(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    Json(ApiResponse::success(serde_json::json!({
-        "min_profit_threshold": state.config.min_profit_threshold,
-        "api_port": state.config.api_port,
-        "log_level": state.config.log_level,
-    })))
+#[utoipa::path(
+    get,
+    path = "/api/v1/config",
+    tag = "system",
+    responses((status = 200, description = "Public configuration", body = ApiResponse<ConfigData>))
+)]
+async fn get_config(State(state): State<Arc<AppState>>) -> Json<ApiResponse<ConfigData>> {
+    Json(ApiResponse::success(ConfigData {
+        min_profit_threshold: state.config.min_profit_threshold,
+        api_port: state.config.api_port,
+        log_level: state.config.log_level.clone(),
+    }))
 }
 
 /// Get bot status including DRY_RUN mode and liveness info
-async fn get_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/api/v1/status",
+    tag = "system",
+    responses((status = 200, description = "Mode and liveness", body = ApiResponse<StatusData>))
+)]
+async fn get_status(State(state): State<Arc<AppState>>) -> Json<ApiResponse<StatusData>> {
     let simulated_pnl = *state.simulated_pnl.read().await;
     let simulated_trades = *state.simulated_trades.read().await;
     let heartbeat_count = *state.heartbeat_count.read().await;
@@ -451,32 +687,47 @@ async fn get_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
 
     let dex_statuses: Vec<_> = dex_health.values().cloned().collect();
 
-    Json(ApiResponse::success(serde_json::json!({
-        "dry_run": state.dry_run,
-        "bot_running": true,
-        "simulated_pnl": simulated_pnl,
-        "simulated_trades": simulated_trades,
-        "heartbeat_count": heartbeat_count,
-        "last_scan_at": last_scan_at.to_rfc3339(),
-        "dex_health": dex_statuses,
-        "max_price_age_seconds": state.max_price_age_seconds,
-    })))
-}
-
-/// Get historical trade analysis
-async fn get_history_analysis(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    // Determine file path based on mode (dry_run or live)
-    let history_file = if state.dry_run {
-        "data/history-sim.jsonl"
-    } else {
-        "data/history-live.jsonl"
-    };
+    Json(ApiResponse::success(StatusData {
+        dry_run: state.dry_run,
+        bot_running: true,
+        simulated_pnl,
+        simulated_trades,
+        heartbeat_count,
+        last_scan_at,
+        dex_health: dex_statuses,
+        max_price_age_seconds: state.max_price_age_seconds,
+    }))
+}
 
-    match HistoryAnalyzer::analyze(history_file) {
-        Ok(report) => Json(ApiResponse::success(report)).into_response(),
+/// Get historical trade analysis, optionally limited to a date range
+#[utoipa::path(
+    get,
+    path = "/api/v1/history/analysis",
+    tag = "history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Trade history report", body = ApiResponse<AnalysisReport>),
+        (status = 500, description = "History could not be read", body = ApiError),
+    )
+)]
+async fn get_history_analysis(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let range = DateRange {
+        from: query.from,
+        to: query.to,
+    };
+    match HistoryAnalyzer::analyze_range(history_file(&state), &range) {
+        Ok(mut report) => {
+            if let Some(rate) = accounting_rate(&state.config) {
+                report.convert(&rate);
+            }
+            Json(ApiResponse::success(report)).into_response()
+        }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<()>::error(format!(
+            Json(ApiError::new(format!(
                 "Failed to analyze history: {}",
                 e
             ))),
@@ -484,3 +735,153 @@ async fn get_history_analysis(State(state): State<Arc<AppState>>) -> impl IntoRe
             .into_response(),
     }
 }
+
+/// List recorded trades, newest first, a page at a time
+#[utoipa::path(
+    get,
+    path = "/api/v1/trades",
+    tag = "history",
+    params(TradesQuery),
+    responses(
+        (status = 200, description = "A page of trades", body = ApiResponse<TradePage>),
+        (status = 500, description = "History could not be read", body = ApiError),
+    )
+)]
+async fn get_trades(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TradesQuery>,
+) -> impl IntoResponse {
+    let range = DateRange {
+        from: query.from,
+        to: query.to,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_TRADES_LIMIT).clamp(1, MAX_TRADES_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    match HistoryAnalyzer::trades(history_file(&state), &range) {
+        Ok(trades) => Json(ApiResponse::success(TradePage {
+            total: trades.len(),
+            trades: trades.into_iter().skip(offset).take(limit).collect(),
+            limit,
+            offset,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::new(format!("Failed to read history: {}", e))),
+        )
+            .into_response(),
+    }
+}
+
+/// Get a recorded trade by record id or transaction signature
+#[utoipa::path(
+    get,
+    path = "/api/v1/trades/{id}",
+    tag = "history",
+    params(("id" = String, Path, description = "Record UUID or transaction signature")),
+    responses(
+        (status = 200, description = "The trade", body = ApiResponse<TradeRecord>),
+        (status = 404, description = "No such trade", body = ApiError),
+        (status = 500, description = "History could not be read", body = ApiError),
+    )
+)]
+async fn get_trade(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> impl IntoResponse {
+    match HistoryAnalyzer::find_trade(history_file(&state), &id) {
+        Ok(Some(trade)) => Json(ApiResponse::success(trade)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, Json(ApiError::new("Trade not found"))).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::new(format!("Failed to read history: {}", e))),
+        )
+            .into_response(),
+    }
+}
+
+/// Get P&L per UTC day, optionally limited to a date range
+#[utoipa::path(
+    get,
+    path = "/api/v1/pnl/daily",
+    tag = "history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "P&L per day, oldest first", body = ApiResponse<Vec<DailyPnl>>),
+        (status = 500, description = "History could not be read", body = ApiError),
+    )
+)]
+async fn get_daily_pnl(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let range = DateRange {
+        from: query.from,
+        to: query.to,
+    };
+    match HistoryAnalyzer::daily_pnl(history_file(&state), &range) {
+        Ok(days) => Json(ApiResponse::success(days)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::new(format!("Failed to read history: {}", e))),
+        )
+            .into_response(),
+    }
+}
+
+/// Get monthly investor statements from realized live P&L
+#[utoipa::path(
+    get,
+    path = "/api/v1/ledger/statements",
+    tag = "history",
+    params(StatementsQuery),
+    responses(
+        (status = 200, description = "Statements per month and account", body = ApiResponse<Vec<MonthlyStatement>>),
+        (status = 404, description = "No capital accounts configured", body = ApiError),
+        (status = 500, description = "Statements could not be built", body = ApiError),
+    )
+)]
+async fn get_ledger_statements(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatementsQuery>,
+) -> impl IntoResponse {
+    let ledger = match Ledger::load(CAPITAL_ACCOUNTS_PATH) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::new(format!(
+                    "Failed to load capital accounts from {}: {}",
+                    CAPITAL_ACCOUNTS_PATH, e
+                ))),
+            )
+                .into_response()
+        }
+    };
+
+    let statements = HistoryAnalyzer::realized_trades("data/history-live.jsonl")
+        .map_err(Into::into)
+        .and_then(|trades| ledger.statements(&trades));
+    match statements {
+        Ok(statements) => {
+            let rate = accounting_rate(&state.config);
+            let statements: Vec<_> = statements
+                .into_iter()
+                .filter(|s| query.month.as_ref().is_none_or(|m| &s.month == m))
+                .filter(|s| query.account.as_ref().is_none_or(|a| &s.account == a))
+                .map(|mut s| {
+                    if let Some(rate) = &rate {
+                        s.convert(rate);
+                    }
+                    s
+                })
+                .collect();
+            Json(ApiResponse::success(statements)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::new(format!(
+                "Failed to build statements: {}",
+                e
+            ))),
+        )
+            .into_response(),
+    }
+}
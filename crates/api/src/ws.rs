@@ -1,16 +1,21 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
     },
     response::IntoResponse,
 };
 use futures::{sink::SinkExt, stream::StreamExt};
-use serde::Serialize;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::info;
 
 use crate::AppState;
+use solana_arb_core::events::TradingEvent;
 use solana_arb_core::{ArbitrageOpportunity, PriceData};
 
 /// WebSocket message sent to clients
@@ -22,44 +27,189 @@ pub enum WebSocketMessage {
     /// Real-time price update
     PriceUpdate(Vec<PriceData>),
     /// New arbitrage opportunity detected
-    NewOpportunity(ArbitrageOpportunity),
+    NewOpportunity(Box<ArbitrageOpportunity>),
+    /// Trading event of the bot, relayed from `EVENT_SOCKET_PATH`
+    Event(Box<TradingEvent>),
     /// Heartbeat / Ping
     Heartbeat(u64),
 }
 
+/// Subscription of a client, from the `/ws` query string
+/// (`?pairs=SOL/USDC,RAY-USDC&min_profit=0.5`) or a JSON text message
+/// (`{"pairs": ["SOL/USDC"], "min_profit": 0.5}`)
+#[derive(Debug, Default, Deserialize)]
+pub struct SubscriptionQuery {
+    /// Comma-separated pairs
+    pairs: Option<String>,
+    /// Minimum net profit in percent
+    min_profit: Option<Decimal>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SubscriptionUpdate {
+    #[serde(default)]
+    pairs: Vec<String>,
+    min_profit: Option<Decimal>,
+}
+
+/// What a client is sent. Pair filters drop price updates, opportunities and
+/// events of other pairs; events about no single pair always pass. The
+/// profit filter drops opportunities below it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientFilter {
+    pairs: Option<HashSet<String>>,
+    min_profit_pct: Option<Decimal>,
+}
+
+impl ClientFilter {
+    pub fn new<S: AsRef<str>>(pairs: &[S], min_profit_pct: Option<Decimal>) -> Self {
+        let pairs: HashSet<String> = pairs
+            .iter()
+            .map(|pair| normalize_pair(pair.as_ref()))
+            .filter(|pair| !pair.is_empty())
+            .collect();
+        Self {
+            pairs: (!pairs.is_empty()).then_some(pairs),
+            min_profit_pct,
+        }
+    }
+
+    fn wants_pair(&self, pair: &str) -> bool {
+        self.pairs
+            .as_ref()
+            .is_none_or(|pairs| pairs.contains(&normalize_pair(pair)))
+    }
+
+    fn wants_profit(&self, profit_pct: Decimal) -> bool {
+        self.min_profit_pct.is_none_or(|min| profit_pct >= min)
+    }
+
+    /// The part of `msg` this client wants, if any
+    pub fn apply<'a>(&self, msg: &'a WebSocketMessage) -> Option<Cow<'a, WebSocketMessage>> {
+        match msg {
+            WebSocketMessage::PriceUpdate(prices) if self.pairs.is_some() => {
+                let prices: Vec<PriceData> = prices
+                    .iter()
+                    .filter(|price| self.wants_pair(&price.pair.symbol()))
+                    .cloned()
+                    .collect();
+                (!prices.is_empty()).then_some(Cow::Owned(WebSocketMessage::PriceUpdate(prices)))
+            }
+            WebSocketMessage::NewOpportunity(opp) => (self.wants_pair(&opp.pair.symbol())
+                && self.wants_profit(opp.net_profit_pct))
+            .then_some(Cow::Borrowed(msg)),
+            WebSocketMessage::Event(event) => {
+                let wanted = match event.as_ref() {
+                    TradingEvent::OpportunityDetected {
+                        expected_profit_bps,
+                        ..
+                    } => self.wants_profit(
+                        Decimal::try_from(*expected_profit_bps / 100.0).unwrap_or_default(),
+                    ),
+                    event => event.pair().is_none_or(|pair| self.wants_pair(pair)),
+                };
+                wanted.then_some(Cow::Borrowed(msg))
+            }
+            _ => Some(Cow::Borrowed(msg)),
+        }
+    }
+}
+
+impl std::fmt::Display for ClientFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.pairs {
+            Some(pairs) => {
+                let mut pairs: Vec<&str> = pairs.iter().map(String::as_str).collect();
+                pairs.sort_unstable();
+                write!(f, "pairs {}", pairs.join(","))?;
+            }
+            None => write!(f, "all pairs")?,
+        }
+        if let Some(min) = self.min_profit_pct {
+            write!(f, ", min profit {}%", min)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<SubscriptionQuery> for ClientFilter {
+    fn from(query: SubscriptionQuery) -> Self {
+        let pairs: Vec<&str> = query.pairs.as_deref().unwrap_or("").split(',').collect();
+        Self::new(&pairs, query.min_profit)
+    }
+}
+
+/// `sol-usdc` → `SOL/USDC`
+fn normalize_pair(pair: &str) -> String {
+    pair.trim().replace('-', "/").to_uppercase()
+}
+
 /// WebSocket handler function
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<SubscriptionQuery>,
     State(state): State<Arc<AppState>>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    let filter = ClientFilter::from(query);
+    ws.on_upgrade(|socket| handle_socket(socket, state, filter))
 }
 
 /// Handle a single WebSocket connection
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+async fn handle_socket(socket: WebSocket, state: Arc<AppState>, mut filter: ClientFilter) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to broadcast channel
     let mut rx = state.tx.subscribe();
+    // Subscription changes sent by the client
+    let (updates_tx, mut updates) = mpsc::unbounded_channel::<Result<ClientFilter, String>>();
 
     // Spawn task to forward broadcast messages to WebSocket client
     let mut send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            // Serialize message to JSON string
-            if let Ok(json) = serde_json::to_string(&msg) {
-                if sender.send(Message::Text(json)).await.is_err() {
-                    break;
+        let mut next = Some(WebSocketMessage::Status(format!(
+            "Subscribed to {}",
+            filter
+        )));
+        loop {
+            if let Some(msg) = next.take() {
+                // Serialize message to JSON string
+                if let Ok(json) = serde_json::to_string(&msg) {
+                    if sender.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
                 }
             }
+            next = tokio::select! {
+                update = updates.recv() => match update {
+                    Some(Ok(update)) => {
+                        filter = update;
+                        Some(WebSocketMessage::Status(format!("Subscribed to {}", filter)))
+                    }
+                    Some(Err(e)) => Some(WebSocketMessage::Status(format!("Invalid subscription: {}", e))),
+                    None => break,
+                },
+                msg = rx.recv() => match msg {
+                    Ok(msg) => filter.apply(&msg).map(Cow::into_owned),
+                    // A slow client misses messages rather than being dropped
+                    Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
         }
     });
 
-    // Handle incoming messages (mostly for PING/PONG or commands if needed)
-    // For now, we just keep the connection alive
+    // Handle incoming messages: subscription changes and close
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
                 Message::Close(_) => break,
+                Message::Text(text) => {
+                    let update = serde_json::from_str::<SubscriptionUpdate>(&text)
+                        .map(|update| ClientFilter::new(&update.pairs, update.min_profit))
+                        .map_err(|e| e.to_string());
+                    if updates_tx.send(update).is_err() {
+                        break;
+                    }
+                }
                 Message::Ping(_) => {} // Automatically handled by axum/tungstenite mostly
                 _ => {}
             }
@@ -74,3 +224,144 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
 
     info!("WebSocket client disconnected");
 }
+
+/// Forward the bot's trading events from its event socket to WebSocket
+/// clients, reconnecting while the bot is down or restarting
+#[cfg(unix)]
+pub async fn relay_events(path: String, tx: broadcast::Sender<WebSocketMessage>) {
+    use solana_arb_core::ipc::{socket::IpcSubscriber, IpcMessage};
+
+    const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+    let mut connected_before = false;
+    loop {
+        match IpcSubscriber::connect(&path).await {
+            Ok(mut subscriber) => {
+                info!("Relaying trading events from {}", path);
+                connected_before = true;
+                loop {
+                    match subscriber.recv().await {
+                        Ok(IpcMessage::Event(event)) => {
+                            let _ = tx.send(WebSocketMessage::Event(Box::new(event)));
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            tracing::warn!("Event socket {} closed: {}", path, e);
+                            break;
+                        }
+                    }
+                }
+            }
+            // Logged once, not every retry while the bot is not running
+            Err(e) if !connected_before => {
+                tracing::warn!("Event socket {} unavailable, retrying: {}", path, e);
+                connected_before = true;
+            }
+            Err(_) => {}
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use solana_arb_core::{DexType, TokenPair};
+
+    fn pct(value: i64) -> Decimal {
+        Decimal::new(value, 1)
+    }
+
+    fn price(pair: TokenPair) -> PriceData {
+        PriceData::new(
+            DexType::Raydium,
+            pair,
+            Decimal::from(100),
+            Decimal::from(101),
+        )
+    }
+
+    fn opportunity(pair: TokenPair, net_profit_pct: Decimal) -> WebSocketMessage {
+        WebSocketMessage::NewOpportunity(Box::new(ArbitrageOpportunity {
+            id: solana_arb_core::Uuid::new_v4(),
+            pair,
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: net_profit_pct,
+            net_profit_pct,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: Default::default(),
+            strategy: None,
+        }))
+    }
+
+    #[test]
+    fn test_filter_by_pair_and_profit() {
+        let query = SubscriptionQuery {
+            pairs: Some("sol-usdc, RAY/USDC".into()),
+            min_profit: Some(pct(5)),
+        };
+        let filter = ClientFilter::from(query);
+        assert_eq!(
+            filter.to_string(),
+            "pairs RAY/USDC,SOL/USDC, min profit 0.5%"
+        );
+        let (sol, bonk) = (
+            TokenPair::new("SOL", "USDC"),
+            TokenPair::new("BONK", "USDC"),
+        );
+
+        let prices = WebSocketMessage::PriceUpdate(vec![price(sol.clone()), price(bonk.clone())]);
+        match filter.apply(&prices).unwrap().into_owned() {
+            WebSocketMessage::PriceUpdate(prices) => {
+                assert_eq!(prices.len(), 1);
+                assert_eq!(prices[0].pair, sol);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let bonk_prices = WebSocketMessage::PriceUpdate(vec![price(bonk.clone())]);
+        assert!(filter.apply(&bonk_prices).is_none());
+
+        assert!(filter.apply(&opportunity(sol.clone(), pct(6))).is_some());
+        assert!(filter.apply(&opportunity(sol, pct(4))).is_none());
+        assert!(filter.apply(&opportunity(bonk, pct(20))).is_none());
+        assert!(filter.apply(&WebSocketMessage::Heartbeat(1)).is_some());
+    }
+
+    #[test]
+    fn test_filter_events() {
+        let filter = ClientFilter::new(&["SOL/USDC"], Some(pct(5)));
+        let event = |event| WebSocketMessage::Event(Box::new(event));
+        let stuck = |pair: &str| TradingEvent::TradeStuck {
+            id: "trade".into(),
+            pair: pair.into(),
+            elapsed_secs: 60,
+        };
+        let detected = |bps| TradingEvent::OpportunityDetected {
+            id: "opp".into(),
+            strategy: "arbitrage".into(),
+            expected_profit_bps: bps,
+        };
+
+        assert!(filter.apply(&event(stuck("SOL/USDC"))).is_some());
+        assert!(filter.apply(&event(stuck("BONK/USDC"))).is_none());
+        assert!(filter.apply(&event(detected(60.0))).is_some());
+        assert!(filter.apply(&event(detected(40.0))).is_none());
+        // Not about one pair
+        let stopping = TradingEvent::SystemStopping {
+            reason: "shutdown".into(),
+        };
+        assert!(filter.apply(&event(stopping)).is_some());
+        // No filter passes everything
+        assert!(ClientFilter::default()
+            .apply(&event(stuck("BONK/USDC")))
+            .is_some());
+    }
+}
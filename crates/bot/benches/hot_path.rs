@@ -0,0 +1,272 @@
+//! Execution hot path benchmarks
+//!
+//! `cargo bench --bench hot_path` measures the work between a price update
+//! and a signed transaction: detection over N pairs, path finding over M
+//! tokens, Jupiter quote parsing, instruction conversion and building a v0
+//! flash loan message with lookup tables.
+//!
+//! The run fails when a benchmark's mean exceeds its budget in [`BUDGETS_US`],
+//! or, when compared against a saved baseline, slows down by more than
+//! `BENCH_MAX_REGRESSION_PCT` (default 10%):
+//!
+//! ```bash
+//! cargo bench --bench hot_path -- --save-baseline main   # on main
+//! cargo bench --bench hot_path -- --baseline main        # before deploying
+//! ```
+
+use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
+use base64::Engine;
+use chrono::Utc;
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+use rust_decimal::Decimal;
+use solana_arb_bot::execution::{Executor, JupiterAccountMeta, JupiterInstruction};
+use solana_arb_bot::flash_loan_tx_builder::FlashLoanTxBuilder;
+use solana_arb_bot::trade_traces::QuoteSnapshot;
+use solana_arb_core::arbitrage::ArbitrageDetector;
+use solana_arb_core::pathfinding::PathFinder;
+use solana_arb_core::tokens::TokenRegistry;
+use solana_arb_core::{ArbitrageOpportunity, DexType, OpportunitySource, PriceData, TokenPair, Uuid};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Mean time each benchmark may take, in microseconds. These are ceilings
+/// for a small cloud host; regressions are caught earlier by comparing
+/// against a baseline.
+const BUDGETS_US: &[(&str, f64)] = &[
+    ("quote_parse", 50.0),
+    ("convert_jupiter_instruction", 50.0),
+    ("flash_loan_message_with_alts", 250.0),
+    ("detector_scan/10", 100.0),
+    ("detector_scan/100", 1_000.0),
+    ("detector_scan/500", 5_000.0),
+    ("pathfinder/4", 2_000.0),
+    ("pathfinder/8", 40_000.0),
+    ("pathfinder/12", 300_000.0),
+];
+
+const DEFAULT_MAX_REGRESSION_PCT: f64 = 10.0;
+
+const QUOTE: &str = r#"{
+    "inputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "inAmount": "1000000000",
+    "outputMint": "So11111111111111111111111111111111111111112",
+    "outAmount": "9950000000",
+    "otherAmountThreshold": "9900250000",
+    "swapMode": "ExactIn",
+    "slippageBps": 50,
+    "priceImpactPct": "0.0012",
+    "routePlan": [
+        {"swapInfo": {"ammKey": "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2", "label": "Raydium", "inputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "outputMint": "So11111111111111111111111111111111111111112", "inAmount": "600000000", "outAmount": "5970000000", "feeAmount": "1500000", "feeMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"}, "percent": 60},
+        {"swapInfo": {"ammKey": "Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE", "label": "Whirlpool", "inputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "outputMint": "So11111111111111111111111111111111111111112", "inAmount": "400000000", "outAmount": "3980000000", "feeAmount": "1200000", "feeMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"}, "percent": 40}
+    ],
+    "contextSlot": 250000000,
+    "timeTaken": 0.012
+}"#;
+
+fn opportunity() -> ArbitrageOpportunity {
+    ArbitrageOpportunity {
+        id: Uuid::new_v4(),
+        pair: TokenPair::new("SOL", "USDC"),
+        buy_dex: DexType::Raydium,
+        sell_dex: DexType::Orca,
+        buy_price: Decimal::from(100),
+        sell_price: Decimal::new(1005, 1),
+        gross_profit_pct: Decimal::new(5, 1),
+        net_profit_pct: Decimal::new(2, 1),
+        estimated_profit_usd: Some(Decimal::from(2)),
+        recommended_size: Some(Decimal::from(1000)),
+        detected_at: Utc::now(),
+        expired_at: None,
+        buy_quote_at: None,
+        sell_quote_at: None,
+        source: OpportunitySource::Detector,
+        strategy: None,
+    }
+}
+
+/// A swap instruction shaped like Jupiter's: a program, `accounts` accounts
+/// and a short payload
+fn jupiter_instruction(accounts: usize) -> JupiterInstruction {
+    JupiterInstruction {
+        program_id: "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+        accounts: (0..accounts)
+            .map(|i| JupiterAccountMeta {
+                pubkey: Pubkey::new_unique().to_string(),
+                is_signer: false,
+                is_writable: i % 3 == 0,
+            })
+            .collect(),
+        data: BASE64_ENGINE.encode([7u8; 64]),
+    }
+}
+
+fn bench_quote_parse(c: &mut Criterion) {
+    let registry = TokenRegistry::mainnet();
+    let opp = opportunity();
+    c.bench_function("quote_parse", |b| {
+        b.iter(|| {
+            let quote: serde_json::Value = serde_json::from_str(black_box(QUOTE)).unwrap();
+            QuoteSnapshot::from_quote(&quote, &registry, &opp)
+        })
+    });
+}
+
+fn bench_convert_instruction(c: &mut Criterion) {
+    let ix = jupiter_instruction(24);
+    c.bench_function("convert_jupiter_instruction", |b| {
+        b.iter(|| Executor::convert_jupiter_instruction(black_box(&ix)).unwrap())
+    });
+}
+
+fn bench_flash_loan_message(c: &mut Criterion) {
+    let builder = FlashLoanTxBuilder::new(Keypair::new(), false);
+    let opp = opportunity();
+    let mint: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
+    let swaps: Vec<_> = [jupiter_instruction(8), jupiter_instruction(24), jupiter_instruction(4)]
+        .iter()
+        .map(|ix| Executor::convert_jupiter_instruction(ix).unwrap())
+        .collect();
+    // Most swap accounts are served from two lookup tables, as Jupiter's are
+    let accounts: Vec<Pubkey> = swaps
+        .iter()
+        .flat_map(|ix| ix.accounts.iter().map(|a| a.pubkey))
+        .collect();
+    let tables: Vec<_> = accounts
+        .chunks(accounts.len() / 2 + 1)
+        .map(|addresses| AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: addresses.to_vec(),
+        })
+        .collect();
+
+    c.bench_function("flash_loan_message_with_alts", |b| {
+        b.iter(|| {
+            let message = builder
+                .build_message(&opp, 1_000_000_000, &mint, swaps.clone(), &tables, Hash::default())
+                .unwrap();
+            bincode::serialize(&message).unwrap().len()
+        })
+    });
+}
+
+fn bench_detector_scan(c: &mut Criterion) {
+    let dexes = [DexType::Raydium, DexType::Orca, DexType::Meteora, DexType::Phoenix];
+    let mut group = c.benchmark_group("detector_scan");
+    for pairs in [10usize, 100, 500] {
+        let mut detector = ArbitrageDetector::default();
+        for i in 0..pairs {
+            let pair = TokenPair::new(format!("T{}", i), "USDC");
+            for (d, dex) in dexes.iter().enumerate() {
+                // Quotes differ slightly per venue so some pairs clear fees
+                let mid = Decimal::from(100 + i as i64) + Decimal::new((d * (i % 7)) as i64, 1);
+                let half_spread = Decimal::new(1, 2);
+                detector.update_price(PriceData::new(*dex, pair.clone(), mid - half_spread, mid + half_spread));
+            }
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(pairs), &detector, |b, detector| {
+            b.iter(|| detector.find_all_opportunities())
+        });
+    }
+    group.finish();
+}
+
+fn bench_pathfinder(c: &mut Criterion) {
+    // Every token pair is quoted on two venues, so the number of cycles
+    // grows steeply with the token count
+    let mut group = c.benchmark_group("pathfinder");
+    group.sample_size(10);
+    for tokens in [4usize, 8, 12] {
+        let mut finder = PathFinder::new(3);
+        for i in 0..tokens {
+            for j in (i + 1)..tokens {
+                let pair = TokenPair::new(format!("T{}", i), format!("T{}", j));
+                for (k, dex) in [DexType::Raydium, DexType::Orca].iter().enumerate() {
+                    let rate = Decimal::from((j - i) as i64) + Decimal::new(((i + j + k) % 5) as i64, 2);
+                    finder.add_price(&PriceData::new(*dex, pair.clone(), rate, rate));
+                }
+            }
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(tokens), &finder, |b, finder| {
+            b.iter(|| finder.find_all_profitable_paths())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    hot_path,
+    bench_quote_parse,
+    bench_convert_instruction,
+    bench_flash_loan_message,
+    bench_detector_scan,
+    bench_pathfinder
+);
+
+fn main() {
+    let started = SystemTime::now();
+    hot_path();
+    Criterion::default().configure_from_args().final_summary();
+
+    let failures = check_thresholds(&criterion_dir(), started);
+    for failure in &failures {
+        eprintln!("REGRESSION {}", failure);
+    }
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// Where criterion writes its estimates
+fn criterion_dir() -> PathBuf {
+    if let Some(home) = std::env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target"))
+        .join("criterion")
+}
+
+/// Mean of an estimates file: nanoseconds for `new`, a fraction for `change`
+fn mean_estimate(path: &Path, since: SystemTime) -> Option<f64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    if modified < since {
+        return None; // left over from an earlier run
+    }
+    let estimates: serde_json::Value = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+    estimates.pointer("/mean/point_estimate")?.as_f64()
+}
+
+/// Benchmarks measured in this run that broke their budget or regressed
+fn check_thresholds(dir: &Path, since: SystemTime) -> Vec<String> {
+    let max_regression_pct = std::env::var("BENCH_MAX_REGRESSION_PCT")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_MAX_REGRESSION_PCT);
+
+    let mut failures = Vec::new();
+    for (id, budget_us) in BUDGETS_US {
+        let bench_dir = dir.join(id);
+        let Some(mean_ns) = mean_estimate(&bench_dir.join("new/estimates.json"), since) else {
+            continue;
+        };
+        if mean_ns / 1_000.0 > *budget_us {
+            failures.push(format!("{}: mean {:.1}µs over budget {}µs", id, mean_ns / 1_000.0, budget_us));
+        }
+        if let Some(change) = mean_estimate(&bench_dir.join("change/estimates.json"), since) {
+            if change * 100.0 > max_regression_pct {
+                failures.push(format!(
+                    "{}: {:.1}% slower than the baseline (limit {}%)",
+                    id,
+                    change * 100.0,
+                    max_regression_pct
+                ));
+            }
+        }
+    }
+    failures
+}
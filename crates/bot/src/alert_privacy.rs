@@ -0,0 +1,288 @@
+//! Alert Privacy
+//!
+//! Telegram and Discord webhooks are third-party services, and alerts carry
+//! trade sizes, P&L and balances. Each channel can redact those amounts,
+//! encrypt the whole message to a recipient public key, or both.
+//!
+//! Encryption is a NaCl `crypto_box` (X25519 + XSalsa20-Poly1305) from a
+//! fresh ephemeral key per message. The payload is base64 of
+//! `ephemeral public key (32) || nonce (24) || box`, so any NaCl library
+//! can open it, e.g. with PyNaCl:
+//! `Box(recipient_secret, PublicKey(raw[:32])).decrypt(raw[32:])`.
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use poly1305::universal_hash::NewUniversalHash;
+use poly1305::Poly1305;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use salsa20::cipher::generic_array::GenericArray;
+use salsa20::cipher::{NewCipher, StreamCipher};
+use salsa20::{hsalsa20, XSalsa20};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const MAC_LEN: usize = 16;
+
+/// Placeholder for redacted amounts
+const REDACTED: &str = "***";
+
+/// How alerts are rendered for one channel
+#[derive(Debug, Clone, Default)]
+pub struct ChannelPrivacy {
+    /// Mask dollar amounts and token quantities
+    pub redact: bool,
+    /// X25519 public key to encrypt messages to
+    pub recipient: Option<[u8; KEY_LEN]>,
+}
+
+impl ChannelPrivacy {
+    /// `public_key` is a base64-encoded 32-byte X25519 key
+    pub fn new(redact: bool, public_key: Option<&str>) -> Result<Self> {
+        let recipient = public_key.map(parse_public_key).transpose()?;
+        Ok(Self { redact, recipient })
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.recipient.is_some()
+    }
+
+    /// The text to post for `message`
+    pub fn render(&self, message: &str) -> String {
+        let message = if self.redact {
+            redact(message)
+        } else {
+            message.to_string()
+        };
+        match &self.recipient {
+            Some(recipient) => format!("🔒 {}", seal(recipient, message.as_bytes())),
+            None => message,
+        }
+    }
+}
+
+fn parse_public_key(encoded: &str) -> Result<[u8; KEY_LEN]> {
+    BASE64
+        .decode(encoded.trim())
+        .context("invalid base64 public key")?
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow!("public key must be 32 bytes, got {}", bytes.len()))
+}
+
+/// Mask dollar amounts (`$1,234.56` → `$***`) and token quantities
+/// (`1.5 SOL` → `*** SOL`). Percentages and counts are kept.
+pub fn redact(message: &str) -> String {
+    let chars: Vec<char> = message.chars().collect();
+    let is_amount_char = |c: char| c.is_ascii_digit() || c == '.' || c == ',';
+    let mut out = String::with_capacity(message.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // $12.34, $-20.00
+        if c == '$' {
+            let mut end = i + 1;
+            if chars.get(end) == Some(&'-') {
+                end += 1;
+            }
+            if chars.get(end).is_some_and(|c| c.is_ascii_digit()) {
+                while chars.get(end).is_some_and(|c| is_amount_char(*c)) {
+                    end += 1;
+                }
+                out.push('$');
+                out.push_str(REDACTED);
+                i = end;
+                continue;
+            }
+        }
+
+        // A number followed by a token symbol: 1.5 SOL
+        let starts_number = c.is_ascii_digit()
+            && (i == 0 || !(chars[i - 1].is_alphanumeric() || chars[i - 1] == '.'));
+        if starts_number {
+            let mut end = i;
+            while chars.get(end).is_some_and(|c| is_amount_char(*c)) {
+                end += 1;
+            }
+            let symbol_len = chars[end..]
+                .iter()
+                .skip(1)
+                .take_while(|c| c.is_ascii_uppercase())
+                .count();
+            if chars.get(end) == Some(&' ') && symbol_len >= 2 {
+                out.push_str(REDACTED);
+            } else {
+                out.extend(&chars[i..end]);
+            }
+            i = end;
+            continue;
+        }
+
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn x25519(secret: &[u8; KEY_LEN], public: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+    (Scalar::from_bits(clamp(*secret)) * MontgomeryPoint(*public)).to_bytes()
+}
+
+/// `crypto_box_beforenm`: HSalsa20 of the X25519 shared secret
+fn box_key(secret: &[u8; KEY_LEN], public: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+    let shared = x25519(secret, public);
+    hsalsa20(GenericArray::from_slice(&shared), &GenericArray::default()).into()
+}
+
+/// XSalsa20 keystream cipher; the first 32 bytes of keystream are the
+/// Poly1305 key
+fn cipher(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN]) -> (XSalsa20, Poly1305) {
+    let mut cipher = XSalsa20::new(GenericArray::from_slice(key), GenericArray::from_slice(nonce));
+    let mut mac_key = [0u8; KEY_LEN];
+    cipher.apply_keystream(&mut mac_key);
+    (cipher, Poly1305::new(GenericArray::from_slice(&mac_key)))
+}
+
+/// Encrypt `plaintext` to `recipient`, returning the base64 payload
+pub fn seal(recipient: &[u8; KEY_LEN], plaintext: &[u8]) -> String {
+    let mut ephemeral = [0u8; KEY_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut ephemeral);
+    OsRng.fill_bytes(&mut nonce);
+    let ephemeral_public = (Scalar::from_bits(clamp(ephemeral)) * X25519_BASEPOINT).to_bytes();
+    let boxed = seal_box(&box_key(&ephemeral, recipient), &nonce, plaintext);
+
+    let mut payload = Vec::with_capacity(KEY_LEN + NONCE_LEN + boxed.len());
+    payload.extend_from_slice(&ephemeral_public);
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&boxed);
+    BASE64.encode(payload)
+}
+
+/// Decrypt a payload produced by [`seal`] with the recipient's secret key.
+/// Returns `None` if it is malformed or fails authentication.
+pub fn open(secret: &[u8; KEY_LEN], payload: &str) -> Option<Vec<u8>> {
+    let payload = BASE64.decode(payload).ok()?;
+    if payload.len() < KEY_LEN + NONCE_LEN + MAC_LEN {
+        return None;
+    }
+    let (ephemeral_public, rest) = payload.split_at(KEY_LEN);
+    let (nonce, boxed) = rest.split_at(NONCE_LEN);
+    open_box(&box_key(secret, ephemeral_public.try_into().ok()?), nonce.try_into().ok()?, boxed)
+}
+
+/// `crypto_box_afternm`: `tag (16) || ciphertext`
+fn seal_box(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], plaintext: &[u8]) -> Vec<u8> {
+    let (mut cipher, mac) = cipher(key, nonce);
+    let mut ciphertext = plaintext.to_vec();
+    cipher.apply_keystream(&mut ciphertext);
+    let mut boxed = mac.compute_unpadded(&ciphertext).into_bytes().to_vec();
+    boxed.extend_from_slice(&ciphertext);
+    boxed
+}
+
+/// `crypto_box_open_afternm`; `None` if `boxed` fails authentication
+fn open_box(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], boxed: &[u8]) -> Option<Vec<u8>> {
+    if boxed.len() < MAC_LEN {
+        return None;
+    }
+    let (tag, ciphertext) = boxed.split_at(MAC_LEN);
+    let (mut cipher, mac) = cipher(key, nonce);
+    // Output comparison is constant time
+    if mac.compute_unpadded(ciphertext) != poly1305::Tag::new(*GenericArray::from_slice(tag)) {
+        return None;
+    }
+    let mut plaintext = ciphertext.to_vec();
+    cipher.apply_keystream(&mut plaintext);
+    Some(plaintext)
+}
+
+fn clamp(mut secret: [u8; KEY_LEN]) -> [u8; KEY_LEN] {
+    secret[0] &= 248;
+    secret[31] &= 127;
+    secret[31] |= 64;
+    secret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(hex_str: &str) -> Vec<u8> {
+        (0..hex_str.len() / 2)
+            .map(|i| u8::from_str_radix(&hex_str[i * 2..i * 2 + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn key(hex_str: &str) -> [u8; KEY_LEN] {
+        bytes(hex_str).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_box_key_matches_nacl() {
+        // crypto_box_beforenm test vector from NaCl
+        let alice_secret = key("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob_public = key("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        assert_eq!(
+            box_key(&alice_secret, &bob_public),
+            key("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389")
+        );
+    }
+
+    #[test]
+    fn test_crypto_box_matches_nacl() {
+        // crypto_box test vector from NaCl (tests/box.c, box2.c)
+        let alice_secret = key("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob_public = key("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        let nonce: [u8; NONCE_LEN] = bytes("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37").try_into().unwrap();
+        let message = bytes(concat!(
+            "be075fc53c81f2d5cf141316ebeb0c7b5228c52a4c62cbd44b66849b64244ffc",
+            "e5ecbaaf33bd751a1ac728d45e6c61296cdc3c01233561f41db66cce314adb31",
+            "0e3be8250c46f06dceea3a7fa1348057e2f6556ad6b1318a024a838f21af1fde",
+            "048977eb48f59ffd4924ca1c60902e52f0a089bc76897040e082f93776384864",
+            "5e0705",
+        ));
+        let boxed = bytes(concat!(
+            "f3ffc7703f9400e52a7dfb4b3d3305d98e993b9f48681273c29650ba32fc76ce",
+            "48332ea7164d96a4476fb8c531a1186ac0dfc17c98dce87b4da7f011ec48c972",
+            "71d2c20f9b928fe2270d6fb863d51738b48eeee314a7cc8ab932164548e526ae",
+            "90224368517acfeabd6bb3732bc0e9da99832b61ca01b6de56244a9e88d5f9b3",
+            "7973f622a43d14a6599b1f654cb45a74e355a5",
+        ));
+
+        let key = box_key(&alice_secret, &bob_public);
+        assert_eq!(seal_box(&key, &nonce, &message), boxed);
+        assert_eq!(open_box(&key, &nonce, &boxed), Some(message));
+    }
+
+    #[test]
+    fn test_encrypted_and_redacted_rendering() {
+        let secret = key("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let public = (Scalar::from_bits(clamp(secret)) * X25519_BASEPOINT).to_bytes();
+
+        let privacy = ChannelPrivacy::new(true, Some(&BASE64.encode(public))).unwrap();
+        assert!(privacy.is_encrypted());
+        assert!(ChannelPrivacy::new(false, Some("c2hvcnQ=")).is_err());
+
+        let rendered = privacy.render("💰 Profit: $12.50\nSize: $1,000 | Balance 2.5 SOL");
+        let payload = rendered.strip_prefix("🔒 ").unwrap();
+        let plaintext = String::from_utf8(open(&secret, payload).unwrap()).unwrap();
+        assert_eq!(plaintext, "💰 Profit: $***\nSize: $*** | Balance *** SOL");
+
+        // Tampering fails authentication
+        let mut raw = BASE64.decode(payload).unwrap();
+        *raw.last_mut().unwrap() ^= 1;
+        assert!(open(&secret, &BASE64.encode(raw)).is_none());
+
+        assert_eq!(
+            redact("P&L: $-20.00 over 7 trades, VaR 95%, SOL/USDC 3x"),
+            "P&L: $*** over 7 trades, VaR 95%, SOL/USDC 3x"
+        );
+        assert_eq!(ChannelPrivacy::default().render("Size: $5"), "Size: $5");
+    }
+}
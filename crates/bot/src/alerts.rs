@@ -2,9 +2,15 @@
 //!
 //! Manages external notifications via Telegram, Discord, and other channels.
 
+use crate::alert_privacy::ChannelPrivacy;
+use crate::incidents::{Incident, IncidentStore, Raised};
 use reqwest::Client;
+use rust_decimal::prelude::*;
 use serde_json::json;
-use tracing::{error, info};
+use solana_arb_core::fx::{FxRate, SharedFxRate};
+use solana_arb_core::risk::DailyRiskDigest;
+use std::sync::Arc;
+use tracing::{debug, error, info};
 
 /// Manages system alerts via multiple channels (Telegram, Discord).
 ///
@@ -15,6 +21,15 @@ use tracing::{error, info};
 pub struct AlertManager {
     telegram_webhook: Option<String>,
     discord_webhook: Option<String>,
+    telegram_privacy: ChannelPrivacy,
+    discord_privacy: ChannelPrivacy,
+    /// Incidents opened by critical alerts, when tracked
+    incidents: Option<Arc<IncidentStore>>,
+    /// Extra destination for escalated incidents (Slack/Discord-style webhook)
+    escalation_webhook: Option<String>,
+    escalation_privacy: ChannelPrivacy,
+    /// Rate amounts in alerts are shown at, in the accounting currency
+    fx: SharedFxRate,
     http_client: Client,
 }
 
@@ -25,30 +40,126 @@ impl AlertManager {
         Self {
             telegram_webhook,
             discord_webhook,
+            telegram_privacy: ChannelPrivacy::default(),
+            discord_privacy: ChannelPrivacy::default(),
+            incidents: None,
+            escalation_webhook: None,
+            escalation_privacy: ChannelPrivacy::default(),
+            fx: SharedFxRate::default(),
             http_client: Client::new(),
         }
     }
 
+    /// Sets redaction/encryption for each channel.
+    pub fn with_privacy(mut self, telegram: ChannelPrivacy, discord: ChannelPrivacy) -> Self {
+        self.telegram_privacy = telegram;
+        self.discord_privacy = discord;
+        self
+    }
+
+    /// Tracks critical alerts as incidents in `store`, suppressing duplicates
+    /// of open ones. Escalations also go to `escalation_webhook`, if set,
+    /// rendered with `escalation_privacy`.
+    pub fn with_incidents(
+        mut self,
+        store: Arc<IncidentStore>,
+        escalation_webhook: Option<String>,
+        escalation_privacy: ChannelPrivacy,
+    ) -> Self {
+        self.incidents = Some(store);
+        self.escalation_webhook = escalation_webhook;
+        self.escalation_privacy = escalation_privacy;
+        self
+    }
+
+    /// Shows amounts in the accounting currency, at the latest rate
+    pub fn with_fx(mut self, fx: SharedFxRate) -> Self {
+        self.fx = fx;
+        self
+    }
+
+    /// A USD amount in the accounting currency, e.g. "$12.34" or "11.37 EUR"
+    pub fn money(&self, usd: f64) -> String {
+        self.fx.format(usd)
+    }
+
+    /// `message` as the Telegram channel's privacy settings show it
+    pub fn render_telegram(&self, message: &str) -> String {
+        self.telegram_privacy.render(message)
+    }
+
+    /// Incident store critical alerts are tracked in, if any
+    pub fn incidents(&self) -> Option<&Arc<IncidentStore>> {
+        self.incidents.as_ref()
+    }
+
     /// Creates an AlertManager from environment variables.
     pub fn from_env() -> Self {
         Self {
             telegram_webhook: std::env::var("TELEGRAM_WEBHOOK_URL").ok(),
             discord_webhook: std::env::var("DISCORD_WEBHOOK_URL").ok(),
+            telegram_privacy: ChannelPrivacy::default(),
+            discord_privacy: ChannelPrivacy::default(),
+            incidents: None,
+            escalation_webhook: None,
+            escalation_privacy: ChannelPrivacy::default(),
+            fx: SharedFxRate::default(),
             http_client: Client::new(),
         }
     }
     
     /// Sends a critical alert (prefixed with "🚨 CRITICAL") to all configured channels.
+    ///
+    /// With incident tracking, the alert opens an incident and is tagged with
+    /// its id; a repeat of an open incident is logged but not sent.
     pub async fn send_critical(&self, message: &str) {
-        let formatted = format!("🚨 CRITICAL: {}", message);
+        let formatted = match self.incidents.as_ref().map(|store| store.raise(message)) {
+            Some(Raised::Duplicate(id)) => {
+                debug!("Suppressed duplicate of incident #{}: {}", id, message);
+                return;
+            }
+            Some(Raised::New(id)) => format!("🚨 CRITICAL [incident #{}]: {}", id, message),
+            None => format!("🚨 CRITICAL: {}", message),
+        };
         error!("{}", formatted);
-        
+        self.broadcast_critical(&formatted).await;
+    }
+
+    /// Re-sends an incident nobody has acknowledged, also to the escalation webhook
+    pub async fn send_escalation(&self, incident: &Incident) {
+        let formatted = format!(
+            "🔺 ESCALATED [incident #{}] unacknowledged for {} min ({} alerts): {}",
+            incident.id,
+            (chrono::Utc::now() - incident.opened_at).num_minutes(),
+            incident.occurrences,
+            incident.last_message
+        );
+        error!("{}", formatted);
+        self.broadcast_critical(&formatted).await;
+
+        if let Some(url) = &self.escalation_webhook {
+            let _ = self.http_client
+                .post(url)
+                .json(&self.escalation_body(&formatted))
+                .send()
+                .await
+                .map_err(|e| error!("Failed to send escalation: {}", e));
+        }
+    }
+
+    /// Escalation webhook payload, as its privacy settings show `formatted`
+    fn escalation_body(&self, formatted: &str) -> serde_json::Value {
+        let text = self.escalation_privacy.render(formatted);
+        json!({ "text": text, "content": text })
+    }
+
+    async fn broadcast_critical(&self, formatted: &str) {
         // Send to Telegram
         if let Some(url) = &self.telegram_webhook {
             let _ = self.http_client
                 .post(url)
                 .json(&json!({
-                    "text": formatted,
+                    "text": self.telegram_privacy.render(formatted),
                     "parse_mode": "HTML"
                 }))
                 .send()
@@ -61,7 +172,7 @@ impl AlertManager {
             let _ = self.http_client
                 .post(url)
                 .json(&json!({
-                    "content": format!("@everyone {}", formatted),
+                    "content": format!("@everyone {}", self.discord_privacy.render(formatted)),
                     "username": "ArbEngine Alert"
                 }))
                 .send()
@@ -78,7 +189,7 @@ impl AlertManager {
         if let Some(url) = &self.telegram_webhook {
             let _ = self.http_client
                 .post(url)
-                .json(&json!({"text": formatted}))
+                .json(&json!({"text": self.telegram_privacy.render(&formatted)}))
                 .send()
                 .await
                 .map_err(|e| error!("Failed to send Telegram info: {}", e));
@@ -86,7 +197,114 @@ impl AlertManager {
     }
     
     pub async fn send_profit_alert(&self, profit: f64, details: &str) {
-        let formatted = format!("💰 Profit: ${:.2}\n{}", profit, details);
+        let formatted = format!("💰 Profit: {}\n{}", self.money(profit), details);
         self.send_info(&formatted).await;
     }
 }
+
+/// Formats the end-of-day digest: P&L, VaR/ES per method and the VaR backtest.
+pub fn daily_digest_message(digest: &DailyRiskDigest, fx: &FxRate) -> String {
+    let money = |usd: Decimal| fx.format(usd.to_f64().unwrap_or_default());
+    let mut lines = vec![
+        format!("📅 Daily digest {}", digest.date),
+        format!("Trades: {} | P&L: {}", digest.trades, money(digest.daily_pnl)),
+    ];
+
+    lines.push(match digest.var_breached {
+        Some(true) => "⚠️ Loss exceeded yesterday's VaR forecast".to_string(),
+        Some(false) => "VaR forecast held".to_string(),
+        None => "No VaR forecast to backtest yet".to_string(),
+    });
+
+    for estimate in &digest.estimates {
+        let marker = if estimate.method == digest.var_forecast.method { " *" } else { "" };
+        lines.push(format!(
+            "VaR {:.0}% {} ({}p): {} | ES {}{}",
+            estimate.confidence * 100.0,
+            estimate.method,
+            estimate.horizon_periods,
+            money(estimate.var),
+            money(estimate.expected_shortfall),
+            marker
+        ));
+    }
+
+    let backtest = &digest.backtest;
+    lines.push(format!(
+        "Backtest: {}/{} breaches ({:.1}% vs {:.1}% expected){}",
+        backtest.breaches,
+        backtest.observations,
+        backtest.breach_rate * 100.0,
+        backtest.expected_rate * 100.0,
+        if backtest.rejected { " - model REJECTED (Kupiec)" } else { "" }
+    ));
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use solana_arb_core::risk::var::{VarBacktest, VarEstimate, VarMethod};
+
+    #[test]
+    fn test_daily_digest_message() {
+        let estimate = |method| VarEstimate {
+            method,
+            confidence: 0.95,
+            horizon_periods: 1,
+            var: Decimal::from(12),
+            expected_shortfall: Decimal::from(15),
+            scenarios: 100,
+        };
+        let digest = DailyRiskDigest {
+            date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            trades: 7,
+            daily_pnl: Decimal::from(-20),
+            worst_trade: None,
+            day_forecast: Some(Decimal::from(15)),
+            var_forecast: estimate(VarMethod::Historical),
+            estimates: vec![estimate(VarMethod::Parametric), estimate(VarMethod::Historical)],
+            var_breached: Some(true),
+            backtest: VarBacktest::new(10).summary(0.95),
+        };
+
+        let message = daily_digest_message(&digest, &FxRate::default());
+        assert!(message.contains("Trades: 7 | P&L: $-20.00"));
+        assert!(message.contains("exceeded"));
+        assert!(message.contains("VaR 95% historical (1p): $12.00 | ES $15.00 *"));
+        assert!(message.contains("Backtest: 0/0 breaches"));
+
+        let eur = FxRate {
+            currency: "EUR".to_string(),
+            usd_per_unit: Decimal::new(125, 2),
+            updated_at: chrono::Utc::now(),
+        };
+        let message = daily_digest_message(&digest, &eur);
+        assert!(message.contains("Trades: 7 | P&L: -16.00 EUR"));
+        assert!(message.contains("(1p): 9.60 EUR | ES 12.00 EUR *"));
+    }
+
+    #[test]
+    fn test_escalation_webhook_follows_its_privacy() {
+        let store = Arc::new(IncidentStore::new(None));
+        let formatted = "🔺 ESCALATED [incident #1]: Balance 2.5 SOL below $300";
+
+        let plain = AlertManager::new(None, None).with_incidents(store.clone(), None, ChannelPrivacy::default());
+        assert_eq!(plain.escalation_body(formatted)["text"], formatted);
+
+        let redacted = AlertManager::new(None, None)
+            .with_incidents(store.clone(), None, ChannelPrivacy::new(true, None).unwrap());
+        let body = redacted.escalation_body(formatted);
+        assert_eq!(body["text"], "🔺 ESCALATED [incident #1]: Balance *** SOL below $***");
+        assert_eq!(body["content"], body["text"]);
+
+        let public = "3p7bfXt9wbTTW2HC7OQ1Nz+DQ8hbeGdNrfx+FG+IK08=";
+        let encrypted = AlertManager::new(None, None)
+            .with_incidents(store, None, ChannelPrivacy::new(false, Some(public)).unwrap());
+        let text = encrypted.escalation_body(formatted)["text"].as_str().unwrap().to_string();
+        assert!(text.starts_with("🔒 "));
+        assert!(!text.contains("ESCALATED"));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::blacklist::Blacklist;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Deserialize)]
+struct RemoveRequest {
+    pair: String,
+    /// With both venues, only that route's entry is removed
+    #[serde(default)]
+    buy_dex: Option<String>,
+    #[serde(default)]
+    sell_dex: Option<String>,
+}
+
+/// Routes to list blacklisted pairs/routes and lift entries by hand.
+///
+/// Removing an entry requires `Authorization: Bearer <token>` and is refused
+/// while no `admin_token` is set.
+pub fn blacklist_routes(blacklist: Arc<RwLock<Blacklist>>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/blacklist", get(list_blacklist))
+        .route("/blacklist/remove", post(remove_entry))
+        .layer(Extension(blacklist))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn list_blacklist(
+    Extension(blacklist): Extension<Arc<RwLock<Blacklist>>>,
+) -> impl IntoResponse {
+    Json(json!({ "entries": blacklist.read().await.entries() }))
+}
+
+async fn remove_entry(
+    Extension(blacklist): Extension<Arc<RwLock<Blacklist>>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<RemoveRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let mut blacklist = blacklist.write().await;
+    let removed = blacklist.remove(&req.pair, req.buy_dex.as_deref(), req.sell_dex.as_deref());
+    if removed.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("{} is not blacklisted", req.pair) })),
+        );
+    }
+    if let Err(e) = blacklist.save() {
+        tracing::warn!("Failed to save blacklist: {}", e);
+    }
+    for entry in &removed {
+        tracing::info!("✅ {} removed from the blacklist via API", entry.label());
+    }
+    (StatusCode::OK, Json(json!({ "removed": removed })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use crate::config_manager::RoutingConfig;
+
+    #[tokio::test]
+    async fn test_remove_requires_the_admin_token() {
+        let path = std::env::temp_dir().join(format!("blacklist-{}.json", solana_arb_core::Uuid::new_v4()));
+        let config = RoutingConfig { max_pair_failures: 1, ..RoutingConfig::default() };
+        let mut list = Blacklist::load(&path, config);
+        list.record_failure("SOL/USDC", "Raydium", "Orca", "err");
+        let blacklist = Arc::new(RwLock::new(list));
+        let routes = |token: Option<&str>| blacklist_routes(blacklist.clone(), token.map(str::to_string));
+        let body = || Some(json!({ "pair": "SOL/USDC" }));
+
+        let status = send(routes(None), "POST", "/blacklist/remove", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/blacklist/remove", Some("wrong"), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/blacklist/remove", None, body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(blacklist.read().await.entries().len(), 1);
+
+        let status = send(routes(Some(TOKEN)), "POST", "/blacklist/remove", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(blacklist.read().await.entries().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
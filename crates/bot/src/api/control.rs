@@ -0,0 +1,100 @@
+use crate::control::TradingControl;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+struct PauseRequest {
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Routes to pause and resume trading by hand. Telegram commands share the
+/// same `TradingControl`.
+///
+/// Pausing and resuming require `Authorization: Bearer <token>` and are
+/// refused while no `admin_token` is set.
+pub fn control_routes(control: Arc<TradingControl>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/control", get(control_status))
+        .route("/control/pause", post(pause))
+        .route("/control/resume", post(resume))
+        .layer(Extension(control))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn control_status(Extension(control): Extension<Arc<TradingControl>>) -> impl IntoResponse {
+    Json(json!({ "paused": control.paused() }))
+}
+
+async fn pause(
+    Extension(control): Extension<Arc<TradingControl>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    body: Option<Json<PauseRequest>>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let reason = body.and_then(|Json(req)| req.reason);
+    if !control.pause("api", reason) {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "trading is already paused", "paused": control.paused() })),
+        );
+    }
+    tracing::warn!("⏸️ Trading paused via API");
+    (StatusCode::OK, Json(json!({ "paused": control.paused() })))
+}
+
+async fn resume(
+    Extension(control): Extension<Arc<TradingControl>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    match control.resume() {
+        Some(lifted) => {
+            tracing::info!("▶️ Trading resumed via API (paused by {})", lifted.by);
+            (StatusCode::OK, Json(json!({ "resumed": lifted })))
+        }
+        None => (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "trading is not paused" })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+
+    #[tokio::test]
+    async fn test_pause_and_resume_require_the_admin_token() {
+        let control = Arc::new(TradingControl::new());
+        let routes = |token: Option<&str>| control_routes(control.clone(), token.map(str::to_string));
+
+        for (uri, paused_after) in [("/control/pause", true), ("/control/resume", false)] {
+            let status = send(routes(None), "POST", uri, Some(TOKEN), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", uri, Some("wrong"), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", uri, None, None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(control.paused().is_some(), !paused_after);
+
+            let status = send(routes(Some(TOKEN)), "POST", uri, Some(TOKEN), None).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(control.paused().is_some(), paused_after);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use solana_arb_core::discovery::PoolDiscovery;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[derive(Debug, Deserialize)]
+struct PairRequest {
+    /// e.g. `BONK/USDC`
+    pair: String,
+}
+
+/// Routes to list pairs proposed by pool discovery and to enable or disable
+/// them by hand. Only pairs the token screener approved can be enabled.
+///
+/// Enabling and disabling require `Authorization: Bearer <token>` and are
+/// refused while no `admin_token` is set.
+pub fn discovery_routes(discovery: Arc<RwLock<PoolDiscovery>>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/discovery/pairs", get(list_proposals))
+        .route("/discovery/enable", post(enable_pair))
+        .route("/discovery/disable", post(disable_pair))
+        .layer(Extension(discovery))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn list_proposals(
+    Extension(discovery): Extension<Arc<RwLock<PoolDiscovery>>>,
+) -> impl IntoResponse {
+    let discovery = discovery.read().await;
+    Json(json!({
+        "last_scan": discovery.last_scan(),
+        "proposals": discovery.proposals(),
+    }))
+}
+
+async fn enable_pair(
+    Extension(discovery): Extension<Arc<RwLock<PoolDiscovery>>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<PairRequest>,
+) -> impl IntoResponse {
+    change(&discovery, &admin_token, &headers, &req.pair, true).await
+}
+
+async fn disable_pair(
+    Extension(discovery): Extension<Arc<RwLock<PoolDiscovery>>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<PairRequest>,
+) -> impl IntoResponse {
+    change(&discovery, &admin_token, &headers, &req.pair, false).await
+}
+
+async fn change(
+    discovery: &RwLock<PoolDiscovery>,
+    admin_token: &Option<String>,
+    headers: &HeaderMap,
+    pair: &str,
+    enable: bool,
+) -> (StatusCode, Json<serde_json::Value>) {
+    if !super::is_authorized(headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let mut discovery = discovery.write().await;
+    let pair = pair.trim().to_uppercase();
+    let result = if enable {
+        discovery.enable(&pair)
+    } else {
+        discovery.disable(&pair)
+    };
+    match result {
+        Ok(proposal) => {
+            if let Err(e) = discovery.save() {
+                tracing::warn!("Failed to save discovered pairs: {}", e);
+            }
+            tracing::info!(
+                "🔎 {} {} via API",
+                pair,
+                if enable { "enabled" } else { "disabled" }
+            );
+            (StatusCode::OK, Json(json!({ "proposal": proposal })))
+        }
+        Err(e) => (StatusCode::CONFLICT, Json(json!({ "error": e }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use solana_arb_core::discovery::DiscoveryConfig;
+
+    #[tokio::test]
+    async fn test_enable_and_disable_require_the_admin_token() {
+        let path = std::env::temp_dir().join(format!("discovery-{}.json", solana_arb_core::Uuid::new_v4()));
+        let discovery = Arc::new(RwLock::new(PoolDiscovery::load(&path, DiscoveryConfig::default())));
+        let routes = |token: Option<&str>| discovery_routes(discovery.clone(), token.map(str::to_string));
+        let body = || Some(json!({ "pair": "BONK/USDC" }));
+
+        for uri in ["/discovery/enable", "/discovery/disable"] {
+            let status = send(routes(None), "POST", uri, Some(TOKEN), body()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", uri, Some("wrong"), body()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", uri, None, body()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            // Past the token check, the pair was simply never proposed
+            let status = send(routes(Some(TOKEN)), "POST", uri, Some(TOKEN), body()).await;
+            assert_eq!(status, StatusCode::CONFLICT);
+        }
+    }
+}
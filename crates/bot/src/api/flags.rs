@@ -0,0 +1,92 @@
+use crate::feature_flags::FeatureFlags;
+use axum::{
+    extract::Path,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct SetFlagRequest {
+    enabled: bool,
+    /// Who is flipping the flag, recorded in the audit trail
+    #[serde(default)]
+    actor: Option<String>,
+}
+
+/// Routes to list, flip and audit feature flags.
+///
+/// Flipping a flag requires `Authorization: Bearer <token>` and is refused
+/// while no `admin_token` is set.
+pub fn flag_routes(flags: Arc<FeatureFlags>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/flags", get(list_flags))
+        .route("/flags/audit", get(flag_audit))
+        .route("/flags/:name", put(set_flag))
+        .layer(Extension(flags))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn list_flags(Extension(flags): Extension<Arc<FeatureFlags>>) -> impl IntoResponse {
+    Json(json!({ "flags": flags.list() }))
+}
+
+async fn flag_audit(Extension(flags): Extension<Arc<FeatureFlags>>) -> impl IntoResponse {
+    Json(json!({ "changes": flags.audit_trail() }))
+}
+
+async fn set_flag(
+    Extension(flags): Extension<Arc<FeatureFlags>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<SetFlagRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let actor = req.actor.unwrap_or_else(|| "api".to_string());
+    match flags.set(&name, req.enabled, &actor).await {
+        Ok(change) => (
+            StatusCode::OK,
+            Json(json!({ "flag": name, "enabled": req.enabled, "changed": change.is_some() })),
+        ),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use crate::feature_flags::FLASH_LOANS;
+
+    fn routes(flags: &Arc<FeatureFlags>, token: Option<&str>) -> Router {
+        flag_routes(flags.clone(), token.map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn test_set_flag_requires_the_admin_token() {
+        let flags = Arc::new(FeatureFlags::with_defaults());
+        let uri = format!("/flags/{}", FLASH_LOANS);
+        let body = || Some(json!({ "enabled": true }));
+
+        // No token configured: refused even with a bearer header
+        let status = send(routes(&flags, None), "PUT", &uri, Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(&flags, Some(TOKEN)), "PUT", &uri, Some("wrong"), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(&flags, Some(TOKEN)), "PUT", &uri, None, body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!flags.is_enabled(FLASH_LOANS));
+
+        let status = send(routes(&flags, Some(TOKEN)), "PUT", &uri, Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(flags.is_enabled(FLASH_LOANS));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::incidents::{IncidentState, IncidentStore};
+use axum::{
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    /// `open`, `acked` or `resolved`
+    state: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AckRequest {
+    #[serde(default)]
+    by: Option<String>,
+}
+
+/// Routes to list critical alert incidents, acknowledge them (which stops
+/// escalation) and resolve them.
+///
+/// Acknowledging and resolving require `Authorization: Bearer <token>` and
+/// are refused while no `admin_token` is set.
+pub fn incident_routes(incidents: Arc<IncidentStore>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/incidents", get(list_incidents))
+        .route("/incidents/:id/ack", post(acknowledge))
+        .route("/incidents/:id/resolve", post(resolve))
+        .layer(Extension(incidents))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn list_incidents(
+    Extension(incidents): Extension<Arc<IncidentStore>>,
+    Query(query): Query<ListQuery>,
+) -> impl IntoResponse {
+    let state = match query.state.as_deref().map(str::parse::<IncidentState>).transpose() {
+        Ok(state) => state,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    (StatusCode::OK, Json(json!({ "incidents": incidents.list(state) })))
+}
+
+async fn acknowledge(
+    Extension(incidents): Extension<Arc<IncidentStore>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Path(id): Path<u64>,
+    body: Option<Json<AckRequest>>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let by = body
+        .and_then(|Json(req)| req.by)
+        .unwrap_or_else(|| "api".to_string());
+    match incidents.acknowledge(id, &by) {
+        Ok(incident) => {
+            tracing::info!("✅ Incident #{} acknowledged by {}", id, by);
+            (StatusCode::OK, Json(json!(incident)))
+        }
+        Err(e) => (StatusCode::NOT_FOUND, Json(json!({ "error": e }))),
+    }
+}
+
+async fn resolve(
+    Extension(incidents): Extension<Arc<IncidentStore>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    match incidents.resolve(id) {
+        Ok(incident) => {
+            tracing::info!("✅ Incident #{} resolved", id);
+            (StatusCode::OK, Json(json!(incident)))
+        }
+        Err(e) => (StatusCode::NOT_FOUND, Json(json!({ "error": e }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use crate::incidents::Raised;
+
+    #[tokio::test]
+    async fn test_ack_and_resolve_require_the_admin_token() {
+        let incidents = Arc::new(IncidentStore::new(None));
+        let Raised::New(id) = incidents.raise("wallet balance low") else {
+            panic!("expected a new incident");
+        };
+        let routes = |token: Option<&str>| incident_routes(incidents.clone(), token.map(str::to_string));
+
+        for action in ["ack", "resolve"] {
+            let uri = format!("/incidents/{}/{}", id, action);
+            let status = send(routes(None), "POST", &uri, Some(TOKEN), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", &uri, Some("wrong"), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let status = send(routes(Some(TOKEN)), "POST", &uri, None, None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(incidents.list(Some(IncidentState::Resolved)).len(), 0);
+            let status = send(routes(Some(TOKEN)), "POST", &uri, Some(TOKEN), None).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        assert_eq!(incidents.list(Some(IncidentState::Resolved)).len(), 1);
+    }
+}
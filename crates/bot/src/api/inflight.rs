@@ -0,0 +1,17 @@
+use crate::inflight::InFlightTrades;
+use axum::{routing::get, Extension, Json, Router};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Route listing the trades executing right now, with their current stage,
+/// time spent in each stage and signatures
+pub fn inflight_routes(inflight: Arc<InFlightTrades>) -> Router {
+    Router::new()
+        .route("/api/inflight", get(list_inflight))
+        .layer(Extension(inflight))
+}
+
+async fn list_inflight(Extension(inflight): Extension<Arc<InFlightTrades>>) -> Json<serde_json::Value> {
+    let trades = inflight.snapshot();
+    Json(json!({ "count": trades.len(), "trades": trades }))
+}
@@ -0,0 +1,73 @@
+use crate::manual_trade::{ManualTradeRequest, ManualTrades};
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::post,
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Route to fire a manual trade and wait for its report.
+///
+/// Like the other admin routes, this one is refused unless `admin_token` is
+/// set, and then requires `Authorization: Bearer <token>`.
+pub fn manual_trade_routes(trades: ManualTrades, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/trades/manual", post(manual_trade))
+        .layer(Extension(trades))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn manual_trade(
+    Extension(trades): Extension<ManualTrades>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(request): Json<ManualTradeRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let order = match request.validate() {
+        Ok(order) => order,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    tracing::warn!(
+        "🖐️ Manual trade requested via API: buy {} on {}, sell on {}, ${}",
+        order.pair,
+        order.buy_dex,
+        order.sell_dex,
+        order.size_usd
+    );
+    match trades.submit(order).await {
+        Ok(report) => (StatusCode::OK, Json(json!(report))),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": format!("{:#}", e) })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+
+    #[tokio::test]
+    async fn test_manual_trade_requires_the_admin_token() {
+        let (trades, _jobs) = ManualTrades::channel();
+        let routes = |token: Option<&str>| manual_trade_routes(trades.clone(), token.map(str::to_string));
+        // Fails validation, so an authorized request never reaches the queue
+        let body = || {
+            Some(json!({ "pair": "SOL/USDC", "buy_dex": "Orca", "sell_dex": "Orca", "size_usd": "10" }))
+        };
+
+        let status = send(routes(None), "POST", "/trades/manual", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/trades/manual", None, body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let status = send(routes(Some(TOKEN)), "POST", "/trades/manual", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
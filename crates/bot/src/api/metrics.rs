@@ -1,12 +1,83 @@
+use crate::listeners::SharedEndpoints;
 use crate::metrics::prometheus::MetricsCollector;
-use axum::{response::IntoResponse, routing::get, Extension, Router};
+use crate::metrics::recorder::{parse_time, to_csv, MetricsRecorder};
+use axum::{
+    extract::Query, http::StatusCode, response::IntoResponse, routing::get, Extension, Json,
+    Router,
+};
 use prometheus::{Encoder, TextEncoder};
+use serde::Deserialize;
 use std::sync::Arc;
 
-pub fn metrics_routes(metrics: Arc<MetricsCollector>) -> Router {
+/// `GET /metrics/export` parameters: RFC 3339 or `YYYY-MM-DD` bounds and
+/// comma-separated series prefixes
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    from: Option<String>,
+    to: Option<String>,
+    series: Option<String>,
+}
+
+pub fn metrics_routes(
+    metrics: Arc<MetricsCollector>,
+    recorder: Arc<MetricsRecorder>,
+    endpoints: SharedEndpoints,
+) -> Router {
     Router::new()
         .route("/metrics", get(metrics_handler))
+        .route("/metrics/export", get(export_handler))
+        .route("/endpoints", get(endpoints_handler))
         .layer(Extension(metrics))
+        .layer(Extension(recorder))
+        .layer(Extension(endpoints))
+}
+
+/// Where the health API and this server are listening
+async fn endpoints_handler(Extension(endpoints): Extension<SharedEndpoints>) -> impl IntoResponse {
+    Json(endpoints.read().map(|e| e.clone()).unwrap_or_default())
+}
+
+/// Recorded metrics for a time range as CSV
+async fn export_handler(
+    Extension(recorder): Extension<Arc<MetricsRecorder>>,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
+    let bounds = (|| {
+        Ok::<_, anyhow::Error>((
+            query.from.as_deref().map(parse_time).transpose()?,
+            query.to.as_deref().map(parse_time).transpose()?,
+        ))
+    })();
+    let (from, to) = match bounds {
+        Ok(bounds) => bounds,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                [(axum::http::header::CONTENT_TYPE, "text/plain")],
+                e.to_string(),
+            )
+        }
+    };
+    let series: Vec<String> = query
+        .series
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    match recorder.read_range(from, to) {
+        Ok(samples) => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "text/csv")],
+            to_csv(&samples, &series),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(axum::http::header::CONTENT_TYPE, "text/plain")],
+            format!("failed to read metrics timeseries: {}", e),
+        ),
+    }
 }
 
 async fn metrics_handler(
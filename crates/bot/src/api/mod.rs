@@ -1 +1,106 @@
+pub mod blacklist;
+pub mod control;
+pub mod discovery;
+pub mod flags;
+pub mod incidents;
+pub mod inflight;
+pub mod manual_trade;
 pub mod metrics;
+pub mod postmortems;
+pub mod priority_fee;
+pub mod quarantine;
+pub mod regime;
+pub mod replay;
+pub mod replication;
+pub mod scoreboard;
+pub mod sessions;
+pub mod traces;
+pub mod webhooks;
+
+use axum::http::HeaderMap;
+
+/// Whether the request carries `Authorization: Bearer <admin_token>`.
+/// Always false when no admin token is configured, so admin routes are
+/// closed until `ADMIN_API_TOKEN` is set.
+pub(crate) fn is_authorized(headers: &HeaderMap, admin_token: Option<&str>) -> bool {
+    let Some(token) = admin_token else {
+        return false;
+    };
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early on the first differing byte, so the
+/// response time does not leak how much of the token was guessed right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Helpers for driving the route modules in tests
+#[cfg(test)]
+pub(crate) mod testing {
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    /// Token the route tests configure
+    pub const TOKEN: &str = "test-admin-token";
+
+    /// Send `method uri` with an optional bearer token and JSON body
+    pub async fn send(
+        router: Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> StatusCode {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let body = match body {
+            Some(json) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(json.to_string())
+            }
+            None => Body::empty(),
+        };
+        router.oneshot(request.body(body).unwrap()).await.unwrap().status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let value = HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+        headers.insert(axum::http::header::AUTHORIZATION, value);
+        headers
+    }
+
+    #[test]
+    fn test_is_authorized_denies_without_a_configured_token() {
+        assert!(!is_authorized(&HeaderMap::new(), None));
+        assert!(!is_authorized(&bearer("anything"), None));
+    }
+
+    #[test]
+    fn test_is_authorized_checks_the_bearer_token() {
+        assert!(is_authorized(&bearer("secret"), Some("secret")));
+        assert!(!is_authorized(&bearer("secreT"), Some("secret")));
+        assert!(!is_authorized(&bearer("secret2"), Some("secret")));
+        assert!(!is_authorized(&HeaderMap::new(), Some("secret")));
+    }
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+struct PostmortemDir(PathBuf);
+
+/// Routes to list the post-mortem bundles of losing trades and download one.
+///
+/// Both require `Authorization: Bearer <token>` and are refused while no
+/// `admin_token` is set, as bundles hold signatures, sizes and simulation logs.
+pub fn postmortem_routes(dir: PathBuf, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/postmortems", get(list_postmortems))
+        .route("/postmortems/:file", get(download_postmortem))
+        .layer(Extension(Arc::new(PostmortemDir(dir))))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn list_postmortems(
+    Extension(dir): Extension<Arc<PostmortemDir>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let mut bundles: Vec<_> = std::fs::read_dir(&dir.0)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let size = entry.metadata().ok()?.len();
+            name.ends_with(".zip")
+                .then(|| json!({ "file": name, "bytes": size }))
+        })
+        .collect();
+    // Names start with the trade time, so newest first
+    bundles.sort_by(|a, b| b["file"].as_str().cmp(&a["file"].as_str()));
+    (StatusCode::OK, Json(json!({ "postmortems": bundles })))
+}
+
+async fn download_postmortem(
+    Extension(dir): Extension<Arc<PostmortemDir>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Path(file): Path<String>,
+) -> Response {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" }))).into_response();
+    }
+    // Plain bundle names only, so callers cannot read elsewhere
+    if file.contains(['/', '\\']) || file.starts_with('.') || !file.ends_with(".zip") {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid bundle name" })))
+            .into_response();
+    }
+    match tokio::fs::read(dir.0.join(&file)).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, "application/zip".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", file),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, Json(json!({ "error": "no such post-mortem" })))
+            .into_response(),
+    }
+}
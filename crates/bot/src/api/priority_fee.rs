@@ -0,0 +1,144 @@
+use crate::priority_fee::PriorityFeeControl;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct SetOverrideRequest {
+    micro_lamports: u64,
+    ttl_secs: u64,
+    /// Who is setting the fee, recorded in the audit log
+    #[serde(default)]
+    actor: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ClearOverrideRequest {
+    #[serde(default)]
+    actor: Option<String>,
+}
+
+/// Routes to view, set and clear the priority fee override.
+///
+/// `configured` is the fee used when no override is in force. Changes
+/// require `Authorization: Bearer <token>` and are refused while no
+/// `admin_token` is set.
+pub fn priority_fee_routes(
+    control: Arc<PriorityFeeControl>,
+    configured: u64,
+    admin_token: Option<String>,
+) -> Router {
+    Router::new()
+        .route(
+            "/priority-fee",
+            get(get_override).put(set_override).delete(clear_override),
+        )
+        .layer(Extension(control))
+        .layer(Extension(configured))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn get_override(
+    Extension(control): Extension<Arc<PriorityFeeControl>>,
+    Extension(configured): Extension<u64>,
+) -> impl IntoResponse {
+    let active = control.active();
+    Json(json!({
+        "configured_micro_lamports": configured,
+        "effective_micro_lamports": active.as_ref().map_or(configured, |fee| fee.micro_lamports),
+        "override": active,
+    }))
+}
+
+async fn set_override(
+    Extension(control): Extension<Arc<PriorityFeeControl>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<SetOverrideRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let actor = req.actor.unwrap_or_else(|| "api".to_string());
+    match control
+        .set(req.micro_lamports, req.ttl_secs, &actor, req.reason)
+        .await
+    {
+        Ok(fee) => (StatusCode::OK, Json(json!({ "override": fee }))),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    }
+}
+
+async fn clear_override(
+    Extension(control): Extension<Arc<PriorityFeeControl>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    body: Option<Json<ClearOverrideRequest>>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let actor = body.and_then(|Json(req)| req.actor).unwrap_or_else(|| "api".to_string());
+    let cleared = control.clear(&actor).await;
+    (StatusCode::OK, Json(json!({ "cleared": cleared })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+
+    fn routes(control: &Arc<PriorityFeeControl>, token: Option<&str>) -> Router {
+        priority_fee_routes(control.clone(), 10_000, token.map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn test_set_override_refused_without_a_configured_token() {
+        let control = Arc::new(PriorityFeeControl::new());
+        let body = Some(json!({ "micro_lamports": 500_000, "ttl_secs": 60 }));
+        let status = send(routes(&control, None), "PUT", "/priority-fee", Some(TOKEN), body).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(control.active().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_override_refused_with_a_wrong_token() {
+        let control = Arc::new(PriorityFeeControl::new());
+        let body = || Some(json!({ "micro_lamports": 500_000, "ttl_secs": 60 }));
+        let status = send(routes(&control, Some(TOKEN)), "PUT", "/priority-fee", Some("wrong"), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(&control, Some(TOKEN)), "PUT", "/priority-fee", None, body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(control.active().is_none());
+
+        let status = send(routes(&control, Some(TOKEN)), "PUT", "/priority-fee", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(control.fee_or(10_000), 500_000);
+    }
+
+    #[tokio::test]
+    async fn test_clear_override_requires_the_admin_token() {
+        let control = Arc::new(PriorityFeeControl::new());
+        control.set(500_000, 60, "test", None).await.unwrap();
+
+        let status = send(routes(&control, None), "DELETE", "/priority-fee", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(&control, Some(TOKEN)), "DELETE", "/priority-fee", Some("wrong"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(control.active().is_some());
+
+        let status = send(routes(&control, Some(TOKEN)), "DELETE", "/priority-fee", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(control.active().is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::quarantine::Quarantine;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct UnlockRequest {
+    /// Public key of the quarantined wallet, as a confirmation
+    wallet: String,
+}
+
+/// Routes to inspect and lift a wallet quarantine.
+///
+/// Unlocking always requires `Authorization: Bearer <token>`: without an
+/// `ADMIN_API_TOKEN` the quarantine can only be lifted by deleting its
+/// state file while the bot is stopped.
+pub fn quarantine_routes(quarantine: Arc<Quarantine>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/quarantine", get(quarantine_status))
+        .route("/quarantine/unlock", post(unlock))
+        .layer(Extension(quarantine))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn quarantine_status(Extension(quarantine): Extension<Arc<Quarantine>>) -> impl IntoResponse {
+    Json(json!({ "quarantine": quarantine.record() }))
+}
+
+async fn unlock(
+    Extension(quarantine): Extension<Arc<Quarantine>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<UnlockRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    match quarantine.unlock(&req.wallet) {
+        Ok(lifted) => {
+            tracing::warn!("🔓 Wallet quarantine lifted via API (was: {})", lifted.reason);
+            (StatusCode::OK, Json(json!({ "unlocked": lifted })))
+        }
+        Err(e) => (StatusCode::CONFLICT, Json(json!({ "error": e.to_string() }))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+
+    #[tokio::test]
+    async fn test_unlock_requires_the_admin_token() {
+        let path = std::env::temp_dir().join(format!("quarantine-{}.json", solana_arb_core::Uuid::new_v4()));
+        let quarantine = Arc::new(Quarantine::load(&path));
+        let routes = |token: Option<&str>| quarantine_routes(quarantine.clone(), token.map(str::to_string));
+        let body = || Some(json!({ "wallet": "wallet" }));
+
+        let status = send(routes(None), "POST", "/quarantine/unlock", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/quarantine/unlock", Some("wrong"), body()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Authorized, but there is no quarantine to lift
+        let status = send(routes(Some(TOKEN)), "POST", "/quarantine/unlock", Some(TOKEN), body()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
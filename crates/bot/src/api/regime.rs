@@ -0,0 +1,18 @@
+use axum::{response::IntoResponse, routing::get, Extension, Json, Router};
+use serde_json::json;
+use solana_arb_core::regime::RegimeTracker;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Route to read the cross-DEX spread index and market regime
+pub fn regime_routes(tracker: Arc<RwLock<RegimeTracker>>) -> Router {
+    Router::new()
+        .route("/market/regime", get(current_regime))
+        .layer(Extension(tracker))
+}
+
+async fn current_regime(
+    Extension(tracker): Extension<Arc<RwLock<RegimeTracker>>>,
+) -> impl IntoResponse {
+    Json(json!({ "regime": tracker.read().await.latest() }))
+}
@@ -0,0 +1,96 @@
+use crate::replay::{self, PriceSnapshots, ReplaySettings};
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::post,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct ReplayRequest {
+    /// Archive file name, e.g. `prices-2026-03-02.jsonl`; defaults to the
+    /// file for the day of `from`
+    file: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    /// Return every tick, not only those with opportunities
+    #[serde(default)]
+    all: bool,
+}
+
+struct ReplayContext {
+    snapshots: Option<PriceSnapshots>,
+    settings: ReplaySettings,
+}
+
+/// Route to replay a window of archived prices through a fresh detector and
+/// the built-in strategies, returning the opportunities they find.
+///
+/// Replays require `Authorization: Bearer <token>` and are refused while no
+/// `admin_token` is set.
+pub fn replay_routes(
+    snapshots: Option<PriceSnapshots>,
+    settings: ReplaySettings,
+    admin_token: Option<String>,
+) -> Router {
+    Router::new()
+        .route("/replay", post(replay_snapshot))
+        .layer(Extension(Arc::new(ReplayContext { snapshots, settings })))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn replay_snapshot(
+    Extension(context): Extension<Arc<ReplayContext>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Json(req): Json<ReplayRequest>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let Some(snapshots) = &context.snapshots else {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "price snapshots are not archived (PRICE_SNAPSHOT_DIR is not set)" })),
+        );
+    };
+    let path = match (&req.file, req.from) {
+        (Some(file), _) => snapshots.resolve(file),
+        (None, Some(from)) => Ok(snapshots.path_for(from.date_naive())),
+        (None, None) => Err("either file or from is required".to_string()),
+    };
+    let path = match path {
+        Ok(path) => path,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))),
+    };
+    let ticks = match replay::load_ticks(&path, req.from, req.to) {
+        Ok(ticks) => ticks,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(json!({ "error": e.to_string() }))),
+    };
+
+    let results = replay::replay(&ticks, &context.settings).await;
+    let opportunities: usize = results.iter().map(|t| t.opportunities.len()).sum();
+    let results: Vec<_> = results
+        .into_iter()
+        .filter(|t| req.all || !t.opportunities.is_empty())
+        .collect();
+    tracing::info!(
+        "🔁 Replayed {} ticks from {}: {} opportunities",
+        ticks.len(),
+        path.display(),
+        opportunities
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "file": path.file_name().map(|name| name.to_string_lossy()),
+            "ticks": ticks.len(),
+            "opportunities": opportunities,
+            "results": results,
+        })),
+    )
+}
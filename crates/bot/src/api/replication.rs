@@ -0,0 +1,150 @@
+use crate::replication::Replication;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+struct PromoteRequest {
+    #[serde(default)]
+    reason: Option<String>,
+    /// Promote even though the primary is still answering
+    #[serde(default)]
+    force: bool,
+}
+
+/// Routes for warm standby replication: the primary serves its latest
+/// snapshot, and a standby can be promoted by hand.
+///
+/// Fetching a snapshot and promoting require `Authorization: Bearer <token>`
+/// and are refused while no `admin_token` is set. Promoting a standby whose
+/// primary is still answering also needs `"force": true`.
+pub fn replication_routes(replication: Arc<Replication>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/replication/status", get(replication_status))
+        .route("/replication/snapshot", get(replication_snapshot))
+        .route("/replication/promote", post(promote))
+        .layer(Extension(replication))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn replication_status(
+    Extension(replication): Extension<Arc<Replication>>,
+) -> impl IntoResponse {
+    Json(replication.status())
+}
+
+async fn replication_snapshot(
+    Extension(replication): Extension<Arc<Replication>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    match replication.latest() {
+        Some(snapshot) => (StatusCode::OK, Json(json!(snapshot))),
+        None if replication.is_standby() => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "this instance is a standby" })),
+        ),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "no snapshot taken yet" })),
+        ),
+    }
+}
+
+async fn promote(
+    Extension(replication): Extension<Arc<Replication>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    body: Option<Json<PromoteRequest>>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    let request = body.map(|Json(req)| req).unwrap_or_default();
+    if replication.primary_alive() && !request.force {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "the primary is still answering; pass \"force\": true to promote anyway",
+                "last_sync": replication.status().last_sync,
+            })),
+        );
+    }
+    let reason = request
+        .reason
+        .unwrap_or_else(|| "promoted via API".to_string());
+    if !replication.promote(&reason) {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "this instance already is the primary" })),
+        );
+    }
+    tracing::warn!("⬆️ Standby promoted to primary via API ({})", reason);
+    (StatusCode::OK, Json(json!(replication.status())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use crate::replication::ReplicaSnapshot;
+    use chrono::Utc;
+    use solana_arb_core::risk::RiskManager;
+
+    async fn snapshot() -> ReplicaSnapshot {
+        ReplicaSnapshot {
+            taken_at: Utc::now(),
+            risk: RiskManager::default().snapshot().await,
+            blacklist: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_requires_the_admin_token() {
+        let primary = Arc::new(Replication::new(None, None));
+        primary.publish(snapshot().await);
+        let routes = |token: Option<&str>| replication_routes(primary.clone(), token.map(str::to_string));
+
+        let status = send(routes(None), "GET", "/replication/snapshot", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "GET", "/replication/snapshot", Some("wrong"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "GET", "/replication/snapshot", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "GET", "/replication/snapshot", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_promote_requires_the_admin_token_and_a_silent_primary() {
+        let standby = Arc::new(Replication::new(Some("http://primary:8080".to_string()), None));
+        standby.accept(&snapshot().await);
+        let routes = |token: Option<&str>| replication_routes(standby.clone(), token.map(str::to_string));
+
+        let status = send(routes(None), "POST", "/replication/promote", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/replication/promote", Some("wrong"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let status = send(routes(Some(TOKEN)), "POST", "/replication/promote", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // The primary synced a moment ago
+        let status = send(routes(Some(TOKEN)), "POST", "/replication/promote", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(standby.is_standby());
+
+        let force = Some(json!({ "reason": "drill", "force": true }));
+        let status = send(routes(Some(TOKEN)), "POST", "/replication/promote", Some(TOKEN), force).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!standby.is_standby());
+    }
+}
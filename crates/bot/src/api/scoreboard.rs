@@ -0,0 +1,18 @@
+use crate::route_scoreboard::RouteScoreboard;
+use axum::{response::IntoResponse, routing::get, Extension, Json, Router};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Route to read the per-route detection quality scoreboard
+pub fn scoreboard_routes(scoreboard: Arc<RwLock<RouteScoreboard>>) -> Router {
+    Router::new()
+        .route("/routes/scoreboard", get(list_scores))
+        .layer(Extension(scoreboard))
+}
+
+async fn list_scores(
+    Extension(scoreboard): Extension<Arc<RwLock<RouteScoreboard>>>,
+) -> impl IntoResponse {
+    Json(json!({ "routes": scoreboard.read().await.scores() }))
+}
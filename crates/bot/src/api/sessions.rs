@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use solana_arb_core::database::timescale::TimescaleClient;
+use solana_arb_core::session::SessionComparison;
+use std::sync::Arc;
+
+/// Default and maximum number of sessions returned by `GET /sessions`
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareQuery {
+    a: String,
+    b: String,
+}
+
+/// Routes to list sessions, inspect one and compare the KPIs of two.
+pub fn session_routes(db: Arc<TimescaleClient>) -> Router {
+    Router::new()
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/compare", get(compare_sessions))
+        .route("/sessions/:id", get(get_session))
+        .layer(Extension(db))
+}
+
+fn db_error(e: anyhow::Error) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "error": format!("session store unavailable: {}", e) })),
+    )
+}
+
+async fn list_sessions(
+    Extension(db): Extension<Arc<TimescaleClient>>,
+    Query(query): Query<ListQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    match db.list_sessions(limit).await {
+        Ok(sessions) => (StatusCode::OK, Json(json!({ "sessions": sessions }))),
+        Err(e) => db_error(e),
+    }
+}
+
+async fn get_session(
+    Extension(db): Extension<Arc<TimescaleClient>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let session = match db.get_session(&id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": format!("unknown session '{}'", id) })),
+            )
+        }
+        Err(e) => return db_error(e),
+    };
+
+    match db.session_kpis(&id).await {
+        Ok(kpis) => (
+            StatusCode::OK,
+            Json(json!({ "session": session, "kpis": kpis })),
+        ),
+        Err(e) => db_error(e),
+    }
+}
+
+async fn compare_sessions(
+    Extension(db): Extension<Arc<TimescaleClient>>,
+    Query(query): Query<CompareQuery>,
+) -> impl IntoResponse {
+    for id in [&query.a, &query.b] {
+        match db.get_session(id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({ "error": format!("unknown session '{}'", id) })),
+                )
+            }
+            Err(e) => return db_error(e),
+        }
+    }
+
+    let kpis = async {
+        Ok::<_, anyhow::Error>((
+            db.session_kpis(&query.a).await?,
+            db.session_kpis(&query.b).await?,
+        ))
+    };
+    match kpis.await {
+        Ok((a, b)) => (
+            StatusCode::OK,
+            Json(json!({
+                "a": query.a,
+                "b": query.b,
+                "comparison": SessionComparison::new(a, b),
+            })),
+        ),
+        Err(e) => db_error(e),
+    }
+}
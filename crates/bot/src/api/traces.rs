@@ -0,0 +1,38 @@
+use crate::trade_traces::TradeTraces;
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use serde_json::json;
+use solana_arb_core::Uuid;
+use std::sync::Arc;
+
+/// Route comparing the plan, quote, simulation and on-chain outcome of a
+/// recent trade by opportunity ID
+pub fn trace_routes(traces: Arc<TradeTraces>) -> Router {
+    Router::new()
+        .route("/trades/:opportunity_id/diff", get(trade_diff))
+        .layer(Extension(traces))
+}
+
+async fn trade_diff(
+    Extension(traces): Extension<Arc<TradeTraces>>,
+    Path(opportunity_id): Path<String>,
+) -> impl IntoResponse {
+    let Ok(id) = opportunity_id.parse::<Uuid>() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid opportunity id" })),
+        );
+    };
+    match traces.get(id) {
+        Some(trace) => (StatusCode::OK, Json(json!(trace.diff()))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "no recent trade for this opportunity" })),
+        ),
+    }
+}
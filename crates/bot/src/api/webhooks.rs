@@ -0,0 +1,97 @@
+use crate::trade_webhooks::TradeWebhooks;
+use axum::{
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+struct ReplayQuery {
+    /// UTC trade date, `YYYY-MM-DD`
+    date: NaiveDate,
+}
+
+/// Routes to inspect, retry and replay trade webhooks.
+///
+/// Retry and replay require `Authorization: Bearer <token>` and are refused
+/// while no `admin_token` is set.
+pub fn webhook_routes(webhooks: Arc<TradeWebhooks>, admin_token: Option<String>) -> Router {
+    Router::new()
+        .route("/webhooks/trades", get(webhook_status))
+        .route("/webhooks/trades/retry", post(retry_failed))
+        .route("/webhooks/trades/replay", post(replay_date))
+        .layer(Extension(webhooks))
+        .layer(Extension(Arc::new(admin_token)))
+}
+
+async fn webhook_status(
+    Extension(webhooks): Extension<Arc<TradeWebhooks>>,
+) -> impl IntoResponse {
+    Json(json!({ "webhooks": webhooks.status() }))
+}
+
+async fn retry_failed(
+    Extension(webhooks): Extension<Arc<TradeWebhooks>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    (StatusCode::OK, Json(json!({ "queued": webhooks.retry_failed() })))
+}
+
+async fn replay_date(
+    Extension(webhooks): Extension<Arc<TradeWebhooks>>,
+    Extension(admin_token): Extension<Arc<Option<String>>>,
+    headers: HeaderMap,
+    Query(query): Query<ReplayQuery>,
+) -> impl IntoResponse {
+    if !super::is_authorized(&headers, admin_token.as_deref()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    match webhooks.replay(query.date).await {
+        Ok(queued) => (
+            StatusCode::OK,
+            Json(json!({ "date": query.date, "queued": queued })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e.to_string() })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::testing::{send, TOKEN};
+    use crate::trade_webhooks::TradeWebhookConfig;
+
+    fn routes(token: Option<&str>) -> Router {
+        let webhooks = TradeWebhooks::new(TradeWebhookConfig {
+            url: "http://127.0.0.1:9/hook".to_string(),
+            secret: None,
+            max_attempts: 1,
+            journal_path: std::env::temp_dir()
+                .join(format!("webhooks-{}.jsonl", solana_arb_core::Uuid::new_v4())),
+        });
+        webhook_routes(webhooks, token.map(str::to_string))
+    }
+
+    #[tokio::test]
+    async fn test_retry_and_replay_require_the_admin_token() {
+        for uri in ["/webhooks/trades/retry", "/webhooks/trades/replay?date=2024-01-02"] {
+            assert_eq!(send(routes(None), "POST", uri, Some(TOKEN), None).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(send(routes(Some(TOKEN)), "POST", uri, Some("wrong"), None).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(send(routes(Some(TOKEN)), "POST", uri, None, None).await, StatusCode::UNAUTHORIZED);
+            assert_eq!(send(routes(Some(TOKEN)), "POST", uri, Some(TOKEN), None).await, StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Backtesting
+//!
+//! Replays recorded prices through a fresh detector, the path finder and the
+//! built-in strategies (see [`crate::replay`]) and trades what they find on
+//! paper. Each trade is sized at `trade_size_usd`, filled at the quoted
+//! prices moved against us by `slippage_bps` on every leg, and pays each
+//! venue's swap fee plus a flat network fee. Our trades do not move prices.
+//!
+//! A spread that persists over several ticks is one opportunity, not one per
+//! tick: a route is traded when it appears and again only after it has
+//! disappeared for at least a tick.
+//!
+//! The report gives trade count, win rate, P&L, a per-trade Sharpe ratio and
+//! the maximum drawdown of cumulative P&L, overall and per source
+//! (`detector`, `path` or the strategy name). Running it with different
+//! `STAT_ARB_WINDOW` / `STAT_ARB_Z_SCORE` values compares statistical
+//! strategy settings before they go live.
+
+use crate::paths;
+use crate::replay::{ReplaySettings, Replayer, SnapshotTick};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::config::Config;
+use solana_arb_core::pathfinding::{PathFinder, TradingPath};
+use solana_arb_core::{ArbitrageOpportunity, PriceData};
+use std::collections::{BTreeMap, HashSet};
+
+/// How opportunities are filled and which are taken
+#[derive(Debug, Clone)]
+pub struct BacktestSettings {
+    pub replay: ReplaySettings,
+    /// Size of every trade (USD)
+    pub trade_size_usd: Decimal,
+    /// Adverse price move on every leg, in basis points
+    pub slippage_bps: Decimal,
+    /// Network fee of every trade (USD)
+    pub fee_usd: Decimal,
+    /// Opportunities reporting less net profit (%) are not traded
+    pub min_profit_pct: Decimal,
+    /// Trade multi-hop cycles from the path finder
+    pub paths: bool,
+}
+
+impl BacktestSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            replay: ReplaySettings::from_config(config),
+            trade_size_usd: Decimal::from(100),
+            slippage_bps: Decimal::from(10),
+            fee_usd: Decimal::new(1, 2),
+            min_profit_pct: Decimal::ZERO,
+            paths: true,
+        }
+    }
+}
+
+/// One paper trade
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestTrade {
+    pub at: DateTime<Utc>,
+    /// `detector`, `path` or the strategy name
+    pub source: String,
+    /// Pair symbol, or the token cycle of a path
+    pub route: String,
+    /// Buy and sell venue, or each hop's venue for a path
+    pub venues: Vec<String>,
+    /// Net profit the opportunity reported (%)
+    pub expected_profit_pct: Decimal,
+    pub size_usd: Decimal,
+    /// After slippage, swap fees and the network fee
+    pub profit_usd: Decimal,
+}
+
+/// Performance of a set of trades
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Performance {
+    pub trades: usize,
+    pub wins: usize,
+    /// Share of trades with a positive P&L, 0 to 1
+    pub win_rate: f64,
+    pub profit_usd: Decimal,
+    pub volume_usd: Decimal,
+    /// Mean over standard deviation of per-trade returns, not annualized;
+    /// 0 with fewer than two trades or no variance
+    pub sharpe: f64,
+    /// Largest fall of cumulative P&L from a previous peak (USD)
+    pub max_drawdown_usd: Decimal,
+}
+
+impl Performance {
+    pub fn of(trades: &[&BacktestTrade]) -> Self {
+        let mut performance = Self {
+            trades: trades.len(),
+            ..Self::default()
+        };
+        let (mut cumulative, mut peak) = (Decimal::ZERO, Decimal::ZERO);
+        let mut returns = Vec::with_capacity(trades.len());
+        for trade in trades {
+            if trade.profit_usd > Decimal::ZERO {
+                performance.wins += 1;
+            }
+            performance.volume_usd += trade.size_usd;
+            cumulative += trade.profit_usd;
+            peak = peak.max(cumulative);
+            performance.max_drawdown_usd = performance.max_drawdown_usd.max(peak - cumulative);
+            if !trade.size_usd.is_zero() {
+                returns.push((trade.profit_usd / trade.size_usd).to_f64().unwrap_or(0.0));
+            }
+        }
+        performance.profit_usd = cumulative;
+        if !trades.is_empty() {
+            performance.win_rate = performance.wins as f64 / trades.len() as f64;
+        }
+        performance.sharpe = sharpe(&returns);
+        performance
+    }
+}
+
+fn sharpe(returns: &[f64]) -> f64 {
+    if returns.len() < 2 {
+        return 0.0;
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    if variance <= f64::EPSILON {
+        0.0
+    } else {
+        mean / variance.sqrt()
+    }
+}
+
+/// Result of a backtest
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub ticks: usize,
+    pub overall: Performance,
+    pub by_source: BTreeMap<String, Performance>,
+    /// In the order they were made
+    pub trades: Vec<BacktestTrade>,
+}
+
+/// Group prices recorded one by one (e.g. rows of the `price_data` table)
+/// into ticks, one per second
+pub fn ticks_from_prices(mut prices: Vec<PriceData>) -> Vec<SnapshotTick> {
+    prices.sort_by_key(|p| p.timestamp);
+    let mut ticks: Vec<SnapshotTick> = Vec::new();
+    for price in prices {
+        let second = price.timestamp.timestamp();
+        match ticks.last_mut() {
+            Some(tick) if tick.at.timestamp() == second => {
+                tick.at = price.timestamp;
+                tick.prices.push(price);
+            }
+            _ => ticks.push(SnapshotTick {
+                at: price.timestamp,
+                prices: vec![price],
+            }),
+        }
+    }
+    ticks
+}
+
+fn adverse(bps: Decimal) -> Decimal {
+    bps / Decimal::from(10_000)
+}
+
+/// Profit (USD) of buying at the opportunity's buy price and selling at its
+/// sell price, after slippage and swap fees
+fn fill_pair(opp: &ArbitrageOpportunity, settings: &BacktestSettings) -> Decimal {
+    let slippage = adverse(settings.slippage_bps);
+    let buy = opp.buy_price * (Decimal::ONE + slippage);
+    if buy <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    let base = settings.trade_size_usd / buy * (Decimal::ONE - opp.buy_dex.fee_percentage());
+    let proceeds = base
+        * opp.sell_price
+        * (Decimal::ONE - slippage)
+        * (Decimal::ONE - opp.sell_dex.fee_percentage());
+    proceeds - settings.trade_size_usd
+}
+
+/// Profit (USD) of a cycle started with `trade_size_usd` of its start token,
+/// after slippage and swap fees on every hop
+fn fill_path(path: &TradingPath, settings: &BacktestSettings) -> Decimal {
+    let slippage = adverse(settings.slippage_bps);
+    let ratio = path.edges.iter().fold(Decimal::ONE, |ratio, edge| {
+        ratio * edge.rate * (Decimal::ONE - slippage) * (Decimal::ONE - edge.dex.fee_percentage())
+    });
+    settings.trade_size_usd * (ratio - Decimal::ONE)
+}
+
+/// Hops of a cycle as `TOKEN@venue`, rotated to start at the smallest, so
+/// the same cycle found from different start tokens has one key
+fn cycle_hops(path: &TradingPath) -> Vec<String> {
+    let mut hops: Vec<String> = path
+        .edges
+        .iter()
+        .map(|edge| format!("{}@{}", edge.from_token, edge.dex))
+        .collect();
+    if let Some(start) = hops.iter().enumerate().min_by(|a, b| a.1.cmp(b.1)).map(|(i, _)| i) {
+        hops.rotate_left(start);
+    }
+    hops
+}
+
+/// Replay `ticks` and trade what is found
+pub async fn backtest(ticks: &[SnapshotTick], settings: &BacktestSettings) -> BacktestReport {
+    let mut replayer = Replayer::new(&settings.replay);
+    let mut path_finder = PathFinder::new(4);
+    // Routes seen in the previous tick, which are not traded again
+    let mut open: HashSet<String> = HashSet::new();
+    let mut trades = Vec::new();
+
+    for tick in ticks {
+        let replayed = replayer.step(tick).await;
+        let mut seen = HashSet::new();
+
+        for found in &replayed.opportunities {
+            let opp = &found.opportunity;
+            if opp.net_profit_pct < settings.min_profit_pct {
+                continue;
+            }
+            let venues = vec![opp.buy_dex.to_string(), opp.sell_dex.to_string()];
+            let key = format!("{} {} {}", found.source, opp.pair, venues.join(">"));
+            if !seen.insert(key.clone()) || open.contains(&key) {
+                continue;
+            }
+            trades.push(BacktestTrade {
+                at: tick.at,
+                source: found.source.clone(),
+                route: opp.pair.symbol(),
+                venues,
+                expected_profit_pct: opp.net_profit_pct,
+                size_usd: settings.trade_size_usd,
+                profit_usd: fill_pair(opp, settings) - settings.fee_usd,
+            });
+        }
+
+        if settings.paths {
+            path_finder.clear();
+            for price in replayer.current_prices() {
+                path_finder.add_price(price);
+            }
+            for path in path_finder.find_all_profitable_paths() {
+                if path.profit_percentage() < settings.min_profit_pct {
+                    continue;
+                }
+                let key = format!("path {}", cycle_hops(&path).join(">"));
+                if !seen.insert(key.clone()) || open.contains(&key) {
+                    continue;
+                }
+                trades.push(BacktestTrade {
+                    at: tick.at,
+                    source: "path".to_string(),
+                    route: paths::path_label(&path),
+                    venues: path.edges.iter().map(|e| e.dex.to_string()).collect(),
+                    expected_profit_pct: path.profit_percentage(),
+                    size_usd: settings.trade_size_usd,
+                    profit_usd: fill_path(&path, settings) - settings.fee_usd,
+                });
+            }
+        }
+        open = seen;
+    }
+
+    let all: Vec<&BacktestTrade> = trades.iter().collect();
+    let mut sources: BTreeMap<String, Vec<&BacktestTrade>> = BTreeMap::new();
+    for trade in &trades {
+        sources.entry(trade.source.clone()).or_default().push(trade);
+    }
+    BacktestReport {
+        from: ticks.first().map(|t| t.at),
+        to: ticks.last().map(|t| t.at),
+        ticks: ticks.len(),
+        overall: Performance::of(&all),
+        by_source: sources
+            .into_iter()
+            .map(|(source, trades)| (source, Performance::of(&trades)))
+            .collect(),
+        trades,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy_profile::{StrategyProfile, StrategySettings};
+    use chrono::Duration;
+    use solana_arb_core::pricing::spread_guard::SpreadGuard;
+    use solana_arb_core::{DexType, TokenPair};
+
+    fn price(dex: DexType, bid: i64, ask: i64, at: DateTime<Utc>) -> PriceData {
+        let mut price = PriceData::new(dex, TokenPair::new("SOL", "USDC"), Decimal::from(bid), Decimal::from(ask));
+        price.timestamp = at;
+        price
+    }
+
+    fn trade(profit: i64) -> BacktestTrade {
+        BacktestTrade {
+            at: Utc::now(),
+            source: "detector".into(),
+            route: "SOL/USDC".into(),
+            venues: Vec::new(),
+            expected_profit_pct: Decimal::ONE,
+            size_usd: Decimal::from(100),
+            profit_usd: Decimal::from(profit),
+        }
+    }
+
+    #[test]
+    fn test_performance_metrics() {
+        let trades: Vec<_> = [3, -1, -4, 2, 5].into_iter().map(trade).collect();
+        let performance = Performance::of(&trades.iter().collect::<Vec<_>>());
+        assert_eq!((performance.trades, performance.wins), (5, 3));
+        assert_eq!(performance.win_rate, 0.6);
+        assert_eq!(performance.profit_usd, Decimal::from(5));
+        assert_eq!(performance.volume_usd, Decimal::from(500));
+        // Peak of 3 after the first trade, trough of -2 two trades later
+        assert_eq!(performance.max_drawdown_usd, Decimal::from(5));
+        assert!((performance.sharpe - 0.2828).abs() < 1e-3);
+
+        assert_eq!(Performance::of(&[]), Performance::default());
+        assert_eq!(Performance::of(&[&trade(1)]).sharpe, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_spread_is_traded_once() {
+        let t0 = "2026-03-02T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let at = |secs| t0 + Duration::seconds(secs);
+        // Orca 2% above Raydium for two ticks, gone, then back
+        let ticks: Vec<_> = [
+            (0, 102),
+            (1, 102),
+            (2, 100),
+            (3, 102),
+        ]
+        .into_iter()
+        .map(|(secs, orca)| SnapshotTick {
+            at: at(secs),
+            prices: vec![price(DexType::Raydium, 100, 100, at(secs)), price(DexType::Orca, orca, orca, at(secs))],
+        })
+        .collect();
+        let settings = BacktestSettings {
+            replay: ReplaySettings {
+                spread_guard: SpreadGuard::new(1.0),
+                max_price_age_seconds: 5,
+                max_quote_skew_ms: 0,
+                strategies: StrategySettings {
+                    profile: StrategyProfile::Conservative,
+                    ..StrategySettings::default()
+                },
+            },
+            paths: false,
+            ..BacktestSettings::from_config(&Config::default())
+        };
+
+        let report = backtest(&ticks, &settings).await;
+        assert_eq!(report.ticks, 4);
+        let detector: Vec<_> = report.trades.iter().filter(|t| t.source == "detector").collect();
+        assert!(detector.is_empty(), "conservative profile skips the detector");
+        let crossed: Vec<_> = report.trades.iter().filter(|t| t.source == "Crossed Market").collect();
+        assert_eq!(crossed.iter().map(|t| t.at).collect::<Vec<_>>(), [at(0), at(3)]);
+        // $100 at 100.1 less 0.25%, sold at 101.898 less 0.30%, less $0.01
+        let profit = crossed[0].profit_usd;
+        assert!(profit > Decimal::new(120, 2) && profit < Decimal::new(125, 2), "{}", profit);
+        assert_eq!(report.by_source["Crossed Market"].trades, 2);
+        assert_eq!(report.overall.win_rate, 1.0);
+    }
+
+    #[test]
+    fn test_ticks_from_prices_groups_by_second() {
+        let t0 = "2026-03-02T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let prices = vec![
+            price(DexType::Orca, 100, 100, t0 + Duration::milliseconds(1500)),
+            price(DexType::Raydium, 100, 100, t0),
+            price(DexType::Orca, 100, 100, t0 + Duration::milliseconds(300)),
+        ];
+        let ticks = ticks_from_prices(prices);
+        assert_eq!(ticks.iter().map(|t| t.prices.len()).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!(ticks[0].at, t0 + Duration::milliseconds(300));
+    }
+}
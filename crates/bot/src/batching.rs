@@ -0,0 +1,231 @@
+//! Batch Execution Planning
+//!
+//! When several tiny opportunities on disjoint pairs appear in the same tick,
+//! the fixed cost of a transaction (base fee + priority fee) eats most of their
+//! profit. The batcher packs 2-3 compatible swaps into one transaction, staying
+//! inside compute unit and packet size limits, and splits the shared fee across
+//! the legs for accounting.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use solana_arb_core::ArbitrageOpportunity;
+use std::collections::HashSet;
+
+/// Maximum serialized transaction size (`solana_sdk::packet::PACKET_DATA_SIZE`)
+pub const MAX_TX_BYTES: usize = 1232;
+
+/// Maximum compute units a single transaction may request
+pub const MAX_TX_COMPUTE_UNITS: u32 = 1_400_000;
+
+/// Base fee per signature in lamports
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Batching limits
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// Maximum number of swaps in one transaction
+    pub max_legs: usize,
+    /// Only opportunities at or below this size (USD) are batched
+    pub max_leg_size_usd: Decimal,
+    /// Compute units budgeted for each swap
+    pub compute_units_per_leg: u32,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_legs: 3,
+            max_leg_size_usd: Decimal::from(100),
+            compute_units_per_leg: 300_000,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Compute unit limit requested for a batch of `legs` swaps
+    pub fn compute_unit_limit(&self, legs: usize) -> u32 {
+        (self.compute_units_per_leg.saturating_mul(legs as u32)).min(MAX_TX_COMPUTE_UNITS)
+    }
+}
+
+/// One swap in a batch, with its risk-approved size
+#[derive(Debug, Clone)]
+pub struct BatchLeg {
+    pub opportunity: ArbitrageOpportunity,
+    pub size: Decimal,
+}
+
+/// Result of submitting (or simulating) a batch transaction
+#[derive(Debug, Clone)]
+pub struct BatchExecution {
+    /// Shared transaction signature for every leg
+    pub signature: String,
+    /// Total transaction fee in lamports
+    pub fee_lamports: u64,
+    /// Each leg's share of `fee_lamports`, in leg order
+    pub fee_shares: Vec<u64>,
+    /// Net payer balance change from the pre-submission simulation, in quote
+    /// currency (only when submitted)
+    pub simulated_profit: Option<Decimal>,
+}
+
+/// Pick compatible legs from `candidates` (best first).
+///
+/// Legs must be small, on distinct pairs and fit the compute budget. Returns
+/// an empty plan unless at least two legs qualify - a single leg goes through
+/// the normal execution path.
+pub fn plan_batch(candidates: &[BatchLeg], config: &BatchConfig) -> Vec<BatchLeg> {
+    if config.max_legs < 2 {
+        return Vec::new();
+    }
+
+    let mut seen_pairs = HashSet::new();
+    let mut plan = Vec::new();
+
+    for leg in candidates {
+        if plan.len() >= config.max_legs {
+            break;
+        }
+        if leg.size <= Decimal::ZERO || leg.size > config.max_leg_size_usd {
+            continue;
+        }
+        let next_cu = u64::from(config.compute_units_per_leg) * (plan.len() as u64 + 1);
+        if next_cu > u64::from(MAX_TX_COMPUTE_UNITS) {
+            break;
+        }
+        if seen_pairs.insert(leg.opportunity.pair.symbol()) {
+            plan.push(leg.clone());
+        }
+    }
+
+    if plan.len() < 2 {
+        plan.clear();
+    }
+    plan
+}
+
+/// Total fee in lamports for one transaction
+pub fn transaction_fee_lamports(compute_unit_limit: u32, micro_lamports_per_cu: u64) -> u64 {
+    let priority = (u128::from(compute_unit_limit) * u128::from(micro_lamports_per_cu))
+        .div_ceil(1_000_000);
+    LAMPORTS_PER_SIGNATURE + priority as u64
+}
+
+/// Split a shared fee across legs in proportion to their size.
+///
+/// Shares always sum to `total`; leftover lamports from rounding go to the
+/// largest legs first.
+pub fn split_fee(total: u64, sizes: &[Decimal]) -> Vec<u64> {
+    if sizes.is_empty() {
+        return Vec::new();
+    }
+
+    let sum: Decimal = sizes.iter().copied().filter(|s| *s > Decimal::ZERO).sum();
+    let mut shares: Vec<u64> = if sum.is_zero() {
+        vec![total / sizes.len() as u64; sizes.len()]
+    } else {
+        sizes
+            .iter()
+            .map(|s| {
+                (Decimal::from(total) * (*s).max(Decimal::ZERO) / sum)
+                    .floor()
+                    .to_u64()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i]));
+    let mut remainder = total - shares.iter().sum::<u64>();
+    for i in order.iter().cycle() {
+        if remainder == 0 {
+            break;
+        }
+        shares[*i] += 1;
+        remainder -= 1;
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use chrono::Utc;
+    use solana_arb_core::{DexType, TokenPair, Uuid};
+
+    fn leg(base: &str, size: i64) -> BatchLeg {
+        BatchLeg {
+            opportunity: ArbitrageOpportunity {
+                id: Uuid::new_v4(),
+                pair: TokenPair::new(base, "USDC"),
+                buy_dex: DexType::Orca,
+                sell_dex: DexType::Raydium,
+                buy_price: Decimal::from(100),
+                sell_price: Decimal::from(101),
+                gross_profit_pct: Decimal::ONE,
+                net_profit_pct: Decimal::new(5, 1),
+                estimated_profit_usd: None,
+                recommended_size: None,
+                detected_at: Utc::now(),
+                expired_at: None,
+                buy_quote_at: None,
+                sell_quote_at: None,
+                source: OpportunitySource::Detector,
+                strategy: None,
+            },
+            size: Decimal::from(size),
+        }
+    }
+
+    #[test]
+    fn test_plan_picks_small_disjoint_legs() {
+        let candidates = vec![
+            leg("SOL", 50),
+            leg("SOL", 40),  // same pair, skipped
+            leg("RAY", 500), // too large, skipped
+            leg("ORCA", 30),
+            leg("JUP", 20),
+            leg("BONK", 10), // over max_legs
+        ];
+        let plan = plan_batch(&candidates, &BatchConfig::default());
+        let pairs: Vec<String> = plan.iter().map(|l| l.opportunity.pair.symbol()).collect();
+        assert_eq!(pairs, vec!["SOL/USDC", "ORCA/USDC", "JUP/USDC"]);
+    }
+
+    #[test]
+    fn test_plan_requires_two_legs_and_fits_compute() {
+        assert!(plan_batch(&[leg("SOL", 50), leg("SOL", 40)], &BatchConfig::default()).is_empty());
+        let single = BatchConfig {
+            max_legs: 1,
+            ..Default::default()
+        };
+        assert!(plan_batch(&[leg("SOL", 50), leg("RAY", 40)], &single).is_empty());
+
+        let heavy = BatchConfig {
+            compute_units_per_leg: 600_000,
+            ..Default::default()
+        };
+        let plan = plan_batch(&[leg("SOL", 50), leg("RAY", 40), leg("ORCA", 30)], &heavy);
+        assert_eq!(plan.len(), 2);
+        assert!(heavy.compute_unit_limit(plan.len()) <= MAX_TX_COMPUTE_UNITS);
+    }
+
+    #[test]
+    fn test_split_fee_sums_to_total() {
+        let shares = split_fee(10_001, &[Decimal::from(50), Decimal::from(30), Decimal::from(20)]);
+        assert_eq!(shares.iter().sum::<u64>(), 10_001);
+        assert_eq!(shares, vec![5_001, 3_000, 2_000]);
+
+        let even = split_fee(10, &[Decimal::ZERO, Decimal::ZERO, Decimal::ZERO]);
+        assert_eq!(even.iter().sum::<u64>(), 10);
+    }
+
+    #[test]
+    fn test_transaction_fee() {
+        // 600k CU at 50k µL/CU = 30,000 lamports priority + 5,000 base
+        assert_eq!(transaction_fee_lamports(600_000, 50_000), 35_000);
+        assert_eq!(transaction_fee_lamports(1, 1), 5_001);
+    }
+}
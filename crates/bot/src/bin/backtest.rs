@@ -0,0 +1,132 @@
+//! Backtest
+//!
+//! `backtest FILE... [--from TIME] [--to TIME]` replays archived price
+//! snapshot files (see `PRICE_SNAPSHOT_DIR`) and trades what the detector,
+//! path finder and built-in strategies find on paper. `--db` reads the
+//! `price_data` table at `DATABASE_URL` instead, for the last day unless
+//! `--from`/`--to` say otherwise. Strategy settings, the spread guard and
+//! the price age limit come from the environment.
+//!
+//! `--size USD` (default 100), `--slippage-bps N` (10 per leg),
+//! `--fee-usd USD` (0.01 per trade) and `--min-profit PCT` (0) set how
+//! trades are filled and taken; `--no-paths` leaves multi-hop cycles out.
+//! `--json` prints the full report, every trade included.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use solana_arb_bot::backtest::{self, BacktestSettings, Performance};
+use solana_arb_bot::replay;
+use solana_arb_core::config::Config;
+use solana_arb_core::database::timescale::TimescaleClient;
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("backtest: {}", e);
+        std::process::exit(2);
+    }
+}
+
+async fn run(args: &[String]) -> anyhow::Result<()> {
+    let config = Config::from_env().unwrap_or_default();
+    let mut settings = BacktestSettings::from_config(&config);
+    let mut files = Vec::new();
+    let (mut from, mut to) = (None, None);
+    let (mut db, mut json) = (false, false);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a time", arg))?;
+                let time: DateTime<Utc> = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid time '{}'", value))?;
+                if arg == "--from" {
+                    from = Some(time);
+                } else {
+                    to = Some(time);
+                }
+            }
+            "--size" | "--slippage-bps" | "--fee-usd" | "--min-profit" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a number", arg))?;
+                let number: Decimal = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid number '{}'", value))?;
+                match arg.as_str() {
+                    "--size" => settings.trade_size_usd = number,
+                    "--slippage-bps" => settings.slippage_bps = number,
+                    "--fee-usd" => settings.fee_usd = number,
+                    _ => settings.min_profit_pct = number,
+                }
+            }
+            "--no-paths" => settings.paths = false,
+            "--db" => db = true,
+            "--json" => json = true,
+            other if other.starts_with("--") => anyhow::bail!("unknown argument '{}'", other),
+            path => files.push(PathBuf::from(path)),
+        }
+    }
+
+    let ticks = if db {
+        let to = to.unwrap_or_else(Utc::now);
+        let from = from.unwrap_or(to - Duration::days(1));
+        let client = TimescaleClient::new(&config.database_url).await?;
+        backtest::ticks_from_prices(client.price_history(from, to).await?)
+    } else {
+        if files.is_empty() {
+            anyhow::bail!("usage: backtest FILE... [--from TIME] [--to TIME] | --db [--from TIME] [--to TIME]");
+        }
+        let mut ticks = Vec::new();
+        for file in &files {
+            ticks.extend(replay::load_ticks(file, from, to)?);
+        }
+        ticks.sort_by_key(|tick| tick.at);
+        ticks
+    };
+    if ticks.is_empty() {
+        anyhow::bail!("no price ticks in the window");
+    }
+
+    let report = backtest::backtest(&ticks, &settings).await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} ticks from {} to {}, ${} per trade, {} bps slippage per leg, ${} fee",
+        report.ticks,
+        report.from.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        report.to.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        settings.trade_size_usd,
+        settings.slippage_bps,
+        settings.fee_usd
+    );
+    println!(
+        "{:<24} {:>7} {:>8} {:>12} {:>8} {:>12}",
+        "source", "trades", "win rate", "P&L", "sharpe", "max drawdown"
+    );
+    let row = |name: &str, p: &Performance| {
+        println!(
+            "{:<24} {:>7} {:>7.1}% {:>12} {:>8.3} {:>12}",
+            name,
+            p.trades,
+            p.win_rate * 100.0,
+            p.profit_usd.round_dp(2),
+            p.sharpe,
+            p.max_drawdown_usd.round_dp(2)
+        );
+    };
+    for (source, performance) in &report.by_source {
+        row(source, performance);
+    }
+    row("all", &report.overall);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Price snapshot replay
+//!
+//! `replay FILE [--from TIME] [--to TIME] [--all]` runs the ticks of an
+//! archived price snapshot file (see `PRICE_SNAPSHOT_DIR`) through a fresh
+//! detector and the built-in strategies, with the spread guard and price
+//! age limit from the environment, and prints the opportunities found.
+//! Times are RFC 3339, e.g. `2026-03-02T14:32:00Z`. `--all` also prints
+//! ticks that found nothing.
+
+use chrono::{DateTime, Utc};
+use solana_arb_bot::replay::{self, ReplaySettings};
+use solana_arb_core::config::Config;
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("replay: {}", e);
+        std::process::exit(2);
+    }
+}
+
+async fn run(args: &[String]) -> anyhow::Result<()> {
+    let mut file = None;
+    let (mut from, mut to) = (None, None);
+    let mut all = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a time", arg))?;
+                let time: DateTime<Utc> = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid time '{}'", value))?;
+                if arg == "--from" {
+                    from = Some(time);
+                } else {
+                    to = Some(time);
+                }
+            }
+            "--all" => all = true,
+            other if other.starts_with("--") => anyhow::bail!("unknown argument '{}'", other),
+            path => file = Some(PathBuf::from(path)),
+        }
+    }
+    let file = file.ok_or_else(|| {
+        anyhow::anyhow!("usage: replay FILE [--from TIME] [--to TIME] [--all]")
+    })?;
+
+    let config = Config::from_env().unwrap_or_default();
+    let ticks = replay::load_ticks(&file, from, to)?;
+    let results = replay::replay(&ticks, &ReplaySettings::from_config(&config)).await;
+
+    let mut found = 0;
+    for tick in &results {
+        if tick.opportunities.is_empty() && !all {
+            continue;
+        }
+        let rejected = if tick.rejected_spread.is_empty() {
+            String::new()
+        } else {
+            format!(", spread rejected {}", tick.rejected_spread.join(" "))
+        };
+        println!("{} ({} prices{})", tick.at.to_rfc3339(), tick.prices, rejected);
+        for o in &tick.opportunities {
+            let opp = &o.opportunity;
+            println!(
+                "  {} {} buy {} @ {} sell {} @ {} net {}%",
+                o.source,
+                opp.pair,
+                opp.buy_dex,
+                opp.buy_price,
+                opp.sell_dex,
+                opp.sell_price,
+                opp.net_profit_pct.round_dp(4)
+            );
+        }
+        found += tick.opportunities.len();
+    }
+    println!("{} ticks replayed, {} opportunities", results.len(), found);
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Session archives for bug reports
+//!
+//! `session-archive export --from TIME --to TIME [--sim] [--out FILE]`
+//! packs the archived prices (see `PRICE_SNAPSHOT_DIR`), the opportunities
+//! detection finds in them, the trade history (`--sim`: the dry-run history)
+//! and the replay settings of the window into one zip.
+//!
+//! `session-archive import FILE [--into DIR]` replays an archive with its
+//! own settings and prints the ticks where detection now differs from what
+//! was recorded. Exits 1 if any tick differs. `--into` also unpacks the
+//! prices as daily snapshot files for `replay`. Times are RFC 3339.
+
+use chrono::{DateTime, Utc};
+use solana_arb_bot::config_manager::ConfigManager;
+use solana_arb_bot::replay::PriceSnapshots;
+use solana_arb_bot::session_archive::{ArchiveConfig, SessionArchive};
+use solana_arb_core::config::Config;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "usage: session-archive export --from TIME --to TIME [--sim] [--out FILE]\n       session-archive import FILE [--into DIR]";
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("export") => export(&args[1..]).await.map(|()| true),
+        Some("import") => import(&args[1..]).await,
+        _ => Err(anyhow::anyhow!(USAGE)),
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("session-archive: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+async fn export(args: &[String]) -> anyhow::Result<()> {
+    let (mut from, mut to) = (None, None);
+    let mut out = None;
+    let mut sim = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a time", arg))?;
+                let time: DateTime<Utc> = value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid time '{}'", value))?;
+                if arg == "--from" {
+                    from = Some(time);
+                } else {
+                    to = Some(time);
+                }
+            }
+            "--out" => {
+                out = Some(PathBuf::from(
+                    iter.next().ok_or_else(|| anyhow::anyhow!("--out needs a file"))?,
+                ))
+            }
+            "--sim" => sim = true,
+            other => anyhow::bail!("unknown argument '{}'\n{}", other, USAGE),
+        }
+    }
+    let (Some(from), Some(to)) = (from, to) else {
+        anyhow::bail!(USAGE);
+    };
+
+    let config = Config::from_env().unwrap_or_default();
+    let dir = config
+        .price_snapshot_dir
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("PRICE_SNAPSHOT_DIR is not set, so no prices are archived"))?;
+    let trading_config = match ConfigManager::new("config/trading_config.json") {
+        Ok(manager) => serde_json::to_value(manager.get().await).ok(),
+        Err(_) => None,
+    };
+
+    let archive = SessionArchive::collect(
+        &PriceSnapshots::new(dir),
+        Path::new(solana_arb_bot::history_file_path(sim)),
+        from,
+        to,
+        ArchiveConfig::from_config(&config, trading_config),
+    )
+    .await?;
+    if archive.ticks.is_empty() {
+        anyhow::bail!("no archived prices between {} and {}", from, to);
+    }
+    let out = out.unwrap_or_else(|| PathBuf::from("data").join(archive.file_name()));
+    archive.write_zip(&out)?;
+    println!(
+        "{}: {} ticks, {} opportunities, {} trades",
+        out.display(),
+        archive.manifest.ticks,
+        archive.manifest.opportunities,
+        archive.manifest.trades
+    );
+    Ok(())
+}
+
+/// Whether the replay matches the archive
+async fn import(args: &[String]) -> anyhow::Result<bool> {
+    let mut file = None;
+    let mut into = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--into" => {
+                into = Some(PathBuf::from(
+                    iter.next().ok_or_else(|| anyhow::anyhow!("--into needs a directory"))?,
+                ))
+            }
+            other if other.starts_with("--") => anyhow::bail!("unknown argument '{}'", other),
+            path => file = Some(PathBuf::from(path)),
+        }
+    }
+    let file = file.ok_or_else(|| anyhow::anyhow!(USAGE))?;
+
+    let archive = SessionArchive::read_zip(&file)?;
+    let manifest = &archive.manifest;
+    println!(
+        "{} to {}, exported {} from {}: {} ticks, {} opportunities, {} trades",
+        manifest.from.to_rfc3339(),
+        manifest.to.to_rfc3339(),
+        manifest.created_at.to_rfc3339(),
+        manifest.git_rev.as_deref().unwrap_or("unknown revision"),
+        manifest.ticks,
+        manifest.opportunities,
+        manifest.trades
+    );
+    if let Some(dir) = into {
+        for path in archive.unpack_prices(&dir)? {
+            println!("wrote {}", path.display());
+        }
+    }
+
+    let differences = archive.verify().await;
+    for tick in &differences {
+        println!("{}", tick.at.to_rfc3339());
+        for key in &tick.missing {
+            println!("  - {}", key);
+        }
+        for key in &tick.unexpected {
+            println!("  + {}", key);
+        }
+    }
+    if differences.is_empty() {
+        println!("Replay matches the recorded opportunities");
+    } else {
+        println!("{} ticks differ (- recorded only, + replay only)", differences.len());
+    }
+    Ok(differences.is_empty())
+}
@@ -0,0 +1,74 @@
+//! Opportunity simulator
+//!
+//! `simulate [--update] [DIR]` runs every fixture in `DIR/fixtures` through
+//! detection and compares the result with `DIR/golden`. Exits non-zero on
+//! any difference. `--update` rewrites the golden files instead, for
+//! intended detection changes. `DIR` defaults to the bot crate's
+//! `tests/simulator`.
+
+use solana_arb_bot::simulator::{self, Fixture};
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let update = args.iter().any(|a| a == "--update");
+    let dir = args
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/simulator"));
+
+    match run(&dir, update).await {
+        Ok(0) => {}
+        Ok(failures) => {
+            eprintln!("{} fixture(s) differ from their golden files", failures);
+            eprintln!("If the change is intended, rerun with --update and commit the result");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("simulate: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+async fn run(dir: &std::path::Path, update: bool) -> anyhow::Result<usize> {
+    let fixtures = simulator::fixtures(dir)?;
+    if fixtures.is_empty() {
+        anyhow::bail!("no fixtures in {}", dir.join("fixtures").display());
+    }
+
+    let mut failures = 0;
+    for path in fixtures {
+        let record = simulator::simulate(&Fixture::load(&path)?).await;
+        let golden = simulator::golden_path(&path);
+        let name = path.display();
+
+        if update {
+            simulator::save_golden(&golden, &record)?;
+            println!("updated {} ({} opportunities)", golden.display(), record.len());
+            continue;
+        }
+
+        let expected = match simulator::load_golden(&golden) {
+            Ok(expected) => expected,
+            Err(e) => {
+                println!("FAIL {}: no golden file {} ({})", name, golden.display(), e);
+                failures += 1;
+                continue;
+            }
+        };
+        let diff = simulator::diff(&expected, &record);
+        if diff.is_empty() {
+            println!("ok   {} ({} opportunities)", name, record.len());
+        } else {
+            println!("FAIL {}", name);
+            for line in diff {
+                println!("     {}", line);
+            }
+            failures += 1;
+        }
+    }
+    Ok(failures)
+}
@@ -0,0 +1,259 @@
+//! Pair and Route Blacklist
+//!
+//! A pair whose transactions keep failing on-chain is usually broken, not
+//! unlucky: a migrated pool, a frozen mint, a route Jupiter cannot build.
+//! Consecutive on-chain failures are counted per route (pair, buy venue,
+//! sell venue) and per pair. A route or pair that reaches its threshold is
+//! blacklisted until the entry expires or an operator removes it. Entries
+//! are saved to disk so a restart does not hand the bot a fresh set of
+//! failures to repeat.
+
+use crate::config_manager::RoutingConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::info;
+
+/// One suspended pair, or one route of a pair when the venues are set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub pair: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buy_dex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sell_dex: Option<String>,
+    /// Consecutive on-chain failures that triggered the entry
+    pub failures: u32,
+    /// Error of the last failure
+    pub reason: String,
+    pub added_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl BlacklistEntry {
+    fn matches(&self, pair: &str, buy_dex: &str, sell_dex: &str) -> bool {
+        self.pair == pair
+            && self.buy_dex.as_deref().is_none_or(|d| d == buy_dex)
+            && self.sell_dex.as_deref().is_none_or(|d| d == sell_dex)
+    }
+
+    /// "SOL/USDC" or "SOL/USDC Raydium → Orca"
+    pub fn label(&self) -> String {
+        match (&self.buy_dex, &self.sell_dex) {
+            (Some(buy), Some(sell)) => format!("{} {} → {}", self.pair, buy, sell),
+            _ => self.pair.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedBlacklist {
+    entries: Vec<BlacklistEntry>,
+}
+
+/// Failure streaks and the persisted blacklist
+#[derive(Debug)]
+pub struct Blacklist {
+    config: RoutingConfig,
+    path: PathBuf,
+    entries: Vec<BlacklistEntry>,
+    /// Consecutive failures per route key and per pair
+    route_streaks: HashMap<(String, String, String), u32>,
+    pair_streaks: HashMap<String, u32>,
+}
+
+impl Blacklist {
+    /// Load saved entries from `path`, dropping expired ones. A missing or
+    /// unreadable file starts an empty blacklist.
+    pub fn load(path: impl Into<PathBuf>, config: RoutingConfig) -> Self {
+        let path = path.into();
+        let saved: SavedBlacklist = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let now = Utc::now();
+        Self {
+            config,
+            path,
+            entries: saved.entries.into_iter().filter(|e| e.expires_at > now).collect(),
+            route_streaks: HashMap::new(),
+            pair_streaks: HashMap::new(),
+        }
+    }
+
+    /// Replace the thresholds (hot reload)
+    pub fn set_config(&mut self, config: RoutingConfig) {
+        self.config = config;
+    }
+
+    pub fn entries(&self) -> &[BlacklistEntry] {
+        &self.entries
+    }
+
+    /// The entry blocking a route, if any
+    pub fn blocked(&self, pair: &str, buy_dex: &str, sell_dex: &str) -> Option<&BlacklistEntry> {
+        let now = Utc::now();
+        self.entries
+            .iter()
+            .find(|e| e.expires_at > now && e.matches(pair, buy_dex, sell_dex))
+    }
+
+    /// A trade on the route landed; its streaks start over
+    pub fn record_success(&mut self, pair: &str, buy_dex: &str, sell_dex: &str) {
+        self.route_streaks
+            .remove(&(pair.to_string(), buy_dex.to_string(), sell_dex.to_string()));
+        self.pair_streaks.remove(pair);
+    }
+
+    /// A trade on the route failed on-chain. Returns the entries this adds.
+    pub fn record_failure(
+        &mut self,
+        pair: &str,
+        buy_dex: &str,
+        sell_dex: &str,
+        reason: &str,
+    ) -> Vec<BlacklistEntry> {
+        let route_key = (pair.to_string(), buy_dex.to_string(), sell_dex.to_string());
+        let route_failures = {
+            let streak = self.route_streaks.entry(route_key.clone()).or_default();
+            *streak += 1;
+            *streak
+        };
+        let pair_failures = {
+            let streak = self.pair_streaks.entry(pair.to_string()).or_default();
+            *streak += 1;
+            *streak
+        };
+
+        let mut added = Vec::new();
+        if pair_failures >= self.config.max_pair_failures {
+            self.pair_streaks.remove(pair);
+            self.route_streaks.retain(|(p, _, _), _| p != pair);
+            // A pair entry covers its routes
+            self.entries.retain(|e| e.pair != pair);
+            added.push(self.entry(pair, None, pair_failures, reason));
+        } else if route_failures >= self.config.max_route_failures
+            && self.blocked(pair, buy_dex, sell_dex).is_none()
+        {
+            self.route_streaks.remove(&route_key);
+            added.push(self.entry(pair, Some((buy_dex, sell_dex)), route_failures, reason));
+        }
+        self.entries.extend(added.iter().cloned());
+        added
+    }
+
+    fn entry(
+        &self,
+        pair: &str,
+        route: Option<(&str, &str)>,
+        failures: u32,
+        reason: &str,
+    ) -> BlacklistEntry {
+        let now = Utc::now();
+        BlacklistEntry {
+            pair: pair.to_string(),
+            buy_dex: route.map(|(buy, _)| buy.to_string()),
+            sell_dex: route.map(|(_, sell)| sell.to_string()),
+            failures,
+            reason: reason.to_string(),
+            added_at: now,
+            expires_at: now + chrono::Duration::seconds(self.config.blacklist_secs as i64),
+        }
+    }
+
+    /// Manually lift an entry. Without venues this removes the pair entry
+    /// and every route entry of the pair.
+    pub fn remove(
+        &mut self,
+        pair: &str,
+        buy_dex: Option<&str>,
+        sell_dex: Option<&str>,
+    ) -> Vec<BlacklistEntry> {
+        let (removed, kept) = std::mem::take(&mut self.entries).into_iter().partition(|e| {
+            e.pair == pair
+                && (buy_dex.is_none() || e.buy_dex.as_deref() == buy_dex)
+                && (sell_dex.is_none() || e.sell_dex.as_deref() == sell_dex)
+        });
+        self.entries = kept;
+        removed
+    }
+
+    /// Drop entries past their expiry. Returns them.
+    pub fn expire(&mut self) -> Vec<BlacklistEntry> {
+        let now = Utc::now();
+        let (expired, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|e| e.expires_at <= now);
+        self.entries = kept;
+        for entry in &expired {
+            info!("⏱️ Blacklist entry for {} expired", entry.label());
+        }
+        expired
+    }
+
+    /// Take over the entries of another instance. Returns whether they
+    /// changed.
+    pub fn replace_entries(&mut self, entries: Vec<BlacklistEntry>) -> bool {
+        if self.entries == entries {
+            return false;
+        }
+        self.entries = entries;
+        true
+    }
+
+    /// Write the entries to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let saved = SavedBlacklist {
+            entries: self.entries.clone(),
+        };
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RoutingConfig {
+        RoutingConfig {
+            max_route_failures: 2,
+            max_pair_failures: 3,
+            blacklist_secs: 3600,
+            ..RoutingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_route_then_pair_blacklisted_and_persisted() {
+        let path = std::env::temp_dir().join(format!("blacklist-{}.json", solana_arb_core::Uuid::new_v4()));
+        let mut blacklist = Blacklist::load(&path, config());
+
+        assert!(blacklist.record_failure("SOL/USDC", "Raydium", "Orca", "err").is_empty());
+        blacklist.record_success("SOL/USDC", "Raydium", "Orca");
+        assert!(blacklist.record_failure("SOL/USDC", "Raydium", "Orca", "err").is_empty());
+        let added = blacklist.record_failure("SOL/USDC", "Raydium", "Orca", "custom program error");
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].label(), "SOL/USDC Raydium → Orca");
+        assert!(blacklist.blocked("SOL/USDC", "Raydium", "Orca").is_some());
+        assert!(blacklist.blocked("SOL/USDC", "Orca", "Raydium").is_none());
+
+        // Third failure on the pair, on another route, suspends the whole pair
+        let added = blacklist.record_failure("SOL/USDC", "Orca", "Raydium", "err");
+        assert_eq!(added[0].buy_dex, None);
+        assert_eq!(blacklist.entries().len(), 1);
+        assert!(blacklist.blocked("SOL/USDC", "Orca", "Phoenix").is_some());
+
+        blacklist.save().unwrap();
+        let mut reloaded = Blacklist::load(&path, config());
+        assert_eq!(reloaded.entries(), blacklist.entries());
+        assert_eq!(reloaded.remove("SOL/USDC", None, None).len(), 1);
+        assert!(reloaded.blocked("SOL/USDC", "Orca", "Raydium").is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+}
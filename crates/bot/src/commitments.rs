@@ -0,0 +1,126 @@
+//! Per-Operation RPC Commitment
+//!
+//! One commitment does not suit every RPC call. Balance and account reads
+//! want the freshest state ("processed"), submissions want blockhashes and
+//! simulations a fork is unlikely to drop ("confirmed"), and P&L should
+//! only be settled once it cannot be rolled back ("finalized").
+//!
+//! `RPC_COMMITMENT_READS` and `RPC_COMMITMENT_SUBMISSION` default to
+//! `RPC_COMMITMENT`. `RPC_COMMITMENT_ACCOUNTING` (default "finalized") is
+//! the commitment a live trade is tracked to before its P&L is settled; it
+//! cannot be lower than "confirmed", the commitment trades are recorded at.
+
+use crate::confirmations::Commitment;
+use solana_arb_core::config::Config;
+use solana_sdk::commitment_config::CommitmentConfig;
+use tracing::warn;
+
+/// Commitment of each kind of RPC operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Commitments {
+    /// Wallet balances, token accounts and other state reads
+    pub reads: CommitmentConfig,
+    /// Blockhashes, simulations (preflight included) and the confirmation
+    /// a submitted transaction is waited for
+    pub submission: CommitmentConfig,
+    /// Commitment at which tracked trades are settled
+    pub accounting: Commitment,
+}
+
+impl Default for Commitments {
+    fn default() -> Self {
+        Self {
+            reads: CommitmentConfig::confirmed(),
+            submission: CommitmentConfig::confirmed(),
+            accounting: Commitment::Finalized,
+        }
+    }
+}
+
+impl Commitments {
+    pub fn from_config(config: &Config) -> Self {
+        let fallback = parse(&config.rpc_commitment).unwrap_or_else(CommitmentConfig::confirmed);
+        let level = |name: &str, value: &str| {
+            if value.is_empty() {
+                return fallback;
+            }
+            parse(value).unwrap_or_else(|| {
+                warn!("Unknown {} {:?}; using {:?}", name, value, fallback.commitment);
+                fallback
+            })
+        };
+        let accounting = match config.rpc_commitment_accounting.as_str() {
+            "finalized" => Commitment::Finalized,
+            "confirmed" => Commitment::Confirmed,
+            other => {
+                warn!("RPC_COMMITMENT_ACCOUNTING {:?} is not confirmed or finalized; using finalized", other);
+                Commitment::Finalized
+            }
+        };
+        Self {
+            reads: level("RPC_COMMITMENT_READS", &config.rpc_commitment_reads),
+            submission: level("RPC_COMMITMENT_SUBMISSION", &config.rpc_commitment_submission),
+            accounting,
+        }
+    }
+
+    /// Name of the submission commitment, as recorded in the trade history
+    pub fn submission_label(&self) -> &'static str {
+        label(&self.submission)
+    }
+
+    /// Name of the read commitment, as pool account streams subscribe at
+    pub fn reads_label(&self) -> &'static str {
+        label(&self.reads)
+    }
+}
+
+fn label(commitment: &CommitmentConfig) -> &'static str {
+    if commitment.is_finalized() {
+        "finalized"
+    } else if commitment.is_confirmed() {
+        "confirmed"
+    } else {
+        "processed"
+    }
+}
+
+/// "processed", "confirmed" or "finalized"
+pub fn parse(level: &str) -> Option<CommitmentConfig> {
+    match level {
+        "processed" => Some(CommitmentConfig::processed()),
+        "confirmed" => Some(CommitmentConfig::confirmed()),
+        "finalized" => Some(CommitmentConfig::finalized()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operations_fall_back_to_rpc_commitment() {
+        assert_eq!(Commitments::from_config(&Config::default()), Commitments::default());
+
+        let commitments = Commitments::from_config(&Config {
+            rpc_commitment: "finalized".into(),
+            rpc_commitment_reads: "processed".into(),
+            rpc_commitment_accounting: "confirmed".into(),
+            ..Config::default()
+        });
+        assert_eq!(commitments.reads, CommitmentConfig::processed());
+        assert_eq!(commitments.submission, CommitmentConfig::finalized());
+        assert_eq!(commitments.submission_label(), "finalized");
+        assert_eq!(commitments.accounting, Commitment::Confirmed);
+
+        // Unknown levels fall back; accounting never settles below confirmed
+        let commitments = Commitments::from_config(&Config {
+            rpc_commitment_submission: "fast".into(),
+            rpc_commitment_accounting: "processed".into(),
+            ..Config::default()
+        });
+        assert_eq!(commitments.submission, CommitmentConfig::confirmed());
+        assert_eq!(commitments.accounting, Commitment::Finalized);
+    }
+}
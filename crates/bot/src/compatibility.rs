@@ -0,0 +1,251 @@
+//! Startup Compatibility Checks
+//!
+//! Verifies that the dynamic config, persisted history and persisted risk
+//! state were written in a format this binary understands before live
+//! trading is allowed.
+
+use crate::config_manager::DynamicConfig;
+use solana_arb_core::history::{TradeRecord, HISTORY_SCHEMA_VERSION};
+use solana_arb_core::risk::returns::ReturnArchive;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use tracing::{error, info, warn};
+
+/// Major version of `config/trading_config.json` this binary supports
+pub const SUPPORTED_CONFIG_MAJOR: u64 = 1;
+
+/// Outcome of a single compatibility check
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompatibilityStatus {
+    Compatible,
+    /// Readable after an in-memory migration
+    Migrated(String),
+    Incompatible(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CompatibilityCheck {
+    pub component: String,
+    pub status: CompatibilityStatus,
+}
+
+/// Result of all startup compatibility checks
+#[derive(Debug, Clone)]
+pub struct CompatibilityReport {
+    pub binary_version: &'static str,
+    pub checks: Vec<CompatibilityCheck>,
+}
+
+impl CompatibilityReport {
+    /// Live trading is only allowed when nothing is incompatible
+    pub fn allows_live_trading(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|c| matches!(c.status, CompatibilityStatus::Incompatible(_)))
+    }
+
+    /// Human-readable report, one line per check
+    pub fn summary(&self) -> String {
+        let mut out = format!("Compatibility report (binary v{}):\n", self.binary_version);
+        for check in &self.checks {
+            let line = match &check.status {
+                CompatibilityStatus::Compatible => format!("✅ {}: compatible", check.component),
+                CompatibilityStatus::Migrated(detail) => {
+                    format!("🔄 {}: migrated - {}", check.component, detail)
+                }
+                CompatibilityStatus::Incompatible(detail) => {
+                    format!("❌ {}: INCOMPATIBLE - {}", check.component, detail)
+                }
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn log(&self) {
+        for line in self.summary().lines() {
+            if line.contains("INCOMPATIBLE") {
+                error!("{}", line);
+            } else if line.contains("migrated") {
+                warn!("{}", line);
+            } else {
+                info!("{}", line);
+            }
+        }
+    }
+}
+
+/// Check the dynamic config version against the supported major version
+pub fn check_config_version(version: &str) -> CompatibilityCheck {
+    let major = version
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|m| m.parse::<u64>().ok());
+
+    let status = match major {
+        Some(SUPPORTED_CONFIG_MAJOR) => CompatibilityStatus::Compatible,
+        Some(major) => CompatibilityStatus::Incompatible(format!(
+            "config version {} (major {}) is not supported, expected {}.x",
+            version, major, SUPPORTED_CONFIG_MAJOR
+        )),
+        None => CompatibilityStatus::Incompatible(format!(
+            "config version '{}' is not a valid version string",
+            version
+        )),
+    };
+
+    CompatibilityCheck {
+        component: "config".to_string(),
+        status,
+    }
+}
+
+/// Check that every record in a history file uses a known schema version
+pub fn check_history_file(path: &Path) -> CompatibilityCheck {
+    let component = format!("history ({})", path.display());
+    let incompatible = |detail: String| CompatibilityCheck {
+        component: component.clone(),
+        status: CompatibilityStatus::Incompatible(detail),
+    };
+
+    if !path.exists() {
+        return CompatibilityCheck {
+            component,
+            status: CompatibilityStatus::Compatible,
+        };
+    }
+
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) => return incompatible(format!("cannot read file: {}", e)),
+    };
+
+    let mut legacy = 0usize;
+    let mut unreadable = 0usize;
+    let mut newest = 0u32;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<TradeRecord>(&line) {
+            Ok(record) => {
+                newest = newest.max(record.schema_version);
+                if record.schema_version == 0 {
+                    legacy += 1;
+                }
+            }
+            Err(_) => unreadable += 1,
+        }
+    }
+
+    let status = if newest > HISTORY_SCHEMA_VERSION {
+        CompatibilityStatus::Incompatible(format!(
+            "records use schema v{} but this binary supports up to v{}",
+            newest, HISTORY_SCHEMA_VERSION
+        ))
+    } else if unreadable > 0 {
+        CompatibilityStatus::Incompatible(format!("{} records could not be parsed", unreadable))
+    } else if legacy > 0 {
+        CompatibilityStatus::Migrated(format!(
+            "{} unversioned records read as schema v{}",
+            legacy, HISTORY_SCHEMA_VERSION
+        ))
+    } else {
+        CompatibilityStatus::Compatible
+    };
+
+    CompatibilityCheck { component, status }
+}
+
+/// Check that the persisted VaR return archive can be loaded. Without it
+/// VaR would start from no history and understate risk.
+pub fn check_risk_state_file(path: &Path) -> CompatibilityCheck {
+    let component = format!("risk state ({})", path.display());
+    let status = match ReturnArchive::load(path) {
+        Ok(_) => CompatibilityStatus::Compatible,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => CompatibilityStatus::Compatible,
+        Err(e) => CompatibilityStatus::Incompatible(format!("cannot load VaR returns: {}", e)),
+    };
+    CompatibilityCheck { component, status }
+}
+
+/// Run all startup compatibility checks
+pub fn run_compatibility_checks(
+    config: &DynamicConfig,
+    history_path: &Path,
+    risk_state_path: &Path,
+) -> CompatibilityReport {
+    CompatibilityReport {
+        binary_version: env!("CARGO_PKG_VERSION"),
+        checks: vec![
+            check_config_version(&config.version),
+            check_history_file(history_path),
+            check_risk_state_file(risk_state_path),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_version_check() {
+        assert_eq!(check_config_version("1.0.0").status, CompatibilityStatus::Compatible);
+        assert_eq!(check_config_version("v1.2").status, CompatibilityStatus::Compatible);
+        assert!(matches!(
+            check_config_version("2.0.0").status,
+            CompatibilityStatus::Incompatible(_)
+        ));
+        assert!(matches!(
+            check_config_version("latest").status,
+            CompatibilityStatus::Incompatible(_)
+        ));
+    }
+
+    #[test]
+    fn test_history_schema_check() {
+        let dir = std::env::temp_dir().join(format!("arb-compat-{}", solana_arb_core::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).ok();
+        let path = dir.join("history.jsonl");
+
+        let legacy = r#"{"timestamp":"t","session_id":"s","trade_type":"REAL","pair":"SOL/USDC","buy_dex":"Orca","sell_dex":"Raydium","size_usd":"10","profit_usd":"0.1","profit_pct":"1","tx_signature":null,"success":true,"error":null}"#;
+        std::fs::write(&path, format!("{}\n", legacy)).unwrap();
+        assert!(matches!(
+            check_history_file(&path).status,
+            CompatibilityStatus::Migrated(_)
+        ));
+
+        let future = legacy.replacen('{', r#"{"schema_version":99,"#, 1);
+        std::fs::write(&path, format!("{}\n{}\n", legacy, future)).unwrap();
+        let report = CompatibilityReport {
+            binary_version: "test",
+            checks: vec![check_history_file(&path)],
+        };
+        assert!(!report.allows_live_trading());
+
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(check_history_file(&path).status, CompatibilityStatus::Compatible);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn test_risk_state_check() {
+        let path = std::env::temp_dir().join(format!("arb-compat-risk-{}.json", solana_arb_core::Uuid::new_v4()));
+        assert_eq!(check_risk_state_file(&path).status, CompatibilityStatus::Compatible);
+
+        ReturnArchive::new(10).save(&path).unwrap();
+        assert_eq!(check_risk_state_file(&path).status, CompatibilityStatus::Compatible);
+
+        std::fs::write(&path, r#"{"returns":[1,2]}"#).unwrap();
+        assert!(matches!(
+            check_risk_state_file(&path).status,
+            CompatibilityStatus::Incompatible(_)
+        ));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
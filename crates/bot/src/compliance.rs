@@ -0,0 +1,481 @@
+//! Pre-Trade Compliance Hooks
+//!
+//! Every live arbitrage transaction is described as an [`ExecutionPlan`] and
+//! passed to each configured [`ComplianceHook`] before it is signed; any hook
+//! can veto it. The built-in and external hooks are described in the runbook.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::config::Config;
+use solana_arb_core::Uuid;
+use solana_sdk::message::VersionedMessage;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::execution_mode::ExecutionMode;
+
+/// Programs every swap needs, allowed without being listed
+const BASE_PROGRAMS: [&str; 4] = [
+    "11111111111111111111111111111111",
+    "ComputeBudget111111111111111111111111111111",
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+];
+
+/// One opportunity in a plan; batches have several
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanLeg {
+    pub opportunity_id: Uuid,
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub size_usd: Decimal,
+    /// Loss at the quote's least accepted output, if the quote gave one
+    pub worst_case_loss_usd: Option<Decimal>,
+}
+
+/// A token the route touches
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PlanToken {
+    pub mint: String,
+    /// `None` for tokens missing from the registry
+    pub symbol: Option<String>,
+}
+
+/// A built transaction, as shown to compliance hooks before it is signed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionPlan {
+    pub mode: ExecutionMode,
+    pub legs: Vec<PlanLeg>,
+    /// Summed leg sizes
+    pub notional_usd: Decimal,
+    /// Pair tokens and every intermediate token of the routes
+    pub tokens: Vec<PlanToken>,
+    /// AMM labels of the route hops
+    pub venues: Vec<String>,
+    /// Top-level programs the transaction calls
+    pub programs: Vec<String>,
+}
+
+impl ExecutionPlan {
+    /// Input and output mints and AMM labels of a Jupiter quote's route
+    pub fn quote_route(quote: &serde_json::Value) -> (Vec<String>, Vec<String>) {
+        let mut mints = Vec::new();
+        let mut venues = Vec::new();
+        for key in ["inputMint", "outputMint"] {
+            if let Some(mint) = quote.get(key).and_then(|v| v.as_str()) {
+                mints.push(mint.to_string());
+            }
+        }
+        let hops = quote.get("routePlan").and_then(|plan| plan.as_array());
+        for hop in hops.into_iter().flatten() {
+            for key in ["inputMint", "outputMint"] {
+                if let Some(mint) = hop.pointer(&format!("/swapInfo/{}", key)).and_then(|v| v.as_str()) {
+                    mints.push(mint.to_string());
+                }
+            }
+            if let Some(label) = hop.pointer("/swapInfo/label").and_then(|v| v.as_str()) {
+                venues.push(label.to_string());
+            }
+        }
+        (mints, venues)
+    }
+
+    /// Top-level programs called by `message`
+    pub fn message_programs(message: &VersionedMessage) -> Vec<String> {
+        let keys = message.static_account_keys();
+        let mut programs: Vec<String> = Vec::new();
+        for ix in message.instructions() {
+            if let Some(program) = keys.get(ix.program_id_index as usize) {
+                let program = program.to_string();
+                if !programs.contains(&program) {
+                    programs.push(program);
+                }
+            }
+        }
+        programs
+    }
+}
+
+/// A hook's verdict on a plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplianceDecision {
+    Allow,
+    Deny(String),
+}
+
+/// A policy check run on every plan before it is signed
+#[async_trait]
+pub trait ComplianceHook: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn check(&self, plan: &ExecutionPlan) -> ComplianceDecision;
+}
+
+/// Every token must be listed by symbol or mint
+pub struct TokenAllowlist {
+    allowed: HashSet<String>,
+}
+
+impl TokenAllowlist {
+    pub fn new(entries: &[String]) -> Self {
+        Self {
+            allowed: entries.iter().map(|e| e.to_uppercase()).collect(),
+        }
+    }
+
+    fn allows(&self, token: &PlanToken) -> bool {
+        self.allowed.contains(&token.mint.to_uppercase())
+            || token
+                .symbol
+                .as_ref()
+                .is_some_and(|symbol| self.allowed.contains(&symbol.to_uppercase()))
+    }
+}
+
+#[async_trait]
+impl ComplianceHook for TokenAllowlist {
+    fn name(&self) -> &str {
+        "token_allowlist"
+    }
+
+    async fn check(&self, plan: &ExecutionPlan) -> ComplianceDecision {
+        match plan.tokens.iter().find(|token| !self.allows(token)) {
+            Some(token) => ComplianceDecision::Deny(format!(
+                "token {} is not allowlisted",
+                token.symbol.as_deref().unwrap_or(&token.mint)
+            )),
+            None => ComplianceDecision::Allow,
+        }
+    }
+}
+
+/// Every top-level program must be listed (or be a base program)
+pub struct ProgramAllowlist {
+    allowed: HashSet<String>,
+}
+
+impl ProgramAllowlist {
+    pub fn new(programs: &[String]) -> Self {
+        Self {
+            allowed: programs
+                .iter()
+                .cloned()
+                .chain(BASE_PROGRAMS.iter().map(|p| p.to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl ComplianceHook for ProgramAllowlist {
+    fn name(&self) -> &str {
+        "program_allowlist"
+    }
+
+    async fn check(&self, plan: &ExecutionPlan) -> ComplianceDecision {
+        match plan.programs.iter().find(|p| !self.allowed.contains(*p)) {
+            Some(program) => ComplianceDecision::Deny(format!("program {} is not allowlisted", program)),
+            None => ComplianceDecision::Allow,
+        }
+    }
+}
+
+/// Caps the notional of a transaction, and of each pair within it
+pub struct NotionalCap {
+    /// `None`: no overall cap
+    max_usd: Option<Decimal>,
+    pair_max_usd: HashMap<String, Decimal>,
+}
+
+impl NotionalCap {
+    pub fn new(max_usd: Option<Decimal>, pair_max_usd: HashMap<String, Decimal>) -> Self {
+        Self {
+            max_usd,
+            pair_max_usd,
+        }
+    }
+}
+
+#[async_trait]
+impl ComplianceHook for NotionalCap {
+    fn name(&self) -> &str {
+        "notional_cap"
+    }
+
+    async fn check(&self, plan: &ExecutionPlan) -> ComplianceDecision {
+        if let Some(max) = self.max_usd.filter(|max| plan.notional_usd > *max) {
+            return ComplianceDecision::Deny(format!(
+                "notional ${} exceeds cap ${}",
+                plan.notional_usd.round_dp(2),
+                max
+            ));
+        }
+        let mut by_pair: HashMap<String, Decimal> = HashMap::new();
+        for leg in &plan.legs {
+            *by_pair.entry(leg.pair.to_uppercase()).or_default() += leg.size_usd;
+        }
+        for (pair, notional) in by_pair {
+            if let Some(max) = self.pair_max_usd.get(&pair).filter(|max| notional > **max) {
+                return ComplianceDecision::Deny(format!(
+                    "{} notional ${} exceeds cap ${}",
+                    pair,
+                    notional.round_dp(2),
+                    max
+                ));
+            }
+        }
+        ComplianceDecision::Allow
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HookResponse {
+    allow: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Posts the plan to an external policy service
+pub struct HttpComplianceHook {
+    client: reqwest::Client,
+    url: String,
+    timeout: Duration,
+    /// Allow when the service does not answer properly
+    fail_open: bool,
+}
+
+impl HttpComplianceHook {
+    pub fn new(url: &str, timeout: Duration, fail_open: bool) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            timeout,
+            fail_open,
+        }
+    }
+
+    async fn ask(&self, plan: &ExecutionPlan) -> anyhow::Result<HookResponse> {
+        let request = async {
+            self.client
+                .post(&self.url)
+                .json(plan)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<HookResponse>()
+                .await
+        };
+        tokio::time::timeout(self.timeout, request)
+            .await
+            .with_context(|| format!("no answer within {}ms", self.timeout.as_millis()))?
+            .map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl ComplianceHook for HttpComplianceHook {
+    fn name(&self) -> &str {
+        "external"
+    }
+
+    async fn check(&self, plan: &ExecutionPlan) -> ComplianceDecision {
+        match self.ask(plan).await {
+            Ok(HookResponse { allow: true, .. }) => ComplianceDecision::Allow,
+            Ok(HookResponse { allow: false, reason }) => {
+                ComplianceDecision::Deny(reason.unwrap_or_else(|| "denied".to_string()))
+            }
+            Err(e) if self.fail_open => {
+                warn!("Compliance hook {} failed, allowing: {:#}", self.url, e);
+                ComplianceDecision::Allow
+            }
+            Err(e) => ComplianceDecision::Deny(format!("hook unavailable: {:#}", e)),
+        }
+    }
+}
+
+/// A plan vetoed by a compliance hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceVeto {
+    pub hook: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ComplianceVeto {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Compliance veto by {}: {}", self.hook, self.reason)
+    }
+}
+
+impl std::error::Error for ComplianceVeto {}
+
+/// Whether `error` is (or wraps) a [`ComplianceVeto`]
+pub fn is_compliance_veto(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ComplianceVeto>().is_some()
+}
+
+/// The configured hooks, run in order; the first veto stops the trade
+#[derive(Default)]
+pub struct ComplianceGate {
+    hooks: Vec<Box<dyn ComplianceHook>>,
+}
+
+impl std::fmt::Debug for ComplianceGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComplianceGate")
+            .field("hooks", &self.hook_names())
+            .finish()
+    }
+}
+
+impl ComplianceGate {
+    pub fn new(hooks: Vec<Box<dyn ComplianceHook>>) -> Self {
+        Self { hooks }
+    }
+
+    /// Hooks from the `COMPLIANCE_*` settings
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let list = |spec: &str| -> Vec<String> {
+            spec.split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let mut hooks: Vec<Box<dyn ComplianceHook>> = Vec::new();
+
+        let tokens = list(&config.compliance_token_allowlist);
+        if !tokens.is_empty() {
+            hooks.push(Box::new(TokenAllowlist::new(&tokens)));
+        }
+        let programs = list(&config.compliance_program_allowlist);
+        if !programs.is_empty() {
+            hooks.push(Box::new(ProgramAllowlist::new(&programs)));
+        }
+
+        let max_usd = Decimal::from_f64(config.compliance_max_notional_usd).filter(|max| *max > Decimal::ZERO);
+        let mut pair_max_usd = HashMap::new();
+        for entry in list(&config.compliance_pair_notional_usd) {
+            let (pair, usd) = entry
+                .rsplit_once(':')
+                .ok_or_else(|| anyhow::anyhow!("expected PAIR:usd, got '{}'", entry))?;
+            let usd: Decimal = usd
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid notional cap for {}", pair.trim()))?;
+            pair_max_usd.insert(pair.trim().to_uppercase(), usd);
+        }
+        if max_usd.is_some() || !pair_max_usd.is_empty() {
+            hooks.push(Box::new(NotionalCap::new(max_usd, pair_max_usd)));
+        }
+
+        if let Some(url) = &config.compliance_hook_url {
+            hooks.push(Box::new(HttpComplianceHook::new(
+                url,
+                Duration::from_millis(config.compliance_hook_timeout_ms),
+                config.compliance_hook_fail_open,
+            )));
+        }
+        Ok(Self::new(hooks))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Names of the configured hooks, in order
+    pub fn hook_names(&self) -> Vec<&str> {
+        self.hooks.iter().map(|hook| hook.name()).collect()
+    }
+
+    pub async fn check(&self, plan: &ExecutionPlan) -> Result<(), ComplianceVeto> {
+        for hook in &self.hooks {
+            if let ComplianceDecision::Deny(reason) = hook.check(plan).await {
+                return Err(ComplianceVeto {
+                    hook: hook.name().to_string(),
+                    reason,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(pair: &str, size: i64) -> ExecutionPlan {
+        ExecutionPlan {
+            mode: ExecutionMode::Standard,
+            legs: vec![PlanLeg {
+                opportunity_id: Uuid::new_v4(),
+                pair: pair.to_string(),
+                buy_dex: "Raydium".to_string(),
+                sell_dex: "Orca".to_string(),
+                size_usd: Decimal::from(size),
+                worst_case_loss_usd: None,
+            }],
+            notional_usd: Decimal::from(size),
+            tokens: vec![
+                PlanToken {
+                    mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+                    symbol: Some("USDC".to_string()),
+                },
+                PlanToken {
+                    mint: "So11111111111111111111111111111111111111112".to_string(),
+                    symbol: Some("SOL".to_string()),
+                },
+            ],
+            venues: vec!["Raydium".to_string(), "Whirlpool".to_string()],
+            programs: vec![
+                "ComputeBudget111111111111111111111111111111".to_string(),
+                "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string(),
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builtin_hooks_and_gate_order() {
+        let trade = plan("SOL/USDC", 800);
+
+        let tokens = TokenAllowlist::new(&["sol".to_string(), "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()]);
+        assert_eq!(tokens.check(&trade).await, ComplianceDecision::Allow);
+        let tokens = TokenAllowlist::new(&["USDC".to_string()]);
+        assert_eq!(
+            tokens.check(&trade).await,
+            ComplianceDecision::Deny("token SOL is not allowlisted".to_string())
+        );
+
+        let jupiter = ProgramAllowlist::new(&["JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4".to_string()]);
+        assert_eq!(jupiter.check(&trade).await, ComplianceDecision::Allow);
+        assert!(matches!(ProgramAllowlist::new(&[]).check(&trade).await, ComplianceDecision::Deny(_)));
+
+        let caps = NotionalCap::new(
+            Some(Decimal::from(1000)),
+            HashMap::from([("BONK/USDC".to_string(), Decimal::from(500))]),
+        );
+        assert_eq!(caps.check(&trade).await, ComplianceDecision::Allow);
+        assert_eq!(caps.check(&plan("bonk/usdc", 800)).await, ComplianceDecision::Deny(
+            "BONK/USDC notional $800 exceeds cap $500".to_string()
+        ));
+        assert!(matches!(caps.check(&plan("SOL/USDC", 1200)).await, ComplianceDecision::Deny(_)));
+
+        // The first veto wins; an empty gate allows everything
+        let gate = ComplianceGate::new(vec![Box::new(jupiter), Box::new(caps), Box::new(tokens)]);
+        assert_eq!(gate.hook_names(), vec!["program_allowlist", "notional_cap", "token_allowlist"]);
+        assert_eq!(gate.check(&plan("SOL/USDC", 1200)).await.unwrap_err().hook, "notional_cap");
+        assert_eq!(gate.check(&trade).await.unwrap_err().hook, "token_allowlist");
+        assert!(ComplianceGate::default().check(&plan("SOL/USDC", 1_000_000)).await.is_ok());
+
+        // An unreachable external hook denies unless it fails open
+        let closed = HttpComplianceHook::new("http://127.0.0.1:9/check", Duration::from_millis(200), false);
+        assert!(matches!(closed.check(&trade).await, ComplianceDecision::Deny(_)));
+        let open = HttpComplianceHook::new("http://127.0.0.1:9/check", Duration::from_millis(200), true);
+        assert_eq!(open.check(&trade).await, ComplianceDecision::Allow);
+    }
+}
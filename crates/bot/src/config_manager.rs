@@ -4,7 +4,13 @@
 //! validates all values on load, and supports hot-reloading via file change detection.
 #![allow(dead_code)]
 
+use crate::execution_mode::ExecutionMode;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use solana_arb_core::risk::hedge::HedgeConfig;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -23,6 +29,21 @@ pub struct DynamicConfig {
     pub performance: PerformanceConfig,
     /// Alert configuration
     pub alerts: AlertConfig,
+    /// Jupiter route filtering
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    /// Feature flag overrides (see `feature_flags`)
+    #[serde(default)]
+    pub features: HashMap<String, bool>,
+    /// Perp hedging budget, separate from the spot risk limits
+    #[serde(default)]
+    pub hedging: HedgingConfig,
+    /// Slippage tolerance overrides by pair and trade size
+    #[serde(default)]
+    pub slippage: SlippageConfig,
+    /// Per-pair trading direction restrictions and the daily flatten
+    #[serde(default)]
+    pub directions: DirectionConfig,
 }
 
 /// Trading-specific configuration
@@ -49,6 +70,14 @@ pub struct RiskConfig {
     pub max_daily_loss: f64,
     /// Value at Risk limit percentage
     pub var_limit_percent: f64,
+    /// Maximum total exposure in USD. Lowering it below current exposure
+    /// unwinds positions gradually.
+    #[serde(default)]
+    pub max_total_exposure: Option<f64>,
+    /// Largest loss in USD a single trade may risk at its quote's worst
+    /// accepted output. Unset disables the cap.
+    #[serde(default)]
+    pub max_trade_loss_usd: Option<f64>,
 }
 
 /// Performance tuning configuration
@@ -75,6 +104,313 @@ pub struct AlertConfig {
     pub alert_on_loss: f64,
 }
 
+/// Jupiter route filtering configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Jupiter AMM labels never to route through (`excludeDexes`)
+    #[serde(default)]
+    pub exclude_dexes: Vec<String>,
+    /// If non-empty, only route through these AMM labels (`onlyDexes`)
+    #[serde(default)]
+    pub only_dexes: Vec<String>,
+    /// Consecutive failed trades before a venue is excluded
+    #[serde(default = "default_max_venue_failures")]
+    pub max_venue_failures: u32,
+    /// How long an unhealthy venue stays excluded
+    #[serde(default = "default_venue_cooldown_secs")]
+    pub venue_cooldown_secs: u64,
+    /// Routes realizing less than this fraction of their detected edge are deprioritized
+    #[serde(default = "default_min_edge_capture")]
+    pub min_edge_capture: f64,
+    /// Executions on a route before its edge capture is judged
+    #[serde(default = "default_edge_capture_min_samples")]
+    pub edge_capture_min_samples: usize,
+    /// How long a route with collapsed edge stays deprioritized
+    #[serde(default = "default_route_deprioritize_secs")]
+    pub route_deprioritize_secs: u64,
+    /// Routes realizing this much more slippage (bps) than modeled are sized down
+    #[serde(default = "default_slippage_error_tolerance_bps")]
+    pub slippage_error_tolerance_bps: f64,
+    /// Executions on a route before its slippage is judged
+    #[serde(default = "default_slippage_feedback_min_samples")]
+    pub slippage_feedback_min_samples: usize,
+    /// Smallest share of the position limit a sized-down route keeps
+    #[serde(default = "default_min_route_size_scale")]
+    pub min_route_size_scale: f64,
+    /// Consecutive on-chain failures on a route before it is blacklisted
+    #[serde(default = "default_max_route_failures")]
+    pub max_route_failures: u32,
+    /// Consecutive on-chain failures on a pair, across routes, before it is blacklisted
+    #[serde(default = "default_max_pair_failures")]
+    pub max_pair_failures: u32,
+    /// How long a blacklist entry lasts unless removed
+    #[serde(default = "default_blacklist_secs")]
+    pub blacklist_secs: u64,
+}
+
+fn default_max_venue_failures() -> u32 {
+    3
+}
+
+fn default_venue_cooldown_secs() -> u64 {
+    600
+}
+
+fn default_min_edge_capture() -> f64 {
+    0.25
+}
+
+fn default_edge_capture_min_samples() -> usize {
+    5
+}
+
+fn default_route_deprioritize_secs() -> u64 {
+    1800
+}
+
+fn default_slippage_error_tolerance_bps() -> f64 {
+    10.0
+}
+
+fn default_slippage_feedback_min_samples() -> usize {
+    5
+}
+
+fn default_min_route_size_scale() -> f64 {
+    0.25
+}
+
+fn default_max_route_failures() -> u32 {
+    3
+}
+
+fn default_max_pair_failures() -> u32 {
+    5
+}
+
+fn default_blacklist_secs() -> u64 {
+    21600
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            exclude_dexes: Vec::new(),
+            only_dexes: Vec::new(),
+            max_venue_failures: default_max_venue_failures(),
+            venue_cooldown_secs: default_venue_cooldown_secs(),
+            min_edge_capture: default_min_edge_capture(),
+            edge_capture_min_samples: default_edge_capture_min_samples(),
+            route_deprioritize_secs: default_route_deprioritize_secs(),
+            slippage_error_tolerance_bps: default_slippage_error_tolerance_bps(),
+            slippage_feedback_min_samples: default_slippage_feedback_min_samples(),
+            min_route_size_scale: default_min_route_size_scale(),
+            max_route_failures: default_max_route_failures(),
+            max_pair_failures: default_max_pair_failures(),
+            blacklist_secs: default_blacklist_secs(),
+        }
+    }
+}
+
+/// Perp hedging targets and risk budget (see `hedging`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgingConfig {
+    /// Share of each spot position left unhedged (0: delta neutral)
+    #[serde(default)]
+    pub target_delta: f64,
+    /// Hedges within this many USD of their target are left alone
+    #[serde(default = "default_hedge_rebalance_usd")]
+    pub rebalance_threshold_usd: f64,
+    /// Only spot positions costing more than this to rebalance are hedged
+    #[serde(default = "default_hedge_min_spot_cost_bps")]
+    pub min_spot_cost_bps: f64,
+    /// Cap on the summed notional of all hedges in USD
+    #[serde(default = "default_hedge_max_notional_usd")]
+    pub max_notional_usd: f64,
+    /// Cap on margin used as a share of perp collateral
+    #[serde(default = "default_hedge_max_margin_usage")]
+    pub max_margin_usage: f64,
+    /// Leverage the margin requirement is estimated with
+    #[serde(default = "default_hedge_leverage")]
+    pub leverage: f64,
+    /// Hedge loss in USD that closes all hedges until the next day
+    #[serde(default = "default_hedge_max_loss_usd")]
+    pub max_loss_usd: f64,
+}
+
+fn default_hedge_rebalance_usd() -> f64 {
+    25.0
+}
+
+fn default_hedge_min_spot_cost_bps() -> f64 {
+    30.0
+}
+
+fn default_hedge_max_notional_usd() -> f64 {
+    1000.0
+}
+
+fn default_hedge_max_margin_usage() -> f64 {
+    0.5
+}
+
+fn default_hedge_leverage() -> f64 {
+    2.0
+}
+
+fn default_hedge_max_loss_usd() -> f64 {
+    100.0
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self {
+            target_delta: 0.0,
+            rebalance_threshold_usd: default_hedge_rebalance_usd(),
+            min_spot_cost_bps: default_hedge_min_spot_cost_bps(),
+            max_notional_usd: default_hedge_max_notional_usd(),
+            max_margin_usage: default_hedge_max_margin_usage(),
+            leverage: default_hedge_leverage(),
+            max_loss_usd: default_hedge_max_loss_usd(),
+        }
+    }
+}
+
+impl HedgingConfig {
+    /// The core hedger's view of this section
+    pub fn hedge_config(&self) -> HedgeConfig {
+        let decimal = |v: f64| Decimal::from_f64(v).unwrap_or_default();
+        HedgeConfig {
+            target_delta: decimal(self.target_delta),
+            rebalance_threshold: decimal(self.rebalance_threshold_usd),
+            min_spot_cost_bps: decimal(self.min_spot_cost_bps),
+            max_notional: decimal(self.max_notional_usd),
+            max_margin_usage: decimal(self.max_margin_usage),
+            leverage: decimal(self.leverage),
+            max_loss: decimal(self.max_loss_usd),
+        }
+    }
+}
+
+/// Slippage tolerance overrides. Trades no override matches use
+/// `SLIPPAGE_BPS`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlippageConfig {
+    /// Checked in order; the first match applies
+    #[serde(default)]
+    pub overrides: Vec<SlippageOverride>,
+}
+
+/// Slippage tolerance for one pair (or every pair) within a size bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlippageOverride {
+    /// e.g. `SOL/USDC`, or `*` for any pair
+    pub pair: String,
+    /// Smallest trade size in USD this applies to
+    #[serde(default)]
+    pub min_size_usd: f64,
+    /// Trades of this size in USD and up are not covered (`None`: no limit)
+    #[serde(default)]
+    pub max_size_usd: Option<f64>,
+    pub slippage_bps: u64,
+}
+
+impl SlippageOverride {
+    fn matches(&self, pair: &str, size_usd: f64) -> bool {
+        (self.pair == "*" || self.pair.eq_ignore_ascii_case(pair))
+            && size_usd >= self.min_size_usd
+            && self.max_size_usd.is_none_or(|max| size_usd < max)
+    }
+}
+
+impl SlippageConfig {
+    /// Tolerance for a trade of `size_usd` on `pair`, if an override covers it
+    pub fn slippage_bps(&self, pair: &str, size_usd: f64) -> Option<u64> {
+        self.overrides
+            .iter()
+            .find(|o| o.matches(pair, size_usd))
+            .map(|o| o.slippage_bps)
+    }
+}
+
+/// Which trades may run on a pair, by what they do with its base token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeDirection {
+    #[default]
+    Both,
+    /// Only standard trades, which buy the base token with the wallet's quote token
+    BuyOnly,
+    /// Only flash loan round trips, which sell borrowed base tokens first
+    /// and never leave any in the wallet
+    SellOnly,
+}
+
+impl TradeDirection {
+    pub fn allows(self, mode: ExecutionMode) -> bool {
+        match (self, mode) {
+            (TradeDirection::Both, _) => true,
+            (TradeDirection::BuyOnly, mode) => mode == ExecutionMode::Standard,
+            (TradeDirection::SellOnly, mode) => mode == ExecutionMode::FlashLoan,
+        }
+    }
+}
+
+/// Restrictions for one pair
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PairDirection {
+    #[serde(default)]
+    pub direction: TradeDirection,
+    /// Sell the wallet's base token back to the quote token at
+    /// `flatten_at_utc` every day, so none is held overnight
+    #[serde(default)]
+    pub flatten_daily: bool,
+}
+
+/// Trading direction restrictions. Pairs not listed trade both ways.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectionConfig {
+    /// By pair, e.g. `BONK/USDC`
+    #[serde(default)]
+    pub pairs: HashMap<String, PairDirection>,
+    /// Time of day (`HH:MM`, UTC) of the daily flatten
+    #[serde(default)]
+    pub flatten_at_utc: Option<String>,
+}
+
+impl DirectionConfig {
+    pub fn direction(&self, pair: &str) -> TradeDirection {
+        self.pairs
+            .iter()
+            .find(|(p, _)| p.eq_ignore_ascii_case(pair))
+            .map(|(_, d)| d.direction)
+            .unwrap_or_default()
+    }
+
+    pub fn flatten_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.flatten_at_utc.as_deref()?, "%H:%M").ok()
+    }
+
+    /// Whether the day's flatten is due at `now`, given the day it last ran.
+    /// A bot started after the flatten time flattens straight away.
+    pub fn flatten_due(&self, last_flattened: Option<NaiveDate>, now: DateTime<Utc>) -> bool {
+        self.flatten_time()
+            .is_some_and(|at| now.time() >= at && last_flattened != Some(now.date_naive()))
+    }
+
+    /// Pairs whose base token is flattened daily, sorted
+    pub fn flatten_pairs(&self) -> Vec<String> {
+        let mut pairs: Vec<String> = self
+            .pairs
+            .iter()
+            .filter(|(_, d)| d.flatten_daily)
+            .map(|(p, _)| p.to_uppercase())
+            .collect();
+        pairs.sort();
+        pairs
+    }
+}
+
 impl DynamicConfig {
     /// Validate all configuration values
     pub fn validate(&self) -> Result<(), String> {
@@ -93,15 +429,123 @@ impl DynamicConfig {
         if self.risk.var_limit_percent <= 0.0 || self.risk.var_limit_percent > 100.0 {
             return Err("risk.var_limit_percent must be between 0 and 100".into());
         }
+        if self.risk.max_total_exposure.is_some_and(|limit| limit <= 0.0) {
+            return Err("risk.max_total_exposure must be > 0".into());
+        }
+        if self.risk.max_trade_loss_usd.is_some_and(|cap| cap <= 0.0) {
+            return Err("risk.max_trade_loss_usd must be > 0".into());
+        }
         if self.performance.poll_interval_ms < 50 {
             return Err("performance.poll_interval_ms must be >= 50ms".into());
         }
         if self.alerts.alert_on_loss < 0.0 {
             return Err("alerts.alert_on_loss must be >= 0".into());
         }
+        if self.routing.max_venue_failures == 0 {
+            return Err("routing.max_venue_failures must be > 0".into());
+        }
+        if self.routing.edge_capture_min_samples == 0 {
+            return Err("routing.edge_capture_min_samples must be > 0".into());
+        }
+        if self.routing.slippage_feedback_min_samples == 0 {
+            return Err("routing.slippage_feedback_min_samples must be > 0".into());
+        }
+        if self.routing.min_route_size_scale <= 0.0 || self.routing.min_route_size_scale > 1.0 {
+            return Err("routing.min_route_size_scale must be > 0 and <= 1".into());
+        }
+        if self.routing.max_route_failures == 0 || self.routing.max_pair_failures == 0 {
+            return Err("routing.max_route_failures and max_pair_failures must be > 0".into());
+        }
+        if !(0.0..=1.0).contains(&self.hedging.target_delta) {
+            return Err("hedging.target_delta must be between 0 and 1".into());
+        }
+        if self.hedging.max_margin_usage <= 0.0 || self.hedging.max_margin_usage > 1.0 {
+            return Err("hedging.max_margin_usage must be > 0 and <= 1".into());
+        }
+        if self.hedging.leverage < 1.0 {
+            return Err("hedging.leverage must be >= 1".into());
+        }
+        if self.hedging.max_notional_usd < 0.0 || self.hedging.max_loss_usd < 0.0 {
+            return Err("hedging.max_notional_usd and max_loss_usd must be >= 0".into());
+        }
+        for o in &self.slippage.overrides {
+            if o.slippage_bps == 0 || o.slippage_bps > self.trading.max_slippage_bps {
+                return Err(format!(
+                    "slippage override for {}: slippage_bps must be > 0 and <= trading.max_slippage_bps",
+                    o.pair
+                ));
+            }
+            if o.min_size_usd < 0.0 || o.max_size_usd.is_some_and(|max| max <= o.min_size_usd) {
+                return Err(format!(
+                    "slippage override for {}: size bucket must satisfy 0 <= min_size_usd < max_size_usd",
+                    o.pair
+                ));
+            }
+        }
+        if let Some(pair) = self.directions.pairs.keys().find(|p| p.split_once('/').is_none()) {
+            return Err(format!("directions: '{}' is not a BASE/QUOTE pair", pair));
+        }
+        if self.directions.flatten_at_utc.is_some() && self.directions.flatten_time().is_none() {
+            return Err("directions.flatten_at_utc must be HH:MM".into());
+        }
+        if self.directions.flatten_at_utc.is_none() && !self.directions.flatten_pairs().is_empty() {
+            return Err("directions.flatten_at_utc is required for flatten_daily pairs".into());
+        }
 
         Ok(())
     }
+
+    /// List the execution-affecting settings that differ from `previous`
+    pub fn execution_changes(&self, previous: &DynamicConfig) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.trading.max_slippage_bps != previous.trading.max_slippage_bps {
+            changes.push(format!(
+                "max_slippage_bps {} → {}",
+                previous.trading.max_slippage_bps, self.trading.max_slippage_bps
+            ));
+        }
+        if self.trading.max_position_size != previous.trading.max_position_size {
+            changes.push(format!(
+                "max_position_size {} → {}",
+                previous.trading.max_position_size, self.trading.max_position_size
+            ));
+        }
+        if self.risk.max_trade_loss_usd != previous.risk.max_trade_loss_usd {
+            let show = |cap: Option<f64>| cap.map_or("off".to_string(), |cap| cap.to_string());
+            changes.push(format!(
+                "max_trade_loss_usd {} → {}",
+                show(previous.risk.max_trade_loss_usd),
+                show(self.risk.max_trade_loss_usd)
+            ));
+        }
+        if self.routing.exclude_dexes != previous.routing.exclude_dexes {
+            changes.push(format!(
+                "exclude_dexes {:?} → {:?}",
+                previous.routing.exclude_dexes, self.routing.exclude_dexes
+            ));
+        }
+        if self.routing.only_dexes != previous.routing.only_dexes {
+            changes.push(format!(
+                "only_dexes {:?} → {:?}",
+                previous.routing.only_dexes, self.routing.only_dexes
+            ));
+        }
+        if self.slippage != previous.slippage {
+            changes.push(format!(
+                "slippage overrides {} → {}",
+                previous.slippage.overrides.len(),
+                self.slippage.overrides.len()
+            ));
+        }
+        if self.directions != previous.directions {
+            changes.push(format!(
+                "direction restrictions {} → {} pairs",
+                previous.directions.pairs.len(),
+                self.directions.pairs.len()
+            ));
+        }
+        changes
+    }
 }
 
 /// Manages dynamic configuration with hot-reload support
@@ -131,24 +575,22 @@ impl ConfigManager {
     }
 
     /// Reload configuration from disk
-    pub async fn reload(&self) -> Result<(), String> {
+    ///
+    /// Returns the execution-affecting changes between the old and new config.
+    pub async fn reload(&self) -> Result<Vec<String>, String> {
         let new_config = Self::load_config(&self.config_path)?;
         new_config.validate()?;
 
-        let old_version = {
-            let current = self.config.read().await;
-            current.version.clone()
-        };
-
         let mut config = self.config.write().await;
-        *config = new_config;
+        let changes = new_config.execution_changes(&config);
+        let old_version = std::mem::replace(&mut *config, new_config).version;
 
         info!(
             "Configuration reloaded: {} → {}",
             old_version, config.version
         );
 
-        Ok(())
+        Ok(changes)
     }
 
     /// Get a cloneable reference to the config Arc for sharing
@@ -170,6 +612,7 @@ impl ConfigManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     fn valid_config() -> DynamicConfig {
         DynamicConfig {
@@ -185,6 +628,8 @@ mod tests {
                 max_consecutive_losses: 5,
                 max_daily_loss: 500.0,
                 var_limit_percent: 2.0,
+                max_total_exposure: None,
+                max_trade_loss_usd: None,
             },
             performance: PerformanceConfig {
                 poll_interval_ms: 500,
@@ -197,6 +642,11 @@ mod tests {
                 alert_on_profit: 50.0,
                 alert_on_loss: 10.0,
             },
+            routing: RoutingConfig::default(),
+            features: HashMap::new(),
+            hedging: HedgingConfig::default(),
+            slippage: SlippageConfig::default(),
+            directions: DirectionConfig::default(),
         }
     }
 
@@ -233,6 +683,111 @@ mod tests {
         assert!(c.validate().is_err());
     }
 
+    #[test]
+    fn test_non_positive_exposure_limit_fails() {
+        let mut c = valid_config();
+        c.risk.max_total_exposure = Some(0.0);
+        assert!(c.validate().is_err());
+    }
+
+    #[test]
+    fn test_slippage_overrides_first_match_by_pair_and_size() {
+        let bucket = |pair: &str, min: f64, max: Option<f64>, bps| SlippageOverride {
+            pair: pair.to_string(),
+            min_size_usd: min,
+            max_size_usd: max,
+            slippage_bps: bps,
+        };
+        let mut c = valid_config();
+        c.slippage.overrides = vec![
+            bucket("SOL/USDC", 0.0, None, 20),
+            bucket("*", 0.0, Some(1000.0), 50),
+            bucket("*", 1000.0, None, 100),
+        ];
+        assert!(c.validate().is_ok());
+        assert_eq!(c.slippage.slippage_bps("sol/usdc", 5000.0), Some(20));
+        assert_eq!(c.slippage.slippage_bps("BONK/USDC", 999.0), Some(50));
+        assert_eq!(c.slippage.slippage_bps("BONK/USDC", 1000.0), Some(100));
+        assert_eq!(SlippageConfig::default().slippage_bps("SOL/USDC", 10.0), None);
+
+        let old = valid_config();
+        assert_eq!(c.execution_changes(&old), vec!["slippage overrides 0 → 3"]);
+
+        c.slippage.overrides[2].slippage_bps = 150; // above max_slippage_bps
+        assert!(c.validate().is_err());
+        c.slippage.overrides[2] = bucket("*", 1000.0, Some(1000.0), 100);
+        assert!(c.validate().is_err());
+    }
+
+    #[test]
+    fn test_direction_restrictions_and_daily_flatten() {
+        let mut c = valid_config();
+        c.directions = serde_json::from_value(serde_json::json!({
+            "pairs": {
+                "BONK/USDC": { "direction": "sell_only", "flatten_daily": true },
+                "JUP/USDC": { "direction": "buy_only" }
+            },
+            "flatten_at_utc": "23:30"
+        }))
+        .unwrap();
+        assert!(c.validate().is_ok());
+
+        let d = &c.directions;
+        assert!(!d.direction("bonk/usdc").allows(ExecutionMode::Standard));
+        assert!(d.direction("BONK/USDC").allows(ExecutionMode::FlashLoan));
+        assert!(d.direction("JUP/USDC").allows(ExecutionMode::Standard));
+        assert!(!d.direction("JUP/USDC").allows(ExecutionMode::FlashLoan));
+        assert_eq!(d.direction("SOL/USDC"), TradeDirection::Both);
+        assert_eq!(d.flatten_pairs(), vec!["BONK/USDC"]);
+
+        let at = |h, m| Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap();
+        let today = at(0, 0).date_naive();
+        assert!(!d.flatten_due(None, at(23, 29)));
+        assert!(d.flatten_due(None, at(23, 30)));
+        assert!(!d.flatten_due(Some(today), at(23, 45)));
+        assert!(d.flatten_due(today.pred_opt(), at(23, 45)));
+
+        c.directions.flatten_at_utc = Some("25:00".into());
+        assert!(c.validate().is_err());
+        c.directions.flatten_at_utc = None;
+        assert!(c.validate().is_err()); // BONK/USDC flattens daily
+        c.directions.pairs.insert("BONK".into(), PairDirection::default());
+        c.directions.pairs.get_mut("BONK/USDC").unwrap().flatten_daily = false;
+        assert!(c.validate().is_err()); // not a pair
+    }
+
+    #[test]
+    fn test_execution_changes() {
+        let old = valid_config();
+        let mut new = valid_config();
+        new.trading.min_profit_bps = 80.0;
+        assert!(new.execution_changes(&old).is_empty());
+
+        new.trading.max_slippage_bps = 150;
+        let changes = new.execution_changes(&old);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].starts_with("max_slippage_bps"));
+    }
+
+    #[test]
+    fn test_excluded_venues_are_an_execution_change() {
+        let old = valid_config();
+        let mut new = valid_config();
+        new.routing.exclude_dexes = vec!["Lifinity".to_string()];
+        assert_eq!(new.execution_changes(&old), vec![r#"exclude_dexes [] → ["Lifinity"]"#]);
+    }
+
+    #[test]
+    fn test_allowed_venues_are_an_execution_change() {
+        let old = valid_config();
+        let mut new = valid_config();
+        new.routing.only_dexes = vec!["Orca".to_string(), "Raydium".to_string()];
+        assert_eq!(
+            new.execution_changes(&old),
+            vec![r#"only_dexes [] → ["Orca", "Raydium"]"#]
+        );
+    }
+
     #[tokio::test]
     async fn test_config_manager_load_and_get() {
         // Write a temp config file
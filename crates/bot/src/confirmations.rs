@@ -0,0 +1,304 @@
+//! Confirmation Tracking
+//!
+//! Trades are recorded once their transaction reaches the configured
+//! commitment (usually "confirmed"), but a confirmed block can still be
+//! abandoned by a fork. Live trades are tracked here until they reach the
+//! accounting commitment (finalized unless `RPC_COMMITMENT_ACCOUNTING`
+//! says otherwise). A signature that the cluster stops knowing about, or
+//! that turns up failed, is treated as rolled back so its P&L can be
+//! reverted.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use solana_arb_core::history::{CommitmentUpdate, COMMITMENT_REORGED};
+use solana_arb_core::memory::MemoryUsage;
+use std::time::{Duration, Instant};
+
+/// How often signature statuses are polled
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive polls a signature may be unknown before it counts as reorged
+pub const MISSING_LIMIT: u32 = 3;
+
+/// Commitment a tracked transaction has reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+/// What one status poll said about a signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Observation {
+    /// Landed without error at this commitment
+    Seen(Commitment),
+    /// Landed, but the transaction failed
+    Failed(String),
+    /// The cluster does not know the signature
+    Missing,
+}
+
+/// A live trade waiting for finalization
+#[derive(Debug, Clone)]
+pub struct TrackedTrade {
+    pub id: String,
+    pub signature: String,
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub size_usd: Decimal,
+    /// P&L recorded for the trade
+    pub profit_usd: Decimal,
+    /// Timestamp of the outcome given to the risk manager, used to revert it
+    pub recorded_at: DateTime<Utc>,
+    pub commitment: Commitment,
+    pub tracked_since: Instant,
+    missing_polls: u32,
+}
+
+impl TrackedTrade {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: &str,
+        signature: &str,
+        pair: &str,
+        buy_dex: &str,
+        sell_dex: &str,
+        size_usd: Decimal,
+        profit_usd: Decimal,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            signature: signature.to_string(),
+            pair: pair.to_string(),
+            buy_dex: buy_dex.to_string(),
+            sell_dex: sell_dex.to_string(),
+            size_usd,
+            profit_usd,
+            recorded_at,
+            commitment: Commitment::Confirmed,
+            tracked_since: Instant::now(),
+            missing_polls: 0,
+        }
+    }
+
+    /// History update recording the trade's new commitment
+    pub fn commitment_update(&self, commitment: &str, reason: Option<String>) -> CommitmentUpdate {
+        CommitmentUpdate {
+            pair: self.pair.clone(),
+            buy_dex: self.buy_dex.clone(),
+            sell_dex: self.sell_dex.clone(),
+            size_usd: self.size_usd,
+            profit_usd: self.profit_usd,
+            signature: self.signature.clone(),
+            commitment: commitment.to_string(),
+            reason,
+        }
+    }
+}
+
+/// A tracked trade reached a terminal state
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    /// Reached the accounting commitment, recorded in the trade
+    Finalized(TrackedTrade),
+    Reorged { trade: TrackedTrade, reason: String },
+}
+
+impl Resolution {
+    pub fn update(&self) -> CommitmentUpdate {
+        match self {
+            Resolution::Finalized(trade) => trade.commitment_update(trade.commitment.as_str(), None),
+            Resolution::Reorged { trade, reason } => {
+                trade.commitment_update(COMMITMENT_REORGED, Some(reason.clone()))
+            }
+        }
+    }
+}
+
+/// Live trades awaiting the accounting commitment
+#[derive(Debug)]
+pub struct ConfirmationTracker {
+    trades: Vec<TrackedTrade>,
+    settle_at: Commitment,
+}
+
+impl Default for ConfirmationTracker {
+    fn default() -> Self {
+        Self {
+            trades: Vec::new(),
+            settle_at: Commitment::Finalized,
+        }
+    }
+}
+
+impl ConfirmationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settle trades once they reach `commitment` instead of finalization
+    pub fn with_settlement(mut self, commitment: Commitment) -> Self {
+        self.settle_at = commitment;
+        self
+    }
+
+    pub fn track(&mut self, trade: TrackedTrade) {
+        self.trades.push(trade);
+    }
+
+    pub fn len(&self) -> usize {
+        self.trades.len()
+    }
+
+    /// Approximate memory held by trades awaiting finalization
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of::<TrackedTrade>(self.trades.len(), 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trades.is_empty()
+    }
+
+    /// Distinct signatures to poll
+    pub fn signatures(&self) -> Vec<String> {
+        let mut signatures: Vec<String> = Vec::new();
+        for trade in &self.trades {
+            if !signatures.contains(&trade.signature) {
+                signatures.push(trade.signature.clone());
+            }
+        }
+        signatures
+    }
+
+    /// Apply a poll result to every trade with `signature` (a batch shares
+    /// one). Trades that are settled or rolled back stop being tracked and
+    /// are returned.
+    pub fn observe(&mut self, signature: &str, observation: Observation) -> Vec<Resolution> {
+        let mut resolved = false;
+        for trade in self.trades.iter_mut().filter(|t| t.signature == signature) {
+            resolved = match &observation {
+                Observation::Failed(_) => true,
+                Observation::Seen(commitment) => {
+                    trade.missing_polls = 0;
+                    trade.commitment = trade.commitment.max(*commitment);
+                    *commitment >= self.settle_at
+                }
+                Observation::Missing => {
+                    trade.missing_polls += 1;
+                    trade.missing_polls >= MISSING_LIMIT
+                }
+            };
+        }
+        if !resolved {
+            return Vec::new();
+        }
+
+        let (done, pending): (Vec<TrackedTrade>, Vec<TrackedTrade>) = std::mem::take(&mut self.trades)
+            .into_iter()
+            .partition(|t| t.signature == signature);
+        self.trades = pending;
+
+        done.into_iter()
+            .map(|trade| match &observation {
+                Observation::Seen(_) => Resolution::Finalized(trade),
+                Observation::Failed(err) => Resolution::Reorged {
+                    reason: format!("transaction failed on the surviving fork: {}", err),
+                    trade,
+                },
+                Observation::Missing => Resolution::Reorged {
+                    reason: format!(
+                        "signature unknown to the cluster for {} polls after reaching {}",
+                        MISSING_LIMIT,
+                        trade.commitment.as_str()
+                    ),
+                    trade,
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(signature: &str) -> TrackedTrade {
+        TrackedTrade::new(
+            "opp-1",
+            signature,
+            "SOL/USDC",
+            "Raydium",
+            "Orca",
+            Decimal::from(100),
+            Decimal::from(2),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_finalized_trades_stop_being_tracked() {
+        let mut tracker = ConfirmationTracker::new();
+        // Two legs of a batch share a signature
+        tracker.track(trade("sig-a"));
+        tracker.track(trade("sig-a"));
+        assert_eq!(tracker.signatures(), vec!["sig-a".to_string()]);
+
+        assert!(tracker
+            .observe("sig-a", Observation::Seen(Commitment::Confirmed))
+            .is_empty());
+        let resolutions = tracker.observe("sig-a", Observation::Seen(Commitment::Finalized));
+        assert_eq!(resolutions.len(), 2);
+        assert!(matches!(resolutions[0], Resolution::Finalized(_)));
+        assert_eq!(resolutions[0].update().commitment, "finalized");
+        assert!(tracker.is_empty());
+
+        // Settling at confirmed stops there
+        let mut tracker = ConfirmationTracker::new().with_settlement(Commitment::Confirmed);
+        tracker.track(trade("sig-b"));
+        assert!(tracker
+            .observe("sig-b", Observation::Seen(Commitment::Processed))
+            .is_empty());
+        let resolutions = tracker.observe("sig-b", Observation::Seen(Commitment::Confirmed));
+        assert_eq!(resolutions[0].update().commitment, "confirmed");
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_missing_or_failed_signatures_are_reorged() {
+        let mut tracker = ConfirmationTracker::new();
+        tracker.track(trade("sig-a"));
+        tracker.track(trade("sig-b"));
+
+        // A reappearance resets the missing count
+        for _ in 0..MISSING_LIMIT - 1 {
+            assert!(tracker.observe("sig-a", Observation::Missing).is_empty());
+        }
+        tracker.observe("sig-a", Observation::Seen(Commitment::Confirmed));
+        for _ in 0..MISSING_LIMIT - 1 {
+            assert!(tracker.observe("sig-a", Observation::Missing).is_empty());
+        }
+        let resolutions = tracker.observe("sig-a", Observation::Missing);
+        let update = resolutions[0].update();
+        assert_eq!(update.commitment, COMMITMENT_REORGED);
+        assert_eq!(update.profit_usd, Decimal::from(2));
+
+        let resolutions =
+            tracker.observe("sig-b", Observation::Failed("InstructionError".to_string()));
+        assert!(matches!(resolutions[0], Resolution::Reorged { .. }));
+        assert!(tracker.is_empty());
+        assert!(tracker.observe("sig-c", Observation::Missing).is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Manual Trading Pause
+//!
+//! Operators pause and resume trading by hand, from the REST API
+//! (`/control/pause`, `/control/resume`) or from Telegram commands. Both
+//! go through the same `TradingControl`. While paused, the main loop skips
+//! detection and execution; housekeeping (unwinds, confirmations, the fee
+//! reserve) carries on. A pause is not persisted: a restart resumes trading.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Who paused trading, when and why
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Pause {
+    /// e.g. `api` or `telegram:<chat id>`
+    pub by: String,
+    pub reason: Option<String>,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct TradingControl {
+    pause: Mutex<Option<Pause>>,
+}
+
+impl TradingControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause trading. Returns `false`, keeping the first pause, if trading
+    /// was already paused.
+    pub fn pause(&self, by: &str, reason: Option<String>) -> bool {
+        let mut pause = self.lock();
+        if pause.is_some() {
+            return false;
+        }
+        *pause = Some(Pause {
+            by: by.to_string(),
+            reason,
+            since: Utc::now(),
+        });
+        true
+    }
+
+    /// Resume trading, returning the pause that was lifted
+    pub fn resume(&self) -> Option<Pause> {
+        self.lock().take()
+    }
+
+    pub fn paused(&self) -> Option<Pause> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Pause>> {
+        self.pause.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_keeps_first_and_resume_lifts_it() {
+        let control = TradingControl::new();
+        assert!(control.paused().is_none());
+        assert!(control.resume().is_none());
+
+        assert!(control.pause("api", Some("maintenance".into())));
+        assert!(!control.pause("telegram:42", None));
+        let pause = control.paused().unwrap();
+        assert_eq!(pause.by, "api");
+        assert_eq!(pause.reason.as_deref(), Some("maintenance"));
+
+        assert_eq!(control.resume(), Some(pause));
+        assert!(control.paused().is_none());
+        assert!(control.pause("telegram:42", None));
+    }
+}
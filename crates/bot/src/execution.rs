@@ -12,25 +12,83 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_rpc_client::nonblocking::rpc_client::RpcClient;
-use solana_rpc_client_api::config::RpcSendTransactionConfig;
-use solana_sdk::commitment_config::CommitmentConfig;
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client_api::config::{
+    RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
 use solana_sdk::transaction::VersionedTransaction;
-use std::collections::HashMap;
 use tracing::{debug, error, info, warn};
 
 use crate::wallet::Wallet;
 use solana_arb_core::jito::JitoClient;
-use solana_arb_core::types::TradeResult;
-use solana_arb_core::ArbitrageOpportunity;
-
+use solana_arb_core::tokens::{Rounding, TokenInfo, TokenRegistry};
+use solana_arb_core::types::{FailureKind, TradeResult};
+use solana_arb_core::pathfinding::TradingPath;
+use solana_arb_core::{ArbitrageOpportunity, PriceData, TokenPair, Uuid};
+
+use crate::batching::{
+    split_fee, transaction_fee_lamports, BatchConfig, BatchExecution, BatchLeg, MAX_TX_BYTES,
+    MAX_TX_COMPUTE_UNITS,
+};
+use crate::commitments::Commitments;
+use crate::compliance::{ComplianceGate, ComplianceVeto, ExecutionPlan, PlanLeg, PlanToken};
+use crate::config_manager::SlippageConfig;
+use crate::execution_mode::ExecutionMode;
+use crate::latency_slo::{self, BuildDeadline, LatencySlo};
+use crate::loss_guard::{self, MaxLossExceeded, WorstCase};
+use crate::priority_fee::PriorityFeeControl;
+use crate::inflight::{FlightStage, InFlightTrades};
+use crate::intent_wal::IntentWal;
+use crate::quote_cache::{QuoteCache, QuoteRoute};
+use crate::trade_traces::{QuoteSnapshot, TradeTraces};
+use crate::wsol;
 use crate::flash_loan_tx_builder::FlashLoanTxBuilder;
+use crate::jupiter_endpoint::{normalize_base_url, PUBLIC_JUPITER_API_URL};
+use crate::routing::{verify_route, RouteFilter, RouteMismatch};
+use crate::simulation::{
+    balance_deltas, simulated_profit, AccountState, BalanceDelta, MintValuation,
+};
+use crate::paths::{self, PathConfig, PathExecution};
+use crate::quarantine::Quarantine;
+use crate::retry_policy::{self, FailureCause, ProgramFailure, RetryAction};
+use crate::spending_guard::{SpendingGuard, SpendingLimits};
+use crate::submission::{AccountSet, SubmissionCoordinator, SubmissionPermit};
+use solana_arb_flash_loans::FlashLoanRouter;
+use solana_sdk::account::Account;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::message::{v0, VersionedMessage};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address;
 use std::str::FromStr;
 
-const JUPITER_API_URL: &str = "https://quote-api.jup.ag/v6";
+/// How far a trade is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStage {
+    /// Build the transaction only
+    Build,
+    /// Build and simulate it, without checks, signing or submission
+    Simulate,
+    /// Build, check, sign and submit it
+    Submit,
+}
+
+/// Outcome of a successful pre-submission simulation
+#[derive(Debug, Clone)]
+struct SimulationSummary {
+    compute_units: u64,
+    /// Net payer balance change in the quote token
+    profit: Decimal,
+    /// Payer balance changes, including every mint the spending guard caps
+    deltas: Vec<BalanceDelta>,
+    /// Program logs of the simulation
+    logs: Vec<String>,
+}
 
 // Token Mints (Mainnet)
+/// Trades above this size (USD) are funded with a flash loan when enabled
+pub const FLASH_LOAN_THRESHOLD_USD: u64 = 1000;
+
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 pub const RAY_MINT: &str = "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R";
@@ -50,6 +108,14 @@ pub struct ExecutionConfig {
     pub max_retries: u32,
     /// RPC commitment level (e.g., "confirmed", "finalized").
     pub rpc_commitment: String,
+    /// Commitment of reads and of submissions
+    pub commitments: Commitments,
+    /// Let Jupiter wrap and unwrap SOL in every swap. Off in wSOL mode,
+    /// where swaps use the wallet's standing wSOL account.
+    pub wrap_and_unwrap_sol: bool,
+    /// Time quote, swap instructions and build may take before the trade is
+    /// abandoned. `None` never abandons.
+    pub build_deadline: Option<std::time::Duration>,
 }
 
 impl Default for ExecutionConfig {
@@ -60,12 +126,16 @@ impl Default for ExecutionConfig {
             slippage_bps: 50,
             max_retries: 3,
             rpc_commitment: "confirmed".to_string(),
+            commitments: Commitments::default(),
+            wrap_and_unwrap_sol: true,
+            build_deadline: Some(std::time::Duration::from_millis(800)),
         }
     }
 }
 
 use solana_arb_core::alt::AltManager;
 use solana_arb_core::rate_limiter::RateLimiter;
+use solana_arb_core::rpc_batch::RpcBatcher;
 use std::sync::Arc;
 
 /// Main execution component responsible for processing trades.
@@ -77,24 +147,59 @@ use std::sync::Arc;
 pub struct Executor {
     /// HTTP client for making API requests.
     client: Client,
-    /// Cache of token mint addresses.
-    token_map: HashMap<String, String>,
+    /// Token mints and decimals.
+    token_registry: TokenRegistry,
     /// Execution configuration.
     config: ExecutionConfig,
     /// Builder for flash loan transactions.
     flash_loan_builder: FlashLoanTxBuilder,
-    /// Whether flash loans are enabled.
-    flash_loans_enabled: bool,
+    /// Jupiter `excludeDexes` / `onlyDexes` applied to every quote.
+    route_filter: std::sync::RwLock<RouteFilter>,
+    /// Per-pair and per-size slippage overrides, ahead of `config.slippage_bps`.
+    slippage: std::sync::RwLock<SlippageConfig>,
+    /// Largest worst-case loss (USD) a trade may risk; `None` is uncapped.
+    max_trade_loss: std::sync::RwLock<Option<Decimal>>,
+    /// Operator priority fee override, ahead of the configured fee.
+    priority_fees: Arc<PriorityFeeControl>,
+    /// Where the quotes behind each trade are recorded, if anywhere.
+    trade_traces: Option<Arc<TradeTraces>>,
+    /// Stages of the trades being executed, for the in-flight view
+    inflight: Option<Arc<InFlightTrades>>,
+    /// Where signed transactions are logged until seen confirmed
+    intents: Option<Arc<IntentWal>>,
+    /// Recent Jupiter quotes, reused while fresh
+    quote_cache: Option<Arc<QuoteCache>>,
+    /// Hooks that can veto a live trade before it is signed.
+    compliance: Option<Arc<ComplianceGate>>,
+    /// Where build latencies and deadline aborts are reported, if anywhere.
+    latency_slo: Option<Arc<LatencySlo>>,
     /// Optional Address Lookup Table (ALT) manager.
     alt_manager: Option<Arc<AltManager>>,
+    /// Batches balance lookups with other RPC reads, if set.
+    rpc_batcher: Option<Arc<RpcBatcher>>,
     /// Rate limiter for RPC requests.
     pub rpc_rate_limiter: Option<Arc<RateLimiter>>,
     /// Rate limiter for Jupiter API requests.
     pub jupiter_rate_limiter: Option<Arc<RateLimiter>>,
+    /// Jupiter v6 API base URL, public or self-hosted.
+    jupiter_api_url: String,
+    /// Hard outflow caps checked before every signature.
+    spending_guard: Arc<SpendingGuard>,
+    /// Orders the wallet's submissions that touch the same accounts
+    submissions: Arc<SubmissionCoordinator>,
+    /// Refuses signatures while the wallet is quarantined and records the
+    /// ones made, if set.
+    quarantine: Option<Arc<Quarantine>>,
+    /// Failed submissions by cause and retry action, since the last
+    /// [`Self::take_retry_stats`]
+    retry_stats: std::sync::Mutex<std::collections::HashMap<(FailureCause, RetryAction), u64>>,
+    /// Latest USDC mid price of each token, to size pairs whose quote token
+    /// is not a USD stablecoin (e.g. LST/SOL)
+    usd_prices: std::sync::RwLock<std::collections::HashMap<String, Decimal>>,
 }
 
 /// Request body for Jupiter /swap endpoint (full transaction mode)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct SwapRequest {
     #[serde(rename = "userPublicKey")]
     user_public_key: String,
@@ -102,6 +207,8 @@ struct SwapRequest {
     quote_response: serde_json::Value,
     #[serde(rename = "computeUnitPriceMicroLamports")]
     compute_unit_price_micro_lamports: Option<u64>,
+    #[serde(rename = "wrapAndUnwrapSol")]
+    wrap_and_unwrap_sol: bool,
 }
 
 /// Response from Jupiter /swap endpoint
@@ -176,51 +283,356 @@ impl Executor {
                 .unwrap_or_default()
                 .contains("devnet");
 
-        let mut token_map = HashMap::new();
+        let mut token_registry = TokenRegistry::mainnet();
         if is_devnet {
-            // Devnet Mints
-            // Solend Devnet USDC: zVzi5VAf4qMEwzv7NXECVx5v2pQ7xnqVVjCXZwS9XzA
-            token_map.insert("SOL".to_string(), SOL_MINT.to_string());
-            token_map.insert(
-                "USDC".to_string(),
-                "zVzi5VAf4qMEwzv7NXECVx5v2pQ7xnqVVjCXZwS9XzA".to_string(),
-            );
-            // Other mints (RAY, ORCA) might not work on Devnet or use different addresses.
-            // Leaving them pointing to Mainnet but users should be aware.
-            token_map.insert("RAY".to_string(), RAY_MINT.to_string());
-            token_map.insert("ORCA".to_string(), ORCA_MINT.to_string());
-        } else {
-            token_map.insert("SOL".to_string(), SOL_MINT.to_string());
-            token_map.insert("USDC".to_string(), USDC_MINT.to_string());
-            token_map.insert("RAY".to_string(), RAY_MINT.to_string());
-            token_map.insert("ORCA".to_string(), ORCA_MINT.to_string());
+            // Solend Devnet USDC. Other mints (RAY, ORCA) might not work on
+            // Devnet or use different addresses; users should be aware.
+            token_registry.register(TokenInfo::new(
+                "USDC",
+                "zVzi5VAf4qMEwzv7NXECVx5v2pQ7xnqVVjCXZwS9XzA",
+                6,
+            ));
         }
 
-        let wallet = crate::wallet::Wallet::new().expect("Failed to load wallet for executor");
-        let keypair = if let Some(kp) = wallet.signer() {
-            Keypair::from_bytes(&kp.to_bytes()).expect("Failed to clone keypair")
-        } else {
-            Keypair::new()
-        };
+        let keypair = crate::wallet::Wallet::new()
+            .ok()
+            .and_then(|wallet| wallet.signer().and_then(|kp| Keypair::from_bytes(&kp.to_bytes()).ok()))
+            .unwrap_or_else(Keypair::new);
+
+        let spending_guard = Arc::new(SpendingGuard::new(SpendingLimits::from_env(&token_registry)));
 
         Self {
             client: Client::new(),
-            token_map,
+            token_registry,
             config: config.clone(),
             flash_loan_builder: FlashLoanTxBuilder::new(keypair, is_devnet),
-            flash_loans_enabled: std::env::var("ENABLE_FLASH_LOANS").unwrap_or("false".to_string())
-                == "true",
+            route_filter: std::sync::RwLock::new(RouteFilter::default()),
+            slippage: std::sync::RwLock::new(SlippageConfig::default()),
+            max_trade_loss: std::sync::RwLock::new(None),
+            priority_fees: Arc::new(PriorityFeeControl::new()),
+            trade_traces: None,
+            inflight: None,
+            intents: None,
+            quote_cache: None,
+            compliance: None,
+            latency_slo: None,
             alt_manager: None,
+            rpc_batcher: None,
             rpc_rate_limiter: None,
             jupiter_rate_limiter: None,
+            jupiter_api_url: PUBLIC_JUPITER_API_URL.to_string(),
+            spending_guard,
+            submissions: Arc::new(SubmissionCoordinator::new()),
+            quarantine: None,
+            retry_stats: std::sync::Mutex::new(std::collections::HashMap::new()),
+            usd_prices: std::sync::RwLock::new(std::collections::HashMap::new()),
+        }
+    }
+
+    /// Remember the USD prices among `prices`: the mid of every pair quoted in USDC
+    pub fn record_usd_prices(&self, prices: &[PriceData]) {
+        let mut usd_prices = self.usd_prices.write().unwrap_or_else(|e| e.into_inner());
+        for price in prices.iter().filter(|p| p.pair.quote == "USDC" && p.mid_price > Decimal::ZERO) {
+            usd_prices.insert(price.pair.base.clone(), price.mid_price);
+        }
+    }
+
+    /// USD price of `token`: one for USD stablecoins, otherwise its latest
+    /// USDC mid price
+    fn usd_price(&self, token: &str) -> Result<Decimal> {
+        if matches!(token, "USDC" | "USDT") {
+            return Ok(Decimal::ONE);
         }
+        self.usd_prices
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(token)
+            .copied()
+            .ok_or_else(|| anyhow!("no USD price for {} yet", token))
+    }
+
+    /// `size_usd` worth of `pair`'s quote token
+    pub fn quote_amount(&self, pair: &TokenPair, size_usd: Decimal) -> Result<Decimal> {
+        Ok(size_usd / self.usd_price(&pair.quote)?)
+    }
+
+    /// `size_usd` worth of `pair`'s quote token in atoms, rounded down so a
+    /// trade never spends more than the approved size
+    pub fn quote_atoms(&self, pair: &TokenPair, size_usd: Decimal) -> Result<u64> {
+        Ok(self
+            .token_registry
+            .to_atoms(&pair.quote, self.quote_amount(pair, size_usd)?, Rounding::Down)?)
+    }
+
+    pub fn submissions(&self) -> &Arc<SubmissionCoordinator> {
+        &self.submissions
+    }
+
+    /// Failed submissions by cause and retry action since the last call
+    pub fn take_retry_stats(&self) -> Vec<(FailureCause, RetryAction, u64)> {
+        let mut stats = self.retry_stats.lock().unwrap_or_else(|e| e.into_inner());
+        stats.drain().map(|((cause, action), count)| (cause, action, count)).collect()
+    }
+
+    /// Waits until no other submission of the wallet uses the accounts
+    /// `message` writes; the fee payer is its first account
+    async fn submission_permit(&self, message: &VersionedMessage) -> SubmissionPermit {
+        let payer = message.static_account_keys().first().copied().unwrap_or_default();
+        self.submissions
+            .acquire(AccountSet::from_message(message, &payer))
+            .await
     }
 
     /// Sets the address lookup table manager for optimizing transaction size.
     pub fn set_alt_manager(&mut self, manager: Arc<AltManager>) {
         self.alt_manager = Some(manager);
     }
+
+    /// Sends balance checks through the shared RPC batcher.
+    pub fn set_rpc_batcher(&mut self, batcher: Arc<RpcBatcher>) {
+        self.rpc_batcher = Some(batcher);
+    }
     
+    /// Replaces the venue filter applied to Jupiter quotes.
+    pub fn set_route_filter(&self, filter: RouteFilter) {
+        *self.route_filter.write().unwrap_or_else(|e| e.into_inner()) = filter;
+    }
+
+    /// The venue filter currently applied to Jupiter quotes.
+    pub fn route_filter(&self) -> RouteFilter {
+        self.route_filter
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replaces the slippage overrides applied to Jupiter quotes.
+    pub fn set_slippage(&self, slippage: SlippageConfig) {
+        *self.slippage.write().unwrap_or_else(|e| e.into_inner()) = slippage;
+    }
+
+    /// Slippage tolerance for a trade of `size_usd` on `pair`: the first
+    /// matching override, otherwise the configured tolerance.
+    pub fn slippage_bps_for(&self, pair: &TokenPair, size_usd: Decimal) -> u64 {
+        self.slippage
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .slippage_bps(&pair.symbol(), size_usd.to_f64().unwrap_or_default())
+            .unwrap_or(self.config.slippage_bps)
+    }
+
+    /// Replaces the per-trade cap on worst-case loss; unset or non-positive
+    /// values remove it.
+    pub fn set_max_trade_loss(&self, max_trade_loss_usd: Option<f64>) {
+        *self.max_trade_loss.write().unwrap_or_else(|e| e.into_inner()) =
+            loss_guard::cap_from_config(max_trade_loss_usd);
+    }
+
+    /// Whether `mint` is in the token registry
+    pub fn knows_token(&self, mint: &str) -> bool {
+        self.token_registry.get(mint).is_some()
+    }
+
+    /// Adds a token, e.g. one of a discovered pair, to the registry
+    pub fn register_token(&mut self, info: TokenInfo) {
+        self.token_registry.register(info);
+    }
+
+    /// Whether flash loans can be built for `base`, i.e. the flash loan
+    /// builder or its router has a reserve for its mint.
+    pub fn supports_flash_loan(&self, base: &str) -> bool {
+        self.token_registry
+            .mint(base)
+            .and_then(|mint| Pubkey::from_str(mint).ok())
+            .is_some_and(|mint| self.flash_loan_builder.supports(&mint))
+    }
+
+    /// Takes flash loans from the provider `router` picks for each loan.
+    pub fn set_flash_loan_router(&mut self, router: Arc<FlashLoanRouter>) {
+        self.flash_loan_builder.set_router(router);
+    }
+
+    /// Shares the operator priority fee override with the executor and the
+    /// flash loan builder.
+    pub fn set_priority_fee_control(&mut self, control: Arc<PriorityFeeControl>) {
+        self.flash_loan_builder.set_priority_fee_control(control.clone());
+        self.priority_fees = control;
+    }
+
+    /// Records the quote each trade is built from in `traces`.
+    pub fn set_trade_traces(&mut self, traces: Arc<TradeTraces>) {
+        self.trade_traces = Some(traces);
+    }
+
+    /// Reports each trade's stage and signatures to `inflight`.
+    pub fn set_inflight(&mut self, inflight: Arc<InFlightTrades>) {
+        self.inflight = Some(inflight);
+    }
+
+    /// Logs every signed transaction to `intents` until it is confirmed.
+    pub fn set_intent_wal(&mut self, intents: Arc<IntentWal>) {
+        self.intents = Some(intents);
+    }
+
+    /// Reuses quotes from `cache` while they are fresh.
+    pub fn set_quote_cache(&mut self, cache: Arc<QuoteCache>) {
+        self.quote_cache = Some(cache);
+    }
+
+    /// The quote cache, if quotes are cached.
+    pub fn quote_cache(&self) -> Option<&Arc<QuoteCache>> {
+        self.quote_cache.as_ref()
+    }
+
+    /// Drops cached quotes between `pair`'s tokens, by symbol or mint.
+    pub fn invalidate_quotes(&self, pair: &TokenPair) {
+        if let Some(cache) = &self.quote_cache {
+            let mut tokens = vec![pair.base.as_str(), pair.quote.as_str()];
+            tokens.extend(self.token_registry.mint(&pair.base));
+            tokens.extend(self.token_registry.mint(&pair.quote));
+            cache.invalidate(&tokens);
+        }
+    }
+
+    /// Sets the compliance hooks run before live trades are signed.
+    pub fn set_compliance(&mut self, gate: Arc<ComplianceGate>) {
+        self.compliance = Some(gate);
+    }
+
+    /// Checks `quarantine` before every signature and records each one.
+    pub fn set_quarantine(&mut self, quarantine: Arc<Quarantine>) {
+        self.quarantine = Some(quarantine);
+    }
+
+    /// Drops the flash loan payer key while the wallet is quarantined.
+    pub fn revoke_keys(&mut self) {
+        self.flash_loan_builder.revoke_payer();
+    }
+
+    /// Restores the flash loan payer key after a quarantine is lifted.
+    pub fn restore_keys(&mut self, payer: Keypair) {
+        self.flash_loan_builder.restore_payer(payer);
+    }
+
+    /// Signs `message`, unless the wallet is quarantined.
+    fn sign(&self, message: VersionedMessage, signer: &Keypair) -> Result<VersionedTransaction> {
+        if let Some(quarantine) = &self.quarantine {
+            quarantine.ensure_signing()?;
+        }
+        let tx = VersionedTransaction::try_new(message, &[signer])?;
+        self.note_signed(&tx);
+        Ok(tx)
+    }
+
+    fn note_signed(&self, tx: &VersionedTransaction) {
+        if let (Some(quarantine), Some(signature)) = (&self.quarantine, tx.signatures.first()) {
+            quarantine.note_signed(&signature.to_string());
+        }
+        if let Some(intents) = &self.intents {
+            intents.signed(tx);
+        }
+    }
+
+    /// Close the intent of a transaction seen confirmed. Intents of
+    /// transactions whose fate is unknown stay open for reconciliation.
+    fn note_confirmed(&self, signature: &solana_sdk::signature::Signature) {
+        if let Some(intents) = &self.intents {
+            intents.resolve(&signature.to_string(), "confirmed");
+        }
+    }
+
+    /// Reports build latencies and deadline aborts to `slo`.
+    pub fn set_latency_slo(&mut self, slo: Arc<LatencySlo>) {
+        self.latency_slo = Some(slo);
+    }
+
+    /// Run one build step under `deadline`, reporting an abort to the
+    /// latency SLO
+    async fn before_deadline<T>(
+        &self,
+        deadline: &BuildDeadline,
+        step: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let result = deadline.within(step).await;
+        if let (Err(e), Some(slo)) = (&result, &self.latency_slo) {
+            if latency_slo::is_deadline_exceeded(e) {
+                slo.record_aborted(deadline.elapsed());
+            }
+        }
+        result
+    }
+
+    /// The build of a trade is done; what follows is signing and sending
+    fn record_build(&self, deadline: &BuildDeadline) {
+        if let Some(slo) = &self.latency_slo {
+            slo.record_built(deadline.elapsed());
+        }
+    }
+
+    fn enter_stage(&self, id: Uuid, stage: FlightStage) {
+        if let Some(inflight) = &self.inflight {
+            inflight.enter(id, stage);
+        }
+    }
+
+    fn record_signature(&self, id: Uuid, signature: &str) {
+        if let Some(inflight) = &self.inflight {
+            inflight.record_signature(id, signature);
+        }
+    }
+
+    fn trace_simulation(&self, id: Uuid, logs: &[String]) {
+        if let Some(traces) = &self.trade_traces {
+            traces.record_simulation(id, logs.to_vec());
+        }
+    }
+
+    fn trace_quote(&self, opp: &ArbitrageOpportunity, quote: &serde_json::Value) {
+        if let Some(traces) = &self.trade_traces {
+            if let Some(snapshot) = QuoteSnapshot::from_quote(quote, &self.token_registry, opp) {
+                traces.record_quote(opp.id, snapshot);
+            }
+        }
+    }
+
+    /// The worst case `quote` accepts for a trade of `size_usd`, recorded in
+    /// the trade's trace
+    fn worst_case(
+        &self,
+        opp: &ArbitrageOpportunity,
+        quote: &serde_json::Value,
+        size_usd: Decimal,
+    ) -> Option<WorstCase> {
+        let quote_usd = self.usd_price(&opp.pair.quote).ok()?;
+        let worst = WorstCase::from_quote(quote, &self.token_registry, opp, size_usd, quote_usd);
+        if let (Some(traces), Some(worst)) = (&self.trade_traces, &worst) {
+            traces.record_worst_case(opp.id, worst.loss_usd);
+        }
+        worst
+    }
+
+    /// Refuses a worst case that loses more than the per-trade cap
+    fn check_max_loss(&self, worst: Option<&WorstCase>) -> std::result::Result<(), MaxLossExceeded> {
+        let cap = *self.max_trade_loss.read().unwrap_or_else(|e| e.into_inner());
+        loss_guard::check(worst, cap)
+    }
+
+    /// Compute unit price for the next transaction: the override while one
+    /// is in force, otherwise the configured fee.
+    pub fn priority_fee(&self) -> u64 {
+        self.priority_fees.fee_or(self.config.priority_fee_micro_lamports)
+    }
+
+    /// Points quotes and swaps at a different Jupiter API, e.g. a self-hosted one.
+    pub fn set_jupiter_api_url(&mut self, url: &str) {
+        self.jupiter_api_url = normalize_base_url(url);
+    }
+
+    /// The Jupiter API base URL in use.
+    pub fn jupiter_api_url(&self) -> &str {
+        &self.jupiter_api_url
+    }
+
     /// Configures rate limiters for the executor.
     pub fn set_rate_limiters(
         &mut self,
@@ -231,6 +643,23 @@ impl Executor {
         self.jupiter_rate_limiter = jupiter;
     }
 
+    /// Sends a Jupiter API request under the Jupiter rate limiter, reporting
+    /// its latency and whether it was throttled.
+    async fn send_jupiter(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        if let Some(limiter) = &self.jupiter_rate_limiter {
+            limiter.acquire().await;
+        }
+        let started = std::time::Instant::now();
+        let response = request.send().await?;
+        if let Some(limiter) = &self.jupiter_rate_limiter {
+            limiter.record_response(
+                started.elapsed(),
+                response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            );
+        }
+        Ok(response)
+    }
+
     /// Fetches a swap quote from the Jupiter API.
     ///
     /// # Arguments
@@ -238,38 +667,127 @@ impl Executor {
     /// * `input_mint` - Mint address of the token to swap from
     /// * `output_mint` - Mint address of the token to swap to
     /// * `amount` - Amount of input token in atomic units
+    /// * `slippage_bps` - Slippage tolerance, see `slippage_bps_for`
+    ///
+    /// A cached quote is only reused for exactly the same amount, since the
+    /// quote may be executed.
     pub async fn get_quote(
         &self,
         input_mint: &str,
         output_mint: &str,
         amount: u64,
+        slippage_bps: u64,
     ) -> Result<serde_json::Value> {
+        self.quote(input_mint, output_mint, amount, slippage_bps, true)
+            .await
+    }
+
+    /// Like [`Self::get_quote`], but any cached quote in `amount`'s size
+    /// bucket will do. For estimates, never for execution.
+    pub async fn get_quote_estimate(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u64,
+    ) -> Result<serde_json::Value> {
+        self.quote(input_mint, output_mint, amount, slippage_bps, false)
+            .await
+    }
+
+    async fn quote(
+        &self,
+        input_mint: &str,
+        output_mint: &str,
+        amount: u64,
+        slippage_bps: u64,
+        exact: bool,
+    ) -> Result<serde_json::Value> {
+        let route = QuoteRoute {
+            input: input_mint.to_string(),
+            output: output_mint.to_string(),
+            slippage_bps,
+            params: self.route_filter().query_params(),
+        };
+        if let Some(quote) = self
+            .quote_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&route, amount, exact))
+        {
+            debug!("Reusing cached quote {} → {} for {}", input_mint, output_mint, amount);
+            return Ok(quote);
+        }
+
         let url = format!(
-            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}",
-            JUPITER_API_URL, input_mint, output_mint, amount, self.config.slippage_bps
+            "{}/quote?inputMint={}&outputMint={}&amount={}&slippageBps={}{}",
+            self.jupiter_api_url,
+            input_mint,
+            output_mint,
+            amount,
+            slippage_bps,
+            route.params
         );
 
         debug!("Fetching quote from {}", url);
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_jupiter(self.client.get(&url)).await?;
         if !response.status().is_success() {
             let err_text = response.text().await?;
             return Err(anyhow!("Jupiter quote failed: {}", err_text));
         }
         let quote: serde_json::Value = response.json().await?;
+        if let Some(cache) = &self.quote_cache {
+            cache.insert(route, amount, quote.clone());
+        }
         Ok(quote)
     }
 
+    /// Quoted price impact (%) of buying `size_usd` of `opp`'s base token
+    /// and selling it back: the sum of both quotes' `priceImpactPct`.
+    pub async fn round_trip_impact_pct(
+        &self,
+        opp: &ArbitrageOpportunity,
+        size_usd: Decimal,
+    ) -> Result<Decimal> {
+        let (quote_token, base_token) = (&opp.pair.quote, &opp.pair.base);
+        let amount_atoms = self
+            .quote_atoms(&opp.pair, size_usd)
+            .map_err(|e| anyhow!("Cannot size quote: {}", e))?;
+        let slippage_bps = self.slippage_bps_for(&opp.pair, size_usd);
+        let field = |quote: &serde_json::Value, name: &str| {
+            quote
+                .get(name)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow!("Jupiter quote has no {}", name))
+                .map(str::to_string)
+        };
+
+        let buy = self
+            .get_quote_estimate(quote_token, base_token, amount_atoms, slippage_bps)
+            .await?;
+        let bought: u64 = field(&buy, "outAmount")?.parse()?;
+        let sell = self
+            .get_quote_estimate(base_token, quote_token, bought, slippage_bps)
+            .await?;
+        Ok(Decimal::from_str(&field(&buy, "priceImpactPct")?)?
+            + Decimal::from_str(&field(&sell, "priceImpactPct")?)?)
+    }
+
     /// Checks the SOL balance of the provided wallet.
     pub async fn check_balance(&self, wallet: &Wallet, rpc_url: &str) -> Result<u64> {
-        let client = RpcClient::new(rpc_url.to_string());
         let pubkey = Pubkey::from_str(&wallet.pubkey())
             .map_err(|e| anyhow!("Invalid wallet pubkey: {}", e))?;
+        if let Some(batcher) = &self.rpc_batcher {
+            return batcher.get_balance(&pubkey).await;
+        }
+        let client = RpcClient::new_with_commitment(rpc_url.to_string(), self.config.commitments.reads);
         Ok(client.get_balance(&pubkey).await?)
     }
 
-    /// Executes an arbitrage trade.
+    /// Executes an arbitrage trade in `mode`, as chosen by the
+    /// [`ExecutionModeSelector`](crate::execution_mode::ExecutionModeSelector).
     ///
-    /// Decides whether to use a flash loan based on trade size and configuration.
+    /// A trade whose build misses the execution deadline is abandoned before
+    /// signing and comes back failed with `FailureKind::AbortedDeadline`.
     ///
     /// # Arguments
     ///
@@ -279,6 +797,8 @@ impl Executor {
     /// * `submit` - If true, submits the transaction; otherwise, simulates
     /// * `rpc_url` - The RPC URL to use
     /// * `jito_client` - Optional Jito client for MEV protection
+    /// * `mode` - Standard or flash-loan funded
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         &self,
         wallet: &Wallet,
@@ -287,41 +807,124 @@ impl Executor {
         submit: bool,
         rpc_url: &str,
         jito_client: Option<&JitoClient>,
+        mode: ExecutionMode,
     ) -> Result<TradeResult> {
-        let flash_loan_threshold = Decimal::from(1000);
-        let use_flash_loan = self.flash_loans_enabled && amount_usd > flash_loan_threshold;
+        let stage = if submit {
+            ExecutionStage::Submit
+        } else {
+            ExecutionStage::Build
+        };
+        self.run(wallet, opp, amount_usd, stage, rpc_url, jito_client, mode)
+            .await
+    }
 
-        if use_flash_loan {
-            return self
-                .execute_with_flash_loan(wallet, opp, amount_usd, submit, rpc_url, jito_client)
-                .await;
+    /// Builds and simulates a trade without signing or submitting it, and
+    /// returns its simulated profit in the quote token.
+    ///
+    /// A standard trade's bought base token is valued at the opportunity's
+    /// sell price, a flash loan's round trip at its net balance change. Fails
+    /// if the trade cannot be built, its route does not match, the simulation
+    /// errors or it shows no profit.
+    pub async fn preflight(
+        &self,
+        wallet: &Wallet,
+        opp: &ArbitrageOpportunity,
+        amount_usd: Decimal,
+        rpc_url: &str,
+        mode: ExecutionMode,
+    ) -> Result<Decimal> {
+        let result = self
+            .run(wallet, opp, amount_usd, ExecutionStage::Simulate, rpc_url, None, mode)
+            .await?;
+        match result.simulated_profit {
+            Some(profit) if result.success => Ok(profit),
+            _ => Err(anyhow!(result
+                .error
+                .unwrap_or_else(|| "not simulated".to_string()))),
         }
+    }
 
-        self.execute_standard(wallet, opp, amount_usd, submit, rpc_url, jito_client)
-            .await
+    #[allow(clippy::too_many_arguments)]
+    async fn run(
+        &self,
+        wallet: &Wallet,
+        opp: &ArbitrageOpportunity,
+        amount_usd: Decimal,
+        stage: ExecutionStage,
+        rpc_url: &str,
+        jito_client: Option<&JitoClient>,
+        mode: ExecutionMode,
+    ) -> Result<TradeResult> {
+        let deadline = BuildDeadline::start(self.config.build_deadline);
+        let result = if mode == ExecutionMode::FlashLoan {
+            self.execute_with_flash_loan(wallet, opp, amount_usd, stage, rpc_url, jito_client, &deadline)
+                .await
+        } else {
+            self.execute_standard(wallet, opp, amount_usd, stage, rpc_url, jito_client, &deadline)
+                .await
+        };
+
+        match result {
+            Err(e) if latency_slo::is_deadline_exceeded(&e) => {
+                Ok(Self::deadline_result(opp, &e, &deadline))
+            }
+            result => result,
+        }
+    }
+
+    /// Failed result for a trade abandoned at its build deadline
+    fn deadline_result(opp: &ArbitrageOpportunity, error: &anyhow::Error, deadline: &BuildDeadline) -> TradeResult {
+        warn!(
+            "⏱️ Abandoning {} {} → {} after {}ms: {}",
+            opp.pair,
+            opp.buy_dex,
+            opp.sell_dex,
+            deadline.elapsed().as_millis(),
+            error
+        );
+        TradeResult {
+            opportunity_id: opp.id,
+            signature: None,
+            success: false,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: Some(error.to_string()),
+            simulated_profit: None,
+            failure_kind: Some(FailureKind::AbortedDeadline),
+        }
     }
 
     /// Executes a standard (non-flash-loan) arbitrage trade.
     ///
     /// Fetches a quote, gets swap instructions, checks balance, and submits the transaction.
+    /// Fails with [`latency_slo::DeadlineExceeded`] if the quote and swap
+    /// transaction are not in by `deadline`.
     #[allow(clippy::too_many_arguments)]
     pub async fn execute_standard(
         &self,
         wallet: &Wallet,
         opp: &ArbitrageOpportunity,
         amount_usd: Decimal,
-        submit: bool,
+        stage: ExecutionStage,
         rpc_url: &str,
         jito_client: Option<&JitoClient>,
+        deadline: &BuildDeadline,
     ) -> Result<TradeResult> {
+        let submit = stage == ExecutionStage::Submit;
         let (input_token, output_token) = (&opp.pair.quote, &opp.pair.base);
 
-        let amount_atoms = (amount_usd * Decimal::from(1_000_000))
-            .to_u64()
-            .unwrap_or(1_000_000);
+        // The quote token is spent, so the USD size is converted to it
+        let amount_atoms = self
+            .quote_atoms(&opp.pair, amount_usd)
+            .map_err(|e| anyhow!("Cannot size trade: {}", e))?;
 
+        let slippage_bps = self.slippage_bps_for(&opp.pair, amount_usd);
+        self.enter_stage(opp.id, FlightStage::Quoting);
         let quote = match self
-            .get_quote(input_token, output_token, amount_atoms)
+            .before_deadline(
+                deadline,
+                self.get_quote(input_token, output_token, amount_atoms, slippage_bps),
+            )
             .await
         {
             Ok(q) => {
@@ -332,11 +935,12 @@ impl Executor {
                         input_token,
                         out_amount,
                         output_token,
-                        self.config.slippage_bps
+                        slippage_bps
                     );
                 }
                 q
             }
+            Err(e) if latency_slo::is_deadline_exceeded(&e) => return Err(e),
             Err(e) => {
                 warn!("Failed to get quote from Jupiter: {}", e);
                 return Ok(TradeResult {
@@ -346,36 +950,76 @@ impl Executor {
                     actual_profit: Decimal::ZERO,
                     executed_at: chrono::Utc::now(),
                     error: Some(format!("Failed to get quote: {}", e)),
+                    simulated_profit: None,
+                    failure_kind: None,
                 });
             }
         };
 
+        self.trace_quote(opp, &quote);
+        if let Err(mismatch) = verify_route(&quote, opp.buy_dex, opp.sell_dex) {
+            return Ok(Self::route_mismatch_result(opp, &mismatch));
+        }
+        let route = ExecutionPlan::quote_route(&quote);
+        let worst = self.worst_case(opp, &quote, amount_usd);
+        self.enter_stage(opp.id, FlightStage::Building);
+
         let swap_req = SwapRequest {
             user_public_key: wallet.pubkey(),
             quote_response: quote,
             compute_unit_price_micro_lamports: if submit {
-                Some(self.config.priority_fee_micro_lamports)
+                Some(self.priority_fee())
             } else {
                 None
             },
+            wrap_and_unwrap_sol: self.config.wrap_and_unwrap_sol,
         };
 
         debug!("Requesting swap instruction...");
+        let swap_url = format!("{}/swap", self.jupiter_api_url);
         let response = self
-            .client
-            .post(format!("{}/swap", JUPITER_API_URL))
-            .json(&swap_req)
-            .send()
+            .before_deadline(deadline, self.send_jupiter(self.client.post(swap_url).json(&swap_req)))
             .await?;
 
         if response.status().is_success() {
-            let swap_resp: SwapResponse = response.json().await?;
+            let swap_resp: SwapResponse = self
+                .before_deadline(deadline, async { Ok(response.json().await?) })
+                .await?;
+            self.record_build(deadline);
             info!(
                 "✅ Received swap transaction (Base64 length: {})",
                 swap_resp.swap_transaction.len()
             );
 
+            if stage == ExecutionStage::Simulate {
+                let tx: VersionedTransaction =
+                    bincode::deserialize(&BASE64_ENGINE.decode(&swap_resp.swap_transaction)?)?;
+                let payer = Pubkey::from_str(&wallet.pubkey())
+                    .map_err(|e| anyhow!("Invalid wallet pubkey: {}", e))?;
+                let valuations = self.valuations_at(opp, opp.sell_price)?;
+                let rpc_client = self.submission_client(rpc_url);
+                self.enter_stage(opp.id, FlightStage::Simulating);
+                let simulation = self
+                    .simulate_with_profit(&rpc_client, &tx, &payer, &valuations)
+                    .await?;
+                self.trace_simulation(opp.id, &simulation.logs);
+                return Ok(Self::simulated_result(opp, simulation.profit));
+            }
+
             if submit {
+                let tx: VersionedTransaction =
+                    bincode::deserialize(&BASE64_ENGINE.decode(&swap_resp.swap_transaction)?)?;
+                if let Err(refused) = self.check_max_loss(worst.as_ref()) {
+                    return Ok(Self::max_loss_result(opp, &refused));
+                }
+                let leg = (opp, amount_usd, worst.map(|w| w.loss_usd));
+                if let Err(veto) = self
+                    .check_compliance(ExecutionMode::Standard, &[leg], &[route], &tx.message)
+                    .await
+                {
+                    return Ok(Self::compliance_veto_result(opp, &veto));
+                }
+
                 if let Ok(balance) = self.check_balance(wallet, rpc_url).await {
                     let min_balance = 10_000_000;
                     if balance < min_balance {
@@ -386,6 +1030,8 @@ impl Executor {
                             actual_profit: Decimal::ZERO,
                             executed_at: chrono::Utc::now(),
                             error: Some("Insufficient SOL balance".to_string()),
+                            simulated_profit: None,
+                            failure_kind: None,
                         });
                     }
                 }
@@ -393,9 +1039,11 @@ impl Executor {
 
                 match self.submit_with_retry(
                     wallet,
-                    &swap_resp.swap_transaction,
+                    swap_req,
+                    swap_resp.swap_transaction,
                     rpc_url,
                     jito_client,
+                    Some(opp.id),
                 ).await {
                     Ok(signature) => {
                         info!("✅ Swap submitted: {}", signature);
@@ -406,6 +1054,8 @@ impl Executor {
                             actual_profit: opp.estimated_profit_usd.unwrap_or_default(),
                             executed_at: chrono::Utc::now(),
                             error: None,
+                            simulated_profit: None,
+                            failure_kind: None,
                         })
                     }
                     Err(e) => Ok(TradeResult {
@@ -415,6 +1065,8 @@ impl Executor {
                         actual_profit: Decimal::ZERO,
                         executed_at: chrono::Utc::now(),
                         error: Some(format!("Submission failed: {}", e)),
+                        simulated_profit: None,
+                        failure_kind: retry_policy::is_program_failure(&e).then_some(FailureKind::ProgramError),
                     }),
                 }
             } else {
@@ -426,6 +1078,8 @@ impl Executor {
                     actual_profit: opp.estimated_profit_usd.unwrap_or_default(),
                     executed_at: chrono::Utc::now(),
                     error: None,
+                    simulated_profit: None,
+                    failure_kind: None,
                 })
             }
         } else {
@@ -438,52 +1092,268 @@ impl Executor {
                 actual_profit: Decimal::ZERO,
                 executed_at: chrono::Utc::now(),
                 error: Some(format!("Failed to get swap transaction: {}", error_text)),
+                simulated_profit: None,
+                failure_kind: None,
             })
         }
     }
 
-    /// Submits a transaction with exponential backoff retry logic.
+    /// Executes a housekeeping trade that sells `base_amount` of the pair's
+    /// base token for its quote token, reducing an open position.
+    ///
+    /// `size_usd` is the trade's approximate USD value, which picks its
+    /// slippage tolerance. Returns the transaction signature (or Jito bundle
+    /// id). When `submit` is false the swap is only built.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_reduction(
+        &self,
+        wallet: &Wallet,
+        pair: &TokenPair,
+        base_amount: Decimal,
+        size_usd: Decimal,
+        submit: bool,
+        rpc_url: &str,
+        jito_client: Option<&JitoClient>,
+    ) -> Result<String> {
+        let amount_atoms = self
+            .token_registry
+            .to_atoms(&pair.base, base_amount, Rounding::Down)
+            .map_err(|e| anyhow!("Cannot size reduction: {}", e))?;
+        let slippage_bps = self.slippage_bps_for(pair, size_usd);
+        let quote = self
+            .get_quote(&pair.base, &pair.quote, amount_atoms, slippage_bps)
+            .await?;
+
+        let swap_req = SwapRequest {
+            user_public_key: wallet.pubkey(),
+            quote_response: quote,
+            compute_unit_price_micro_lamports: if submit {
+                Some(self.priority_fee())
+            } else {
+                None
+            },
+            wrap_and_unwrap_sol: self.config.wrap_and_unwrap_sol,
+        };
+        let response = self
+            .send_jupiter(self.client.post(format!("{}/swap", self.jupiter_api_url)).json(&swap_req))
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Failed to get swap transaction: {}",
+                response.text().await?
+            ));
+        }
+        let swap_resp: SwapResponse = response.json().await?;
+
+        if !submit {
+            info!("📝 [SIMULATION] Reduction of {} {} would be sent here.", base_amount, pair.base);
+            return Ok("simulated_signature".to_string());
+        }
+        self.submit_with_retry(wallet, swap_req, swap_resp.swap_transaction, rpc_url, jito_client, None)
+            .await
+    }
+
+    /// Balance of the wallet's wSOL account in lamports; zero if it does
+    /// not exist.
+    pub async fn wsol_balance(&self, owner: &Pubkey, rpc_url: &str) -> Result<u64> {
+        use solana_sdk::program_pack::Pack;
+
+        let client = RpcClient::new(rpc_url.to_string());
+        let account = client
+            .get_account_with_commitment(&wsol::wsol_account(owner), self.config.commitments.reads)
+            .await?
+            .value;
+        match account {
+            Some(account) => Ok(spl_token::state::Account::unpack_from_slice(&account.data)?.amount),
+            None => Ok(0),
+        }
+    }
+
+    /// Balance of the wallet's associated account for `token`, in whole
+    /// tokens; zero if it does not exist. Native SOL is not included.
+    pub async fn token_balance(&self, owner: &Pubkey, token: &str, rpc_url: &str) -> Result<Decimal> {
+        use solana_sdk::program_pack::Pack;
+
+        let mint = self
+            .token_registry
+            .mint(token)
+            .ok_or_else(|| anyhow!("Unknown token: {}", token))?;
+        let account = get_associated_token_address(owner, &Pubkey::from_str(mint)?);
+        let client = RpcClient::new(rpc_url.to_string());
+        let atoms = match client
+            .get_account_with_commitment(&account, self.config.commitments.reads)
+            .await?
+            .value
+        {
+            Some(account) => spl_token::state::Account::unpack_from_slice(&account.data)?.amount,
+            None => 0,
+        };
+        Ok(self.token_registry.from_atoms(token, atoms)?)
+    }
+
+    /// Signs and sends housekeeping instructions that only move funds between
+    /// the wallet's own accounts (wrapping and unwrapping SOL). They still go
+    /// through the spending guard.
+    pub async fn send_wallet_instructions(
+        &self,
+        wallet: &Wallet,
+        rpc_url: &str,
+        instructions: &[solana_sdk::instruction::Instruction],
+    ) -> Result<String> {
+        let signer = wallet
+            .signer()
+            .ok_or_else(|| anyhow!("No keypair available for signing"))?;
+        let payer = signer.pubkey();
+        let client = self.submission_client(rpc_url);
+
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee())];
+        all.extend_from_slice(instructions);
+        let blockhash = client.get_latest_blockhash().await?;
+        let message = VersionedMessage::V0(v0::Message::try_compile(&payer, &all, &[], blockhash)?);
+        let tx = unsigned_transaction(message);
+
+        let addresses = self.spending_guard.watched_accounts(&payer);
+        let (_, deltas, _) = self.simulate_balances(&client, &tx, &payer, &addresses).await?;
+        self.spending_guard.authorize(&tx.message, &payer, &deltas)?;
+        let signed_tx = self.sign(tx.message, signer)?;
+        let _permit = self.submission_permit(&signed_tx.message).await;
+        let signature = client.send_and_confirm_transaction(&signed_tx).await?;
+        self.note_confirmed(&signature);
+        Ok(signature.to_string())
+    }
+
+    /// Submits the transaction `swap_req` was built into, retrying by the
+    /// failure's cause (see [`retry_policy`]): expired blockhashes rebuild
+    /// from the same quote, moved prices re-quote, throttling and transient
+    /// errors back off exponentially, and slippage and program errors stop.
+    /// The simulation logs are traced under `trace_id` when given.
+    #[allow(clippy::too_many_arguments)]
     async fn submit_with_retry(
         &self,
         wallet: &Wallet,
-        encoded_tx: &str,
+        mut swap_req: SwapRequest,
+        mut encoded_tx: String,
         rpc_url: &str,
         jito_client: Option<&JitoClient>,
+        trace_id: Option<Uuid>,
     ) -> Result<String> {
         let mut last_error = None;
-        
+
         for attempt in 0..self.config.max_retries {
             // Apply rate limit before attempt
             if let Some(limiter) = &self.rpc_rate_limiter {
                 limiter.acquire().await;
             }
 
-            match self.submit_swap_transaction(wallet, encoded_tx, rpc_url, jito_client).await {
+            let started = std::time::Instant::now();
+            let result = self
+                .submit_swap_transaction(wallet, &encoded_tx, rpc_url, jito_client, trace_id)
+                .await;
+            if let Some(limiter) = &self.rpc_rate_limiter {
+                let throttled = result.as_ref().err().is_some_and(|e| is_throttled(e));
+                limiter.record_response(started.elapsed(), throttled);
+            }
+
+            let e = match result {
                 Ok(sig) => return Ok(sig),
-                Err(e) => {
+                Err(e) => e,
+            };
+            let cause = FailureCause::classify(&e);
+            let action = cause.action();
+            *self
+                .retry_stats
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry((cause, action))
+                .or_default() += 1;
+            warn!(
+                "⚠️ Transaction attempt {}/{} failed ({}, {}): {}",
+                attempt + 1,
+                self.config.max_retries,
+                cause.label(),
+                action.label(),
+                e
+            );
+            match action {
+                RetryAction::Abort => return Err(e.context(format!("Not retried: {}", cause.label()))),
+                RetryAction::Escalate => {
+                    return Err(anyhow::Error::new(ProgramFailure { error: format!("{:#}", e) }))
+                }
+                _ if attempt + 1 == self.config.max_retries => {}
+                RetryAction::Backoff => {
                     let delay_ms = 500 * 2u64.pow(attempt);
-                    warn!(
-                        "⚠️ Transaction attempt {}/{} failed: {}. Retrying in {}ms...",
-                        attempt + 1,
-                        self.config.max_retries,
-                        e,
-                        delay_ms
-                    );
                     tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-                    last_error = Some(e);
                 }
+                RetryAction::Rebuild => match self.build_swap(&swap_req).await {
+                    Ok(rebuilt) => encoded_tx = rebuilt,
+                    Err(rebuild) => warn!("Could not rebuild transaction: {}", rebuild),
+                },
+                RetryAction::Requote => match self.requote(&swap_req.quote_response).await {
+                    Ok(quote) => {
+                        swap_req.quote_response = quote;
+                        match self.build_swap(&swap_req).await {
+                            Ok(rebuilt) => encoded_tx = rebuilt,
+                            Err(rebuild) => warn!("Could not rebuild transaction: {}", rebuild),
+                        }
+                    }
+                    // Without a quote as good as the original, the edge is gone
+                    Err(requote) => return Err(e.context(format!("Re-quote refused: {}", requote))),
+                },
             }
+            last_error = Some(e);
         }
 
         Err(last_error.unwrap_or_else(|| anyhow!("All retry attempts exhausted")))
     }
 
+    /// Posts `swap_req` to Jupiter's /swap, returning the encoded transaction
+    async fn build_swap(&self, swap_req: &SwapRequest) -> Result<String> {
+        let response = self
+            .send_jupiter(self.client.post(format!("{}/swap", self.jupiter_api_url)).json(swap_req))
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Failed to get swap transaction: {}",
+                response.text().await?
+            ));
+        }
+        let swap_resp: SwapResponse = response.json().await?;
+        Ok(swap_resp.swap_transaction)
+    }
+
+    /// A fresh quote for the same swap as `original`, bypassing the quote
+    /// cache; refused unless its minimum output is at least the original's
+    async fn requote(&self, original: &serde_json::Value) -> Result<serde_json::Value> {
+        let field = |name: &str| {
+            original
+                .get(name)
+                .and_then(|v| v.as_str().map(str::to_string).or_else(|| v.as_u64().map(|n| n.to_string())))
+                .ok_or_else(|| anyhow!("Quote has no {}", name))
+        };
+        let (input, output) = (field("inputMint")?, field("outputMint")?);
+        let amount: u64 = field("inAmount")?.parse()?;
+        let slippage_bps: u64 = field("slippageBps")?.parse()?;
+        if let Some(cache) = &self.quote_cache {
+            cache.invalidate(&[input.as_str(), output.as_str()]);
+        }
+        let quote = self.get_quote(&input, &output, amount, slippage_bps).await?;
+        if !retry_policy::requote_acceptable(original, &quote) {
+            return Err(anyhow!(
+                "fresh quote's minimum output {} is below the original {}",
+                quote["otherAmountThreshold"],
+                original["otherAmountThreshold"]
+            ));
+        }
+        Ok(quote)
+    }
+
     async fn submit_swap_transaction(
         &self,
         wallet: &Wallet,
         encoded_tx: &str,
         rpc_url: &str,
         jito_client: Option<&JitoClient>,
+        trace_id: Option<Uuid>,
     ) -> Result<String> {
         let signer = wallet
             .signer()
@@ -491,7 +1361,26 @@ impl Executor {
 
         let tx_bytes = BASE64_ENGINE.decode(encoded_tx)?;
         let tx: VersionedTransaction = bincode::deserialize(&tx_bytes)?;
-        let signed_tx = VersionedTransaction::try_new(tx.message, &[signer])?;
+
+        let commitment = self.config.commitments.submission;
+        let client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment);
+
+        // Check outflows against the spending caps before signing
+        let payer = signer.pubkey();
+        let addresses = self.spending_guard.watched_accounts(&payer);
+        if let Some(id) = trace_id {
+            self.enter_stage(id, FlightStage::Simulating);
+        }
+        let (_, deltas, logs) = self
+            .simulate_balances(&client, &tx, &payer, &addresses)
+            .await?;
+        if let Some(id) = trace_id {
+            self.trace_simulation(id, &logs);
+        }
+        self.spending_guard.authorize(&tx.message, &payer, &deltas)?;
+        let signed_tx = self.sign(tx.message, signer)?;
+        // Held until confirmation, or until Jito accepts the bundle
+        let _permit = self.submission_permit(&signed_tx.message).await;
 
         if let Some(jito) = jito_client {
             let signed_tx_bytes = bincode::serialize(&signed_tx)?;
@@ -502,12 +1391,13 @@ impl Executor {
             })?;
 
             info!("🚀 Sent via Jito! Bundle ID: {}", bundle_id);
+            if let Some(id) = trace_id {
+                self.enter_stage(id, FlightStage::Submitted);
+                self.record_signature(id, &bundle_id);
+            }
             return Ok(bundle_id);
         }
 
-        let commitment = self.parse_commitment();
-        let client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment);
-
         let config = RpcSendTransactionConfig {
             skip_preflight: true,
             ..Default::default()
@@ -519,12 +1409,18 @@ impl Executor {
             "📡 Transaction sent: {}. Waiting for confirmation...",
             signature
         );
+        if let Some(id) = trace_id {
+            self.enter_stage(id, FlightStage::Submitted);
+            self.record_signature(id, &signature.to_string());
+            self.enter_stage(id, FlightStage::Confirming);
+        }
         match client.confirm_transaction_with_spinner(
             &signature,
             &client.get_latest_blockhash().await?,
             commitment,
         ).await {
             Ok(_) => {
+                self.note_confirmed(&signature);
                 info!("✅ Transaction confirmed: {}", signature);
             }
             Err(e) => {
@@ -535,12 +1431,10 @@ impl Executor {
         Ok(signature.to_string())
     }
 
-    fn parse_commitment(&self) -> CommitmentConfig {
-        match self.config.rpc_commitment.as_str() {
-            "processed" => CommitmentConfig::processed(),
-            "finalized" => CommitmentConfig::finalized(),
-            _ => CommitmentConfig::confirmed(),
-        }
+    /// Client for blockhashes, simulations and sending, at the submission
+    /// commitment
+    fn submission_client(&self, rpc_url: &str) -> RpcClient {
+        RpcClient::new_with_commitment(rpc_url.to_string(), self.config.commitments.submission)
     }
 
     /// Execute a flash loan arbitrage trade using Jupiter's `/swap-instructions` API.
@@ -549,15 +1443,21 @@ impl Executor {
     /// deserializing it (fragile), this calls `/swap-instructions` which returns
     /// structured JSON instructions that can be directly converted to
     /// `solana_sdk::Instruction`.
+    ///
+    /// Quote, swap instructions, lookup tables and blockhash must be in by
+    /// `deadline`, or this fails with [`latency_slo::DeadlineExceeded`].
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute_with_flash_loan(
         &self,
         wallet: &Wallet,
         opp: &ArbitrageOpportunity,
         amount_usd: Decimal,
-        submit: bool,
+        stage: ExecutionStage,
         rpc_url: &str,
         _jito_client: Option<&JitoClient>,
+        deadline: &BuildDeadline,
     ) -> Result<TradeResult> {
+        let submit = stage == ExecutionStage::Submit;
         info!(
             "⚡ Executing FLASH LOAN trade for opportunity: {} (amount: {} USD)",
             opp.id, amount_usd
@@ -565,28 +1465,41 @@ impl Executor {
 
         // 1. Resolve mint address
         let input_mint_str = self
-            .token_map
-            .get(&opp.pair.base)
+            .token_registry
+            .mint(&opp.pair.base)
             .ok_or_else(|| anyhow!("Unknown base token: {}", opp.pair.base))?;
         let output_mint_str = self
-            .token_map
-            .get(&opp.pair.quote)
+            .token_registry
+            .mint(&opp.pair.quote)
             .ok_or_else(|| anyhow!("Unknown quote token: {}", opp.pair.quote))?;
         let input_mint = Pubkey::from_str(input_mint_str)?;
 
-        // 2. Convert USD amount to token atoms
-        let decimals = if opp.pair.base == "USDC" { 6 } else { 9 };
-        let amount_atoms = (amount_usd * Decimal::from(10u64.pow(decimals)))
-            .to_u64()
-            .unwrap_or(0);
+        // 2. Convert USD amount to base token atoms, rounding down so the
+        //    borrow never exceeds the approved size
+        if opp.buy_price <= Decimal::ZERO {
+            return Err(anyhow!("Invalid buy price for flash loan sizing"));
+        }
+        let quote_amount = self
+            .quote_amount(&opp.pair, amount_usd)
+            .map_err(|e| anyhow!("Cannot size flash loan: {}", e))?;
+        let base_amount = quote_amount / opp.buy_price;
+        let amount_atoms = self
+            .token_registry
+            .to_atoms(&opp.pair.base, base_amount, Rounding::Down)
+            .map_err(|e| anyhow!("Cannot size flash loan: {}", e))?;
 
         if amount_atoms == 0 {
             return Err(anyhow!("Invalid flash loan amount: zero atoms"));
         }
 
         // 3. Get quote from Jupiter
+        let slippage_bps = self.slippage_bps_for(&opp.pair, amount_usd);
+        self.enter_stage(opp.id, FlightStage::Quoting);
         let quote = self
-            .get_quote(input_mint_str, output_mint_str, amount_atoms)
+            .before_deadline(
+                deadline,
+                self.get_quote(input_mint_str, output_mint_str, amount_atoms, slippage_bps),
+            )
             .await?;
 
         if let Some(out_amount) = quote.get("outAmount") {
@@ -596,9 +1509,18 @@ impl Executor {
             );
         }
 
+        self.trace_quote(opp, &quote);
+
+        // The flash loan sells the borrowed base token first
+        if let Err(mismatch) = verify_route(&quote, opp.sell_dex, opp.buy_dex) {
+            return Ok(Self::route_mismatch_result(opp, &mismatch));
+        }
+        let worst = self.worst_case(opp, &quote, amount_usd);
+
         // 4. Get structured swap instructions (NOT full transaction)
+        self.enter_stage(opp.id, FlightStage::Building);
         let swap_instructions_resp = self
-            .get_swap_instructions(&wallet.pubkey(), &quote)
+            .before_deadline(deadline, self.get_swap_instructions(&wallet.pubkey(), &quote))
             .await?;
 
         info!(
@@ -630,7 +1552,7 @@ impl Executor {
                     .iter()
                     .filter_map(|addr| Pubkey::from_str(addr).ok())
                     .collect();
-                alt_manager.get_tables(&table_pubkeys).await?
+                self.before_deadline(deadline, alt_manager.get_tables(&table_pubkeys)).await?
             } else {
                 warn!("ALTs returned by Jupiter but AltManager not configured; proceeding without");
                 vec![]
@@ -640,12 +1562,14 @@ impl Executor {
         };
 
         // 7. Build flash loan transaction via FlashLoanTxBuilder
-        let rpc_client_instance = RpcClient::new(rpc_url.to_string());
-        let recent_blockhash = rpc_client_instance.get_latest_blockhash().await?;
+        let rpc_client_instance = self.submission_client(rpc_url);
+        let recent_blockhash = self
+            .before_deadline(deadline, async { Ok(rpc_client_instance.get_latest_blockhash().await?) })
+            .await?;
 
-        let tx = self
+        let message = self
             .flash_loan_builder
-            .build_transaction(
+            .build_message(
                 opp,
                 amount_atoms,
                 &input_mint,
@@ -654,38 +1578,75 @@ impl Executor {
                 recent_blockhash,
             )
             .map_err(|e| anyhow!("Failed to build flash loan tx: {}", e))?;
-
-        // 8. Simulate transaction before submission
-        if submit {
-            debug!("🔍 Simulating flash loan transaction...");
-            let sim_result = rpc_client_instance.simulate_transaction(&tx).await?;
-
-            if let Some(err) = sim_result.value.err {
-                return Err(anyhow!(
-                    "Flash loan simulation failed: {:?}. Logs: {:?}",
-                    err,
-                    sim_result.value.logs.unwrap_or_default()
-                ));
+        let tx = unsigned_transaction(message);
+        self.record_build(deadline);
+
+        // 8. Simulate transaction before submission and require a positive
+        //    net balance change for the payer
+        let mut simulated = None;
+        if stage != ExecutionStage::Build {
+            let route = ExecutionPlan::quote_route(&quote);
+            if submit {
+                if let Err(refused) = self.check_max_loss(worst.as_ref()) {
+                    return Ok(Self::max_loss_result(opp, &refused));
+                }
+                let leg = (opp, amount_usd, worst.map(|w| w.loss_usd));
+                if let Err(veto) = self
+                    .check_compliance(ExecutionMode::FlashLoan, &[leg], &[route], &tx.message)
+                    .await
+                {
+                    return Ok(Self::compliance_veto_result(opp, &veto));
+                }
             }
 
-            let compute_units = sim_result.value.units_consumed.unwrap_or(0);
-            if compute_units > 1_400_000 {
+            debug!("🔍 Simulating flash loan transaction...");
+            let payer = Pubkey::from_str(&wallet.pubkey())
+                .map_err(|e| anyhow!("Invalid wallet pubkey: {}", e))?;
+            let valuations = self.pair_valuations(opp)?;
+            self.enter_stage(opp.id, FlightStage::Simulating);
+            let simulation = self
+                .simulate_with_profit(&rpc_client_instance, &tx, &payer, &valuations)
+                .await
+                .map_err(|e| anyhow!("Flash loan {}", e))?;
+            self.trace_simulation(opp.id, &simulation.logs);
+
+            if simulation.compute_units > 1_400_000 {
                 return Err(anyhow!(
                     "Compute units {} exceed limit 1,400,000",
-                    compute_units
+                    simulation.compute_units
                 ));
             }
 
             info!(
-                "✅ Simulation passed (compute units: {})",
-                compute_units
+                "✅ Simulation passed (compute units: {}, simulated profit: {} {})",
+                simulation.compute_units, simulation.profit, opp.pair.quote
             );
+            if !submit {
+                return Ok(Self::simulated_result(opp, simulation.profit));
+            }
+            self.spending_guard
+                .authorize(&tx.message, &payer, &simulation.deltas)?;
+            simulated = Some(simulation.profit);
         }
 
         // 9. Submit or simulate
         let signature = if submit {
-            let client = RpcClient::new(rpc_url.to_string());
+            if let Some(quarantine) = &self.quarantine {
+                quarantine.ensure_signing()?;
+            }
+            let tx = self
+                .flash_loan_builder
+                .sign(tx.message)
+                .map_err(|e| anyhow!("Failed to sign flash loan tx: {}", e))?;
+            self.note_signed(&tx);
+            let client = self.submission_client(rpc_url);
+            let _permit = self.submission_permit(&tx.message).await;
+            // Sent and confirmed in one call: the signature is known at once
+            self.enter_stage(opp.id, FlightStage::Submitted);
+            self.record_signature(opp.id, &tx.signatures[0].to_string());
+            self.enter_stage(opp.id, FlightStage::Confirming);
             let sig = client.send_and_confirm_transaction(&tx).await?;
+            self.note_confirmed(&sig);
             info!("✅ Flash loan transaction confirmed: {}", sig);
             sig.to_string()
         } else {
@@ -700,9 +1661,765 @@ impl Executor {
             actual_profit: opp.estimated_profit_usd.unwrap_or(Decimal::ZERO),
             executed_at: chrono::Utc::now(),
             error: None,
+            simulated_profit: simulated,
+            failure_kind: None,
+        })
+    }
+
+    /// Result of a trade built and simulated by [`Self::preflight`]
+    fn simulated_result(opp: &ArbitrageOpportunity, profit: Decimal) -> TradeResult {
+        TradeResult {
+            opportunity_id: opp.id,
+            signature: None,
+            success: true,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: None,
+            simulated_profit: Some(profit),
+            failure_kind: None,
+        }
+    }
+
+    /// Failed result for a quote whose route does not match the opportunity
+    fn route_mismatch_result(opp: &ArbitrageOpportunity, mismatch: &RouteMismatch) -> TradeResult {
+        warn!(
+            "🔀 Refusing {} {} → {}: {}",
+            opp.pair, opp.buy_dex, opp.sell_dex, mismatch
+        );
+        TradeResult {
+            opportunity_id: opp.id,
+            signature: None,
+            success: false,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: Some(format!("Route mismatch: {}", mismatch)),
+            simulated_profit: None,
+            failure_kind: Some(FailureKind::RouteMismatch),
+        }
+    }
+
+    /// Run the compliance hooks on a built transaction before it is signed
+    async fn check_compliance(
+        &self,
+        mode: ExecutionMode,
+        legs: &[(&ArbitrageOpportunity, Decimal, Option<Decimal>)],
+        routes: &[(Vec<String>, Vec<String>)],
+        message: &VersionedMessage,
+    ) -> std::result::Result<(), ComplianceVeto> {
+        let plan_legs = legs
+            .iter()
+            .map(|(opp, size, worst_case_loss_usd)| PlanLeg {
+                opportunity_id: opp.id,
+                pair: opp.pair.symbol(),
+                buy_dex: opp.buy_dex.to_string(),
+                sell_dex: opp.sell_dex.to_string(),
+                size_usd: *size,
+                worst_case_loss_usd: *worst_case_loss_usd,
+            })
+            .collect();
+        let pair_tokens: Vec<&str> = legs
+            .iter()
+            .flat_map(|(opp, _, _)| [opp.pair.base.as_str(), opp.pair.quote.as_str()])
+            .collect();
+        self.check_plan(mode, plan_legs, &pair_tokens, routes, message).await
+    }
+
+    /// Run the compliance hooks on `legs`, which trade `tokens` (symbols)
+    /// along `routes`
+    async fn check_plan(
+        &self,
+        mode: ExecutionMode,
+        legs: Vec<PlanLeg>,
+        tokens: &[&str],
+        routes: &[(Vec<String>, Vec<String>)],
+        message: &VersionedMessage,
+    ) -> std::result::Result<(), ComplianceVeto> {
+        let Some(gate) = self.compliance.as_ref().filter(|gate| !gate.is_empty()) else {
+            return Ok(());
+        };
+
+        let mut mints: Vec<String> = Vec::new();
+        let mut venues: Vec<String> = Vec::new();
+        let pair_mints = tokens
+            .iter()
+            .filter_map(|token| self.token_registry.mint(token).map(str::to_string));
+        let route_mints = routes.iter().flat_map(|(mints, _)| mints.iter().cloned());
+        for mint in pair_mints.chain(route_mints) {
+            if !mints.contains(&mint) {
+                mints.push(mint);
+            }
+        }
+        for venue in routes.iter().flat_map(|(_, venues)| venues) {
+            if !venues.contains(venue) {
+                venues.push(venue.clone());
+            }
+        }
+
+        let plan = ExecutionPlan {
+            mode,
+            notional_usd: legs.iter().map(|leg| leg.size_usd).sum(),
+            legs,
+            tokens: mints
+                .into_iter()
+                .map(|mint| PlanToken {
+                    symbol: self.token_registry.get(&mint).map(|info| info.symbol.clone()),
+                    mint,
+                })
+                .collect(),
+            venues,
+            programs: ExecutionPlan::message_programs(message),
+        };
+        gate.check(&plan).await.inspect_err(|veto| {
+            let pairs: Vec<&str> = plan.legs.iter().map(|leg| leg.pair.as_str()).collect();
+            warn!("🛑 {} (${} {})", veto, plan.notional_usd.round_dp(2), pairs.join(", "));
+        })
+    }
+
+    /// Failed result for a trade a compliance hook vetoed
+    fn max_loss_result(opp: &ArbitrageOpportunity, refused: &MaxLossExceeded) -> TradeResult {
+        warn!("🛑 Trade {} refused: {}", opp.id, refused);
+        TradeResult {
+            opportunity_id: opp.id,
+            signature: None,
+            success: false,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: Some(refused.to_string()),
+            simulated_profit: None,
+            failure_kind: Some(FailureKind::MaxLossExceeded),
+        }
+    }
+
+    fn compliance_veto_result(opp: &ArbitrageOpportunity, veto: &ComplianceVeto) -> TradeResult {
+        TradeResult {
+            opportunity_id: opp.id,
+            signature: None,
+            success: false,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: Some(veto.to_string()),
+            simulated_profit: None,
+            failure_kind: Some(FailureKind::ComplianceVeto),
+        }
+    }
+
+    /// Valuations (in USD) for the payer balances touched by `opp`
+    fn pair_valuations(&self, opp: &ArbitrageOpportunity) -> Result<Vec<MintValuation>> {
+        self.valuations_at(opp, opp.buy_price)
+    }
+
+    /// Quote token at its USD price, base token at `base_price` quote tokens
+    fn valuations_at(
+        &self,
+        opp: &ArbitrageOpportunity,
+        base_price: Decimal,
+    ) -> Result<Vec<MintValuation>> {
+        let token = |symbol: &str| {
+            self.token_registry
+                .get(symbol)
+                .ok_or_else(|| anyhow!("Unknown token: {}", symbol))
+                .and_then(|info| Ok((Pubkey::from_str(&info.mint)?, info.decimals)))
+        };
+        let (base_mint, base_decimals) = token(&opp.pair.base)?;
+        let (quote_mint, quote_decimals) = token(&opp.pair.quote)?;
+        let quote_usd = self.usd_price(&opp.pair.quote)?;
+
+        Ok(vec![
+            MintValuation {
+                mint: quote_mint,
+                decimals: quote_decimals,
+                price: quote_usd,
+            },
+            MintValuation {
+                mint: base_mint,
+                decimals: base_decimals,
+                price: base_price * quote_usd,
+            },
+        ])
+    }
+
+    /// Simulate `tx` and value the payer's net balance change.
+    ///
+    /// The payer's associated token accounts for every valued mint (plus its
+    /// SOL balance when SOL is valued) are read before simulating, and the RPC
+    /// returns the same accounts as they would be afterwards. Fails if the
+    /// simulation errors, the change cannot be valued, or it is not positive.
+    async fn simulate_with_profit(
+        &self,
+        rpc_client: &RpcClient,
+        tx: &VersionedTransaction,
+        payer: &Pubkey,
+        valuations: &[MintValuation],
+    ) -> Result<SimulationSummary> {
+        let native_mint = spl_token::native_mint::id();
+        let mut addresses = Vec::new();
+        if valuations.iter().any(|v| v.mint == native_mint) {
+            addresses.push(*payer);
+        }
+        for valuation in valuations {
+            let ata = get_associated_token_address(payer, &valuation.mint);
+            if !addresses.contains(&ata) {
+                addresses.push(ata);
+            }
+        }
+        // Also observe everything the spending guard caps, so one simulation
+        // serves both the profit check and the guard
+        for address in self.spending_guard.watched_accounts(payer) {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+
+        let (compute_units, deltas, logs) = self
+            .simulate_balances(rpc_client, tx, payer, &addresses)
+            .await?;
+        debug!("Simulated payer balance deltas: {:?}", deltas);
+
+        // Only valued mints count toward profit; the spending guard checks
+        // outflows in the others
+        let valued: Vec<BalanceDelta> = deltas
+            .iter()
+            .filter(|d| valuations.iter().any(|v| v.mint == d.mint))
+            .cloned()
+            .collect();
+        let profit = simulated_profit(&valued, valuations)
+            .ok_or_else(|| anyhow!("simulation changed balances of an unvalued mint"))?;
+        if profit <= Decimal::ZERO {
+            return Err(anyhow!(
+                "simulation shows no profit (net balance change {})",
+                profit
+            ));
+        }
+
+        Ok(SimulationSummary {
+            compute_units,
+            profit,
+            deltas,
+            logs,
         })
     }
 
+    /// Simulate `tx` and diff the payer's balances in `addresses` before and
+    /// after. Returns the compute units used, the per-mint deltas and the
+    /// program logs.
+    async fn simulate_balances(
+        &self,
+        rpc_client: &RpcClient,
+        tx: &VersionedTransaction,
+        payer: &Pubkey,
+        addresses: &[Pubkey],
+    ) -> Result<(u64, Vec<BalanceDelta>, Vec<String>)> {
+
+        let to_state = |account: Account| AccountState {
+            lamports: account.lamports,
+            data: account.data,
+        };
+        let pre: Vec<Option<AccountState>> = rpc_client
+            .get_multiple_accounts(addresses)
+            .await?
+            .into_iter()
+            .map(|a| a.map(to_state))
+            .collect();
+
+        let config = RpcSimulateTransactionConfig {
+            commitment: Some(rpc_client.commitment()),
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            }),
+            ..Default::default()
+        };
+        if let Some(limiter) = &self.rpc_rate_limiter {
+            limiter.acquire().await;
+        }
+        let started = std::time::Instant::now();
+        let result = rpc_client.simulate_transaction_with_config(tx, config).await;
+        if let Some(limiter) = &self.rpc_rate_limiter {
+            let throttled = result.as_ref().err().is_some_and(|e| is_throttled(e));
+            limiter.record_response(started.elapsed(), throttled);
+        }
+        let sim_result = result?.value;
+
+        if let Some(err) = sim_result.err {
+            return Err(anyhow!(
+                "simulation failed: {:?}. Logs: {:?}",
+                err,
+                sim_result.logs.unwrap_or_default()
+            ));
+        }
+
+        let post: Vec<Option<AccountState>> = sim_result
+            .accounts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|a| a.and_then(|ui| ui.decode::<Account>()).map(to_state))
+            .collect();
+
+        let deltas = balance_deltas(payer, addresses, &pre, &post);
+        Ok((
+            sim_result.units_consumed.unwrap_or(0),
+            deltas,
+            sim_result.logs.unwrap_or_default(),
+        ))
+    }
+
+    /// Each leg's share (in lamports) of the fee for a batch transaction
+    pub fn estimate_batch_fee(&self, legs: &[BatchLeg], batch_config: &BatchConfig) -> Vec<u64> {
+        let total = transaction_fee_lamports(
+            batch_config.compute_unit_limit(legs.len()),
+            self.priority_fee(),
+        );
+        let sizes: Vec<Decimal> = legs.iter().map(|l| l.size).collect();
+        split_fee(total, &sizes)
+    }
+
+    /// Execute several small opportunities as one transaction.
+    ///
+    /// Each leg is quoted and converted to Jupiter swap instructions, then all
+    /// legs share a single compute budget and priority fee. The batch is
+    /// rejected if it would not fit in one packet.
+    pub async fn execute_batch(
+        &self,
+        wallet: &Wallet,
+        legs: &[BatchLeg],
+        batch_config: &BatchConfig,
+        submit: bool,
+        rpc_url: &str,
+    ) -> Result<BatchExecution> {
+        let payer = Pubkey::from_str(&wallet.pubkey())
+            .map_err(|e| anyhow!("Invalid wallet pubkey: {}", e))?;
+        let deadline = BuildDeadline::start(self.config.build_deadline);
+
+        let compute_unit_limit = batch_config.compute_unit_limit(legs.len());
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee()),
+        ];
+        let mut table_addresses: Vec<Pubkey> = Vec::new();
+        let mut routes = Vec::with_capacity(legs.len());
+        let mut worst_cases = Vec::with_capacity(legs.len());
+        let enter_stage = |stage| {
+            for leg in legs {
+                self.enter_stage(leg.opportunity.id, stage);
+            }
+        };
+        enter_stage(FlightStage::Quoting);
+
+        for leg in legs {
+            let opp = &leg.opportunity;
+            let input_mint = self
+                .token_registry
+                .mint(&opp.pair.quote)
+                .ok_or_else(|| anyhow!("Unknown quote token: {}", opp.pair.quote))?;
+            let output_mint = self
+                .token_registry
+                .mint(&opp.pair.base)
+                .ok_or_else(|| anyhow!("Unknown base token: {}", opp.pair.base))?;
+            let amount_atoms = self
+                .quote_atoms(&opp.pair, leg.size)
+                .map_err(|e| anyhow!("Cannot size batch leg: {}", e))?;
+
+            let slippage_bps = self.slippage_bps_for(&opp.pair, leg.size);
+            let quote = self
+                .before_deadline(
+                    &deadline,
+                    self.get_quote(input_mint, output_mint, amount_atoms, slippage_bps),
+                )
+                .await?;
+            self.trace_quote(opp, &quote);
+            routes.push(ExecutionPlan::quote_route(&quote));
+            worst_cases.push(self.worst_case(opp, &quote, leg.size));
+            let resp = self
+                .before_deadline(&deadline, self.get_swap_instructions(&wallet.pubkey(), &quote))
+                .await?;
+
+            for jup_ix in &resp.setup_instructions {
+                instructions.push(Self::convert_jupiter_instruction(jup_ix)?);
+            }
+            instructions.push(Self::convert_jupiter_instruction(&resp.swap_instruction)?);
+            if let Some(cleanup) = &resp.cleanup_instruction {
+                instructions.push(Self::convert_jupiter_instruction(cleanup)?);
+            }
+
+            for addr in &resp.address_lookup_table_addresses {
+                if let Ok(pubkey) = Pubkey::from_str(addr) {
+                    if !table_addresses.contains(&pubkey) {
+                        table_addresses.push(pubkey);
+                    }
+                }
+            }
+        }
+
+        enter_stage(FlightStage::Building);
+        let lookup_tables = match (&self.alt_manager, table_addresses.is_empty()) {
+            (_, true) => vec![],
+            (Some(alt_manager), false) => {
+                self.before_deadline(&deadline, alt_manager.get_tables(&table_addresses))
+                    .await?
+            }
+            (None, false) => {
+                warn!("ALTs returned by Jupiter but AltManager not configured; proceeding without");
+                vec![]
+            }
+        };
+
+        let rpc_client = self.submission_client(rpc_url);
+        let recent_blockhash = self
+            .before_deadline(&deadline, async { Ok(rpc_client.get_latest_blockhash().await?) })
+            .await?;
+        let message = v0::Message::try_compile(&payer, &instructions, &lookup_tables, recent_blockhash)
+            .map_err(|e| anyhow!("Failed to compile batch message: {}", e))?;
+        let message = VersionedMessage::V0(message);
+        self.record_build(&deadline);
+
+        // One signature (1 byte length + 64 bytes) plus the message
+        let tx_size = 1 + 64 + bincode::serialize(&message)?.len();
+        if tx_size > MAX_TX_BYTES {
+            return Err(anyhow!(
+                "Batch of {} legs is {} bytes, exceeds {} byte limit",
+                legs.len(),
+                tx_size,
+                MAX_TX_BYTES
+            ));
+        }
+
+        let fee_shares = self.estimate_batch_fee(legs, batch_config);
+        let fee_lamports = fee_shares.iter().sum();
+
+        let mut simulated = None;
+        let signature = if submit {
+            let signer = wallet
+                .signer()
+                .ok_or_else(|| anyhow!("No keypair available for signing"))?;
+            let tx = unsigned_transaction(message);
+            for worst in &worst_cases {
+                self.check_max_loss(worst.as_ref())?;
+            }
+            let plan_legs: Vec<_> = legs
+                .iter()
+                .zip(&worst_cases)
+                .map(|(leg, worst)| (&leg.opportunity, leg.size, worst.map(|w| w.loss_usd)))
+                .collect();
+            self.check_compliance(ExecutionMode::Standard, &plan_legs, &routes, &tx.message)
+                .await?;
+
+            let mut valuations: Vec<MintValuation> = Vec::new();
+            for leg in legs {
+                for valuation in self.pair_valuations(&leg.opportunity)? {
+                    if !valuations.iter().any(|v| v.mint == valuation.mint) {
+                        valuations.push(valuation);
+                    }
+                }
+            }
+            enter_stage(FlightStage::Simulating);
+            let simulation = self
+                .simulate_with_profit(&rpc_client, &tx, &payer, &valuations)
+                .await
+                .map_err(|e| anyhow!("Batch {}", e))?;
+            for leg in legs {
+                self.trace_simulation(leg.opportunity.id, &simulation.logs);
+            }
+            info!(
+                "✅ Batch simulation passed (compute units: {}, simulated profit: {})",
+                simulation.compute_units, simulation.profit
+            );
+            simulated = Some(simulation.profit);
+
+            self.spending_guard
+                .authorize(&tx.message, &payer, &simulation.deltas)?;
+            let tx = self.sign(tx.message, signer)?;
+
+            let _permit = self.submission_permit(&tx.message).await;
+            if let Some(limiter) = &self.rpc_rate_limiter {
+                limiter.acquire().await;
+            }
+            enter_stage(FlightStage::Submitted);
+            for leg in legs {
+                self.record_signature(leg.opportunity.id, &tx.signatures[0].to_string());
+            }
+            enter_stage(FlightStage::Confirming);
+            let started = std::time::Instant::now();
+            let result = rpc_client.send_and_confirm_transaction(&tx).await;
+            if let Some(limiter) = &self.rpc_rate_limiter {
+                let throttled = result.as_ref().err().is_some_and(|e| is_throttled(e));
+                limiter.record_response(started.elapsed(), throttled);
+            }
+            let sig = result?;
+            self.note_confirmed(&sig);
+            info!("✅ Batch transaction confirmed ({} legs): {}", legs.len(), sig);
+            sig.to_string()
+        } else {
+            info!(
+                "📝 [SIMULATION] Batch of {} legs ({} bytes, {} CU) would be submitted here.",
+                legs.len(),
+                tx_size,
+                compute_unit_limit
+            );
+            "simulated_batch_tx".to_string()
+        };
+
+        Ok(BatchExecution {
+            signature,
+            fee_lamports,
+            fee_shares,
+            simulated_profit: simulated,
+        })
+    }
+
+    /// Execute every hop of `path` in one transaction, starting with
+    /// `amount` of its start token (worth `size_usd`).
+    ///
+    /// Each hop is quoted with the previous hop's minimum output as its input,
+    /// so the swaps chain without leftovers the next hop cannot spend. With
+    /// `flash_loan` the start token is borrowed and repaid around the swaps.
+    /// Fails before anything is signed unless the final quote returns more
+    /// than the amount put in (plus the flash loan fee) and the simulation
+    /// shows a profit.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_path(
+        &self,
+        wallet: &Wallet,
+        path: &TradingPath,
+        amount: Decimal,
+        size_usd: Decimal,
+        flash_loan: bool,
+        path_config: &PathConfig,
+        submit: bool,
+        rpc_url: &str,
+    ) -> Result<PathExecution> {
+        let label = paths::path_label(path);
+        let start = paths::start_token(path).ok_or_else(|| anyhow!("Empty path"))?;
+        let payer = Pubkey::from_str(&wallet.pubkey())
+            .map_err(|e| anyhow!("Invalid wallet pubkey: {}", e))?;
+        let start_mint = self
+            .token_registry
+            .mint(start)
+            .ok_or_else(|| anyhow!("Unknown token: {}", start))
+            .and_then(|mint| Ok(Pubkey::from_str(mint)?))?;
+        let amount_atoms = self
+            .token_registry
+            .to_atoms(start, amount, Rounding::Down)
+            .map_err(|e| anyhow!("Cannot size path: {}", e))?;
+        if amount_atoms == 0 {
+            return Err(anyhow!("Invalid path amount: zero atoms"));
+        }
+        let deadline = BuildDeadline::start(self.config.build_deadline);
+        let atoms = |quote: &serde_json::Value, field: &str| -> Result<u64> {
+            quote
+                .get(field)
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| anyhow!("Jupiter quote has no {}", field))
+        };
+
+        let mut swap_instructions = Vec::new();
+        let mut table_addresses: Vec<Pubkey> = Vec::new();
+        let mut routes = Vec::with_capacity(path.edges.len());
+        let mut hop_in = amount_atoms;
+        let mut returned = 0;
+        for edge in &path.edges {
+            let input_mint = self
+                .token_registry
+                .mint(&edge.from_token)
+                .ok_or_else(|| anyhow!("Unknown token: {}", edge.from_token))?;
+            let output_mint = self
+                .token_registry
+                .mint(&edge.to_token)
+                .ok_or_else(|| anyhow!("Unknown token: {}", edge.to_token))?;
+            let pair = TokenPair::new(&edge.from_token, &edge.to_token);
+            let slippage_bps = self.slippage_bps_for(&pair, size_usd);
+            let quote = self
+                .before_deadline(
+                    &deadline,
+                    self.get_quote(input_mint, output_mint, hop_in, slippage_bps),
+                )
+                .await?;
+            returned = atoms(&quote, "outAmount")?;
+            // The next hop spends only what this one is sure to return
+            hop_in = atoms(&quote, "otherAmountThreshold")?;
+            routes.push(ExecutionPlan::quote_route(&quote));
+            let resp = self
+                .before_deadline(&deadline, self.get_swap_instructions(&wallet.pubkey(), &quote))
+                .await?;
+
+            for jup_ix in &resp.setup_instructions {
+                swap_instructions.push(Self::convert_jupiter_instruction(jup_ix)?);
+            }
+            swap_instructions.push(Self::convert_jupiter_instruction(&resp.swap_instruction)?);
+            if let Some(cleanup) = &resp.cleanup_instruction {
+                swap_instructions.push(Self::convert_jupiter_instruction(cleanup)?);
+            }
+            for addr in &resp.address_lookup_table_addresses {
+                if let Ok(pubkey) = Pubkey::from_str(addr) {
+                    if !table_addresses.contains(&pubkey) {
+                        table_addresses.push(pubkey);
+                    }
+                }
+            }
+        }
+
+        let owed = if flash_loan {
+            self.flash_loan_builder.repay_amount(amount_atoms, &start_mint)
+        } else {
+            amount_atoms
+        };
+        if returned <= owed {
+            return Err(anyhow!(
+                "Path {} no longer profitable: quoted {} atoms back for {}",
+                label,
+                returned,
+                owed
+            ));
+        }
+
+        let lookup_tables = match (&self.alt_manager, table_addresses.is_empty()) {
+            (_, true) => vec![],
+            (Some(alt_manager), false) => {
+                self.before_deadline(&deadline, alt_manager.get_tables(&table_addresses))
+                    .await?
+            }
+            (None, false) => {
+                warn!("ALTs returned by Jupiter but AltManager not configured; proceeding without");
+                vec![]
+            }
+        };
+
+        let rpc_client = self.submission_client(rpc_url);
+        let recent_blockhash = self
+            .before_deadline(&deadline, async { Ok(rpc_client.get_latest_blockhash().await?) })
+            .await?;
+        let (message, compute_unit_limit) = if flash_loan {
+            let message = self
+                .flash_loan_builder
+                .build_loan_message(
+                    amount_atoms,
+                    &start_mint,
+                    swap_instructions,
+                    &lookup_tables,
+                    recent_blockhash,
+                )
+                .map_err(|e| anyhow!("Failed to build flash loan path message: {}", e))?;
+            (message, MAX_TX_COMPUTE_UNITS)
+        } else {
+            let compute_unit_limit = path_config.compute_unit_limit(path.edges.len());
+            let mut instructions = vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
+                ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee()),
+            ];
+            instructions.extend(swap_instructions);
+            let message =
+                v0::Message::try_compile(&payer, &instructions, &lookup_tables, recent_blockhash)
+                    .map_err(|e| anyhow!("Failed to compile path message: {}", e))?;
+            (VersionedMessage::V0(message), compute_unit_limit)
+        };
+        self.record_build(&deadline);
+
+        // One signature (1 byte length + 64 bytes) plus the message
+        let tx_size = 1 + 64 + bincode::serialize(&message)?.len();
+        if tx_size > MAX_TX_BYTES {
+            return Err(anyhow!(
+                "Path {} is {} bytes, exceeds {} byte limit",
+                label,
+                tx_size,
+                MAX_TX_BYTES
+            ));
+        }
+        let fee_lamports = transaction_fee_lamports(compute_unit_limit, self.priority_fee());
+
+        let mut simulated = None;
+        let signature = if submit {
+            let tx = unsigned_transaction(message);
+            let mode = if flash_loan { ExecutionMode::FlashLoan } else { ExecutionMode::Standard };
+            let plan_leg = PlanLeg {
+                opportunity_id: Uuid::new_v4(),
+                pair: label.clone(),
+                buy_dex: path.edges[0].dex.to_string(),
+                sell_dex: path.edges[path.edges.len() - 1].dex.to_string(),
+                size_usd,
+                worst_case_loss_usd: None,
+            };
+            let tokens: Vec<&str> = path.edges.iter().map(|e| e.from_token.as_str()).collect();
+            self.check_plan(mode, vec![plan_leg], &tokens, &routes, &tx.message)
+                .await?;
+
+            let valuations = self.path_valuations(path)?;
+            let simulation = self
+                .simulate_with_profit(&rpc_client, &tx, &payer, &valuations)
+                .await
+                .map_err(|e| anyhow!("Path {} {}", label, e))?;
+            info!(
+                "✅ Path simulation passed (compute units: {}, simulated profit: {} {})",
+                simulation.compute_units, simulation.profit, start
+            );
+            simulated = Some(simulation.profit);
+            self.spending_guard
+                .authorize(&tx.message, &payer, &simulation.deltas)?;
+
+            let tx = if flash_loan {
+                if let Some(quarantine) = &self.quarantine {
+                    quarantine.ensure_signing()?;
+                }
+                let tx = self
+                    .flash_loan_builder
+                    .sign(tx.message)
+                    .map_err(|e| anyhow!("Failed to sign flash loan path: {}", e))?;
+                self.note_signed(&tx);
+                tx
+            } else {
+                let signer = wallet
+                    .signer()
+                    .ok_or_else(|| anyhow!("No keypair available for signing"))?;
+                self.sign(tx.message, signer)?
+            };
+
+            let _permit = self.submission_permit(&tx.message).await;
+            if let Some(limiter) = &self.rpc_rate_limiter {
+                limiter.acquire().await;
+            }
+            let started = std::time::Instant::now();
+            let result = rpc_client.send_and_confirm_transaction(&tx).await;
+            if let Some(limiter) = &self.rpc_rate_limiter {
+                let throttled = result.as_ref().err().is_some_and(|e| is_throttled(e));
+                limiter.record_response(started.elapsed(), throttled);
+            }
+            let sig = result?;
+            self.note_confirmed(&sig);
+            info!("✅ Path transaction confirmed ({}): {}", label, sig);
+            sig.to_string()
+        } else {
+            info!(
+                "📝 [SIMULATION] Path {} ({} bytes, {} CU) would be submitted here.",
+                label, tx_size, compute_unit_limit
+            );
+            "simulated_path_tx".to_string()
+        };
+
+        Ok(PathExecution {
+            signature,
+            hops: path.edges.len(),
+            fee_lamports,
+            simulated_profit: simulated,
+        })
+    }
+
+    /// Valuations (in the start token) for the payer balances a path touches
+    fn path_valuations(&self, path: &TradingPath) -> Result<Vec<MintValuation>> {
+        paths::hop_prices(path)
+            .into_iter()
+            .map(|(symbol, price)| {
+                let info = self
+                    .token_registry
+                    .get(&symbol)
+                    .ok_or_else(|| anyhow!("Unknown token: {}", symbol))?;
+                Ok(MintValuation {
+                    mint: Pubkey::from_str(&info.mint)?,
+                    decimals: info.decimals,
+                    price,
+                })
+            })
+            .collect()
+    }
+
     /// Call Jupiter's `/swap-instructions` endpoint to get structured swap instructions.
     ///
     /// This returns individual instructions (setup, swap, cleanup) instead of a
@@ -715,15 +2432,12 @@ impl Executor {
         let req = SwapInstructionsRequest {
             user_public_key: user_pubkey.to_string(),
             quote_response: quote.clone(),
-            wrap_and_unwrap_sol: true,
+            wrap_and_unwrap_sol: self.config.wrap_and_unwrap_sol,
             compute_unit_price_micro_lamports: None, // Handled by FlashLoanTxBuilder
         };
 
         let response = self
-            .client
-            .post(format!("{}/swap-instructions", JUPITER_API_URL))
-            .json(&req)
-            .send()
+            .send_jupiter(self.client.post(format!("{}/swap-instructions", self.jupiter_api_url)).json(&req))
             .await?;
 
         if !response.status().is_success() {
@@ -771,6 +2485,23 @@ impl Executor {
     }
 }
 
+/// A transaction with placeholder signatures, for simulation before signing
+/// Whether an RPC error is an HTTP 429 rate limit response
+fn is_throttled(error: &dyn std::fmt::Display) -> bool {
+    let text = format!("{:#}", error);
+    text.contains("429") || text.contains("Too Many Requests")
+}
+
+fn unsigned_transaction(message: VersionedMessage) -> VersionedTransaction {
+    VersionedTransaction {
+        signatures: vec![
+            solana_sdk::signature::Signature::default();
+            message.header().num_required_signatures as usize
+        ],
+        message,
+    }
+}
+
 impl Default for Executor {
     fn default() -> Self {
         Self::new()
@@ -780,6 +2511,30 @@ impl Default for Executor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_arb_core::DexType;
+
+    #[test]
+    fn test_lst_pairs_are_sized_in_sol() {
+        let executor = Executor::new();
+        let lst = TokenPair::new("mSOL", "SOL");
+        let size_usd = Decimal::from(1000);
+
+        // A SOL-quoted trade cannot be sized before SOL has a USD price
+        assert!(executor.quote_atoms(&lst, size_usd).is_err());
+
+        executor.record_usd_prices(&[PriceData::new(
+            DexType::Orca,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::from(149),
+            Decimal::from(151),
+        )]);
+        // $1,000 at $150 per SOL, not 1,000 SOL
+        assert_eq!(executor.quote_atoms(&lst, size_usd).unwrap(), 6_666_666_666);
+        assert_eq!(
+            executor.quote_atoms(&TokenPair::new("SOL", "USDC"), size_usd).unwrap(),
+            1_000_000_000
+        );
+    }
 
     #[test]
     fn test_convert_jupiter_instruction_valid() {
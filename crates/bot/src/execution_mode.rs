@@ -0,0 +1,197 @@
+//! Execution Mode Selection
+//!
+//! Decides whether a trade runs on the wallet's own capital or is funded by
+//! a flash loan. The rules, checked in order:
+//!
+//! 1. Trades of at most `FLASH_LOAN_THRESHOLD_USD` ($1000) run standard.
+//! 2. Without the `flash_loans` feature flag (`ENABLE_FLASH_LOANS`) every
+//!    trade runs standard.
+//! 3. The flash loan builder must have a reserve for the base token.
+//! 4. When the reserve's live liquidity is known, it must be able to lend
+//!    the whole size. Unknown liquidity does not block a flash loan.
+//!
+//! Whatever passes all four is flash-loan funded.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::Uuid;
+
+use crate::execution::FLASH_LOAN_THRESHOLD_USD;
+
+/// How a trade is funded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    /// Swaps on the wallet's own capital
+    Standard,
+    /// Borrow, swap and repay in one transaction
+    FlashLoan,
+}
+
+/// Which rule decided the mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModeReason {
+    BelowThreshold,
+    FlashLoansDisabled,
+    NoProvider,
+    ReserveTooShallow,
+    Eligible,
+}
+
+/// What the selection is based on
+#[derive(Debug, Clone, Copy)]
+pub struct ModeInputs {
+    pub size_usd: Decimal,
+    /// The `flash_loans` feature flag
+    pub flash_loans_enabled: bool,
+    /// Whether the flash loan builder has a reserve for the base token
+    pub provider_available: bool,
+    /// USD the reserve can lend within the configured reserve fraction,
+    /// `None` until its liquidity has been read
+    pub reserve_capacity_usd: Option<Decimal>,
+}
+
+/// A selected mode and why
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ModeSelection {
+    pub mode: ExecutionMode,
+    pub reason: ModeReason,
+}
+
+/// Mode selected for one trade, as shown on `/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeModeRecord {
+    pub opportunity_id: Uuid,
+    pub pair: String,
+    pub size_usd: Decimal,
+    pub selected_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub selection: ModeSelection,
+}
+
+/// Applies the execution mode rules
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutionModeSelector {
+    threshold_usd: Decimal,
+}
+
+impl Default for ExecutionModeSelector {
+    fn default() -> Self {
+        Self::new(Decimal::from(FLASH_LOAN_THRESHOLD_USD))
+    }
+}
+
+impl ExecutionModeSelector {
+    pub fn new(threshold_usd: Decimal) -> Self {
+        Self { threshold_usd }
+    }
+
+    pub fn threshold_usd(&self) -> Decimal {
+        self.threshold_usd
+    }
+
+    pub fn select(&self, inputs: &ModeInputs) -> ModeSelection {
+        let reason = if inputs.size_usd <= self.threshold_usd {
+            ModeReason::BelowThreshold
+        } else if !inputs.flash_loans_enabled {
+            ModeReason::FlashLoansDisabled
+        } else if !inputs.provider_available {
+            ModeReason::NoProvider
+        } else if inputs
+            .reserve_capacity_usd
+            .is_some_and(|capacity| capacity < inputs.size_usd)
+        {
+            ModeReason::ReserveTooShallow
+        } else {
+            ModeReason::Eligible
+        };
+        let mode = if reason == ModeReason::Eligible {
+            ExecutionMode::FlashLoan
+        } else {
+            ExecutionMode::Standard
+        };
+        ModeSelection { mode, reason }
+    }
+
+    /// Cap a size that would be flash-loan funded at what the reserve can
+    /// lend. A reserve that cannot lend even the threshold brings the size
+    /// down to the threshold, where the trade runs standard instead.
+    pub fn cap_size(&self, inputs: &ModeInputs) -> Decimal {
+        match inputs.reserve_capacity_usd {
+            Some(capacity)
+                if inputs.flash_loans_enabled
+                    && inputs.size_usd > self.threshold_usd
+                    && inputs.size_usd > capacity =>
+            {
+                capacity.max(self.threshold_usd)
+            }
+            _ => inputs.size_usd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_matrix() {
+        use ExecutionMode::*;
+        use ModeReason::*;
+
+        let selector = ExecutionModeSelector::default();
+        let usd = Decimal::from;
+        // size, flag, provider, reserve capacity => mode, reason
+        let matrix = [
+            (usd(500), false, false, None, Standard, BelowThreshold),
+            (usd(500), true, true, None, Standard, BelowThreshold),
+            (usd(500), true, true, Some(usd(10_000)), Standard, BelowThreshold),
+            (usd(1000), true, true, Some(usd(10_000)), Standard, BelowThreshold),
+            (usd(1001), true, true, Some(usd(10_000)), FlashLoan, Eligible),
+            (usd(5000), false, true, Some(usd(10_000)), Standard, FlashLoansDisabled),
+            (usd(5000), false, false, None, Standard, FlashLoansDisabled),
+            (usd(5000), true, false, Some(usd(10_000)), Standard, NoProvider),
+            (usd(5000), true, false, None, Standard, NoProvider),
+            (usd(5000), true, true, None, FlashLoan, Eligible),
+            (usd(5000), true, true, Some(usd(5000)), FlashLoan, Eligible),
+            (usd(5000), true, true, Some(usd(4999)), Standard, ReserveTooShallow),
+            (usd(5000), true, true, Some(Decimal::ZERO), Standard, ReserveTooShallow),
+        ];
+        for (size, enabled, provider, capacity, mode, reason) in matrix {
+            let inputs = ModeInputs {
+                size_usd: size,
+                flash_loans_enabled: enabled,
+                provider_available: provider,
+                reserve_capacity_usd: capacity,
+            };
+            assert_eq!(
+                selector.select(&inputs),
+                ModeSelection { mode, reason },
+                "{:?}",
+                inputs
+            );
+        }
+
+        // Capping to the reserve keeps a deep enough reserve eligible and
+        // sends a shallow one back to the threshold, i.e. standard
+        let inputs = |size, capacity| ModeInputs {
+            size_usd: usd(size),
+            flash_loans_enabled: true,
+            provider_available: true,
+            reserve_capacity_usd: capacity,
+        };
+        let capped = selector.cap_size(&inputs(5000, Some(usd(3000))));
+        assert_eq!(capped, usd(3000));
+        assert_eq!(selector.select(&inputs(3000, Some(usd(3000)))).mode, FlashLoan);
+        assert_eq!(selector.cap_size(&inputs(5000, Some(usd(200)))), usd(1000));
+        assert_eq!(selector.cap_size(&inputs(5000, None)), usd(5000));
+        assert_eq!(selector.cap_size(&inputs(800, Some(usd(200)))), usd(800));
+        let disabled = ModeInputs {
+            flash_loans_enabled: false,
+            ..inputs(5000, Some(usd(200)))
+        };
+        assert_eq!(selector.cap_size(&disabled), usd(5000));
+    }
+}
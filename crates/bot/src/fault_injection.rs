@@ -0,0 +1,113 @@
+//! Dry-run Failure Injection
+//!
+//! Lets operators verify alert wiring end-to-end without real losses.
+//! Touch a flag file in the working directory (like the `.kill` switch)
+//! and the next loop tick synthesizes the failure, then removes the flag:
+//!
+//! - `.inject_circuit_breaker` - trips the circuit breaker
+//! - `.inject_daily_loss`      - publishes a daily loss limit breach
+//! - `.inject_stuck_trade`     - publishes a stuck trade
+//!
+//! Flags are ignored in live mode.
+
+use crate::BotState;
+use solana_arb_core::events::TradingEvent;
+use std::path::Path;
+use tracing::warn;
+
+/// A failure that can be synthesized in dry-run mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectedFault {
+    CircuitBreakerTrip,
+    DailyLossBreach,
+    StuckTrade,
+}
+
+impl InjectedFault {
+    pub fn all() -> &'static [InjectedFault] {
+        &[
+            InjectedFault::CircuitBreakerTrip,
+            InjectedFault::DailyLossBreach,
+            InjectedFault::StuckTrade,
+        ]
+    }
+
+    /// Flag file that triggers this fault
+    pub fn flag_file(&self) -> &'static str {
+        match self {
+            InjectedFault::CircuitBreakerTrip => ".inject_circuit_breaker",
+            InjectedFault::DailyLossBreach => ".inject_daily_loss",
+            InjectedFault::StuckTrade => ".inject_stuck_trade",
+        }
+    }
+}
+
+/// Return the faults whose flag files exist in `dir`.
+///
+/// When `consume` is set the flag files are removed so each fault fires once.
+pub fn pending_faults(dir: &Path, consume: bool) -> Vec<InjectedFault> {
+    InjectedFault::all()
+        .iter()
+        .copied()
+        .filter(|fault| {
+            let flag = dir.join(fault.flag_file());
+            if !flag.exists() {
+                return false;
+            }
+            if consume {
+                if let Err(e) = std::fs::remove_file(&flag) {
+                    warn!("Failed to remove injection flag {:?}: {}", flag, e);
+                }
+            }
+            true
+        })
+        .collect()
+}
+
+/// Synthesize a fault through the same paths a real failure would take
+pub(crate) async fn inject(state: &BotState, fault: InjectedFault) {
+    warn!("🧪 [DRY RUN] Injecting fault: {:?}", fault);
+
+    match fault {
+        InjectedFault::CircuitBreakerTrip => {
+            state
+                .risk_manager
+                .circuit_breaker
+                .force_open("INJECTED test fault")
+                .await;
+        }
+        InjectedFault::DailyLossBreach => {
+            state.event_bus.publish(TradingEvent::RiskLimitBreached {
+                limit_type: "daily_loss (INJECTED)".to_string(),
+                current: 150.0,
+                max: 100.0,
+            });
+        }
+        InjectedFault::StuckTrade => {
+            state.event_bus.publish(TradingEvent::TradeStuck {
+                id: "INJECTED".to_string(),
+                pair: "SOL/USDC".to_string(),
+                elapsed_secs: 120,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_faults_consumes_flags() {
+        let dir = std::env::temp_dir().join(format!("arb-fault-injection-{}", solana_arb_core::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".inject_daily_loss"), "").unwrap();
+
+        // Peeking leaves the flag in place
+        assert_eq!(pending_faults(&dir, false), vec![InjectedFault::DailyLossBreach]);
+        assert_eq!(pending_faults(&dir, true), vec![InjectedFault::DailyLossBreach]);
+        assert!(pending_faults(&dir, true).is_empty());
+
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
@@ -0,0 +1,305 @@
+//! Runtime Feature Flags
+//!
+//! Single place to ask "is X enabled?" instead of reading environment
+//! variables at construction time. Flags start from env defaults, are
+//! overridden by the `features` section of the dynamic config, and can be
+//! flipped at runtime through the API. Every flip is kept in an in-memory
+//! audit trail and, when configured, appended to the audit log.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_arb_core::audit_log::{AuditEvent, AuditLogger};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+pub const FLASH_LOANS: &str = "flash_loans";
+pub const JITO: &str = "jito";
+pub const WS_STREAMING: &str = "ws_streaming";
+pub const DIRECT_POOL_EXECUTION: &str = "direct_pool_execution";
+pub const BATCH_EXECUTION: &str = "batch_execution";
+pub const PATH_EXECUTION: &str = "path_execution";
+pub const PERP_HEDGING: &str = "perp_hedging";
+pub const AUTO_PAIR_LISTING: &str = "auto_pair_listing";
+
+/// Prefix for free-form experiment flags (e.g. `experiment.new_sizing`)
+pub const EXPERIMENT_PREFIX: &str = "experiment.";
+
+/// Number of flag changes kept in memory
+const AUDIT_TRAIL_LEN: usize = 100;
+
+/// Built-in flags with their env variable and default
+const KNOWN_FLAGS: &[(&str, &str, bool, &str)] = &[
+    (FLASH_LOANS, "ENABLE_FLASH_LOANS", false, "Use flash loans for large trades"),
+    (JITO, "USE_JITO", false, "Submit trades as Jito bundles"),
+    (WS_STREAMING, "ENABLE_WS_STREAMING", true, "WebSocket price streaming"),
+    (DIRECT_POOL_EXECUTION, "ENABLE_DIRECT_POOL_EXECUTION", false, "Swap directly against pools"),
+    (BATCH_EXECUTION, "BATCH_EXECUTION_ENABLED", false, "Batch small arbs into one transaction"),
+    (PATH_EXECUTION, "PATH_EXECUTION_ENABLED", false, "Trade multi-hop paths from the path finder"),
+    (PERP_HEDGING, "ENABLE_PERP_HEDGING", false, "Hedge costly spot inventory with Drift perps"),
+    (AUTO_PAIR_LISTING, "ENABLE_AUTO_PAIR_LISTING", false, "Trade discovered pairs once screened"),
+];
+
+/// Where a flag's current value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagSource {
+    Default,
+    Env,
+    Config,
+    Runtime,
+}
+
+/// Current state of a flag
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagStatus {
+    pub name: String,
+    pub enabled: bool,
+    pub source: FlagSource,
+    pub description: String,
+}
+
+/// A recorded flag flip
+#[derive(Debug, Clone, Serialize)]
+pub struct FlagChange {
+    pub timestamp: DateTime<Utc>,
+    pub flag: String,
+    pub previous: Option<bool>,
+    pub enabled: bool,
+    pub actor: String,
+}
+
+/// Shared, thread-safe feature flag registry
+pub struct FeatureFlags {
+    flags: RwLock<BTreeMap<String, FlagStatus>>,
+    trail: RwLock<VecDeque<FlagChange>>,
+    audit_logger: Option<Arc<AuditLogger>>,
+}
+
+impl FeatureFlags {
+    /// Built-in flags at their defaults, ignoring the environment
+    pub fn with_defaults() -> Self {
+        let flags = KNOWN_FLAGS
+            .iter()
+            .map(|(name, _, default, description)| {
+                (
+                    name.to_string(),
+                    FlagStatus {
+                        name: name.to_string(),
+                        enabled: *default,
+                        source: FlagSource::Default,
+                        description: description.to_string(),
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            flags: RwLock::new(flags),
+            trail: RwLock::new(VecDeque::new()),
+            audit_logger: None,
+        }
+    }
+
+    /// Built-in flags, taking defaults from their environment variables
+    pub fn from_env() -> Self {
+        let flags = Self::with_defaults();
+        {
+            let mut map = flags.flags.write().unwrap_or_else(|e| e.into_inner());
+            for (name, env_var, _, _) in KNOWN_FLAGS {
+                if let Ok(value) = std::env::var(env_var) {
+                    if let Some(status) = map.get_mut(*name) {
+                        status.enabled = value == "true" || value == "1";
+                        status.source = FlagSource::Env;
+                    }
+                }
+            }
+        }
+        flags
+    }
+
+    /// Append every flag flip to `logger`
+    pub fn with_audit_logger(mut self, logger: Arc<AuditLogger>) -> Self {
+        self.audit_logger = Some(logger);
+        self
+    }
+
+    /// Whether `name` is enabled. Unknown flags are disabled.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .map(|f| f.enabled)
+            .unwrap_or(false)
+    }
+
+    /// Snapshot of all flags, sorted by name
+    pub fn list(&self) -> Vec<FlagStatus> {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Recent flag flips, oldest first
+    pub fn audit_trail(&self) -> Vec<FlagChange> {
+        self.trail
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Change a flag at runtime.
+    ///
+    /// Only built-in flags and `experiment.*` flags can be set. Returns the
+    /// recorded change, or `None` if the value did not change.
+    pub async fn set(&self, name: &str, enabled: bool, actor: &str) -> Result<Option<FlagChange>, String> {
+        self.apply(name, enabled, actor, FlagSource::Runtime).await
+    }
+
+    /// Apply the `features` section of the dynamic config
+    pub async fn apply_config(&self, features: &HashMap<String, bool>) {
+        for (name, enabled) in features {
+            if let Err(e) = self.apply(name, *enabled, "config", FlagSource::Config).await {
+                warn!("Ignoring feature flag from config: {}", e);
+            }
+        }
+    }
+
+    async fn apply(
+        &self,
+        name: &str,
+        enabled: bool,
+        actor: &str,
+        source: FlagSource,
+    ) -> Result<Option<FlagChange>, String> {
+        let previous = {
+            let mut map = self.flags.write().unwrap_or_else(|e| e.into_inner());
+            let previous = match map.get_mut(name) {
+                Some(status) => {
+                    let previous = status.enabled;
+                    status.enabled = enabled;
+                    status.source = source;
+                    Some(previous)
+                }
+                None if name.starts_with(EXPERIMENT_PREFIX) && name.len() > EXPERIMENT_PREFIX.len() => {
+                    map.insert(
+                        name.to_string(),
+                        FlagStatus {
+                            name: name.to_string(),
+                            enabled,
+                            source,
+                            description: "Experiment".to_string(),
+                        },
+                    );
+                    None
+                }
+                None => return Err(format!("unknown feature flag '{}'", name)),
+            };
+            if previous == Some(enabled) {
+                return Ok(None);
+            }
+            previous
+        };
+
+        let change = FlagChange {
+            timestamp: Utc::now(),
+            flag: name.to_string(),
+            previous,
+            enabled,
+            actor: actor.to_string(),
+        };
+        info!(
+            "🚩 Feature flag '{}' {} by {}",
+            name,
+            if enabled { "ENABLED" } else { "DISABLED" },
+            actor
+        );
+
+        {
+            let mut trail = self.trail.write().unwrap_or_else(|e| e.into_inner());
+            if trail.len() >= AUDIT_TRAIL_LEN {
+                trail.pop_front();
+            }
+            trail.push_back(change.clone());
+        }
+
+        if let Some(logger) = &self.audit_logger {
+            let event = AuditEvent {
+                timestamp: change.timestamp.to_rfc3339(),
+                category: "CONFIG".to_string(),
+                action: "FLAG_FLIP".to_string(),
+                resource: name.to_string(),
+                result: "SUCCESS".to_string(),
+                details: serde_json::json!({
+                    "previous": previous,
+                    "enabled": enabled,
+                    "actor": actor,
+                }),
+            };
+            if let Err(e) = logger.log(event).await {
+                warn!("Failed to write flag flip to audit log: {}", e);
+            }
+        }
+
+        Ok(Some(change))
+    }
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl std::fmt::Debug for FeatureFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeatureFlags")
+            .field("flags", &self.list())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runtime_flip_is_audited() {
+        let flags = FeatureFlags::with_defaults();
+        assert!(!flags.is_enabled(FLASH_LOANS));
+
+        let change = flags.set(FLASH_LOANS, true, "api").await.unwrap().unwrap();
+        assert_eq!(change.previous, Some(false));
+        assert!(flags.is_enabled(FLASH_LOANS));
+
+        // Setting the same value is not a flip
+        assert!(flags.set(FLASH_LOANS, true, "api").await.unwrap().is_none());
+        assert_eq!(flags.audit_trail().len(), 1);
+
+        let status = flags.list().into_iter().find(|f| f.name == FLASH_LOANS).unwrap();
+        assert_eq!(status.source, FlagSource::Runtime);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_experiment_flags() {
+        let flags = FeatureFlags::with_defaults();
+        assert!(flags.set("typo", true, "api").await.is_err());
+        assert!(flags.set(EXPERIMENT_PREFIX, true, "api").await.is_err());
+        assert!(!flags.is_enabled("typo"));
+
+        flags
+            .apply_config(&HashMap::from([
+                ("experiment.new_sizing".to_string(), true),
+                (JITO.to_string(), true),
+            ]))
+            .await;
+        assert!(flags.is_enabled("experiment.new_sizing"));
+        assert!(flags.is_enabled(JITO));
+    }
+}
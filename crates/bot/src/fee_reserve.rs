@@ -0,0 +1,236 @@
+//! SOL Fee Reserve
+//!
+//! Every transaction needs SOL for fees, but profits accrue in USDC, so the
+//! bot can strand itself with plenty of USDC and no SOL. The reserve monitor
+//! keeps the wallet's SOL above a minimum: below it, trades are blocked and
+//! the bot either swaps a capped amount of USDC back to the target balance
+//! or, if it cannot, alerts an operator. Trading resumes once the balance is
+//! back above the minimum.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Fee reserve thresholds
+#[derive(Debug, Clone)]
+pub struct FeeReserveConfig {
+    /// Trades are blocked below this SOL balance
+    pub min_sol: Decimal,
+    /// Top-ups aim for this SOL balance
+    pub target_sol: Decimal,
+    /// Swap USDC to SOL automatically (otherwise only alert)
+    pub auto_topup: bool,
+    /// Largest single top-up in USD
+    pub max_topup_usd: Decimal,
+    /// Wait after a top-up before judging the balance again
+    pub topup_cooldown: Duration,
+}
+
+impl Default for FeeReserveConfig {
+    fn default() -> Self {
+        Self {
+            min_sol: Decimal::new(5, 2),
+            target_sol: Decimal::new(2, 1),
+            auto_topup: false,
+            max_topup_usd: Decimal::from(25),
+            topup_cooldown: Duration::from_secs(300),
+        }
+    }
+}
+
+/// What the monitor should do after a balance observation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReserveAction {
+    None,
+    /// Swap this much USDC to SOL
+    TopUp { usdc: Decimal },
+    /// The reserve dropped below the minimum and cannot be topped up
+    Alert { reason: String },
+    /// The balance is back above the minimum; trading resumes
+    Restored,
+}
+
+/// Snapshot for status reporting
+#[derive(Debug, Clone, Serialize)]
+pub struct FeeReserveStatus {
+    pub balance_sol: Option<Decimal>,
+    pub min_sol: Decimal,
+    pub target_sol: Decimal,
+    pub blocked: bool,
+    pub auto_topup: bool,
+    pub topups: u64,
+    pub last_error: Option<String>,
+}
+
+/// Tracks the SOL balance against the reserve
+#[derive(Debug)]
+pub struct FeeReserve {
+    config: FeeReserveConfig,
+    balance_sol: Option<Decimal>,
+    blocked: bool,
+    last_topup: Option<Instant>,
+    topups: u64,
+    last_error: Option<String>,
+}
+
+impl FeeReserve {
+    pub fn new(config: FeeReserveConfig) -> Self {
+        Self {
+            config,
+            balance_sol: None,
+            blocked: false,
+            last_topup: None,
+            topups: 0,
+            last_error: None,
+        }
+    }
+
+    /// Whether trades that pay fees are currently blocked
+    pub fn is_blocked(&self) -> bool {
+        self.blocked
+    }
+
+    /// Judge a fresh balance. `sol_price` (USD) sizes a top-up and
+    /// `usdc_balance` caps it; either being unknown means alerting instead.
+    pub fn observe(
+        &mut self,
+        balance_sol: Decimal,
+        sol_price: Option<Decimal>,
+        usdc_balance: Option<Decimal>,
+    ) -> ReserveAction {
+        self.balance_sol = Some(balance_sol);
+        if balance_sol >= self.config.min_sol {
+            let was_blocked = std::mem::replace(&mut self.blocked, false);
+            self.last_error = None;
+            return if was_blocked {
+                ReserveAction::Restored
+            } else {
+                ReserveAction::None
+            };
+        }
+
+        let newly_blocked = !std::mem::replace(&mut self.blocked, true);
+        if self
+            .last_topup
+            .is_some_and(|at| at.elapsed() < self.config.topup_cooldown)
+        {
+            return ReserveAction::None;
+        }
+
+        let alert = |reason: String| {
+            if newly_blocked {
+                ReserveAction::Alert { reason }
+            } else {
+                ReserveAction::None
+            }
+        };
+        if !self.config.auto_topup {
+            return alert(format!(
+                "SOL balance {} is below the {} SOL fee reserve",
+                balance_sol, self.config.min_sol
+            ));
+        }
+        let Some(price) = sol_price.filter(|p| *p > Decimal::ZERO) else {
+            return alert("no SOL price to size a top-up".to_string());
+        };
+
+        let needed = (self.config.target_sol - balance_sol) * price;
+        let usdc = needed
+            .min(self.config.max_topup_usd)
+            .min(usdc_balance.unwrap_or(Decimal::ZERO))
+            .round_dp(2);
+        if usdc <= Decimal::ZERO {
+            return alert(format!(
+                "SOL balance {} is below the {} SOL fee reserve and there is no USDC to swap",
+                balance_sol, self.config.min_sol
+            ));
+        }
+        self.last_topup = Some(Instant::now());
+        ReserveAction::TopUp { usdc }
+    }
+
+    /// Record the outcome of a top-up swap
+    pub fn record_topup(&mut self, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.topups += 1;
+                self.last_error = None;
+            }
+            Err(e) => self.last_error = Some(e),
+        }
+    }
+
+    pub fn status(&self) -> FeeReserveStatus {
+        FeeReserveStatus {
+            balance_sol: self.balance_sol,
+            min_sol: self.config.min_sol,
+            target_sol: self.config.target_sol,
+            blocked: self.blocked,
+            auto_topup: self.config.auto_topup,
+            topups: self.topups,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_topup_blocks_until_restored() {
+        let mut reserve = FeeReserve::new(FeeReserveConfig {
+            auto_topup: true,
+            ..Default::default()
+        });
+        let price = Some(Decimal::from(150));
+
+        assert_eq!(reserve.observe(sol("0.5"), price, None), ReserveAction::None);
+        assert!(!reserve.is_blocked());
+
+        // (0.2 - 0.04) SOL * $150 = $24, within the $25 cap
+        assert_eq!(
+            reserve.observe(sol("0.04"), price, Some(Decimal::from(1000))),
+            ReserveAction::TopUp { usdc: Decimal::from(24) }
+        );
+        assert!(reserve.is_blocked());
+        // No second swap while the first settles
+        assert_eq!(
+            reserve.observe(sol("0.04"), price, Some(Decimal::from(1000))),
+            ReserveAction::None
+        );
+
+        reserve.record_topup(Ok(()));
+        assert_eq!(reserve.observe(sol("0.2"), price, None), ReserveAction::Restored);
+        assert!(!reserve.is_blocked());
+        assert_eq!(reserve.status().topups, 1);
+    }
+
+    #[test]
+    fn test_alerts_once_without_topup() {
+        let mut reserve = FeeReserve::new(FeeReserveConfig::default());
+        assert!(matches!(
+            reserve.observe(sol("0.01"), Some(Decimal::from(150)), Some(Decimal::from(1000))),
+            ReserveAction::Alert { .. }
+        ));
+        assert_eq!(
+            reserve.observe(sol("0.01"), Some(Decimal::from(150)), None),
+            ReserveAction::None
+        );
+        assert!(reserve.is_blocked());
+
+        // Auto top-up capped by the USDC actually held
+        let mut reserve = FeeReserve::new(FeeReserveConfig {
+            auto_topup: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            reserve.observe(sol("0"), Some(Decimal::from(150)), Some(Decimal::from(10))),
+            ReserveAction::TopUp { usdc: Decimal::from(10) }
+        );
+    }
+}
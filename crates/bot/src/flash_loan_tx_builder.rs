@@ -1,3 +1,7 @@
+use crate::priority_fee::PriorityFeeControl;
+use solana_arb_flash_loans::solend::SolendFlashLoan;
+use solana_arb_flash_loans::FlashLoanRouter;
+use solana_arb_core::tokens::{bps_of_atoms, Rounding};
 use solana_arb_core::ArbitrageOpportunity;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
@@ -8,12 +12,29 @@ use solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signer},
 };
+use std::sync::Arc;
 
-#[derive(Debug)]
 pub struct FlashLoanTxBuilder {
-    payer: Keypair,
+    payer_pubkey: Pubkey,
+    /// Dropped while the wallet is quarantined
+    payer: Option<Keypair>,
     solend_program_id: Pubkey,
     is_devnet: bool,
+    /// Operator override, ahead of the size heuristic
+    priority_fees: Arc<PriorityFeeControl>,
+    /// Picks the lender; without one, every loan is taken from Solend
+    router: Option<Arc<FlashLoanRouter>>,
+}
+
+impl std::fmt::Debug for FlashLoanTxBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlashLoanTxBuilder")
+            .field("payer_pubkey", &self.payer_pubkey)
+            .field("solend_program_id", &self.solend_program_id)
+            .field("is_devnet", &self.is_devnet)
+            .field("router", &self.router.as_ref().map(|r| r.provider_names()))
+            .finish()
+    }
 }
 
 impl FlashLoanTxBuilder {
@@ -29,15 +50,30 @@ impl FlashLoanTxBuilder {
         };
 
         Self {
-            payer,
+            payer_pubkey: payer.pubkey(),
+            payer: Some(payer),
             // Safety: SOLEND_PROGRAM_MAINNET and SOLEND_PROGRAM_DEVNET are valid base58 pubkeys
             solend_program_id: program_id_str
                 .parse()
                 .expect("Solend program ID constants must be valid pubkeys"),
             is_devnet,
+            priority_fees: Arc::new(PriorityFeeControl::new()),
+            router: None,
         }
     }
 
+    /// Take loans from whichever provider `router` picks. The router's
+    /// Solend entry only prices loans: Solend legs are still built here,
+    /// from the known reserves.
+    pub fn set_router(&mut self, router: Arc<FlashLoanRouter>) {
+        self.router = Some(router);
+    }
+
+    /// Share the operator priority fee override
+    pub fn set_priority_fee_control(&mut self, control: Arc<PriorityFeeControl>) {
+        self.priority_fees = control;
+    }
+
     /// Build complete flash loan transaction (V0 with ALT support)
     pub fn build_transaction(
         &self,
@@ -48,70 +84,118 @@ impl FlashLoanTxBuilder {
         lookup_tables: &[AddressLookupTableAccount],
         recent_blockhash: solana_sdk::hash::Hash,
     ) -> Result<VersionedTransaction, Box<dyn std::error::Error>> {
+        let message = self.build_message(
+            opportunity,
+            borrow_amount,
+            token_mint,
+            swap_instructions,
+            lookup_tables,
+            recent_blockhash,
+        )?;
+        self.sign(message)
+    }
+
+    /// Build the unsigned flash loan message (V0 with ALT support)
+    pub fn build_message(
+        &self,
+        _opportunity: &ArbitrageOpportunity,
+        borrow_amount: u64,
+        token_mint: &Pubkey,
+        swap_instructions: Vec<Instruction>,
+        lookup_tables: &[AddressLookupTableAccount],
+        recent_blockhash: solana_sdk::hash::Hash,
+    ) -> Result<VersionedMessage, Box<dyn std::error::Error>> {
+        self.build_loan_message(
+            borrow_amount,
+            token_mint,
+            swap_instructions,
+            lookup_tables,
+            recent_blockhash,
+        )
+    }
+
+    /// Build the unsigned message borrowing `borrow_amount` of `token_mint`
+    /// around `swap_instructions`, for swaps that are not an opportunity's
+    /// buy and sell (e.g. the hops of a path)
+    pub fn build_loan_message(
+        &self,
+        borrow_amount: u64,
+        token_mint: &Pubkey,
+        swap_instructions: Vec<Instruction>,
+        lookup_tables: &[AddressLookupTableAccount],
+        recent_blockhash: solana_sdk::hash::Hash,
+    ) -> Result<VersionedMessage, Box<dyn std::error::Error>> {
         let mut all_instructions = Vec::new();
 
         // 1. Compute budget
         all_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(1_400_000));
 
-        let priority_fee = self.calculate_priority_fee(opportunity, borrow_amount);
+        let priority_fee = self.calculate_priority_fee(borrow_amount);
         all_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
             priority_fee,
         ));
 
         // 2. Get/Create ATA for Payer
         let ata = spl_associated_token_account::get_associated_token_address(
-            &self.payer.pubkey(),
+            &self.payer_pubkey,
             token_mint,
         );
 
         // Create ATA idempotent (if it doesn't exist)
         all_instructions.push(
             spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                &self.payer.pubkey(),
-                &self.payer.pubkey(),
+                &self.payer_pubkey,
+                &self.payer_pubkey,
                 token_mint,
                 &spl_token::id(),
             ),
         );
 
-        // 3. Flash borrow from Solend to ATA
-        all_instructions.push(self.build_flash_borrow_instruction(
-            borrow_amount,
-            token_mint,
-            &ata,
-        )?);
-
-        // 4. Add all swap instructions
+        // 3. Flash borrow to ATA, 4. swaps, 5. flash repay (amount + fee)
+        //    from ATA, inside the provider's start and end instructions if
+        //    it has them
+        let (provider, borrow, repay) = self.loan_instructions(borrow_amount, token_mint, &ata)?;
+        let end_index = (all_instructions.len() + 3 + swap_instructions.len()) as u64;
+        let bounds = provider.and_then(|provider| self.router.as_ref()?.loan_bounds(provider, end_index));
+        let (start, end) = bounds.unzip();
+        all_instructions.extend(start);
+        all_instructions.push(borrow);
         all_instructions.extend(swap_instructions);
-
-        // 5. Flash repay (amount + fee) from ATA
-        let repay_amount = self.calculate_repay_amount(borrow_amount);
-        all_instructions.push(self.build_flash_repay_instruction(
-            repay_amount,
-            token_mint,
-            &ata,
-        )?);
+        all_instructions.push(repay);
+        all_instructions.extend(end);
 
         // Build V0 Message with ALTs
         let message = v0::Message::try_compile(
-            &self.payer.pubkey(),
+            &self.payer_pubkey,
             &all_instructions,
             lookup_tables,
             recent_blockhash,
         )?;
 
-        // Build Versioned Transaction
-        let transaction =
-            VersionedTransaction::try_new(VersionedMessage::V0(message), &[&self.payer])?;
-
-        Ok(transaction)
+        Ok(VersionedMessage::V0(message))
     }
 
-    fn calculate_priority_fee(
+    /// Sign a message built by [`Self::build_message`] with the payer
+    pub fn sign(
         &self,
-        _opportunity: &ArbitrageOpportunity,
-        borrow_amount: u64,
-    ) -> u64 {
+        message: VersionedMessage,
+    ) -> Result<VersionedTransaction, Box<dyn std::error::Error>> {
+        let payer = self.payer.as_ref().ok_or("flash loan payer key revoked")?;
+        Ok(VersionedTransaction::try_new(message, &[payer])?)
+    }
+
+    /// Drop the payer key; messages can still be built but not signed
+    pub fn revoke_payer(&mut self) {
+        self.payer = None;
+    }
+
+    /// Restore the payer key after [`Self::revoke_payer`]
+    pub fn restore_payer(&mut self, payer: Keypair) {
+        self.payer_pubkey = payer.pubkey();
+        self.payer = Some(payer);
+    }
+
+    fn calculate_priority_fee(&self, borrow_amount: u64) -> u64 {
         // 5% of expected profit as priority fee
         // heuristic: profit ~ 0.5% of amount
         // profit_amt = amount * 0.005
@@ -119,12 +203,51 @@ impl FlashLoanTxBuilder {
         let fee = (borrow_amount as f64 * 0.00025) as u64;
 
         // Cap at reasonable limits, min 50k micro-lamports
-        fee.clamp(50_000, 1_000_000)
+        self.priority_fees.fee_or(fee.clamp(50_000, 1_000_000))
+    }
+
+    /// Borrow and repay legs of a loan of `amount` atoms into `ata`, from
+    /// the router's pick (named), or from Solend when it picks Solend or
+    /// there is no router
+    fn loan_instructions(
+        &self,
+        amount: u64,
+        token_mint: &Pubkey,
+        ata: &Pubkey,
+    ) -> Result<(Option<&'static str>, Instruction, Instruction), Box<dyn std::error::Error>> {
+        if let Some(router) = &self.router {
+            match router.instructions(amount, token_mint) {
+                Ok((provider, borrow, repay)) if provider != SolendFlashLoan::PROTOCOL_NAME => {
+                    return Ok((Some(provider), borrow, repay));
+                }
+                Err(e) if self.get_solend_reserve(token_mint).is_err() => return Err(e.into()),
+                _ => {}
+            }
+        }
+        let borrow = self.build_flash_borrow_instruction(amount, token_mint, ata)?;
+        let repay_amount = self.calculate_repay_amount(amount);
+        let repay = self.build_flash_repay_instruction(repay_amount, token_mint, ata)?;
+        Ok((None, borrow, repay))
+    }
+
+    /// Amount repaid for a loan of `borrowed` atoms of `token_mint`, to
+    /// whichever provider the loan is taken from
+    pub fn repay_amount(&self, borrowed: u64, token_mint: &Pubkey) -> u64 {
+        let routed_fee = self.router.as_ref().and_then(|router| {
+            let (provider, _, _) = router.instructions(borrowed, token_mint).ok()?;
+            (provider != SolendFlashLoan::PROTOCOL_NAME).then(|| router.fee(provider, borrowed))?
+        });
+        match routed_fee {
+            Some(fee) => borrowed + fee,
+            None => self.calculate_repay_amount(borrowed),
+        }
     }
 
-    fn calculate_repay_amount(&self, borrowed: u64) -> u64 {
-        // Solend fee: 0.03% (3 basis points)
-        borrowed + (borrowed * Self::FEE_BPS / 10000)
+    /// Amount repaid to Solend for a loan of `borrowed` atoms
+    pub fn calculate_repay_amount(&self, borrowed: u64) -> u64 {
+        // Solend fee: 0.03% (3 basis points), rounded up so the repayment
+        // is never short by a fractional atom
+        borrowed + bps_of_atoms(borrowed, Self::FEE_BPS, Rounding::Up)
     }
 
     fn build_flash_borrow_instruction(
@@ -171,7 +294,7 @@ impl FlashLoanTxBuilder {
             solana_sdk::instruction::AccountMeta::new(reserve.liquidity_supply_pubkey, false),
             solana_sdk::instruction::AccountMeta::new(reserve.reserve_pubkey, false),
             solana_sdk::instruction::AccountMeta::new_readonly(reserve.lending_market, false),
-            solana_sdk::instruction::AccountMeta::new_readonly(self.payer.pubkey(), true),
+            solana_sdk::instruction::AccountMeta::new_readonly(self.payer_pubkey, true),
             solana_sdk::instruction::AccountMeta::new_readonly(spl_token::id(), false),
         ];
 
@@ -182,6 +305,18 @@ impl FlashLoanTxBuilder {
         })
     }
 
+    /// Whether a flash loan reserve is known for `token_mint`, on Solend or
+    /// on another provider of the router
+    pub fn supports(&self, token_mint: &Pubkey) -> bool {
+        self.get_solend_reserve(token_mint).is_ok()
+            || self.router.as_ref().is_some_and(|router| {
+                router
+                    .reserves(token_mint)
+                    .iter()
+                    .any(|(provider, _)| *provider != SolendFlashLoan::PROTOCOL_NAME)
+            })
+    }
+
     fn get_solend_reserve(
         &self,
         token_mint: &Pubkey,
@@ -254,6 +389,7 @@ struct SolendReserve {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_arb_flash_loans::marginfi::MarginfiFlashLoan;
     use solana_sdk::signature::Keypair;
 
     #[test]
@@ -280,4 +416,54 @@ mod tests {
             "FNNkz4RCQezSSS71rW2tvqZH1LCkTzaiG7Nd1LeA5x5y"
         );
     }
+
+    #[test]
+    fn test_loans_follow_the_router() {
+        let payer = Keypair::new();
+        let owner = payer.pubkey();
+        let mut builder = FlashLoanTxBuilder::new(payer, false);
+        let (bonk, bank) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let usdc: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
+        assert!(!builder.supports(&bonk));
+
+        let marginfi = MarginfiFlashLoan::new(Pubkey::new_unique(), Pubkey::new_unique(), owner, 2)
+            .with_bank(bonk, bank);
+        builder.set_router(Arc::new(FlashLoanRouter::new(vec![
+            Box::new(SolendFlashLoan::new(Pubkey::new_unique())),
+            Box::new(marginfi),
+        ])));
+        assert!(builder.supports(&bonk));
+        assert_eq!(builder.repay_amount(1_000_000, &bonk), 1_000_200);
+
+        let message = builder
+            .build_loan_message(1_000_000, &bonk, vec![], &[], Default::default())
+            .unwrap();
+        let keys = message.static_account_keys();
+        let programs: Vec<String> = message
+            .instructions()
+            .iter()
+            .map(|ix| keys[ix.program_id_index as usize].to_string())
+            .collect();
+        // Start, borrow, repay and end, with the start naming the end's index
+        assert_eq!(programs.len(), 7);
+        assert_eq!(programs[3..], [MarginfiFlashLoan::MARGINFI_PROGRAM_ID; 4]);
+        assert_eq!(message.instructions()[3].data[8..], 6u64.to_le_bytes());
+
+        // USDC is cheaper on Solend, whose legs are built from the known reserves
+        assert_eq!(builder.repay_amount(10_000, &usdc), 10_003);
+        let message = builder
+            .build_loan_message(10_000, &usdc, vec![], &[], Default::default())
+            .unwrap();
+        let keys = message.static_account_keys();
+        let borrow = &message.instructions()[3];
+        assert_eq!(keys[borrow.program_id_index as usize].to_string(), FlashLoanTxBuilder::SOLEND_PROGRAM_MAINNET);
+    }
+
+    #[test]
+    fn test_repay_amount_rounds_fee_up() {
+        let builder = FlashLoanTxBuilder::new(Keypair::new(), true);
+        assert_eq!(builder.calculate_repay_amount(10_000), 10_003);
+        // 0.3003 atoms of fee must still be repaid as a whole atom
+        assert_eq!(builder.calculate_repay_amount(1_001), 1_002);
+    }
 }
@@ -0,0 +1,164 @@
+//! Drift Perp Hedging
+//!
+//! Executes the perp orders planned by the core
+//! [`HedgeManager`](solana_arb_core::risk::hedge::HedgeManager) on Drift,
+//! through a self-hosted Drift Gateway (`DRIFT_GATEWAY_URL`). The gateway
+//! holds the Drift sub-account key and signs the orders, so the hedge account
+//! and its collateral stay separate from the arbitrage wallet.
+//!
+//! Spot base tokens map to Drift perp market indexes with
+//! `DRIFT_PERP_MARKETS=SOL:0,...`; tokens without a market are never hedged.
+//! Dry runs fill hedges on paper at the spot mid price.
+
+use anyhow::{Context, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use solana_arb_core::risk::hedge::{HedgeOrder, MarginAccount};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Parse `SYMBOL:index,...` into perp market indexes
+pub fn parse_markets(spec: &str) -> Result<HashMap<String, u16>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (symbol, index) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("expected SYMBOL:index, got '{}'", entry))?;
+            let index = index
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid market index for {}", symbol.trim()))?;
+            Ok((symbol.trim().to_uppercase(), index))
+        })
+        .collect()
+}
+
+/// Client for the Drift Gateway HTTP API
+pub struct DriftGateway {
+    client: reqwest::Client,
+    base_url: String,
+    markets: HashMap<String, u16>,
+}
+
+impl DriftGateway {
+    pub fn new(base_url: &str, markets: HashMap<String, u16>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            markets,
+        }
+    }
+
+    /// Whether a gateway URL is set; without one only dry runs can hedge
+    pub fn is_configured(&self) -> bool {
+        !self.base_url.is_empty()
+    }
+
+    /// Base tokens that have a perp market
+    pub fn markets(&self) -> Vec<String> {
+        let mut markets: Vec<String> = self.markets.keys().cloned().collect();
+        markets.sort();
+        markets
+    }
+
+    /// Collateral and margin in use on the Drift sub-account
+    pub async fn account(&self) -> Result<MarginAccount> {
+        let body: Value = self
+            .client
+            .get(format!("{}/v2/collateral", self.base_url))
+            .send()
+            .await
+            .context("Drift Gateway unreachable")?
+            .error_for_status()?
+            .json()
+            .await?;
+        let total = decimal_field(&body, "total")?;
+        let free = decimal_field(&body, "free")?;
+        Ok(MarginAccount {
+            collateral: total,
+            margin_used: (total - free).max(Decimal::ZERO),
+        })
+    }
+
+    /// Place `order` as a market order. Returns the transaction signature.
+    pub async fn place(&self, order: &HedgeOrder) -> Result<String> {
+        let index = self
+            .markets
+            .get(&order.market)
+            .ok_or_else(|| anyhow::anyhow!("no Drift perp market for {}", order.market))?;
+        let response: Value = self
+            .client
+            .post(format!("{}/v2/orders", self.base_url))
+            .json(&order_request(*index, order))
+            .send()
+            .await
+            .context("Drift Gateway unreachable")?
+            .error_for_status()?
+            .json()
+            .await?;
+        response["tx"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Drift Gateway returned no transaction: {}", response))
+    }
+}
+
+fn order_request(market_index: u16, order: &HedgeOrder) -> Value {
+    json!({
+        "orders": [{
+            "marketIndex": market_index,
+            "marketType": "perp",
+            "amount": order.base_amount.round_dp(9).to_f64().unwrap_or(0.0),
+            "price": 0,
+            "orderType": "market",
+            "postOnly": false,
+            "reduceOnly": order.reduce_only,
+        }]
+    })
+}
+
+/// The gateway reports amounts as strings or numbers
+fn decimal_field(body: &Value, field: &str) -> Result<Decimal> {
+    match &body[field] {
+        Value::String(s) => Decimal::from_str(s).with_context(|| format!("invalid {}", field)),
+        Value::Number(n) => Decimal::from_str(&n.to_string()).with_context(|| format!("invalid {}", field)),
+        _ => anyhow::bail!("Drift Gateway response has no {}", field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markets_and_order_request() {
+        let markets = parse_markets("sol:0, JUP : 24").unwrap();
+        assert_eq!(markets.get("SOL"), Some(&0));
+        assert_eq!(markets.get("JUP"), Some(&24));
+        assert!(parse_markets("SOL").is_err());
+        assert!(parse_markets("SOL:perp").is_err());
+        assert!(parse_markets("").unwrap().is_empty());
+
+        let order = HedgeOrder {
+            market: "SOL".to_string(),
+            base_amount: Decimal::new(-25, 1),
+            reduce_only: true,
+            reason: "reduce to target".to_string(),
+        };
+        let request = order_request(0, &order);
+        assert_eq!(request["orders"][0]["amount"], json!(-2.5));
+        assert_eq!(request["orders"][0]["reduceOnly"], json!(true));
+        assert_eq!(request["orders"][0]["marketType"], json!("perp"));
+
+        assert_eq!(decimal_field(&json!({"free": "12.5"}), "free").unwrap(), Decimal::new(125, 1));
+        assert_eq!(decimal_field(&json!({"free": 3}), "free").unwrap(), Decimal::from(3));
+        assert!(decimal_field(&json!({}), "free").is_err());
+    }
+}
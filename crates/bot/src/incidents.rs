@@ -0,0 +1,276 @@
+//! Incident Tracking
+//!
+//! Every critical alert opens an incident, or joins the open incident for
+//! the same condition. Alerts are matched on their text with the numbers
+//! masked, so "Low balance: 0.08 SOL" and "Low balance: 0.05 SOL" are the
+//! same incident. A repeat of an open or acknowledged incident is counted
+//! but not sent again.
+//!
+//! An incident nobody acknowledges within `INCIDENT_ESCALATE_MINUTES` is
+//! escalated, and again every period after that until someone acknowledges
+//! it with `POST /incidents/:id/ack`. Resolving an incident lets the next
+//! alert for the same condition open a new one.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use solana_arb_core::memory::MemoryUsage;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Closed incidents kept for the API
+const RESOLVED_KEPT: usize = 200;
+
+/// Lifecycle of an incident
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncidentState {
+    Open,
+    Acked,
+    Resolved,
+}
+
+impl std::str::FromStr for IncidentState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(Self::Open),
+            "acked" => Ok(Self::Acked),
+            "resolved" => Ok(Self::Resolved),
+            other => Err(format!("unknown incident state '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub id: u64,
+    /// Alert text with numbers masked; repeats with the same key are duplicates
+    pub key: String,
+    pub message: String,
+    /// Text of the latest duplicate
+    pub last_message: String,
+    pub state: IncidentState,
+    /// Alerts raised for this incident, including the first
+    pub occurrences: u64,
+    pub opened_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub acked_at: Option<DateTime<Utc>>,
+    pub acked_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Times this incident has been escalated
+    pub escalations: u32,
+    pub escalated_at: Option<DateTime<Utc>>,
+}
+
+/// What raising an alert did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Raised {
+    /// A new incident; send the alert
+    New(u64),
+    /// Joined this open or acknowledged incident; suppress the alert
+    Duplicate(u64),
+}
+
+struct Incidents {
+    next_id: u64,
+    /// Open and acknowledged incidents
+    active: Vec<Incident>,
+    resolved: VecDeque<Incident>,
+}
+
+/// Critical alert incidents, shared by the alert manager and the API
+pub struct IncidentStore {
+    /// Escalate incidents unacknowledged this long (`None`: never)
+    escalate_after: Option<Duration>,
+    incidents: Mutex<Incidents>,
+}
+
+impl IncidentStore {
+    pub fn new(escalate_after: Option<std::time::Duration>) -> Self {
+        Self {
+            escalate_after: escalate_after.and_then(|after| Duration::from_std(after).ok()),
+            incidents: Mutex::new(Incidents {
+                next_id: 1,
+                active: Vec::new(),
+                resolved: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Record a critical alert
+    pub fn raise(&self, message: &str) -> Raised {
+        let key = incident_key(message);
+        let now = Utc::now();
+        let mut incidents = self.lock();
+        if let Some(incident) = incidents.active.iter_mut().find(|i| i.key == key) {
+            incident.occurrences += 1;
+            incident.last_seen = now;
+            incident.last_message = message.to_string();
+            return Raised::Duplicate(incident.id);
+        }
+        let id = incidents.next_id;
+        incidents.next_id += 1;
+        incidents.active.push(Incident {
+            id,
+            key,
+            message: message.to_string(),
+            last_message: message.to_string(),
+            state: IncidentState::Open,
+            occurrences: 1,
+            opened_at: now,
+            last_seen: now,
+            acked_at: None,
+            acked_by: None,
+            resolved_at: None,
+            escalations: 0,
+            escalated_at: None,
+        });
+        Raised::New(id)
+    }
+
+    /// Acknowledge an open incident, which stops its escalation
+    pub fn acknowledge(&self, id: u64, by: &str) -> Result<Incident, String> {
+        let mut incidents = self.lock();
+        let incident = incidents
+            .active
+            .iter_mut()
+            .find(|i| i.id == id)
+            .ok_or_else(|| format!("no open incident #{}", id))?;
+        if incident.state == IncidentState::Open {
+            incident.state = IncidentState::Acked;
+            incident.acked_at = Some(Utc::now());
+            incident.acked_by = Some(by.to_string());
+        }
+        Ok(incident.clone())
+    }
+
+    /// Close an incident; the next alert for its condition opens a new one
+    pub fn resolve(&self, id: u64) -> Result<Incident, String> {
+        let mut incidents = self.lock();
+        let index = incidents
+            .active
+            .iter()
+            .position(|i| i.id == id)
+            .ok_or_else(|| format!("no open incident #{}", id))?;
+        let mut incident = incidents.active.remove(index);
+        incident.state = IncidentState::Resolved;
+        incident.resolved_at = Some(Utc::now());
+        if incidents.resolved.len() >= RESOLVED_KEPT {
+            incidents.resolved.pop_front();
+        }
+        incidents.resolved.push_back(incident.clone());
+        Ok(incident)
+    }
+
+    /// Open incidents due for escalation at `now`, marked as escalated
+    pub fn due_escalations(&self, now: DateTime<Utc>) -> Vec<Incident> {
+        let Some(after) = self.escalate_after else {
+            return Vec::new();
+        };
+        let mut incidents = self.lock();
+        incidents
+            .active
+            .iter_mut()
+            .filter(|i| i.state == IncidentState::Open)
+            .filter(|i| now - i.escalated_at.unwrap_or(i.opened_at) >= after)
+            .map(|incident| {
+                incident.escalations += 1;
+                incident.escalated_at = Some(now);
+                incident.clone()
+            })
+            .collect()
+    }
+
+    /// Incidents, newest first, optionally only those in `state`
+    pub fn list(&self, state: Option<IncidentState>) -> Vec<Incident> {
+        let incidents = self.lock();
+        let mut list: Vec<Incident> = incidents
+            .active
+            .iter()
+            .chain(incidents.resolved.iter())
+            .filter(|i| state.is_none_or(|state| i.state == state))
+            .cloned()
+            .collect();
+        list.sort_by_key(|i| std::cmp::Reverse(i.id));
+        list
+    }
+
+    /// Approximate memory held by open and kept resolved incidents
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let incidents = self.lock();
+        let all = || incidents.active.iter().chain(&incidents.resolved);
+        let text: usize = all()
+            .map(|i| i.key.capacity() + i.message.capacity() + i.last_message.capacity())
+            .sum();
+        MemoryUsage::of::<Incident>(all().count(), 0).with_bytes(text)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Incidents> {
+        self.incidents.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The alert text with every run of digits (and decimal points between
+/// them) masked, so changing figures do not make a new incident
+fn incident_key(message: &str) -> String {
+    let mut key = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            while chars
+                .peek()
+                .is_some_and(|next| next.is_ascii_digit() || *next == '.' || *next == ',')
+            {
+                chars.next();
+            }
+            key.push('#');
+        } else {
+            key.push(c);
+        }
+    }
+    key.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incident_lifecycle_dedup_and_escalation() {
+        let store = IncidentStore::new(Some(std::time::Duration::from_secs(600)));
+        let Raised::New(low) = store.raise("⚠️ Low balance: 0.0812 SOL") else {
+            panic!("first alert opens an incident");
+        };
+        assert_eq!(store.raise("⚠️ Low balance: 0.05 SOL"), Raised::Duplicate(low));
+        let Raised::New(breaker) = store.raise("⚡ Circuit breaker OPEN - trading halted") else {
+            panic!("another condition is another incident");
+        };
+        let incident = &store.list(None)[1];
+        assert_eq!((incident.id, incident.occurrences), (low, 2));
+        assert_eq!(incident.last_message, "⚠️ Low balance: 0.05 SOL");
+
+        // Nothing is due before the escalation period, then both are
+        let opened = store.list(None)[0].opened_at;
+        assert!(store.due_escalations(opened + Duration::minutes(5)).is_empty());
+        assert_eq!(store.due_escalations(opened + Duration::minutes(10)).len(), 2);
+
+        // Acknowledged incidents stop escalating but still absorb duplicates
+        store.acknowledge(low, "ops").unwrap();
+        assert_eq!(store.raise("⚠️ Low balance: 0.01 SOL"), Raised::Duplicate(low));
+        let due = store.due_escalations(opened + Duration::minutes(20));
+        assert_eq!(due.len(), 1);
+        assert_eq!((due[0].id, due[0].escalations), (breaker, 2));
+        assert!(store.due_escalations(opened + Duration::minutes(25)).is_empty());
+
+        // Resolving lets the condition open a fresh incident
+        store.resolve(low).unwrap();
+        assert!(store.acknowledge(low, "ops").is_err());
+        assert!(matches!(store.raise("⚠️ Low balance: 0.02 SOL"), Raised::New(id) if id > breaker));
+        assert_eq!(store.list(Some(IncidentState::Resolved)).len(), 1);
+        assert_eq!(store.list(Some(IncidentState::Open)).len(), 2);
+        assert_eq!(store.list(Some(IncidentState::Acked)).len(), 0);
+
+        assert!(IncidentStore::new(None).due_escalations(opened + Duration::days(1)).is_empty());
+    }
+}
@@ -0,0 +1,274 @@
+//! Trades In Flight
+//!
+//! A trade spends most of its life waiting on something: the Jupiter quote,
+//! the swap transaction, the simulation, the leader, the confirmation. Each
+//! executing trade walks a small state machine through those stages, and
+//! the time it entered each one is kept, so the API can show where trades
+//! are stuck right now. Trades leave the set when their execution returns.
+
+use crate::execution_mode::ExecutionMode;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::{ArbitrageOpportunity, Uuid};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::debug;
+
+/// Where an executing trade is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlightStage {
+    /// Waiting for the Jupiter quote
+    Quoting,
+    /// Fetching swap instructions and assembling the transaction
+    Building,
+    /// Simulating it before signing
+    Simulating,
+    /// Sent, signature known
+    Submitted,
+    /// Waiting for the configured commitment
+    Confirming,
+}
+
+impl FlightStage {
+    /// Stages only move forward, except that a resubmission simulates again
+    pub fn can_follow(self, current: Option<FlightStage>) -> bool {
+        match current {
+            None => true,
+            Some(current) => {
+                self > current
+                    || (self == FlightStage::Simulating && current >= FlightStage::Submitted)
+            }
+        }
+    }
+}
+
+/// Time spent in one stage
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageTime {
+    pub stage: FlightStage,
+    pub entered_at: DateTime<Utc>,
+    /// Until the next stage, or until now for the current one
+    pub elapsed_ms: u64,
+}
+
+/// An executing trade, as reported by the API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InFlightTrade {
+    pub opportunity_id: Uuid,
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub size_usd: Decimal,
+    pub mode: ExecutionMode,
+    /// Other trades sharing its transaction, when batched
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub batched_with: Vec<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub elapsed_ms: u64,
+    /// Current stage; `None` until the executor picks the trade up
+    pub stage: Option<FlightStage>,
+    pub stages: Vec<StageTime>,
+    /// Transaction signatures (or Jito bundle ids), one per submission
+    pub signatures: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Flight {
+    trade: InFlightTrade,
+    started: Instant,
+    /// Entry into each stage, in order
+    entered: Vec<(FlightStage, DateTime<Utc>, Instant)>,
+}
+
+impl Flight {
+    fn snapshot(&self, now: Instant) -> InFlightTrade {
+        let stages = self
+            .entered
+            .iter()
+            .enumerate()
+            .map(|(i, (stage, entered_at, at))| {
+                let until = self.entered.get(i + 1).map_or(now, |next| next.2);
+                StageTime {
+                    stage: *stage,
+                    entered_at: *entered_at,
+                    elapsed_ms: until.saturating_duration_since(*at).as_millis() as u64,
+                }
+            })
+            .collect();
+        InFlightTrade {
+            elapsed_ms: now.saturating_duration_since(self.started).as_millis() as u64,
+            stage: self.entered.last().map(|(stage, _, _)| *stage),
+            stages,
+            ..self.trade.clone()
+        }
+    }
+}
+
+/// Trades currently executing, by opportunity ID
+#[derive(Debug, Default)]
+pub struct InFlightTrades {
+    flights: Mutex<HashMap<Uuid, Flight>>,
+}
+
+impl InFlightTrades {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `opp` until the returned guard is dropped
+    pub fn start(
+        self: &Arc<Self>,
+        opp: &ArbitrageOpportunity,
+        size_usd: Decimal,
+        mode: ExecutionMode,
+        batched_with: Vec<Uuid>,
+    ) -> InFlight {
+        let trade = InFlightTrade {
+            opportunity_id: opp.id,
+            pair: opp.pair.symbol(),
+            buy_dex: opp.buy_dex.display_name().to_string(),
+            sell_dex: opp.sell_dex.display_name().to_string(),
+            size_usd,
+            mode,
+            batched_with,
+            started_at: Utc::now(),
+            elapsed_ms: 0,
+            stage: None,
+            stages: Vec::new(),
+            signatures: Vec::new(),
+        };
+        self.lock().insert(
+            opp.id,
+            Flight {
+                trade,
+                started: Instant::now(),
+                entered: Vec::new(),
+            },
+        );
+        InFlight {
+            trades: self.clone(),
+            id: opp.id,
+        }
+    }
+
+    /// Move a tracked trade to `stage`. Untracked trades (e.g. preflight
+    /// candidates) and out-of-order transitions are ignored.
+    pub fn enter(&self, id: Uuid, stage: FlightStage) {
+        let mut flights = self.lock();
+        let Some(flight) = flights.get_mut(&id) else {
+            return;
+        };
+        let current = flight.entered.last().map(|(stage, _, _)| *stage);
+        if stage.can_follow(current) {
+            flight.entered.push((stage, Utc::now(), Instant::now()));
+        } else {
+            debug!("Trade {} cannot move from {:?} to {:?}", id, current, stage);
+        }
+    }
+
+    pub fn record_signature(&self, id: Uuid, signature: &str) {
+        if let Some(flight) = self.lock().get_mut(&id) {
+            flight.trade.signatures.push(signature.to_string());
+        }
+    }
+
+    /// Executing trades, longest running first
+    pub fn snapshot(&self) -> Vec<InFlightTrade> {
+        let now = Instant::now();
+        let mut trades: Vec<_> = self.lock().values().map(|f| f.snapshot(now)).collect();
+        trades.sort_by_key(|t| std::cmp::Reverse(t.elapsed_ms));
+        trades
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Flight>> {
+        self.flights.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Keeps a trade in flight; dropping it (execution returned) removes it
+#[derive(Debug)]
+pub struct InFlight {
+    trades: Arc<InFlightTrades>,
+    id: Uuid,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.trades.lock().remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use solana_arb_core::{DexType, TokenPair};
+
+    #[test]
+    fn test_stages_and_signatures_of_a_trade_in_flight() {
+        let trades = Arc::new(InFlightTrades::new());
+        let opp = ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::ONE,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+
+        // Preflight candidates are not tracked
+        trades.enter(opp.id, FlightStage::Quoting);
+        assert!(trades.snapshot().is_empty());
+
+        let guard = trades.start(&opp, Decimal::from(250), ExecutionMode::Standard, Vec::new());
+        for stage in [
+            FlightStage::Quoting,
+            FlightStage::Building,
+            FlightStage::Quoting, // backwards: ignored
+            FlightStage::Simulating,
+            FlightStage::Submitted,
+            FlightStage::Simulating, // resubmission
+            FlightStage::Submitted,
+            FlightStage::Confirming,
+        ] {
+            trades.enter(opp.id, stage);
+        }
+        trades.record_signature(opp.id, "sig1");
+
+        let inflight = trades.snapshot();
+        assert_eq!(inflight.len(), 1);
+        let trade = &inflight[0];
+        assert_eq!(trade.pair, "SOL/USDC");
+        assert_eq!(trade.stage, Some(FlightStage::Confirming));
+        let stages: Vec<_> = trade.stages.iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            vec![
+                FlightStage::Quoting,
+                FlightStage::Building,
+                FlightStage::Simulating,
+                FlightStage::Submitted,
+                FlightStage::Simulating,
+                FlightStage::Submitted,
+                FlightStage::Confirming,
+            ]
+        );
+        assert_eq!(trade.signatures, vec!["sig1".to_string()]);
+
+        drop(guard);
+        assert!(trades.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+//! Transaction Intent Log
+//!
+//! Every transaction the executor signs is appended here before it is
+//! sent, with the blockhash it expires with and the lookup tables it uses,
+//! and resolved once it is seen confirmed. Whatever is still open when the
+//! bot starts was in flight when it stopped: startup reconciliation looks
+//! each one up on chain (see [`crate::reconciliation`]).
+//!
+//! The log is JSON lines at `INTENT_WAL_PATH` (default `data/intents.jsonl`),
+//! rewritten with only the open intents after each reconciliation.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A signed transaction that may have been sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Intent {
+    pub signature: String,
+    /// The transaction cannot land once this blockhash has expired
+    pub blockhash: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lookup_tables: Vec<String>,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// One line of the log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Entry {
+    Signed(Intent),
+    /// Seen confirmed, failed on chain, or expired
+    Resolved {
+        signature: String,
+        outcome: String,
+        at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug)]
+pub struct IntentWal {
+    path: PathBuf,
+    /// Serializes appends and rewrites
+    lock: Mutex<()>,
+}
+
+impl IntentWal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Log `tx` as about to be sent
+    pub fn signed(&self, tx: &VersionedTransaction) {
+        let Some(signature) = tx.signatures.first() else {
+            return;
+        };
+        let lookup_tables = match &tx.message {
+            VersionedMessage::V0(message) => message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.account_key.to_string())
+                .collect(),
+            VersionedMessage::Legacy(_) => Vec::new(),
+        };
+        self.append(&Entry::Signed(Intent {
+            signature: signature.to_string(),
+            blockhash: tx.message.recent_blockhash().to_string(),
+            lookup_tables,
+            signed_at: Utc::now(),
+        }));
+    }
+
+    /// Close the intent of `signature`, e.g. with "confirmed" or "expired"
+    pub fn resolve(&self, signature: &str, outcome: &str) {
+        self.append(&Entry::Resolved {
+            signature: signature.to_string(),
+            outcome: outcome.to_string(),
+            at: Utc::now(),
+        });
+    }
+
+    /// Intents not resolved yet, oldest first
+    pub fn open(&self) -> std::io::Result<Vec<Intent>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.read_open()
+    }
+
+    /// Rewrite the log with only the open intents
+    pub fn compact(&self) -> std::io::Result<usize> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let open = self.read_open()?;
+        let mut contents = String::new();
+        for intent in &open {
+            contents.push_str(&serde_json::to_string(&Entry::Signed(intent.clone()))?);
+            contents.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(open.len())
+    }
+
+    fn read_open(&self) -> std::io::Result<Vec<Intent>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut order = Vec::new();
+        let mut open: HashMap<String, Intent> = HashMap::new();
+        // A line torn by a crash is skipped; compaction drops it
+        for entry in contents.lines().filter_map(|line| serde_json::from_str::<Entry>(line).ok()) {
+            match entry {
+                Entry::Signed(intent) => {
+                    order.push(intent.signature.clone());
+                    open.insert(intent.signature.clone(), intent);
+                }
+                Entry::Resolved { signature, .. } => {
+                    open.remove(&signature);
+                }
+            }
+        }
+        Ok(order.into_iter().filter_map(|sig| open.remove(&sig)).collect())
+    }
+
+    fn append(&self, entry: &Entry) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let json = match serde_json::to_string(entry) {
+            Ok(json) => json,
+            Err(e) => return tracing::error!("Failed to serialize intent: {}", e),
+        };
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", json).and_then(|_| file.sync_data()));
+        if let Err(e) = result {
+            tracing::error!("Failed to write intent log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::Uuid;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::v0;
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::system_instruction;
+
+    fn signed_tx(payer: &Keypair) -> VersionedTransaction {
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+        let message = v0::Message::try_compile(&payer.pubkey(), &[ix], &[], Hash::new_unique()).unwrap();
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer]).unwrap()
+    }
+
+    #[test]
+    fn test_open_intents_survive_compaction() {
+        let path = std::env::temp_dir().join(format!("intents-{}.jsonl", Uuid::new_v4()));
+        let wal = IntentWal::new(&path);
+        let payer = Keypair::new();
+        let (first, second) = (signed_tx(&payer), signed_tx(&payer));
+
+        wal.signed(&first);
+        wal.signed(&second);
+        wal.resolve(&second.signatures[0].to_string(), "confirmed");
+        // A line torn by a crash
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"op\":\"sig").unwrap();
+
+        let open = wal.open().unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].signature, first.signatures[0].to_string());
+        assert_eq!(open[0].blockhash, first.message.recent_blockhash().to_string());
+
+        assert_eq!(wal.compact().unwrap(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert_eq!(wal.open().unwrap(), open);
+        fs::remove_file(&path).unwrap();
+    }
+}
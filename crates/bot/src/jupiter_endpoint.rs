@@ -0,0 +1,158 @@
+//! Jupiter API Endpoint
+//!
+//! The executor can talk to the public Jupiter v6 API or to a self-hosted
+//! `jupiter-swap-api` instance. A self-hosted endpoint has no rate limits, so
+//! the Jupiter rate limiter is skipped for it. Its health is probed
+//! periodically with a small quote, and the same probe against the public
+//! endpoint measures how much latency self-hosting saves.
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Public Jupiter v6 swap API
+pub const PUBLIC_JUPITER_API_URL: &str = "https://quote-api.jup.ag/v6";
+
+/// Quote used to probe an endpoint: 1 SOL -> USDC
+const PROBE_QUERY: &str = "/quote?inputMint=So11111111111111111111111111111111111111112\
+&outputMint=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v&amount=1000000000&slippageBps=50";
+
+/// Weight of the newest probe in the average latency
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+
+/// Strip trailing slashes so paths can be appended with `format!("{}/quote")`
+pub fn normalize_base_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+/// Whether `base_url` points somewhere other than the public API
+pub fn is_self_hosted(base_url: &str) -> bool {
+    normalize_base_url(base_url) != PUBLIC_JUPITER_API_URL
+}
+
+/// Time one probe quote against `base_url`
+pub async fn probe(client: &Client, base_url: &str) -> Result<Duration> {
+    let start = Instant::now();
+    let response = client
+        .get(format!("{}{}", normalize_base_url(base_url), PROBE_QUERY))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await?;
+    let elapsed = start.elapsed();
+
+    if !response.status().is_success() {
+        return Err(anyhow!("probe returned HTTP {}", response.status()));
+    }
+    let quote: serde_json::Value = response.json().await?;
+    if quote.get("outAmount").is_none() {
+        return Err(anyhow!("probe response has no outAmount"));
+    }
+    Ok(elapsed)
+}
+
+/// Probe history for one endpoint
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointStats {
+    pub healthy: bool,
+    pub last_latency_ms: Option<f64>,
+    /// Exponentially weighted average of successful probe latencies
+    pub avg_latency_ms: Option<f64>,
+    pub probes: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl EndpointStats {
+    /// Record a probe. Returns `true` if the endpoint's health changed.
+    pub fn record(&mut self, result: &Result<Duration>) -> bool {
+        let was_healthy = self.healthy;
+        self.probes += 1;
+        match result {
+            Ok(latency) => {
+                let ms = latency.as_secs_f64() * 1000.0;
+                self.healthy = true;
+                self.last_latency_ms = Some(ms);
+                self.avg_latency_ms = Some(match self.avg_latency_ms {
+                    Some(avg) => LATENCY_EWMA_ALPHA * ms + (1.0 - LATENCY_EWMA_ALPHA) * avg,
+                    None => ms,
+                });
+                self.last_error = None;
+            }
+            Err(e) => {
+                self.healthy = false;
+                self.failures += 1;
+                self.last_error = Some(e.to_string());
+            }
+        }
+        // The first probe establishes the baseline rather than a change
+        self.probes > 1 && was_healthy != self.healthy
+    }
+}
+
+/// Jupiter endpoint status reported in `/status`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JupiterStatus {
+    pub base_url: String,
+    pub self_hosted: bool,
+    pub endpoint: EndpointStats,
+    /// Public endpoint probed for comparison (self-hosted only)
+    pub public: Option<EndpointStats>,
+}
+
+impl JupiterStatus {
+    pub fn new(base_url: &str, compare_public: bool) -> Self {
+        let self_hosted = is_self_hosted(base_url);
+        Self {
+            base_url: normalize_base_url(base_url),
+            self_hosted,
+            endpoint: EndpointStats::default(),
+            public: (self_hosted && compare_public).then(EndpointStats::default),
+        }
+    }
+
+    /// Average latency saved per request by using the configured endpoint
+    /// instead of the public one (negative if it is slower)
+    pub fn latency_saving_ms(&self) -> Option<f64> {
+        let public = self.public.as_ref()?.avg_latency_ms?;
+        Some(public - self.endpoint.avg_latency_ms?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_hosted_detection() {
+        assert!(!is_self_hosted("https://quote-api.jup.ag/v6/"));
+        assert!(is_self_hosted("http://jupiter-api:8080"));
+        assert_eq!(normalize_base_url(" http://localhost:8080/ "), "http://localhost:8080");
+
+        assert!(JupiterStatus::new("http://localhost:8080", true).public.is_some());
+        assert!(JupiterStatus::new("http://localhost:8080", false).public.is_none());
+        assert!(JupiterStatus::new(PUBLIC_JUPITER_API_URL, true).public.is_none());
+    }
+
+    #[test]
+    fn test_stats_and_latency_saving() {
+        let mut status = JupiterStatus::new("http://localhost:8080", true);
+        assert!(!status.endpoint.record(&Ok(Duration::from_millis(20))));
+        status
+            .public
+            .as_mut()
+            .unwrap()
+            .record(&Ok(Duration::from_millis(120)));
+        assert_eq!(status.latency_saving_ms(), Some(100.0));
+
+        // EWMA: 0.2 * 70 + 0.8 * 20
+        status.endpoint.record(&Ok(Duration::from_millis(70)));
+        assert!((status.endpoint.avg_latency_ms.unwrap() - 30.0).abs() < 1e-9);
+
+        // Health transitions are reported once
+        assert!(status.endpoint.record(&Err(anyhow!("connection refused"))));
+        assert!(!status.endpoint.record(&Err(anyhow!("connection refused"))));
+        assert_eq!(status.endpoint.failures, 2);
+        assert!(status.endpoint.record(&Ok(Duration::from_millis(20))));
+    }
+}
@@ -0,0 +1,204 @@
+//! Execution Deadline and Latency SLO
+//!
+//! Quote, swap instructions and transaction build have to finish within the
+//! execution deadline (`EXECUTION_DEADLINE_MS`): past it the detected edge
+//! is most likely gone. A [`BuildDeadline`] is started per trade and every
+//! network step of the build runs under a tokio timeout for what is left of
+//! it. Once the budget is spent the trade aborts before anything is signed
+//! and is recorded with the `aborted_deadline` failure kind.
+//!
+//! [`LatencySlo`] keeps the build latencies of recent trades, aborted ones
+//! included, so the share of trades built within the deadline can be
+//! watched on `/status`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Build latencies kept for the SLO
+const SLO_WINDOW: usize = 500;
+
+/// The build of a trade ran past its deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineExceeded {
+    pub budget: Duration,
+}
+
+impl std::fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Execution deadline of {}ms exceeded", self.budget.as_millis())
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+/// Whether `error` is (or wraps) a [`DeadlineExceeded`]
+pub fn is_deadline_exceeded(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<DeadlineExceeded>().is_some()
+}
+
+/// Time budget for building one trade
+#[derive(Debug, Clone, Copy)]
+pub struct BuildDeadline {
+    started: Instant,
+    budget: Option<Duration>,
+}
+
+impl BuildDeadline {
+    /// Start the clock. Without a budget nothing is ever cut short.
+    pub fn start(budget: Option<Duration>) -> Self {
+        Self {
+            started: Instant::now(),
+            budget,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Run one build step, failing with [`DeadlineExceeded`] if the
+    /// deadline passes first
+    pub async fn within<T>(&self, step: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some(budget) = self.budget else {
+            return step.await;
+        };
+        match tokio::time::timeout_at(self.started + budget, step).await {
+            Ok(result) => result,
+            Err(_) => Err(DeadlineExceeded { budget }.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    millis: u64,
+    aborted: bool,
+}
+
+/// Build latency SLO over the most recent trades
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatencySloStatus {
+    pub deadline_ms: Option<u64>,
+    pub samples: usize,
+    pub aborted: usize,
+    /// Share of builds that finished within the deadline, in percent
+    pub within_deadline_pct: Option<f64>,
+    pub p50_ms: Option<u64>,
+    pub p99_ms: Option<u64>,
+}
+
+/// Recent build latencies
+#[derive(Debug)]
+pub struct LatencySlo {
+    deadline: Option<Duration>,
+    samples: Mutex<VecDeque<Sample>>,
+}
+
+impl LatencySlo {
+    pub fn new(deadline: Option<Duration>) -> Self {
+        Self {
+            deadline,
+            samples: Mutex::new(VecDeque::with_capacity(SLO_WINDOW)),
+        }
+    }
+
+    /// A build finished after `elapsed`
+    pub fn record_built(&self, elapsed: Duration) {
+        self.push(elapsed, false);
+    }
+
+    /// A build was aborted at its deadline after `elapsed`
+    pub fn record_aborted(&self, elapsed: Duration) {
+        self.push(elapsed, true);
+    }
+
+    fn push(&self, elapsed: Duration, aborted: bool) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        if samples.len() == SLO_WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back(Sample {
+            millis: elapsed.as_millis() as u64,
+            aborted,
+        });
+    }
+
+    pub fn status(&self) -> LatencySloStatus {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let mut millis: Vec<u64> = samples.iter().map(|s| s.millis).collect();
+        millis.sort_unstable();
+        let percentile = |p: usize| {
+            (!millis.is_empty()).then(|| millis[((millis.len() - 1) * p).div_ceil(100)])
+        };
+        let deadline_ms = self.deadline.map(|d| d.as_millis() as u64);
+        let within_deadline_pct = deadline_ms.filter(|_| !samples.is_empty()).map(|deadline| {
+            let within = samples
+                .iter()
+                .filter(|s| !s.aborted && s.millis <= deadline)
+                .count();
+            within as f64 * 100.0 / samples.len() as f64
+        });
+        LatencySloStatus {
+            deadline_ms,
+            samples: samples.len(),
+            aborted: samples.iter().filter(|s| s.aborted).count(),
+            within_deadline_pct,
+            p50_ms: percentile(50),
+            p99_ms: percentile(99),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deadline_aborts_slow_steps_and_feeds_slo() {
+        let deadline = BuildDeadline::start(Some(Duration::from_millis(200)));
+        let quote = deadline
+            .within(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(1)
+            })
+            .await;
+        assert_eq!(quote.unwrap(), 1);
+
+        // The second step shares what is left of the same budget
+        let swap = deadline
+            .within(async {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                Ok(2)
+            })
+            .await;
+        let error = swap.unwrap_err();
+        assert!(is_deadline_exceeded(&error));
+        assert!(deadline.elapsed() >= Duration::from_millis(200));
+        assert!(deadline.elapsed() < Duration::from_secs(2));
+
+        let unbounded = BuildDeadline::start(None);
+        let slow = unbounded
+            .within(async {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok(())
+            })
+            .await;
+        assert!(slow.is_ok());
+
+        let slo = LatencySlo::new(Some(Duration::from_millis(800)));
+        assert_eq!(slo.status().within_deadline_pct, None);
+        for millis in [100, 200, 300] {
+            slo.record_built(Duration::from_millis(millis));
+        }
+        slo.record_aborted(Duration::from_millis(800));
+        let status = slo.status();
+        assert_eq!((status.samples, status.aborted), (4, 1));
+        assert_eq!(status.within_deadline_pct, Some(75.0));
+        assert_eq!((status.p50_ms, status.p99_ms), (Some(300), Some(800)));
+    }
+}
@@ -2,60 +2,222 @@
 //!
 //! Automated trading bot that executes arbitrage opportunities.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::Utc;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive; // Needed for from_f64
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, watch, RwLock};
 use tracing::{debug, error, info, warn};
 
-use solana_arb_core::events::{EventBus, TradingEvent};
+use solana_arb_core::events::{parse_overflow_policies, EventBus, TradingEvent};
+use solana_arb_core::memory::MemoryUsage;
+use solana_arb_core::rpc_batch::RpcBatcher;
 
 pub mod execution;
+pub mod execution_mode;
 pub mod wallet;
+pub mod wsol;
 // mod jito; // Migrated to core
 pub mod api;
+pub mod backtest;
+pub mod commitments;
+pub mod compatibility;
 pub mod config_manager;
+pub mod confirmations;
+pub mod control;
+pub mod fault_injection;
+pub mod fee_reserve;
+pub mod hedging;
+pub mod incidents;
+pub mod inflight;
+pub mod intent_wal;
+pub mod feature_flags;
 pub mod flash_loan_tx_builder;
+pub mod jupiter_endpoint;
+pub mod latency_slo;
+pub mod loss_guard;
+pub mod listeners;
 pub mod logging;
+pub mod manual_trade;
+pub mod market_state;
 pub mod metrics;
+pub mod onboarding;
+pub mod replay;
+pub mod replication;
+pub mod risk_report;
+pub mod paper_broker;
+pub mod paths;
+pub mod pipeline;
+pub mod postmortem;
+pub mod preflight;
+pub mod price_compaction;
+pub mod priority_fee;
+pub mod quarantine;
+pub mod quote_cache;
+pub mod reconciliation;
+pub mod retry_policy;
+pub mod route_repair;
+pub mod route_scoreboard;
+pub mod routing;
+pub mod simulator;
 pub mod alerts;
+pub mod alert_privacy;
+pub mod batching;
+pub mod blacklist;
+pub mod compliance;
 pub mod safety_checks;
+pub mod session_archive;
+pub mod simulation;
+pub mod size_feedback;
 pub mod solend_config;
+pub mod spending_guard;
+pub mod status_page;
+pub mod strategy_profile;
+pub mod submission;
+pub mod telegram_commands;
+pub mod trade_traces;
+pub mod trade_webhooks;
 
+use crate::alert_privacy::ChannelPrivacy;
 use crate::alerts::AlertManager;
+use crate::blacklist::{Blacklist, BlacklistEntry};
+use crate::commitments::Commitments;
+use crate::compliance::ComplianceGate;
 use crate::config_manager::ConfigManager;
+use crate::replay::{PriceSnapshots, ReplaySettings};
+use crate::execution_mode::{ExecutionMode, ExecutionModeSelector, ModeInputs, ModeSelection, TradeModeRecord};
+use crate::fee_reserve::{FeeReserve, FeeReserveConfig, FeeReserveStatus, ReserveAction};
+use crate::confirmations::{ConfirmationTracker, Observation, Resolution, TrackedTrade};
+use crate::paper_broker::{PaperBroker, PaperTrade};
+use crate::listeners::SharedEndpoints;
+use crate::postmortem::{PostmortemConfig, Postmortems};
+use crate::preflight::PreflightConfig;
+use crate::priority_fee::PriorityFeeControl;
+use crate::quote_cache::QuoteCache;
+use crate::quarantine::Quarantine;
+use crate::route_repair::{RepairPolicy, Stranded};
+use crate::route_scoreboard::RouteScoreboard;
+use crate::size_feedback::SizeFeedback;
 use crate::safety_checks::run_preflight_checks;
+use crate::inflight::InFlightTrades;
+use crate::intent_wal::IntentWal;
+use crate::reconciliation::{Reconciler, ReconciliationReport};
+use crate::manual_trade::{ManualJob, ManualOrder, ManualTradeReport, ManualTradeStatus, ManualTrades};
+use crate::market_state::{MarketState, SharedMarket};
+use crate::control::TradingControl;
+use crate::pipeline::{Candidates, HaltGeneration, PipelineEvent, PriceBatch};
+use crate::price_compaction::RetentionPolicy;
+use crate::status_page::{PnlSign, PublicStatus};
+use crate::strategy_profile::{StrategySettings, WarmUpStatus};
+use crate::telegram_commands::{ChatCommand, Command, TelegramCommands};
+use crate::trade_traces::TradeTraces;
+use crate::latency_slo::LatencySlo;
+use crate::hedging::DriftGateway;
+use crate::incidents::IncidentStore;
+use crate::replication::{ReplicaSnapshot, Replication};
+use crate::trade_webhooks::{TradeWebhookConfig, TradeWebhookPayload, TradeWebhooks};
 use axum::{routing::get, Json, Router};
+use batching::{BatchConfig, BatchLeg};
+use paths::PathConfig;
+use feature_flags::FeatureFlags;
+use routing::{RouteFilter, RoutePolicy};
+use jupiter_endpoint::{JupiterStatus, PUBLIC_JUPITER_API_URL};
 use execution::{Executor, ORCA_MINT, RAY_MINT, SOL_MINT, USDC_MINT};
 use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Instant;
 use metrics::prometheus::MetricsCollector;
+use metrics::recorder::MetricsRecorder;
 use solana_arb_core::{
     alt::AltManager,
     arbitrage::ArbitrageDetector,
+    ArbitrageConfig,
+    OpportunitySource,
     config::Config,
     dex::{jupiter::JupiterProvider, orca::OrcaProvider, raydium::RaydiumProvider, DexManager},
-    history::HistoryRecorder,
+    discovery::{DiscoveryConfig, PoolDiscovery, ProposalStatus, ScreenVerdict, TokenScreener},
+    fx::{self, FxRate, SharedFxRate},
+    history::{rotation::RotationPolicy, HistoryRecorder},
+    database::{timescale::TimescaleClient, trade_store::TradeStore},
+    session::Session,
     jito::JitoClient,
-    pathfinding::PathFinder,
-    pricing::parallel_fetcher::ParallelPriceFetcher,
-    rate_limiter::RateLimiter,
-    risk::{RiskConfig, RiskManager, TradeDecision, TradeOutcome},
-    types::TradeResult,
+    pathfinding::{PathFinder, TradingPath},
+    pricing::{
+        parallel_fetcher::{FetchTimeouts, ParallelPriceFetcher},
+        projection::PriceProjector,
+        spread_guard::SpreadGuard,
+    },
+    streaming::{
+        aggregator::StreamingPriceAggregator, pool_accounts::parse_pool_accounts,
+        ws_manager::WebSocketManager,
+    },
+    rate_limiter::{
+        shared::SharedBudget,
+        tuner::{BudgetTuner, LearnedBudgets},
+        RateLimiter,
+    },
+    regime::{spread_index, MarketRegime, RegimeTracker},
+    risk::{
+        canary::CanaryStatus,
+        hedge::{HedgeConfig, HedgeManager, HedgeStatus, MarginAccount, SpotExposure},
+        impact::{self, ImpactCurveCache},
+        returns::ReturnArchive,
+        unwind::{UnwindStatus, UnwindStep},
+        var::VarMethod,
+        MoneyLimits, RiskConfig, RiskManager, TradeDecision, TradeOutcome,
+    },
+    tokens::TokenRegistry,
+    types::{FailureKind, TradeResult},
     DexType, TokenPair,
 };
-use solana_arb_dex_plugins::{LifinityProvider, MeteoraProvider, PhoenixProvider};
+use solana_arb_dex_plugins::{
+    sanctum, LifinityProvider, MeteoraProvider, PhoenixProvider, SanctumProvider,
+};
 use solana_arb_flash_loans::solend::SolendFlashLoan;
-use solana_arb_flash_loans::FlashLoanProvider;
-use solana_arb_strategies::{LatencyArbitrage, StatisticalArbitrage, Strategy};
+use solana_arb_flash_loans::kamino::KaminoFlashLoan;
+use solana_arb_flash_loans::marginfi::MarginfiFlashLoan;
+use solana_arb_flash_loans::{FlashLoanProvider, FlashLoanRouter, ReserveLiquidity};
+use solana_arb_strategies::{RemoteStrategy, RemoteStrategyConfig, Strategy};
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
 use std::str::FromStr;
 use wallet::Wallet;
 
+/// Archived price returns for historical / Monte Carlo VaR
+const RETURN_ARCHIVE_PATH: &str = "data/var_returns.json";
+
+/// Pairs and routes suspended after repeated on-chain failures
+const BLACKLIST_PATH: &str = "data/blacklist.json";
+
+/// Pairs proposed and enabled by pool discovery
+const DISCOVERY_PATH: &str = "data/discovered_pairs.json";
+
+/// Rate limiter budgets learned by auto-tuning
+const RATE_LIMIT_STATE_PATH: &str = "data/rate_limits.json";
+const RPC_LIMITER: &str = "rpc";
+const JUPITER_LIMITER: &str = "jupiter";
+
+/// How often housekeeping (kill switch, status, cooldowns) runs
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the price source checks whether held trading resumed
+const TRADING_HELD_RECHECK: Duration = Duration::from_secs(5);
+
+/// Streamed price updates waiting to be cached before streams are held back
+const PRICE_STREAM_CAPACITY: usize = 1024;
+
+/// Trades whose execution mode is kept for `/status`
+const RECENT_EXECUTION_MODES: usize = 20;
+
+/// Built-in strategies of the standard profile, shared by the bot and the
+/// opportunity simulator
+pub(crate) fn default_strategies() -> Vec<Box<dyn Strategy>> {
+    StrategySettings::default().strategies()
+}
+
 /// System health status
 #[derive(Clone, Debug)]
 pub struct SystemHealth {
@@ -65,6 +227,24 @@ pub struct SystemHealth {
     pub circuit_breaker_state: String,
     pub balance_usd: f64,
     pub start_time: Instant,
+    pub venue_exposure: HashMap<String, f64>,
+    /// Capital committed to trades that have not confirmed yet
+    pub committed_capital: f64,
+    pub canary: Option<CanaryStatus>,
+    pub route_filter: RouteFilter,
+    pub jupiter: JupiterStatus,
+    /// Live trades confirmed but not yet finalized
+    pub pending_finalization: usize,
+    /// Unwind of exposure above a lowered limit, while one is in progress
+    pub unwind: Option<UnwindStatus>,
+    /// SOL fee reserve, once the wallet balance has been checked
+    pub fee_reserve: Option<FeeReserveStatus>,
+    /// Perp hedges and hedge budget, once the hedger has run
+    pub hedging: Option<HedgeStatus>,
+    /// Execution mode selected for the latest trades, newest last
+    pub execution_modes: VecDeque<TradeModeRecord>,
+    /// Warm-up of the running strategies
+    pub strategies: Vec<WarmUpStatus>,
 }
 
 impl Default for SystemHealth {
@@ -76,6 +256,17 @@ impl Default for SystemHealth {
             circuit_breaker_state: "Closed".to_string(),
             balance_usd: 0.0,
             start_time: Instant::now(),
+            venue_exposure: HashMap::new(),
+            committed_capital: 0.0,
+            canary: None,
+            route_filter: RouteFilter::default(),
+            jupiter: JupiterStatus::default(),
+            pending_finalization: 0,
+            unwind: None,
+            fee_reserve: None,
+            hedging: None,
+            execution_modes: VecDeque::new(),
+            strategies: Vec::new(),
         }
     }
 }
@@ -83,30 +274,105 @@ impl Default for SystemHealth {
 /// Trading bot state holding all component instances and shared data.
 #[allow(dead_code)]
 struct BotState {
-    /// Service for detecting arbitrage opportunities.
-    detector: ArbitrageDetector,
-    /// Service for finding profitable paths.
-    path_finder: PathFinder,
+    /// Detector, path finder, spread guard and price projection, behind
+    /// their own lock so applying prices does not wait for execution
+    market: SharedMarket,
     /// Risk management system.
     risk_manager: RiskManager,
     /// Manager for decentralized exchanges.
     dex_manager: DexManager,
     /// Service for fetching token prices.
-    price_fetcher: ParallelPriceFetcher,
-    /// Component for executing trades.
-    executor: Executor,
-    /// Wallet for signing transactions.
-    wallet: Wallet,
-    /// Provider for flash loans.
-    flash_loan_provider: Box<dyn FlashLoanProvider>,
+    price_fetcher: Arc<ParallelPriceFetcher>,
+    /// Component for executing trades (None in watch-only mode).
+    executor: Option<Executor>,
+    /// Wallet for signing transactions (None in watch-only mode).
+    wallet: Option<Wallet>,
+    /// Whether the bot only detects and reports opportunities.
+    watch_only: bool,
+    /// Limits for batching small opportunities into one transaction.
+    batch_config: BatchConfig,
+    /// Limits for trading multi-hop paths from the path finder.
+    path_config: PathConfig,
+    /// Candidates simulated each tick before one is executed
+    preflight: PreflightConfig,
+    /// Binary search steps sizing trades against quoted impact (0: off)
+    size_search_steps: u32,
+    /// Smallest trade size the impact search considers
+    size_search_min_usd: Decimal,
+    /// Sampled impact curve per route, reused for a short TTL
+    impact_curves: ImpactCurveCache,
+    /// Order book snapshots for Phoenix, the one order book venue.
+    phoenix: Arc<PhoenixProvider>,
+    /// Fills dry-run trades against order books.
+    paper_broker: PaperBroker,
+    /// SOL kept for transaction fees; trades are blocked while it is low.
+    fee_reserve: FeeReserve,
+    /// Perp hedges of spot inventory (gated by the `perp_hedging` flag)
+    hedge_manager: HedgeManager,
+    /// Drift Gateway the hedges are placed through
+    drift: Arc<DriftGateway>,
+    /// Standing wSOL balance in lamports, in wSOL mode
+    wsol_target_lamports: Option<u64>,
+    /// Selling what failed standard trades leave in the wallet, if enabled
+    route_repair: Option<RepairPolicy>,
+    /// Exposure and breaker journal behind the end-of-day risk report
+    risk_journal: Option<risk_report::RiskJournal>,
+    /// Venue health and configured Jupiter route exclusions.
+    route_policy: RoutePolicy,
+    /// Detection quality per (buy DEX, sell DEX) route
+    route_scoreboard: Arc<RwLock<RouteScoreboard>>,
+    /// Per-route size limits from realized against modeled slippage
+    size_feedback: Arc<SizeFeedback>,
+    /// Pairs and routes suspended after repeated on-chain failures
+    blacklist: Arc<RwLock<Blacklist>>,
+    /// Cross-DEX spread index and the market regime it implies
+    regime: Arc<RwLock<RegimeTracker>>,
+    /// Multiplier on the minimum profit while the market is dislocated
+    regime_edge_multiplier: Decimal,
+    /// Flash loan providers, quoted per trade for the cheapest. The executor
+    /// takes its loans from the same router.
+    flash_loan_router: Arc<FlashLoanRouter>,
+    /// Live liquidity of the flash loan reserve lending each token, by mint.
+    flash_liquidity: HashMap<Pubkey, ReserveLiquidity>,
+    /// Largest share of a reserve's available liquidity one flash loan may take.
+    flash_loan_max_reserve_fraction: Decimal,
     /// Recorder for trade history.
     history_recorder: HistoryRecorder,
-    /// Optional Jito client for MEV protection.
-    jito_client: Option<JitoClient>,
+    /// Live trades awaiting finalization.
+    confirmations: ConfirmationTracker,
+    /// Identifier of this run, shared by history records and the session store.
+    session_id: String,
+    /// Session store (None when the database is unavailable).
+    session_db: Option<Arc<TimescaleClient>>,
+    /// Postgres mirror of trades, traded opportunities and risk events
+    /// (None unless enabled and reachable).
+    trade_store: Option<TradeStore>,
+    /// Accounting webhooks for completed live trades (None when not configured).
+    trade_webhooks: Option<Arc<TradeWebhooks>>,
+    /// Plan, quote, execution and on-chain outcome of recent trades
+    trade_traces: Arc<TradeTraces>,
+    /// Stages of the trades executing right now
+    inflight: Arc<InFlightTrades>,
+    /// Signed transactions not yet seen confirmed
+    intents: Arc<IntentWal>,
+    /// Manual pause, shared with the REST API and Telegram commands
+    control: Arc<TradingControl>,
+    /// Signing stop on signs of a leaked key (None when disabled)
+    quarantine: Option<Arc<Quarantine>>,
+    /// Post-mortem bundles of losing live trades (None when disabled).
+    postmortems: Option<Arc<Postmortems>>,
+    /// Primary or warm standby role; a standby never trades
+    replication: Arc<Replication>,
+    /// Jito client for MEV protection (gated by the `jito` flag).
+    jito_client: JitoClient,
+    /// Runtime feature flags.
+    feature_flags: Arc<FeatureFlags>,
     /// Address Lookup Table (ALT) manager.
     alt_manager: Arc<AltManager>,
     /// List of active trading strategies.
     strategies: Vec<Box<dyn Strategy>>,
+    /// Strategy profile; the conservative one skips the spread detector.
+    strategy_settings: StrategySettings,
     /// Whether the bot is currently running.
     is_running: bool,
     /// Whether the bot is in dry-run mode.
@@ -115,10 +381,18 @@ struct BotState {
     rpc_url: String,
     /// Maximum age of price data in seconds.
     max_price_age_seconds: i64,
+    /// Latency a projection covers beyond a quote's age
+    projection_latency: Duration,
+    /// Archive of each tick's fetched prices, for replay.
+    price_snapshots: Option<PriceSnapshots>,
     /// Metrics collector.
     metrics: Arc<MetricsCollector>,
     /// Alert manager for notifications.
     alert_manager: AlertManager,
+    /// Rate of the accounting currency, shared with alerts.
+    fx: SharedFxRate,
+    /// Money risk limits as configured, in the accounting currency.
+    risk_limits: MoneyLimits,
     /// Shared system health status.
     system_health: Arc<RwLock<SystemHealth>>,
     /// Event bus for system-wide events.
@@ -127,6 +401,8 @@ struct BotState {
     consecutive_errors: u32,
     /// Rate limiter for RPC requests.
     rpc_rate_limiter: Arc<RateLimiter>,
+    /// Coalesces balance and account lookups into batched RPC requests
+    rpc_batcher: Arc<RpcBatcher>,
     /// Rate limiter for Jupiter API requests.
     jupiter_rate_limiter: Arc<RateLimiter>,
     /// Dynamic configuration manager.
@@ -140,16 +416,32 @@ impl BotState {
     ///
     /// * `config` - Application configuration
     /// * `dry_run` - Whether to run in simulation mode
+    /// * `watch_only` - Skip wallet and executor initialization entirely
+    /// * `feature_flags` - Shared runtime feature flags
     /// * `metrics` - Metrics collector
     /// * `alert_manager` - Alert manager
     /// * `system_health` - Shared system health status
+    /// * `event_bus` - Shared event bus
+    #[allow(clippy::too_many_arguments)]
     fn new(
         config: &Config,
         dry_run: bool,
+        watch_only: bool,
+        feature_flags: Arc<FeatureFlags>,
+        priority_fees: Arc<PriorityFeeControl>,
         metrics: Arc<MetricsCollector>,
         alert_manager: AlertManager,
         system_health: Arc<RwLock<SystemHealth>>,
+        event_bus: Arc<EventBus>,
         config_manager: Arc<ConfigManager>,
+        route_scoreboard: Arc<RwLock<RouteScoreboard>>,
+        size_feedback: Arc<SizeFeedback>,
+        blacklist: Arc<RwLock<Blacklist>>,
+        regime: Arc<RwLock<RegimeTracker>>,
+        trade_traces: Arc<TradeTraces>,
+        inflight: Arc<InFlightTrades>,
+        latency_slo: Arc<LatencySlo>,
+        record_signer: Option<Arc<Keypair>>,
     ) -> Self {
         let risk_config = RiskConfig {
             max_position_size: Decimal::from(1000),
@@ -159,8 +451,29 @@ impl BotState {
                 .min_profit_threshold
                 .try_into()
                 .unwrap_or(Decimal::new(5, 3)),
+            max_venue_exposure: Decimal::from_f64(config.max_venue_exposure)
+                .unwrap_or(Decimal::from(2500)),
+            canary_size: Decimal::from_f64(config.canary_size_usd).unwrap_or(Decimal::from(50)),
+            unwind_step_size: Decimal::from_f64(config.unwind_step_usd)
+                .unwrap_or(Decimal::from(250)),
+            canary_required_successes: config.canary_required_successes,
+            var_method: config.var_method.parse().unwrap_or_else(|e| {
+                warn!("{}, using historical VaR", e);
+                VarMethod::Historical
+            }),
+            var_confidence: config.var_confidence,
+            var_horizon_periods: config.var_horizon_periods,
+            var_monte_carlo_paths: config.var_monte_carlo_paths,
+            commitment_ttl_secs: config.capital_commitment_ttl_secs,
             ..Default::default()
         };
+        let mut risk_manager = RiskManager::new(risk_config);
+        let risk_limits = risk_manager.money_limits();
+        match ReturnArchive::load(std::path::Path::new(RETURN_ARCHIVE_PATH)) {
+            Ok(archive) => risk_manager.return_archive = archive,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Ignoring VaR return archive {}: {}", RETURN_ARCHIVE_PATH, e),
+        }
 
         let mut dex_manager = DexManager::new();
 
@@ -180,114 +493,323 @@ impl BotState {
         dex_manager.add_provider(Arc::new(MeteoraProvider::new()));
         info!("🔌 Registered DEX provider: Meteora");
 
-        dex_manager.add_provider(Arc::new(PhoenixProvider::new()));
+        let phoenix = Arc::new(PhoenixProvider::new());
+        dex_manager.add_provider(phoenix.clone());
         info!("🔌 Registered DEX provider: Phoenix");
 
+        if config.lst_pairs_enabled {
+            dex_manager.add_provider(Arc::new(SanctumProvider::with_api_url(
+                &config.sanctum_api_url,
+                config.sanctum_fee_bps,
+            )));
+            info!("🔌 Registered DEX provider: Sanctum");
+        }
+
         info!(
             "🔌 DexManager initialized with {} providers",
             dex_manager.providers().len()
         );
 
-        let price_fetcher = ParallelPriceFetcher::new(dex_manager.providers().to_vec());
-
-        // Initialize Flash Loan Provider (Solend)
-        // For now using USDC reserve placeholder - in prod this would be dynamic or config based
-        // Safety: this is a valid base58-encoded Solana pubkey constant
-        let usdc_reserve =
-            Pubkey::from_str("BgxfHJDzm44T7XG68MYKx7YisTjZu73tVovyZSjJMpmw")
-                .expect("Mainnet USDC reserve is a valid pubkey constant");
-        let flash_loan_provider = Box::new(SolendFlashLoan::new(usdc_reserve));
-        info!(
-            "🏦 Initialized Flash Loan Provider: {}",
-            flash_loan_provider.name()
+        let fetch_timeouts = FetchTimeouts::from_config(config)
+            .with_venue_timeouts(&config.dex_timeout_ms_by_venue)
+            .unwrap_or_else(|e| {
+                warn!("Invalid DEX_TIMEOUT_MS_BY_VENUE: {}. Using DEX_TIMEOUT_MS for every venue.", e);
+                FetchTimeouts::from_config(config)
+            });
+        let price_fetcher = Arc::new(
+            ParallelPriceFetcher::new(dex_manager.providers().to_vec()).with_timeouts(fetch_timeouts),
         );
 
+        let commitments = Commitments::from_config(config);
+        let intents = Arc::new(IntentWal::new(&config.intent_wal_path));
         let temp_session_id = format!("SESSION-{}", Utc::now().format("%Y%m%d-%H%M%S"));
-        let history_file = if dry_run {
-            "data/history-sim.jsonl"
-        } else {
-            "data/history-live.jsonl"
-        };
-        let history_recorder = HistoryRecorder::new(history_file, &temp_session_id);
+        let history_file = history_file_path(dry_run);
+        let mut history_recorder = HistoryRecorder::new(history_file, &temp_session_id)
+            .with_commitment_level(commitments.submission_label())
+            .with_rotation(RotationPolicy::new(
+                config.history_rotate_max_mb,
+                config.history_rotate_max_age_hours,
+            ));
+        if let Some(signer) = record_signer {
+            history_recorder = history_recorder.with_signer(signer);
+        }
         info!("📜 Trade history will be saved to: {}", history_file);
 
-        // Initialize Jito Client (Optional)
-        let jito_client = if std::env::var("USE_JITO").unwrap_or("false".to_string()) == "true" {
-            let engine_url = std::env::var("JITO_BLOCK_ENGINE_URL")
-                .unwrap_or("https://mainnet.block-engine.jito.wtf".to_string());
-            let tip = std::env::var("JITO_TIP_LAMPORTS")
-                .unwrap_or("100000".to_string())
-                .parse()
-                .unwrap_or(100000);
+        // Initialize Jito Client (used only while the `jito` flag is on)
+        let engine_url = std::env::var("JITO_BLOCK_ENGINE_URL")
+            .unwrap_or("https://mainnet.block-engine.jito.wtf".to_string());
+        let tip = std::env::var("JITO_TIP_LAMPORTS")
+            .unwrap_or("100000".to_string())
+            .parse()
+            .unwrap_or(100000);
+        let jito_client = JitoClient::new(&engine_url, tip);
+        if feature_flags.is_enabled(feature_flags::JITO) {
             info!(
                 "🛡️ Jito MEV Protection enabled (Engine: {}, Tip: {} lamports)",
                 engine_url, tip
             );
-            Some(JitoClient::new(&engine_url, tip))
         } else {
             info!("⚠️ Jito MEV Protection DISABLED");
-            None
-        };
+        }
 
         // Initialize ALT Manager
-        let alt_manager = Arc::new(AltManager::new(&config.solana_rpc_url));
+        let rpc_batcher = Arc::new(RpcBatcher::new_with_commitment(
+            &config.solana_rpc_url,
+            Duration::from_millis(config.rpc_batch_window_ms),
+            commitments.reads,
+        ));
+        let alt_manager =
+            Arc::new(AltManager::new(&config.solana_rpc_url).with_batcher(rpc_batcher.clone()));
         info!("📇 Address Lookup Table (ALT) Manager initialized");
 
         // Initialize Strategies
-        let mut strategies: Vec<Box<dyn Strategy>> = Vec::new();
-
-        // Statistical Arbitrage (Window: 20 ticks, Z-score: 2.0)
-        strategies.push(Box::new(StatisticalArbitrage::new(20, Decimal::new(20, 1))));
-        info!("🧠 Strategy initialized: Statistical Arbitrage");
-
-        // Latency Arbitrage
-        strategies.push(Box::new(LatencyArbitrage::new()));
-        info!("🧠 Strategy initialized: Latency Arbitrage");
-
-        let mut executor = Executor::with_config(execution::ExecutionConfig {
-            priority_fee_micro_lamports: config.priority_fee_micro_lamports,
-            compute_unit_limit: config.compute_unit_limit,
-            slippage_bps: config.slippage_bps,
-            max_retries: config.max_retries,
-            rpc_commitment: config.rpc_commitment.clone(),
-        });
-        
-        // Initialize Rate Limiters
-        // RPC: 10 requests/second (conservative default)
-        let rpc_rate_limiter = Arc::new(RateLimiter::per_second(10));
-        // Jupiter: 5 requests/second (public API limit)
-        let jupiter_rate_limiter = Arc::new(RateLimiter::per_second(5));
-
-        executor.set_rate_limiters(
-            Some(rpc_rate_limiter.clone()), 
-            Some(jupiter_rate_limiter.clone())
+        let strategy_settings = StrategySettings::from_config(config);
+        info!("🧠 Strategy profile: {:?}", strategy_settings.profile);
+        let strategies = strategy_settings.strategies_with_books(&phoenix);
+        for strategy in &strategies {
+            info!("🧠 Strategy initialized: {}", strategy.name());
+        }
+
+        // Initialize Rate Limiters, resuming from learned budgets when auto-tuning
+        let learned = if config.rate_limit_autotune {
+            match LearnedBudgets::load(std::path::Path::new(RATE_LIMIT_STATE_PATH)) {
+                Ok(learned) => learned,
+                Err(e) => {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        warn!("Ignoring learned rate limits {}: {}", RATE_LIMIT_STATE_PATH, e);
+                    }
+                    LearnedBudgets::default()
+                }
+            }
+        } else {
+            LearnedBudgets::default()
+        };
+        let initial_budget = |name: &str, configured: usize| {
+            if config.rate_limit_autotune {
+                rate_limit_tuner(config, name).clamp(learned.get(name).unwrap_or(configured))
+            } else {
+                configured
+            }
+        };
+        let limiter = |name: &str, configured: usize| {
+            let limiter = RateLimiter::per_second(initial_budget(name, configured));
+            Arc::new(match SharedBudget::from_config(config, name) {
+                Some(budget) => limiter.with_shared_budget(budget),
+                None => limiter,
+            })
+        };
+        let rpc_rate_limiter = limiter(RPC_LIMITER, config.rpc_rate_limit);
+        // Public Jupiter API only; a self-hosted API is not limited
+        let jupiter_rate_limiter = limiter(JUPITER_LIMITER, config.jupiter_rate_limit);
+        for (name, limiter) in [(RPC_LIMITER, &rpc_rate_limiter), (JUPITER_LIMITER, &jupiter_rate_limiter)] {
+            metrics
+                .rate_limit_budget
+                .with_label_values(&[name])
+                .set(limiter.max_requests() as i64);
+        }
+
+        let (mut executor, wallet) = if watch_only {
+            info!("👀 Watch-only mode: wallet and executor disabled");
+            (None, None)
+        } else {
+            let mut executor = Executor::with_config(execution::ExecutionConfig {
+                priority_fee_micro_lamports: config.priority_fee_micro_lamports,
+                compute_unit_limit: config.compute_unit_limit,
+                slippage_bps: config.slippage_bps,
+                max_retries: config.max_retries,
+                rpc_commitment: config.rpc_commitment.clone(),
+                commitments,
+                wrap_and_unwrap_sol: !config.wsol_mode,
+                build_deadline: execution_deadline(config),
+            });
+
+            // A self-hosted Jupiter API has no rate limits
+            let self_hosted = jupiter_endpoint::is_self_hosted(&config.jupiter_api_url);
+            executor.set_jupiter_api_url(&config.jupiter_api_url);
+            executor.set_rate_limiters(
+                Some(rpc_rate_limiter.clone()),
+                (!self_hosted).then(|| jupiter_rate_limiter.clone())
+            );
+            if self_hosted {
+                info!("🪐 Using self-hosted Jupiter API at {}", executor.jupiter_api_url());
+            }
+
+            executor.set_alt_manager(alt_manager.clone());
+            executor.set_rpc_batcher(rpc_batcher.clone());
+            executor.set_priority_fee_control(priority_fees);
+            executor.set_trade_traces(trade_traces.clone());
+            executor.set_inflight(inflight.clone());
+            executor.set_intent_wal(intents.clone());
+            let quote_cache = QuoteCache::from_config(config);
+            if quote_cache.is_enabled() {
+                executor.set_quote_cache(Arc::new(quote_cache));
+            }
+            match ComplianceGate::from_config(config) {
+                Ok(gate) if !gate.is_empty() => {
+                    info!("🛡️ Compliance hooks: {}", gate.hook_names().join(", "));
+                    executor.set_compliance(Arc::new(gate));
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Compliance hooks disabled (invalid settings): {}", e),
+            }
+            executor.set_latency_slo(latency_slo);
+
+            (Some(executor), Some(Wallet::new().expect("Failed to load wallet")))
+        };
+
+        // Initialize Flash Loan Providers (Solend, optionally Kamino and marginfi)
+        // For now using USDC reserve placeholder - in prod this would be dynamic or config based
+        // Safety: this is a valid base58-encoded Solana pubkey constant
+        let usdc_reserve =
+            Pubkey::from_str("BgxfHJDzm44T7XG68MYKx7YisTjZu73tVovyZSjJMpmw")
+                .expect("Mainnet USDC reserve is a valid pubkey constant");
+        let mut flash_loan_providers: Vec<Box<dyn FlashLoanProvider>> =
+            vec![Box::new(SolendFlashLoan::new(usdc_reserve))];
+        if config.kamino_flash_loans_enabled {
+            match kamino_flash_loan(config, wallet.as_ref()) {
+                Ok(kamino) => flash_loan_providers.push(Box::new(kamino)),
+                Err(e) => warn!("Kamino flash loans disabled (invalid settings): {}", e),
+            }
+        }
+        if config.marginfi_flash_loans_enabled {
+            match marginfi_flash_loan(config, wallet.as_ref()) {
+                Ok(marginfi) => flash_loan_providers.push(Box::new(marginfi)),
+                Err(e) => warn!("Marginfi flash loans disabled (invalid settings): {}", e),
+            }
+        }
+        let flash_loan_router = Arc::new(FlashLoanRouter::new(flash_loan_providers));
+        info!(
+            "🏦 Initialized Flash Loan Providers: {}",
+            flash_loan_router.provider_names().join(", ")
         );
+        if let Some(executor) = executor.as_mut() {
+            executor.set_flash_loan_router(flash_loan_router.clone());
+        }
 
-        executor.set_alt_manager(alt_manager.clone());
+        let market = MarketState::new(
+            ArbitrageDetector::new(ArbitrageConfig {
+                max_quote_skew_ms: config.max_quote_skew_ms,
+                ..Default::default()
+            }),
+            PathFinder::new(4),
+            SpreadGuard::new(config.max_spread_pct)
+                .with_venue_limits(&config.max_spread_pct_by_venue)
+                .unwrap_or_else(|e| {
+                    warn!("Invalid MAX_SPREAD_PCT_BY_VENUE: {}. Using MAX_SPREAD_PCT for every venue.", e);
+                    SpreadGuard::new(config.max_spread_pct)
+                }),
+            config
+                .price_projection_enabled
+                .then(|| {
+                    PriceProjector::new(
+                        &config.price_projection_venues,
+                        config.price_projection_alpha,
+                        config.price_projection_max_bps,
+                    )
+                    .map_err(|e| warn!("Invalid PRICE_PROJECTION_VENUES: {}. Prices are not projected.", e))
+                    .ok()
+                })
+                .flatten(),
+        )
+        .shared();
 
         Self {
-            detector: ArbitrageDetector::default(),
-            path_finder: PathFinder::new(4),
-            risk_manager: RiskManager::new(risk_config),
+            market,
+            risk_manager,
             dex_manager,
             price_fetcher,
             executor,
-            wallet: Wallet::new().expect("Failed to load wallet"),
-            flash_loan_provider,
+            wallet,
+            watch_only,
+            route_policy: RoutePolicy::new(Default::default()),
+            route_scoreboard,
+            size_feedback,
+            blacklist,
+            regime,
+            regime_edge_multiplier: Decimal::from_f64(config.regime_edge_multiplier)
+                .unwrap_or(Decimal::from(2)),
+            batch_config: BatchConfig {
+                max_legs: config.batch_max_legs,
+                max_leg_size_usd: Decimal::from_f64(config.batch_max_leg_size_usd)
+                    .unwrap_or(Decimal::from(100)),
+                ..Default::default()
+            },
+            path_config: PathConfig {
+                max_size_usd: Decimal::from_f64(config.path_max_size_usd)
+                    .unwrap_or(Decimal::from(100)),
+                ..Default::default()
+            },
+            preflight: PreflightConfig {
+                top_k: config.preflight_top_k,
+                concurrency: config.preflight_concurrency.max(1),
+            },
+            size_search_steps: config.size_search_steps,
+            size_search_min_usd: Decimal::from_f64(config.size_search_min_usd)
+                .unwrap_or(Decimal::from(50)),
+            impact_curves: ImpactCurveCache::new(Duration::from_secs(config.impact_curve_ttl_secs)),
+            phoenix,
+            paper_broker: PaperBroker::new(
+                Decimal::from_f64(config.paper_queue_ahead_pct).unwrap_or(Decimal::from(25)),
+            ),
+            wsol_target_lamports: config
+                .wsol_mode
+                .then(|| lamports(Decimal::from_f64(config.wsol_target_sol).unwrap_or(Decimal::ONE))),
+            route_repair: RepairPolicy::from_config(config),
+            risk_journal: risk_report::RiskJournal::new(&config.risk_report_dir),
+            fee_reserve: FeeReserve::new(FeeReserveConfig {
+                min_sol: Decimal::from_f64(config.fee_reserve_min_sol).unwrap_or(Decimal::new(5, 2)),
+                target_sol: Decimal::from_f64(config.fee_reserve_target_sol)
+                    .unwrap_or(Decimal::new(2, 1)),
+                auto_topup: config.fee_reserve_auto_topup,
+                max_topup_usd: Decimal::from_f64(config.fee_reserve_max_topup_usd)
+                    .unwrap_or(Decimal::from(25)),
+                ..Default::default()
+            }),
+            hedge_manager: HedgeManager::new(HedgeConfig::default()),
+            drift: Arc::new(DriftGateway::new(
+                &config.drift_gateway_url,
+                hedging::parse_markets(&config.drift_perp_markets).unwrap_or_else(|e| {
+                    warn!("Invalid DRIFT_PERP_MARKETS: {:#}. No token will be hedged.", e);
+                    HashMap::new()
+                }),
+            )),
+            flash_loan_router,
+            flash_liquidity: HashMap::new(),
+            flash_loan_max_reserve_fraction: Decimal::from_f64(config.flash_loan_max_reserve_fraction)
+                .unwrap_or(Decimal::new(1, 1)),
             history_recorder,
+            confirmations: ConfirmationTracker::new().with_settlement(commitments.accounting),
+            session_id: temp_session_id,
+            session_db: None,
+            trade_store: None,
+            trade_webhooks: None,
+            trade_traces,
+            inflight,
+            intents,
+            control: Arc::new(TradingControl::new()),
+            quarantine: None,
+            postmortems: None,
+            replication: Arc::new(Replication::new(None, None)),
             jito_client,
+            feature_flags,
             alt_manager,
             strategies,
+            strategy_settings,
             is_running: true,
             dry_run,
             rpc_url: config.solana_rpc_url.clone(),
             max_price_age_seconds: config.max_price_age_seconds,
+            projection_latency: execution_deadline(config).unwrap_or(Duration::from_secs(1)),
+            price_snapshots: config.price_snapshot_dir.as_deref().map(PriceSnapshots::new),
             metrics,
             alert_manager,
+            fx: SharedFxRate::default(),
+            risk_limits,
             system_health,
-            event_bus: Arc::new(EventBus::new(1000)),
+            event_bus,
             consecutive_errors: 0,
             rpc_rate_limiter,
+            rpc_batcher,
             jupiter_rate_limiter,
             config_manager,
         }
@@ -295,813 +817,5315 @@ impl BotState {
     
 
 
-    /// Check risk parameters and calculate position size
-    async fn check_risk_and_size(&self, opp: &solana_arb_core::ArbitrageOpportunity) -> (bool, TradeDecision, String) {
-        let optimal_size = self.risk_manager.calculate_position_size(
-            &opp.pair.symbol(),
+    /// Size to trade `opp` at: the size with the highest expected profit on
+    /// the route's sampled impact curve, if one is cached, otherwise the
+    /// risk manager's edge-scaled size; a manual trade's own size. Routes slipping more than modeled
+    /// are capped below the position limit.
+    fn optimal_size(&self, opp: &solana_arb_core::ArbitrageOpportunity) -> Decimal {
+        let pair = opp.pair.symbol();
+        // A manual trade's size is the operator's, up to the position limit
+        let manual = opp
+            .recommended_size
+            .filter(|_| opp.source == OpportunitySource::Manual)
+            .map(|size| size.min(self.risk_manager.position_limit(&pair)));
+        let size = manual.unwrap_or_else(|| {
+            let sampled = self.impact_curves.get(opp).and_then(|curve| {
+                curve.best_size(opp.net_profit_pct, self.risk_manager.position_limit(&pair))
+            });
+            sampled.unwrap_or_else(|| {
+                self.risk_manager.calculate_position_size(
+                    &pair,
+                    opp.net_profit_pct,
+                    Decimal::from(10000), // Assume high liquidity for now or get from opp
+                )
+            })
+        });
+        let size = self
+            .size_feedback
+            .cap(opp, size, self.risk_manager.position_limit(&pair));
+        self.cap_flash_loan_size(opp, size)
+    }
+
+    /// Sample the quoted price impact along `opp`'s route unless a fresh
+    /// curve is cached, so `optimal_size` can size against it
+    async fn sample_impact_curve(&self, opp: &solana_arb_core::ArbitrageOpportunity) {
+        let Some(executor) = self.executor.as_ref().filter(|_| self.size_search_steps > 0) else {
+            return;
+        };
+        if self.impact_curves.get(opp).is_some() {
+            return;
+        }
+        let limit = self.risk_manager.position_limit(&opp.pair.symbol());
+        let curve = impact::search(
             opp.net_profit_pct,
-            Decimal::from(10000), // Assume high liquidity for now or get from opp
-        );
+            self.size_search_min_usd,
+            limit,
+            self.size_search_steps,
+            |size| executor.round_trip_impact_pct(opp, size),
+        )
+        .await;
+        match curve {
+            Ok(curve) => {
+                debug!(
+                    "📐 Impact curve for {} {} -> {}: best ${} of ${} limit at {}% edge",
+                    opp.pair,
+                    opp.buy_dex,
+                    opp.sell_dex,
+                    curve.best_size(opp.net_profit_pct, limit).unwrap_or_default(),
+                    limit,
+                    opp.net_profit_pct
+                );
+                self.impact_curves.insert(opp, curve);
+            }
+            Err(e) => debug!("Cannot sample price impact for {}: {}", opp.pair, e),
+        }
+    }
+
+    /// Cap a size that would be funded by a flash loan at the configured
+    /// fraction of the reserve's available liquidity. A reserve that cannot
+    /// lend that much brings the size down to the flash loan threshold, where
+    /// the trade runs on own capital instead. Unknown liquidity leaves the
+    /// size as is.
+    fn cap_flash_loan_size(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) -> Decimal {
+        let capped = ExecutionModeSelector::default().cap_size(&self.mode_inputs(opp, size));
+        if capped < size {
+            debug!("Flash loan size ${} capped to ${} by the {} reserve", size, capped, opp.pair.base);
+        }
+        capped
+    }
+
+    /// What the execution mode of a trade of `size` in `opp` depends on
+    fn mode_inputs(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) -> ModeInputs {
+        ModeInputs {
+            size_usd: size,
+            flash_loans_enabled: self.feature_flags.is_enabled(feature_flags::FLASH_LOANS),
+            provider_available: self
+                .executor
+                .as_ref()
+                .is_some_and(|executor| executor.supports_flash_loan(&opp.pair.base)),
+            reserve_capacity_usd: resolve_mint(&opp.pair.base)
+                .and_then(|mint| self.flash_liquidity.get(&mint))
+                .map(|liquidity| liquidity.max_borrow(self.flash_loan_max_reserve_fraction) * opp.buy_price),
+        }
+    }
+
+    /// Select how a trade of `size` is funded and keep the choice for `/status`
+    async fn select_execution_mode(
+        &self,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+        size: Decimal,
+    ) -> ModeSelection {
+        let selection = ExecutionModeSelector::default().select(&self.mode_inputs(opp, size));
+        debug!("{} ${} runs {:?} ({:?})", opp.pair, size, selection.mode, selection.reason);
+        let mut health = self.system_health.write().await;
+        if health.execution_modes.len() >= RECENT_EXECUTION_MODES {
+            health.execution_modes.pop_front();
+        }
+        health.execution_modes.push_back(TradeModeRecord {
+            opportunity_id: opp.id,
+            pair: opp.pair.symbol(),
+            size_usd: size,
+            selected_at: Utc::now(),
+            selection,
+        });
+        selection
+    }
 
+    /// Check risk parameters and calculate position size
+    async fn check_risk_and_size(&self, opp: &solana_arb_core::ArbitrageOpportunity) -> (bool, TradeDecision, String) {
         let decision = self
             .risk_manager
-            .can_trade(&opp.pair.symbol(), optimal_size)
+            .can_trade_on_venues(&opp.pair.symbol(), self.optimal_size(opp), &[opp.buy_dex, opp.sell_dex])
             .await;
             
         (self.dry_run, decision, self.rpc_url.clone())
     }
 
-    /// Check if a flash loan is viable and return the quote if so
-    async fn check_flash_loan(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) -> Option<solana_arb_flash_loans::FlashLoanQuote> {
-        if let Some(mint) = resolve_mint(&opp.pair.base) {
-            // Assume borrowing base asset
-            match self.flash_loan_provider.get_quote(mint, size).await {
-                Ok(quote) => {
-                    // Simplified: fee is in base token.
-                    let fee_pct = (quote.fee / size) * Decimal::from(100);
+    /// Like `check_risk_and_size`, but commits the approved size to the
+    /// opportunity until `release_trade` is called for it
+    async fn reserve_risk_and_size(&mut self, opp: &solana_arb_core::ArbitrageOpportunity) -> (bool, TradeDecision, String) {
+        let optimal_size = self.optimal_size(opp);
+        let decision = self
+            .risk_manager
+            .reserve_trade(
+                &opp.id.to_string(),
+                &opp.pair.symbol(),
+                optimal_size,
+                &[opp.buy_dex, opp.sell_dex],
+            )
+            .await;
 
-                    if opp.net_profit_pct > fee_pct {
-                        info!(
-                            "⚡ Flash Loan Viable! Borrowing {} {} costs {} {} ({:.4}%) - Net edge: {:.4}%",
-                            size, opp.pair.base, quote.fee, opp.pair.base, fee_pct, opp.net_profit_pct - fee_pct
-                        );
-                        Some(quote)
-                    } else {
-                        debug!(
-                            "Flash Loan fee too high: {:.4}% > {:.4}% profit",
-                            fee_pct, opp.net_profit_pct
-                        );
-                        None
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to get flash loan quote: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
+        (self.dry_run, decision, self.rpc_url.clone())
+    }
+
+    /// Dry-run fill of `opp` at `size`. Phoenix legs are filled against the
+    /// latest book snapshot (none filled without one); AMM legs fill in full.
+    fn paper_trade(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) -> PaperTrade {
+        let book = |dex: DexType| {
+            (dex == DexType::Phoenix).then(|| self.phoenix.book(&opp.pair).unwrap_or_default())
+        };
+        let (buy_book, sell_book) = (book(opp.buy_dex), book(opp.sell_dex));
+        let trade = self
+            .paper_broker
+            .round_trip(opp, size, buy_book.as_ref(), sell_book.as_ref());
+        if trade.filled_size < size {
+            info!(
+                "🔵 [DRY RUN] Order book depth fills ${:.2} of ${} for {}",
+                trade.filled_size,
+                size,
+                opp.pair.symbol()
+            );
         }
+        trade
     }
 
-    /// Record trade outcome to all systems (Metrics, History, Risk, EventBus)
-    async fn record_trade_outcome(
+    /// `opportunities` with fast venues' legs projected over their quote age
+    /// and the execution latency, best net profit first
+    fn project_opportunities(
         &self,
-        opp: &solana_arb_core::ArbitrageOpportunity,
-        pair_symbol: &str,
-        size: Decimal,
-        outcome: &TradeResult,
-        start_time: Instant,
-    ) -> TradeOutcome { // Added return type
-        let execution_time_ms = start_time.elapsed().as_millis() as u64;
-        let success = outcome.success;
+        opportunities: &[solana_arb_core::ArbitrageOpportunity],
+    ) -> Vec<solana_arb_core::ArbitrageOpportunity> {
+        let market = self.market();
+        let Some(projector) = &market.price_projection else {
+            return opportunities.to_vec();
+        };
+        let now = Utc::now();
+        let mut projected: Vec<_> = opportunities
+            .iter()
+            .map(|opp| match projector.project(opp, self.projection_latency, now) {
+                Some(lowered) => {
+                    let cut_bps = (opp.net_profit_pct - lowered.net_profit_pct) * Decimal::from(100);
+                    self.metrics
+                        .projection_edge_cut_bps
+                        .observe(cut_bps.to_f64().unwrap_or_default());
+                    debug!(
+                        "Projected {} {} -> {}: net {}% -> {}%",
+                        opp.pair.symbol(),
+                        opp.buy_dex,
+                        opp.sell_dex,
+                        opp.net_profit_pct.round_dp(4),
+                        lowered.net_profit_pct.round_dp(4)
+                    );
+                    lowered
+                }
+                None => opp.clone(),
+            })
+            .collect();
+        projected.sort_by_key(|o| std::cmp::Reverse(o.net_profit_pct));
+        projected
+    }
 
-        // 1. Metrics
-        let metrics = &self.metrics;
-        if success {
-            metrics.trades_successful.inc();
-            metrics.trade_execution_time.observe(start_time.elapsed().as_secs_f64());
-            if let Some(profit_f64) = opp.net_profit_pct.to_f64() {
-                metrics.opportunity_profit.observe(profit_f64);
-            }
+    /// Minimum net profit (%) an opportunity needs, raised while the market
+    /// is dislocated
+    async fn required_profit_pct(&self, min_profit_bps: f64) -> Decimal {
+        let min_profit_pct = Decimal::from_f64(min_profit_bps).unwrap_or_default() / Decimal::from(100);
+        if self.regime.read().await.regime() == MarketRegime::Dislocated {
+            min_profit_pct * self.regime_edge_multiplier
         } else {
-            metrics.trades_failed.inc();
+            min_profit_pct
         }
+    }
 
-        // 2. EventBus
-        let profit_usd = if success {
-             opp.estimated_profit_usd.unwrap_or_default().to_f64().unwrap_or(0.0)
-        } else {
-             0.0
+    /// Fold this tick's prices into the spread index and pass the resulting
+    /// regime to metrics and strategies
+    async fn update_regime(&self, prices: &[solana_arb_core::PriceData]) {
+        let Some(index) = spread_index(prices) else {
+            return;
         };
-        
-        self.event_bus.publish(TradingEvent::TradeExecuted {
-            id: opp.id.to_string(),
-            pair: pair_symbol.to_string(),
-            success,
-            profit: profit_usd,
-            execution_time_ms,
-        });
-
-        // 3. History Recorder
-        let (est_profit, tx_sig, error_msg) = if success {
-             (
-                 (size * opp.net_profit_pct) / Decimal::from(100),
-                 outcome.signature.clone(),
-                 None
-             )
-        } else {
-             (
-                 Decimal::ZERO,
-                 None,
-                 outcome.error.clone().or(Some("Unknown error".to_string()))
-             )
+        let (previous, snapshot) = {
+            let mut tracker = self.regime.write().await;
+            let previous = tracker.regime();
+            (previous, tracker.record(index))
         };
-        
-        self.history_recorder.record_trade(
-            opp,
-            size,
-            est_profit,
-            success,
-            tx_sig,
-            error_msg,
-            false,
-        );
 
-        // 4. Return outcome for Risk Manager
-        TradeOutcome {
-            timestamp: Utc::now(),
-            pair: pair_symbol.to_string(),
-            profit_loss: est_profit,
-            was_successful: success,
+        self.metrics.spread_index.set(snapshot.index_bps);
+        self.metrics
+            .market_dislocated
+            .set(i64::from(snapshot.regime == MarketRegime::Dislocated));
+        if snapshot.regime != previous {
+            match snapshot.regime {
+                MarketRegime::Dislocated => warn!(
+                    "🌪️ Market dislocated: spread index {:.1} bps vs baseline {:.1} bps, requiring {}x edge",
+                    snapshot.index_bps,
+                    snapshot.baseline_bps.unwrap_or_default(),
+                    self.regime_edge_multiplier
+                ),
+                MarketRegime::Normal if previous == MarketRegime::Dislocated => info!(
+                    "Market regime back to normal: spread index {:.1} bps",
+                    snapshot.index_bps
+                ),
+                _ => {}
+            }
+        }
+        for strategy in &self.strategies {
+            strategy.on_regime(&snapshot);
         }
     }
-}
 
-/// Main trading loop that orchestrates price collection, opportunity detection, and execution.
-///
-/// Runs indefinitely until a stop signal is received or a critical error occurs.
-async fn run_trading_loop(state: Arc<RwLock<BotState>>, pairs: Vec<TokenPair>) {
-    info!("🤖 Trading bot started");
+    /// Push the per-venue exposure breakdown to metrics and system health
+    async fn publish_venue_exposure(&self) {
+        let rate = self.fx.get();
+        let mut breakdown = HashMap::new();
+        for dex in DexType::all() {
+            let exposure = self
+                .risk_manager
+                .venue_exposure_for(*dex)
+                .to_f64()
+                .unwrap_or(0.0);
+            self.metrics
+                .venue_exposure
+                .with_label_values(&[dex.display_name()])
+                .set(exposure);
+            self.metrics
+                .venue_exposure_accounting
+                .with_label_values(&[dex.display_name(), rate.currency.as_str()])
+                .set(rate.from_usd_f64(exposure));
+            if exposure > 0.0 {
+                breakdown.insert(dex.display_name().to_string(), exposure);
+            }
+        }
 
-    // Publish startup event
-    {
-        let s = state.read().await;
-        s.event_bus.publish(TradingEvent::SystemStarted {
-            mode: if s.dry_run { "dry-run".to_string() } else { "live".to_string() },
-        });
+        let mut health = self.system_health.write().await;
+        health.venue_exposure = breakdown;
+        health.committed_capital = self.risk_manager.committed_capital().to_f64().unwrap_or(0.0);
     }
 
-    // Spawn event logger subscriber
-    {
-        let mut event_rx = state.read().await.event_bus.subscribe();
-        tokio::spawn(async move {
-            while let Ok(event) = event_rx.recv().await {
-                match &event {
-                    TradingEvent::TradeExecuted { id, success, profit, .. } => {
-                        if *success {
-                            tracing::info!(id, profit, "📈 Event: trade executed");
-                        } else {
-                            tracing::warn!(id, "📉 Event: trade failed");
-                        }
-                    }
-                    TradingEvent::CircuitBreakerStateChanged { new_state, .. } => {
-                        tracing::warn!(state = %new_state, "⚡ Event: circuit breaker changed");
-                    }
-                    TradingEvent::EmergencyStop { reason } => {
-                        tracing::error!(reason, "🛑 Event: EMERGENCY STOP");
-                    }
-                    _ => {
-                        tracing::debug!(event = ?event, "Event received");
-                    }
-                }
-            }
-        });
+    /// Feed live trade outcomes into venue health, updating Jupiter route
+    /// exclusions when a venue becomes unhealthy or recovers
+    async fn record_venue_results(&mut self, venues: &[DexType], success: bool) {
+        let before = self.route_policy.route_filter();
+        for dex in venues {
+            self.route_policy.record_result(*dex, success);
+        }
+        if self.route_policy.route_filter() != before {
+            self.refresh_route_filter().await;
+        }
+    }
+
+    /// Push the effective route filter to the executor, history and /status
+    async fn refresh_route_filter(&self) {
+        let filter = self.route_policy.route_filter();
+        if let Some(executor) = &self.executor {
+            executor.set_route_filter(filter.clone());
+        }
+        self.history_recorder
+            .set_excluded_dexes(filter.exclude_dexes.clone());
+        self.system_health.write().await.route_filter = filter;
     }
 
-    let mut tick = 0u64;
-    let mut last_balance_check = Instant::now();
+    /// Read guard on the market state; never hold it across an await
+    fn market(&self) -> std::sync::RwLockReadGuard<'_, MarketState> {
+        self.market.read().unwrap_or_else(|e| e.into_inner())
+    }
 
-    loop {
-        // 1. Check Kill Switch
-        if std::path::Path::new(".kill").exists() {
-            let state = state.read().await;
-            state.alert_manager.send_critical("🛑 Kill switch (.kill) detected - shutting down").await;
-            info!("Kill switch file detected - graceful shutdown");
-            
-            // Close all positions logic could go here
-            
-            // Update health
-            let mut health = state.system_health.write().await;
-            health.is_running = false;
-            
-            break;
+    /// Jito client, if the `jito` flag is currently enabled
+    fn jito(&self) -> Option<&JitoClient> {
+        self.feature_flags
+            .is_enabled(feature_flags::JITO)
+            .then_some(&self.jito_client)
+    }
+
+    /// Latest SOL price in USD from the detector, averaged across venues
+    fn sol_price_usd(&self) -> Decimal {
+        let prices: Vec<Decimal> = self
+            .market()
+            .detector
+            .get_prices()
+            .iter()
+            .filter(|((pair, _), _)| pair.base == "SOL" && pair.quote == "USDC")
+            .map(|(_, price)| price.mid_price)
+            .collect();
+        if prices.is_empty() {
+            // Approximation used elsewhere until a price is available
+            Decimal::from(150)
+        } else {
+            prices.iter().sum::<Decimal>() / Decimal::from(prices.len())
         }
+    }
 
-        // 2. Wrap main logic in async block for error handling
-        let loop_result = async {
-            // Check if still running (internal state)
-            {
-                let state = state.read().await;
-                if !state.is_running {
-                    return Ok::<_, anyhow::Error>(false); // Stop signal
-                }
-                
-                // Fetch dynamic config
-                let dynamic_config = state.config_manager.get().await;
-                if !dynamic_config.trading.enabled {
-                    info!("⏸️ Trading disabled via dynamic config. Sleeping...");
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                    return Ok(true);
-                }
-            }
+    /// USD price of `token` from the detector: one for USD stablecoins,
+    /// otherwise its USDC mid price averaged across venues
+    fn token_price_usd(&self, token: &str) -> Option<Decimal> {
+        if matches!(token, "USDC" | "USDT") {
+            return Some(Decimal::ONE);
+        }
+        self.pair_mid_price(&format!("{}/USDC", token))
+    }
 
-            tick += 1;
+    /// Mid price of `pair` from the detector, averaged across venues
+    fn pair_mid_price(&self, pair: &str) -> Option<Decimal> {
+        let prices: Vec<Decimal> = self
+            .market()
+            .detector
+            .get_prices()
+            .iter()
+            .filter(|((p, _), _)| p.symbol() == pair)
+            .map(|(_, price)| price.mid_price)
+            .collect();
+        (!prices.is_empty()).then(|| prices.iter().sum::<Decimal>() / Decimal::from(prices.len()))
+    }
 
-            // Every 10 ticks, log status
-            if tick.is_multiple_of(10) {
-                let state = state.read().await;
-                let status = state.risk_manager.status().await;
-                info!(
-                    "📊 Status - Exposure: ${:.2}, VaR (95%): ${:.2}, P&L: ${:.2}, Trades: {}, Paused: {}",
-                    status.total_exposure,
-                    status.portfolio_var,
-                    status.daily_pnl,
-                    status.trades_today,
-                    status.is_paused
-                );
-                
-                // Update Health
-                let mut health = state.system_health.write().await;
-                health.circuit_breaker_state = if status.is_paused { "Open".to_string() } else { "Closed".to_string() };
-                health.total_trades = status.trades_today as u64;
-            }
-
-            let start = std::time::Instant::now();
-
-            // Collect prices
-            let recent_prices = match collect_prices(&state, &pairs).await {
-                Ok(p) => {
-                    // Reset consecutive errors on success
-                    state.write().await.consecutive_errors = 0;
-                    p
-                }
+    /// Assemble the post-mortem of a losing trade in the background and
+    /// raise a critical alert linking it
+    fn spawn_postmortem(
+        &self,
+        postmortems: Arc<Postmortems>,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+        size: Decimal,
+        profit_loss: Decimal,
+        outcome: &TradeResult,
+    ) {
+        let latest_prices: Vec<_> = self
+            .market()
+            .detector
+            .get_prices()
+            .values()
+            .filter(|p| p.pair == opp.pair)
+            .cloned()
+            .collect();
+        let alert_manager = self.alert_manager.clone();
+        let (opp, outcome) = (opp.clone(), outcome.clone());
+        let headline = format!(
+            "🩻 {} trade {} lost {} after fees",
+            opp.pair,
+            outcome.signature.as_deref().unwrap_or_default(),
+            alert_manager.money((-profit_loss).to_f64().unwrap_or_default())
+        );
+        warn!("{}. Assembling post-mortem.", headline);
+        tokio::spawn(async move {
+            let message = match postmortems
+                .capture(opp, size, profit_loss, outcome, latest_prices)
+                .await
+            {
+                Ok(path) => format!(
+                    "{}. Post-mortem: {} (GET /postmortems/{})",
+                    headline,
+                    path.display(),
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ),
                 Err(e) => {
-                    return Err(anyhow::anyhow!("Failed to collect prices: {}", e));
+                    error!("Failed to write post-mortem: {:#}", e);
+                    format!("{}. Post-mortem failed: {}", headline, e)
                 }
             };
+            alert_manager.send_critical(&message).await;
+        });
+    }
 
-            {
-                let state = state.read().await;
-                state
-                    .metrics
-                    .price_fetch_latency
-                    .observe(start.elapsed().as_secs_f64());
-            }
-
-            // Find and evaluate opportunities
-            let opportunities = {
-                let state = state.read().await;
-                let mut opps = state.detector.find_all_opportunities();
-                let _paths = state.path_finder.find_all_profitable_paths();
-
-                state
-                    .metrics
-                    .opportunities_detected
-                    .inc_by(opps.len() as u64);
-                
-                // Execute Strategies
-                for strategy in &state.strategies {
-                    if let Ok(strategy_opps) = strategy.analyze(&recent_prices).await {
-                         opps.extend(strategy_opps);
-                    }
-                }
-                opps
-            };
+    /// Approximate memory held by each long-lived buffer and cache
+    async fn memory_report(&self) -> Vec<(String, MemoryUsage)> {
+        let mut report = vec![
+            ("prices".to_string(), self.market().detector.memory_usage()),
+            ("paths".to_string(), self.market().path_finder.memory_usage()),
+            ("daily_trades".to_string(), self.risk_manager.memory_usage()),
+            (
+                "var_returns".to_string(),
+                self.risk_manager.return_archive.memory_usage(),
+            ),
+            ("event_journal".to_string(), self.event_bus.memory_usage()),
+            ("trade_traces".to_string(), self.trade_traces.memory_usage()),
+            ("confirmations".to_string(), self.confirmations.memory_usage()),
+            ("impact_curves".to_string(), self.impact_curves.memory_usage()),
+        ];
+        if let Some(incidents) = self.alert_manager.incidents() {
+            report.push(("incidents".to_string(), incidents.memory_usage()));
+        }
+        if let Some(postmortems) = &self.postmortems {
+            report.push((
+                "postmortem_events".to_string(),
+                postmortems.events().memory_usage(),
+            ));
+        }
+        if let Some(webhooks) = &self.trade_webhooks {
+            report.push(("webhook_retries".to_string(), webhooks.memory_usage()));
+        }
+        for strategy in &self.strategies {
+            report.push((
+                format!("strategy:{}", strategy.name()),
+                strategy.memory_usage().await,
+            ));
+        }
+        report
+    }
 
-            if !opportunities.is_empty() {
-                let state_read = state.read().await;
-                let mut health = state_read.system_health.write().await;
-                health.last_opportunity_time = Some(Instant::now());
-            }
+    /// Spot inventory per Drift perp market, for the hedger
+    fn spot_exposures(&self) -> Vec<SpotExposure> {
+        self.drift
+            .markets()
+            .into_iter()
+            .filter_map(|market| {
+                let pair = format!("{}/USDC", market);
+                let price = self.pair_mid_price(&pair).filter(|p| *p > Decimal::ZERO)?;
+                let spot_usd = self
+                    .risk_manager
+                    .positions()
+                    .iter()
+                    .filter(|(p, _)| p.split('/').next() == Some(market.as_str()))
+                    .map(|(_, size)| *size)
+                    .sum();
+                // The tightest venue is where the inventory would be worked off
+                let rebalance_cost_bps = self
+                    .market()
+                    .detector
+                    .get_prices()
+                    .iter()
+                    .filter(|((p, _), _)| p.symbol() == pair)
+                    .map(|(_, quote)| quote.spread_percentage() * Decimal::from(100))
+                    .min()
+                    .unwrap_or_default();
+                Some(SpotExposure {
+                    market,
+                    spot_usd,
+                    price,
+                    rebalance_cost_bps,
+                })
+            })
+            .collect()
+    }
 
-            // Execute best opportunity
-            for opp in opportunities.iter().take(1) {
-                // ... (Execution logic same as before, calling execute_trade)
-                 let should_execute = {
-                    let state = state.read().await;
-                    let config = state.config_manager.get().await;
-                    let min_profit_bps = Decimal::from_f64(config.trading.min_profit_bps).unwrap_or_default();
-                    let min_profit_pct = min_profit_bps / Decimal::from(100);
+    /// Apply `risk.max_total_exposure` from the dynamic config if it changed.
+    /// Lowering it below current exposure schedules an unwind.
+    async fn apply_exposure_limit(&mut self) {
+        let Some(limit) = self
+            .config_manager
+            .get()
+            .await
+            .risk
+            .max_total_exposure
+            .and_then(Decimal::from_f64)
+        else {
+            return;
+        };
+        let previous = self.risk_limits.max_total_exposure;
+        if limit == previous {
+            return;
+        }
 
-                    if opp.net_profit_pct < min_profit_pct {
-                         debug!("Skipping opportunity: Profit {}% < Min {}%", opp.net_profit_pct, min_profit_pct);
-                        false
-                    } else {
-                        let optimal_size = state.risk_manager.calculate_position_size(
-                            &opp.pair.symbol(),
-                            opp.net_profit_pct,
-                            Decimal::from(10000),
-                        );
-                        let decision = state.risk_manager.can_trade(&opp.pair.symbol(), optimal_size).await;
-                        matches!(decision, TradeDecision::Approved { .. } | TradeDecision::Reduced { .. })
-                    }
-                };
+        let rate = self.fx.get();
+        info!("Total exposure limit {} → {} {}", previous, limit, rate.currency);
+        self.risk_limits.max_total_exposure = limit;
+        self.risk_manager.set_max_total_exposure(rate.to_usd(limit).round_dp(2));
+        self.publish_unwind_status().await;
+    }
 
-                if should_execute {
-                    execute_trade(&state, opp).await;
-                }
-            }
+    /// Convert the configured money limits to USD at the current rate of
+    /// the accounting currency and publish the rate. A weaker currency
+    /// lowers the limits, which can schedule an unwind.
+    async fn apply_fx(&mut self) {
+        let rate = self.fx.get();
+        self.metrics
+            .fx_usd_per_unit
+            .with_label_values(&[rate.currency.as_str()])
+            .set(rate.usd_per_unit.to_f64().unwrap_or(0.0));
+        self.metrics
+            .fx_rate_age_seconds
+            .set(rate.age_secs(Utc::now()).max(0) as f64);
 
-            // Balance Check
-            if last_balance_check.elapsed() > Duration::from_secs(600) {
-                 last_balance_check = Instant::now();
-                 // Logic to check balance
-                 let (rpc_url, pubkey_str, alert_manager) = {
-                     let state = state.read().await;
-                     (state.rpc_url.clone(), state.wallet.pubkey(), state.alert_manager.clone())
-                 };
-                 
-                 // Spawn check
-                 let state_clone = state.clone();
-                 tokio::spawn(async move {
-                     use solana_rpc_client::nonblocking::rpc_client::RpcClient;
-                     use solana_sdk::pubkey::Pubkey;
-                     let client = RpcClient::new(rpc_url);
-                     if let Ok(pubkey) = Pubkey::from_str(&pubkey_str) {
-                         if let Ok(balance) = client.get_balance(&pubkey).await {
-                             let balance_sol = balance as f64 / 1_000_000_000.0;
-                             
-                             // Get system_health Arc and drop state lock
-                             let system_health = {
-                                 let state = state_clone.read().await;
-                                 state.system_health.clone()
-                             };
+        let limits = self.risk_limits.map(|limit| rate.to_usd(limit).round_dp(2));
+        if limits == self.risk_manager.money_limits() {
+            return;
+        }
+        debug!("Risk limits at {} USD/{}: {:?}", rate.usd_per_unit, rate.currency, limits);
+        self.risk_manager.set_money_limits(limits);
+        self.publish_unwind_status().await;
+        self.publish_venue_exposure().await;
+    }
 
-                             // Update health
-                             {
-                                 let mut h = system_health.write().await;
-                                 // Approximation: 1 SOL = $150 (should fetch real price)
-                                 h.balance_usd = balance_sol * 150.0; 
-                             }
+    async fn publish_unwind_status(&self) {
+        let status = self
+            .risk_manager
+            .unwind
+            .status(self.risk_manager.total_exposure());
+        self.system_health.write().await.unwind = status;
+    }
 
-                             if balance_sol < 0.1 {
-                                 alert_manager.send_critical(&format!("⚠️ Low balance: {:.4} SOL", balance_sol)).await;
-                             }
-                         }
-                     }
-                 });
-            }
+    /// Execute one housekeeping trade, selling `step.size` USD of the pair's
+    /// base token. Dry runs only log the trade.
+    async fn execute_unwind_step(&self, step: &UnwindStep) -> anyhow::Result<String> {
+        if self.dry_run {
+            info!("🔵 [DRY RUN] Would reduce {} by ${}", step.pair, step.size);
+            return Ok("dry_run".to_string());
+        }
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Err(anyhow::anyhow!("no executor or wallet for housekeeping trades"));
+        };
+        let (base, quote) = step
+            .pair
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("invalid pair {}", step.pair))?;
+        let price = self
+            .pair_mid_price(&step.pair)
+            .filter(|price| *price > Decimal::ZERO)
+            .ok_or_else(|| anyhow::anyhow!("no price for {}", step.pair))?;
 
-            Ok(true) // Continue running
-        }.await;
+        executor
+            .execute_reduction(
+                wallet,
+                &TokenPair::new(base, quote),
+                step.size / price,
+                step.size,
+                true,
+                &self.rpc_url,
+                self.jito(),
+            )
+            .await
+    }
 
-        match loop_result {
-            Ok(should_continue) => {
-                if !should_continue {
-                    break;
-                }
+    /// Carry out an operator command from Telegram and describe the result,
+    /// rendered with the Telegram channel's privacy settings
+    async fn command_reply(&self, chat_id: i64, command: Command) -> String {
+        let money = |usd: Decimal| self.alert_manager.money(usd.to_f64().unwrap_or_default());
+        let reply = match command {
+            Command::Status => {
+                let status = self.risk_manager.status().await;
+                let health = self.system_health.read().await;
+                let pause = match self.control.paused() {
+                    Some(pause) => format!(
+                        "paused by {} since {}{}",
+                        pause.by,
+                        pause.since.format("%H:%M UTC"),
+                        pause.reason.map(|r| format!(" ({})", r)).unwrap_or_default()
+                    ),
+                    None => "trading".to_string(),
+                };
+                format!(
+                    "Mode: {}\nState: {}\nCircuit breaker: {}\nExposure: {}\nTrades today: {}\nIn flight: {}",
+                    mode_label(self.watch_only, self.dry_run),
+                    pause,
+                    health.circuit_breaker_state,
+                    money(status.total_exposure),
+                    status.trades_today,
+                    self.inflight.snapshot().len()
+                )
             }
-            Err(e) => {
-                // Classify error severity for appropriate response
-                let err_str = e.to_string();
-                let is_retryable = err_str.contains("timeout") || err_str.contains("rate limit");
-
-                if err_str.contains("Daily loss") || err_str.contains("Circuit breaker") {
-                    error!("🔴 CRITICAL error in main loop: {}", e);
-                    let state_r = state.read().await;
-                    state_r.alert_manager.send_critical(&format!("CRITICAL: {}", e)).await;
-                } else if is_retryable {
-                    warn!("⚠️ Retryable error in main loop: {}", e);
+            Command::Pause(reason) => {
+                let by = format!("telegram:{}", chat_id);
+                if self.control.pause(&by, reason) {
+                    warn!("⏸️ Trading paused via Telegram (chat {})", chat_id);
+                    "⏸️ Trading paused".to_string()
                 } else {
-                    error!("❌ Error in main loop: {}", e);
+                    "Trading is already paused".to_string()
+                }
+            }
+            Command::Resume => match self.control.resume() {
+                Some(lifted) => {
+                    info!("▶️ Trading resumed via Telegram (chat {}, paused by {})", chat_id, lifted.by);
+                    "▶️ Trading resumed".to_string()
+                }
+                None => "Trading is not paused".to_string(),
+            },
+            Command::Pnl => {
+                let status = self.risk_manager.status().await;
+                format!(
+                    "P&L today: {} over {} trades",
+                    money(status.daily_pnl),
+                    status.trades_today
+                )
+            }
+            Command::LastTrades(n) => {
+                let path = std::path::Path::new(history_file_path(self.dry_run));
+                match telegram_commands::last_trades(path, n) {
+                    Ok(trades) if trades.is_empty() => "No trades recorded yet".to_string(),
+                    Ok(trades) => trades
+                        .iter()
+                        .map(|t| {
+                            let profit = t.profit_usd.parse().unwrap_or(Decimal::ZERO);
+                            format!(
+                                "{} {} {}→{} ${} {} {}",
+                                t.timestamp.get(..16).unwrap_or(&t.timestamp),
+                                t.pair,
+                                t.buy_dex,
+                                t.sell_dex,
+                                t.size_usd,
+                                if t.success { "✅" } else { "❌" },
+                                money(profit)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Err(e) => format!("Cannot read the trade history: {}", e),
                 }
+            }
+            Command::Help => telegram_commands::HELP.to_string(),
+        };
+        self.alert_manager.render_telegram(&reply)
+    }
 
-                // Track consecutive errors
-                let consecutive = {
-                    let mut state_w = state.write().await;
-                    state_w.consecutive_errors += 1;
-                    state_w.consecutive_errors
-                };
+    /// Sell the wallet's whole balance of `pair`'s base token for its quote
+    /// token. Returns the USD value sold and the signature, or `None` when
+    /// there was nothing worth selling. Dry runs only log it.
+    async fn execute_flatten(&self, pair: &TokenPair) -> anyhow::Result<Option<(Decimal, String)>> {
+        let symbol = pair.symbol();
+        let price = self
+            .pair_mid_price(&symbol)
+            .filter(|price| *price > Decimal::ZERO)
+            .ok_or_else(|| anyhow::anyhow!("no price for {}", symbol))?;
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Err(anyhow::anyhow!("no executor or wallet for housekeeping trades"));
+        };
+        let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey())?;
+        let balance = executor.token_balance(&owner, &pair.base, &self.rpc_url).await?;
+        let value = balance * price;
+        if value < Decimal::ONE {
+            return Ok(None);
+        }
+        if self.dry_run {
+            info!("🔵 [DRY RUN] Would flatten {} {} (${})", balance, pair.base, value.round_dp(2));
+            return Ok(Some((value, "dry_run".to_string())));
+        }
+        let signature = executor
+            .execute_reduction(wallet, pair, balance, value, true, &self.rpc_url, self.jito())
+            .await?;
+        Ok(Some((value, signature)))
+    }
 
-                // Exponential backoff based on consecutive error count
-                let backoff = Duration::from_secs(2u64.pow(consecutive.min(5)));
-                debug!(consecutive, backoff_secs = backoff.as_secs(), "Backing off");
-                tokio::time::sleep(backoff).await;
+    /// The wallet's balance of `pair`'s base token before a live standard
+    /// trade, when route repair is enabled. `None` if it cannot be read.
+    async fn repair_baseline(&self, pair: &TokenPair, mode: ExecutionMode) -> Option<Decimal> {
+        if self.route_repair.is_none() || self.dry_run || mode != ExecutionMode::Standard {
+            return None;
+        }
+        let (executor, wallet) = (self.executor.as_ref()?, self.wallet.as_ref()?);
+        let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey()).ok()?;
+        match executor.token_balance(&owner, &pair.base, &self.rpc_url).await {
+            Ok(balance) => Some(balance),
+            Err(e) => {
+                warn!("Cannot read {} balance for route repair: {}", pair.base, e);
+                None
             }
         }
-
-        tokio::time::sleep(Duration::from_millis(500)).await;
     }
-}
 
-/// Collects recent price data from all registered DEX providers.
-///
-/// Updates the local state with new prices, clears stale data, and updates
-/// strategy internal state.
-async fn collect_prices(
-    state: &Arc<RwLock<BotState>>,
-    pairs: &[TokenPair],
-) -> Result<Vec<solana_arb_core::PriceData>, Box<dyn std::error::Error>> {
-    let prices = {
-        let state = state.read().await;
+    /// Base token a failed trade of `opp` left in the wallet, against the
+    /// balance `base_before` it was submitted with
+    async fn stranded_after(
+        &self,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+        base_before: Decimal,
+        min_usd: Decimal,
+    ) -> anyhow::Result<Option<Stranded>> {
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Err(anyhow::anyhow!("no executor or wallet for housekeeping trades"));
+        };
+        let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey())?;
+        let after = executor.token_balance(&owner, &opp.pair.base, &self.rpc_url).await?;
+        let price = self
+            .pair_mid_price(&opp.pair.symbol())
+            .filter(|price| *price > Decimal::ZERO)
+            .unwrap_or(opp.sell_price);
+        Ok(Stranded::find(base_before, after, price, opp.buy_price, min_usd))
+    }
 
-        // Use parallel fetcher for all pairs at once!
-        let all_prices = state.price_fetcher.fetch_all_prices(pairs).await;
-        info!(
-            "💓 Parallel fetch complete — {} prices collected",
-            all_prices.len()
-        );
-        all_prices
-    };
+    /// Sell `stranded` back to the pair's quote token. Jupiter quotes the
+    /// route paying the most for it across the allowed venues.
+    async fn execute_repair(
+        &self,
+        pair: &TokenPair,
+        stranded: &Stranded,
+    ) -> anyhow::Result<String> {
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Err(anyhow::anyhow!("no executor or wallet for housekeeping trades"));
+        };
+        executor
+            .execute_reduction(
+                wallet,
+                pair,
+                stranded.base_amount,
+                stranded.value_usd,
+                true,
+                &self.rpc_url,
+                self.jito(),
+            )
+            .await
+    }
 
-    info!("📈 Received price data from DEX ({} prices)", prices.len());
+    /// Whether live trades are blocked because the SOL fee reserve is low.
+    /// Dry runs pay no fees and are never blocked.
+    fn fees_blocked(&self) -> bool {
+        !self.dry_run && self.fee_reserve.is_blocked()
+    }
 
-    // Update state
-    {
-        let mut state = state.write().await;
+    /// Whether the wallet is quarantined and nothing may be signed
+    fn quarantined(&self) -> bool {
+        self.quarantine.as_ref().is_some_and(|q| q.is_active())
+    }
 
-        // Update detector
-        state.detector.update_prices(prices.clone());
-        let max_age = state.max_price_age_seconds;
-        state.detector.clear_stale_prices(max_age);
+    /// Drop the wallet's keypairs from memory. Returns whether any were held.
+    fn revoke_keys(&mut self) -> bool {
+        let held = self.wallet.as_ref().is_some_and(|w| w.signer().is_some());
+        if let Some(wallet) = &mut self.wallet {
+            wallet.revoke();
+        }
+        if let Some(executor) = &mut self.executor {
+            executor.revoke_keys();
+        }
+        held
+    }
 
-        // Update pathfinder
-        state.path_finder.clear();
-        for price in &prices {
-            state.path_finder.add_price(price);
+    /// Reload the keypairs from `PRIVATE_KEY` after a quarantine is lifted
+    fn restore_keys(&mut self) -> anyhow::Result<String> {
+        let keypair = Wallet::env_keypair().ok_or_else(|| anyhow::anyhow!("PRIVATE_KEY is not set or invalid"))?;
+        let copy = Keypair::from_bytes(&keypair.to_bytes())?;
+        if let Some(executor) = &mut self.executor {
+            executor.restore_keys(copy);
         }
+        let wallet = self.wallet.as_mut().ok_or_else(|| anyhow::anyhow!("no wallet"))?;
+        wallet.restore(keypair);
+        Ok(wallet.pubkey())
+    }
 
-        // Update risk manager volatility tracking
-        state.risk_manager.update_prices(&prices);
+    /// Swap `usdc` USDC to SOL to refill the fee reserve. Dry runs only log it.
+    async fn execute_fee_topup(&self, usdc: Decimal) -> anyhow::Result<String> {
+        if self.dry_run {
+            info!("🔵 [DRY RUN] Would swap ${} USDC to SOL for fees", usdc);
+            return Ok("dry_run".to_string());
+        }
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Err(anyhow::anyhow!("no executor or wallet for a fee top-up"));
+        };
 
-        // Update strategies
-        for strategy in &state.strategies {
-            for price in &prices {
-                if let Err(e) = strategy.update_state(price).await {
-                    warn!("Strategy {} update failed: {}", strategy.name(), e);
-                }
+        // In wSOL mode, unwrap from the standing balance first. A USDC swap
+        // lands in the wSOL account and is unwrapped on the next check.
+        if self.wsol_target_lamports.is_some() {
+            let status = self.fee_reserve.status();
+            let needed = lamports(status.target_sol - status.balance_sol.unwrap_or_default());
+            let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey())?;
+            let wsol = executor.wsol_balance(&owner, &self.rpc_url).await?;
+            if needed > 0 && wsol >= needed {
+                info!("⛽ Unwrapping {} lamports of wSOL for fees", needed);
+                return executor
+                    .send_wallet_instructions(
+                        wallet,
+                        &self.rpc_url,
+                        &wsol::release_instructions(&owner, wsol, needed)?,
+                    )
+                    .await;
             }
         }
+        executor
+            .execute_reduction(
+                wallet,
+                &TokenPair::new(USDC_MINT, SOL_MINT),
+                usdc,
+                usdc,
+                true,
+                &self.rpc_url,
+                self.jito(),
+            )
+            .await
     }
 
-    validate_dex_coverage(&prices, pairs);
-
-    Ok(prices)
-}
-
-fn validate_dex_coverage(prices: &[solana_arb_core::PriceData], pairs: &[TokenPair]) {
-    let mut coverage: std::collections::HashMap<String, std::collections::HashSet<DexType>> =
-        std::collections::HashMap::new();
-
-    for price in prices {
-        coverage
-            .entry(price.pair.symbol())
-            .or_default()
-            .insert(price.dex);
+    /// Wrap native SOL up to the standing wSOL balance, keeping the fee
+    /// reserve's target unwrapped. No-op outside live wSOL mode.
+    async fn maintain_wsol(&self) -> anyhow::Result<()> {
+        let Some(target) = self.wsol_target_lamports.filter(|_| !self.dry_run) else {
+            return Ok(());
+        };
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return Ok(());
+        };
+        let owner = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey())?;
+        let native = executor.check_balance(wallet, &self.rpc_url).await?;
+        let wsol = executor.wsol_balance(&owner, &self.rpc_url).await?;
+        let amount = wsol::wrap_amount(wsol, native, target, lamports(self.fee_reserve.status().target_sol));
+        // Top-ups below 1% of the target are not worth a transaction
+        if amount == 0 || amount < target / 100 {
+            return Ok(());
+        }
+        let signature = executor
+            .send_wallet_instructions(wallet, &self.rpc_url, &wsol::wrap_instructions(&owner, amount)?)
+            .await?;
+        info!("🌯 Wrapped {} lamports into the standing wSOL balance ({})", amount, signature);
+        Ok(())
     }
 
-    for pair in pairs {
-        let seen = coverage.get(&pair.symbol());
-        let missing: Vec<_> = DexType::all()
-            .iter()
-            .filter(|dex| seen.is_none_or(|set| !set.contains(dex)))
-            .collect();
-
-        if !missing.is_empty() {
-            let missing_labels: Vec<_> = missing.iter().map(|dex| dex.display_name()).collect();
-            warn!(
-                "⚠️ Missing DEX coverage for {}: {}",
-                pair,
-                missing_labels.join(", ")
-            );
+    /// Unwrap the whole standing wSOL balance (on shutdown)
+    async fn unwrap_wsol(&self) {
+        if self.wsol_target_lamports.is_none() || self.dry_run {
+            return;
+        }
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return;
+        };
+        let Ok(owner) = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey()) else {
+            return;
+        };
+        match executor.wsol_balance(&owner, &self.rpc_url).await {
+            Ok(0) => {}
+            Ok(balance) => {
+                let result = match wsol::unwrap_instructions(&owner) {
+                    Ok(instructions) => {
+                        executor.send_wallet_instructions(wallet, &self.rpc_url, &instructions).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(signature) => info!("🌯 Unwrapped {} lamports of wSOL ({})", balance, signature),
+                    Err(e) => error!("Failed to unwrap wSOL on shutdown: {}", e),
+                }
+            }
+            Err(e) => error!("Failed to read wSOL balance on shutdown: {}", e),
         }
     }
-}
-
-/// Executes a detected arbitrage opportunity.
-///
-/// This function handles:
-/// 1. Risk checks and position sizing
-/// 2. Flash loan viability analysis
-/// 3. Dry-run simulation (if enabled)
-/// 4. Actual trade execution via the Executor
-/// 5. Outcome recording (Metrics, History, Risk Manager)
-async fn execute_trade(state: &Arc<RwLock<BotState>>, opp: &solana_arb_core::ArbitrageOpportunity) {
-    let start_time = std::time::Instant::now();
-    let pair_symbol = opp.pair.symbol();
 
-    // We need to release the read lock before acquiring write lock later,
-    // AND calling async execution which shouldn't hold locks if possible.
-    // However, Executor is stateless (HttpClient) so we can clone data needed.
+    /// Check balances, wSOL accounts, open transactions and lookup tables
+    /// against the chain. `None` without a wallet.
+    async fn reconcile(&self) -> Option<anyhow::Result<ReconciliationReport>> {
+        let (Some(executor), Some(wallet)) = (&self.executor, &self.wallet) else {
+            return None;
+        };
+        let client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new_with_commitment(
+            self.rpc_url.clone(),
+            solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+        );
+        let reconciler = Reconciler {
+            client: &client,
+            executor,
+            wallet,
+            rpc_url: &self.rpc_url,
+            alt_manager: &self.alt_manager,
+            intents: &self.intents,
+            snapshot_path: std::path::Path::new(reconciliation::DEFAULT_SNAPSHOT_PATH),
+            wsol_mode: self.wsol_target_lamports.is_some(),
+        };
+        Some(reconciler.run().await)
+    }
 
-    let (is_dry_run, decision, rpc_url) = {
-        let state = state.read().await;
-        state.check_risk_and_size(opp).await
-    };
+    /// Record wallet balances for the next startup's reconciliation (on shutdown)
+    async fn snapshot_wallet(&self) {
+        if self.dry_run {
+            return;
+        }
+        let Some(wallet) = &self.wallet else {
+            return;
+        };
+        let Ok(owner) = solana_sdk::pubkey::Pubkey::from_str(&wallet.pubkey()) else {
+            return;
+        };
+        let client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new_with_commitment(
+            self.rpc_url.clone(),
+            solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+        );
+        reconciliation::snapshot_on_shutdown(
+            &client,
+            &owner,
+            std::path::Path::new(reconciliation::DEFAULT_SNAPSHOT_PATH),
+        )
+        .await;
+    }
 
-    let size = match decision {
-        TradeDecision::Approved { size } => size,
-        TradeDecision::Reduced { new_size, reason } => {
-            info!("Trade size reduced: {}", reason);
-            new_size
+    /// Track a successful live trade until its transaction is finalized
+    fn track_confirmation(
+        &mut self,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+        size: Decimal,
+        signature: Option<&str>,
+        outcome: &TradeOutcome,
+    ) {
+        if self.dry_run || !outcome.was_successful {
+            return;
         }
-        TradeDecision::Rejected { reason } => {
-            debug!("Trade rejected: {}", reason);
+        let Some(signature) = signature else {
+            return;
+        };
+        // Jito submissions return a bundle id, which has no signature status
+        if solana_sdk::signature::Signature::from_str(signature).is_err() {
+            debug!("Not tracking finalization of {} (not a transaction signature)", signature);
             return;
         }
-    };
+        self.confirmations.track(TrackedTrade::new(
+            &opp.id.to_string(),
+            signature,
+            &outcome.pair,
+            opp.buy_dex.display_name(),
+            opp.sell_dex.display_name(),
+            size,
+            outcome.profit_loss,
+            outcome.timestamp,
+        ));
+    }
 
-    // Record attempt
-    {
-        let state = state.read().await;
-        state.metrics.trades_attempted.inc();
+    /// Record a finalized or rolled-back trade. A rollback removes the
+    /// trade's P&L from the risk manager and publishes `ChainReorg`.
+    fn resolve_confirmation(&mut self, resolution: Resolution) {
+        self.history_recorder
+            .record_commitment_update(&resolution.update());
+        match resolution {
+            Resolution::Finalized(trade) => {
+                debug!("Trade {} finalized ({})", trade.id, trade.signature);
+            }
+            Resolution::Reorged { trade, reason } => {
+                if self.risk_manager.revert_trade(&trade.pair, trade.recorded_at).is_none() {
+                    warn!("Reorged trade {} is no longer part of today's P&L", trade.id);
+                }
+                self.event_bus.publish(TradingEvent::ChainReorg {
+                    id: trade.id,
+                    pair: trade.pair,
+                    signature: trade.signature,
+                    reverted_pnl: trade.profit_usd.to_f64().unwrap_or(0.0),
+                    reason,
+                });
+            }
+        }
     }
 
-    // Check Flash Loan Viability
-    let _flash_loan_quote = {
-        let state_read = state.read().await;
-        state_read.check_flash_loan(opp, size).await
-    };
+    /// The blacklist entry blocking an opportunity's route, if any
+    async fn blacklisted(
+        &self,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+    ) -> Option<BlacklistEntry> {
+        self.blacklist
+            .read()
+            .await
+            .blocked(
+                &opp.pair.symbol(),
+                opp.buy_dex.display_name(),
+                opp.sell_dex.display_name(),
+            )
+            .cloned()
+    }
 
-    if is_dry_run {
-        // Simulate trade
-        info!(
-            "🔵 [DRY RUN] Would execute: Buy {} on {}, Sell on {} | Size: ${} | Profit: {}%",
-            pair_symbol, opp.buy_dex, opp.sell_dex, size, opp.net_profit_pct
-        );
+    /// Count a finalized or failed transaction towards its route's failure
+    /// streak, alerting on new blacklist entries. Trades dropped by a fork
+    /// say nothing about the route and are ignored.
+    async fn record_onchain_result(&self, resolution: &Resolution, failure: Option<&str>) {
+        let (trade, status) = match (resolution, failure) {
+            (Resolution::Finalized(trade), _) => (trade, trade.commitment.as_str()),
+            (Resolution::Reorged { trade, .. }, Some(_)) => (trade, "failed"),
+            (Resolution::Reorged { trade, .. }, None) => (trade, solana_arb_core::history::COMMITMENT_REORGED),
+        };
+        self.trade_traces.record_onchain(&trade.id, status, failure);
 
-        // Fetch quote simulation (optional)
-        {
-            let state_read = state.read().await;
-            if let Err(e) = state_read
-                .executor
-                .execute(&state_read.wallet, opp, size, false, &rpc_url, None)
-                .await
-            {
-                warn!("Simulation execution failed: {}", e);
+        let mut blacklist = self.blacklist.write().await;
+        let added = match (resolution, failure) {
+            (Resolution::Finalized(trade), _) => {
+                blacklist.record_success(&trade.pair, &trade.buy_dex, &trade.sell_dex);
+                return;
+            }
+            (Resolution::Reorged { trade, .. }, Some(err)) => {
+                blacklist.record_failure(&trade.pair, &trade.buy_dex, &trade.sell_dex, err)
             }
+            (Resolution::Reorged { .. }, None) => return,
+        };
+        if added.is_empty() {
+            return;
         }
+        if let Err(e) = blacklist.save() {
+            warn!("Failed to save blacklist: {}", e);
+        }
+        drop(blacklist);
 
-        // Record simulation history
-        {
-            let state_read = state.read().await;
-            let est_profit = (size * opp.net_profit_pct) / Decimal::from(100);
+        for entry in added {
+            let message = format!(
+                "⛔ Blacklisted {} after {} consecutive on-chain failures (until {}): {}",
+                entry.label(),
+                entry.failures,
+                entry.expires_at.format("%Y-%m-%d %H:%M UTC"),
+                entry.reason
+            );
+            warn!("{}", message);
+            let alert_manager = self.alert_manager.clone();
+            tokio::spawn(async move { alert_manager.send_critical(&message).await });
+        }
+    }
+
+    /// Drop expired blacklist entries
+    async fn expire_blacklist(&self) {
+        let mut blacklist = self.blacklist.write().await;
+        if !blacklist.expire().is_empty() {
+            if let Err(e) = blacklist.save() {
+                warn!("Failed to save blacklist: {}", e);
+            }
+        }
+    }
+
+    /// Mark this run's session as ended in the session store
+    async fn end_session(&self) {
+        if let Some(db) = &self.session_db {
+            match db.end_session(&self.session_id, Utc::now()).await {
+                Ok(()) => info!("🗂️ Session {} closed", self.session_id),
+                Err(e) => warn!("Failed to close session {}: {}", self.session_id, e),
+            }
+        }
+    }
+
+    /// Check if a flash loan is viable and return the quote if so
+    async fn check_flash_loan(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) -> Option<solana_arb_flash_loans::FlashLoanQuote> {
+        if let Some(mint) = resolve_mint(&opp.pair.base) {
+            // Assume borrowing base asset
+            match self.flash_loan_router.quote(mint, size).await {
+                Ok(quote) => {
+                    // Simplified: fee is in base token.
+                    let fee_pct = (quote.fee / size) * Decimal::from(100);
+
+                    if opp.net_profit_pct > fee_pct {
+                        info!(
+                            "⚡ Flash Loan Viable! Borrowing {} {} via {} costs {} {} ({:.4}%) - Net edge: {:.4}%",
+                            size, opp.pair.base, quote.provider, quote.fee, opp.pair.base, fee_pct, opp.net_profit_pct - fee_pct
+                        );
+                        self.metrics
+                            .flash_loan_provider_selected
+                            .with_label_values(&[&quote.provider])
+                            .inc();
+                        Some(quote)
+                    } else {
+                        debug!(
+                            "Flash Loan fee too high: {:.4}% > {:.4}% profit",
+                            fee_pct, opp.net_profit_pct
+                        );
+                        None
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to get flash loan quote: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Compare the quoted price of a trade against the detected one and flag
+    /// slippage beyond the tolerance its quote was requested with
+    fn check_quote_slippage(&self, opp: &solana_arb_core::ArbitrageOpportunity, size: Decimal) {
+        let (Some(executor), Some(trace)) = (&self.executor, self.trade_traces.get(opp.id)) else {
+            return;
+        };
+        let Some(pct) = trace.diff().quote_price_slippage_pct else {
+            return;
+        };
+        // Negative slippage is worse than detected
+        let slippage_bps = (-pct * Decimal::from(100)).max(Decimal::ZERO);
+        self.metrics
+            .slippage_distribution
+            .observe(slippage_bps.to_f64().unwrap_or(0.0));
+        let allowed = executor.slippage_bps_for(&opp.pair, size);
+        if slippage_bps > Decimal::from(allowed) {
+            self.metrics.trades_slippage_exceeded.inc();
+            warn!(
+                "📉 {} quoted {} bps worse than detected, above its {} bps tolerance (${} trade)",
+                opp.pair,
+                slippage_bps.round_dp(1),
+                allowed,
+                size.round_dp(2)
+            );
+        }
+    }
+
+    /// Record trade outcome to all systems (Metrics, History, Risk, EventBus)
+    ///
+    /// `fee_usd` is this trade's share of a transaction fee that is accounted
+    /// separately (batched trades); it is deducted from the recorded profit.
+    async fn record_trade_outcome(
+        &self,
+        opp: &solana_arb_core::ArbitrageOpportunity,
+        pair_symbol: &str,
+        size: Decimal,
+        outcome: &TradeResult,
+        start_time: Instant,
+        fee_usd: Decimal,
+    ) -> TradeOutcome { // Added return type
+        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+        let success = outcome.success;
+
+        let realized = if success {
+            outcome.actual_profit - fee_usd
+        } else {
+            Decimal::ZERO
+        };
+        self.trade_traces.record_execution(
+            outcome,
+            fee_usd,
+            self.executor.as_ref().map(|e| e.priority_fee()),
+        );
+        self.check_quote_slippage(opp, size);
+        // Edge lost against the plan, before separately paid fees, feeds the
+        // per-route size limits
+        if success {
+            if let Some(trace) = self.trade_traces.get(opp.id) {
+                let modeled = trace.quote.and_then(|q| q.price_impact_pct).unwrap_or_default();
+                if let Some(plan) = trace.plan {
+                    let shortfall = plan.expected_profit_usd - outcome.actual_profit;
+                    self.size_feedback.record(opp, size, shortfall, modeled);
+                }
+            }
+        }
+        self.route_scoreboard
+            .write()
+            .await
+            .record_execution(opp, size, realized);
+
+        // Trade store keeps the opportunity behind the trade; the trade itself
+        // reaches it through the history recorder
+        if let Some(store) = &self.trade_store {
+            let store = store.clone();
+            let opp = opp.clone();
+            tokio::spawn(async move {
+                if let Err(e) = store.insert_opportunity(&opp).await {
+                    warn!("Failed to record opportunity in trade store: {}", e);
+                }
+            });
+        }
+
+        // Session store (fire and forget; latency-sensitive path)
+        if let Some(db) = &self.session_db {
+            let db = db.clone();
+            let session_id = self.session_id.clone();
+            let signature = outcome.signature.clone().unwrap_or_default();
+            let opp_id = opp.id;
+            let trade = TradeOutcome {
+                timestamp: outcome.executed_at,
+                pair: pair_symbol.to_string(),
+                profit_loss: outcome.actual_profit - fee_usd,
+                was_successful: success,
+            };
+            tokio::spawn(async move {
+                if let Err(e) = db
+                    .insert_trade(&trade, Some(opp_id), &signature, Some(&session_id), execution_time_ms)
+                    .await
+                {
+                    warn!("Failed to record trade in session store: {}", e);
+                }
+            });
+        }
+
+        // Accounting webhook for completed live trades
+        if let Some(webhooks) = self.trade_webhooks.as_ref().filter(|_| success && !self.dry_run) {
+            webhooks.emit(TradeWebhookPayload::new(
+                opp,
+                &self.session_id,
+                size,
+                outcome,
+                fee_usd,
+            ));
+        }
+
+        // Post-mortem of a landed live trade that lost too much
+        let profit_loss = outcome.actual_profit - fee_usd;
+        if let Some(postmortems) = self.postmortems.as_ref().filter(|p| {
+            !self.dry_run && outcome.signature.is_some() && p.should_capture(profit_loss)
+        }) {
+            self.spawn_postmortem(postmortems.clone(), opp, size, profit_loss, outcome);
+        }
+
+        // 1. Metrics
+        let metrics = &self.metrics;
+        metrics
+            .trades_by_source
+            .with_label_values(&[opp.source_label(), if success { "success" } else { "failed" }])
+            .inc();
+        if let Some(realized) = realized.to_f64() {
+            metrics.profit_by_source.with_label_values(&[opp.source_label()]).add(realized);
+        }
+        if success {
+            metrics.trades_successful.inc();
+            metrics.trade_execution_time.observe(start_time.elapsed().as_secs_f64());
+            if let Some(profit_f64) = opp.net_profit_pct.to_f64() {
+                metrics.opportunity_profit.observe(profit_f64);
+            }
+        } else {
+            metrics.trades_failed.inc();
+            match outcome.failure_kind {
+                Some(FailureKind::RouteMismatch) => metrics.trades_route_mismatch.inc(),
+                Some(FailureKind::AbortedDeadline) => metrics.trades_aborted_deadline.inc(),
+                Some(FailureKind::ComplianceVeto) => metrics.trades_compliance_veto.inc(),
+                Some(FailureKind::MaxLossExceeded) => metrics.trades_max_loss_exceeded.inc(),
+                Some(FailureKind::ProgramError) => {
+                    metrics.trades_program_error.inc();
+                    let message = format!(
+                        "❌ Program error on {} trade {}, not retried: {}",
+                        pair_symbol,
+                        opp.id,
+                        outcome.error.as_deref().unwrap_or("unknown error")
+                    );
+                    let alert_manager = self.alert_manager.clone();
+                    tokio::spawn(async move { alert_manager.send_critical(&message).await });
+                }
+                None => {}
+            }
+        }
+
+        // 2. EventBus
+        let profit_usd = if success {
+             (opp.estimated_profit_usd.unwrap_or_default() - fee_usd).to_f64().unwrap_or(0.0)
+        } else {
+             0.0
+        };
+
+        self.event_bus.publish(TradingEvent::TradeExecuted {
+            id: opp.id.to_string(),
+            pair: pair_symbol.to_string(),
+            success,
+            profit: profit_usd,
+            execution_time_ms,
+        });
+
+        // 3. History Recorder
+        let (est_profit, tx_sig, error_msg) = if success {
+             (
+                 (size * opp.net_profit_pct) / Decimal::from(100) - fee_usd,
+                 outcome.signature.clone(),
+                 None
+             )
+        } else {
+             (
+                 Decimal::ZERO,
+                 None,
+                 outcome.error.clone().or(Some("Unknown error".to_string()))
+             )
+        };
+
+        self.history_recorder.record_trade(
+            opp,
+            size,
+            est_profit,
+            success,
+            tx_sig,
+            error_msg,
+            outcome.simulated_profit,
+            outcome.failure_kind,
+            false,
+        );
+
+        // 4. Return outcome for Risk Manager
+        TradeOutcome {
+            timestamp: Utc::now(),
+            pair: pair_symbol.to_string(),
+            profit_loss: est_profit,
+            was_successful: success,
+        }
+    }
+}
+
+/// Starts the trading pipeline (price source, detection and execution, see
+/// [`pipeline`]) and runs housekeeping until a stop signal is received.
+async fn run_trading_loop(
+    state: Arc<RwLock<BotState>>,
+    pairs: Vec<TokenPair>,
+    discovery: Arc<RwLock<PoolDiscovery>>,
+    streams: Option<Arc<StreamingPriceAggregator>>,
+    poll_interval: Duration,
+    queue_depth: usize,
+) {
+    info!("🤖 Trading bot started");
+
+    // Publish startup event
+    {
+        let s = state.read().await;
+        s.event_bus.publish(TradingEvent::SystemStarted {
+            mode: mode_label(s.watch_only, s.dry_run).to_string(),
+        });
+    }
+
+    // Spawn event logger subscriber (also forwards critical risk events to alerts)
+    {
+        let (mut event_rx, alert_manager, risk_journal) = {
+            let s = state.read().await;
+            (s.event_bus.subscribe_as("event_logger"), s.alert_manager.clone(), s.risk_journal.clone())
+        };
+        tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                if let Some(journal) = &risk_journal {
+                    let entry = match &event {
+                        TradingEvent::CircuitBreakerStateChanged { new_state, .. } => {
+                            Some(risk_report::JournalEvent::Breaker { state: new_state.clone() })
+                        }
+                        TradingEvent::RiskLimitBreached { limit_type, current, max } => {
+                            Some(risk_report::JournalEvent::LimitBreached {
+                                limit_type: limit_type.clone(),
+                                current: *current,
+                                max: *max,
+                            })
+                        }
+                        _ => None,
+                    };
+                    if let Some(Err(e)) = entry.map(|entry| journal.record(entry)) {
+                        warn!("Failed to journal risk event: {}", e);
+                    }
+                }
+                match &event {
+                    TradingEvent::TradeExecuted { id, success, profit, .. } => {
+                        if *success {
+                            tracing::info!(id, profit, "📈 Event: trade executed");
+                        } else {
+                            tracing::warn!(id, "📉 Event: trade failed");
+                        }
+                    }
+                    TradingEvent::CircuitBreakerStateChanged { new_state, .. } => {
+                        tracing::warn!(state = %new_state, "⚡ Event: circuit breaker changed");
+                        if new_state == "Open" {
+                            alert_manager
+                                .send_critical("⚡ Circuit breaker OPEN - trading halted")
+                                .await;
+                        }
+                    }
+                    TradingEvent::RiskLimitBreached { limit_type, current, max } => {
+                        tracing::error!(limit_type, current, max, "🚨 Event: risk limit breached");
+                        alert_manager
+                            .send_critical(&format!(
+                                "🚨 Risk limit breached: {} ({} / {})",
+                                limit_type, alert_manager.money(*current), alert_manager.money(*max)
+                            ))
+                            .await;
+                    }
+                    TradingEvent::TradeStuck { id, pair, elapsed_secs } => {
+                        tracing::error!(id, pair, elapsed_secs, "⏳ Event: trade stuck");
+                        alert_manager
+                            .send_critical(&format!(
+                                "⏳ Trade {} on {} stuck for {}s",
+                                id, pair, elapsed_secs
+                            ))
+                            .await;
+                    }
+                    TradingEvent::ChainReorg { id, pair, signature, reverted_pnl, reason } => {
+                        tracing::error!(id, pair, signature, reverted_pnl, reason, "🔀 Event: chain reorg");
+                        alert_manager
+                            .send_critical(&format!(
+                                "🔀 Trade {} on {} rolled back by a fork ({}). Reverted P&L: {}. Tx: {}",
+                                id, pair, reason, alert_manager.money(*reverted_pnl), signature
+                            ))
+                            .await;
+                    }
+                    TradingEvent::CanaryStarted { reason, required_successes } => {
+                        tracing::warn!(reason, required_successes, "🐤 Event: canary mode armed");
+                    }
+                    TradingEvent::CanaryCompleted { successes } => {
+                        tracing::info!(successes, "🐤 Event: canary mode complete");
+                    }
+                    TradingEvent::UnwindStarted { limit, overage } => {
+                        tracing::warn!(limit, overage, "📉 Event: unwind started");
+                        alert_manager
+                            .send_info(&format!(
+                                "📉 Exposure is {} over the new {} limit - unwinding with housekeeping trades",
+                                alert_manager.money(*overage), alert_manager.money(*limit)
+                            ))
+                            .await;
+                    }
+                    TradingEvent::UnwindCompleted { reduced, steps } => {
+                        tracing::info!(reduced, steps, "📉 Event: unwind complete");
+                        alert_manager
+                            .send_info(&format!(
+                                "📉 Unwind complete - reduced {} in {} housekeeping trades",
+                                alert_manager.money(*reduced), steps
+                            ))
+                            .await;
+                    }
+                    TradingEvent::EmergencyStop { reason } => {
+                        tracing::error!(reason, "🛑 Event: EMERGENCY STOP");
+                    }
+                    _ => {
+                        tracing::debug!(event = ?event, "Event received");
+                    }
+                }
+            }
+        });
+    }
+
+    let metrics = state.read().await.metrics.clone();
+    let (events_tx, events_rx) = mpsc::channel(queue_depth.max(1));
+    let (candidates_tx, candidates_rx) = mpsc::channel(queue_depth.max(1));
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let halts = HaltGeneration::default();
+
+    // Config reloads and risk events join the price batches. Halting events
+    // invalidate queued candidates at once, ahead of the batches before them.
+    {
+        let mut bus = state.read().await.event_bus.subscribe_as("pipeline");
+        let events = events_tx.clone();
+        let halts = halts.clone();
+        tokio::spawn(async move {
+            while let Some(event) = bus.recv().await {
+                if pipeline::is_halting(&event) {
+                    halts.advance();
+                }
+                let Some(event) = PipelineEvent::from_bus(event) else {
+                    continue;
+                };
+                if events.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let detection = tokio::spawn(run_detection_stage(
+        state.clone(),
+        metrics.clone(),
+        events_rx,
+        candidates_tx,
+        halts.clone(),
+        shutdown_rx.clone(),
+    ));
+    let source = tokio::spawn(run_price_source(
+        state.clone(),
+        metrics.clone(),
+        pairs,
+        discovery,
+        streams,
+        events_tx,
+        shutdown_rx,
+        poll_interval,
+    ));
+
+    let execution = run_execution_stage(state.clone(), metrics.clone(), candidates_rx, halts);
+    let housekeeping = async {
+        let mut round = 0u64;
+        let mut last_balance_check = Instant::now();
+        let mut trading_day = Utc::now().date_naive();
+        let mut timer = tokio::time::interval(HOUSEKEEPING_INTERVAL);
+        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            timer.tick().await;
+            round += 1;
+
+            // 1. Check Kill Switch
+            if std::path::Path::new(".kill").exists() {
+                let state = metrics.timed_read(&state, "housekeeping").await;
+                state.alert_manager.send_critical("🛑 Kill switch (.kill) detected - shutting down").await;
+                info!("Kill switch file detected - graceful shutdown");
+
+                // Close all positions logic could go here
+
+                if let Err(e) = state.risk_manager.return_archive.save(std::path::Path::new(RETURN_ARCHIVE_PATH)) {
+                    warn!("Failed to save VaR return archive: {}", e);
+                }
+                state.unwrap_wsol().await;
+                state.snapshot_wallet().await;
+
+                // Update health
+                let mut health = state.system_health.write().await;
+                health.is_running = false;
+
+                break;
+            }
+
+            // Check if still running (internal state)
+            if !metrics.timed_read(&state, "housekeeping").await.is_running {
+                break;
+            }
+
+            // Failure injection flags (dry-run only)
+            {
+                let state = metrics.timed_read(&state, "housekeeping").await;
+                let cwd = std::path::Path::new(".");
+                if state.dry_run {
+                    for fault in fault_injection::pending_faults(cwd, true) {
+                        fault_injection::inject(&state, fault).await;
+                    }
+                } else if !fault_injection::pending_faults(cwd, false).is_empty() && round.is_multiple_of(10) {
+                    warn!("⚠️ Failure injection flags present but ignored in LIVE mode");
+                }
+            }
+
+            // Every 10 rounds, log status
+            if round.is_multiple_of(10) {
+                let state = metrics.timed_read(&state, "housekeeping").await;
+                let status = state.risk_manager.status().await;
+                info!(
+                    "📊 Status - Exposure: {}, VaR (95%): {}, P&L: {}, Trades: {}, Paused: {}",
+                    state.alert_manager.money(status.total_exposure.to_f64().unwrap_or_default()),
+                    state.alert_manager.money(status.portfolio_var.to_f64().unwrap_or_default()),
+                    state.alert_manager.money(status.daily_pnl.to_f64().unwrap_or_default()),
+                    status.trades_today,
+                    status.is_paused
+                );
+                if let Some(journal) = &state.risk_journal {
+                    let sample = risk_report::JournalEvent::Exposure {
+                        total: status.total_exposure,
+                        limit: state.risk_manager.max_total_exposure(),
+                    };
+                    if let Err(e) = journal.record(sample) {
+                        warn!("Failed to journal exposure: {}", e);
+                    }
+                }
+
+                let mut warm_up = Vec::with_capacity(state.strategies.len());
+                for strategy in &state.strategies {
+                    warm_up.push(WarmUpStatus::of(strategy.as_ref()).await);
+                }
+
+                // Update Health
+                let mut health = state.system_health.write().await;
+                health.circuit_breaker_state = if status.is_paused { "Open".to_string() } else { "Closed".to_string() };
+                health.total_trades = status.trades_today as u64;
+                health.canary = status.canary.active.then_some(status.canary);
+                for strategy in warm_up.iter().filter(|s| s.ready) {
+                    if !health.strategies.iter().any(|s| s.name == strategy.name && s.ready) {
+                        info!("🌡️ Strategy {} warmed up", strategy.name);
+                    }
+                }
+                health.strategies = warm_up;
+                drop(health);
+
+                for (component, usage) in state.memory_report().await {
+                    metrics
+                        .memory_bytes
+                        .with_label_values(&[&component])
+                        .set(usage.bytes as i64);
+                    metrics
+                        .memory_entries
+                        .with_label_values(&[&component])
+                        .set(usage.entries as i64);
+                }
+                // Subscribers sharing a name are summed
+                let mut event_lag: HashMap<String, (u64, u64)> = HashMap::new();
+                for subscriber in state.event_bus.subscribers() {
+                    let entry = event_lag.entry(subscriber.name).or_default();
+                    entry.0 += subscriber.lag;
+                    entry.1 += subscriber.dropped;
+                }
+                for (subscriber, (lag, dropped)) in event_lag {
+                    metrics.event_bus_lag.with_label_values(&[&subscriber]).set(lag as i64);
+                    metrics.event_bus_dropped.with_label_values(&[&subscriber]).set(dropped as i64);
+                }
+                if let Some(resident) = solana_arb_core::memory::resident_bytes() {
+                    metrics.process_resident_bytes.set(resident as i64);
+                }
+            }
+
+            // Let excluded venues back into routing once their cooldown passes
+            if round.is_multiple_of(10) {
+                let mut state = metrics.timed_write(&state, "housekeeping").await;
+                state.route_policy.expire_cooldowns();
+                state.refresh_route_filter().await;
+                state.expire_blacklist().await;
+                let expired = state.risk_manager.expire_commitments();
+                for commitment in &expired {
+                    warn!(
+                        "Released ${} committed to trade {} after {}s without confirmation",
+                        commitment.size,
+                        commitment.id,
+                        commitment.committed_at.elapsed().as_secs()
+                    );
+                }
+                if !expired.is_empty() {
+                    state.publish_venue_exposure().await;
+                }
+                state.route_scoreboard.write().await.expire_deprioritizations();
+            }
+
+            // Close the UTC day: VaR backtest and daily digest
+            let today = Utc::now().date_naive();
+            if today != trading_day {
+                // A standby's day is closed by the primary and replicated
+                if !state.read().await.replication.is_standby() {
+                    send_daily_digest(&state, trading_day).await;
+                }
+                trading_day = today;
+            }
+
+            // Balance Check
+            let wallet_pubkey = metrics
+                .timed_read(&state, "housekeeping")
+                .await
+                .wallet
+                .as_ref()
+                .map(|w| w.pubkey());
+            if let Some(pubkey_str) = wallet_pubkey.filter(|_| last_balance_check.elapsed() > Duration::from_secs(600)) {
+                 last_balance_check = Instant::now();
+                 // Logic to check balance
+                 let (rpc_batcher, alert_manager) = {
+                     let state = metrics.timed_read(&state, "housekeeping").await;
+                     (state.rpc_batcher.clone(), state.alert_manager.clone())
+                 };
+
+                 // Spawn check
+                 let state_clone = state.clone();
+                 tokio::spawn(async move {
+                     use solana_sdk::pubkey::Pubkey;
+                     if let Ok(pubkey) = Pubkey::from_str(&pubkey_str) {
+                         if let Ok(balance) = rpc_batcher.get_balance(&pubkey).await {
+                             let balance_sol = balance as f64 / 1_000_000_000.0;
+
+                             // Get system_health Arc and drop state lock
+                             let system_health = {
+                                 let state = state_clone.read().await;
+                                 state.system_health.clone()
+                             };
+
+                             // Update health
+                             {
+                                 let mut h = system_health.write().await;
+                                 // Approximation: 1 SOL = $150 (should fetch real price)
+                                 h.balance_usd = balance_sol * 150.0;
+                             }
+
+                             if balance_sol < 0.1 {
+                                 alert_manager.send_critical(&format!("⚠️ Low balance: {:.4} SOL", balance_sol)).await;
+                             }
+                         }
+                     }
+                 });
+            }
+        }
+
+        // Stop fetching; detection and execution finish what they hold
+        let _ = shutdown_tx.send(true);
+    };
+    // Execution runs on this task: trade execution holds state guards
+    // across awaits, which keeps it from being spawned
+    tokio::join!(execution, housekeeping);
+    for (stage, handle) in [("price source", source), ("detection", detection)] {
+        if let Err(e) = handle.await {
+            error!("Trading pipeline {} stage failed: {}", stage, e);
+        }
+    }
+
+    state.read().await.end_session().await;
+}
+
+/// Whether trading is disabled by the dynamic config or paused by hand
+async fn trading_held(state: &Arc<RwLock<BotState>>, metrics: &MetricsCollector) -> bool {
+    let state = metrics.timed_read(state, "housekeeping").await;
+    if !state.config_manager.get().await.trading.enabled {
+        info!("⏸️ Trading disabled via dynamic config. Sleeping...");
+        return true;
+    }
+    if let Some(pause) = state.control.paused() {
+        debug!("⏸️ Trading paused by {} since {}. Sleeping...", pause.by, pause.since);
+        return true;
+    }
+    false
+}
+
+/// Fetch prices for the configured and discovered pairs at most once per
+/// `interval` and send them to detection. With price streams, streamed pairs
+/// are sent as their updates arrive and only pairs whose streams are stale
+/// are polled. Stops on shutdown, or when detection is gone.
+#[allow(clippy::too_many_arguments)]
+async fn run_price_source(
+    state: Arc<RwLock<BotState>>,
+    metrics: Arc<MetricsCollector>,
+    pairs: Vec<TokenPair>,
+    discovery: Arc<RwLock<PoolDiscovery>>,
+    streams: Option<Arc<StreamingPriceAggregator>>,
+    events: mpsc::Sender<PipelineEvent>,
+    mut shutdown: watch::Receiver<bool>,
+    interval: Duration,
+) {
+    let mut polled_last = 0;
+    while !*shutdown.borrow() {
+        let started = Instant::now();
+        let wait = if trading_held(&state, &metrics).await {
+            TRADING_HELD_RECHECK
+        } else {
+            let tick_pairs = active_pairs(&state, &pairs, &discovery).await;
+            let streaming = metrics
+                .timed_read(&state, "fetch")
+                .await
+                .feature_flags
+                .is_enabled(feature_flags::WS_STREAMING);
+            let streams = streams.as_ref().filter(|_| streaming);
+            let poll_pairs = match streams {
+                Some(streams) => {
+                    let stale = streams.stale_pairs(&tick_pairs);
+                    if stale.len() != polled_last {
+                        info!(
+                            "📡 {} of {} pairs streamed, polling the rest",
+                            tick_pairs.len() - stale.len(),
+                            tick_pairs.len()
+                        );
+                        polled_last = stale.len();
+                    }
+                    stale
+                }
+                None => tick_pairs.clone(),
+            };
+
+            if let (Some(streams), true) = (streams, poll_pairs.is_empty() && !tick_pairs.is_empty()) {
+                // Every pair streams: react to the next updates
+                let prices = tokio::select! {
+                    prices = streams.next_batch(interval) => prices,
+                    _ = shutdown.changed() => break,
+                };
+                if !prices.is_empty() {
+                    metrics.price_batches.with_label_values(&["stream"]).inc();
+                    // Nothing was asked of every venue, so no coverage check
+                    let batch = PriceBatch {
+                        prices,
+                        pairs: Vec::new(),
+                        fetch_started: started,
+                    };
+                    if events.send(PipelineEvent::Prices(batch)).await.is_err() {
+                        break;
+                    }
+                    metrics
+                        .pipeline_queue_depth
+                        .with_label_values(&["detect"])
+                        .set(pipeline::depth(&events) as i64);
+                }
+                continue;
+            }
+
+            let mut prices = {
+                let _timer = metrics.stage_timer("fetch");
+                fetch_prices(&state, &metrics, &poll_pairs).await
+            };
+            metrics.price_fetch_latency.observe(started.elapsed().as_secs_f64());
+            if let Some(streams) = streams {
+                prices.extend(streams.take_pending());
+            }
+
+            if prices.is_empty() && !poll_pairs.is_empty() {
+                // Track consecutive errors
+                let consecutive = {
+                    let mut state_w = state.write().await;
+                    state_w.consecutive_errors += 1;
+                    state_w.consecutive_errors
+                };
+                // Exponential backoff based on consecutive error count
+                let backoff = Duration::from_secs(2u64.pow(consecutive.min(5)));
+                warn!("⚠️ No venue returned prices; retrying in {}s", backoff.as_secs());
+                backoff
+            } else {
+                metrics.timed_write(&state, "fetch").await.consecutive_errors = 0;
+                metrics.price_batches.with_label_values(&["poll"]).inc();
+                let batch = PriceBatch {
+                    prices,
+                    pairs: poll_pairs,
+                    fetch_started: started,
+                };
+                if events.send(PipelineEvent::Prices(batch)).await.is_err() {
+                    break;
+                }
+                metrics
+                    .pipeline_queue_depth
+                    .with_label_values(&["detect"])
+                    .set(pipeline::depth(&events) as i64);
+                interval.saturating_sub(started.elapsed())
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = shutdown.changed() => break,
+        }
+    }
+}
+
+/// Apply price batches and run detection after each burst of events. A
+/// config reload re-runs detection on the last prices.
+async fn run_detection_stage(
+    state: Arc<RwLock<BotState>>,
+    metrics: Arc<MetricsCollector>,
+    mut events: mpsc::Receiver<PipelineEvent>,
+    candidates: mpsc::Sender<Candidates>,
+    halts: HaltGeneration,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let market = state.read().await.market.clone();
+    let mut recent_prices = Vec::new();
+    loop {
+        let first = tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = shutdown.changed() => break,
+        };
+        let mut burst = vec![first];
+        while let Ok(event) = events.try_recv() {
+            burst.push(event);
+        }
+
+        let mut detect = false;
+        for event in burst {
+            match event {
+                PipelineEvent::Prices(batch) => {
+                    let _timer = metrics.stage_timer("fetch");
+                    recent_prices = apply_prices(&state, &market, &metrics, batch.prices, &batch.pairs).await;
+                    debug!(
+                        "Applied {} prices fetched {}ms ago",
+                        recent_prices.len(),
+                        batch.fetch_started.elapsed().as_millis()
+                    );
+                    detect = true;
+                }
+                PipelineEvent::ConfigReloaded(changes) => {
+                    debug!("Config reloaded ({}), re-running detection", changes.join(", "));
+                    detect |= !recent_prices.is_empty();
+                }
+                PipelineEvent::Risk(event) => {
+                    if pipeline::is_halting(&event) {
+                        info!("Dropping queued candidates after {:?}", event);
+                    }
+                }
+            }
+        }
+        if !detect {
+            continue;
+        }
+
+        let opportunities = detect_opportunities(&state, &market, &metrics, &recent_prices).await;
+        let paths = detect_paths(&state, &market, &metrics).await;
+        if opportunities.is_empty() && paths.is_empty() {
+            continue;
+        }
+        let batch = Candidates {
+            opportunities,
+            paths,
+            generation: halts.current(),
+            detected_at: Instant::now(),
+        };
+        if candidates.send(batch).await.is_err() {
+            break;
+        }
+        metrics
+            .pipeline_queue_depth
+            .with_label_values(&["execute"])
+            .set(pipeline::depth(&candidates) as i64);
+    }
+}
+
+/// Find opportunities with the detector and the strategies, ordered by the
+/// route scoreboard
+async fn detect_opportunities(
+    state: &Arc<RwLock<BotState>>,
+    market: &SharedMarket,
+    metrics: &MetricsCollector,
+    recent_prices: &[solana_arb_core::PriceData],
+) -> Vec<solana_arb_core::ArbitrageOpportunity> {
+    // Find and evaluate opportunities
+    let detect_timer = metrics.stage_timer("detect");
+    let mut opportunities = {
+        let uses_detector = metrics.timed_read(state, "detect").await.strategy_settings.uses_detector();
+        let market = market.read().unwrap_or_else(|e| e.into_inner());
+        let opps = if uses_detector {
+            market.detector.find_all_opportunities()
+        } else {
+            Vec::new()
+        };
+        metrics.opportunities_detected.inc_by(opps.len() as u64);
+        let skew = market.detector.take_quote_skew();
+        for sample in skew.samples_ms {
+            metrics.quote_skew_ms.observe(sample);
+        }
+        metrics.quote_skew_rejections.inc_by(skew.rejected);
+        opps
+    };
+    drop(detect_timer);
+
+    // Execute Strategies
+    {
+        let _timer = metrics.stage_timer("strategy");
+        let state = metrics.timed_read(state, "strategy").await;
+        for strategy in &state.strategies {
+            // Signals of a strategy still warming up are not valid yet
+            if !strategy.is_ready().await {
+                continue;
+            }
+            if let Ok(strategy_opps) = strategy.analyze(recent_prices).await {
+                 opportunities.extend(strategy_opps.into_iter().map(|opp| opp.attributed_to(strategy.name())));
+            }
+        }
+    }
+
+    // Attribute what was found to its source
+    {
+        let state = metrics.timed_read(state, "detect").await;
+        for opp in &opportunities {
+            metrics
+                .opportunities_by_source
+                .with_label_values(&[opp.source_label()])
+                .inc();
+            state.event_bus.publish(TradingEvent::OpportunityDetected {
+                id: opp.id.to_string(),
+                strategy: opp.source_label().to_string(),
+                expected_profit_bps: (opp.net_profit_pct * Decimal::from(100)).to_f64().unwrap_or(0.0),
+            });
+        }
+    }
+
+    // Score routes and put those whose edge keeps collapsing last
+    {
+        let _timer = metrics.stage_timer("detect");
+        let state = metrics.timed_read(state, "detect").await;
+        let mut scoreboard = state.route_scoreboard.write().await;
+        scoreboard.record_detections(&opportunities);
+        scoreboard.prioritize(&mut opportunities);
+    }
+
+    if !opportunities.is_empty() {
+        let state_read = metrics.timed_read(state, "detect").await;
+        let mut health = state_read.system_health.write().await;
+        health.last_opportunity_time = Some(Instant::now());
+    }
+    opportunities
+}
+
+/// Profitable multi-hop paths from the path finder, when path execution is on
+async fn detect_paths(
+    state: &Arc<RwLock<BotState>>,
+    market: &SharedMarket,
+    metrics: &MetricsCollector,
+) -> Vec<TradingPath> {
+    let _timer = metrics.stage_timer("detect");
+    if !metrics
+        .timed_read(state, "detect")
+        .await
+        .feature_flags
+        .is_enabled(feature_flags::PATH_EXECUTION)
+    {
+        return Vec::new();
+    }
+    market
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .path_finder
+        .find_all_profitable_paths()
+}
+
+/// Execute the newest candidates of the current halt generation; older
+/// batches waiting behind them are superseded
+async fn run_execution_stage(
+    state: Arc<RwLock<BotState>>,
+    metrics: Arc<MetricsCollector>,
+    mut queue: mpsc::Receiver<Candidates>,
+    halts: HaltGeneration,
+) {
+    while let Some(first) = queue.recv().await {
+        let (batch, superseded) = pipeline::latest(first, &mut queue);
+        metrics.pipeline_queue_depth.with_label_values(&["execute"]).set(0);
+        metrics
+            .pipeline_dropped
+            .with_label_values(&["superseded"])
+            .inc_by(superseded);
+        if !halts.is_current(&batch) {
+            metrics.pipeline_dropped.with_label_values(&["halted"]).inc();
+            continue;
+        }
+        if trading_held(&state, &metrics).await {
+            continue;
+        }
+        debug!(
+            "Executing {} candidates and {} paths detected {}ms ago",
+            batch.opportunities.len(),
+            batch.paths.len(),
+            batch.detected_at.elapsed().as_millis()
+        );
+        execute_candidates(&state, &metrics, &batch.opportunities).await;
+        try_execute_path(&state, &metrics, &batch.paths).await;
+    }
+}
+
+/// Batch several small opportunities into one transaction when possible,
+/// otherwise size, approve and execute the best ones
+async fn execute_candidates(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    opportunities: &[solana_arb_core::ArbitrageOpportunity],
+) {
+    let opportunities = &metrics.timed_read(state, "execute").await.project_opportunities(opportunities);
+
+    // Batch several small opportunities into one transaction when possible,
+    // otherwise execute the best one
+    let selected = {
+        let _timer = metrics.stage_timer("execute");
+        if try_execute_batch(state, metrics, opportunities).await {
+            Vec::new()
+        } else {
+            select_for_execution(state, metrics, opportunities).await
+        }
+    };
+    for opp in selected {
+        // ... (Execution logic same as before, calling execute_trade)
+         let risk_timer = metrics.stage_timer("risk");
+         let should_execute = {
+            let state = metrics.timed_read(state, "risk").await;
+            let config = state.config_manager.get().await;
+            let min_profit_pct = state.required_profit_pct(config.trading.min_profit_bps).await;
+
+            if opp.net_profit_pct < min_profit_pct {
+                 debug!("Skipping opportunity: Profit {}% < Min {}%", opp.net_profit_pct, min_profit_pct);
+                false
+            } else {
+                state.sample_impact_curve(opp).await;
+                let optimal_size = state.optimal_size(opp);
+                let decision = state
+                    .risk_manager
+                    .can_trade_on_venues(&opp.pair.symbol(), optimal_size, &[opp.buy_dex, opp.sell_dex])
+                    .await;
+                matches!(decision, TradeDecision::Approved { .. } | TradeDecision::Reduced { .. })
+            }
+        };
+        drop(risk_timer);
+
+        if should_execute {
+            let (watch_only, standby) = {
+                let s = metrics.timed_read(state, "execute").await;
+                (s.watch_only, s.replication.is_standby())
+            };
+            if watch_only {
+                info!(
+                    "👀 [WATCH ONLY] Opportunity: Buy {} on {}, Sell on {} | Profit: {}%",
+                    opp.pair.symbol(), opp.buy_dex, opp.sell_dex, opp.net_profit_pct
+                );
+            } else if standby {
+                debug!(
+                    "🕰️ [STANDBY] Opportunity left to the primary: {} {} -> {}",
+                    opp.pair.symbol(), opp.buy_dex, opp.sell_dex
+                );
+            } else {
+                let _timer = metrics.stage_timer("execute");
+                execute_trade(state, metrics, opp).await;
+            }
+        }
+    }
+}
+
+/// Price an operator's order at the current quotes and take it through the
+/// same checks and execution as a detected opportunity, minus the profit
+/// threshold
+async fn execute_manual_trade(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    order: ManualOrder,
+) -> ManualTradeReport {
+    let opp = {
+        let s = metrics.timed_read(state, "execute").await;
+        if s.watch_only {
+            return ManualTradeReport::skipped("watch-only mode never trades");
+        }
+        if s.replication.is_standby() {
+            return ManualTradeReport::skipped("this instance is a standby");
+        }
+        let Some(mut opp) = s.market().detector.price_route(&order.pair, order.buy_dex, order.sell_dex) else {
+            return ManualTradeReport::skipped(format!(
+                "no current {} quote on both {} and {}",
+                order.pair, order.buy_dex, order.sell_dex
+            ));
+        };
+        opp.source = OpportunitySource::Manual;
+        opp.recommended_size = Some(order.size_usd);
+        opp
+    };
+    let priced = |mut report: ManualTradeReport| {
+        report.opportunity_id = Some(opp.id);
+        report.net_profit_pct = Some(opp.net_profit_pct.round_dp(4));
+        report
+    };
+    if trading_held(state, metrics).await {
+        return priced(ManualTradeReport::skipped("trading is paused or disabled"));
+    }
+
+    {
+        let s = metrics.timed_read(state, "execute").await;
+        s.metrics
+            .opportunities_by_source
+            .with_label_values(&[opp.source_label()])
+            .inc();
+        s.event_bus.publish(TradingEvent::OpportunityDetected {
+            id: opp.id.to_string(),
+            strategy: opp.source_label().to_string(),
+            expected_profit_bps: (opp.net_profit_pct * Decimal::from(100)).to_f64().unwrap_or(0.0),
+        });
+        // Give the operator the risk manager's reason; execution checks again
+        if let (_, TradeDecision::Rejected { reason }, _) = s.check_risk_and_size(&opp).await {
+            return priced(ManualTradeReport::skipped(format!("rejected by risk checks: {}", reason)));
+        }
+    }
+
+    info!(
+        "🖐️ Manual trade: buy {} on {}, sell on {} | ${} at {}%",
+        opp.pair, opp.buy_dex, opp.sell_dex, order.size_usd, opp.net_profit_pct.round_dp(4)
+    );
+    execute_trade(state, metrics, &opp).await;
+
+    let (dry_run, trace) = {
+        let s = metrics.timed_read(state, "execute").await;
+        (s.dry_run, s.trade_traces.get(opp.id))
+    };
+    let Some(plan) = trace.as_ref().and_then(|t| t.plan.clone()) else {
+        return priced(ManualTradeReport::skipped(
+            "stopped by a safety check (fee reserve, quarantine, blacklist or direction); see the logs",
+        ));
+    };
+    let execution = trace.and_then(|t| t.execution).filter(|_| !dry_run);
+    ManualTradeReport {
+        status: if dry_run {
+            ManualTradeStatus::Simulated
+        } else if execution.as_ref().is_some_and(|e| e.success) {
+            ManualTradeStatus::Executed
+        } else {
+            ManualTradeStatus::Failed
+        },
+        reason: execution.as_ref().and_then(|e| e.error.clone()),
+        opportunity_id: Some(opp.id),
+        net_profit_pct: Some(opp.net_profit_pct.round_dp(4)),
+        size_usd: Some(plan.size_usd),
+        signature: execution.as_ref().and_then(|e| e.signature.clone()),
+        profit_usd: execution.map(|e| e.actual_profit_usd),
+    }
+}
+
+/// Collects recent price data from all registered DEX providers and
+/// archives it. The state lock is not held during the fetch.
+async fn fetch_prices(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    pairs: &[TokenPair],
+) -> Vec<solana_arb_core::PriceData> {
+    let (price_fetcher, price_snapshots) = {
+        let state = metrics.timed_read(state, "fetch").await;
+        (state.price_fetcher.clone(), state.price_snapshots.clone())
+    };
+
+    // Use parallel fetcher for all pairs at once!
+    let prices = price_fetcher.fetch_all_prices(pairs).await;
+    let stats = price_fetcher.take_stats();
+    for (dex, timeouts) in stats.timeouts {
+        metrics
+            .price_fetch_timeouts
+            .with_label_values(&[dex.display_name()])
+            .inc_by(timeouts);
+    }
+    for (result, hedges) in [("sent", stats.hedges_sent), ("won", stats.hedges_won)] {
+        for (dex, count) in hedges {
+            metrics
+                .price_fetch_hedges
+                .with_label_values(&[dex.display_name(), result])
+                .inc_by(count);
+        }
+    }
+    info!(
+        "💓 Parallel fetch complete — {} prices collected",
+        prices.len()
+    );
+    if let Some(snapshots) = &price_snapshots {
+        if let Err(e) = snapshots.record(Utc::now(), &prices).await {
+            warn!("Failed to archive price snapshot: {}", e);
+        }
+    }
+    prices
+}
+
+/// Updates the local state with fetched prices, clears stale data, and
+/// updates strategy internal state. Returns the prices the spread guard kept.
+///
+/// Only the market state is written here. The bot state is read, so a batch
+/// is applied while execution holds it; the risk manager's price samples
+/// wait until its write lock is free.
+async fn apply_prices(
+    state: &Arc<RwLock<BotState>>,
+    market: &SharedMarket,
+    metrics: &MetricsCollector,
+    prices: Vec<solana_arb_core::PriceData>,
+    pairs: &[TokenPair],
+) -> Vec<solana_arb_core::PriceData> {
+    info!("📈 Received price data from DEX ({} prices)", prices.len());
+    // BONK/SOL and SOL/BONK quotes describe one market
+    let prices: Vec<_> = prices.into_iter().map(solana_arb_core::PriceData::normalized).collect();
+
+    // Update state
+    let prices = {
+        let state = metrics.timed_read(state, "fetch").await;
+
+        // Drop quotes too wide to trade, along with the venue's older price
+        let applied = market
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .apply(prices, state.max_price_age_seconds);
+        for price in &applied.too_wide {
+            debug!(
+                "Skipping {} on {}: spread {:.4}% over limit",
+                price.pair,
+                price.dex,
+                price.spread_percentage()
+            );
+            metrics
+                .prices_rejected_spread
+                .with_label_values(&[price.dex.display_name()])
+                .inc();
+        }
+        let prices = applied.kept;
+
+        // Cached quotes of a pair whose price moved are stale
+        if let Some(executor) = &state.executor {
+            for pair in &applied.moved {
+                executor.invalidate_quotes(pair);
+            }
+            executor.record_usd_prices(&prices);
+            if let Some(cache) = executor.quote_cache() {
+                let stats = cache.take_stats();
+                metrics.quote_cache_lookups.with_label_values(&["hit"]).inc_by(stats.hits);
+                metrics.quote_cache_lookups.with_label_values(&["miss"]).inc_by(stats.misses);
+                metrics.quote_cache_invalidations.inc_by(stats.invalidated);
+            }
+            let submissions = executor.submissions().take_stats();
+            for wait in submissions.waits_secs {
+                metrics.submission_wait.observe(wait);
+            }
+            metrics.submission_queue_depth.set(submissions.peak_waiting as i64);
+            for (cause, action, count) in executor.take_retry_stats() {
+                metrics
+                    .submit_failures
+                    .with_label_values(&[cause.label(), action.label()])
+                    .inc_by(count);
+            }
+        }
+
+        // Update strategies
+        for strategy in &state.strategies {
+            for price in &prices {
+                if let Err(e) = strategy.update_state(price).await {
+                    warn!("Strategy {} update failed: {}", strategy.name(), e);
+                }
+            }
+        }
+
+        state.update_regime(&prices).await;
+        prices
+    };
+
+    // Update risk manager volatility tracking, unless execution holds the
+    // state; the samples are kept for the next batch then
+    if let Ok(mut state) = state.try_write() {
+        let pending = market.write().unwrap_or_else(|e| e.into_inner()).take_risk_prices();
+        state.risk_manager.update_prices(&pending);
+    }
+
+    validate_dex_coverage(&prices, pairs);
+
+    prices
+}
+
+fn validate_dex_coverage(prices: &[solana_arb_core::PriceData], pairs: &[TokenPair]) {
+    let mut coverage: std::collections::HashMap<String, std::collections::HashSet<DexType>> =
+        std::collections::HashMap::new();
+
+    for price in prices {
+        coverage
+            .entry(price.pair.symbol())
+            .or_default()
+            .insert(price.dex);
+    }
+
+    for pair in pairs {
+        let seen = coverage.get(&pair.canonical().symbol());
+        let missing: Vec<_> = DexType::all()
+            .iter()
+            .filter(|dex| seen.is_none_or(|set| !set.contains(dex)))
+            .collect();
+
+        if !missing.is_empty() {
+            let missing_labels: Vec<_> = missing.iter().map(|dex| dex.display_name()).collect();
+            warn!(
+                "⚠️ Missing DEX coverage for {}: {}",
+                pair,
+                missing_labels.join(", ")
+            );
+        }
+    }
+}
+
+/// Closes out `day` in the risk manager, persists the return archive and
+/// sends the daily digest.
+async fn send_daily_digest(state: &Arc<RwLock<BotState>>, day: chrono::NaiveDate) {
+    let mut s = state.write().await;
+    let digest = s.risk_manager.close_day(day).await;
+    s.hedge_manager.reset_daily();
+    if let Err(e) = s
+        .risk_manager
+        .return_archive
+        .save(std::path::Path::new(RETURN_ARCHIVE_PATH))
+    {
+        warn!("Failed to save VaR return archive: {}", e);
+    }
+    let mut message = alerts::daily_digest_message(&digest, &s.fx.get());
+    if let Some(journal) = &s.risk_journal {
+        let report = risk_report::RiskReport {
+            journal: journal.read(day),
+            limits: s.risk_manager.money_limits(),
+            digest,
+        };
+        match report.write(journal.dir()) {
+            Ok(path) => message.push_str(&format!("\n📄 Risk report: {}", path.display())),
+            Err(e) => warn!("Failed to write risk report for {}: {}", day, e),
+        }
+    }
+    s.alert_manager.send_info(&message).await;
+}
+
+/// Executes a batch of small opportunities on disjoint pairs as one transaction.
+///
+/// Returns `false` (without side effects) when batching is disabled or fewer
+/// than two opportunities qualify, so the caller can fall back to
+/// [`execute_trade`]. The shared transaction fee is split across legs by size
+/// and deducted from each leg's recorded profit.
+async fn try_execute_batch(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    opportunities: &[solana_arb_core::ArbitrageOpportunity],
+) -> bool {
+    let (plan, is_dry_run, rpc_url) = {
+        let s = metrics.timed_read(state, "execute").await;
+        if s.watch_only
+            || s.replication.is_standby()
+            || s.fees_blocked()
+            || s.quarantined()
+            || !s.feature_flags.is_enabled(feature_flags::BATCH_EXECUTION)
+            || opportunities.len() < 2
+        {
+            return false;
+        }
+
+        let config = s.config_manager.get().await;
+        let min_profit_pct = s.required_profit_pct(config.trading.min_profit_bps).await;
+
+        let mut candidates = Vec::new();
+        for opp in opportunities.iter().filter(|o| o.net_profit_pct >= min_profit_pct) {
+            if s.blacklisted(opp).await.is_some() {
+                continue;
+            }
+            // Batch legs are standard buys
+            if !config
+                .directions
+                .direction(&opp.pair.symbol())
+                .allows(ExecutionMode::Standard)
+            {
+                continue;
+            }
+            let (_, decision, _) = s.check_risk_and_size(opp).await;
+            let size = match decision {
+                TradeDecision::Approved { size } => size,
+                TradeDecision::Reduced { new_size, .. } => new_size,
+                TradeDecision::Rejected { .. } => continue,
+            };
+            candidates.push(BatchLeg {
+                opportunity: opp.clone(),
+                size,
+            });
+        }
+
+        (
+            batching::plan_batch(&candidates, &s.batch_config),
+            s.dry_run,
+            s.rpc_url.clone(),
+        )
+    };
+
+    if plan.is_empty() {
+        return false;
+    }
+
+    let start_time = Instant::now();
+    info!(
+        "📦 Batching {} opportunities into one transaction: {}",
+        plan.len(),
+        plan.iter()
+            .map(|l| format!("{} (${})", l.opportunity.pair.symbol(), l.size))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let ids: Vec<_> = plan.iter().map(|leg| leg.opportunity.id).collect();
+    let _in_flight: Vec<_> = {
+        let s = metrics.timed_read(state, "execute").await;
+        plan.iter()
+            .map(|leg| {
+                let others = ids.iter().copied().filter(|id| *id != leg.opportunity.id).collect();
+                s.inflight.start(&leg.opportunity, leg.size, ExecutionMode::Standard, others)
+            })
+            .collect()
+    };
+
+    {
+        let mut s = metrics.timed_write(state, "execute").await;
+        for leg in &plan {
+            s.metrics.trades_attempted.inc();
+            s.trade_traces.record_plan(&leg.opportunity, leg.size);
+            s.risk_manager.commit_trade(
+                &leg.opportunity.id.to_string(),
+                leg.size,
+                &[leg.opportunity.buy_dex, leg.opportunity.sell_dex],
+            );
+        }
+        s.publish_venue_exposure().await;
+    }
+
+    let (result, fee_estimate, sol_price) = {
+        let s = metrics.timed_read(state, "execute").await;
+        let result = match (&s.executor, &s.wallet) {
+            (Some(executor), Some(wallet)) => {
+                executor
+                    .execute_batch(wallet, &plan, &s.batch_config, !is_dry_run, &rpc_url)
+                    .await
+            }
+            _ => Err(anyhow::anyhow!("Executor disabled (watch-only mode)")),
+        };
+        let fee_estimate = s
+            .executor
+            .as_ref()
+            .map(|e| e.estimate_batch_fee(&plan, &s.batch_config))
+            .unwrap_or_else(|| vec![0; plan.len()]);
+        (result, fee_estimate, s.sol_price_usd())
+    };
+    let total_size: Decimal = plan.iter().map(|l| l.size).sum();
+
+    let fee_shares = match &result {
+        Ok(batch) => batch.fee_shares.clone(),
+        Err(e) => {
+            if is_dry_run {
+                warn!("Batch simulation failed: {}", e);
+            } else {
+                error!("❌ Batch failed (Executor Error): {}", e);
+            }
+            fee_estimate
+        }
+    };
+
+    for (leg, fee_lamports) in plan.iter().zip(fee_shares) {
+        let opp = &leg.opportunity;
+        let pair_symbol = opp.pair.symbol();
+        let fee_usd = Decimal::from(fee_lamports) / Decimal::from(1_000_000_000u64) * sol_price;
+
+        let outcome = if is_dry_run {
+            info!(
+                "🔵 [DRY RUN] Batched: Buy {} on {}, Sell on {} | Size: ${} | Fee share: ${:.4}",
+                pair_symbol, opp.buy_dex, opp.sell_dex, leg.size, fee_usd
+            );
+            let s = metrics.timed_read(state, "execute").await;
+            let paper = s.paper_trade(opp, leg.size);
+            let est_profit = paper.profit - fee_usd;
+            s.history_recorder
+                .record_trade(opp, paper.filled_size, est_profit, true, None, None, None, None, true);
+            TradeOutcome {
+                timestamp: Utc::now(),
+                pair: pair_symbol,
+                profit_loss: est_profit,
+                was_successful: true,
+            }
+        } else {
+            let trade_result = match &result {
+                Ok(batch) => TradeResult {
+                    opportunity_id: opp.id,
+                    signature: Some(batch.signature.clone()),
+                    success: true,
+                    actual_profit: opp.estimated_profit_usd.unwrap_or(Decimal::ZERO) - fee_usd,
+                    executed_at: Utc::now(),
+                    error: None,
+                    // The simulation covers the whole batch; attribute it by leg size
+                    simulated_profit: batch
+                        .simulated_profit
+                        .filter(|_| !total_size.is_zero())
+                        .map(|p| p * leg.size / total_size),
+                    failure_kind: None,
+                },
+                Err(e) => TradeResult {
+                    opportunity_id: opp.id,
+                    signature: None,
+                    success: false,
+                    actual_profit: Decimal::ZERO,
+                    executed_at: Utc::now(),
+                    error: Some(e.to_string()),
+                    simulated_profit: None,
+                    failure_kind: if latency_slo::is_deadline_exceeded(e) {
+                        Some(FailureKind::AbortedDeadline)
+                    } else if compliance::is_compliance_veto(e) {
+                        Some(FailureKind::ComplianceVeto)
+                    } else if loss_guard::is_max_loss_exceeded(e) {
+                        Some(FailureKind::MaxLossExceeded)
+                    } else if retry_policy::is_program_failure(e) {
+                        Some(FailureKind::ProgramError)
+                    } else {
+                        None
+                    },
+                },
+            };
+            let s = metrics.timed_read(state, "execute").await;
+            s.record_trade_outcome(opp, &pair_symbol, leg.size, &trade_result, start_time, fee_usd)
+                .await
+        };
+
+        let mut s = metrics.timed_write(state, "execute").await;
+        // Deadline aborts, compliance vetoes and loss cap refusals stop
+        // before submission
+        let aborted = result.as_ref().is_err_and(|e| {
+            latency_slo::is_deadline_exceeded(e)
+                || compliance::is_compliance_veto(e)
+                || loss_guard::is_max_loss_exceeded(e)
+        });
+        if !is_dry_run && !aborted {
+            s.record_venue_results(&[opp.buy_dex, opp.sell_dex], outcome.was_successful)
+                .await;
+        }
+        let signature = result.as_ref().ok().map(|batch| batch.signature.as_str());
+        s.track_confirmation(opp, leg.size, signature, &outcome);
+        s.risk_manager.record_trade(outcome).await;
+        s.risk_manager.release_trade(&opp.id.to_string());
+    }
+
+    metrics.timed_read(state, "execute").await.publish_venue_exposure().await;
+    true
+}
+
+/// Trade the most profitable multi-hop path in one transaction.
+///
+/// The path must clear the same required profit as opportunities and start
+/// from a token with a USD price. It is sized at most `PATH_MAX_SIZE_USD`, as
+/// far as the risk manager approves, and funded by a flash loan when flash
+/// loans are on and the builder has a reserve for the start token.
+async fn try_execute_path(state: &Arc<RwLock<BotState>>, metrics: &MetricsCollector, paths: &[TradingPath]) {
+    if paths.is_empty() {
+        return;
+    }
+    let (path, label, venues, amount, size, flash_loan, is_dry_run, rpc_url) = {
+        let s = metrics.timed_read(state, "execute").await;
+        if s.watch_only
+            || s.replication.is_standby()
+            || s.fees_blocked()
+            || s.quarantined()
+            || !s.feature_flags.is_enabled(feature_flags::PATH_EXECUTION)
+        {
+            return;
+        }
+        let config = s.config_manager.get().await;
+        let min_profit_pct = s.required_profit_pct(config.trading.min_profit_bps).await;
+        let Some(path) =
+            paths::select_path(paths, min_profit_pct, |token| s.token_price_usd(token).is_some())
+        else {
+            return;
+        };
+        let (Some(start), Some(start_price)) = (
+            paths::start_token(path),
+            paths::start_token(path).and_then(|token| s.token_price_usd(token)),
+        ) else {
+            return;
+        };
+        let label = paths::path_label(path);
+        let mut venues: Vec<DexType> = Vec::new();
+        for edge in &path.edges {
+            if !venues.contains(&edge.dex) {
+                venues.push(edge.dex);
+            }
+        }
+        let size = match s
+            .risk_manager
+            .can_trade_on_venues(&label, s.path_config.max_size_usd, &venues)
+            .await
+        {
+            TradeDecision::Approved { size } => size,
+            TradeDecision::Reduced { new_size, .. } => new_size,
+            TradeDecision::Rejected { reason } => {
+                debug!("Skipping path {}: {}", label, reason);
+                return;
+            }
+        };
+        let flash_loan = s.feature_flags.is_enabled(feature_flags::FLASH_LOANS)
+            && s.executor.as_ref().is_some_and(|e| e.supports_flash_loan(start));
+        (
+            path.clone(),
+            label,
+            venues,
+            size / start_price,
+            size,
+            flash_loan,
+            s.dry_run,
+            s.rpc_url.clone(),
+        )
+    };
+
+    let start_time = Instant::now();
+    let id = solana_arb_core::Uuid::new_v4().to_string();
+    info!(
+        "🔺 Trading path {} ({:.4}%) at ${}{}",
+        label,
+        path.profit_percentage(),
+        size,
+        if flash_loan { " with a flash loan" } else { "" }
+    );
+    {
+        let mut s = metrics.timed_write(state, "execute").await;
+        s.metrics.trades_attempted.inc();
+        s.risk_manager.commit_trade(&id, size, &venues);
+        s.publish_venue_exposure().await;
+    }
+
+    let (result, sol_price, start_price) = {
+        let s = metrics.timed_read(state, "execute").await;
+        let result = match (&s.executor, &s.wallet) {
+            (Some(executor), Some(wallet)) => {
+                executor
+                    .execute_path(
+                        wallet,
+                        &path,
+                        amount,
+                        size,
+                        flash_loan,
+                        &s.path_config,
+                        !is_dry_run,
+                        &rpc_url,
+                    )
+                    .await
+            }
+            _ => Err(anyhow::anyhow!("Executor disabled (watch-only mode)")),
+        };
+        let start_price = paths::start_token(&path)
+            .and_then(|token| s.token_price_usd(token))
+            .unwrap_or(Decimal::ZERO);
+        (result, s.sol_price_usd(), start_price)
+    };
+
+    let estimated = size * path.profit_percentage() / Decimal::from(100);
+    let (profit_loss, success, result_label) = match &result {
+        Ok(execution) => {
+            let fee_usd =
+                Decimal::from(execution.fee_lamports) / Decimal::from(1_000_000_000u64) * sol_price;
+            let profit = execution
+                .simulated_profit
+                .map(|p| p * start_price)
+                .unwrap_or(estimated);
+            if is_dry_run {
+                info!(
+                    "🔵 [DRY RUN] Path {} | Size: ${} | Est. profit: ${:.4}",
+                    label, size, profit - fee_usd
+                );
+            }
+            (profit - fee_usd, true, if is_dry_run { "dry_run" } else { "success" })
+        }
+        Err(e) => {
+            if is_dry_run {
+                warn!("Path {} simulation failed: {}", label, e);
+            } else {
+                error!("❌ Path {} failed (Executor Error): {}", label, e);
+            }
+            (Decimal::ZERO, false, "failed")
+        }
+    };
+    metrics.path_trades.with_label_values(&[result_label]).inc();
+
+    let mut s = metrics.timed_write(state, "execute").await;
+    if success {
+        s.metrics.trades_successful.inc();
+        s.metrics.trade_execution_time.observe(start_time.elapsed().as_secs_f64());
+    } else {
+        s.metrics.trades_failed.inc();
+    }
+    // Deadline aborts and compliance vetoes stop before submission
+    let aborted = result.as_ref().is_err_and(|e| {
+        latency_slo::is_deadline_exceeded(e) || compliance::is_compliance_veto(e)
+    });
+    if !is_dry_run && !aborted {
+        s.record_venue_results(&venues, success).await;
+    }
+    s.event_bus.publish(TradingEvent::TradeExecuted {
+        id: id.clone(),
+        pair: label.clone(),
+        success,
+        profit: profit_loss.to_f64().unwrap_or(0.0),
+        execution_time_ms: start_time.elapsed().as_millis() as u64,
+    });
+    s.risk_manager
+        .record_trade(TradeOutcome {
+            timestamp: Utc::now(),
+            pair: label,
+            profit_loss,
+            was_successful: success,
+        })
+        .await;
+    s.risk_manager.release_trade(&id);
+    s.publish_venue_exposure().await;
+}
+
+/// The opportunity to execute this tick: the top one, or with preflight
+/// enabled the candidate with the best simulated profit among the top
+/// `PREFLIGHT_TOP_K` that pass the profit, blacklist and risk checks. Empty
+/// when every candidate's simulation fails.
+async fn select_for_execution<'a>(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    opportunities: &'a [solana_arb_core::ArbitrageOpportunity],
+) -> Vec<&'a solana_arb_core::ArbitrageOpportunity> {
+    let top: Vec<_> = opportunities.iter().take(1).collect();
+    let s = metrics.timed_read(state, "execute").await;
+    let (Some(executor), Some(wallet)) = (&s.executor, &s.wallet) else {
+        return top;
+    };
+    if !s.preflight.enabled() || s.replication.is_standby() || s.fees_blocked() {
+        return top;
+    }
+
+    let config = s.config_manager.get().await;
+    let min_profit_pct = s.required_profit_pct(config.trading.min_profit_bps).await;
+    let mut candidates = Vec::new();
+    for opp in opportunities.iter().filter(|o| o.net_profit_pct >= min_profit_pct) {
+        if candidates.len() >= s.preflight.top_k {
+            break;
+        }
+        if s.blacklisted(opp).await.is_some() {
+            continue;
+        }
+        s.sample_impact_curve(opp).await;
+        let (_, decision, _) = s.check_risk_and_size(opp).await;
+        let size = match decision {
+            TradeDecision::Approved { size } => size,
+            TradeDecision::Reduced { new_size, .. } => new_size,
+            TradeDecision::Rejected { .. } => continue,
+        };
+        let mode = ExecutionModeSelector::default().select(&s.mode_inputs(opp, size)).mode;
+        candidates.push((opp, size, mode));
+    }
+    if candidates.len() < 2 {
+        return top;
+    }
+
+    let rpc_url = s.rpc_url.clone();
+    let results = preflight::simulate_all(&candidates, s.preflight.concurrency, |(opp, size, mode)| {
+        executor.preflight(wallet, opp, *size, &rpc_url, *mode)
+    })
+    .await;
+    metrics.preflight_simulations.inc_by(results.len() as u64);
+    for ((opp, _, _), result) in candidates.iter().zip(&results) {
+        if let Err(e) = result {
+            debug!("Preflight {} {} -> {} failed: {}", opp.pair, opp.buy_dex, opp.sell_dex, e);
+        }
+    }
+    let passed = results.iter().filter(|r| r.is_ok()).count();
+    metrics.preflight_passed.inc_by(passed as u64);
+
+    match preflight::best(&results) {
+        Some(index) => {
+            let (opp, size, _) = candidates[index];
+            info!(
+                "🧪 Preflight: {}/{} candidates passed; best {} {} -> {} (${}) simulated profit {} {}",
+                passed,
+                candidates.len(),
+                opp.pair,
+                opp.buy_dex,
+                opp.sell_dex,
+                size,
+                results[index].as_ref().copied().unwrap_or_default(),
+                opp.pair.quote
+            );
+            vec![opp]
+        }
+        None => {
+            info!("🧪 Preflight: none of {} candidates passed simulation", candidates.len());
+            Vec::new()
+        }
+    }
+}
+
+/// Executes a detected arbitrage opportunity.
+///
+/// This function handles:
+/// 1. Risk checks and position sizing
+/// 2. Flash loan viability analysis
+/// 3. Dry-run simulation (if enabled)
+/// 4. Actual trade execution via the Executor
+/// 5. Outcome recording (Metrics, History, Risk Manager)
+async fn execute_trade(
+    state: &Arc<RwLock<BotState>>,
+    metrics: &MetricsCollector,
+    opp: &solana_arb_core::ArbitrageOpportunity,
+) {
+    let start_time = std::time::Instant::now();
+    let pair_symbol = opp.pair.symbol();
+
+    if metrics.timed_read(state, "execute").await.fees_blocked() {
+        warn!("Skipping {}: SOL fee reserve is below its minimum", pair_symbol);
+        return;
+    }
+    if metrics.timed_read(state, "execute").await.quarantined() {
+        debug!("Skipping {}: wallet is quarantined", pair_symbol);
+        return;
+    }
+    if let Some(entry) = metrics.timed_read(state, "execute").await.blacklisted(opp).await {
+        debug!("Skipping {}: blacklisted until {}", entry.label(), entry.expires_at);
+        return;
+    }
+
+    // We need to release the read lock before acquiring write lock later,
+    // AND calling async execution which shouldn't hold locks if possible.
+    // However, Executor is stateless (HttpClient) so we can clone data needed.
+
+    // Approval commits the size until the trade confirms or fails, so
+    // opportunities approved meanwhile see it against the exposure limit
+    let (is_dry_run, decision, rpc_url) = {
+        let mut state = metrics.timed_write(state, "execute").await;
+        let reservation = state.reserve_risk_and_size(opp).await;
+        state.publish_venue_exposure().await;
+        reservation
+    };
+
+    let size = match decision {
+        TradeDecision::Approved { size } => size,
+        TradeDecision::Reduced { new_size, reason } => {
+            info!("Trade size reduced: {}", reason);
+            new_size
+        }
+        TradeDecision::Rejected { reason } => {
+            debug!("Trade rejected: {}", reason);
+            return;
+        }
+    };
+
+    // The mode decides whether the trade leaves the base token in the
+    // wallet, which the pair's direction restriction may forbid
+    let mode = {
+        let state_read = metrics.timed_read(state, "execute").await;
+        let mode = state_read.select_execution_mode(opp, size).await.mode;
+        let direction = state_read
+            .config_manager
+            .get()
+            .await
+            .directions
+            .direction(&pair_symbol);
+        if !direction.allows(mode) {
+            info!(
+                "Skipping {}: a {:?} trade is not allowed by its {:?} restriction",
+                pair_symbol, mode, direction
+            );
+            drop(state_read);
+            let mut state = metrics.timed_write(state, "execute").await;
+            state.risk_manager.release_trade(&opp.id.to_string());
+            state.publish_venue_exposure().await;
+            return;
+        }
+        mode
+    };
+
+    // Record attempt
+    {
+        let state = metrics.timed_read(state, "execute").await;
+        state.metrics.trades_attempted.inc();
+        state.trade_traces.record_plan(opp, size);
+    }
+
+    let venues = [opp.buy_dex, opp.sell_dex];
+
+    // Check Flash Loan Viability
+    let (_flash_loan_quote, _in_flight) = {
+        let state_read = metrics.timed_read(state, "execute").await;
+        let in_flight = state_read.inflight.start(opp, size, mode, Vec::new());
+        (state_read.check_flash_loan(opp, size).await, in_flight)
+    };
+
+    if is_dry_run {
+        // Simulate trade
+        info!(
+            "🔵 [DRY RUN] Would execute: Buy {} on {}, Sell on {} | Size: ${} | Profit: {}%",
+            pair_symbol, opp.buy_dex, opp.sell_dex, size, opp.net_profit_pct
+        );
+
+        // Fetch quote simulation (optional)
+        {
+            let state_read = metrics.timed_read(state, "execute").await;
+            if let (Some(executor), Some(wallet)) = (&state_read.executor, &state_read.wallet) {
+                match executor.execute(wallet, opp, size, false, &rpc_url, None, mode).await {
+                    Ok(result) => {
+                        state_read.trade_traces.record_execution(
+                            &result,
+                            Decimal::ZERO,
+                            Some(executor.priority_fee()),
+                        );
+                        state_read.check_quote_slippage(opp, size);
+                    }
+                    Err(e) => warn!("Simulation execution failed: {}", e),
+                }
+            }
+        }
+
+        // Record simulation history, filled against order book depth
+        let est_profit = {
+            let state_read = metrics.timed_read(state, "execute").await;
+            let paper = state_read.paper_trade(opp, size);
             state_read
                 .history_recorder
-                .record_trade(opp, size, est_profit, true, None, None, true);
+                .record_trade(opp, paper.filled_size, paper.profit, true, None, None, None, None, true);
+            paper.profit
+        };
+
+        // Simulate successful outcome
+        let outcome = TradeOutcome {
+            timestamp: Utc::now(),
+            pair: pair_symbol,
+            profit_loss: est_profit,
+            was_successful: true,
+        };
+
+        let mut state = metrics.timed_write(state, "execute").await;
+        state.risk_manager.record_trade(outcome).await;
+        state.risk_manager.release_trade(&opp.id.to_string());
+        state.publish_venue_exposure().await;
+    } else {
+        // Real execution via Jupiter API
+        info!(
+            "🟢 Executing: Buy {} on {}, Sell on {} | Size: ${} | Expected Profit: {}%",
+            pair_symbol, opp.buy_dex, opp.sell_dex, size, opp.net_profit_pct
+        );
+
+        let base_before = metrics
+            .timed_read(state, "execute")
+            .await
+            .repair_baseline(&opp.pair, mode)
+            .await;
+
+        let result: Result<TradeResult> = {
+            let state_read = metrics.timed_read(state, "execute").await;
+            match (&state_read.executor, &state_read.wallet) {
+                (Some(executor), Some(wallet)) => {
+                    executor
+                        .execute(
+                            wallet,
+                            opp,
+                            size,
+                            true,
+                            &rpc_url,
+                            state_read.jito(),
+                            mode,
+                        )
+                        .await
+                }
+                _ => Err(anyhow::anyhow!("Executor disabled (watch-only mode)")),
+            }
+        };
+
+        if let Some(base_before) = base_before {
+            if route_repair::may_be_stranded(result.as_ref().ok()) {
+                spawn_route_repair(state.clone(), opp.clone(), base_before);
+            }
+        }
+
+        match result {
+            Ok(trade_result) => {
+                if trade_result.success {
+                    let tx_signature = trade_result.signature.as_deref().unwrap_or("unknown");
+                    info!("✅ Trade submitted! Signature: {}", tx_signature);
+                } else {
+                    let error_msg = trade_result.error.as_deref().unwrap_or("Unknown error");
+                    warn!("❌ Trade execution returned failure: {}", error_msg);
+                }
+
+                // Record outcome
+                let outcome = {
+                    let state_read = metrics.timed_read(state, "execute").await;
+                    state_read
+                        .record_trade_outcome(opp, &pair_symbol, size, &trade_result, start_time, Decimal::ZERO)
+                        .await
+                };
+
+                // Update Risk Manager and venue health. Route mismatches,
+                // deadline aborts, compliance vetoes and loss cap refusals
+                // stop before submission and say nothing about the venues.
+                let mut state = metrics.timed_write(state, "execute").await;
+                if trade_result.failure_kind.is_none() {
+                    state.record_venue_results(&venues, outcome.was_successful).await;
+                }
+                state.track_confirmation(opp, size, trade_result.signature.as_deref(), &outcome);
+                state.risk_manager.record_trade(outcome).await;
+                state.risk_manager.release_trade(&opp.id.to_string());
+                state.publish_venue_exposure().await;
+            }
+            Err(e) => {
+                error!("❌ Trade failed (Executor Error): {}", e);
+
+                // Construct failed TradeResult
+                let failed_result = TradeResult {
+                    opportunity_id: opp.id,
+                    signature: None,
+                    success: false,
+                    actual_profit: Decimal::ZERO,
+                    executed_at: Utc::now(),
+                    error: Some(e.to_string()),
+                    simulated_profit: None,
+                    failure_kind: None,
+                };
+
+                // Record outcome
+                let outcome = {
+                    let state_read = metrics.timed_read(state, "execute").await;
+                    state_read
+                        .record_trade_outcome(opp, &pair_symbol, size, &failed_result, start_time, Decimal::ZERO)
+                        .await
+                };
+
+                // Update Risk Manager and venue health
+                let mut state = metrics.timed_write(state, "execute").await;
+                state.record_venue_results(&venues, outcome.was_successful).await;
+                state.risk_manager.record_trade(outcome).await;
+                state.risk_manager.release_trade(&opp.id.to_string());
+                state.publish_venue_exposure().await;
+            }
+        }
+    }
+}
+
+pub async fn run_bot() {
+    // Load config first
+    dotenvy::dotenv().ok();
+
+    // Initialize logging
+    logging::setup();
+
+    // Read MIN_PROFIT_THRESHOLD directly from environment at runtime
+    let min_profit_threshold: f64 = std::env::var("MIN_PROFIT_THRESHOLD")
+        .unwrap_or_else(|_| "0.5".to_string())
+        .parse()
+        .expect("Invalid MIN_PROFIT_THRESHOLD value");
+
+    // Create config with runtime-loaded value
+    let mut config = Config::from_env().unwrap_or_default();
+    config.min_profit_threshold = min_profit_threshold;
+
+    info!("🚀 Solana Arbitrage Bot starting...");
+    info!("   Min profit threshold: {}%", min_profit_threshold);
+    info!(
+        "   Priority fee: {} µL/CU",
+        config.priority_fee_micro_lamports
+    );
+    info!("   Slippage tolerance: {} bps", config.slippage_bps);
+    if config.wsol_mode {
+        info!("   wSOL mode: standing balance {} SOL", config.wsol_target_sol);
+    }
+    info!("   RPC commitment: {}", config.rpc_commitment);
+    info!("   Max retries: {}", config.max_retries);
+    info!("   RPC URL: {}", config.solana_rpc_url);
+
+    // Check for dry-run mode (watch-only never trades, so it counts as dry-run)
+    let watch_only = config.watch_only;
+    let dry_run = config.dry_run || watch_only;
+
+    // Initialize Alert Manager
+    // A channel whose encryption key is invalid is disabled, never sent in plaintext
+    let channel_privacy = |channel: &str, webhook: &Option<String>, redact: bool, key: &Option<String>| {
+        match ChannelPrivacy::new(redact, key.as_deref()) {
+            Ok(privacy) => (webhook.clone(), privacy),
+            Err(e) => {
+                error!("❌ {} alerts disabled: {:#}", channel, e);
+                (None, ChannelPrivacy::default())
+            }
+        }
+    };
+    let (telegram_webhook, telegram_privacy) = channel_privacy(
+        "Telegram",
+        &config.telegram_webhook_url,
+        config.telegram_alert_redact,
+        &config.telegram_alert_public_key,
+    );
+    let (discord_webhook, discord_privacy) = channel_privacy(
+        "Discord",
+        &config.discord_webhook_url,
+        config.discord_alert_redact,
+        &config.discord_alert_public_key,
+    );
+    let (escalation_webhook, escalation_privacy) = channel_privacy(
+        "Escalation",
+        &config.incident_escalation_webhook_url,
+        config.escalation_alert_redact,
+        &config.escalation_alert_public_key,
+    );
+    let incidents = Arc::new(IncidentStore::new(
+        (config.incident_escalate_minutes > 0)
+            .then(|| Duration::from_secs(config.incident_escalate_minutes * 60)),
+    ));
+    // Amounts in alerts are shown in the accounting currency, once its rate loads
+    let fx_rate = SharedFxRate::default();
+    let alert_manager = AlertManager::new(telegram_webhook, discord_webhook)
+        .with_privacy(telegram_privacy, discord_privacy)
+        .with_incidents(incidents.clone(), escalation_webhook, escalation_privacy)
+        .with_fx(fx_rate.clone());
+    spawn_incident_escalation(alert_manager.clone(), incidents.clone());
+
+    // Alert on startup
+    alert_manager.send_info(if watch_only {
+        "🚀 ArbEngine-Pro started (Mode: WATCH-ONLY)"
+    } else if dry_run {
+        "🚀 ArbEngine-Pro started (Mode: DRY-RUN)"
+    } else {
+        "🚀 ArbEngine-Pro started (Mode: LIVE TRADING)"
+    }).await;
+
+    if watch_only {
+        info!("👀 Running in WATCH-ONLY mode - no wallet loaded, opportunities are only reported");
+    } else if dry_run {
+        info!("⚠️  Running in DRY RUN mode - no real trades will be executed");
+    } else {
+        warn!("⚠️  LIVE TRADING MODE - Real trades will be executed!");
+    }
+
+    // Initialize RPC client for pre-flight checks
+    let rpc_client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new(config.solana_rpc_url.clone());
+
+    // Run Pre-flight Checks
+    info!("Running pre-flight safety checks...");
+    if let Ok(warnings) = run_preflight_checks(&rpc_client, &config).await {
+        if !warnings.is_empty() {
+             let mut msg = String::from("⚠️ Pre-flight warnings:\n");
+             for w in &warnings {
+                 warn!("{}", w);
+                 msg.push_str(&format!("- {}\n", w));
+             }
+             alert_manager.send_critical(&msg).await;
+
+             // Wait for user or continue if safe
+             if !dry_run {
+                 info!("Waiting 10 seconds before continuing...");
+                 tokio::time::sleep(Duration::from_secs(10)).await;
+             }
+        } else {
+             info!("✅ All pre-flight checks passed");
+        }
+    } else {
+         error!("❌ Pre-flight checks failed completely");
+         // Maybe exit? For now just log
+    }
+
+    // Initialize Feature Flags (env defaults; config overrides applied below)
+    let mut feature_flags = FeatureFlags::from_env();
+    let mut priority_fees = PriorityFeeControl::new();
+    let record_signer = config.sign_records.then(Wallet::env_keypair).flatten().map(Arc::new);
+    if config.sign_records && record_signer.is_none() {
+        warn!("SIGN_RECORDS is on but PRIVATE_KEY is missing or invalid; records are only hash-chained");
+    }
+    match solana_arb_core::audit_log::AuditLogger::new("data/audit.jsonl").await {
+        Ok(mut logger) => {
+            if let Some(signer) = &record_signer {
+                logger = logger.with_signer(signer.clone());
+            }
+            let logger = Arc::new(logger);
+            feature_flags = feature_flags.with_audit_logger(logger.clone());
+            priority_fees = priority_fees.with_audit_logger(logger);
+        }
+        Err(e) => warn!(
+            "Audit log unavailable, flag flips and fee overrides will only be kept in memory: {}",
+            e
+        ),
+    }
+    let feature_flags = Arc::new(feature_flags);
+    let priority_fees = Arc::new(priority_fees);
+
+    // Initialize System Health
+    let system_health = Arc::new(RwLock::new(SystemHealth::default()));
+    let event_policies = parse_overflow_policies(&config.event_bus_policies).unwrap_or_else(|e| {
+        warn!("EVENT_BUS_POLICIES ignored: {}", e);
+        Default::default()
+    });
+    let event_bus = Arc::new(
+        EventBus::new(config.event_bus_capacity)
+            .with_policies(event_policies)
+            .with_spill_dir(&config.event_bus_spill_dir),
+    );
+    let status_event_bus = event_bus.clone();
+
+    // Start Health Check Server
+    let health_clone = system_health.clone();
+    let mode = mode_label(watch_only, dry_run);
+    let strategy_profile = StrategySettings::from_config(&config).profile;
+    let admin_token = std::env::var("ADMIN_API_TOKEN").ok().filter(|t| !t.is_empty());
+    if admin_token.is_none() {
+        warn!("ADMIN_API_TOKEN is not set: admin API routes will refuse every change");
+    }
+    let flag_routes = api::flags::flag_routes(feature_flags.clone(), admin_token.clone());
+    let priority_fee_routes = api::priority_fee::priority_fee_routes(
+        priority_fees.clone(),
+        config.priority_fee_micro_lamports,
+        admin_token.clone(),
+    );
+    let status_fees = priority_fees.clone();
+    let configured_fee = config.priority_fee_micro_lamports;
+    let session_db = match TimescaleClient::new(&config.database_url).await {
+        Ok(db) => Some(Arc::new(db)),
+        Err(e) => {
+            warn!("Session store disabled (invalid DATABASE_URL): {}", e);
+            None
+        }
+    };
+    let route_scoreboard = Arc::new(RwLock::new(RouteScoreboard::new(Default::default())));
+    let scoreboard_routes = api::scoreboard::scoreboard_routes(route_scoreboard.clone());
+    let size_feedback = Arc::new(SizeFeedback::new(Default::default()));
+    let status_size_feedback = size_feedback.clone();
+    let blacklist = Arc::new(RwLock::new(Blacklist::load(BLACKLIST_PATH, Default::default())));
+    let blacklist_routes = api::blacklist::blacklist_routes(blacklist.clone(), admin_token.clone());
+    let incident_routes = api::incidents::incident_routes(incidents, admin_token.clone());
+    let discovery = Arc::new(RwLock::new(PoolDiscovery::load(DISCOVERY_PATH, discovery_config(&config))));
+    let discovery_routes = api::discovery::discovery_routes(discovery.clone(), admin_token.clone());
+    let replay_routes = api::replay::replay_routes(
+        config.price_snapshot_dir.as_deref().map(PriceSnapshots::new),
+        ReplaySettings::from_config(&config),
+        admin_token.clone(),
+    );
+    let regime = Arc::new(RwLock::new(RegimeTracker::new(
+        config.regime_window_ticks,
+        config.regime_dislocation_ratio,
+    )));
+    let regime_routes = api::regime::regime_routes(regime.clone());
+    let trade_traces = Arc::new(TradeTraces::new());
+    let latency_slo = Arc::new(LatencySlo::new(execution_deadline(&config)));
+    let status_slo = latency_slo.clone();
+    let trace_routes = api::traces::trace_routes(trade_traces.clone());
+    let inflight = Arc::new(InFlightTrades::new());
+    let inflight_routes = api::inflight::inflight_routes(inflight.clone());
+    let control = Arc::new(TradingControl::new());
+    let control_routes = api::control::control_routes(control.clone(), admin_token.clone());
+    let (manual_trades, manual_orders) = ManualTrades::channel();
+    let manual_trade_routes = api::manual_trade::manual_trade_routes(manual_trades, admin_token.clone());
+    let status_control = control.clone();
+    let reconciliation_report: Arc<std::sync::Mutex<Option<ReconciliationReport>>> = Arc::default();
+    let status_reconciliation = reconciliation_report.clone();
+    let quarantine = (config.quarantine_enabled && !watch_only)
+        .then(|| Arc::new(Quarantine::load(&config.quarantine_state_path)));
+    if let Some(record) = quarantine.as_ref().and_then(|q| q.record()) {
+        warn!("🔒 Wallet {} is quarantined since {}: {}", record.wallet, record.since, record.reason);
+    }
+    let quarantine_routes = match &quarantine {
+        Some(quarantine) => api::quarantine::quarantine_routes(quarantine.clone(), admin_token.clone()),
+        None => Router::new(),
+    };
+    let status_quarantine = quarantine.clone();
+    let postmortems = PostmortemConfig::from_config(&config).map(|postmortem_config| {
+        Arc::new(Postmortems::new(
+            postmortem_config,
+            trade_traces.clone(),
+            config.price_snapshot_dir.as_deref().map(PriceSnapshots::new),
+            config.solana_rpc_url.clone(),
+        ))
+    });
+    let postmortem_routes =
+        api::postmortems::postmortem_routes(config.postmortem_dir.clone().into(), admin_token.clone());
+    let session_routes = match &session_db {
+        Some(db) => api::sessions::session_routes(db.clone()),
+        None => Router::new(),
+    };
+    let trade_webhooks = config.trade_webhook_url.clone().map(|url| {
+        if config.trade_webhook_secret.is_none() {
+            warn!("TRADE_WEBHOOK_SECRET is not set, trade webhooks will be sent unsigned");
+        }
+        TradeWebhooks::new(TradeWebhookConfig {
+            url,
+            secret: config.trade_webhook_secret.clone(),
+            max_attempts: config.trade_webhook_max_attempts,
+            journal_path: "data/trade_webhooks.jsonl".into(),
+        })
+    });
+    let webhook_routes = match &trade_webhooks {
+        Some(webhooks) => api::webhooks::webhook_routes(webhooks.clone(), admin_token.clone()),
+        None => Router::new(),
+    };
+    let replication = Arc::new(Replication::from_config(&config));
+    let replication_routes =
+        api::replication::replication_routes(replication.clone(), admin_token.clone());
+    let status_replication = replication.clone();
+    let endpoints = SharedEndpoints::default();
+    let status_endpoints = endpoints.clone();
+    let (health_listener, health_endpoint) = listeners::bind(
+        &config.health_bind_addr,
+        config.health_port,
+        config.port_fallback_range,
+    )
+    .await;
+    match (&health_endpoint.bound, &health_endpoint.error) {
+        (Some(bound), _) => info!("🏥 Health check server running on http://{}", bound),
+        (None, error) => error!(
+            "Failed to bind health check server on {} or the {} ports after it: {}",
+            health_endpoint.configured,
+            config.port_fallback_range,
+            error.as_deref().unwrap_or_default()
+        ),
+    }
+    if let Ok(mut endpoints) = endpoints.write() {
+        endpoints.health = Some(health_endpoint);
+    }
+    tokio::spawn(async move {
+        let app = Router::new()
+            .route("/health", get(|| async {
+                Json(json!({
+                    "status": "ok",
+                    "timestamp": Utc::now().to_rfc3339()
+                }))
+            }))
+            .route("/status", get(move || {
+                let health = health_clone.clone();
+                let fee_override = status_fees.active();
+                let latency = status_slo.status();
+                let replication = status_replication.status();
+                let paused = status_control.paused();
+                let reconciliation = status_reconciliation
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone();
+                let quarantine = status_quarantine.as_ref().and_then(|q| q.record());
+                let event_subscribers = status_event_bus.subscribers();
+                let endpoints = status_endpoints.read().map(|e| e.clone()).unwrap_or_default();
+                let route_sizes = status_size_feedback.status();
+                async move {
+                    let h = health.read().await;
+                    Json(json!({
+                        "mode": mode,
+                        "strategy_profile": strategy_profile,
+                        "strategies": h.strategies,
+                        "paused": paused,
+                        "reconciliation": reconciliation,
+                        "quarantine": quarantine,
+                        "event_subscribers": event_subscribers,
+                        "is_running": h.is_running,
+                        "total_trades": h.total_trades,
+                        "circuit_breaker": h.circuit_breaker_state,
+                        "balance_usd": h.balance_usd,
+                        "venue_exposure": h.venue_exposure,
+                        "committed_capital": h.committed_capital,
+                        "canary": h.canary,
+                        "route_filter": h.route_filter,
+                        "pending_finalization": h.pending_finalization,
+                        "unwind": h.unwind,
+                        "fee_reserve": h.fee_reserve,
+                        "hedging": h.hedging,
+                        "execution_modes": h.execution_modes,
+                        "route_size_scales": route_sizes,
+                        "priority_fee": {
+                            "configured_micro_lamports": configured_fee,
+                            "effective_micro_lamports": fee_override
+                                .as_ref()
+                                .map_or(configured_fee, |fee| fee.micro_lamports),
+                            "override": fee_override,
+                        },
+                        "execution_latency": latency,
+                        "replication": replication,
+                        "jupiter": {
+                            "status": h.jupiter,
+                            "latency_saving_ms": h.jupiter.latency_saving_ms(),
+                        },
+                        "endpoints": endpoints,
+                        "uptime_seconds": h.start_time.elapsed().as_secs()
+                    }))
+                }
+            }))
+            .merge(flag_routes)
+            .merge(priority_fee_routes)
+            .merge(scoreboard_routes)
+            .merge(blacklist_routes)
+            .merge(discovery_routes)
+            .merge(incident_routes)
+            .merge(replay_routes)
+            .merge(regime_routes)
+            .merge(trace_routes)
+            .merge(inflight_routes)
+            .merge(control_routes)
+            .merge(manual_trade_routes)
+            .merge(quarantine_routes)
+            .merge(postmortem_routes)
+            .merge(session_routes)
+            .merge(webhook_routes)
+            .merge(replication_routes);
+
+        if let Some(listener) = health_listener {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("Health check server error: {}", e);
+            }
+        }
+    });
+
+    // Define trading pairs
+    let mut pairs = vec![
+        TokenPair::new("SOL", "USDC"),
+        TokenPair::new("RAY", "USDC"),
+        TokenPair::new("ORCA", "USDC"),
+        TokenPair::new("JUP", "USDC"),
+    ];
+    if config.lst_pairs_enabled {
+        pairs.extend(
+            sanctum::LIQUID_STAKING_TOKENS
+                .iter()
+                .map(|(symbol, _)| TokenPair::new(*symbol, "SOL")),
+        );
+    }
+
+    // Initialize metrics
+    let metrics = Arc::new(MetricsCollector::new().expect("Failed to initialize metrics"));
+
+    // Start metrics server
+    let metrics_clone = metrics.clone();
+    let recorder = Arc::new(MetricsRecorder::new(
+        &config.metrics_recorder_path,
+        config.metrics_recorder_retention_days,
+    ));
+    if config.metrics_recorder_enabled {
+        spawn_metrics_recorder(
+            metrics.clone(),
+            recorder.clone(),
+            Duration::from_secs(config.metrics_recorder_interval_secs.max(1)),
+        );
+    }
+    let (metrics_listener, metrics_endpoint) = listeners::bind(
+        &config.metrics_bind_addr,
+        config.metrics_port,
+        config.port_fallback_range,
+    )
+    .await;
+    match (&metrics_endpoint.bound, &metrics_endpoint.error) {
+        (Some(bound), _) => info!("📊 Metrics server running on http://{}/metrics", bound),
+        (None, error) => error!(
+            "Failed to bind metrics server on {} or the {} ports after it: {}",
+            metrics_endpoint.configured,
+            config.port_fallback_range,
+            error.as_deref().unwrap_or_default()
+        ),
+    }
+    if let Ok(mut endpoints) = endpoints.write() {
+        endpoints.metrics = Some(metrics_endpoint);
+    }
+    if let Some(listener) = metrics_listener {
+        let app = api::metrics::metrics_routes(metrics_clone, recorder, endpoints.clone());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("Metrics server error: {}", e);
+            }
+        });
+    }
+
+    // Report where the servers ended up; a moved or missing one is easy to miss
+    let bound = endpoints.read().map(|e| e.clone()).unwrap_or_default();
+    if bound.degraded() {
+        alert_manager
+            .send_critical(&format!("🔌 Server ports degraded:\n{}", bound.summary()))
+            .await;
+    } else {
+        alert_manager
+            .send_info(&format!("🔌 Listening:\n{}", bound.summary()))
+            .await;
+    }
+
+    // Initialize Config Manager
+    let config_path = "config/trading_config.json";
+    let config_manager = Arc::new(ConfigManager::new(config_path)
+        .unwrap_or_else(|e| {
+            warn!("Failed to load dynamic config: {}. Using defaults.", e);
+            // Panic or fallback? Since constructor failed (file read/parse), let's try to survive if possible or panic.
+            // But ConfigManager::new implies loading.
+            panic!("Critical: Failed to load {}: {}", config_path, e);
+        }));
+
+    // Verify persisted state and config were written by a compatible version
+    let report = compatibility::run_compatibility_checks(
+        &config_manager.get().await,
+        std::path::Path::new(history_file_path(dry_run)),
+        std::path::Path::new(RETURN_ARCHIVE_PATH),
+    );
+    report.log();
+    if !report.allows_live_trading() {
+        if dry_run {
+            warn!("⚠️ Compatibility problems found - continuing in DRY-RUN mode only");
+        } else {
+            error!("❌ Refusing to start LIVE trading: incompatible config or state");
+            alert_manager
+                .send_critical(&format!(
+                    "❌ Live trading refused at startup.\n{}Fix or migrate the files above, or run with DRY_RUN=true.",
+                    report.summary()
+                ))
+                .await;
+            return;
+        }
+    }
+
+    // Money limits are configured in the accounting currency, so live trading
+    // needs its rate before any limit can be enforced
+    match fetch_fx_rate(&config).await {
+        Ok(rate) => {
+            if !rate.is_usd() {
+                info!("   Accounting currency: {} at {} USD", rate.currency, rate.usd_per_unit);
+                if let Err(e) = rate.save(fx::FX_RATE_PATH) {
+                    warn!("Failed to save FX rate to {}: {}", fx::FX_RATE_PATH, e);
+                }
+            }
+            fx_rate.set(rate);
+        }
+        Err(e) if dry_run => {
+            warn!("⚠️ {:#} - amounts and limits stay in USD until the rate loads", e);
+        }
+        Err(e) => {
+            error!("❌ Refusing to start LIVE trading: {:#}", e);
+            alert_manager
+                .send_critical(&format!(
+                    "❌ Live trading refused at startup: no {} exchange rate ({:#}).",
+                    config.accounting_currency, e
+                ))
+                .await;
+            return;
+        }
+    }
+
+    // Apply feature flag overrides from the dynamic config
+    feature_flags
+        .apply_config(&config_manager.get().await.features)
+        .await;
+
+    let watcher_fees = priority_fees.clone();
+
+    // Create bot state
+    let state = Arc::new(RwLock::new(BotState::new(
+        &config,
+        dry_run,
+        watch_only,
+        feature_flags,
+        priority_fees,
+        metrics,
+        alert_manager,
+        system_health,
+        event_bus,
+        config_manager,
+        route_scoreboard,
+        size_feedback,
+        blacklist,
+        regime,
+        trade_traces,
+        inflight,
+        latency_slo,
+        record_signer,
+    )));
+
+    // Wire EventBus into RiskManager and load route exclusions
+    {
+        let mut s = state.write().await;
+        let event_bus = s.event_bus.clone();
+        s.risk_manager.set_event_bus(event_bus).await;
+        s.fx = fx_rate;
+        s.control = control;
+        if let Some(quarantine) = &quarantine {
+            if let Some(executor) = &mut s.executor {
+                executor.set_quarantine(quarantine.clone());
+            }
+        }
+        s.quarantine = quarantine.clone();
+        s.apply_exposure_limit().await;
+        s.apply_fx().await;
+        let routing = s.config_manager.get().await.routing;
+        s.route_scoreboard.write().await.set_config(routing.clone());
+        s.size_feedback.set_config(routing.clone());
+        s.blacklist.write().await.set_config(routing.clone());
+        s.route_policy.set_config(routing);
+        s.refresh_route_filter().await;
+        let hedging = s.config_manager.get().await.hedging;
+        s.hedge_manager.set_config(hedging.hedge_config());
+        if let Some(executor) = &s.executor {
+            let dynamic = s.config_manager.get().await;
+            executor.set_slippage(dynamic.slippage);
+            executor.set_max_trade_loss(dynamic.risk.max_trade_loss_usd);
+        }
+    }
+
+    // Plug in an external strategy service
+    if !config.remote_strategy_url.is_empty() {
+        let remote = RemoteStrategy::new(RemoteStrategyConfig {
+            timeout: Duration::from_millis(config.remote_strategy_timeout_ms.max(1)),
+            ..RemoteStrategyConfig::new(&config.remote_strategy_url)
+        });
+        match remote {
+            Ok(remote) => {
+                match remote.handshake().await {
+                    Ok((name, version)) => {
+                        info!("🧠 Strategy initialized: remote {} v{}", name, version)
+                    }
+                    // Still registered so it is picked up once the service is up
+                    Err(e) => warn!("Remote strategy handshake failed: {}", e),
+                }
+                state.write().await.strategies.push(Box::new(remote));
+            }
+            Err(e) => error!("Remote strategy disabled: {}", e),
+        }
+    }
+
+    if trade_webhooks.is_some() {
+        state.write().await.trade_webhooks = trade_webhooks;
+    }
+
+    // Keep recent events for post-mortems of losing trades
+    if let Some(postmortems) = postmortems {
+        let mut s = state.write().await;
+        tokio::spawn(postmortems.events().clone().follow(s.event_bus.subscribe_as("postmortems")));
+        s.postmortems = Some(postmortems);
+    }
+
+    // Publish trading events for the API server's WebSocket clients
+    #[cfg(unix)]
+    if let Some(path) = &config.event_socket_path {
+        use solana_arb_core::ipc::{socket::IpcPublisher, IpcMessage};
+        match IpcPublisher::bind(path, config.event_bus_capacity).await {
+            Ok(publisher) => {
+                let mut events = state.read().await.event_bus.subscribe_as("event_socket");
+                tokio::spawn(async move {
+                    while let Some(event) = events.recv().await {
+                        publisher.publish(&IpcMessage::Event(event));
+                    }
+                });
+            }
+            Err(e) => warn!("Trading events will not be published on {}: {}", path, e),
+        }
+    }
+
+    // Replicate risk state to a warm standby, or follow the primary as one
+    if let Some(primary_url) = replication.primary_url() {
+        info!("🕰️ Running as warm standby of {} - no trades until promoted", primary_url);
+    }
+    state.write().await.replication = replication;
+    spawn_replication(
+        state.clone(),
+        Duration::from_millis(config.replication_sync_ms.max(100)),
+        admin_token,
+    );
+    spawn_manual_trades(state.clone(), manual_orders);
+
+    // Record this run in the session store
+    if let Some(db) = session_db {
+        let mut s = state.write().await;
+        let snapshot = serde_json::to_value(s.config_manager.get().await).unwrap_or_default();
+        let session = Session::start(&s.session_id, mode, snapshot);
+        let inserted = tokio::time::timeout(Duration::from_secs(5), db.insert_session(&session))
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out connecting to database")));
+        match inserted {
+            Ok(()) => {
+                info!(
+                    "🗂️ Session {} started (rev: {})",
+                    session.id,
+                    session.git_rev.as_deref().unwrap_or("unknown")
+                );
+                s.session_db = Some(db);
+            }
+            Err(e) => warn!("Session store unavailable, session will not be recorded: {}", e),
+        }
+    }
+
+    // Mirror trade history and risk events into Postgres
+    if config.trade_store_enabled {
+        let session_id = state.read().await.session_id.clone();
+        let migrated = match TradeStore::new(&config.database_url, &session_id).await {
+            Ok(store) => tokio::time::timeout(Duration::from_secs(5), store.migrate())
+                .await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out connecting to database")))
+                .map(|()| store),
+            Err(e) => Err(e),
+        };
+        match migrated {
+            Ok(store) => {
+                let mut s = state.write().await;
+                s.history_recorder.add_sink(Arc::new(store.clone()));
+                let mut events = s.event_bus.subscribe_as("trade_store");
+                s.trade_store = Some(store.clone());
+                tokio::spawn(async move {
+                    while let Some(event) = events.recv().await {
+                        if let Err(e) = store.insert_risk_event(&event).await {
+                            warn!("Failed to record risk event in trade store: {}", e);
+                        }
+                    }
+                });
+                info!("🗄️ Trade store enabled");
+            }
+            Err(e) => warn!("Trade store unavailable, history stays file-only: {}", e),
+        }
+    }
+
+    // Check what the bot assumes about its wallet against the chain before trading
+    if config.reconcile_on_startup && !dry_run {
+        let s = state.read().await;
+        // The wallet belongs to the primary until this instance is promoted
+        let result = if s.replication.is_standby() { None } else { s.reconcile().await };
+        match result {
+            Some(Ok(report)) => {
+                if let Err(e) = std::fs::write(
+                    reconciliation::REPORT_PATH,
+                    serde_json::to_string_pretty(&report).unwrap_or_default(),
+                ) {
+                    warn!("Failed to write {}: {}", reconciliation::REPORT_PATH, e);
+                }
+                if report.is_clean() {
+                    info!("🧾 Reconciled with the chain: nothing in conflict");
+                    for line in report.summary().lines() {
+                        warn!("🧾 {}", line);
+                    }
+                } else {
+                    let reason = format!(
+                        "startup reconciliation: {} in conflict",
+                        reconciliation::conflict_counts(&report)
+                    );
+                    error!("🧾 Live trading paused, {}:\n{}", reason, report.summary());
+                    s.control.pause("reconciliation", Some(reason.clone()));
+                    s.alert_manager
+                        .send_critical(&format!(
+                            "🧾 Live trading PAUSED, {}. See {} and resume with POST /control/resume once settled.\n{}",
+                            reason,
+                            reconciliation::REPORT_PATH,
+                            report.summary()
+                        ))
+                        .await;
+                }
+                *reconciliation_report.lock().unwrap_or_else(|e| e.into_inner()) = Some(report);
+            }
+            Some(Err(e)) => {
+                let reason = format!("startup reconciliation failed: {:#}", e);
+                error!("🧾 Live trading paused, {}", reason);
+                s.control.pause("reconciliation", Some(reason.clone()));
+                s.alert_manager
+                    .send_critical(&format!("🧾 Live trading PAUSED, {}", reason))
+                    .await;
+            }
+            None => {}
+        }
+    }
+
+    // Follow live trades until finalized so fork rollbacks revert their P&L
+    if !watch_only && !dry_run {
+        spawn_confirmation_tracker(state.clone(), config.solana_rpc_url.clone());
+    }
+
+    // Learn rate limiter budgets from 429s and latency
+    if config.rate_limit_autotune {
+        let s = state.read().await;
+        spawn_rate_limit_tuner(
+            vec![
+                (RPC_LIMITER, s.rpc_rate_limiter.clone(), rate_limit_tuner(&config, RPC_LIMITER)),
+                (
+                    JUPITER_LIMITER,
+                    s.jupiter_rate_limiter.clone(),
+                    rate_limit_tuner(&config, JUPITER_LIMITER),
+                ),
+            ],
+            s.metrics.clone(),
+            Duration::from_secs(config.rate_limit_tune_interval_secs.max(5)),
+        );
+    }
+
+    // Keep SOL for fees topped up, blocking trades while it is low
+    if !watch_only {
+        spawn_fee_reserve_monitor(
+            state.clone(),
+            Duration::from_secs(config.fee_reserve_check_secs.max(10)),
+        );
+    }
+
+    // Stop signing on signs of a leaked key
+    if let Some(quarantine) = quarantine {
+        spawn_quarantine_monitor(
+            state.clone(),
+            quarantine,
+            config.solana_rpc_url.clone(),
+            Duration::from_secs(config.quarantine_check_secs.max(5)),
+            lamports(Decimal::from_f64(config.quarantine_max_outflow_sol).unwrap_or(Decimal::new(5, 1))),
+        );
+    }
+
+    // Track flash loan reserve liquidity for sizing
+    if !watch_only {
+        spawn_flash_liquidity_monitor(
+            state.clone(),
+            pairs.iter().map(|p| p.base.clone()).collect(),
+            Duration::from_secs(config.flash_loan_liquidity_refresh_secs.max(5)),
+        );
+    }
+
+    // Work exposure above a lowered limit off with housekeeping trades
+    if !watch_only {
+        spawn_unwind_worker(
+            state.clone(),
+            Duration::from_secs(config.unwind_interval_secs.max(1)),
+        );
+    }
+
+    // Sell restricted base tokens at the end of the day
+    if !watch_only {
+        spawn_flatten_worker(state.clone());
+    }
+
+    // Compact past days of the price archive
+    if let (Some(dir), true) = (&config.price_snapshot_dir, config.price_compaction_enabled) {
+        spawn_price_compaction(PriceSnapshots::new(dir), &config);
+    }
+
+    // Publish a status bundle safe to host publicly
+    if config.status_page_enabled {
+        spawn_status_page(state.clone(), mode, &config);
+    }
+
+    // Answer operator commands sent to the Telegram bot
+    if let Some(commands) = TelegramCommands::from_config(&config) {
+        info!("💬 Telegram commands enabled");
+        spawn_telegram_commands(state.clone(), commands);
+    }
+
+    // Keep the accounting currency's rate fresh
+    if config.accounting_currency != fx::USD {
+        spawn_fx_refresh(state.clone(), config.clone());
+    }
+
+    // Hedge costly spot inventory with Drift perps (behind the perp_hedging flag)
+    if !watch_only {
+        if dry_run || !config.drift_gateway_url.is_empty() {
+            spawn_hedger(state.clone(), Duration::from_secs(config.hedge_interval_secs.max(5)));
+        } else {
+            info!("DRIFT_GATEWAY_URL not set - perp hedging is only available in dry runs");
+        }
+    }
+
+    // Probe the Jupiter API (and the public one, for comparison, when self-hosted)
+    if !watch_only {
+        spawn_jupiter_probe(
+            state.clone(),
+            config.jupiter_api_url.clone(),
+            config.jupiter_compare_public,
+            Duration::from_secs(config.jupiter_probe_interval_secs.max(5)),
+        );
+    }
+
+    // Start Config Watcher (Polling)
+    // Execution-affecting changes, priority fee moves included, put the risk layer into canary mode
+    let watcher_state = state.clone();
+    tokio::spawn(async move {
+        let mut seen_fee = watcher_fees.fee_or(configured_fee);
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            let (config_manager, feature_flags) = {
+                let s = watcher_state.read().await;
+                (s.config_manager.clone(), s.feature_flags.clone())
+            };
+            let previous = config_manager.get().await;
+            let previous_features = previous.features.clone();
+            let reloaded = config_manager.reload().await;
+
+            // Only flags edited in the file are applied, so runtime flips survive reloads
+            if reloaded.is_ok() {
+                let edited: HashMap<String, bool> = config_manager
+                    .get()
+                    .await
+                    .features
+                    .into_iter()
+                    .filter(|(name, enabled)| previous_features.get(name) != Some(enabled))
+                    .collect();
+                feature_flags.apply_config(&edited).await;
+
+                let mut s = watcher_state.write().await;
+                let routing = config_manager.get().await.routing;
+                s.route_scoreboard.write().await.set_config(routing.clone());
+                s.size_feedback.set_config(routing.clone());
+                s.blacklist.write().await.set_config(routing.clone());
+                s.route_policy.set_config(routing);
+                s.refresh_route_filter().await;
+                s.apply_exposure_limit().await;
+                let hedging = config_manager.get().await.hedging;
+                s.hedge_manager.set_config(hedging.hedge_config());
+                if let Some(executor) = &s.executor {
+                    let dynamic = config_manager.get().await;
+                    executor.set_slippage(dynamic.slippage);
+                    executor.set_max_trade_loss(dynamic.risk.max_trade_loss_usd);
+                }
+            }
+
+            if let Ok(changes) = &reloaded {
+                let current = config_manager.get().await;
+                if serde_json::to_value(&previous).ok() != serde_json::to_value(&current).ok() {
+                    watcher_state.read().await.event_bus.publish(TradingEvent::ConfigReloaded {
+                        changes: changes.clone(),
+                    });
+                }
+            }
+
+            let mut changes = match reloaded {
+                Ok(changes) => changes,
+                Err(e) => {
+                    error!("Failed to reload configuration: {}", e);
+                    Vec::new()
+                }
+            };
+            changes.extend(watcher_fees.fee_change(configured_fee, &mut seen_fee));
+            if !changes.is_empty() {
+                let reason = changes.join(", ");
+                let alert_manager = {
+                    let mut s = watcher_state.write().await;
+                    s.risk_manager.canary.arm(&reason);
+                    let canary = s.risk_manager.canary.status();
+                    s.system_health.write().await.canary = Some(canary);
+                    s.alert_manager.clone()
+                };
+                alert_manager
+                    .send_info(&format!("🐤 Config changed ({}) - canary mode armed", reason))
+                    .await;
+            }
         }
+    });
 
-        // Simulate successful outcome
-        let outcome = TradeOutcome {
-            timestamp: Utc::now(),
-            pair: pair_symbol,
-            profit_loss: size * opp.net_profit_pct / Decimal::from(100),
-            was_successful: true,
+    // Propose pairs from the Raydium and Orca pool registries
+    if config.pool_discovery_enabled {
+        spawn_pool_discovery(
+            state.clone(),
+            discovery.clone(),
+            pairs.clone(),
+            Duration::from_secs(config.pool_discovery_interval_secs.max(60)),
+        );
+    }
+
+    let streams = start_price_streams(&state, &pairs, &config).await;
+
+    // Run trading loop
+    run_trading_loop(
+        state,
+        pairs,
+        discovery,
+        streams,
+        Duration::from_millis(config.price_poll_interval_ms),
+        config.pipeline_queue_depth,
+    )
+    .await;
+}
+
+/// Streams prices of `pairs` from every venue with a WebSocket feed, and
+/// from the configured pool accounts, when streaming is enabled. `None`
+/// polls every pair.
+async fn start_price_streams(
+    state: &Arc<RwLock<BotState>>,
+    pairs: &[TokenPair],
+    config: &Config,
+) -> Option<Arc<StreamingPriceAggregator>> {
+    if !state.read().await.feature_flags.is_enabled(feature_flags::WS_STREAMING) {
+        return None;
+    }
+    let streams = Arc::new(StreamingPriceAggregator::spawn(
+        PRICE_STREAM_CAPACITY,
+        Duration::from_millis(config.price_stream_stale_ms),
+    ));
+    let mut manager = WebSocketManager::new(streams.sender());
+    match parse_pool_accounts(&config.stream_pool_accounts, &TokenRegistry::mainnet()) {
+        Ok(pools) if !pools.is_empty() => {
+            let ws_url = if config.solana_ws_url.is_empty() {
+                config.solana_rpc_url.replacen("http", "ws", 1)
+            } else {
+                config.solana_ws_url.clone()
+            };
+            info!("📡 Streaming {} pool accounts from {}", pools.len(), ws_url);
+            manager = manager.with_pool_accounts(&ws_url, Commitments::from_config(config).reads_label(), pools);
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Invalid STREAM_POOL_ACCOUNTS: {}. Orca and Meteora are polled.", e),
+    }
+    let manager = Arc::new(manager);
+    let mut venues = HashSet::new();
+    for pair in pairs {
+        for dex in manager.streamed_dexes(pair) {
+            venues.insert(dex);
+            streams.expect(dex, pair);
+            let manager = manager.clone();
+            let pair = pair.clone();
+            tokio::spawn(async move { manager.start_with_reconnection(dex, pair).await });
+        }
+    }
+    info!(
+        "📡 Streaming prices of {} pairs from {} venues",
+        pairs.len(),
+        venues.len()
+    );
+    Some(streams)
+}
+
+/// The configured pairs plus those enabled by pool discovery. Tokens of a
+/// newly enabled pair are added to the executor's registry first.
+async fn active_pairs(
+    state: &Arc<RwLock<BotState>>,
+    configured: &[TokenPair],
+    discovery: &RwLock<PoolDiscovery>,
+) -> Vec<TokenPair> {
+    let mut pairs = configured.to_vec();
+    for proposal in discovery.read().await.enabled() {
+        if pairs.contains(&proposal.pair) {
+            continue;
+        }
+        let Some(tokens) = proposal.tokens() else {
+            continue;
         };
+        let unknown = state
+            .read()
+            .await
+            .executor
+            .as_ref()
+            .is_some_and(|executor| tokens.iter().any(|token| !executor.knows_token(&token.mint)));
+        if unknown {
+            if let Some(executor) = state.write().await.executor.as_mut() {
+                info!("🔎 Trading discovered pair {}", proposal.pair);
+                for token in tokens {
+                    executor.register_token(token);
+                }
+            }
+        }
+        pairs.push(proposal.pair);
+    }
+    pairs
+}
 
-        let mut state = state.write().await;
-        state.risk_manager.record_trade(outcome).await;
-    } else {
-        // Real execution via Jupiter API
-        info!(
-            "🟢 Executing: Buy {} on {}, Sell on {} | Size: ${} | Expected Profit: {}%",
-            pair_symbol, opp.buy_dex, opp.sell_dex, size, opp.net_profit_pct
-        );
+/// Every `interval`, scan the Raydium and Orca pool registries for pairs of
+/// whitelisted tokens and screen the mints of new proposals. Approved pairs
+/// are enabled right away behind the auto_pair_listing flag, otherwise they
+/// wait for `POST /discovery/enable`.
+fn spawn_pool_discovery(
+    state: Arc<RwLock<BotState>>,
+    discovery: Arc<RwLock<PoolDiscovery>>,
+    configured: Vec<TokenPair>,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let raydium = RaydiumProvider::new();
+        let orca = OrcaProvider::new();
+        let screener = TokenScreener::new([USDC_MINT.to_string(), SOL_MINT.to_string()]);
+        loop {
+            let (raydium_pools, orca_pools) = tokio::join!(raydium.list_pools(), orca.list_pools());
+            let mut pools = Vec::new();
+            for (dex, listed) in [("Raydium", raydium_pools), ("Orca", orca_pools)] {
+                match listed {
+                    Ok(mut listed) => pools.append(&mut listed),
+                    Err(e) => warn!("Pool discovery: {} registry unavailable: {}", dex, e),
+                }
+            }
 
-        let result: Result<TradeResult> = {
-            let state_read = state.read().await;
-            state_read
-                .executor
-                .execute(
-                    &state_read.wallet,
-                    opp,
-                    size,
-                    true,
-                    &rpc_url,
-                    state_read.jito_client.as_ref(),
+            let to_screen = discovery.write().await.update(&pools, &configured);
+            let (rpc_batcher, auto_enable, alert_manager) = {
+                let s = state.read().await;
+                (
+                    s.rpc_batcher.clone(),
+                    s.feature_flags.is_enabled(feature_flags::AUTO_PAIR_LISTING),
+                    s.alert_manager.clone(),
+                )
+            };
+            for pair in to_screen {
+                let Some(mints) = discovery
+                    .read()
+                    .await
+                    .get(&pair)
+                    .map(|p| [p.base_mint.clone(), p.quote_mint.clone()])
+                else {
+                    continue;
+                };
+                let [base, quote] = match screen_mints(&screener, &rpc_batcher, &mints).await {
+                    Ok(verdicts) => verdicts,
+                    Err(e) => {
+                        warn!("Pool discovery: cannot screen {}: {}", pair, e);
+                        continue;
+                    }
+                };
+                let status = discovery.write().await.apply_screen(&pair, base, quote, auto_enable);
+                let Some(proposal) = discovery.read().await.get(&pair).cloned() else {
+                    continue;
+                };
+                let summary = format!(
+                    "{} ({} venues, ${:.0} liquidity)",
+                    pair,
+                    proposal.venues.len(),
+                    proposal.liquidity_usd
+                );
+                match status {
+                    Some(ProposalStatus::Enabled) => {
+                        alert_manager.send_info(&format!("🔎 Listed discovered pair {}", summary)).await;
+                    }
+                    Some(ProposalStatus::Proposed) => {
+                        alert_manager
+                            .send_info(&format!(
+                                "🔎 New pair proposed: {} - enable with POST /discovery/enable",
+                                summary
+                            ))
+                            .await;
+                    }
+                    _ => info!(
+                        "🔎 Discovered pair {} rejected: {}",
+                        summary,
+                        proposal.reason.as_deref().unwrap_or("unknown")
+                    ),
+                }
+            }
+            if let Err(e) = discovery.read().await.save() {
+                warn!("Failed to save discovered pairs: {}", e);
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Screen the base and quote mints of a proposal in one batched lookup
+async fn screen_mints(
+    screener: &TokenScreener,
+    rpc_batcher: &RpcBatcher,
+    mints: &[String; 2],
+) -> Result<[ScreenVerdict; 2]> {
+    let mut keys = Vec::new();
+    for mint in mints {
+        match Pubkey::from_str(mint) {
+            Ok(key) => keys.push(key),
+            Err(_) => {
+                let rejected = ScreenVerdict::Rejected {
+                    reason: format!("invalid mint address {}", mint),
+                };
+                return Ok([rejected.clone(), rejected]);
+            }
+        }
+    }
+    let accounts = rpc_batcher.get_multiple_accounts(&keys).await?;
+    Ok([
+        screener.screen(&mints[0], accounts[0].as_ref()),
+        screener.screen(&mints[1], accounts[1].as_ref()),
+    ])
+}
+
+/// Latest rate of the accounting currency from its Pyth feed (1 for USD)
+async fn fetch_fx_rate(config: &Config) -> anyhow::Result<FxRate> {
+    let currency = config.accounting_currency.as_str();
+    if currency == fx::USD {
+        return Ok(FxRate::default());
+    }
+    let feed_id = match config.fx_feed_id.as_str() {
+        "" => fx::default_feed_id(currency)
+            .with_context(|| format!("no known Pyth feed for {}, set FX_FEED_ID", currency))?,
+        feed_id => feed_id,
+    };
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    fx::fetch_pyth_rate(&client, &config.fx_oracle_url, feed_id, currency)
+        .await
+        .with_context(|| format!("fetching the {} rate from {}", currency, config.fx_oracle_url))
+}
+
+/// Every FX_REFRESH_SECS, refresh the accounting currency's rate, re-convert
+/// the money limits and publish the rate for the API server's reports. A
+/// rate older than FX_MAX_AGE_SECS is alerted once until it recovers.
+fn spawn_fx_refresh(state: Arc<RwLock<BotState>>, config: Config) {
+    tokio::spawn(async move {
+        let max_age = config.fx_max_age_secs as i64;
+        let mut stale_alerted = false;
+        loop {
+            tokio::time::sleep(Duration::from_secs(config.fx_refresh_secs.max(1))).await;
+            let fetched = fetch_fx_rate(&config).await;
+            let (rate, alert_manager) = {
+                let mut s = state.write().await;
+                match fetched {
+                    Ok(rate) => {
+                        if let Err(e) = rate.save(fx::FX_RATE_PATH) {
+                            warn!("Failed to save FX rate to {}: {}", fx::FX_RATE_PATH, e);
+                        }
+                        s.fx.set(rate);
+                    }
+                    Err(e) => warn!("FX rate refresh failed: {:#}", e),
+                }
+                s.apply_fx().await;
+                (s.fx.get(), s.alert_manager.clone())
+            };
+
+            let age = rate.age_secs(Utc::now());
+            if rate.is_usd() || age <= max_age {
+                stale_alerted = false;
+            } else if !stale_alerted {
+                stale_alerted = true;
+                alert_manager
+                    .send_critical(&format!(
+                        "💱 {} exchange rate is {}s old (max {}s) - limits use the last rate",
+                        config.accounting_currency, age, max_age
+                    ))
+                    .await;
+            }
+        }
+    });
+}
+
+/// Re-send critical alert incidents nobody has acknowledged once their
+/// escalation period is up
+fn spawn_incident_escalation(alert_manager: AlertManager, incidents: Arc<IncidentStore>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            for incident in incidents.due_escalations(Utc::now()) {
+                alert_manager.send_escalation(&incident).await;
+            }
+        }
+    });
+}
+
+/// Every `interval`, bring perp hedges to the targets the hedge manager plans
+/// for the current spot inventory. Dry runs fill on paper against a margin
+/// account collateralized with the hedge budget.
+fn spawn_hedger(state: Arc<RwLock<BotState>>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let (drift, enabled, dry_run, exposures, hedging) = {
+                let s = state.read().await;
+                let enabled = s.feature_flags.is_enabled(feature_flags::PERP_HEDGING);
+                if s.replication.is_standby() || (!enabled && !s.hedge_manager.has_hedges()) {
+                    continue;
+                }
+                (
+                    s.drift.clone(),
+                    enabled,
+                    s.dry_run,
+                    s.spot_exposures(),
+                    s.config_manager.get().await.hedging,
+                )
+            };
+            let prices: HashMap<String, Decimal> = exposures
+                .iter()
+                .map(|e| (e.market.clone(), e.price))
+                .collect();
+
+            let account = if dry_run {
+                let notional = state.read().await.hedge_manager.status(&prices).notional;
+                let leverage = Decimal::from_f64(hedging.leverage)
+                    .unwrap_or(Decimal::ONE)
+                    .max(Decimal::ONE);
+                MarginAccount {
+                    collateral: Decimal::from_f64(hedging.max_notional_usd).unwrap_or_default(),
+                    margin_used: notional / leverage,
+                }
+            } else {
+                match drift.account().await {
+                    Ok(account) => account,
+                    Err(e) => {
+                        warn!("Drift account check failed, hedges left as they are: {:#}", e);
+                        continue;
+                    }
+                }
+            };
+
+            let (orders, was_halted) = {
+                let mut s = state.write().await;
+                let was_halted = s.hedge_manager.status(&prices).halted.is_some();
+                (s.hedge_manager.plan(&exposures, &account, enabled), was_halted)
+            };
+            for order in &orders {
+                let Some(price) = prices.get(&order.market).copied() else {
+                    warn!("No spot price for {}, cannot adjust its hedge", order.market);
+                    continue;
+                };
+                let side = if order.base_amount.is_sign_negative() { "Sell" } else { "Buy" };
+                let result = if dry_run {
+                    info!(
+                        "🔵 [DRY RUN] Would {} {} {}-PERP ({})",
+                        side,
+                        order.base_amount.abs(),
+                        order.market,
+                        order.reason
+                    );
+                    Ok("dry_run".to_string())
+                } else {
+                    drift.place(order).await
+                };
+                match result {
+                    Ok(signature) => {
+                        info!(
+                            "🛡️ Hedge: {} {} {}-PERP at ~${} ({}): {}",
+                            side,
+                            order.base_amount.abs(),
+                            order.market,
+                            price,
+                            order.reason,
+                            signature
+                        );
+                        state.write().await.hedge_manager.record_fill(order, price);
+                    }
+                    Err(e) => {
+                        warn!("Hedge order on {}-PERP failed: {:#}", order.market, e);
+                        break;
+                    }
+                }
+            }
+
+            let (status, alert_manager) = {
+                let s = state.read().await;
+                let status = s.hedge_manager.status(&prices);
+                s.system_health.write().await.hedging = Some(status.clone());
+                (status, s.alert_manager.clone())
+            };
+            if let Some(reason) = status.halted.as_ref().filter(|_| !was_halted) {
+                alert_manager
+                    .send_critical(&format!(
+                        "🛡️ Perp hedging stopped until tomorrow: {}. Open hedges are being closed.",
+                        reason
+                    ))
+                    .await;
+            }
+        }
+    });
+}
+
+/// Primary: publish a replication snapshot every `interval`. Standby: apply
+/// the primary's snapshot, and take over once the primary goes silent.
+fn spawn_replication(state: Arc<RwLock<BotState>>, interval: Duration, admin_token: Option<String>) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(interval.max(Duration::from_secs(1)))
+            .build()
+            .unwrap_or_default();
+        loop {
+            tokio::time::sleep(interval).await;
+            let (replication, blacklist) = {
+                let s = state.read().await;
+                (s.replication.clone(), s.blacklist.clone())
+            };
+
+            if !replication.is_standby() {
+                let risk = state.read().await.risk_manager.snapshot().await;
+                let entries = blacklist.read().await.entries().to_vec();
+                replication.publish(ReplicaSnapshot {
+                    taken_at: Utc::now(),
+                    risk,
+                    blacklist: entries,
+                });
+                continue;
+            }
+
+            let Some(primary_url) = replication.primary_url() else {
+                continue;
+            };
+            match replication::fetch_snapshot(&client, &primary_url, admin_token.as_deref()).await {
+                Ok(snapshot) => {
+                    if replication.accept(&snapshot) {
+                        let mut s = state.write().await;
+                        s.risk_manager.restore(snapshot.risk).await;
+                        s.publish_venue_exposure().await;
+                        let mut blacklist = blacklist.write().await;
+                        if blacklist.replace_entries(snapshot.blacklist) {
+                            if let Err(e) = blacklist.save() {
+                                warn!("Failed to save replicated blacklist: {}", e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    if replication.record_error(&format!("{:#}", e)) {
+                        warn!("Replication from primary failed: {:#}", e);
+                    }
+                }
+            }
+
+            if replication.failover_due() && replication.promote("primary unreachable") {
+                error!("⬆️ Primary at {} unreachable - standby promoted to primary", primary_url);
+                let alert_manager = state.read().await.alert_manager.clone();
+                alert_manager
+                    .send_critical(&format!(
+                        "⬆️ Primary at {} stopped replicating - this standby took over trading",
+                        primary_url
+                    ))
+                    .await;
+            }
+        }
+    });
+}
+
+/// Check the wallet's SOL and USDC every `interval` and act on the fee
+/// reserve: top up, alert, or lift the trading block
+fn spawn_fee_reserve_monitor(state: Arc<RwLock<BotState>>, interval: Duration) {
+    use solana_sdk::pubkey::Pubkey;
+
+    tokio::spawn(async move {
+        loop {
+            let (pubkey, rpc_batcher, standby) = {
+                let s = state.read().await;
+                (
+                    s.wallet.as_ref().map(|w| w.pubkey()),
+                    s.rpc_batcher.clone(),
+                    s.replication.is_standby(),
+                )
+            };
+            // The wallet belongs to the primary until this instance is promoted
+            if standby {
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            let Some(owner) = pubkey.and_then(|p| Pubkey::from_str(&p).ok()) else {
+                return;
+            };
+
+            // Both balances go out in one batched request
+            let usdc_account = Pubkey::from_str(USDC_MINT)
+                .map(|mint| spl_associated_token_account::get_associated_token_address(&owner, &mint))
+                .ok();
+            let (lamports, usdc_balance) = tokio::join!(rpc_batcher.get_balance(&owner), async {
+                match &usdc_account {
+                    Some(account) => rpc_batcher.get_token_balance(account).await.ok(),
+                    None => None,
+                }
+            });
+            let balance_sol = match lamports {
+                Ok(lamports) => Decimal::from(lamports) / Decimal::from(1_000_000_000u64),
+                Err(e) => {
+                    warn!("Fee reserve check failed: {}", e);
+                    tokio::time::sleep(interval).await;
+                    continue;
+                }
+            };
+
+            let (action, alert_manager) = {
+                let mut s = state.write().await;
+                let sol_price = s.pair_mid_price("SOL/USDC");
+                let action = s.fee_reserve.observe(balance_sol, sol_price, usdc_balance);
+                s.metrics
+                    .fee_reserve_sol
+                    .set(balance_sol.to_f64().unwrap_or(0.0));
+                s.system_health.write().await.fee_reserve = Some(s.fee_reserve.status());
+                (action, s.alert_manager.clone())
+            };
+
+            match action {
+                ReserveAction::None => {
+                    let s = state.read().await;
+                    if !s.fee_reserve.is_blocked() {
+                        if let Err(e) = s.maintain_wsol().await {
+                            warn!("Failed to maintain the standing wSOL balance: {}", e);
+                        }
+                    }
+                }
+                ReserveAction::Alert { reason } => {
+                    warn!("⛽ {} - trading blocked", reason);
+                    alert_manager
+                        .send_critical(&format!("⛽ {} - trading blocked until SOL is added", reason))
+                        .await;
+                }
+                ReserveAction::Restored => {
+                    info!("⛽ SOL fee reserve restored ({} SOL) - trading resumed", balance_sol);
+                    alert_manager
+                        .send_info(&format!("⛽ SOL fee reserve restored ({} SOL)", balance_sol))
+                        .await;
+                }
+                ReserveAction::TopUp { usdc } => {
+                    warn!("⛽ SOL balance {} below reserve - swapping ${} USDC to SOL", balance_sol, usdc);
+                    let result = state.read().await.execute_fee_topup(usdc).await;
+                    {
+                        let mut s = state.write().await;
+                        match &result {
+                            Ok(signature) => {
+                                info!("⛽ Fee reserve top-up sent ({})", signature);
+                                s.fee_reserve.record_topup(Ok(()));
+                            }
+                            Err(e) => {
+                                error!("Fee reserve top-up failed: {}", e);
+                                s.fee_reserve.record_topup(Err(e.to_string()));
+                            }
+                        }
+                        s.system_health.write().await.fee_reserve = Some(s.fee_reserve.status());
+                    }
+                    if let Err(e) = result {
+                        alert_manager
+                            .send_critical(&format!(
+                                "⛽ Fee reserve top-up of {} failed: {} - trading blocked",
+                                alert_manager.money(usdc.to_f64().unwrap_or_default()), e
+                            ))
+                            .await;
+                    }
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Scan the wallet's signatures and SOL balance every `interval`,
+/// quarantining it on a transaction the bot did not sign or an outflow of
+/// more than `max_outflow` lamports the bot did not cause. Keys are dropped
+/// while quarantined and reloaded once the quarantine is lifted.
+fn spawn_quarantine_monitor(
+    state: Arc<RwLock<BotState>>,
+    quarantine: Arc<Quarantine>,
+    rpc_url: String,
+    interval: Duration,
+    max_outflow: u64,
+) {
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signature;
+
+    tokio::spawn(async move {
+        let client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new(rpc_url);
+        // History up to the first scan predates this run and is not checked
+        let mut newest: Option<Signature> = None;
+        let mut last_lamports: Option<u64> = None;
+        let mut signed_before = 0;
+        let mut revoked = false;
+        loop {
+            let (pubkey, rpc_batcher, alert_manager, standby) = {
+                let s = state.read().await;
+                (
+                    s.wallet.as_ref().map(|w| w.pubkey()),
+                    s.rpc_batcher.clone(),
+                    s.alert_manager.clone(),
+                    s.replication.is_standby(),
+                )
+            };
+
+            // Keys follow the quarantine, also one persisted by an earlier run
+            if quarantine.is_active() && !revoked {
+                revoked = true;
+                if state.write().await.revoke_keys() {
+                    warn!("🔒 Wallet keys dropped from memory");
+                }
+            } else if !quarantine.is_active() && revoked {
+                revoked = false;
+                newest = None;
+                last_lamports = None;
+                match state.write().await.restore_keys() {
+                    Ok(wallet) => {
+                        info!("🔓 Wallet {} unlocked, keys reloaded", wallet);
+                        alert_manager
+                            .send_info(&format!("🔓 Wallet {} unlocked - signing resumed", wallet))
+                            .await;
+                    }
+                    Err(e) => {
+                        error!("Wallet unlocked but its keys could not be reloaded: {}", e);
+                        alert_manager
+                            .send_critical(&format!("🔓 Wallet unlocked but keys not reloaded ({}) - restart the bot", e))
+                            .await;
+                    }
+                }
+            }
+
+            // The wallet belongs to the primary until this instance is promoted
+            if standby || quarantine.is_active() {
+                newest = None;
+                last_lamports = None;
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            // Keys that failed to reload leave nothing to scan until they are back
+            let Some(owner) = pubkey.and_then(|p| Pubkey::from_str(&p).ok()) else {
+                tokio::time::sleep(interval).await;
+                continue;
+            };
+
+            let mut reason = None;
+            match client
+                .get_signatures_for_address_with_config(
+                    &owner,
+                    solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config {
+                        until: newest,
+                        limit: Some(100),
+                        ..Default::default()
+                    },
                 )
                 .await
+            {
+                Ok(entries) => {
+                    let first_scan = newest.is_none();
+                    if let Some(latest) = entries.first().and_then(|e| Signature::from_str(&e.signature).ok()) {
+                        newest = Some(latest);
+                    }
+                    for entry in entries.iter().filter(|_| !first_scan) {
+                        if quarantine.signed_by_bot(&entry.signature) {
+                            continue;
+                        }
+                        let Ok(signature) = Signature::from_str(&entry.signature) else {
+                            continue;
+                        };
+                        match signed_by(&client, &signature, &owner).await {
+                            Ok(true) => {
+                                reason = Some(format!("unknown transaction {} signed by the wallet", signature));
+                                break;
+                            }
+                            Ok(false) => {}
+                            Err(e) => warn!("Quarantine check of {} failed: {}", signature, e),
+                        }
+                    }
+                }
+                Err(e) => warn!("Quarantine signature scan failed: {}", e),
+            }
+
+            // A transaction signed just before the previous scan may land after it
+            let signed = quarantine.take_signed_since_check();
+            if reason.is_none() {
+                match rpc_batcher.get_balance(&owner).await {
+                    Ok(lamports) => {
+                        if let Some(outflow) = last_lamports
+                            .and_then(|before| quarantine::unexplained_outflow(before, lamports, signed + signed_before, max_outflow))
+                        {
+                            reason = Some(format!(
+                                "{} SOL left the wallet while the bot signed nothing",
+                                Decimal::from(outflow) / Decimal::from(1_000_000_000u64)
+                            ));
+                        }
+                        last_lamports = Some(lamports);
+                    }
+                    Err(e) => warn!("Quarantine balance check failed: {}", e),
+                }
+            }
+            signed_before = signed;
+
+            if let Some(reason) = reason {
+                if quarantine.enter(&owner.to_string(), &reason) {
+                    error!("🔒 Wallet {} quarantined: {}", owner, reason);
+                    revoked = true;
+                    state.write().await.revoke_keys();
+                    alert_manager
+                        .send_critical(&format!(
+                            "🔒 Wallet {} QUARANTINED: {}. Signing stopped and keys dropped. Unlock with POST /quarantine/unlock after investigating.",
+                            owner, reason
+                        ))
+                        .await;
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Whether `owner` is among the signers of the transaction `signature`
+async fn signed_by(
+    client: &solana_rpc_client::nonblocking::rpc_client::RpcClient,
+    signature: &solana_sdk::signature::Signature,
+    owner: &solana_sdk::pubkey::Pubkey,
+) -> anyhow::Result<bool> {
+    let tx = client
+        .get_transaction_with_config(
+            signature,
+            solana_rpc_client_api::config::RpcTransactionConfig {
+                encoding: Some(solana_transaction_status::UiTransactionEncoding::Base64),
+                commitment: None,
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let tx = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow::anyhow!("undecodable transaction"))?;
+    let signers = usize::from(tx.message.header().num_required_signatures);
+    Ok(tx.message.static_account_keys().iter().take(signers).any(|key| key == owner))
+}
+
+/// Kamino's USDC reserve, lending to the bot's wallet (a placeholder
+/// without one, which never borrows)
+fn kamino_flash_loan(config: &Config, wallet: Option<&Wallet>) -> anyhow::Result<KaminoFlashLoan> {
+    let lending_market = Pubkey::from_str(&config.kamino_lending_market)?;
+    let reserve = Pubkey::from_str(&config.kamino_usdc_reserve)?;
+    let usdc = resolve_mint("USDC").ok_or_else(|| anyhow::anyhow!("USDC mint unknown"))?;
+    let owner = match wallet {
+        Some(wallet) => Pubkey::from_str(&wallet.pubkey())?,
+        None => Pubkey::default(),
+    };
+    Ok(KaminoFlashLoan::new(lending_market, reserve, usdc, owner, config.kamino_flash_loan_fee_bps))
+}
+
+/// marginfi's configured banks, lending to the bot's wallet through its
+/// marginfi account (a placeholder without a wallet, which never borrows)
+fn marginfi_flash_loan(config: &Config, wallet: Option<&Wallet>) -> anyhow::Result<MarginfiFlashLoan> {
+    let group = Pubkey::from_str(&config.marginfi_group)?;
+    let account = Pubkey::from_str(&config.marginfi_account)
+        .map_err(|_| anyhow::anyhow!("MARGINFI_ACCOUNT is not a valid account"))?;
+    let owner = match wallet {
+        Some(wallet) => Pubkey::from_str(&wallet.pubkey())?,
+        None => Pubkey::default(),
+    };
+    let mut marginfi = MarginfiFlashLoan::new(group, account, owner, config.marginfi_flash_loan_fee_bps);
+    for entry in config.marginfi_banks.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (token, bank) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("MARGINFI_BANKS entry {} is not TOKEN=bank", entry))?;
+        let mint = resolve_mint(token.trim()).ok_or_else(|| anyhow::anyhow!("{} mint unknown", token))?;
+        marginfi = marginfi.with_bank(mint, Pubkey::from_str(bank.trim())?);
+    }
+    Ok(marginfi)
+}
+
+/// Refresh the liquidity of the flash loan reserves lending `tokens` every
+/// `interval`. Reserves lending a different mint than requested are skipped.
+fn spawn_flash_liquidity_monitor(state: Arc<RwLock<BotState>>, tokens: Vec<String>, interval: Duration) {
+
+    tokio::spawn(async move {
+        let mut tokens = tokens;
+        tokens.sort();
+        tokens.dedup();
+        loop {
+            let (reserves, rpc_batcher) = {
+                let s = state.read().await;
+                let reserves: Vec<(String, Pubkey, &'static str, Pubkey)> = tokens
+                    .iter()
+                    .filter_map(|token| Some((token, resolve_mint(token)?)))
+                    .flat_map(|(token, mint)| {
+                        s.flash_loan_router
+                            .reserves(&mint)
+                            .into_iter()
+                            .map(move |(provider, reserve)| (token.clone(), mint, provider, reserve))
+                    })
+                    .collect();
+                (reserves, s.rpc_batcher.clone())
+            };
+            if reserves.is_empty() {
+                return;
+            }
+
+            let keys: Vec<Pubkey> = reserves.iter().map(|(_, _, _, reserve)| *reserve).collect();
+            let accounts = match rpc_batcher.get_multiple_accounts(&keys).await {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    debug!("Flash loan reserves unavailable: {}", e);
+                    tokio::time::sleep(interval).await;
+                    continue;
+                }
+            };
+            for ((token, mint, provider, reserve), account) in reserves.into_iter().zip(accounts) {
+                let Some(data) = account.map(|account| account.data) else {
+                    debug!("{} flash loan reserve {} for {} not found", provider, reserve, token);
+                    continue;
+                };
+                let mut s = state.write().await;
+                match s.flash_loan_router.record_reserve(provider, &data) {
+                    Ok(liquidity) if liquidity.mint == mint => {
+                        // Sizing follows the deepest reserve lending the token
+                        let Some(deepest) = s.flash_loan_router.deepest(&mint) else {
+                            continue;
+                        };
+                        s.metrics
+                            .flash_reserve_utilization
+                            .with_label_values(&[&token])
+                            .set(deepest.utilization().to_f64().unwrap_or(0.0));
+                        s.flash_liquidity.insert(mint, deepest);
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Cannot read {} flash loan reserve {}: {}", provider, reserve, e),
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Execute the housekeeping trades of an active unwind, one per `interval`
+fn spawn_unwind_worker(state: Arc<RwLock<BotState>>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let step = {
+                let mut s = state.write().await;
+                if s.replication.is_standby() || !s.risk_manager.unwind.is_active() {
+                    continue;
+                }
+                let step = s.risk_manager.next_unwind_step();
+                s.publish_unwind_status().await;
+                step
+            };
+            let Some(step) = step else {
+                continue;
+            };
+
+            let result = state.read().await.execute_unwind_step(&step).await;
+
+            let mut s = state.write().await;
+            match result {
+                Ok(signature) => {
+                    info!(
+                        "📉 Housekeeping trade reduced {} by ${} ({})",
+                        step.pair, step.size, signature
+                    );
+                    s.risk_manager.complete_unwind_step(&step.pair, step.size);
+                }
+                Err(e) => {
+                    warn!("Housekeeping trade on {} failed: {}", step.pair, e);
+                    s.risk_manager.unwind.fail_step();
+                }
+            }
+            s.publish_unwind_status().await;
+        }
+    });
+}
+
+/// Once the failed trade of `opp` has had time to land, sell any base
+/// token it left in the wallet and record and alert the repair
+fn spawn_route_repair(
+    state: Arc<RwLock<BotState>>,
+    opp: solana_arb_core::ArbitrageOpportunity,
+    base_before: Decimal,
+) {
+    tokio::spawn(async move {
+        let Some(policy) = state.read().await.route_repair else {
+            return;
+        };
+        tokio::time::sleep(policy.settle).await;
+
+        let s = state.read().await;
+        let stranded = match s.stranded_after(&opp, base_before, policy.min_usd).await {
+            Ok(Some(stranded)) => stranded,
+            Ok(None) => {
+                debug!("Failed {} trade left nothing to repair", opp.pair);
+                return;
+            }
+            Err(e) => {
+                warn!("Cannot check the wallet after a failed {} trade: {}", opp.pair, e);
+                s.alert_manager
+                    .send_critical(&format!(
+                        "🔧 Cannot check the wallet for {} left by a failed {} trade: {}",
+                        opp.pair.base, opp.pair, e
+                    ))
+                    .await;
+                return;
+            }
         };
 
-        match result {
-            Ok(trade_result) => {
-                if trade_result.success {
-                    let tx_signature = trade_result.signature.as_deref().unwrap_or("unknown");
-                    info!("✅ Trade submitted! Signature: {}", tx_signature);
-                } else {
-                    let error_msg = trade_result.error.as_deref().unwrap_or("Unknown error");
-                    warn!("❌ Trade execution returned failure: {}", error_msg);
-                }
+        let money = |usd: Decimal| s.alert_manager.money(usd.to_f64().unwrap_or_default());
+        warn!(
+            "🔧 Failed {} trade left {} {} (${}) in the wallet; repairing",
+            opp.pair,
+            stranded.base_amount,
+            opp.pair.base,
+            stranded.value_usd.round_dp(2)
+        );
+        match s.execute_repair(&opp.pair, &stranded).await {
+            Ok(signature) => {
+                info!("🔧 Repair sold {} {} ({})", stranded.base_amount, opp.pair.base, signature);
+                s.metrics.route_repairs.with_label_values(&["repaired"]).inc();
+                s.history_recorder
+                    .record_repair(&opp, stranded.value_usd, stranded.pnl_usd, Some(signature), None);
+                s.alert_manager
+                    .send_info(&format!(
+                        "🔧 Repair: a failed {} trade left {} of {} in the wallet, sold back ({} against the buy price)",
+                        opp.pair,
+                        money(stranded.value_usd),
+                        opp.pair.base,
+                        money(stranded.pnl_usd)
+                    ))
+                    .await;
+            }
+            Err(e) => {
+                error!("🔧 Repair of {} failed: {}", opp.pair, e);
+                s.metrics.route_repairs.with_label_values(&["failed"]).inc();
+                s.history_recorder.record_repair(
+                    &opp,
+                    stranded.value_usd,
+                    Decimal::ZERO,
+                    None,
+                    Some(e.to_string()),
+                );
+                s.alert_manager
+                    .send_critical(&format!(
+                        "🔧 A failed {} trade left {} of {} in the wallet and the repair failed: {}",
+                        opp.pair,
+                        money(stranded.value_usd),
+                        opp.pair.base,
+                        e
+                    ))
+                    .await;
+            }
+        }
+    });
+}
 
-                // Record outcome
-                let outcome = {
-                    let state_read = state.read().await;
-                    state_read
-                        .record_trade_outcome(opp, &pair_symbol, size, &trade_result, start_time)
-                        .await
-                };
+/// Long-poll Telegram for operator commands and answer each in its chat
+/// Run manual trades one at a time as operators send them
+fn spawn_manual_trades(state: Arc<RwLock<BotState>>, mut orders: mpsc::Receiver<ManualJob>) {
+    tokio::spawn(async move {
+        let metrics = state.read().await.metrics.clone();
+        while let Some((order, reply)) = orders.recv().await {
+            let report = execute_manual_trade(&state, &metrics, order).await;
+            info!(
+                "🖐️ Manual trade {:?}{}",
+                report.status,
+                report.reason.as_deref().map(|r| format!(": {}", r)).unwrap_or_default()
+            );
+            let _ = reply.send(report);
+        }
+    });
+}
 
-                // Update Risk Manager
-                // Update Risk Manager
-                let mut state = state.write().await;
-                state.risk_manager.record_trade(outcome).await;
+fn spawn_telegram_commands(state: Arc<RwLock<BotState>>, mut commands: TelegramCommands) {
+    tokio::spawn(async move {
+        loop {
+            let received = match commands.poll().await {
+                Ok(received) => received,
+                Err(e) => {
+                    warn!("Telegram command poll failed: {}", e);
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    continue;
+                }
+            };
+            for ChatCommand { chat_id, command } in received {
+                let reply = state.read().await.command_reply(chat_id, command).await;
+                if let Err(e) = commands.reply(chat_id, &reply).await {
+                    warn!("Telegram reply to chat {} failed: {}", chat_id, e);
+                }
             }
-            Err(e) => {
-                error!("❌ Trade failed (Executor Error): {}", e);
+        }
+    });
+}
 
-                // Construct failed TradeResult
-                let failed_result = TradeResult {
-                    opportunity_id: opp.id,
-                    signature: None,
-                    success: false,
-                    actual_profit: Decimal::ZERO,
-                    executed_at: Utc::now(),
-                    error: Some(e.to_string()),
-                };
+/// Once a day at `directions.flatten_at_utc`, sell the base token of every
+/// pair marked `flatten_daily`
+fn spawn_flatten_worker(state: Arc<RwLock<BotState>>) {
+    tokio::spawn(async move {
+        let mut last_flattened = None;
+        loop {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            let (directions, alert_manager) = {
+                let s = state.read().await;
+                if s.replication.is_standby() {
+                    continue;
+                }
+                (s.config_manager.get().await.directions, s.alert_manager.clone())
+            };
+            let now = Utc::now();
+            if !directions.flatten_due(last_flattened, now) {
+                continue;
+            }
+            last_flattened = Some(now.date_naive());
 
-                // Record outcome
-                let outcome = {
-                    let state_read = state.read().await;
-                    state_read
-                        .record_trade_outcome(opp, &pair_symbol, size, &failed_result, start_time)
-                        .await
+            for symbol in directions.flatten_pairs() {
+                let Some((base, quote)) = symbol.split_once('/') else {
+                    continue;
                 };
-
-                // Update Risk Manager
-                let mut state = state.write().await;
-                state.risk_manager.record_trade(outcome).await;
+                let result = state.read().await.execute_flatten(&TokenPair::new(base, quote)).await;
+                match result {
+                    Ok(Some((value, signature))) => {
+                        info!("🌙 Flattened {} (${}): {}", symbol, value.round_dp(2), signature);
+                        alert_manager
+                            .send_info(&format!(
+                                "🌙 End-of-day flatten sold {} of {}",
+                                alert_manager.money(value.to_f64().unwrap_or_default()),
+                                base
+                            ))
+                            .await;
+                    }
+                    Ok(None) => debug!("Nothing to flatten for {}", symbol),
+                    Err(e) => {
+                        warn!("End-of-day flatten of {} failed: {}", symbol, e);
+                        alert_manager
+                            .send_critical(&format!("🌙 End-of-day flatten of {} failed: {}", symbol, e))
+                            .await;
+                    }
+                }
             }
         }
-    }
+    });
 }
 
-pub async fn run_bot() {
-    // Load config first
-    dotenvy::dotenv().ok();
+/// Poll the signature statuses of live trades until they are finalized,
+/// reverting the P&L of any a fork rolled back
+fn spawn_confirmation_tracker(state: Arc<RwLock<BotState>>, rpc_url: String) {
+    use solana_sdk::commitment_config::CommitmentConfig;
+    use solana_sdk::signature::Signature;
 
-    // Initialize logging
-    logging::setup();
+    tokio::spawn(async move {
+        let client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new(rpc_url);
+        loop {
+            tokio::time::sleep(confirmations::POLL_INTERVAL).await;
+            let signatures: Vec<Signature> = state
+                .read()
+                .await
+                .confirmations
+                .signatures()
+                .iter()
+                .filter_map(|s| Signature::from_str(s).ok())
+                .collect();
+            if signatures.is_empty() {
+                continue;
+            }
 
-    // Read MIN_PROFIT_THRESHOLD directly from environment at runtime
-    let min_profit_threshold: f64 = std::env::var("MIN_PROFIT_THRESHOLD")
-        .unwrap_or_else(|_| "0.5".to_string())
-        .parse()
-        .expect("Invalid MIN_PROFIT_THRESHOLD value");
+            // getSignatureStatuses accepts at most 256 signatures per call
+            let mut observations = Vec::with_capacity(signatures.len());
+            for chunk in signatures.chunks(256) {
+                match client.get_signature_statuses_with_history(chunk).await {
+                    Ok(response) => observations.extend(chunk.iter().zip(response.value)),
+                    Err(e) => warn!("Failed to poll signature statuses: {}", e),
+                }
+            }
 
-    // Create config with runtime-loaded value
-    let mut config = Config::from_env().unwrap_or_default();
-    config.min_profit_threshold = min_profit_threshold;
+            let mut s = state.write().await;
+            for (signature, status) in observations {
+                let observation = match status {
+                    None => Observation::Missing,
+                    Some(status) => match &status.err {
+                        Some(err) => Observation::Failed(err.to_string()),
+                        None if status.satisfies_commitment(CommitmentConfig::finalized()) => {
+                            Observation::Seen(confirmations::Commitment::Finalized)
+                        }
+                        None if status.satisfies_commitment(CommitmentConfig::confirmed()) => {
+                            Observation::Seen(confirmations::Commitment::Confirmed)
+                        }
+                        None => Observation::Seen(confirmations::Commitment::Processed),
+                    },
+                };
+                let failure = match &observation {
+                    Observation::Failed(err) => Some(err.clone()),
+                    _ => None,
+                };
+                for resolution in s.confirmations.observe(&signature.to_string(), observation) {
+                    s.record_onchain_result(&resolution, failure.as_deref()).await;
+                    s.resolve_confirmation(resolution);
+                }
+            }
+            let pending = s.confirmations.len();
+            s.system_health.write().await.pending_finalization = pending;
+        }
+    });
+}
 
-    info!("🚀 Solana Arbitrage Bot starting...");
-    info!("   Min profit threshold: {}%", min_profit_threshold);
-    info!(
-        "   Priority fee: {} µL/CU",
-        config.priority_fee_micro_lamports
-    );
-    info!("   Slippage tolerance: {} bps", config.slippage_bps);
-    info!("   RPC commitment: {}", config.rpc_commitment);
-    info!("   Max retries: {}", config.max_retries);
-    info!("   RPC URL: {}", config.solana_rpc_url);
+/// Pool discovery whitelist and liquidity floor from `DISCOVERY_*`
+fn discovery_config(config: &Config) -> DiscoveryConfig {
+    DiscoveryConfig {
+        tokens: DiscoveryConfig::parse_tokens(&config.discovery_tokens),
+        min_liquidity_usd: Decimal::from_f64(config.discovery_min_liquidity_usd).unwrap_or_default(),
+        ..DiscoveryConfig::default()
+    }
+}
 
-    // Check for dry-run mode
-    let dry_run = config.dry_run;
+/// Build deadline for trades, `None` when `EXECUTION_DEADLINE_MS` is 0
+fn execution_deadline(config: &Config) -> Option<std::time::Duration> {
+    (config.execution_deadline_ms > 0)
+        .then(|| std::time::Duration::from_millis(config.execution_deadline_ms))
+}
 
-    // Initialize Alert Manager
-    let alert_manager = AlertManager::new(
-        config.telegram_webhook_url.clone(),
-        config.discord_webhook_url.clone(),
-    );
+/// Whole SOL to lamports, rounding down
+fn lamports(sol: Decimal) -> u64 {
+    (sol.max(Decimal::ZERO) * Decimal::from(1_000_000_000u64))
+        .to_u64()
+        .unwrap_or(0)
+}
 
-    // Alert on startup
-    alert_manager.send_info(if dry_run {
-        "🚀 ArbEngine-Pro started (Mode: DRY-RUN)"
+/// Tuning bounds for the named rate limiter
+fn rate_limit_tuner(config: &Config, name: &str) -> BudgetTuner {
+    let (floor, ceiling) = if name == JUPITER_LIMITER {
+        (config.jupiter_rate_limit_floor, config.jupiter_rate_limit_ceiling)
     } else {
-        "🚀 ArbEngine-Pro started (Mode: LIVE TRADING)"
-    }).await;
+        (config.rpc_rate_limit_floor, config.rpc_rate_limit_ceiling)
+    };
+    BudgetTuner::new(
+        floor,
+        ceiling,
+        Duration::from_millis(config.rate_limit_target_latency_ms),
+    )
+}
 
-    if dry_run {
-        info!("⚠️  Running in DRY RUN mode - no real trades will be executed");
-    } else {
-        warn!("⚠️  LIVE TRADING MODE - Real trades will be executed!");
-    }
+/// Periodically adjust rate limiter budgets from the telemetry observed since
+/// the last step, saving them whenever one changes
+fn spawn_rate_limit_tuner(
+    limiters: Vec<(&'static str, Arc<RateLimiter>, BudgetTuner)>,
+    metrics: Arc<MetricsCollector>,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let path = std::path::Path::new(RATE_LIMIT_STATE_PATH);
+        let mut learned = LearnedBudgets::load(path).unwrap_or_default();
+        loop {
+            tokio::time::sleep(interval).await;
+            let mut changed = false;
+            for (name, limiter, tuner) in &limiters {
+                let telemetry = limiter.take_telemetry();
+                let current = limiter.max_requests();
+                let next = tuner.next_budget(current, &telemetry);
+                if next == current {
+                    continue;
+                }
 
-    // Initialize RPC client for pre-flight checks
-    let rpc_client = solana_rpc_client::nonblocking::rpc_client::RpcClient::new(config.solana_rpc_url.clone());
+                if next < current {
+                    warn!(
+                        "🐢 Lowering {} rate limit {} → {} req/s ({} throttled of {}, avg latency {}ms)",
+                        name,
+                        current,
+                        next,
+                        telemetry.throttled,
+                        telemetry.responses,
+                        telemetry.avg_latency().unwrap_or_default().as_millis()
+                    );
+                } else {
+                    info!("🐇 Raising {} rate limit {} → {} req/s", name, current, next);
+                }
+                limiter.set_max_requests(next);
+                metrics
+                    .rate_limit_budget
+                    .with_label_values(&[name])
+                    .set(next as i64);
+                learned.set(name, next);
+                changed = true;
+            }
 
-    // Run Pre-flight Checks
-    info!("Running pre-flight safety checks...");
-    if let Ok(warnings) = run_preflight_checks(&rpc_client, &config).await {
-        if !warnings.is_empty() {
-             let mut msg = String::from("⚠️ Pre-flight warnings:\n");
-             for w in &warnings {
-                 warn!("{}", w);
-                 msg.push_str(&format!("- {}\n", w));
-             }
-             alert_manager.send_critical(&msg).await;
-             
-             // Wait for user or continue if safe
-             if !dry_run {
-                 info!("Waiting 10 seconds before continuing...");
-                 tokio::time::sleep(Duration::from_secs(10)).await;
-             }
-        } else {
-             info!("✅ All pre-flight checks passed");
+            if changed {
+                if let Err(e) = learned.save(path) {
+                    warn!("Failed to save learned rate limits: {}", e);
+                }
+            }
         }
-    } else {
-         error!("❌ Pre-flight checks failed completely");
-         // Maybe exit? For now just log
-    }
-
-    // Initialize System Health
-    let system_health = Arc::new(RwLock::new(SystemHealth::default()));
+    });
+}
 
-    // Start Health Check Server
-    let health_clone = system_health.clone();
+/// Periodically probe the configured Jupiter API, publishing latency and
+/// health to metrics and `/status` and alerting when it goes up or down.
+fn spawn_jupiter_probe(
+    state: Arc<RwLock<BotState>>,
+    base_url: String,
+    compare_public: bool,
+    interval: Duration,
+) {
     tokio::spawn(async move {
-        let app = Router::new()
-            .route("/health", get(|| async {
-                Json(json!({
-                    "status": "ok",
-                    "timestamp": Utc::now().to_rfc3339()
-                }))
-            }))
-            .route("/status", get(move || {
-                let health = health_clone.clone();
-                async move {
-                    let h = health.read().await;
-                    Json(json!({
-                        "is_running": h.is_running,
-                        "total_trades": h.total_trades,
-                        "circuit_breaker": h.circuit_breaker_state,
-                        "balance_usd": h.balance_usd,
-                        "uptime_seconds": h.start_time.elapsed().as_secs()
-                    }))
+        let client = reqwest::Client::new();
+        let mut status = JupiterStatus::new(&base_url, compare_public);
+        let (metrics, alert_manager, system_health) = {
+            let s = state.read().await;
+            (s.metrics.clone(), s.alert_manager.clone(), s.system_health.clone())
+        };
+
+        loop {
+            let result = jupiter_endpoint::probe(&client, &status.base_url).await;
+            if let Ok(latency) = &result {
+                metrics
+                    .jupiter_probe_latency
+                    .with_label_values(&["configured"])
+                    .observe(latency.as_secs_f64());
+            }
+            metrics
+                .jupiter_endpoint_up
+                .with_label_values(&["configured"])
+                .set(result.is_ok() as i64);
+            if status.endpoint.record(&result) {
+                let message = match &result {
+                    Ok(_) => format!("🪐 Jupiter API at {} recovered", status.base_url),
+                    Err(e) => format!("🪐 Jupiter API at {} is down: {}", status.base_url, e),
+                };
+                if result.is_ok() {
+                    alert_manager.send_info(&message).await;
+                } else {
+                    alert_manager.send_critical(&message).await;
                 }
-            }));
-        
-        // Use a different port or 8080 as configured
-        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 8080));
-        info!("🏥 Health check server running on http://{}", addr);
-        match tokio::net::TcpListener::bind(addr).await {
-            Ok(listener) => {
-                if let Err(e) = axum::serve(listener, app).await {
-                    error!("Health check server error: {}", e);
+            } else if let Err(e) = &result {
+                debug!("Jupiter probe failed: {}", e);
+            }
+
+            if let Some(public) = status.public.as_mut() {
+                let result = jupiter_endpoint::probe(&client, PUBLIC_JUPITER_API_URL).await;
+                if let Ok(latency) = &result {
+                    metrics
+                        .jupiter_probe_latency
+                        .with_label_values(&["public"])
+                        .observe(latency.as_secs_f64());
                 }
+                metrics
+                    .jupiter_endpoint_up
+                    .with_label_values(&["public"])
+                    .set(result.is_ok() as i64);
+                public.record(&result);
+            }
+            if let Some(saving) = status.latency_saving_ms() {
+                metrics.jupiter_latency_saving_ms.set(saving);
             }
-            Err(e) => error!("Failed to bind health check server on {}: {}", addr, e),
+
+            system_health.write().await.jupiter = status.clone();
+            tokio::time::sleep(interval).await;
         }
     });
+}
 
-    // Define trading pairs
-    let pairs = vec![
-        TokenPair::new("SOL", "USDC"),
-        TokenPair::new("RAY", "USDC"),
-        TokenPair::new("ORCA", "USDC"),
-        TokenPair::new("JUP", "USDC"),
-    ];
-
-    // Initialize metrics
-    let metrics = Arc::new(MetricsCollector::new().expect("Failed to initialize metrics"));
-
-    // Start metrics server
-    let metrics_clone = metrics.clone();
-    // Default metrics port from config if possible, or 9090
-    let metrics_port = config.metrics_port;
+/// Snapshot metrics to the local timeseries file every `interval`, pruning
+/// samples past the retention period about once an hour
+fn spawn_metrics_recorder(
+    metrics: Arc<MetricsCollector>,
+    recorder: Arc<MetricsRecorder>,
+    interval: Duration,
+) {
+    info!(
+        "🗃️ Recording metrics to {} every {}s",
+        recorder.path().display(),
+        interval.as_secs()
+    );
     tokio::spawn(async move {
-        let app = api::metrics::metrics_routes(metrics_clone);
-        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], metrics_port));
-        info!("📊 Metrics server running on http://{}/metrics", addr);
-        match tokio::net::TcpListener::bind(addr).await {
-            Ok(listener) => {
-                if let Err(e) = axum::serve(listener, app).await {
-                    error!("Metrics server error: {}", e);
+        let prune_every = (3600 / interval.as_secs().max(1)).max(1);
+        let mut ticks: u64 = 0;
+        loop {
+            if ticks.is_multiple_of(prune_every) {
+                match recorder.prune(Utc::now()) {
+                    Ok(0) => {}
+                    Ok(removed) => debug!("Pruned {} old metrics samples", removed),
+                    Err(e) => warn!("Failed to prune metrics timeseries: {}", e),
                 }
             }
-            Err(e) => error!("Failed to bind metrics server on {}: {}", addr, e),
+            if let Err(e) = recorder.record(metrics.registry()) {
+                warn!("Failed to record metrics snapshot: {}", e);
+            }
+            ticks += 1;
+            tokio::time::sleep(interval).await;
         }
     });
+}
 
-    // Initialize Config Manager
-    let config_path = "config/trading_config.json";
-    let config_manager = Arc::new(ConfigManager::new(config_path)
-        .unwrap_or_else(|e| {
-            warn!("Failed to load dynamic config: {}. Using defaults.", e);
-            // Panic or fallback? Since constructor failed (file read/parse), let's try to survive if possible or panic.
-            // But ConfigManager::new implies loading.
-            panic!("Critical: Failed to load {}: {}", config_path, e);
-        }));
-
-    // Start Config Watcher (Polling)
-    let cm_clone = config_manager.clone();
+/// Deduplicate and bar past days of the price archive every
+/// `PRICE_COMPACTION_INTERVAL_SECS`, then apply retention
+fn spawn_price_compaction(snapshots: PriceSnapshots, config: &Config) {
+    let policy = RetentionPolicy::from_config(config);
+    let interval = Duration::from_secs(config.price_compaction_interval_secs.max(60));
+    // Half the price age limit, so replays see no archived price go stale
+    let heartbeat = chrono::Duration::milliseconds(config.max_price_age_seconds * 500);
+    info!(
+        "🗜️ Compacting price archive in {} every {}s",
+        snapshots.dir().display(),
+        interval.as_secs()
+    );
     tokio::spawn(async move {
         loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-            if let Err(e) = cm_clone.reload().await {
-                error!("Failed to reload configuration: {}", e);
+            let snapshots = snapshots.clone();
+            let compacted = tokio::task::spawn_blocking(move || {
+                price_compaction::run(&snapshots, &policy, heartbeat, Utc::now().date_naive())
+            })
+            .await;
+            match compacted {
+                Ok(Ok(report)) => {
+                    for date in &report.days_compacted {
+                        info!("🗜️ Compacted price archive for {}", date);
+                    }
+                    if report.quotes_removed > 0 || !report.files_deleted.is_empty() {
+                        info!(
+                            "🗜️ Price archive: {} repeated quotes removed, {} expired files deleted",
+                            report.quotes_removed,
+                            report.files_deleted.len()
+                        );
+                    }
+                }
+                Ok(Err(e)) => warn!("Price archive compaction failed: {:#}", e),
+                Err(e) => warn!("Price archive compaction panicked: {}", e),
             }
+            tokio::time::sleep(interval).await;
         }
     });
+}
 
-    // Create bot state
-    let state = Arc::new(RwLock::new(BotState::new(
-        &config,
-        dry_run,
-        metrics,
-        alert_manager,
-        system_health,
-        config_manager,
-    )));
+/// Rewrite the public status bundle every `STATUS_PAGE_INTERVAL_SECS`
+fn spawn_status_page(state: Arc<RwLock<BotState>>, mode: &'static str, config: &Config) {
+    let dir = std::path::PathBuf::from(&config.status_page_dir);
+    let title = config.status_page_title.clone();
+    let interval = Duration::from_secs(config.status_page_interval_secs.max(1));
+    info!("🌐 Writing public status page to {} every {}s", dir.display(), interval.as_secs());
+    tokio::spawn(async move {
+        loop {
+            let status = {
+                let s = state.read().await;
+                let risk = s.risk_manager.status().await;
+                let health = s.system_health.read().await;
+                PublicStatus {
+                    generated_at: Utc::now(),
+                    mode: mode.to_string(),
+                    running: health.is_running,
+                    paused: s.control.paused().is_some(),
+                    uptime_seconds: health.start_time.elapsed().as_secs(),
+                    circuit_breaker: health.circuit_breaker_state.clone(),
+                    daily_pnl: PnlSign::of(risk.daily_pnl),
+                    trades_today: risk.trades_today,
+                    trades_successful: s.metrics.trades_successful.get(),
+                    trades_failed: s.metrics.trades_failed.get(),
+                    refresh_secs: interval.as_secs(),
+                }
+            };
+            if let Err(e) = status.write(&dir, &title) {
+                warn!("Failed to write status page to {}: {}", dir.display(), e);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
 
-    // Wire EventBus into RiskManager
-    {
-        let mut s = state.write().await;
-        let event_bus = s.event_bus.clone();
-        s.risk_manager.set_event_bus(event_bus).await;
+/// Human-readable run mode
+fn mode_label(watch_only: bool, dry_run: bool) -> &'static str {
+    if watch_only {
+        "watch-only"
+    } else if dry_run {
+        "dry-run"
+    } else {
+        "live"
     }
+}
 
-    // Run trading loop
-    run_trading_loop(state, pairs).await;
+/// History file for the given mode
+pub fn history_file_path(dry_run: bool) -> &'static str {
+    if dry_run {
+        "data/history-sim.jsonl"
+    } else {
+        "data/history-live.jsonl"
+    }
 }
 
 /// Resolves a token symbol to its Mint Pubkey.
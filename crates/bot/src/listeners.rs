@@ -0,0 +1,159 @@
+//! Server Listeners
+//!
+//! The bot serves its health and admin API and its Prometheus metrics on two
+//! ports. When a configured port is taken (a second bot, the standalone API
+//! server, a node exporter), binding moves up through the next
+//! `PORT_FALLBACK_RANGE` ports instead of leaving that server off. The
+//! addresses actually bound, or why a server could not start, are kept in
+//! [`BoundEndpoints`]: they go out in the startup alert, and each server
+//! reports both so one can be found from the other.
+
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+
+/// Where one server was asked to listen and where it ended up
+#[derive(Debug, Clone, Serialize)]
+pub struct Endpoint {
+    pub configured: String,
+    /// Address bound, `None` if the server is not running
+    pub bound: Option<String>,
+    /// Whether the server runs on a port other than the configured one
+    pub fell_back: bool,
+    /// Why the server could not bind
+    pub error: Option<String>,
+}
+
+impl Endpoint {
+    fn describe(&self) -> String {
+        match (&self.bound, &self.error) {
+            (Some(bound), _) if self.fell_back => {
+                format!("{} ({} was taken)", bound, self.configured)
+            }
+            (Some(bound), _) => bound.clone(),
+            (None, error) => format!(
+                "NOT RUNNING ({}: {})",
+                self.configured,
+                error.as_deref().unwrap_or("not started")
+            ),
+        }
+    }
+}
+
+/// Addresses of the health and metrics servers
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BoundEndpoints {
+    pub health: Option<Endpoint>,
+    pub metrics: Option<Endpoint>,
+}
+
+impl BoundEndpoints {
+    /// One line per server, for the startup alert
+    pub fn summary(&self) -> String {
+        [("Health API", &self.health), ("Metrics", &self.metrics)]
+            .iter()
+            .filter_map(|(name, endpoint)| {
+                endpoint
+                    .as_ref()
+                    .map(|e| format!("{}: {}", name, e.describe()))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether a server is off or moved to a fallback port
+    pub fn degraded(&self) -> bool {
+        [&self.health, &self.metrics]
+            .into_iter()
+            .flatten()
+            .any(|e| e.bound.is_none() || e.fell_back)
+    }
+}
+
+pub type SharedEndpoints = Arc<RwLock<BoundEndpoints>>;
+
+/// Bind `bind_addr:port`, or the first free port of the `fallback_range`
+/// ports after it. Returns the listener (or the last bind error) with the
+/// endpoint to report.
+pub async fn bind(
+    bind_addr: &str,
+    port: u16,
+    fallback_range: u16,
+) -> (Option<TcpListener>, Endpoint) {
+    let configured = format!("{}:{}", bind_addr, port);
+    let ip: IpAddr = match bind_addr.parse() {
+        Ok(ip) => ip,
+        Err(e) => {
+            let error = format!("invalid bind address '{}': {}", bind_addr, e);
+            return (
+                None,
+                Endpoint {
+                    configured,
+                    bound: None,
+                    fell_back: false,
+                    error: Some(error),
+                },
+            );
+        }
+    };
+
+    let mut last_error = None;
+    for candidate in port..=port.saturating_add(fallback_range) {
+        match TcpListener::bind(SocketAddr::new(ip, candidate)).await {
+            Ok(listener) => {
+                let bound = listener
+                    .local_addr()
+                    .map_or_else(|_| format!("{}:{}", ip, candidate), |a| a.to_string());
+                return (
+                    Some(listener),
+                    Endpoint {
+                        configured,
+                        bound: Some(bound),
+                        fell_back: candidate != port,
+                        error: None,
+                    },
+                );
+            }
+            Err(e) => last_error = Some(e.to_string()),
+        }
+    }
+    (
+        None,
+        Endpoint {
+            configured,
+            bound: None,
+            fell_back: false,
+            error: last_error,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_falls_back_past_an_occupied_port() {
+        let taken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let (listener, endpoint) = bind("127.0.0.1", port, 0).await;
+        assert!(listener.is_none());
+        assert!(endpoint.error.is_some());
+
+        let (listener, endpoint) = bind("127.0.0.1", port, 20).await;
+        let bound = listener.unwrap().local_addr().unwrap().port();
+        assert!(bound > port && bound <= port + 20);
+        assert!(endpoint.fell_back);
+
+        let (listener, endpoint) = bind("not-an-ip", port, 5).await;
+        assert!(listener.is_none());
+        let endpoints = BoundEndpoints {
+            health: Some(endpoint),
+            metrics: None,
+        };
+        assert!(endpoints.degraded());
+        assert!(endpoints.summary().starts_with("Health API: NOT RUNNING"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Per-Trade Loss Cap
+//!
+//! A Jupiter quote carries `otherAmountThreshold`: the least output the swap
+//! accepts under its slippage tolerance. Valued in USD against the trade's
+//! size, it bounds what the trade can lose before the swap reverts. Output
+//! in the quote token counts at the quote token's USD price, the base token
+//! at the opportunity's sell price, where the position is meant to be closed.
+//!
+//! With `risk.max_trade_loss_usd` set, a trade whose bound exceeds the cap
+//! is refused before it is signed and recorded with
+//! `failure_kind: "max_loss_exceeded"`. The bound is kept in the trade
+//! trace either way, so realized results can be compared against it.
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_arb_core::tokens::TokenRegistry;
+use solana_arb_core::ArbitrageOpportunity;
+
+/// The least a quote accepts, and what that leaves of the trade's size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorstCase {
+    /// `otherAmountThreshold` in the output token
+    pub min_out: Decimal,
+    pub value_usd: Decimal,
+    /// Size minus `value_usd`; negative when even the worst case profits
+    pub loss_usd: Decimal,
+}
+
+impl WorstCase {
+    /// The bound of a Jupiter v6 quote for a trade of `size_usd`, with the
+    /// pair's quote token worth `quote_usd`. `None` if the threshold is
+    /// missing or the output token is not one of the pair's.
+    pub fn from_quote(
+        quote: &serde_json::Value,
+        registry: &TokenRegistry,
+        opp: &ArbitrageOpportunity,
+        size_usd: Decimal,
+        quote_usd: Decimal,
+    ) -> Option<Self> {
+        let output = registry.get(quote.get("outputMint")?.as_str()?)?;
+        let atoms = quote.get("otherAmountThreshold")?.as_str()?.parse::<u64>().ok()?;
+        let min_out = solana_arb_core::tokens::from_atoms(atoms, output.decimals);
+        let value_quote = if output.symbol == opp.pair.quote {
+            min_out
+        } else if output.symbol == opp.pair.base {
+            min_out * opp.sell_price
+        } else {
+            return None;
+        };
+        let value_usd = value_quote * quote_usd;
+        Some(Self {
+            min_out,
+            value_usd,
+            loss_usd: (size_usd - value_usd).round_dp(6),
+        })
+    }
+}
+
+/// A trade refused because its worst case loses more than the cap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaxLossExceeded {
+    /// `None` when the quote gave no bound to check
+    pub loss_usd: Option<Decimal>,
+    pub cap_usd: Decimal,
+}
+
+impl std::fmt::Display for MaxLossExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.loss_usd {
+            Some(loss) => write!(
+                f,
+                "Worst-case loss ${} exceeds the ${} per-trade cap",
+                loss, self.cap_usd
+            ),
+            None => write!(f, "Quote has no worst-case output to check against the ${} cap", self.cap_usd),
+        }
+    }
+}
+
+impl std::error::Error for MaxLossExceeded {}
+
+/// Whether `error` is (or wraps) a [`MaxLossExceeded`]
+pub fn is_max_loss_exceeded(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<MaxLossExceeded>().is_some()
+}
+
+/// The cap in force; `None` (no cap) for unset or non-positive values
+pub fn cap_from_config(max_trade_loss_usd: Option<f64>) -> Option<Decimal> {
+    max_trade_loss_usd
+        .and_then(Decimal::from_f64)
+        .filter(|cap| *cap > Decimal::ZERO)
+}
+
+/// Refuse `worst` if it loses more than `cap`. Without a bound a capped
+/// trade is refused too, since nothing limits its loss.
+pub fn check(worst: Option<&WorstCase>, cap: Option<Decimal>) -> Result<(), MaxLossExceeded> {
+    let Some(cap_usd) = cap else {
+        return Ok(());
+    };
+    match worst {
+        Some(worst) if worst.loss_usd <= cap_usd => Ok(()),
+        _ => Err(MaxLossExceeded {
+            loss_usd: worst.map(|w| w.loss_usd),
+            cap_usd,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use solana_arb_core::{DexType, TokenPair, Uuid};
+
+    #[test]
+    fn test_worst_case_output_is_checked_against_cap() {
+        let registry = TokenRegistry::mainnet();
+        let opp = ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::new(5, 1),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: chrono::Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let usdc = registry.mint("USDC").unwrap();
+        let sol = registry.mint("SOL").unwrap();
+
+        // $1,000 of USDC for at least 9.8 SOL, worth $989.80 at the sell price
+        let buy = serde_json::json!({
+            "inputMint": usdc,
+            "outputMint": sol,
+            "otherAmountThreshold": "9800000000",
+        });
+        let worst = WorstCase::from_quote(&buy, &registry, &opp, Decimal::from(1000), Decimal::ONE).unwrap();
+        assert_eq!(worst.min_out, Decimal::new(98, 1));
+        assert_eq!(worst.loss_usd, Decimal::new(1020, 2));
+
+        assert!(check(Some(&worst), None).is_ok());
+        assert!(check(Some(&worst), cap_from_config(Some(15.0))).is_ok());
+        let refused = check(Some(&worst), cap_from_config(Some(10.0))).unwrap_err();
+        assert_eq!(refused.loss_usd, Some(Decimal::new(1020, 2)));
+        assert!(is_max_loss_exceeded(&anyhow::Error::new(refused)));
+
+        // A sell into USDC counts at par; a guaranteed gain is a negative loss
+        let sell = serde_json::json!({
+            "inputMint": sol,
+            "outputMint": usdc,
+            "otherAmountThreshold": "1002000000",
+        });
+        let worst = WorstCase::from_quote(&sell, &registry, &opp, Decimal::from(1000), Decimal::ONE).unwrap();
+        assert_eq!(worst.loss_usd, Decimal::from(-2));
+
+        // Without a threshold there is no bound, and a capped trade is refused
+        let unbounded = serde_json::json!({ "inputMint": usdc, "outputMint": sol });
+        assert_eq!(WorstCase::from_quote(&unbounded, &registry, &opp, Decimal::from(1000), Decimal::ONE), None);
+        assert!(check(None, cap_from_config(Some(10.0))).is_err());
+        assert!(check(None, cap_from_config(Some(0.0))).is_ok());
+    }
+}
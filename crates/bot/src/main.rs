@@ -2,5 +2,40 @@ use solana_arb_bot::run_bot;
 
 #[tokio::main]
 async fn main() {
+    // `bot export-metrics ...` exports the recorded metrics timeseries as CSV
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("export-metrics") {
+        if let Err(e) = solana_arb_bot::metrics::recorder::export_cli(&args[1..]) {
+            eprintln!("export-metrics: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    // `bot init ...` generates .env and the trading config and checks the environment
+    if args.first().map(String::as_str) == Some("init") {
+        match solana_arb_bot::onboarding::init_cli(&args[1..]).await {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("init: {}", e);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    // `bot trade ...` sends a manual trade to the running bot
+    if args.first().map(String::as_str) == Some("trade") {
+        match solana_arb_bot::manual_trade::trade_cli(&args[1..]).await {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("trade: {:#}", e);
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
     run_bot().await;
 }
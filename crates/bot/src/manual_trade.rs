@@ -0,0 +1,247 @@
+//! Manual Trades
+//!
+//! Lets an operator fire a specific arbitrage: a pair, the venues to buy
+//! and sell on, and a size. The opportunity is priced from the detector's
+//! current quotes and goes through the same path as a detected one: the
+//! trading pause, the risk manager, the fee reserve, quarantine, blacklist
+//! and direction checks, execution and recording. Only the profit
+//! threshold is waived, since the operator chose the trade.
+//!
+//! Orders reach the trading loop through [`ManualTrades`], fed by
+//! `POST /trades/manual` and the `bot trade` command, which calls it.
+
+use anyhow::{anyhow, Context, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::{DexType, TokenPair, Uuid};
+use std::str::FromStr;
+use tokio::sync::{mpsc, oneshot};
+
+/// Orders waiting for the trading loop
+const QUEUE_DEPTH: usize = 8;
+
+/// A manual trade as requested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualTradeRequest {
+    /// Pair in its canonical orientation, e.g. `SOL/USDC`
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub size_usd: Decimal,
+}
+
+/// A validated manual trade
+#[derive(Debug, Clone)]
+pub struct ManualOrder {
+    pub pair: TokenPair,
+    pub buy_dex: DexType,
+    pub sell_dex: DexType,
+    pub size_usd: Decimal,
+}
+
+impl ManualTradeRequest {
+    pub fn validate(&self) -> Result<ManualOrder, String> {
+        let (base, quote) = self
+            .pair
+            .split_once('/')
+            .ok_or_else(|| format!("pair '{}' is not BASE/QUOTE", self.pair))?;
+        let pair = TokenPair::new(base.trim(), quote.trim());
+        if !pair.is_canonical() {
+            return Err(format!("pair {} is traded as {}", pair, pair.canonical()));
+        }
+        let buy_dex = parse_dex(&self.buy_dex)?;
+        let sell_dex = parse_dex(&self.sell_dex)?;
+        if buy_dex == sell_dex {
+            return Err("buy and sell venues must differ".to_string());
+        }
+        if self.size_usd <= Decimal::ZERO {
+            return Err("size_usd must be > 0".to_string());
+        }
+        Ok(ManualOrder {
+            pair,
+            buy_dex,
+            sell_dex,
+            size_usd: self.size_usd,
+        })
+    }
+}
+
+fn parse_dex(name: &str) -> Result<DexType, String> {
+    DexType::all()
+        .iter()
+        .find(|dex| dex.display_name().eq_ignore_ascii_case(name.trim()))
+        .copied()
+        .ok_or_else(|| format!("unknown DEX '{}'", name))
+}
+
+/// How a manual trade ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManualTradeStatus {
+    /// Not executed; `reason` says why
+    Skipped,
+    /// Dry run: filled on paper
+    Simulated,
+    Executed,
+    Failed,
+}
+
+/// Outcome of a manual trade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualTradeReport {
+    pub status: ManualTradeStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opportunity_id: Option<Uuid>,
+    /// Net edge at the current quotes, in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_profit_pct: Option<Decimal>,
+    /// Size the risk checks let through
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_usd: Option<Decimal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profit_usd: Option<Decimal>,
+}
+
+impl ManualTradeReport {
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            status: ManualTradeStatus::Skipped,
+            reason: Some(reason.into()),
+            opportunity_id: None,
+            net_profit_pct: None,
+            size_usd: None,
+            signature: None,
+            profit_usd: None,
+        }
+    }
+}
+
+/// An order and where its report goes
+pub type ManualJob = (ManualOrder, oneshot::Sender<ManualTradeReport>);
+
+/// Queue of manual orders to the trading loop
+#[derive(Debug, Clone)]
+pub struct ManualTrades {
+    orders: mpsc::Sender<ManualJob>,
+}
+
+impl ManualTrades {
+    pub fn channel() -> (Self, mpsc::Receiver<ManualJob>) {
+        let (orders, queue) = mpsc::channel(QUEUE_DEPTH);
+        (Self { orders }, queue)
+    }
+
+    /// Queue `order` and wait for its report
+    pub async fn submit(&self, order: ManualOrder) -> Result<ManualTradeReport> {
+        let (reply, report) = oneshot::channel();
+        self.orders
+            .try_send((order, reply))
+            .map_err(|_| anyhow!("too many manual trades waiting"))?;
+        report.await.context("trading loop stopped")
+    }
+}
+
+/// `bot trade --pair SOL/USDC --buy Raydium --sell Orca --size 100`: send a
+/// manual trade to the running bot's API (`BOT_API_URL`, default
+/// `http://localhost:8080`) with `ADMIN_API_TOKEN` and print its report.
+/// Returns whether the trade was executed or simulated.
+pub async fn trade_cli(args: &[String]) -> Result<bool> {
+    let mut pair = None;
+    let mut buy_dex = None;
+    let mut sell_dex = None;
+    let mut size_usd = None;
+    let mut api_url =
+        std::env::var("BOT_API_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("{} needs a value", flag))
+        };
+        match flag.as_str() {
+            "--pair" => pair = Some(value()?),
+            "--buy" => buy_dex = Some(value()?),
+            "--sell" => sell_dex = Some(value()?),
+            "--size" => size_usd = Some(Decimal::from_str(&value()?).context("invalid --size")?),
+            "--api" => api_url = value()?,
+            other => return Err(anyhow!("unknown argument '{}'", other)),
+        }
+    }
+    let request = ManualTradeRequest {
+        pair: pair.ok_or_else(|| anyhow!("--pair is required"))?,
+        buy_dex: buy_dex.ok_or_else(|| anyhow!("--buy is required"))?,
+        sell_dex: sell_dex.ok_or_else(|| anyhow!("--sell is required"))?,
+        size_usd: size_usd.ok_or_else(|| anyhow!("--size is required"))?,
+    };
+    request.validate().map_err(|e| anyhow!(e))?;
+
+    let token = std::env::var("ADMIN_API_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| anyhow!("ADMIN_API_TOKEN is not set"))?;
+    let url = format!("{}/trades/manual", api_url.trim_end_matches('/'));
+    let response = reqwest::Client::new()
+        .post(&url)
+        .bearer_auth(token)
+        .json(&request)
+        .send()
+        .await
+        .with_context(|| format!("bot unreachable at {}", url))?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.context("invalid response from bot")?;
+    println!("{}", serde_json::to_string_pretty(&body)?);
+    if !status.is_success() {
+        return Ok(false);
+    }
+    let report: ManualTradeReport = serde_json::from_value(body)?;
+    Ok(matches!(
+        report.status,
+        ManualTradeStatus::Executed | ManualTradeStatus::Simulated
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(pair: &str, buy: &str, sell: &str, size: i64) -> ManualTradeRequest {
+        ManualTradeRequest {
+            pair: pair.to_string(),
+            buy_dex: buy.to_string(),
+            sell_dex: sell.to_string(),
+            size_usd: Decimal::from(size),
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let order = request("SOL/USDC", "raydium", "Orca", 100).validate().unwrap();
+        assert_eq!(order.pair, TokenPair::new("SOL", "USDC"));
+        assert_eq!((order.buy_dex, order.sell_dex), (DexType::Raydium, DexType::Orca));
+
+        assert!(request("USDC/SOL", "Raydium", "Orca", 100).validate().is_err());
+        assert!(request("SOL/USDC", "Raydium", "Raydium", 100).validate().is_err());
+        assert!(request("SOL/USDC", "Raydium", "Nowhere", 100).validate().is_err());
+        assert!(request("SOL/USDC", "Raydium", "Orca", 0).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submit_waits_for_report() {
+        let (trades, mut queue) = ManualTrades::channel();
+        tokio::spawn(async move {
+            while let Some((order, reply)) = queue.recv().await {
+                let _ = reply.send(ManualTradeReport::skipped(format!("{} paused", order.pair)));
+            }
+        });
+        let order = request("SOL/USDC", "Raydium", "Orca", 100).validate().unwrap();
+        let report = trades.submit(order).await.unwrap();
+        assert_eq!(report.status, ManualTradeStatus::Skipped);
+        assert_eq!(report.reason.as_deref(), Some("SOL/USDC paused"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Market State
+//!
+//! What the pipeline stages derive from prices: the detector, the path finder
+//! rebuilt from its quotes, the spread guard and the price projection. It sits
+//! behind its own lock instead of the bot state's, so applying a price batch
+//! does not wait for an execution holding the bot state. Guards are never
+//! held across an await.
+
+use solana_arb_core::arbitrage::ArbitrageDetector;
+use solana_arb_core::pathfinding::PathFinder;
+use solana_arb_core::pricing::projection::PriceProjector;
+use solana_arb_core::pricing::spread_guard::SpreadGuard;
+use solana_arb_core::{PriceData, TokenPair};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// Price samples the risk manager may fall behind by before the oldest are
+/// dropped
+const MAX_PENDING_RISK_PRICES: usize = 4096;
+
+pub type SharedMarket = Arc<RwLock<MarketState>>;
+
+/// What applying one price batch did
+#[derive(Debug, Default)]
+pub struct AppliedPrices {
+    /// Prices the spread guard kept
+    pub kept: Vec<PriceData>,
+    /// Prices too wide to trade; their venue's older quote was dropped
+    pub too_wide: Vec<PriceData>,
+    /// Pairs whose bid or ask changed on some venue
+    pub moved: HashSet<TokenPair>,
+}
+
+pub struct MarketState {
+    /// Service for detecting arbitrage opportunities.
+    pub detector: ArbitrageDetector,
+    /// Service for finding profitable paths.
+    pub path_finder: PathFinder,
+    /// Per-venue bid/ask spread limits applied before detection.
+    pub spread_guard: SpreadGuard,
+    /// Drift of fast venues' prices, projected over the execution latency
+    /// before edge is judged (None when disabled)
+    pub price_projection: Option<PriceProjector>,
+    /// Applied prices not yet fed to the risk manager's volatility tracking
+    risk_prices: Vec<PriceData>,
+}
+
+impl MarketState {
+    pub fn new(
+        detector: ArbitrageDetector,
+        path_finder: PathFinder,
+        spread_guard: SpreadGuard,
+        price_projection: Option<PriceProjector>,
+    ) -> Self {
+        Self {
+            detector,
+            path_finder,
+            spread_guard,
+            price_projection,
+            risk_prices: Vec::new(),
+        }
+    }
+
+    pub fn shared(self) -> SharedMarket {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// Feed `prices` through the spread guard into the detector, drop
+    /// quotes older than `max_age_seconds` and rebuild the path finder from
+    /// every current quote, since a streamed batch holds only the venues
+    /// that updated
+    pub fn apply(&mut self, prices: Vec<PriceData>, max_age_seconds: i64) -> AppliedPrices {
+        let (kept, too_wide): (Vec<_>, Vec<_>) =
+            prices.into_iter().partition(|p| self.spread_guard.allows(p));
+        for price in &too_wide {
+            self.detector.remove_price(&price.pair, price.dex);
+        }
+
+        let moved = kept
+            .iter()
+            .filter(|p| {
+                self.detector
+                    .get_prices()
+                    .get(&(p.pair.clone(), p.dex))
+                    .is_none_or(|old| old.bid != p.bid || old.ask != p.ask)
+            })
+            .map(|p| p.pair.clone())
+            .collect();
+
+        self.detector.update_prices(kept.clone());
+        self.detector.clear_stale_prices(max_age_seconds);
+
+        self.path_finder.clear();
+        for price in self.detector.get_prices().values() {
+            self.path_finder.add_price(price);
+        }
+
+        if let Some(projector) = &mut self.price_projection {
+            for price in &kept {
+                projector.observe(price);
+            }
+        }
+
+        self.risk_prices.extend(kept.iter().cloned());
+        let overflow = self.risk_prices.len().saturating_sub(MAX_PENDING_RISK_PRICES);
+        self.risk_prices.drain(..overflow);
+
+        AppliedPrices { kept, too_wide, moved }
+    }
+
+    /// Prices applied since the last call, oldest first, for the risk manager
+    pub fn take_risk_prices(&mut self) -> Vec<PriceData> {
+        std::mem::take(&mut self.risk_prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use solana_arb_core::DexType;
+
+    fn quote(dex: DexType, bid: i64, ask: i64) -> PriceData {
+        PriceData::new(dex, TokenPair::new("SOL", "USDC"), Decimal::from(bid), Decimal::from(ask))
+    }
+
+    #[test]
+    fn test_apply_guards_spreads_and_queues_risk_prices() {
+        let mut market = MarketState::new(
+            ArbitrageDetector::default(),
+            PathFinder::new(4),
+            SpreadGuard::new(1.0),
+            None,
+        );
+
+        let applied = market.apply(vec![quote(DexType::Raydium, 995, 1000), quote(DexType::Orca, 995, 1000)], 60);
+        assert_eq!(applied.kept.len(), 2);
+        assert!(applied.moved.contains(&TokenPair::new("SOL", "USDC")));
+        assert_eq!(market.detector.get_prices().len(), 2);
+
+        // An unchanged quote did not move; a too-wide one drops the venue
+        let applied = market.apply(vec![quote(DexType::Raydium, 995, 1000), quote(DexType::Orca, 900, 1100)], 60);
+        assert!(applied.moved.is_empty());
+        assert_eq!(applied.too_wide.len(), 1);
+        assert_eq!(market.detector.get_prices().len(), 1);
+
+        assert_eq!(market.take_risk_prices().len(), 3);
+        assert!(market.take_risk_prices().is_empty());
+    }
+}
@@ -1 +1,2 @@
 pub mod prometheus;
+pub mod recorder;
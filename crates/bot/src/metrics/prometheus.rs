@@ -1,4 +1,9 @@
-use prometheus::{Gauge, Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use prometheus::{
+    Gauge, GaugeVec, Histogram, HistogramOpts, HistogramTimer, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
+use std::time::Instant;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 #[allow(dead_code)]
 pub struct MetricsCollector {
@@ -6,20 +11,68 @@ pub struct MetricsCollector {
 
     // Counters
     pub opportunities_detected: IntCounter,
+    pub opportunities_by_source: IntCounterVec, // labelled by source (detector or strategy name)
     pub trades_attempted: IntCounter,
     pub trades_successful: IntCounter,
     pub trades_failed: IntCounter,
+    pub trades_route_mismatch: IntCounter,
+    pub trades_aborted_deadline: IntCounter,
+    pub trades_slippage_exceeded: IntCounter,
+    pub trades_compliance_veto: IntCounter,
+    pub trades_max_loss_exceeded: IntCounter,
+    pub trades_program_error: IntCounter,
+    pub trades_by_source: IntCounterVec, // labelled by source and result (success/failed)
+    pub profit_by_source: GaugeVec,      // labelled by source
+    pub submit_failures: IntCounterVec, // labelled by cause and action
+    pub path_trades: IntCounterVec, // labelled by result (success/failed/dry_run)
+    pub price_batches: IntCounterVec, // labelled by source (stream/poll)
+    pub route_repairs: IntCounterVec, // labelled by result (repaired/failed)
+    pub preflight_simulations: IntCounter,
+    pub preflight_passed: IntCounter,
+    pub prices_rejected_spread: IntCounterVec, // labelled by venue
+    pub quote_cache_lookups: IntCounterVec,    // labelled by result (hit/miss)
+    pub quote_cache_invalidations: IntCounter,
+    pub quote_skew_rejections: IntCounter,
+    pub pipeline_dropped: IntCounterVec, // labelled by reason (superseded/halted)
+    pub price_fetch_timeouts: IntCounterVec, // labelled by venue
+    pub price_fetch_hedges: IntCounterVec,   // labelled by venue and result (sent/won)
 
     // Gauges
     pub current_balance: Gauge,
     pub active_positions: IntGauge,
     pub circuit_breaker_state: IntGauge, // 0=closed, 1=half-open, 2=open
+    pub venue_exposure: GaugeVec,        // labelled by venue
+    pub venue_exposure_accounting: GaugeVec, // labelled by venue and currency
+    pub fx_usd_per_unit: GaugeVec,       // labelled by currency
+    pub fx_rate_age_seconds: Gauge,
+    pub jupiter_endpoint_up: IntGaugeVec, // labelled by endpoint (configured/public)
+    pub jupiter_latency_saving_ms: Gauge,
+    pub rate_limit_budget: IntGaugeVec, // labelled by limiter (rpc/jupiter)
+    pub spread_index: Gauge,
+    pub market_dislocated: IntGauge,
+    pub fee_reserve_sol: Gauge,
+    pub flash_reserve_utilization: GaugeVec, // labelled by token
+    pub flash_loan_provider_selected: IntCounterVec, // labelled by provider
+    pub memory_bytes: IntGaugeVec,           // labelled by component
+    pub memory_entries: IntGaugeVec,         // labelled by component
+    pub event_bus_lag: IntGaugeVec,          // labelled by subscriber
+    pub event_bus_dropped: IntGaugeVec,      // labelled by subscriber
+    pub process_resident_bytes: IntGauge,
+    pub pipeline_queue_depth: IntGaugeVec, // labelled by stage (detect/execute)
+    pub submission_queue_depth: IntGauge,
 
     // Histograms
     pub opportunity_profit: Histogram,
     pub trade_execution_time: Histogram,
     pub price_fetch_latency: Histogram,
     pub slippage_distribution: Histogram,
+    /// Net edge (bps) taken off opportunities by latency projection
+    pub projection_edge_cut_bps: Histogram,
+    pub quote_skew_ms: Histogram,
+    pub submission_wait: Histogram,
+    pub jupiter_probe_latency: HistogramVec, // labelled by endpoint
+    pub state_lock_wait: HistogramVec,       // labelled by mode (read/write) and stage
+    pub loop_stage_duration: HistogramVec,   // labelled by stage
 }
 
 impl MetricsCollector {
@@ -33,6 +86,15 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(opportunities_detected.clone()))?;
 
+        let opportunities_by_source = IntCounterVec::new(
+            Opts::new(
+                "arb_opportunities_by_source_total",
+                "Opportunities found, by source (the detector or the strategy's name)",
+            ),
+            &["source"],
+        )?;
+        registry.register(Box::new(opportunities_by_source.clone()))?;
+
         let trades_attempted = IntCounter::new(
             "arb_trades_attempted_total",
             "Total number of trades attempted",
@@ -49,6 +111,138 @@ impl MetricsCollector {
             IntCounter::new("arb_trades_failed_total", "Total number of failed trades")?;
         registry.register(Box::new(trades_failed.clone()))?;
 
+        let trades_route_mismatch = IntCounter::new(
+            "arb_trades_route_mismatch_total",
+            "Trades refused because the quoted route did not cross the detected venues",
+        )?;
+        registry.register(Box::new(trades_route_mismatch.clone()))?;
+
+        let trades_aborted_deadline = IntCounter::new(
+            "arb_trades_aborted_deadline_total",
+            "Trades abandoned because quote, swap instructions and build missed the execution deadline",
+        )?;
+        registry.register(Box::new(trades_aborted_deadline.clone()))?;
+
+        let trades_slippage_exceeded = IntCounter::new(
+            "arb_trades_slippage_exceeded_total",
+            "Trades quoted further from the detected price than their slippage tolerance",
+        )?;
+        registry.register(Box::new(trades_slippage_exceeded.clone()))?;
+
+        let trades_compliance_veto = IntCounter::new(
+            "arb_trades_compliance_veto_total",
+            "Trades a pre-trade compliance hook vetoed before signing",
+        )?;
+        registry.register(Box::new(trades_compliance_veto.clone()))?;
+
+        let trades_max_loss_exceeded = IntCounter::new(
+            "arb_trades_max_loss_exceeded_total",
+            "Trades refused because their worst-case loss exceeded the per-trade cap",
+        )?;
+        registry.register(Box::new(trades_max_loss_exceeded.clone()))?;
+
+        let trades_program_error = IntCounter::new(
+            "arb_trades_program_error_total",
+            "Trades stopped without retrying because a program failed the transaction",
+        )?;
+        registry.register(Box::new(trades_program_error.clone()))?;
+
+        let trades_by_source = IntCounterVec::new(
+            Opts::new(
+                "arb_trades_by_source_total",
+                "Executed trades, by the source of their opportunity and result",
+            ),
+            &["source", "result"],
+        )?;
+        registry.register(Box::new(trades_by_source.clone()))?;
+
+        let profit_by_source = GaugeVec::new(
+            Opts::new(
+                "arb_profit_by_source_usd",
+                "Realized profit (USD) of this run, by the source of the opportunity",
+            ),
+            &["source"],
+        )?;
+        registry.register(Box::new(profit_by_source.clone()))?;
+
+        let submit_failures = IntCounterVec::new(
+            Opts::new(
+                "arb_submit_failures_total",
+                "Failed submission attempts, by classified cause and the retry action taken",
+            ),
+            &["cause", "action"],
+        )?;
+        registry.register(Box::new(submit_failures.clone()))?;
+
+        let path_trades = IntCounterVec::new(
+            Opts::new(
+                "arb_path_trades_total",
+                "Multi-hop path trades from the path finder, by result",
+            ),
+            &["result"],
+        )?;
+        registry.register(Box::new(path_trades.clone()))?;
+
+        let price_batches = IntCounterVec::new(
+            Opts::new(
+                "arb_price_batches_total",
+                "Price batches sent to detection, by source (streamed updates or a poll)",
+            ),
+            &["source"],
+        )?;
+        registry.register(Box::new(price_batches.clone()))?;
+
+        let route_repairs = IntCounterVec::new(
+            Opts::new(
+                "arb_route_repairs_total",
+                "Repair trades selling base tokens a failed trade left in the wallet, by result",
+            ),
+            &["result"],
+        )?;
+        registry.register(Box::new(route_repairs.clone()))?;
+
+        let preflight_simulations = IntCounter::new(
+            "arb_preflight_simulations_total",
+            "Candidate opportunities simulated before choosing one to execute",
+        )?;
+        registry.register(Box::new(preflight_simulations.clone()))?;
+
+        let preflight_passed = IntCounter::new(
+            "arb_preflight_passed_total",
+            "Candidate simulations that built, matched their route and showed a profit",
+        )?;
+        registry.register(Box::new(preflight_passed.clone()))?;
+
+        let prices_rejected_spread = IntCounterVec::new(
+            Opts::new(
+                "arb_prices_rejected_spread_total",
+                "Prices dropped before detection because their bid/ask spread exceeded the venue limit",
+            ),
+            &["venue"],
+        )?;
+        registry.register(Box::new(prices_rejected_spread.clone()))?;
+
+        let quote_cache_lookups = IntCounterVec::new(
+            Opts::new(
+                "arb_quote_cache_lookups_total",
+                "Jupiter quote cache lookups, by whether a fresh quote was reused",
+            ),
+            &["result"],
+        )?;
+        registry.register(Box::new(quote_cache_lookups.clone()))?;
+
+        let quote_cache_invalidations = IntCounter::new(
+            "arb_quote_cache_invalidations_total",
+            "Cached Jupiter quotes dropped because their pair's price moved",
+        )?;
+        registry.register(Box::new(quote_cache_invalidations.clone()))?;
+
+        let quote_skew_rejections = IntCounter::new(
+            "arb_quote_skew_rejections_total",
+            "Profitable venue pairings dropped because their quotes were too far apart",
+        )?;
+        registry.register(Box::new(quote_skew_rejections.clone()))?;
+
         // Initialize gauges
         let current_balance =
             Gauge::new("arb_current_balance_usd", "Current account balance in USD")?;
@@ -66,6 +260,183 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(circuit_breaker_state.clone()))?;
 
+        let venue_exposure = GaugeVec::new(
+            Opts::new(
+                "arb_venue_exposure_usd",
+                "Current exposure routed through each DEX venue in USD",
+            ),
+            &["venue"],
+        )?;
+        registry.register(Box::new(venue_exposure.clone()))?;
+
+        let venue_exposure_accounting = GaugeVec::new(
+            Opts::new(
+                "arb_venue_exposure_accounting",
+                "Current exposure routed through each DEX venue in the accounting currency",
+            ),
+            &["venue", "currency"],
+        )?;
+        registry.register(Box::new(venue_exposure_accounting.clone()))?;
+
+        let fx_usd_per_unit = GaugeVec::new(
+            Opts::new(
+                "arb_fx_usd_per_unit",
+                "USD per unit of the accounting currency amounts and limits are converted at",
+            ),
+            &["currency"],
+        )?;
+        registry.register(Box::new(fx_usd_per_unit.clone()))?;
+
+        let fx_rate_age_seconds = Gauge::new(
+            "arb_fx_rate_age_seconds",
+            "Seconds since the oracle published the accounting currency's exchange rate",
+        )?;
+        registry.register(Box::new(fx_rate_age_seconds.clone()))?;
+
+        let jupiter_endpoint_up = IntGaugeVec::new(
+            Opts::new(
+                "arb_jupiter_endpoint_up",
+                "Whether the last Jupiter API probe succeeded (1) or failed (0)",
+            ),
+            &["endpoint"],
+        )?;
+        registry.register(Box::new(jupiter_endpoint_up.clone()))?;
+
+        let jupiter_latency_saving_ms = Gauge::new(
+            "arb_jupiter_latency_saving_ms",
+            "Average quote latency saved by the configured Jupiter API over the public one",
+        )?;
+        registry.register(Box::new(jupiter_latency_saving_ms.clone()))?;
+
+        let rate_limit_budget = IntGaugeVec::new(
+            Opts::new(
+                "arb_rate_limit_budget",
+                "Current rate limiter budget in requests per second",
+            ),
+            &["limiter"],
+        )?;
+        registry.register(Box::new(rate_limit_budget.clone()))?;
+
+        let spread_index = Gauge::new(
+            "arb_spread_index_bps",
+            "Notional-weighted cross-DEX spread across tracked pairs in basis points",
+        )?;
+        registry.register(Box::new(spread_index.clone()))?;
+
+        let market_dislocated = IntGauge::new(
+            "arb_market_dislocated",
+            "Whether the spread index is abnormally far above its baseline (1) or not (0)",
+        )?;
+        registry.register(Box::new(market_dislocated.clone()))?;
+
+        let fee_reserve_sol = Gauge::new(
+            "arb_fee_reserve_sol",
+            "Wallet SOL balance available for transaction fees",
+        )?;
+        registry.register(Box::new(fee_reserve_sol.clone()))?;
+
+        let pipeline_queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "arb_pipeline_queue_depth",
+                "Batches waiting for each trading pipeline stage",
+            ),
+            &["stage"],
+        )?;
+        registry.register(Box::new(pipeline_queue_depth.clone()))?;
+
+        let pipeline_dropped = IntCounterVec::new(
+            Opts::new(
+                "arb_pipeline_dropped_total",
+                "Candidate batches dropped before execution, by reason",
+            ),
+            &["reason"],
+        )?;
+        registry.register(Box::new(pipeline_dropped.clone()))?;
+
+        let price_fetch_timeouts = IntCounterVec::new(
+            Opts::new(
+                "arb_price_fetch_timeouts_total",
+                "Venue price requests cut off by their timeout or the fetch budget",
+            ),
+            &["venue"],
+        )?;
+        registry.register(Box::new(price_fetch_timeouts.clone()))?;
+
+        let price_fetch_hedges = IntCounterVec::new(
+            Opts::new(
+                "arb_price_fetch_hedges_total",
+                "Second price requests sent after a venue's p95 latency, and those answering first",
+            ),
+            &["venue", "result"],
+        )?;
+        registry.register(Box::new(price_fetch_hedges.clone()))?;
+
+        let submission_queue_depth = IntGauge::new(
+            "arb_submission_queue_depth",
+            "Most transactions waiting at once for accounts another submission holds, since the last tick",
+        )?;
+        registry.register(Box::new(submission_queue_depth.clone()))?;
+
+        let flash_reserve_utilization = GaugeVec::new(
+            Opts::new(
+                "arb_flash_reserve_utilization",
+                "Borrowed share (0-1) of the flash loan reserve lending each token",
+            ),
+            &["token"],
+        )?;
+        registry.register(Box::new(flash_reserve_utilization.clone()))?;
+
+        let flash_loan_provider_selected = IntCounterVec::new(
+            Opts::new(
+                "arb_flash_loan_provider_selected_total",
+                "Viable flash loans routed to each provider",
+            ),
+            &["provider"],
+        )?;
+        registry.register(Box::new(flash_loan_provider_selected.clone()))?;
+
+        let memory_bytes = IntGaugeVec::new(
+            Opts::new(
+                "arb_memory_bytes",
+                "Estimated bytes held by each in-memory buffer or cache",
+            ),
+            &["component"],
+        )?;
+        registry.register(Box::new(memory_bytes.clone()))?;
+
+        let memory_entries = IntGaugeVec::new(
+            Opts::new(
+                "arb_memory_entries",
+                "Entries held by each in-memory buffer or cache",
+            ),
+            &["component"],
+        )?;
+        registry.register(Box::new(memory_entries.clone()))?;
+
+        let event_bus_lag = IntGaugeVec::new(
+            Opts::new(
+                "arb_event_bus_lag",
+                "Events published that each event bus subscriber has not consumed yet",
+            ),
+            &["subscriber"],
+        )?;
+        registry.register(Box::new(event_bus_lag.clone()))?;
+
+        let event_bus_dropped = IntGaugeVec::new(
+            Opts::new(
+                "arb_event_bus_dropped",
+                "Events each event bus subscriber lost by falling behind",
+            ),
+            &["subscriber"],
+        )?;
+        registry.register(Box::new(event_bus_dropped.clone()))?;
+
+        let process_resident_bytes = IntGauge::new(
+            "arb_process_resident_bytes",
+            "Resident set size of the bot process in bytes",
+        )?;
+        registry.register(Box::new(process_resident_bytes.clone()))?;
+
         // Initialize histograms
         let opportunity_profit = Histogram::with_opts(
             HistogramOpts::new(
@@ -100,22 +471,151 @@ impl MetricsCollector {
         )?;
         registry.register(Box::new(slippage_distribution.clone()))?;
 
+        let projection_edge_cut_bps = Histogram::with_opts(
+            HistogramOpts::new(
+                "arb_projection_edge_cut_bps",
+                "Net edge removed from opportunities by latency-compensated price projection, in basis points",
+            )
+            .buckets(vec![1.0, 2.0, 5.0, 10.0, 25.0, 50.0]),
+        )?;
+        registry.register(Box::new(projection_edge_cut_bps.clone()))?;
+
+        let quote_skew_ms = Histogram::with_opts(
+            HistogramOpts::new(
+                "arb_quote_skew_ms",
+                "Time between the two venues' quotes of profitable pairings in milliseconds",
+            )
+            .buckets(vec![50.0, 100.0, 250.0, 500.0, 1000.0, 2000.0, 5000.0]),
+        )?;
+        registry.register(Box::new(quote_skew_ms.clone()))?;
+
+        let submission_wait = Histogram::with_opts(
+            HistogramOpts::new(
+                "arb_submission_wait_seconds",
+                "Time a transaction waited for accounts another submission held, in seconds",
+            )
+            .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0]),
+        )?;
+        registry.register(Box::new(submission_wait.clone()))?;
+
+        let jupiter_probe_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "arb_jupiter_probe_seconds",
+                "Jupiter API probe quote latency in seconds",
+            )
+            .buckets(vec![0.01, 0.025, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0]),
+            &["endpoint"],
+        )?;
+        registry.register(Box::new(jupiter_probe_latency.clone()))?;
+
+        let state_lock_wait = HistogramVec::new(
+            HistogramOpts::new(
+                "arb_state_lock_wait_seconds",
+                "Time the main loop waited to acquire the bot state lock",
+            )
+            .buckets(vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0]),
+            &["mode", "stage"],
+        )?;
+        registry.register(Box::new(state_lock_wait.clone()))?;
+
+        let loop_stage_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "arb_loop_stage_seconds",
+                "Time spent in each stage of the main loop, lock waits included",
+            )
+            .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]),
+            &["stage"],
+        )?;
+        registry.register(Box::new(loop_stage_duration.clone()))?;
+
         Ok(Self {
             registry,
             opportunities_detected,
+            opportunities_by_source,
             trades_attempted,
             trades_successful,
             trades_failed,
+            trades_route_mismatch,
+            trades_aborted_deadline,
+            trades_slippage_exceeded,
+            trades_compliance_veto,
+            trades_max_loss_exceeded,
+            trades_program_error,
+            trades_by_source,
+            profit_by_source,
+            submit_failures,
+            path_trades,
+            price_batches,
+            route_repairs,
+            preflight_simulations,
+            preflight_passed,
+            prices_rejected_spread,
+            quote_cache_lookups,
+            quote_cache_invalidations,
+            pipeline_dropped,
+            price_fetch_timeouts,
+            price_fetch_hedges,
+            quote_skew_rejections,
             current_balance,
             active_positions,
             circuit_breaker_state,
+            venue_exposure,
+            venue_exposure_accounting,
+            fx_usd_per_unit,
+            fx_rate_age_seconds,
+            jupiter_endpoint_up,
+            jupiter_latency_saving_ms,
+            rate_limit_budget,
+            spread_index,
+            market_dislocated,
+            fee_reserve_sol,
+            flash_reserve_utilization,
+            flash_loan_provider_selected,
+            memory_bytes,
+            memory_entries,
+            event_bus_lag,
+            event_bus_dropped,
+            process_resident_bytes,
+            pipeline_queue_depth,
+            submission_queue_depth,
             opportunity_profit,
             trade_execution_time,
             price_fetch_latency,
             slippage_distribution,
+            projection_edge_cut_bps,
+            quote_skew_ms,
+            submission_wait,
+            jupiter_probe_latency,
+            state_lock_wait,
+            loop_stage_duration,
         })
     }
 
+    /// Acquire `lock` for reading, recording the wait under `stage`
+    pub async fn timed_read<'a, T>(&self, lock: &'a RwLock<T>, stage: &str) -> RwLockReadGuard<'a, T> {
+        let started = Instant::now();
+        let guard = lock.read().await;
+        self.state_lock_wait
+            .with_label_values(&["read", stage])
+            .observe(started.elapsed().as_secs_f64());
+        guard
+    }
+
+    /// Acquire `lock` for writing, recording the wait under `stage`
+    pub async fn timed_write<'a, T>(&self, lock: &'a RwLock<T>, stage: &str) -> RwLockWriteGuard<'a, T> {
+        let started = Instant::now();
+        let guard = lock.write().await;
+        self.state_lock_wait
+            .with_label_values(&["write", stage])
+            .observe(started.elapsed().as_secs_f64());
+        guard
+    }
+
+    /// Timer recording the time until it is dropped as `stage`'s duration
+    pub fn stage_timer(&self, stage: &str) -> HistogramTimer {
+        self.loop_stage_duration.with_label_values(&[stage]).start_timer()
+    }
+
     pub fn registry(&self) -> &Registry {
         &self.registry
     }
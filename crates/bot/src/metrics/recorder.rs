@@ -0,0 +1,349 @@
+//! Metrics Recorder
+//!
+//! Snapshots the Prometheus registry to a local JSON-lines timeseries file
+//! so metrics can be analysed offline when nothing is scraping `/metrics`.
+//! Ranges are exported as CSV through `GET /metrics/export` or
+//! `bot export-metrics`.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use prometheus::proto::MetricType;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Default location of the timeseries file
+pub const DEFAULT_METRICS_PATH: &str = "data/metrics_timeseries.jsonl";
+
+/// One snapshot of every counter and gauge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub ts: DateTime<Utc>,
+    /// Series key (`name` or `name{label="value"}`) to value
+    #[serde(rename = "v")]
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Current value of every series in `registry`.
+///
+/// Counters and gauges are recorded as-is. Histograms and summaries are
+/// recorded as `<name>_count` and `<name>_sum`, which is enough to derive
+/// rates and averages over a range.
+pub fn snapshot(registry: &Registry) -> BTreeMap<String, f64> {
+    let mut values = BTreeMap::new();
+    for family in registry.gather() {
+        let name = family.get_name();
+        for metric in family.get_metric() {
+            let labels = metric
+                .get_label()
+                .iter()
+                .map(|l| format!("{}=\"{}\"", l.get_name(), l.get_value()))
+                .collect::<Vec<_>>()
+                .join(",");
+            let key = |name: &str| {
+                if labels.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}{{{}}}", name, labels)
+                }
+            };
+
+            match family.get_field_type() {
+                MetricType::COUNTER => {
+                    values.insert(key(name), metric.get_counter().get_value());
+                }
+                MetricType::GAUGE => {
+                    values.insert(key(name), metric.get_gauge().get_value());
+                }
+                MetricType::HISTOGRAM => {
+                    let h = metric.get_histogram();
+                    values.insert(key(&format!("{}_count", name)), h.get_sample_count() as f64);
+                    values.insert(key(&format!("{}_sum", name)), h.get_sample_sum());
+                }
+                MetricType::SUMMARY => {
+                    let s = metric.get_summary();
+                    values.insert(key(&format!("{}_count", name)), s.get_sample_count() as f64);
+                    values.insert(key(&format!("{}_sum", name)), s.get_sample_sum());
+                }
+                MetricType::UNTYPED => {
+                    values.insert(key(name), metric.get_untyped().get_value());
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Append-only timeseries file of [`MetricSample`]s
+#[derive(Debug, Clone)]
+pub struct MetricsRecorder {
+    path: PathBuf,
+    retention: Duration,
+}
+
+impl MetricsRecorder {
+    pub fn new(path: impl Into<PathBuf>, retention_days: u32) -> Self {
+        Self {
+            path: path.into(),
+            retention: Duration::days(retention_days.max(1) as i64),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one sample
+    pub fn append(&self, sample: &MetricSample) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(sample)?)?;
+        Ok(())
+    }
+
+    /// Snapshot `registry` and append it
+    pub fn record(&self, registry: &Registry) -> Result<()> {
+        self.append(&MetricSample {
+            ts: Utc::now(),
+            values: snapshot(registry),
+        })
+    }
+
+    /// Samples with `from <= ts <= to`, oldest first. Unreadable lines are
+    /// skipped; a missing file is an empty range.
+    pub fn read_range(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<MetricSample>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut samples = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let Ok(sample) = serde_json::from_str::<MetricSample>(&line?) else {
+                continue;
+            };
+            if from.is_some_and(|from| sample.ts < from) || to.is_some_and(|to| sample.ts > to) {
+                continue;
+            }
+            samples.push(sample);
+        }
+        Ok(samples)
+    }
+
+    /// Drop samples older than the retention period. Returns how many were removed.
+    pub fn prune(&self, now: DateTime<Utc>) -> Result<usize> {
+        let all = self.read_range(None, None)?;
+        let kept: Vec<&MetricSample> = all
+            .iter()
+            .filter(|s| s.ts >= now - self.retention)
+            .collect();
+        let removed = all.len() - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        let tmp = self.path.with_extension("jsonl.tmp");
+        let mut content = String::new();
+        for sample in kept {
+            content.push_str(&serde_json::to_string(sample)?);
+            content.push('\n');
+        }
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(removed)
+    }
+}
+
+/// Render samples as CSV: a `timestamp` column then one column per series.
+///
+/// `series` limits the columns to keys starting with any of the given
+/// prefixes (e.g. `arb_trades` or a full key). Series missing from a sample
+/// are left empty.
+pub fn to_csv(samples: &[MetricSample], series: &[String]) -> String {
+    let columns: BTreeSet<&String> = samples
+        .iter()
+        .flat_map(|s| s.values.keys())
+        .filter(|key| series.is_empty() || series.iter().any(|p| key.starts_with(p.as_str())))
+        .collect();
+
+    let mut csv = String::from("timestamp");
+    for column in &columns {
+        csv.push(',');
+        csv.push_str(&csv_field(column));
+    }
+    csv.push('\n');
+
+    for sample in samples {
+        csv.push_str(&sample.ts.to_rfc3339());
+        for column in &columns {
+            csv.push(',');
+            if let Some(value) = sample.values.get(*column) {
+                csv.push_str(&value.to_string());
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field if it contains a separator or quote
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (midnight UTC)
+pub fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| anyhow!("invalid time '{}': expected RFC 3339 or YYYY-MM-DD", value))
+}
+
+/// `bot export-metrics [--from T] [--to T] [--series a,b] [--file PATH] [--out PATH]`
+///
+/// Writes the CSV to `--out` or stdout. The file defaults to
+/// `METRICS_RECORDER_PATH`.
+pub fn export_cli(args: &[String]) -> Result<()> {
+    let mut from = None;
+    let mut to = None;
+    let mut series = Vec::new();
+    let mut file =
+        std::env::var("METRICS_RECORDER_PATH").unwrap_or_else(|_| DEFAULT_METRICS_PATH.to_string());
+    let mut out = None;
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("{} needs a value", flag))
+        };
+        match flag.as_str() {
+            "--from" => from = Some(parse_time(&value()?)?),
+            "--to" => to = Some(parse_time(&value()?)?),
+            "--series" => {
+                series = value()?
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            }
+            "--file" => file = value()?,
+            "--out" => out = Some(value()?),
+            other => return Err(anyhow!("unknown argument '{}'", other)),
+        }
+    }
+
+    let samples = MetricsRecorder::new(&file, u32::MAX)
+        .read_range(from, to)
+        .with_context(|| format!("failed to read {}", file))?;
+    let csv = to_csv(&samples, &series);
+    match out {
+        Some(path) => fs::write(&path, csv).with_context(|| format!("failed to write {}", path))?,
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{HistogramOpts, HistogramVec, IntCounter};
+
+    fn temp_recorder() -> MetricsRecorder {
+        let path = std::env::temp_dir().join(format!(
+            "metrics_timeseries_{}.jsonl",
+            solana_arb_core::Uuid::new_v4()
+        ));
+        MetricsRecorder::new(path, 7)
+    }
+
+    fn sample(ts: &str, values: &[(&str, f64)]) -> MetricSample {
+        MetricSample {
+            ts: parse_time(ts).unwrap(),
+            values: values.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_flattens_registry() {
+        let registry = Registry::new();
+        let trades = IntCounter::new("arb_trades_total", "trades").unwrap();
+        registry.register(Box::new(trades.clone())).unwrap();
+        let latency = HistogramVec::new(
+            HistogramOpts::new("arb_probe_seconds", "probe"),
+            &["endpoint"],
+        )
+        .unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+
+        trades.inc_by(3);
+        latency.with_label_values(&["public"]).observe(0.5);
+
+        let values = snapshot(&registry);
+        assert_eq!(values["arb_trades_total"], 3.0);
+        assert_eq!(values["arb_probe_seconds_count{endpoint=\"public\"}"], 1.0);
+        assert_eq!(values["arb_probe_seconds_sum{endpoint=\"public\"}"], 0.5);
+    }
+
+    #[test]
+    fn test_range_prune_and_csv() {
+        let recorder = temp_recorder();
+        recorder
+            .append(&sample("2026-01-01T00:00:00Z", &[("a", 1.0)]))
+            .unwrap();
+        recorder
+            .append(&sample(
+                "2026-01-09T00:00:00Z",
+                &[("a", 2.0), ("b{x=\"1\",y=\"2\"}", 5.0)],
+            ))
+            .unwrap();
+        recorder
+            .append(&sample("2026-01-10T00:00:00Z", &[("a", 3.0)]))
+            .unwrap();
+
+        let range = recorder
+            .read_range(Some(parse_time("2026-01-02").unwrap()), None)
+            .unwrap();
+        assert_eq!(range.len(), 2);
+
+        let csv = to_csv(&range, &[]);
+        assert_eq!(
+            csv,
+            "timestamp,a,\"b{x=\"\"1\"\",y=\"\"2\"\"}\"\n\
+             2026-01-09T00:00:00+00:00,2,5\n\
+             2026-01-10T00:00:00+00:00,3,\n"
+        );
+        assert_eq!(
+            to_csv(&range, &["a".to_string()]).lines().next(),
+            Some("timestamp,a")
+        );
+
+        // Retention is 7 days
+        assert_eq!(
+            recorder.prune(parse_time("2026-01-10").unwrap()).unwrap(),
+            1
+        );
+        assert_eq!(recorder.read_range(None, None).unwrap().len(), 2);
+        let _ = fs::remove_file(recorder.path());
+    }
+}
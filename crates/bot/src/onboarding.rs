@@ -0,0 +1,540 @@
+//! Onboarding Wizard
+//!
+//! `bot init` sets up a new deployment. It asks for the RPC endpoint, wallet
+//! key, alert webhooks, Jito and a few risk limits (or takes them as flags
+//! with `--yes`). It then writes `.env` from `.env.example` and
+//! `config/trading_config.json` from the shipped defaults. Finally it checks
+//! that everything they point at works and writes a readiness report.
+//!
+//! Webhooks are probed without posting anything, so running the wizard never
+//! sends an alert.
+
+use crate::config_manager::DynamicConfig;
+use crate::wallet::Wallet;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_arb_core::jito::JitoClient;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::signature::Signer;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const ENV_TEMPLATE: &str = include_str!("../../../.env.example");
+const CONFIG_TEMPLATE: &str = include_str!("../../../config/trading_config.json");
+
+const PUBLIC_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const DEFAULT_JITO_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// Timeout of each network check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Below this many SOL a live wallet cannot reliably pay fees
+const MIN_LIVE_BALANCE_SOL: f64 = 0.05;
+
+/// Everything the wizard asks for
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitAnswers {
+    pub rpc_url: String,
+    /// Base58 or JSON byte array; empty for a simulated wallet
+    pub private_key: String,
+    pub telegram_webhook: String,
+    pub discord_webhook: String,
+    pub use_jito: bool,
+    pub jito_url: String,
+    pub dry_run: bool,
+    /// Maximum position size in USD
+    pub max_position_size: u64,
+    /// Maximum daily loss in USD
+    pub max_daily_loss: f64,
+    /// Minimum profit in basis points
+    pub min_profit_bps: f64,
+}
+
+impl Default for InitAnswers {
+    fn default() -> Self {
+        Self {
+            rpc_url: PUBLIC_RPC_URL.to_string(),
+            private_key: String::new(),
+            telegram_webhook: String::new(),
+            discord_webhook: String::new(),
+            use_jito: false,
+            jito_url: DEFAULT_JITO_URL.to_string(),
+            dry_run: true,
+            max_position_size: 1000,
+            max_daily_loss: 500.0,
+            min_profit_bps: 50.0,
+        }
+    }
+}
+
+impl InitAnswers {
+    /// `.env` keys and values set from the answers
+    fn env_values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("SOLANA_RPC_URL", self.rpc_url.clone()),
+            ("PRIVATE_KEY", self.private_key.clone()),
+            ("TELEGRAM_WEBHOOK_URL", self.telegram_webhook.clone()),
+            ("DISCORD_WEBHOOK_URL", self.discord_webhook.clone()),
+            ("USE_JITO", self.use_jito.to_string()),
+            ("JITO_BLOCK_ENGINE_URL", self.jito_url.clone()),
+            ("DRY_RUN", self.dry_run.to_string()),
+        ]
+    }
+}
+
+/// Where the wizard writes and how it runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct InitOptions {
+    pub env_path: PathBuf,
+    pub config_path: PathBuf,
+    pub report_path: PathBuf,
+    /// Take the answers from flags and defaults without prompting
+    pub non_interactive: bool,
+    /// Overwrite an existing `.env` or trading config
+    pub force: bool,
+}
+
+impl Default for InitOptions {
+    fn default() -> Self {
+        Self {
+            env_path: PathBuf::from(".env"),
+            config_path: PathBuf::from("config/trading_config.json"),
+            report_path: PathBuf::from("readiness_report.json"),
+            non_interactive: false,
+            force: false,
+        }
+    }
+}
+
+/// Parse `bot init` arguments. Answers given as flags are not asked again.
+pub fn parse_args(args: &[String]) -> Result<(InitOptions, InitAnswers, Vec<&'static str>)> {
+    let mut options = InitOptions::default();
+    let mut answers = InitAnswers::default();
+    let mut given = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("{} needs a value", flag))
+        };
+        let parse_number = |v: String| -> Result<f64> {
+            v.parse().map_err(|_| anyhow!("{} needs a number, got '{}'", flag, v))
+        };
+        match flag.as_str() {
+            "--rpc-url" => answers.rpc_url = value()?,
+            "--private-key" => answers.private_key = value()?,
+            "--telegram-webhook" => answers.telegram_webhook = value()?,
+            "--discord-webhook" => answers.discord_webhook = value()?,
+            "--jito" => answers.use_jito = true,
+            "--jito-url" => {
+                answers.use_jito = true;
+                answers.jito_url = value()?;
+            }
+            "--live" => answers.dry_run = false,
+            "--max-position" => answers.max_position_size = parse_number(value()?)? as u64,
+            "--max-daily-loss" => answers.max_daily_loss = parse_number(value()?)?,
+            "--min-profit-bps" => answers.min_profit_bps = parse_number(value()?)?,
+            "--env" => options.env_path = PathBuf::from(value()?),
+            "--config" => options.config_path = PathBuf::from(value()?),
+            "--report" => options.report_path = PathBuf::from(value()?),
+            "--yes" | "-y" => options.non_interactive = true,
+            "--force" => options.force = true,
+            other => return Err(anyhow!("unknown argument '{}'", other)),
+        }
+        let answered = if flag == "--jito-url" { "--jito" } else { flag.as_str() };
+        if let Some(flag) = ANSWER_FLAGS.iter().find(|f| **f == answered) {
+            given.push(*flag);
+        }
+    }
+    Ok((options, answers, given))
+}
+
+const ANSWER_FLAGS: [&str; 9] = [
+    "--rpc-url",
+    "--private-key",
+    "--telegram-webhook",
+    "--discord-webhook",
+    "--jito",
+    "--live",
+    "--max-position",
+    "--max-daily-loss",
+    "--min-profit-bps",
+];
+
+/// `.env.example` with `values` filled in. Keys missing from the template
+/// are appended.
+pub fn render_env(template: &str, values: &[(&str, String)]) -> String {
+    let mut pending: Vec<&(&str, String)> = values.iter().collect();
+    let mut out = String::with_capacity(template.len());
+    for line in template.lines() {
+        let key = line.split_once('=').map(|(key, _)| key.trim());
+        match pending.iter().position(|(k, _)| Some(*k) == key) {
+            Some(i) => {
+                let (key, value) = pending.remove(i);
+                out.push_str(&format!("{}={}\n", key, value));
+            }
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    for (key, value) in pending {
+        out.push_str(&format!("{}={}\n", key, value));
+    }
+    out
+}
+
+/// The shipped trading config with the answers applied, validated
+pub fn render_config(answers: &InitAnswers) -> Result<DynamicConfig> {
+    let mut config: DynamicConfig =
+        serde_json::from_str(CONFIG_TEMPLATE).context("shipped trading config does not parse")?;
+    config.trading.max_position_size = answers.max_position_size;
+    config.trading.min_profit_bps = answers.min_profit_bps;
+    config.risk.max_daily_loss = answers.max_daily_loss;
+    config.alerts.telegram_enabled = !answers.telegram_webhook.is_empty();
+    config.alerts.discord_enabled = !answers.discord_webhook.is_empty();
+    config.validate().map_err(|e| anyhow!("invalid trading config: {}", e))?;
+    Ok(config)
+}
+
+/// Outcome of one readiness check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// What `bot init` found
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    pub generated_at: DateTime<Utc>,
+    pub env_path: PathBuf,
+    pub config_path: PathBuf,
+    pub dry_run: bool,
+    pub ready: bool,
+    pub checks: Vec<Check>,
+}
+
+impl ReadinessReport {
+    fn new(options: &InitOptions, answers: &InitAnswers, checks: Vec<Check>) -> Self {
+        Self {
+            generated_at: Utc::now(),
+            env_path: options.env_path.clone(),
+            config_path: options.config_path.clone(),
+            dry_run: answers.dry_run,
+            ready: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+        }
+    }
+
+    /// One line per check, for the terminal
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for check in &self.checks {
+            let mark = match check.status {
+                CheckStatus::Pass => "ok  ",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "FAIL",
+                CheckStatus::Skip => "skip",
+            };
+            out.push_str(&format!("{} {:<10} {}\n", mark, check.name, check.detail));
+        }
+        out.push_str(match (self.ready, self.dry_run) {
+            (false, _) => "Not ready: fix the failed checks and run `bot init --force` again\n",
+            (true, true) => "Ready for dry run. Pass --live once you want to trade real funds\n",
+            (true, false) => "Ready for live trading\n",
+        });
+        out
+    }
+}
+
+/// Check the RPC endpoint, wallet, alert webhooks and Jito the answers
+/// point at
+pub async fn check_environment(answers: &InitAnswers) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let rpc = RpcClient::new_with_timeout(answers.rpc_url.clone(), CHECK_TIMEOUT);
+
+    let rpc_ok = match rpc.get_version().await {
+        Ok(version) if answers.rpc_url == PUBLIC_RPC_URL && !answers.dry_run => {
+            checks.push(Check::new(
+                "rpc",
+                CheckStatus::Warn,
+                format!(
+                    "solana-core {} on the rate-limited public endpoint; use a paid RPC for live trading",
+                    version.solana_core
+                ),
+            ));
+            true
+        }
+        Ok(version) => {
+            checks.push(Check::new(
+                "rpc",
+                CheckStatus::Pass,
+                format!("{} (solana-core {})", answers.rpc_url, version.solana_core),
+            ));
+            true
+        }
+        Err(e) => {
+            checks.push(Check::new("rpc", CheckStatus::Fail, format!("{}: {}", answers.rpc_url, e)));
+            false
+        }
+    };
+
+    checks.push(if answers.private_key.is_empty() {
+        let status = if answers.dry_run { CheckStatus::Warn } else { CheckStatus::Fail };
+        Check::new("wallet", status, "no PRIVATE_KEY; the bot runs with a simulated wallet")
+    } else {
+        match Wallet::parse_keypair(&answers.private_key) {
+            Ok(keypair) if rpc_ok => match rpc.get_balance(&keypair.pubkey()).await {
+                Ok(lamports) => {
+                    let sol = lamports as f64 / 1e9;
+                    let status = if !answers.dry_run && sol < MIN_LIVE_BALANCE_SOL {
+                        CheckStatus::Warn
+                    } else {
+                        CheckStatus::Pass
+                    };
+                    Check::new("wallet", status, format!("{} holds {:.4} SOL", keypair.pubkey(), sol))
+                }
+                Err(e) => Check::new(
+                    "wallet",
+                    CheckStatus::Warn,
+                    format!("{} (balance unavailable: {})", keypair.pubkey(), e),
+                ),
+            },
+            Ok(keypair) => Check::new(
+                "wallet",
+                CheckStatus::Warn,
+                format!("{} (balance not checked, RPC is down)", keypair.pubkey()),
+            ),
+            Err(e) => Check::new("wallet", CheckStatus::Fail, format!("PRIVATE_KEY does not parse: {}", e)),
+        }
+    });
+
+    let http = reqwest::Client::builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    checks.push(probe_webhook(&http, "telegram", &answers.telegram_webhook).await);
+    checks.push(probe_webhook(&http, "discord", &answers.discord_webhook).await);
+    if answers.telegram_webhook.is_empty() && answers.discord_webhook.is_empty() {
+        checks.push(Check::new(
+            "alerts",
+            CheckStatus::Warn,
+            "no alert channel configured; critical alerts only reach the logs",
+        ));
+    }
+
+    checks.push(if !answers.use_jito {
+        Check::new("jito", CheckStatus::Skip, "USE_JITO=false")
+    } else {
+        match JitoClient::new(&answers.jito_url, 0).health_check().await {
+            Ok(true) => Check::new("jito", CheckStatus::Pass, answers.jito_url.clone()),
+            _ => Check::new("jito", CheckStatus::Fail, format!("{} is unreachable", answers.jito_url)),
+        }
+    });
+
+    checks
+}
+
+/// GET a webhook URL without posting to it. Telegram and Discord both
+/// answer a GET on a valid URL with something other than 401/403/404.
+async fn probe_webhook(http: &reqwest::Client, name: &'static str, url: &str) -> Check {
+    if url.is_empty() {
+        return Check::new(name, CheckStatus::Skip, "not configured");
+    }
+    match http.get(url).send().await {
+        Ok(resp) => match resp.status().as_u16() {
+            401 | 403 | 404 => Check::new(
+                name,
+                CheckStatus::Fail,
+                format!("webhook rejected (HTTP {}); check the token", resp.status()),
+            ),
+            code if code >= 500 => {
+                Check::new(name, CheckStatus::Warn, format!("webhook host answered HTTP {}", code))
+            }
+            _ => Check::new(name, CheckStatus::Pass, "webhook reachable"),
+        },
+        Err(e) => Check::new(name, CheckStatus::Fail, format!("unreachable: {}", e)),
+    }
+}
+
+/// Ask on stdin, keeping `current` on an empty answer
+fn prompt(question: &str, current: &str) -> Result<String> {
+    let shown = if current.is_empty() { "none" } else { current };
+    print!("{} [{}]: ", question, shown);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    let answer = line.trim();
+    Ok(if answer.is_empty() { current.to_string() } else { answer.to_string() })
+}
+
+fn prompt_bool(question: &str, current: bool) -> Result<bool> {
+    let answer = prompt(&format!("{} (y/n)", question), if current { "y" } else { "n" })?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes" | "true"))
+}
+
+fn prompt_number(question: &str, current: f64) -> Result<f64> {
+    loop {
+        match prompt(question, &current.to_string())?.parse() {
+            Ok(n) => return Ok(n),
+            Err(_) => println!("Please enter a number"),
+        }
+    }
+}
+
+/// Ask for every answer not already given as a flag
+fn ask(answers: &mut InitAnswers, given: &[&str]) -> Result<()> {
+    let asked = |flag: &str| !given.contains(&flag);
+    if asked("--rpc-url") {
+        answers.rpc_url = prompt("Solana RPC URL", &answers.rpc_url)?;
+    }
+    if asked("--live") {
+        answers.dry_run = !prompt_bool("Trade live with real funds", !answers.dry_run)?;
+    }
+    if asked("--private-key") {
+        answers.private_key = prompt(
+            "Wallet private key, base58 or JSON byte array (empty for a simulated wallet)",
+            &answers.private_key,
+        )?;
+    }
+    if asked("--telegram-webhook") {
+        answers.telegram_webhook = prompt("Telegram webhook URL", &answers.telegram_webhook)?;
+    }
+    if asked("--discord-webhook") {
+        answers.discord_webhook = prompt("Discord webhook URL", &answers.discord_webhook)?;
+    }
+    if asked("--jito") {
+        answers.use_jito = prompt_bool("Send transactions through Jito", answers.use_jito)?;
+        if answers.use_jito {
+            answers.jito_url = prompt("Jito block engine URL", &answers.jito_url)?;
+        }
+    }
+    if asked("--max-position") {
+        answers.max_position_size = prompt_number("Max position size (USD)", answers.max_position_size as f64)? as u64;
+    }
+    if asked("--max-daily-loss") {
+        answers.max_daily_loss = prompt_number("Max daily loss (USD)", answers.max_daily_loss)?;
+    }
+    if asked("--min-profit-bps") {
+        answers.min_profit_bps = prompt_number("Min profit (bps)", answers.min_profit_bps)?;
+    }
+    Ok(())
+}
+
+fn write_env(path: &Path, content: &str) -> Result<()> {
+    std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))?;
+    // It holds the private key
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// `bot init [flags]`: write `.env` and the trading config, check the
+/// environment and write the readiness report. Returns whether every check
+/// passed or only warned.
+pub async fn init_cli(args: &[String]) -> Result<bool> {
+    let (options, mut answers, given) = parse_args(args)?;
+    if !options.force {
+        for path in [&options.env_path, &options.config_path] {
+            if path.exists() {
+                bail!("{} already exists; pass --force to overwrite it", path.display());
+            }
+        }
+    }
+    if !options.non_interactive {
+        ask(&mut answers, &given)?;
+    }
+
+    let config = render_config(&answers)?;
+    write_env(&options.env_path, &render_env(ENV_TEMPLATE, &answers.env_values()))?;
+    if let Some(dir) = options.config_path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&options.config_path, serde_json::to_string_pretty(&config)?)
+        .with_context(|| format!("failed to write {}", options.config_path.display()))?;
+    println!("Wrote {} and {}", options.env_path.display(), options.config_path.display());
+
+    let report = ReadinessReport::new(&options, &answers, check_environment(&answers).await);
+    std::fs::write(&options.report_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {}", options.report_path.display()))?;
+    print!("{}", report.render());
+    println!("Report saved to {}", options.report_path.display());
+    Ok(report.ready)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_render_env_and_config() {
+        let args: Vec<String> = [
+            "--yes",
+            "--rpc-url",
+            "https://rpc.example.com",
+            "--discord-webhook",
+            "https://discord.example.com/hook",
+            "--jito-url",
+            "https://jito.example.com",
+            "--max-daily-loss",
+            "250",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let (options, answers, given) = parse_args(&args).unwrap();
+        assert!(options.non_interactive && !options.force);
+        assert_eq!(given, vec!["--rpc-url", "--discord-webhook", "--jito", "--max-daily-loss"]);
+        assert!(answers.use_jito && answers.dry_run);
+        assert!(parse_args(&["--max-position".to_string(), "lots".to_string()]).is_err());
+        assert!(parse_args(&["--rpc-url".to_string()]).is_err());
+
+        let env = render_env(ENV_TEMPLATE, &answers.env_values());
+        assert!(env.contains("\nSOLANA_RPC_URL=https://rpc.example.com\n"));
+        assert!(env.contains("\nUSE_JITO=true\n"));
+        assert!(env.contains("\nDRY_RUN=true\n"));
+        // Comments and untouched settings survive
+        assert!(env.contains("# WALLET"));
+        assert!(env.contains("\nRPC_COMMITMENT=confirmed\n"));
+        let appended = render_env("A=1\n", &[("B", "2".to_string())]);
+        assert_eq!(appended, "A=1\nB=2\n");
+
+        let config = render_config(&answers).unwrap();
+        assert_eq!(config.risk.max_daily_loss, 250.0);
+        assert!(config.alerts.discord_enabled && !config.alerts.telegram_enabled);
+        let zero = InitAnswers {
+            max_position_size: 0,
+            ..InitAnswers::default()
+        };
+        assert!(render_config(&zero).is_err());
+    }
+}
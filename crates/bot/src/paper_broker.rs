@@ -0,0 +1,198 @@
+//! Paper Fills
+//!
+//! Dry-run P&L used to assume the whole size fills at the detected prices.
+//! That holds roughly for AMM pools, whose quotes already price in impact,
+//! but not for an order book: only the displayed size is available at the top
+//! level, and part of it is taken by orders ahead of ours. The paper broker
+//! fills each book level up to its displayed size minus that queue share,
+//! walks deeper levels for the remainder, and sizes the round trip to what
+//! both legs can actually fill.
+
+use rust_decimal::Decimal;
+use solana_arb_core::ArbitrageOpportunity;
+use solana_arb_dex_plugins::phoenix::BookLevel;
+
+pub use solana_arb_dex_plugins::phoenix::OrderBook;
+
+/// Result of filling one leg against a book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fill {
+    /// Base units filled
+    pub base: Decimal,
+    /// Quote paid (buys) or received (sells)
+    pub quote: Decimal,
+}
+
+/// Outcome of a paper round trip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaperTrade {
+    /// Quote actually spent on the buy leg
+    pub filled_size: Decimal,
+    /// Net profit after fees
+    pub profit: Decimal,
+}
+
+/// Fills dry-run trades against order book snapshots
+#[derive(Debug, Clone)]
+pub struct PaperBroker {
+    /// Share of each level's displayed size assumed taken by orders ahead of ours
+    queue_ahead: Decimal,
+}
+
+impl PaperBroker {
+    /// `queue_ahead_pct` is clamped to 0–100
+    pub fn new(queue_ahead_pct: Decimal) -> Self {
+        let hundred = Decimal::from(100);
+        Self {
+            queue_ahead: queue_ahead_pct.clamp(Decimal::ZERO, hundred) / hundred,
+        }
+    }
+
+    fn take(&self, levels: &[BookLevel], base: Decimal) -> Fill {
+        let mut fill = Fill::default();
+        for level in levels {
+            let remaining = base - fill.base;
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+            let available = level.size * (Decimal::ONE - self.queue_ahead);
+            let taken = remaining.min(available);
+            fill.base += taken;
+            fill.quote += taken * level.price;
+        }
+        fill
+    }
+
+    /// Buy up to `base` units from the asks
+    pub fn buy(&self, book: &OrderBook, base: Decimal) -> Fill {
+        self.take(&book.asks, base)
+    }
+
+    /// Sell up to `base` units into the bids
+    pub fn sell(&self, book: &OrderBook, base: Decimal) -> Fill {
+        self.take(&book.bids, base)
+    }
+
+    /// Paper round trip of `size` (quote) for `opp`. A leg with a book is
+    /// filled against it; a leg without one (an AMM) fills in full at the
+    /// detected price. Fees are charged at the opportunity's fee rate on the
+    /// quote spent.
+    pub fn round_trip(
+        &self,
+        opp: &ArbitrageOpportunity,
+        size: Decimal,
+        buy_book: Option<&OrderBook>,
+        sell_book: Option<&OrderBook>,
+    ) -> PaperTrade {
+        if opp.buy_price.is_zero() {
+            return PaperTrade {
+                filled_size: Decimal::ZERO,
+                profit: Decimal::ZERO,
+            };
+        }
+
+        let buy_leg = |base: Decimal| match buy_book {
+            Some(book) => self.buy(book, base),
+            None => Fill {
+                base,
+                quote: base * opp.buy_price,
+            },
+        };
+        let sell_leg = |base: Decimal| match sell_book {
+            Some(book) => self.sell(book, base),
+            None => Fill {
+                base,
+                quote: base * opp.sell_price,
+            },
+        };
+
+        let mut bought = buy_leg(size / opp.buy_price);
+        let mut sold = sell_leg(bought.base);
+        // Only buy what the sell side can absorb
+        if sold.base < bought.base {
+            bought = buy_leg(sold.base);
+            sold = sell_leg(bought.base);
+        }
+
+        let fee_rate = (opp.gross_profit_pct - opp.net_profit_pct) / Decimal::from(100);
+        PaperTrade {
+            filled_size: bought.quote,
+            profit: sold.quote - bought.quote - bought.quote * fee_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use chrono::Utc;
+    use solana_arb_core::{DexType, TokenPair, Uuid};
+
+    fn level(price: i64, size: i64) -> BookLevel {
+        BookLevel {
+            price: Decimal::from(price),
+            size: Decimal::from(size),
+        }
+    }
+
+    fn opportunity(buy_price: i64, sell_price: i64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Phoenix,
+            sell_dex: DexType::Raydium,
+            buy_price: Decimal::from(buy_price),
+            sell_price: Decimal::from(sell_price),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::ONE,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        }
+    }
+
+    #[test]
+    fn test_fill_walks_book_behind_queue() {
+        let book = OrderBook {
+            bids: vec![],
+            asks: vec![level(100, 4), level(101, 10)],
+        };
+
+        // Full displayed size at the top, remainder from the next level
+        let fill = PaperBroker::new(Decimal::ZERO).buy(&book, Decimal::from(6));
+        assert_eq!(fill.base, Decimal::from(6));
+        assert_eq!(fill.quote, Decimal::from(602));
+
+        // Half of each level is queued ahead of us
+        let broker = PaperBroker::new(Decimal::from(50));
+        let fill = broker.buy(&book, Decimal::from(6));
+        assert_eq!(fill.quote, Decimal::from(2 * 100 + 4 * 101));
+        assert_eq!(broker.buy(&book, Decimal::from(20)).base, Decimal::from(7));
+    }
+
+    #[test]
+    fn test_round_trip_limited_by_book_depth() {
+        let broker = PaperBroker::new(Decimal::ZERO);
+        let opp = opportunity(100, 102);
+        let book = OrderBook {
+            bids: vec![],
+            asks: vec![level(100, 2), level(101, 1)],
+        };
+
+        // $1000 wants 10 SOL but the book only offers 3
+        let trade = broker.round_trip(&opp, Decimal::from(1000), Some(&book), None);
+        assert_eq!(trade.filled_size, Decimal::from(301));
+        assert_eq!(trade.profit, Decimal::from(3 * 102 - 301));
+
+        // Without a book the leg fills in full at the detected price
+        let trade = broker.round_trip(&opp, Decimal::from(1000), None, None);
+        assert_eq!(trade.filled_size, Decimal::from(1000));
+        assert_eq!(trade.profit, Decimal::from(20));
+    }
+}
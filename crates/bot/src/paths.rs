@@ -0,0 +1,159 @@
+//! Multi-Hop Path Execution
+//!
+//! The path finder turns every quoted market into two edges and finds cycles
+//! (e.g. USDC → SOL → RAY → USDC) that return more of the start token than
+//! they spend. A cycle is traded as one transaction: each hop is a Jupiter
+//! swap fed with the previous hop's minimum output, so either every hop lands
+//! or none does. The start token is taken from the wallet or, when flash loans
+//! are on and the builder has a reserve for it, borrowed and repaid in the
+//! same transaction.
+
+use rust_decimal::Decimal;
+use solana_arb_core::pathfinding::TradingPath;
+
+use crate::batching::MAX_TX_COMPUTE_UNITS;
+
+/// Path execution limits
+#[derive(Debug, Clone)]
+pub struct PathConfig {
+    /// Largest size (USD) a path is traded at
+    pub max_size_usd: Decimal,
+    /// Compute units budgeted for each hop
+    pub compute_units_per_hop: u32,
+}
+
+impl Default for PathConfig {
+    fn default() -> Self {
+        Self {
+            max_size_usd: Decimal::from(100),
+            compute_units_per_hop: 300_000,
+        }
+    }
+}
+
+impl PathConfig {
+    /// Compute unit limit requested for a path of `hops` swaps
+    pub fn compute_unit_limit(&self, hops: usize) -> u32 {
+        (self.compute_units_per_hop.saturating_mul(hops as u32)).min(MAX_TX_COMPUTE_UNITS)
+    }
+}
+
+/// Result of submitting (or building) a path transaction
+#[derive(Debug, Clone)]
+pub struct PathExecution {
+    pub signature: String,
+    pub hops: usize,
+    /// Transaction fee in lamports
+    pub fee_lamports: u64,
+    /// Net payer balance change from the pre-submission simulation, in the
+    /// start token (only when submitted)
+    pub simulated_profit: Option<Decimal>,
+}
+
+/// The token a path starts and ends with
+pub fn start_token(path: &TradingPath) -> Option<&str> {
+    path.edges.first().map(|edge| edge.from_token.as_str())
+}
+
+/// Tokens in visiting order, e.g. `USDC→SOL→RAY→USDC`
+pub fn path_label(path: &TradingPath) -> String {
+    let mut tokens: Vec<&str> = path.edges.iter().map(|e| e.from_token.as_str()).collect();
+    tokens.extend(path.edges.last().map(|e| e.to_token.as_str()));
+    tokens.join("→")
+}
+
+/// Price of every token the path visits, in the start token, from the edge
+/// rates: a token reached at a cumulative rate `r` is worth `1 / r`
+pub fn hop_prices(path: &TradingPath) -> Vec<(String, Decimal)> {
+    let Some(start) = start_token(path) else {
+        return Vec::new();
+    };
+    let mut prices = vec![(start.to_string(), Decimal::ONE)];
+    let mut rate = Decimal::ONE;
+    for edge in &path.edges {
+        rate *= edge.rate;
+        if rate.is_zero() {
+            break;
+        }
+        if !prices.iter().any(|(token, _)| *token == edge.to_token) {
+            prices.push((edge.to_token.clone(), Decimal::ONE / rate));
+        }
+    }
+    prices
+}
+
+/// The most profitable path at or above `min_profit_pct` whose start token
+/// `can_start` accepts, e.g. one the wallet can price and fund
+pub fn select_path(
+    paths: &[TradingPath],
+    min_profit_pct: Decimal,
+    can_start: impl Fn(&str) -> bool,
+) -> Option<&TradingPath> {
+    paths
+        .iter()
+        .filter(|path| path.edges.len() >= 2 && path.profit_percentage() >= min_profit_pct)
+        .filter(|path| start_token(path).is_some_and(&can_start))
+        .max_by_key(|path| path.profit_ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::pathfinding::TradingEdge;
+    use solana_arb_core::DexType;
+
+    fn edge(from: &str, to: &str, rate: Decimal) -> TradingEdge {
+        TradingEdge {
+            from_token: from.to_string(),
+            to_token: to.to_string(),
+            dex: DexType::Orca,
+            rate,
+            liquidity: Decimal::from(100_000),
+            fee: Decimal::ZERO,
+        }
+    }
+
+    fn path(edges: Vec<TradingEdge>) -> TradingPath {
+        let mut path = TradingPath {
+            edges,
+            profit_ratio: Decimal::ONE,
+            min_liquidity: Decimal::from(100_000),
+        };
+        path.profit_ratio = path.calculate_profit_ratio();
+        path
+    }
+
+    #[test]
+    fn test_path_prices_and_selection() {
+        // 1 USDC → 0.01 SOL → 0.5 RAY → 1.01 USDC
+        let triangle = path(vec![
+            edge("USDC", "SOL", Decimal::new(1, 2)),
+            edge("SOL", "RAY", Decimal::from(50)),
+            edge("RAY", "USDC", Decimal::new(202, 2)),
+        ]);
+        assert_eq!(path_label(&triangle), "USDC→SOL→RAY→USDC");
+        assert_eq!(
+            hop_prices(&triangle),
+            vec![
+                ("USDC".to_string(), Decimal::ONE),
+                ("SOL".to_string(), Decimal::from(100)),
+                ("RAY".to_string(), Decimal::from(2)),
+            ]
+        );
+
+        // 0.5% through BONK
+        let bonk = path(vec![
+            edge("SOL", "BONK", Decimal::from(1_000_000)),
+            edge("BONK", "SOL", Decimal::new(1005, 9)),
+        ]);
+        let paths = vec![bonk, triangle];
+        let best = select_path(&paths, Decimal::new(5, 1), |_| true).unwrap();
+        assert_eq!(start_token(best), Some("USDC"));
+        let best = select_path(&paths, Decimal::new(5, 1), |token| token == "SOL").unwrap();
+        assert_eq!(path_label(best), "SOL→BONK→SOL");
+        assert!(select_path(&paths, Decimal::from(2), |_| true).is_none());
+
+        assert_eq!(PathConfig::default().compute_unit_limit(3), 900_000);
+        assert_eq!(PathConfig::default().compute_unit_limit(5), MAX_TX_COMPUTE_UNITS);
+    }
+}
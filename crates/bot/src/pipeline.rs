@@ -0,0 +1,149 @@
+//! Trading Pipeline
+//!
+//! Queues joining the price source, detection and execution stages of the
+//! trading loop. Prices and the detector live in the market state, behind
+//! their own lock, so applying prices does not wait for execution.
+
+use solana_arb_core::events::TradingEvent;
+use solana_arb_core::pathfinding::TradingPath;
+use solana_arb_core::{ArbitrageOpportunity, PriceData, TokenPair};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Prices of one fetch
+#[derive(Debug, Clone)]
+pub struct PriceBatch {
+    pub prices: Vec<PriceData>,
+    /// Pairs the fetch asked for; none for streamed updates
+    pub pairs: Vec<TokenPair>,
+    pub fetch_started: Instant,
+}
+
+/// What detection reacts to
+#[derive(Debug, Clone)]
+pub enum PipelineEvent {
+    Prices(PriceBatch),
+    /// The dynamic config was reloaded with these execution changes
+    ConfigReloaded(Vec<String>),
+    Risk(TradingEvent),
+}
+
+impl PipelineEvent {
+    /// The pipeline's events among the bus's, if `event` is one
+    pub fn from_bus(event: TradingEvent) -> Option<Self> {
+        match event {
+            TradingEvent::ConfigReloaded { changes } => Some(Self::ConfigReloaded(changes)),
+            TradingEvent::CircuitBreakerStateChanged { .. }
+            | TradingEvent::RiskLimitBreached { .. }
+            | TradingEvent::EmergencyStop { .. } => Some(Self::Risk(event)),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `event` invalidates candidates detected before it
+pub fn is_halting(event: &TradingEvent) -> bool {
+    match event {
+        TradingEvent::CircuitBreakerStateChanged { new_state, .. } => new_state == "Open",
+        TradingEvent::RiskLimitBreached { .. } | TradingEvent::EmergencyStop { .. } => true,
+        _ => false,
+    }
+}
+
+/// Opportunities of one detection pass
+#[derive(Debug, Clone)]
+pub struct Candidates {
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    /// Profitable multi-hop paths, when path execution is on
+    pub paths: Vec<TradingPath>,
+    /// Halt generation they were detected in
+    pub generation: u64,
+    pub detected_at: Instant,
+}
+
+/// Counts halting risk events, so execution can tell candidates detected
+/// before the latest one
+#[derive(Debug, Clone, Default)]
+pub struct HaltGeneration(Arc<AtomicU64>);
+
+impl HaltGeneration {
+    pub fn current(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub fn advance(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn is_current(&self, candidates: &Candidates) -> bool {
+        candidates.generation == self.current()
+    }
+}
+
+/// The newest batch waiting behind `first`, and how many it superseded
+pub fn latest(first: Candidates, queue: &mut mpsc::Receiver<Candidates>) -> (Candidates, u64) {
+    let mut newest = first;
+    let mut superseded = 0;
+    while let Ok(next) = queue.try_recv() {
+        newest = next;
+        superseded += 1;
+    }
+    (newest, superseded)
+}
+
+/// Items waiting in the queue `sender` feeds
+pub fn depth<T>(sender: &mpsc::Sender<T>) -> usize {
+    sender.max_capacity() - sender.capacity()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(generation: u64) -> Candidates {
+        Candidates {
+            opportunities: Vec::new(),
+            paths: Vec::new(),
+            generation,
+            detected_at: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execution_takes_newest_batch_of_current_generation() {
+        let (tx, mut rx) = mpsc::channel(4);
+        for generation in [0, 0, 1] {
+            tx.send(candidates(generation)).await.unwrap();
+        }
+        assert_eq!(depth(&tx), 3);
+
+        let first = rx.recv().await.unwrap();
+        let (newest, superseded) = latest(first, &mut rx);
+        assert_eq!((newest.generation, superseded), (1, 2));
+        assert_eq!(depth(&tx), 0);
+
+        let halts = HaltGeneration::default();
+        assert!(!halts.is_current(&newest));
+        halts.advance();
+        assert!(halts.is_current(&newest));
+
+        let open = TradingEvent::CircuitBreakerStateChanged {
+            old_state: "Closed".into(),
+            new_state: "Open".into(),
+        };
+        let closed = TradingEvent::CircuitBreakerStateChanged {
+            old_state: "Open".into(),
+            new_state: "Closed".into(),
+        };
+        assert!(is_halting(&open));
+        assert!(!is_halting(&closed));
+        assert!(matches!(PipelineEvent::from_bus(closed), Some(PipelineEvent::Risk(_))));
+        assert!(matches!(
+            PipelineEvent::from_bus(TradingEvent::ConfigReloaded { changes: vec!["slippage".into()] }),
+            Some(PipelineEvent::ConfigReloaded(changes)) if changes == ["slippage"]
+        ));
+        assert!(PipelineEvent::from_bus(TradingEvent::SystemStarted { mode: "dry".into() }).is_none());
+    }
+}
@@ -0,0 +1,433 @@
+//! Trade Loss Post-Mortems
+//!
+//! When a live trade loses more than `POSTMORTEM_LOSS_USD` after fees, the
+//! evidence for why is spread over the trade trace, the RPC node, the price
+//! archive and the event bus, and some of it ages out within minutes. Once
+//! `POSTMORTEM_WINDOW_SECS` have passed after the trade, a post-mortem
+//! collects it into one zip in `POSTMORTEM_DIR`, and the critical alert for
+//! the loss links it:
+//!
+//! - `summary.txt`: the loss and what each stage expected
+//! - `opportunity.json`: the opportunity as detected
+//! - `trace.json`: plan, quote, execution and on-chain outcome, with diffs
+//! - `simulation_logs.txt`: program logs of the pre-submission simulation
+//! - `transaction.json`: the landed transaction and its meta
+//! - `price_ticks.jsonl`: the pair's archived prices within the window, or
+//!   the detector's latest prices when prices are not archived
+//! - `events.jsonl`: bus events within the window
+
+use crate::replay::{self, PriceSnapshots, SnapshotTick};
+use crate::trade_traces::{TradeDiff, TradeTraces};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use solana_arb_core::config::Config;
+use solana_arb_core::events::{Subscription, TradingEvent};
+use solana_arb_core::memory::MemoryUsage;
+use solana_arb_core::{ArbitrageOpportunity, PriceData, TokenPair, TradeResult};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Number of bus events kept for post-mortems
+const EVENT_CAPACITY: usize = 1000;
+
+/// Attempts at fetching the landed transaction from the RPC node
+const TRANSACTION_FETCH_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct PostmortemConfig {
+    /// Loss (USD, after fees) beyond which a bundle is assembled
+    pub loss_threshold_usd: Decimal,
+    pub dir: PathBuf,
+    /// Prices and events this long either side of the trade are included
+    pub window: Duration,
+}
+
+impl PostmortemConfig {
+    /// `None` when post-mortems are disabled (`POSTMORTEM_LOSS_USD=0`)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let loss_threshold_usd = Decimal::from_f64(config.postmortem_loss_usd)
+            .filter(|threshold| *threshold > Decimal::ZERO)?;
+        Some(Self {
+            loss_threshold_usd,
+            dir: PathBuf::from(&config.postmortem_dir),
+            window: Duration::seconds(config.postmortem_window_secs as i64),
+        })
+    }
+}
+
+/// A bus event and when it was seen
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordedEvent {
+    pub at: DateTime<Utc>,
+    pub event: TradingEvent,
+}
+
+/// The most recent bus events, price updates excepted (the archive has those)
+#[derive(Debug, Default)]
+pub struct RecentEvents {
+    events: Mutex<VecDeque<RecordedEvent>>,
+}
+
+impl RecentEvents {
+    pub fn record(&self, event: TradingEvent) {
+        if matches!(event, TradingEvent::PriceUpdate { .. }) {
+            return;
+        }
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        if events.len() >= EVENT_CAPACITY {
+            events.pop_front();
+        }
+        events.push_back(RecordedEvent {
+            at: Utc::now(),
+            event,
+        });
+    }
+
+    /// Events seen from `from` up to and including `to`
+    pub fn between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<RecordedEvent> {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        events
+            .iter()
+            .filter(|e| e.at >= from && e.at <= to)
+            .cloned()
+            .collect()
+    }
+
+    /// Record events from `subscription` until the bus closes
+    pub async fn follow(self: Arc<Self>, mut subscription: Subscription) {
+        while let Some(event) = subscription.recv().await {
+            self.record(event);
+        }
+    }
+
+    /// Approximate memory held by the kept events (their strings excluded)
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        MemoryUsage::of::<RecordedEvent>(events.len(), 0)
+    }
+}
+
+/// Everything known about one losing trade
+#[derive(Debug, Clone, Serialize)]
+pub struct PostmortemBundle {
+    pub opportunity: ArbitrageOpportunity,
+    pub size_usd: Decimal,
+    /// Profit after fees; negative
+    pub profit_loss_usd: Decimal,
+    pub signature: Option<String>,
+    pub executed_at: DateTime<Utc>,
+    pub trace: Option<TradeDiff>,
+    /// `getTransaction` result, `None` if the node did not return it
+    pub transaction: Option<serde_json::Value>,
+    pub price_ticks: Vec<SnapshotTick>,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl PostmortemBundle {
+    pub fn file_name(&self) -> String {
+        format!(
+            "postmortem-{}-{}-{}.zip",
+            self.executed_at.format("%Y%m%dT%H%M%SZ"),
+            self.opportunity.pair.symbol().replace('/', "-"),
+            &self.opportunity.id.simple().to_string()[..8]
+        )
+    }
+
+    fn summary(&self) -> String {
+        let opp = &self.opportunity;
+        let mut lines = vec![
+            format!("Opportunity: {}", opp.id),
+            format!(
+                "Route: {} buy on {} at {}, sell on {} at {}",
+                opp.pair,
+                opp.buy_dex.display_name(),
+                opp.buy_price,
+                opp.sell_dex.display_name(),
+                opp.sell_price
+            ),
+            format!("Size: ${}", self.size_usd.round_dp(2)),
+            format!("Net spread detected: {}%", opp.net_profit_pct.round_dp(4)),
+            format!("Profit after fees: ${}", self.profit_loss_usd.round_dp(4)),
+            format!("Signature: {}", self.signature.as_deref().unwrap_or("none")),
+            format!("Executed at: {}", self.executed_at.to_rfc3339()),
+        ];
+        if let Some(diff) = &self.trace {
+            let usd = |value: Option<Decimal>| {
+                value.map_or_else(|| "n/a".to_string(), |v| format!("${}", v.round_dp(4)))
+            };
+            lines.push(format!("Expected profit: {}", usd(diff.expected_profit_usd)));
+            lines.push(format!("Simulated vs expected: {}", usd(diff.simulated_vs_expected_usd)));
+            lines.push(format!("Realized vs expected: {}", usd(diff.realized_vs_expected_usd)));
+            lines.push(format!("Realized vs simulated: {}", usd(diff.realized_vs_simulated_usd)));
+            if let Some(slippage) = diff.quote_price_slippage_pct {
+                lines.push(format!("Quote price vs detected: {}%", slippage));
+            }
+            if let Some(onchain) = &diff.trace.onchain {
+                lines.push(format!("On-chain: {}", onchain.status));
+            }
+        }
+        lines.push(format!(
+            "Price ticks: {}, events: {}, transaction: {}",
+            self.price_ticks.len(),
+            self.events.len(),
+            if self.transaction.is_some() { "fetched" } else { "unavailable" }
+        ));
+        lines.join("\n") + "\n"
+    }
+
+    /// Write the bundle as a zip in `dir`, returning its path
+    pub fn write_zip(&self, dir: &Path) -> Result<PathBuf> {
+        let simulation_logs = self
+            .trace
+            .as_ref()
+            .map(|diff| diff.trace.simulation_logs.join("\n"))
+            .unwrap_or_default();
+        let files = [
+            ("summary.txt", self.summary()),
+            ("opportunity.json", serde_json::to_string_pretty(&self.opportunity)?),
+            ("trace.json", serde_json::to_string_pretty(&self.trace)?),
+            ("simulation_logs.txt", simulation_logs),
+            ("transaction.json", serde_json::to_string_pretty(&self.transaction)?),
+            ("price_ticks.jsonl", json_lines(&self.price_ticks)?),
+            ("events.jsonl", json_lines(&self.events)?),
+        ];
+
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path)?);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        for (name, contents) in files {
+            zip.start_file(name, options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+        zip.finish()?;
+        Ok(path)
+    }
+}
+
+fn json_lines<T: Serialize>(items: &[T]) -> serde_json::Result<String> {
+    items
+        .iter()
+        .map(|item| serde_json::to_string(item).map(|line| line + "\n"))
+        .collect()
+}
+
+/// Archived ticks from `from` to `to` holding `pair`'s prices, reduced to
+/// those prices. Days without an archive file are skipped.
+pub fn archived_ticks(
+    snapshots: &PriceSnapshots,
+    pair: &TokenPair,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<SnapshotTick> {
+    from.date_naive()
+        .iter_days()
+        .take_while(|date| *date <= to.date_naive())
+        .filter_map(|date| replay::load_ticks(&snapshots.path_for(date), Some(from), Some(to)).ok())
+        .flatten()
+        .filter_map(|tick| {
+            let prices: Vec<PriceData> =
+                tick.prices.into_iter().filter(|p| &p.pair == pair).collect();
+            (!prices.is_empty()).then_some(SnapshotTick { at: tick.at, prices })
+        })
+        .collect()
+}
+
+/// The landed transaction and its meta. A just-confirmed transaction may
+/// not be served yet, so this is retried with a growing delay.
+pub async fn fetch_transaction(rpc_url: &str, signature: &str) -> Option<serde_json::Value> {
+    let client = reqwest::Client::new();
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getTransaction",
+        "params": [signature, {
+            "encoding": "json",
+            "commitment": "confirmed",
+            "maxSupportedTransactionVersion": 0,
+        }],
+    });
+    for attempt in 1..=TRANSACTION_FETCH_ATTEMPTS {
+        let response = match client.post(rpc_url).json(&request).send().await {
+            Ok(response) => response.json::<serde_json::Value>().await.ok(),
+            Err(e) => {
+                warn!("getTransaction for post-mortem failed: {}", e);
+                None
+            }
+        };
+        if let Some(transaction) = response
+            .and_then(|mut body| body.get_mut("result").map(serde_json::Value::take))
+            .filter(|result| !result.is_null())
+        {
+            return Some(transaction);
+        }
+        if attempt < TRANSACTION_FETCH_ATTEMPTS {
+            tokio::time::sleep(std::time::Duration::from_secs(2 * attempt as u64)).await;
+        }
+    }
+    None
+}
+
+/// Assembles post-mortems of losing live trades
+#[derive(Debug)]
+pub struct Postmortems {
+    config: PostmortemConfig,
+    events: Arc<RecentEvents>,
+    traces: Arc<TradeTraces>,
+    snapshots: Option<PriceSnapshots>,
+    rpc_url: String,
+}
+
+impl Postmortems {
+    pub fn new(
+        config: PostmortemConfig,
+        traces: Arc<TradeTraces>,
+        snapshots: Option<PriceSnapshots>,
+        rpc_url: String,
+    ) -> Self {
+        Self {
+            config,
+            events: Arc::new(RecentEvents::default()),
+            traces,
+            snapshots,
+            rpc_url,
+        }
+    }
+
+    pub fn events(&self) -> &Arc<RecentEvents> {
+        &self.events
+    }
+
+    /// Whether a trade with this profit after fees lost enough to look into
+    pub fn should_capture(&self, profit_loss_usd: Decimal) -> bool {
+        -profit_loss_usd > self.config.loss_threshold_usd
+    }
+
+    /// Wait out the window after the trade, then assemble and write its
+    /// bundle. `latest_prices` stand in for the archive when there is none.
+    pub async fn capture(
+        &self,
+        opportunity: ArbitrageOpportunity,
+        size_usd: Decimal,
+        profit_loss_usd: Decimal,
+        outcome: TradeResult,
+        latest_prices: Vec<PriceData>,
+    ) -> Result<PathBuf> {
+        tokio::time::sleep(self.config.window.to_std().unwrap_or_default()).await;
+
+        let executed_at = outcome.executed_at;
+        let (from, to) = (executed_at - self.config.window, executed_at + self.config.window);
+        let price_ticks = match &self.snapshots {
+            Some(snapshots) => archived_ticks(snapshots, &opportunity.pair, from, to),
+            None => vec![SnapshotTick {
+                at: executed_at,
+                prices: latest_prices,
+            }],
+        };
+        let transaction = match &outcome.signature {
+            Some(signature) => fetch_transaction(&self.rpc_url, signature).await,
+            None => None,
+        };
+
+        let bundle = PostmortemBundle {
+            trace: self.traces.get(opportunity.id).map(|trace| trace.diff()),
+            opportunity,
+            size_usd,
+            profit_loss_usd,
+            signature: outcome.signature,
+            executed_at,
+            transaction,
+            price_ticks,
+            events: self.events.between(from, to),
+        };
+        let dir = self.config.dir.clone();
+        tokio::task::spawn_blocking(move || bundle.write_zip(&dir)).await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use solana_arb_core::{DexType, Uuid};
+    use std::io::Read;
+
+    #[test]
+    fn test_bundle_zip_holds_every_stage() {
+        let opp = ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::new(5, 1),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let traces = TradeTraces::new();
+        traces.record_plan(&opp, Decimal::from(1000));
+        traces.record_simulation(opp.id, vec!["Program log: swap".to_string()]);
+
+        let events = RecentEvents::default();
+        events.record(TradingEvent::PriceUpdate {
+            pair: "SOL/USDC".to_string(),
+            price: 100.0,
+            source: "Raydium".to_string(),
+            timestamp: 0,
+        });
+        events.record(TradingEvent::TradeRejected {
+            id: opp.id.to_string(),
+            reason: "test".to_string(),
+        });
+        let now = Utc::now();
+        let recent = events.between(now - Duration::seconds(5), now);
+        assert_eq!(recent.len(), 1);
+
+        let bundle = PostmortemBundle {
+            trace: traces.get(opp.id).map(|t| t.diff()),
+            opportunity: opp.clone(),
+            size_usd: Decimal::from(1000),
+            profit_loss_usd: Decimal::from(-40),
+            signature: Some("5ig".to_string()),
+            executed_at: now,
+            transaction: Some(json!({ "meta": { "err": null, "fee": 5000 } })),
+            price_ticks: vec![SnapshotTick {
+                at: now,
+                prices: Vec::new(),
+            }],
+            events: recent,
+        };
+        let dir = std::env::temp_dir().join(format!("postmortem-test-{}", opp.id));
+        let path = bundle.write_zip(&dir).unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().contains("-SOL-USDC-"));
+
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(zip.len(), 7);
+        let mut read = |name: &str| {
+            let mut contents = String::new();
+            zip.by_name(name).unwrap().read_to_string(&mut contents).unwrap();
+            contents
+        };
+        assert!(read("summary.txt").contains("Profit after fees: $-40"));
+        assert_eq!(read("simulation_logs.txt"), "Program log: swap");
+        assert!(read("events.jsonl").contains("TradeRejected"));
+        assert!(read("transaction.json").contains("\"fee\": 5000"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+//! Candidate Preflight
+//!
+//! By default a tick executes its single best-looking opportunity, and only
+//! finds out at execution whether the quoted route still pays. With
+//! `PREFLIGHT_TOP_K` above one, the top candidates that clear the profit,
+//! blacklist and risk checks are built and simulated side by side, at most
+//! `PREFLIGHT_CONCURRENCY` at a time and under the Jupiter and RPC rate
+//! limiters, and the one with the highest simulated profit is executed. A
+//! tick where none pass executes nothing. This raises the hit rate at the
+//! cost of one quote, one swap build and one simulation per candidate.
+
+use futures_util::StreamExt;
+use rust_decimal::Decimal;
+use std::future::Future;
+
+/// How many candidates to simulate each tick, and how many at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreflightConfig {
+    /// Candidates simulated per tick (0 or 1: preflight disabled)
+    pub top_k: usize,
+    /// Simulations in flight at once
+    pub concurrency: usize,
+}
+
+impl PreflightConfig {
+    pub fn enabled(&self) -> bool {
+        self.top_k > 1
+    }
+}
+
+/// Simulate every candidate, at most `concurrency` at a time. Results are
+/// in candidate order: the simulated profit, or why the simulation failed.
+pub async fn simulate_all<'a, T, F, Fut>(
+    candidates: &'a [T],
+    concurrency: usize,
+    simulate: F,
+) -> Vec<Result<Decimal, String>>
+where
+    F: FnMut(&'a T) -> Fut,
+    Fut: Future<Output = anyhow::Result<Decimal>>,
+{
+    futures_util::stream::iter(candidates.iter().map(simulate))
+        .buffered(concurrency.max(1))
+        .map(|result| result.map_err(|e| e.to_string()))
+        .collect()
+        .await
+}
+
+/// Index of the passing simulation with the highest profit (the earliest on
+/// a tie, as candidates come best-detected first)
+pub fn best(results: &[Result<Decimal, String>]) -> Option<usize> {
+    results
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.as_ref().ok().map(|profit| (i, *profit)))
+        .fold(None, |best: Option<(usize, Decimal)>, (i, profit)| match best {
+            Some((_, top)) if top >= profit => best,
+            _ => Some((i, profit)),
+        })
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_simulates_with_bounded_concurrency_and_picks_best() {
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let candidates = [Some(3), None, Some(7), Some(7), Some(-1)];
+
+        let results = simulate_all(&candidates, 2, |candidate| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                match candidate {
+                    Some(profit) if *profit > 0 => Ok(Decimal::from(*profit)),
+                    Some(_) => Err(anyhow::anyhow!("simulation shows no profit")),
+                    None => Err(anyhow::anyhow!("route mismatch")),
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[1], Err("route mismatch".to_string()));
+        assert_eq!(best(&results), Some(2));
+        assert_eq!(best(&results[3..]), Some(0));
+        assert_eq!(best(&results[4..]), None);
+        assert!(!PreflightConfig { top_k: 1, concurrency: 4 }.enabled());
+    }
+}
@@ -0,0 +1,341 @@
+//! Price Archive Compaction
+//!
+//! The snapshot archive (see [`crate::replay`]) grows by roughly 170k lines
+//! a day. With `PRICE_COMPACTION_ENABLED=true` a job runs every
+//! `PRICE_COMPACTION_INTERVAL_SECS` over the days before today:
+//!
+//! 1. Each day's raw file is rewritten without repeated quotes. A venue's
+//!    price whose bid and ask match the last kept one for its pair is
+//!    dropped, unless `heartbeat` has passed since, so a replay never sees
+//!    a price go stale that the bot saw as fresh.
+//! 2. 1-second and 1-minute bars per pair and venue are written to
+//!    `bars-1s-YYYY-MM-DD.jsonl` and `bars-1m-YYYY-MM-DD.jsonl`. Open,
+//!    high, low and close are of the mid price. Venues report no traded
+//!    volume per quote, so a bar counts the quotes it saw and carries the
+//!    last reported 24h volume.
+//! 3. Files past their resolution's retention are deleted: raw ticks after
+//!    `PRICE_RAW_RETENTION_DAYS`, 1-second bars after
+//!    `PRICE_BARS_1S_RETENTION_DAYS`, 1-minute bars after
+//!    `PRICE_BARS_1M_RETENTION_DAYS` (0 keeps them forever).
+//!
+//! A day counts as compacted once its 1-minute bars exist.
+
+use crate::replay::{self, PriceSnapshots, SnapshotTick};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::config::Config;
+use solana_arb_core::DexType;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolution of a bar file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Second,
+    Minute,
+}
+
+impl Resolution {
+    fn seconds(self) -> i64 {
+        match self {
+            Self::Second => 1,
+            Self::Minute => 60,
+        }
+    }
+
+    /// File name prefix, before the date
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Second => "bars-1s-",
+            Self::Minute => "bars-1m-",
+        }
+    }
+
+    fn bucket(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let secs = at.timestamp();
+        DateTime::from_timestamp(secs - secs.rem_euclid(self.seconds()), 0).unwrap_or(at)
+    }
+}
+
+/// One pair's quotes on one venue over a bar's interval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceBar {
+    pub start: DateTime<Utc>,
+    pub pair: String,
+    pub dex: DexType,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    /// Quotes in the bar after deduplication
+    pub quotes: u32,
+    /// Last 24h volume the venue reported in the bar
+    pub volume_24h: Option<Decimal>,
+}
+
+/// Days each resolution is kept; 0 keeps it forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub raw_days: u32,
+    pub second_bar_days: u32,
+    pub minute_bar_days: u32,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            raw_days: config.price_raw_retention_days,
+            second_bar_days: config.price_bars_1s_retention_days,
+            minute_bar_days: config.price_bars_1m_retention_days,
+        }
+    }
+
+    /// Archive files are named `<prefix>YYYY-MM-DD.jsonl`
+    fn days_for(&self, prefix: &str) -> Option<u32> {
+        match prefix {
+            "prices-" => Some(self.raw_days),
+            "bars-1s-" => Some(self.second_bar_days),
+            "bars-1m-" => Some(self.minute_bar_days),
+            _ => None,
+        }
+    }
+}
+
+/// What one compaction run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    pub days_compacted: Vec<NaiveDate>,
+    /// Repeated quotes removed from raw files
+    pub quotes_removed: usize,
+    pub files_deleted: Vec<PathBuf>,
+}
+
+/// Drop each venue's quotes that repeat its last kept bid and ask within
+/// `heartbeat`, and the ticks left empty. Returns how many quotes were dropped.
+pub fn dedup(ticks: &mut Vec<SnapshotTick>, heartbeat: Duration) -> usize {
+    let mut last: HashMap<(String, DexType), (Decimal, Decimal, DateTime<Utc>)> = HashMap::new();
+    let mut removed = 0;
+    for tick in ticks.iter_mut() {
+        let before = tick.prices.len();
+        tick.prices.retain(|price| {
+            let key = (price.pair.symbol(), price.dex);
+            let repeat = last.get(&key).is_some_and(|(bid, ask, kept_at)| {
+                *bid == price.bid && *ask == price.ask && tick.at - *kept_at < heartbeat
+            });
+            if !repeat {
+                last.insert(key, (price.bid, price.ask, tick.at));
+            }
+            !repeat
+        });
+        removed += before - tick.prices.len();
+    }
+    ticks.retain(|tick| !tick.prices.is_empty());
+    removed
+}
+
+/// Bars of `ticks` at `resolution`, ordered by start, pair and venue
+pub fn bars(ticks: &[SnapshotTick], resolution: Resolution) -> Vec<PriceBar> {
+    let mut bars: HashMap<(DateTime<Utc>, String, DexType), PriceBar> = HashMap::new();
+    for tick in ticks {
+        let start = resolution.bucket(tick.at);
+        for price in &tick.prices {
+            let mid = price.mid_price;
+            bars.entry((start, price.pair.symbol(), price.dex))
+                .and_modify(|bar| {
+                    bar.high = bar.high.max(mid);
+                    bar.low = bar.low.min(mid);
+                    bar.close = mid;
+                    bar.quotes += 1;
+                    bar.volume_24h = price.volume_24h.or(bar.volume_24h);
+                })
+                .or_insert_with(|| PriceBar {
+                    start,
+                    pair: price.pair.symbol(),
+                    dex: price.dex,
+                    open: mid,
+                    high: mid,
+                    low: mid,
+                    close: mid,
+                    quotes: 1,
+                    volume_24h: price.volume_24h,
+                });
+        }
+    }
+    let mut bars: Vec<PriceBar> = bars.into_values().collect();
+    bars.sort_by(|a, b| {
+        (a.start, &a.pair, a.dex.display_name()).cmp(&(b.start, &b.pair, b.dex.display_name()))
+    });
+    bars
+}
+
+/// Bar file of `resolution` for `date`
+pub fn bar_path(snapshots: &PriceSnapshots, resolution: Resolution, date: NaiveDate) -> PathBuf {
+    snapshots
+        .dir()
+        .join(format!("{}{}.jsonl", resolution.prefix(), date.format("%Y-%m-%d")))
+}
+
+/// Deduplicate `date`'s raw file and write its bars. Returns the quotes removed.
+pub fn compact_day(snapshots: &PriceSnapshots, date: NaiveDate, heartbeat: Duration) -> anyhow::Result<usize> {
+    let raw = snapshots.path_for(date);
+    let mut ticks = replay::load_ticks(&raw, None, None)?;
+    let removed = dedup(&mut ticks, heartbeat);
+    if removed > 0 {
+        write_lines(&raw, &ticks)?;
+    }
+    // Minute bars last: they mark the day compacted
+    for resolution in [Resolution::Second, Resolution::Minute] {
+        write_lines(&bar_path(snapshots, resolution, date), &bars(&ticks, resolution))?;
+    }
+    Ok(removed)
+}
+
+/// Compact every day before `today` that is not compacted yet, then delete
+/// files past their retention
+pub fn run(
+    snapshots: &PriceSnapshots,
+    policy: &RetentionPolicy,
+    heartbeat: Duration,
+    today: NaiveDate,
+) -> anyhow::Result<CompactionReport> {
+    let mut report = CompactionReport::default();
+    let mut files = archive_files(snapshots.dir())?;
+    files.sort_by_key(|(_, _, date)| *date);
+
+    for (_, prefix, date) in &files {
+        if prefix == "prices-"
+            && *date < today
+            && !bar_path(snapshots, Resolution::Minute, *date).exists()
+        {
+            report.quotes_removed += compact_day(snapshots, *date, heartbeat)?;
+            report.days_compacted.push(*date);
+        }
+    }
+
+    for (path, prefix, date) in archive_files(snapshots.dir())? {
+        let Some(days) = policy.days_for(&prefix).filter(|days| *days > 0) else {
+            continue;
+        };
+        // Raw files are only deleted once their bars exist
+        let compacted = prefix != "prices-" || bar_path(snapshots, Resolution::Minute, date).exists();
+        if compacted && today - date >= Duration::days(days as i64) {
+            fs::remove_file(&path)?;
+            report.files_deleted.push(path);
+        }
+    }
+    Ok(report)
+}
+
+/// Archive files in `dir` with their prefix and date
+fn archive_files(dir: &Path) -> std::io::Result<Vec<(PathBuf, String, NaiveDate)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let Some(stem) = path.file_name().and_then(|n| n.to_str()).and_then(|n| n.strip_suffix(".jsonl")) else {
+            continue;
+        };
+        let Some((prefix, date)) = stem.len().checked_sub(10).map(|at| stem.split_at(at)) else {
+            continue;
+        };
+        if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            files.push((path.clone(), prefix.to_string(), date));
+        }
+    }
+    Ok(files)
+}
+
+fn write_lines<T: Serialize>(path: &Path, items: &[T]) -> anyhow::Result<()> {
+    let mut content = String::new();
+    for item in items {
+        content.push_str(&serde_json::to_string(item)?);
+        content.push('\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::{PriceData, TokenPair};
+
+    fn tick(at: DateTime<Utc>, quotes: &[(DexType, i64, i64)]) -> SnapshotTick {
+        SnapshotTick {
+            at,
+            prices: quotes
+                .iter()
+                .map(|(dex, bid, ask)| {
+                    let mut price = PriceData::new(*dex, TokenPair::new("SOL", "USDC"), Decimal::from(*bid), Decimal::from(*ask));
+                    price.timestamp = at;
+                    price
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compaction_dedups_builds_bars_and_applies_retention() {
+        let dir = std::env::temp_dir().join(format!("price-compaction-{}", solana_arb_core::Uuid::new_v4()));
+        let snapshots = PriceSnapshots::new(&dir);
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let t0 = day.and_hms_opt(14, 31, 0).unwrap().and_utc();
+        let ms = Duration::milliseconds;
+        let ticks = [
+            tick(t0, &[(DexType::Raydium, 99, 101), (DexType::Orca, 100, 102)]),
+            // Raydium repeats; Orca moves
+            tick(t0 + ms(500), &[(DexType::Raydium, 99, 101), (DexType::Orca, 102, 104)]),
+            // Nothing changed
+            tick(t0 + ms(900), &[(DexType::Raydium, 99, 101), (DexType::Orca, 102, 104)]),
+            // A repeat past the heartbeat is kept
+            tick(t0 + ms(3000), &[(DexType::Raydium, 99, 101)]),
+        ];
+        fs::create_dir_all(&dir).unwrap();
+        write_lines(&snapshots.path_for(day), &ticks).unwrap();
+        // An old day whose bars are past retention, and today's live file
+        let old = day - Duration::days(40);
+        write_lines(&bar_path(&snapshots, Resolution::Second, old), &Vec::<PriceBar>::new()).unwrap();
+        write_lines(&snapshots.path_for(day + Duration::days(1)), &ticks[..1]).unwrap();
+
+        let policy = RetentionPolicy {
+            raw_days: 0,
+            second_bar_days: 30,
+            minute_bar_days: 0,
+        };
+        let report = run(&snapshots, &policy, Duration::seconds(2), day + Duration::days(1)).unwrap();
+        assert_eq!(report.days_compacted, [day]);
+        assert_eq!(report.quotes_removed, 3);
+        assert_eq!(report.files_deleted, [bar_path(&snapshots, Resolution::Second, old)]);
+
+        let raw = replay::load_ticks(&snapshots.path_for(day), None, None).unwrap();
+        assert_eq!(raw.iter().map(|t| t.prices.len()).collect::<Vec<_>>(), [2, 1, 1]);
+
+        let read = |resolution| -> Vec<PriceBar> {
+            fs::read_to_string(bar_path(&snapshots, resolution, day))
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+        let minute = read(Resolution::Minute);
+        assert_eq!(minute.len(), 2);
+        let orca = minute.iter().find(|bar| bar.dex == DexType::Orca).unwrap();
+        assert_eq!((orca.open, orca.high, orca.close, orca.quotes), (Decimal::from(101), Decimal::from(103), Decimal::from(103), 2));
+        // Raydium at 14:31:00 and 14:31:03, Orca at 14:31:00
+        assert_eq!(read(Resolution::Second).len(), 3);
+
+        // Compacted days are left alone; today's file is not touched
+        let again = run(&snapshots, &policy, Duration::seconds(2), day + Duration::days(1)).unwrap();
+        assert_eq!(again, CompactionReport::default());
+        assert!(!bar_path(&snapshots, Resolution::Minute, day + Duration::days(1)).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+//! Priority Fee Override
+//!
+//! During congestion the configured priority fee (and the flash loan size
+//! heuristic) can be too low to land anything. An operator can set a
+//! temporary fee through the API; until it expires it takes precedence over
+//! every other source of the compute unit price. Setting and clearing an
+//! override is appended to the audit log.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use solana_arb_core::audit_log::{AuditEvent, AuditLogger};
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+/// Highest fee an override may set, in micro-lamports per compute unit
+pub const MAX_OVERRIDE_MICRO_LAMPORTS: u64 = 10_000_000;
+
+/// Longest an override may last
+pub const MAX_OVERRIDE_SECS: u64 = 24 * 3600;
+
+/// A temporary compute unit price
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PriorityFeeOverride {
+    pub micro_lamports: u64,
+    pub set_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub actor: String,
+    pub reason: Option<String>,
+}
+
+/// Shared holder of the current override
+#[derive(Default)]
+pub struct PriorityFeeControl {
+    current: RwLock<Option<PriorityFeeOverride>>,
+    audit_logger: Option<Arc<AuditLogger>>,
+}
+
+impl PriorityFeeControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append every change to `logger`
+    pub fn with_audit_logger(mut self, logger: Arc<AuditLogger>) -> Self {
+        self.audit_logger = Some(logger);
+        self
+    }
+
+    /// The override in force, if any. Expired overrides are dropped.
+    pub fn active(&self) -> Option<PriorityFeeOverride> {
+        let now = Utc::now();
+        let current = self.current.read().unwrap_or_else(|e| e.into_inner()).clone();
+        match current {
+            Some(fee) if fee.expires_at > now => Some(fee),
+            Some(fee) => {
+                let mut slot = self.current.write().unwrap_or_else(|e| e.into_inner());
+                if slot.as_ref() == Some(&fee) {
+                    *slot = None;
+                    info!(
+                        "⏱️ Priority fee override of {} µL/CU expired",
+                        fee.micro_lamports
+                    );
+                }
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// The override's fee, or `fallback` when none is in force
+    pub fn fee_or(&self, fallback: u64) -> u64 {
+        self.active().map_or(fallback, |fee| fee.micro_lamports)
+    }
+
+    /// Describe how the fee in force moved away from `seen`, then remember it
+    pub fn fee_change(&self, fallback: u64, seen: &mut u64) -> Option<String> {
+        let fee = self.fee_or(fallback);
+        if fee == *seen {
+            return None;
+        }
+        let change = format!("priority fee {} → {} µL/CU", seen, fee);
+        *seen = fee;
+        Some(change)
+    }
+
+    /// Set an override for `ttl_secs`, replacing any current one
+    pub async fn set(
+        &self,
+        micro_lamports: u64,
+        ttl_secs: u64,
+        actor: &str,
+        reason: Option<String>,
+    ) -> Result<PriorityFeeOverride, String> {
+        if micro_lamports == 0 || micro_lamports > MAX_OVERRIDE_MICRO_LAMPORTS {
+            return Err(format!(
+                "micro_lamports must be between 1 and {}",
+                MAX_OVERRIDE_MICRO_LAMPORTS
+            ));
+        }
+        if ttl_secs == 0 || ttl_secs > MAX_OVERRIDE_SECS {
+            return Err(format!("ttl_secs must be between 1 and {}", MAX_OVERRIDE_SECS));
+        }
+
+        let now = Utc::now();
+        let fee = PriorityFeeOverride {
+            micro_lamports,
+            set_at: now,
+            expires_at: now + chrono::Duration::seconds(ttl_secs as i64),
+            actor: actor.to_string(),
+            reason,
+        };
+        let previous = self
+            .current
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(fee.clone());
+        info!(
+            "⛽ Priority fee overridden to {} µL/CU until {} by {}",
+            micro_lamports,
+            fee.expires_at.to_rfc3339(),
+            actor
+        );
+        self.audit(
+            "PRIORITY_FEE_OVERRIDE",
+            serde_json::json!({
+                "micro_lamports": micro_lamports,
+                "expires_at": fee.expires_at.to_rfc3339(),
+                "previous": previous.map(|p| p.micro_lamports),
+                "actor": actor,
+                "reason": fee.reason,
+            }),
+        )
+        .await;
+        Ok(fee)
+    }
+
+    /// Remove the override. Returns it if one was in force.
+    pub async fn clear(&self, actor: &str) -> Option<PriorityFeeOverride> {
+        let active = self.active();
+        self.current.write().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(fee) = &active {
+            info!("⛽ Priority fee override of {} µL/CU cleared by {}", fee.micro_lamports, actor);
+            self.audit(
+                "PRIORITY_FEE_CLEAR",
+                serde_json::json!({ "micro_lamports": fee.micro_lamports, "actor": actor }),
+            )
+            .await;
+        }
+        active
+    }
+
+    async fn audit(&self, action: &str, details: serde_json::Value) {
+        if let Some(logger) = &self.audit_logger {
+            let event = AuditEvent {
+                timestamp: Utc::now().to_rfc3339(),
+                category: "CONFIG".to_string(),
+                action: action.to_string(),
+                resource: "priority_fee".to_string(),
+                result: "SUCCESS".to_string(),
+                details,
+            };
+            if let Err(e) = logger.log(event).await {
+                warn!("Failed to write priority fee change to audit log: {}", e);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for PriorityFeeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriorityFeeControl")
+            .field("current", &self.active())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_override_takes_precedence_until_cleared() {
+        let control = PriorityFeeControl::new();
+        assert_eq!(control.fee_or(50_000), 50_000);
+
+        assert!(control.set(0, 60, "oncall", None).await.is_err());
+        assert!(control.set(500_000, MAX_OVERRIDE_SECS + 1, "oncall", None).await.is_err());
+
+        let fee = control
+            .set(500_000, 60, "oncall", Some("congestion".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(control.fee_or(50_000), 500_000);
+        assert_eq!(control.active(), Some(fee));
+
+        // An expired override no longer applies
+        control.current.write().unwrap().as_mut().unwrap().expires_at = Utc::now();
+        assert_eq!(control.fee_or(50_000), 50_000);
+        assert!(control.clear("oncall").await.is_none());
+
+        control.set(200_000, 60, "oncall", None).await.unwrap();
+        assert_eq!(control.clear("oncall").await.unwrap().micro_lamports, 200_000);
+        assert_eq!(control.fee_or(50_000), 50_000);
+    }
+
+    #[tokio::test]
+    async fn test_fee_change_reports_each_move_once() {
+        let control = PriorityFeeControl::new();
+        let mut seen = 50_000;
+        assert_eq!(control.fee_change(50_000, &mut seen), None);
+
+        control.set(500_000, 60, "oncall", None).await.unwrap();
+        assert_eq!(
+            control.fee_change(50_000, &mut seen).as_deref(),
+            Some("priority fee 50000 → 500000 µL/CU")
+        );
+        assert_eq!(control.fee_change(50_000, &mut seen), None);
+
+        control.clear("oncall").await;
+        assert_eq!(
+            control.fee_change(50_000, &mut seen).as_deref(),
+            Some("priority fee 500000 → 50000 µL/CU")
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Wallet Quarantine
+//!
+//! With `QUARANTINE_ENABLED=true` the bot treats two signs of a leaked key
+//! as a compromise:
+//!
+//! - a transaction signed by the wallet that the bot never signed, found by
+//!   scanning the wallet's recent signatures every `QUARANTINE_CHECK_SECS`;
+//! - a SOL balance drop of more than `QUARANTINE_MAX_OUTFLOW_SOL` between
+//!   two scans while the bot signed nothing.
+//!
+//! Either one quarantines the wallet. The executor refuses to sign, the
+//! in-memory keypairs are dropped and a critical alert goes out. The
+//! quarantine is written to `QUARANTINE_STATE_PATH`, so a restart comes up
+//! quarantined. Only `POST /quarantine/unlock` on the authenticated API
+//! lifts it, and the request has to name the quarantined wallet. The keys
+//! are then reloaded from `PRIVATE_KEY`.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Signatures remembered as the bot's own; older ones have long left the
+/// scanned page of the wallet's history
+const SIGNED_CAPACITY: usize = 10_000;
+
+/// Why and since when the wallet is quarantined
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantineRecord {
+    pub wallet: String,
+    pub reason: String,
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Signed {
+    order: VecDeque<String>,
+    set: HashSet<String>,
+    /// Signed since the last balance check
+    since_check: usize,
+}
+
+#[derive(Debug)]
+pub struct Quarantine {
+    path: PathBuf,
+    record: Mutex<Option<QuarantineRecord>>,
+    signed: Mutex<Signed>,
+}
+
+impl Quarantine {
+    /// Load the quarantine persisted at `path`, if any
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let record = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok());
+        Self {
+            path,
+            record: Mutex::new(record),
+            signed: Mutex::new(Signed::default()),
+        }
+    }
+
+    pub fn record(&self) -> Option<QuarantineRecord> {
+        lock(&self.record).clone()
+    }
+
+    pub fn is_active(&self) -> bool {
+        lock(&self.record).is_some()
+    }
+
+    /// Fails while quarantined; checked before every signature
+    pub fn ensure_signing(&self) -> Result<()> {
+        match &*lock(&self.record) {
+            Some(record) => Err(anyhow!("wallet quarantined since {}: {}", record.since, record.reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Quarantine `wallet`. Returns `false`, keeping the first record, if it
+    /// already was.
+    pub fn enter(&self, wallet: &str, reason: &str) -> bool {
+        let mut record = lock(&self.record);
+        if record.is_some() {
+            return false;
+        }
+        let entered = QuarantineRecord {
+            wallet: wallet.to_string(),
+            reason: reason.to_string(),
+            since: Utc::now(),
+        };
+        if let Err(e) = self.persist(Some(&entered)) {
+            warn!("Failed to persist quarantine to {}: {}", self.path.display(), e);
+        }
+        *record = Some(entered);
+        true
+    }
+
+    /// Lift the quarantine. `wallet` must name the quarantined wallet.
+    pub fn unlock(&self, wallet: &str) -> Result<QuarantineRecord> {
+        let mut record = lock(&self.record);
+        match &*record {
+            None => return Err(anyhow!("wallet is not quarantined")),
+            Some(r) if r.wallet != wallet => {
+                return Err(anyhow!("quarantined wallet is {}, not {}", r.wallet, wallet))
+            }
+            Some(_) => {}
+        }
+        self.persist(None)?;
+        Ok(record.take().expect("checked above"))
+    }
+
+    fn persist(&self, record: Option<&QuarantineRecord>) -> std::io::Result<()> {
+        match record {
+            Some(record) => {
+                if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&self.path, serde_json::to_string_pretty(record)?)
+            }
+            None => match fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Remember a signature the bot made
+    pub fn note_signed(&self, signature: &str) {
+        let mut signed = lock(&self.signed);
+        signed.since_check += 1;
+        if signed.set.insert(signature.to_string()) {
+            signed.order.push_back(signature.to_string());
+        }
+        while signed.order.len() > SIGNED_CAPACITY {
+            if let Some(oldest) = signed.order.pop_front() {
+                signed.set.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn signed_by_bot(&self, signature: &str) -> bool {
+        lock(&self.signed).set.contains(signature)
+    }
+
+    /// Signatures made since the previous call
+    pub fn take_signed_since_check(&self) -> usize {
+        std::mem::take(&mut lock(&self.signed).since_check)
+    }
+}
+
+/// Lamports that left the wallet between two balance checks without the
+/// bot signing anything, if more than `max_lamports`
+pub fn unexplained_outflow(before: u64, after: u64, bot_signed: usize, max_lamports: u64) -> Option<u64> {
+    let outflow = before.saturating_sub(after);
+    (bot_signed == 0 && outflow > max_lamports).then_some(outflow)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarantine_persists_until_unlocked_by_wallet() {
+        let path = std::env::temp_dir().join(format!("quarantine_{}.json", solana_arb_core::Uuid::new_v4()));
+        let quarantine = Quarantine::load(&path);
+        assert!(quarantine.ensure_signing().is_ok());
+
+        quarantine.note_signed("own");
+        assert!(quarantine.signed_by_bot("own") && !quarantine.signed_by_bot("other"));
+        assert_eq!(quarantine.take_signed_since_check(), 1);
+        assert_eq!(quarantine.take_signed_since_check(), 0);
+
+        assert!(quarantine.enter("Wallet1", "unknown signature other"));
+        assert!(!quarantine.enter("Wallet1", "second"));
+        assert!(quarantine.ensure_signing().is_err());
+
+        // A restart comes up quarantined
+        let reloaded = Quarantine::load(&path);
+        assert_eq!(reloaded.record().unwrap().reason, "unknown signature other");
+        assert!(reloaded.unlock("Wallet2").is_err());
+        assert_eq!(reloaded.unlock("Wallet1").unwrap().wallet, "Wallet1");
+        assert!(reloaded.ensure_signing().is_ok());
+        assert!(!path.exists());
+        assert!(reloaded.unlock("Wallet1").is_err());
+
+        // 0.6 SOL gone while the bot signed nothing; the same drop is a
+        // trade's when the bot did sign
+        assert_eq!(unexplained_outflow(2_000_000_000, 1_400_000_000, 0, 500_000_000), Some(600_000_000));
+        assert_eq!(unexplained_outflow(2_000_000_000, 1_400_000_000, 1, 500_000_000), None);
+        assert_eq!(unexplained_outflow(2_000_000_000, 1_800_000_000, 0, 500_000_000), None);
+        assert_eq!(unexplained_outflow(1_000, 5_000, 0, 0), None);
+    }
+}
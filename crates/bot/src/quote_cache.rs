@@ -0,0 +1,198 @@
+//! Jupiter Quote Cache
+//!
+//! Impact sizing and execution ask Jupiter for the same route several times
+//! within a few hundred milliseconds, each time spending rate limit. Quotes
+//! are kept for `QUOTE_CACHE_TTL_MS`, keyed by route (mints, slippage and
+//! route filter) and a size bucket `QUOTE_CACHE_BUCKET_PCT` wide. Estimates
+//! accept any quote in the bucket; a quote that is about to be executed must
+//! be for exactly the same amount. A price tick that moves a pair drops the
+//! quotes of its tokens.
+
+use serde_json::Value;
+use solana_arb_core::config::Config;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What a quote is for, apart from its size
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuoteRoute {
+    /// Mint or symbol, as passed to Jupiter
+    pub input: String,
+    pub output: String,
+    pub slippage_bps: u64,
+    /// Route filter query parameters
+    pub params: String,
+}
+
+#[derive(Debug)]
+struct CachedQuote {
+    amount: u64,
+    quote: Value,
+    fetched_at: Instant,
+}
+
+/// Lookups and invalidations since the last [`QuoteCache::take_stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Quotes dropped because their pair's price moved
+    pub invalidated: u64,
+}
+
+#[derive(Debug)]
+pub struct QuoteCache {
+    ttl: Duration,
+    /// ln(1 + bucket width), 0 for exact amounts only
+    log_bucket_width: f64,
+    quotes: Mutex<HashMap<(QuoteRoute, i64), CachedQuote>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidated: AtomicU64,
+}
+
+impl QuoteCache {
+    pub fn new(ttl: Duration, bucket_pct: f64) -> Self {
+        Self {
+            ttl,
+            log_bucket_width: if bucket_pct > 0.0 { (1.0 + bucket_pct / 100.0).ln() } else { 0.0 },
+            quotes: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidated: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            Duration::from_millis(config.quote_cache_ttl_ms),
+            config.quote_cache_bucket_pct,
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// Buckets grow with the amount, so each spans the same relative width
+    fn bucket(&self, amount: u64) -> i64 {
+        if self.log_bucket_width == 0.0 || amount == 0 {
+            return amount as i64;
+        }
+        ((amount as f64).ln() / self.log_bucket_width).floor() as i64
+    }
+
+    /// A fresh quote for `route` in `amount`'s bucket; with `exact`, only
+    /// one for `amount` itself
+    pub fn get(&self, route: &QuoteRoute, amount: u64, exact: bool) -> Option<Value> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let found = self
+            .lock()
+            .get(&(route.clone(), self.bucket(amount)))
+            .filter(|cached| cached.fetched_at.elapsed() < self.ttl)
+            .filter(|cached| !exact || cached.amount == amount)
+            .map(|cached| cached.quote.clone());
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    pub fn insert(&self, route: QuoteRoute, amount: u64, quote: Value) {
+        if !self.is_enabled() {
+            return;
+        }
+        let key = (route, self.bucket(amount));
+        let mut quotes = self.lock();
+        quotes.retain(|_, cached| cached.fetched_at.elapsed() < self.ttl);
+        quotes.insert(
+            key,
+            CachedQuote {
+                amount,
+                quote,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop quotes between any two of `tokens` (a pair's symbols and mints)
+    pub fn invalidate(&self, tokens: &[&str]) {
+        let mut quotes = self.lock();
+        let before = quotes.len();
+        quotes.retain(|(route, _), _| {
+            !(tokens.contains(&route.input.as_str()) && tokens.contains(&route.output.as_str()))
+        });
+        self.invalidated
+            .fetch_add((before - quotes.len()) as u64, Ordering::Relaxed);
+    }
+
+    /// Counts since the previous call
+    pub fn take_stats(&self) -> QuoteCacheStats {
+        QuoteCacheStats {
+            hits: self.hits.swap(0, Ordering::Relaxed),
+            misses: self.misses.swap(0, Ordering::Relaxed),
+            invalidated: self.invalidated.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(QuoteRoute, i64), CachedQuote>> {
+        self.quotes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn route(input: &str, output: &str) -> QuoteRoute {
+        QuoteRoute {
+            input: input.to_string(),
+            output: output.to_string(),
+            slippage_bps: 50,
+            params: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_buckets_exact_hits_expiry_and_invalidation() {
+        let cache = QuoteCache::new(Duration::from_millis(200), 1.0);
+        let usdc_sol = route("USDC", "SOL");
+        cache.insert(usdc_sol.clone(), 1_000_000_000, json!({ "outAmount": "10" }));
+
+        // Same amount: hit for execution and estimates
+        assert!(cache.get(&usdc_sol, 1_000_000_000, true).is_some());
+        // 0.2% larger: same 1% bucket, good enough for an estimate only
+        assert!(cache.get(&usdc_sol, 1_002_000_000, false).is_some());
+        assert!(cache.get(&usdc_sol, 1_002_000_000, true).is_none());
+        // 5% larger, another route, other slippage: misses
+        assert!(cache.get(&usdc_sol, 1_050_000_000, false).is_none());
+        assert!(cache.get(&route("SOL", "USDC"), 1_000_000_000, false).is_none());
+        assert!(cache
+            .get(&QuoteRoute { slippage_bps: 100, ..usdc_sol.clone() }, 1_000_000_000, false)
+            .is_none());
+        assert_eq!(
+            cache.take_stats(),
+            QuoteCacheStats { hits: 2, misses: 4, invalidated: 0 }
+        );
+
+        // A tick for another pair leaves the quote; one for SOL/USDC drops it
+        cache.insert(route("USDC", "BONK"), 5, json!({}));
+        cache.invalidate(&["SOL", "USDT"]);
+        assert!(cache.get(&usdc_sol, 1_000_000_000, true).is_some());
+        cache.invalidate(&["SOL", "USDC"]);
+        assert!(cache.get(&usdc_sol, 1_000_000_000, true).is_none());
+        assert!(cache.get(&route("USDC", "BONK"), 5, true).is_some());
+        assert_eq!(cache.take_stats().invalidated, 1);
+
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(cache.get(&route("USDC", "BONK"), 5, true).is_none());
+
+        let disabled = QuoteCache::new(Duration::ZERO, 1.0);
+        disabled.insert(usdc_sol.clone(), 1, json!({}));
+        assert!(disabled.get(&usdc_sol, 1, false).is_none());
+    }
+}
@@ -0,0 +1,519 @@
+//! Startup Reconciliation
+//!
+//! Before a live bot trades, what it assumes about its wallet is checked
+//! against the chain:
+//!
+//! - balances per mint, against the snapshot taken at the last startup or
+//!   clean shutdown. Changes are reported as drift (deposits, withdrawals,
+//!   trades of a session that crashed) and the snapshot is replaced.
+//! - open wSOL token accounts. Outside wSOL mode none should be open, and
+//!   temporary ones a swap left behind never should: they are closed.
+//! - transactions still open in the intent log (see [`crate::intent_wal`]).
+//!   Each is looked up: landed, failed or expired ones are closed; one
+//!   whose blockhash is still valid may yet land, and one that cannot be
+//!   looked up is unknown. Both are conflicts.
+//! - the lookup tables those transactions used. A closed or deactivated
+//!   table is dropped from the cache; Jupiter routes through others.
+//!
+//! Fixes are only made when nothing is in conflict. The report is written
+//! to `data/reconciliation.json` and shown on `/status`; any conflict
+//! pauses trading until an operator resumes it.
+
+use crate::execution::Executor;
+use crate::intent_wal::{Intent, IntentWal};
+use crate::wallet::Wallet;
+use crate::wsol;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::UiAccountData;
+use solana_arb_core::alt::{AltLiveness, AltManager};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::request::TokenAccountsFilter;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+pub const DEFAULT_SNAPSHOT_PATH: &str = "data/wallet_snapshot.json";
+pub const REPORT_PATH: &str = "data/reconciliation.json";
+
+/// Key native SOL (lamports) is kept under, apart from the wSOL mint
+pub const NATIVE: &str = "native";
+
+/// Wallet balances per mint (raw amounts), as last seen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// "startup" or "shutdown"
+    pub taken_on: String,
+    pub balances: BTreeMap<String, u64>,
+}
+
+impl BalanceSnapshot {
+    pub fn load(path: &Path) -> Option<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// A token account of the wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenAccount {
+    pub address: Pubkey,
+    pub mint: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Matches what the bot assumed
+    Ok,
+    /// Diverged and was corrected
+    Fixed,
+    /// Diverged in a way that does not block trading
+    Drift,
+    /// Diverged in a way the bot cannot settle; live trading stays paused
+    Conflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    /// "balance", "wsol", "intent" or "lookup_table"
+    pub check: &'static str,
+    /// Mint, account, signature or table
+    pub subject: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Finding {
+    fn new(check: &'static str, subject: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            subject: subject.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationReport {
+    pub at: DateTime<Utc>,
+    pub wallet: String,
+    pub findings: Vec<Finding>,
+}
+
+impl ReconciliationReport {
+    pub fn conflicts(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.status == CheckStatus::Conflict)
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.conflicts().next().is_none()
+    }
+
+    /// One line per finding that is not `ok`
+    pub fn summary(&self) -> String {
+        self.findings
+            .iter()
+            .filter(|f| f.status != CheckStatus::Ok)
+            .map(|f| format!("{:?} {} {}: {}", f.status, f.check, f.subject, f.detail))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Balance changes since `previous`, one finding per mint held now or then
+pub fn balance_findings(previous: Option<&BalanceSnapshot>, current: &BTreeMap<String, u64>) -> Vec<Finding> {
+    let Some(previous) = previous else {
+        return current
+            .iter()
+            .map(|(mint, amount)| {
+                Finding::new("balance", mint, CheckStatus::Ok, format!("{} (no earlier snapshot)", amount))
+            })
+            .collect();
+    };
+    let mints: BTreeSet<&String> = previous.balances.keys().chain(current.keys()).collect();
+    mints
+        .into_iter()
+        .map(|mint| {
+            let before = previous.balances.get(mint).copied().unwrap_or(0);
+            let now = current.get(mint).copied().unwrap_or(0);
+            if before == now {
+                Finding::new("balance", mint, CheckStatus::Ok, now.to_string())
+            } else {
+                Finding::new(
+                    "balance",
+                    mint,
+                    CheckStatus::Drift,
+                    format!(
+                        "{} at {} {}, {} now",
+                        before,
+                        previous.taken_on,
+                        previous.taken_at.to_rfc3339(),
+                        now
+                    ),
+                )
+            }
+        })
+        .collect()
+}
+
+/// What the chain says about an open intent
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntentState {
+    Landed,
+    Failed(String),
+    /// Not found; the blockhash is still valid or has expired
+    Missing { blockhash_valid: bool },
+    /// The lookup failed
+    Unknown(String),
+}
+
+/// Finding for an open intent, and the outcome to close it with, if it can be
+pub fn intent_finding(intent: &Intent, state: &IntentState) -> (Finding, Option<&'static str>) {
+    let finding = |status, detail: String| Finding::new("intent", &intent.signature, status, detail);
+    match state {
+        IntentState::Landed => (
+            finding(
+                CheckStatus::Drift,
+                "landed while unresolved; check that the trade history has it".to_string(),
+            ),
+            Some("landed"),
+        ),
+        IntentState::Failed(err) => (finding(CheckStatus::Fixed, format!("failed on chain: {}", err)), Some("failed")),
+        IntentState::Missing { blockhash_valid: false } => (
+            finding(CheckStatus::Fixed, "never landed and its blockhash expired".to_string()),
+            Some("expired"),
+        ),
+        IntentState::Missing { blockhash_valid: true } => (
+            finding(
+                CheckStatus::Conflict,
+                format!("not landed yet but may still land (signed {})", intent.signed_at.to_rfc3339()),
+            ),
+            None,
+        ),
+        IntentState::Unknown(e) => (finding(CheckStatus::Conflict, format!("status unknown: {}", e)), None),
+    }
+}
+
+/// wSOL token accounts that should not be open: temporary ones always, the
+/// standing account unless wSOL mode keeps it
+pub fn stray_wsol_accounts<'a>(
+    accounts: &'a [TokenAccount],
+    standing: &Pubkey,
+    wsol_mode: bool,
+) -> Vec<&'a TokenAccount> {
+    let native_mint = spl_token::native_mint::id().to_string();
+    accounts
+        .iter()
+        .filter(|account| account.mint == native_mint)
+        .filter(|account| !(wsol_mode && account.address == *standing))
+        .collect()
+}
+
+/// Native SOL and SPL token balances of `owner`, per mint, and its token accounts
+pub async fn wallet_balances(
+    client: &RpcClient,
+    owner: &Pubkey,
+) -> Result<(BTreeMap<String, u64>, Vec<TokenAccount>)> {
+    let mut balances = BTreeMap::new();
+    balances.insert(NATIVE.to_string(), client.get_balance(owner).await?);
+
+    let keyed = client
+        .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(spl_token::id()))
+        .await?;
+    let mut accounts = Vec::with_capacity(keyed.len());
+    for keyed in keyed {
+        let UiAccountData::Json(parsed) = &keyed.account.data else {
+            return Err(anyhow!("token account {} was not returned parsed", keyed.pubkey));
+        };
+        let info = &parsed.parsed["info"];
+        let mint = info["mint"].as_str().ok_or_else(|| anyhow!("token account {} has no mint", keyed.pubkey))?;
+        let amount = info["tokenAmount"]["amount"]
+            .as_str()
+            .and_then(|amount| amount.parse::<u64>().ok())
+            .ok_or_else(|| anyhow!("token account {} has no amount", keyed.pubkey))?;
+        *balances.entry(mint.to_string()).or_default() += amount;
+        accounts.push(TokenAccount {
+            address: Pubkey::from_str(&keyed.pubkey)?,
+            mint: mint.to_string(),
+            amount,
+        });
+    }
+    Ok((balances, accounts))
+}
+
+/// What reconciliation reads and fixes
+pub struct Reconciler<'a> {
+    pub client: &'a RpcClient,
+    pub executor: &'a Executor,
+    pub wallet: &'a Wallet,
+    pub rpc_url: &'a str,
+    pub alt_manager: &'a AltManager,
+    pub intents: &'a IntentWal,
+    pub snapshot_path: &'a Path,
+    pub wsol_mode: bool,
+}
+
+impl Reconciler<'_> {
+    pub async fn run(&self) -> Result<ReconciliationReport> {
+        let owner = Pubkey::from_str(&self.wallet.pubkey())?;
+        let mut findings = Vec::new();
+
+        // Open intents first: fixes wait until none may still land
+        let open = self.intents.open()?;
+        let states = self.intent_states(&open).await;
+        let mut resolutions = Vec::new();
+        let mut tables: BTreeSet<&String> = BTreeSet::new();
+        for (intent, state) in open.iter().zip(&states) {
+            let (finding, outcome) = intent_finding(intent, state);
+            findings.push(finding);
+            if let Some(outcome) = outcome {
+                resolutions.push((intent.signature.as_str(), outcome));
+            }
+            tables.extend(&intent.lookup_tables);
+        }
+        for table in tables {
+            findings.push(self.table_finding(table).await);
+        }
+
+        let wallet = match wallet_balances(self.client, &owner).await {
+            Ok(read) => Some(read),
+            Err(e) => {
+                findings.push(Finding::new(
+                    "balance",
+                    owner.to_string(),
+                    CheckStatus::Conflict,
+                    format!("unreadable: {}", e),
+                ));
+                None
+            }
+        };
+        let settled = !findings.iter().any(|f| f.status == CheckStatus::Conflict);
+
+        if let Some((balances, accounts)) = &wallet {
+            for account in stray_wsol_accounts(accounts, &wsol::wsol_account(&owner), self.wsol_mode) {
+                findings.push(self.close_wsol(&owner, account, settled).await);
+            }
+            let previous = BalanceSnapshot::load(self.snapshot_path);
+            findings.extend(balance_findings(previous.as_ref(), balances));
+            let snapshot = BalanceSnapshot {
+                taken_at: Utc::now(),
+                taken_on: "startup".to_string(),
+                balances: balances.clone(),
+            };
+            if let Err(e) = snapshot.save(self.snapshot_path) {
+                warn!("Failed to save the wallet snapshot: {}", e);
+            }
+        }
+
+        for (signature, outcome) in resolutions {
+            self.intents.resolve(signature, outcome);
+        }
+        match self.intents.compact() {
+            Ok(open) if open > 0 => info!("{} intent(s) left open in {}", open, self.intents.path().display()),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to compact the intent log: {}", e),
+        }
+
+        Ok(ReconciliationReport {
+            at: Utc::now(),
+            wallet: owner.to_string(),
+            findings,
+        })
+    }
+
+    async fn intent_states(&self, open: &[Intent]) -> Vec<IntentState> {
+        let mut states = Vec::with_capacity(open.len());
+        // getSignatureStatuses accepts at most 256 signatures per call
+        for chunk in open.chunks(256) {
+            let signatures: Vec<Signature> = chunk
+                .iter()
+                .map(|intent| Signature::from_str(&intent.signature).unwrap_or_default())
+                .collect();
+            let statuses = match self.client.get_signature_statuses_with_history(&signatures).await {
+                Ok(response) => response.value,
+                Err(e) => {
+                    states.extend(chunk.iter().map(|_| IntentState::Unknown(e.to_string())));
+                    continue;
+                }
+            };
+            for (intent, status) in chunk.iter().zip(statuses) {
+                states.push(match status {
+                    Some(status) => match status.err {
+                        Some(err) => IntentState::Failed(err.to_string()),
+                        None => IntentState::Landed,
+                    },
+                    None => self.missing_state(intent).await,
+                });
+            }
+        }
+        states
+    }
+
+    async fn missing_state(&self, intent: &Intent) -> IntentState {
+        let Ok(blockhash) = Hash::from_str(&intent.blockhash) else {
+            return IntentState::Unknown(format!("invalid blockhash {}", intent.blockhash));
+        };
+        match self.client.is_blockhash_valid(&blockhash, CommitmentConfig::processed()).await {
+            Ok(blockhash_valid) => IntentState::Missing { blockhash_valid },
+            Err(e) => IntentState::Unknown(e.to_string()),
+        }
+    }
+
+    async fn table_finding(&self, table: &str) -> Finding {
+        let finding = |status, detail: &str| Finding::new("lookup_table", table, status, detail);
+        let Ok(address) = Pubkey::from_str(table) else {
+            return finding(CheckStatus::Drift, "invalid address");
+        };
+        match self.alt_manager.liveness(&address).await {
+            Ok(AltLiveness::Active) => finding(CheckStatus::Ok, "active"),
+            Ok(AltLiveness::Deactivated) => finding(CheckStatus::Drift, "deactivated; dropped from the cache"),
+            Ok(AltLiveness::Closed) => finding(CheckStatus::Drift, "closed; dropped from the cache"),
+            Err(e) => finding(CheckStatus::Drift, &format!("unreadable: {}", e)),
+        }
+    }
+
+    async fn close_wsol(&self, owner: &Pubkey, account: &TokenAccount, settled: bool) -> Finding {
+        let subject = account.address.to_string();
+        if !settled {
+            return Finding::new(
+                "wsol",
+                subject,
+                CheckStatus::Drift,
+                format!("open with {} lamports; left open while transactions may land", account.amount),
+            );
+        }
+        let closed = match wsol::close_instructions(owner, &account.address) {
+            Ok(instructions) => {
+                self.executor
+                    .send_wallet_instructions(self.wallet, self.rpc_url, &instructions)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match closed {
+            Ok(signature) => Finding::new(
+                "wsol",
+                subject,
+                CheckStatus::Fixed,
+                format!("closed, {} lamports unwrapped ({})", account.amount, signature),
+            ),
+            Err(e) => Finding::new(
+                "wsol",
+                subject,
+                CheckStatus::Conflict,
+                format!("open with {} lamports and could not be closed: {}", account.amount, e),
+            ),
+        }
+    }
+}
+
+/// Snapshot balances on a clean shutdown, so the next start can tell what moved
+pub async fn snapshot_on_shutdown(client: &RpcClient, owner: &Pubkey, path: &Path) {
+    let balances = match wallet_balances(client, owner).await {
+        Ok((balances, _)) => balances,
+        Err(e) => return warn!("Failed to read balances for the wallet snapshot: {}", e),
+    };
+    let snapshot = BalanceSnapshot {
+        taken_at: Utc::now(),
+        taken_on: "shutdown".to_string(),
+        balances,
+    };
+    if let Err(e) = snapshot.save(path) {
+        warn!("Failed to save the wallet snapshot: {}", e);
+    }
+}
+
+/// Conflicts of `report` by check, for the pause reason
+pub fn conflict_counts(report: &ReconciliationReport) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for finding in report.conflicts() {
+        *counts.entry(finding.check).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort();
+    counts
+        .into_iter()
+        .map(|(check, n)| format!("{} {}", n, check))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intent() -> Intent {
+        Intent {
+            signature: "sig".to_string(),
+            blockhash: Hash::new_unique().to_string(),
+            lookup_tables: Vec::new(),
+            signed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_intents_close_unless_they_may_still_land() {
+        let intent = intent();
+        let (finding, outcome) = intent_finding(&intent, &IntentState::Missing { blockhash_valid: false });
+        assert_eq!((finding.status, outcome), (CheckStatus::Fixed, Some("expired")));
+        let (finding, outcome) = intent_finding(&intent, &IntentState::Landed);
+        assert_eq!((finding.status, outcome), (CheckStatus::Drift, Some("landed")));
+
+        let (pending, outcome) = intent_finding(&intent, &IntentState::Missing { blockhash_valid: true });
+        assert_eq!((pending.status, outcome), (CheckStatus::Conflict, None));
+        let (unknown, _) = intent_finding(&intent, &IntentState::Unknown("timeout".to_string()));
+        assert_eq!(unknown.status, CheckStatus::Conflict);
+
+        let report = ReconciliationReport {
+            at: Utc::now(),
+            wallet: "wallet".to_string(),
+            findings: vec![finding, pending, unknown],
+        };
+        assert!(!report.is_clean());
+        assert_eq!(conflict_counts(&report), "2 intent");
+        assert_eq!(report.summary().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_balance_drift_and_stray_wsol() {
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string();
+        let previous = BalanceSnapshot {
+            taken_at: Utc::now(),
+            taken_on: "shutdown".to_string(),
+            balances: BTreeMap::from([(NATIVE.to_string(), 5), (usdc.clone(), 100)]),
+        };
+        let current = BTreeMap::from([(NATIVE.to_string(), 5), (usdc.clone(), 80)]);
+        let findings = balance_findings(Some(&previous), &current);
+        let status: Vec<_> = findings.iter().map(|f| (f.subject.as_str(), f.status)).collect();
+        assert_eq!(status, vec![(usdc.as_str(), CheckStatus::Drift), (NATIVE, CheckStatus::Ok)]);
+        assert!(balance_findings(None, &current).iter().all(|f| f.status == CheckStatus::Ok));
+
+        let owner = Pubkey::new_unique();
+        let standing = wsol::wsol_account(&owner);
+        let native_mint = spl_token::native_mint::id().to_string();
+        let accounts = vec![
+            TokenAccount { address: standing, mint: native_mint.clone(), amount: 10 },
+            TokenAccount { address: Pubkey::new_unique(), mint: native_mint, amount: 3 },
+            TokenAccount { address: Pubkey::new_unique(), mint: usdc, amount: 80 },
+        ];
+        assert_eq!(stray_wsol_accounts(&accounts, &standing, true), vec![&accounts[1]]);
+        assert_eq!(stray_wsol_accounts(&accounts, &standing, false).len(), 2);
+    }
+}
@@ -0,0 +1,296 @@
+//! Price Snapshot Replay
+//!
+//! With `PRICE_SNAPSHOT_DIR` set, the prices fetched each tick are archived,
+//! before the spread guard, to one JSON line per tick in
+//! `prices-YYYY-MM-DD.jsonl`. Replaying a window of an archive runs those
+//! ticks through a fresh detector and the built-in strategies, with the
+//! bot's spread guard and price age limit, and returns what they would have
+//! found tick by tick. It answers "why didn't the bot trade at 14:32?"
+//! without touching the live detector: a tick with no opportunity was never
+//! an arbitrage; one with an opportunity points at sizing, risk or execution.
+//!
+//! Remote strategies and learned strategy state from before the window are
+//! not part of a replay.
+
+use crate::strategy_profile::StrategySettings;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use solana_arb_core::{
+    arbitrage::ArbitrageDetector, config::Config, pricing::spread_guard::SpreadGuard,
+    ArbitrageConfig, ArbitrageOpportunity, PriceData,
+};
+use solana_arb_strategies::Strategy;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// The prices fetched in one tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotTick {
+    pub at: DateTime<Utc>,
+    pub prices: Vec<PriceData>,
+}
+
+/// Appends each tick's prices to a daily archive file
+#[derive(Debug, Clone)]
+pub struct PriceSnapshots {
+    dir: PathBuf,
+}
+
+impl PriceSnapshots {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Archive file for `date`
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("prices-{}.jsonl", date.format("%Y-%m-%d")))
+    }
+
+    /// Archive file `name` in this directory; only plain file names are
+    /// accepted, so API callers cannot read elsewhere
+    pub fn resolve(&self, name: &str) -> Result<PathBuf, String> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("invalid snapshot file '{}'", name));
+        }
+        Ok(self.dir.join(name))
+    }
+
+    pub async fn record(&self, at: DateTime<Utc>, prices: &[PriceData]) -> std::io::Result<()> {
+        let tick = SnapshotTick {
+            at,
+            prices: prices.to_vec(),
+        };
+        let line = serde_json::to_string(&tick)? + "\n";
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path_for(at.date_naive()))
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+/// Load the ticks of an archive file from `from` up to and including `to`
+pub fn load_ticks(
+    path: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> anyhow::Result<Vec<SnapshotTick>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("cannot read {}: {}", path.display(), e))?;
+    let mut ticks = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tick: SnapshotTick = serde_json::from_str(line)
+            .map_err(|e| anyhow::anyhow!("{} line {}: {}", path.display(), index + 1, e))?;
+        if from.is_none_or(|from| tick.at >= from) && to.is_none_or(|to| tick.at <= to) {
+            ticks.push(tick);
+        }
+    }
+    Ok(ticks)
+}
+
+/// The live filters a replay applies before detection
+#[derive(Debug, Clone)]
+pub struct ReplaySettings {
+    pub spread_guard: SpreadGuard,
+    pub max_price_age_seconds: i64,
+    pub max_quote_skew_ms: u64,
+    pub strategies: StrategySettings,
+}
+
+impl ReplaySettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            spread_guard: SpreadGuard::new(config.max_spread_pct)
+                .with_venue_limits(&config.max_spread_pct_by_venue)
+                .unwrap_or_else(|_| SpreadGuard::new(config.max_spread_pct)),
+            max_price_age_seconds: config.max_price_age_seconds,
+            max_quote_skew_ms: config.max_quote_skew_ms,
+            strategies: StrategySettings::from_config(config),
+        }
+    }
+}
+
+/// An opportunity found in a replayed tick
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayOpportunity {
+    /// `detector` or the strategy name
+    pub source: String,
+    #[serde(flatten)]
+    pub opportunity: ArbitrageOpportunity,
+}
+
+/// What one replayed tick produced
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayTick {
+    pub at: DateTime<Utc>,
+    /// Prices archived for the tick
+    pub prices: usize,
+    /// `PAIR@dex` of prices the spread guard dropped
+    pub rejected_spread: Vec<String>,
+    pub opportunities: Vec<ReplayOpportunity>,
+}
+
+/// Detection state carried from one replayed tick to the next: a fresh
+/// detector and strategies, fed the way the main loop feeds them
+pub struct Replayer<'a> {
+    settings: &'a ReplaySettings,
+    detector: ArbitrageDetector,
+    strategies: Vec<Box<dyn Strategy>>,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(settings: &'a ReplaySettings) -> Self {
+        Self {
+            settings,
+            detector: ArbitrageDetector::new(ArbitrageConfig {
+                max_quote_skew_ms: settings.max_quote_skew_ms,
+                ..Default::default()
+            }),
+            strategies: settings.strategies.strategies(),
+        }
+    }
+
+    /// Prices the detector holds after the last tick, stale ones dropped
+    pub fn current_prices(&self) -> impl Iterator<Item = &PriceData> {
+        self.detector.get_prices().values()
+    }
+
+    /// Feed one tick and return what it produced
+    pub async fn step(&mut self, tick: &SnapshotTick) -> ReplayTick {
+        let settings = self.settings;
+        let (prices, too_wide): (Vec<_>, Vec<_>) = tick
+            .prices
+            .iter()
+            .cloned()
+            .partition(|p| settings.spread_guard.allows(p));
+        for price in &too_wide {
+            self.detector.remove_price(&price.pair, price.dex);
+        }
+        self.detector.update_prices(prices.clone());
+        self.detector
+            .clear_prices_older_than(tick.at, settings.max_price_age_seconds);
+        for strategy in &self.strategies {
+            for price in &prices {
+                let _ = strategy.update_state(price).await;
+            }
+        }
+
+        let mut opportunities: Vec<ReplayOpportunity> = if settings.strategies.uses_detector() {
+            self.detector
+                .find_all_opportunities()
+                .into_iter()
+                .map(|opportunity| ReplayOpportunity {
+                    source: "detector".to_string(),
+                    opportunity,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for strategy in &self.strategies {
+            if !strategy.is_ready().await {
+                continue;
+            }
+            if let Ok(opps) = strategy.analyze(&prices).await {
+                opportunities.extend(opps.into_iter().map(|opportunity| ReplayOpportunity {
+                    source: strategy.name().to_string(),
+                    opportunity: opportunity.attributed_to(strategy.name()),
+                }));
+            }
+        }
+
+        ReplayTick {
+            at: tick.at,
+            prices: tick.prices.len(),
+            rejected_spread: too_wide
+                .iter()
+                .map(|p| format!("{}@{}", p.pair, p.dex))
+                .collect(),
+            opportunities,
+        }
+    }
+}
+
+/// Run archived ticks through a fresh detector and strategies, the way the
+/// main loop does
+pub async fn replay(ticks: &[SnapshotTick], settings: &ReplaySettings) -> Vec<ReplayTick> {
+    let mut replayer = Replayer::new(settings);
+    let mut results = Vec::with_capacity(ticks.len());
+    for tick in ticks {
+        results.push(replayer.step(tick).await);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal::Decimal;
+    use solana_arb_core::{DexType, TokenPair};
+
+    fn price(dex: DexType, bid: i64, ask: i64, at: DateTime<Utc>) -> PriceData {
+        let mut price = PriceData::new(dex, TokenPair::new("SOL", "USDC"), Decimal::from(bid), Decimal::from(ask));
+        price.timestamp = at;
+        price
+    }
+
+    #[tokio::test]
+    async fn test_record_load_and_replay_window() {
+        let dir = std::env::temp_dir().join(format!("arb_replay_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let snapshots = PriceSnapshots::new(&dir);
+        let t0 = "2026-03-02T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        // Tick 1: no spread. Tick 2: Orca trades 2% above Raydium.
+        // Tick 3: Orca's quote is too wide and Raydium's price has aged out.
+        let ticks = [
+            vec![price(DexType::Raydium, 100, 100, t0), price(DexType::Orca, 100, 100, t0)],
+            vec![price(DexType::Orca, 102, 102, t0 + Duration::seconds(1))],
+            vec![price(DexType::Orca, 90, 110, t0 + Duration::seconds(30))],
+        ];
+        for prices in &ticks {
+            snapshots.record(prices[0].timestamp, prices).await.unwrap();
+        }
+        let path = snapshots.path_for(t0.date_naive());
+        assert_eq!(snapshots.resolve("prices-2026-03-02.jsonl").unwrap(), path);
+        assert!(snapshots.resolve("../secrets.json").is_err());
+
+        assert_eq!(load_ticks(&path, None, None).unwrap().len(), 3);
+        let window = load_ticks(&path, Some(t0 + Duration::seconds(1)), None).unwrap();
+        assert_eq!(window.len(), 2);
+
+        let settings = ReplaySettings {
+            spread_guard: SpreadGuard::new(1.0),
+            max_price_age_seconds: 5,
+            max_quote_skew_ms: 0,
+            strategies: StrategySettings::default(),
+        };
+        let results = replay(&load_ticks(&path, None, None).unwrap(), &settings).await;
+        assert!(results[0].opportunities.iter().all(|o| o.source != "detector"));
+        let found: Vec<_> = results[1]
+            .opportunities
+            .iter()
+            .filter(|o| o.source == "detector")
+            .collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].opportunity.buy_dex, found[0].opportunity.sell_dex),
+            (DexType::Raydium, DexType::Orca)
+        );
+        assert_eq!(results[2].rejected_spread, vec!["SOL/USDC@Orca"]);
+        assert!(results[2].opportunities.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
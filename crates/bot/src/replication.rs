@@ -0,0 +1,281 @@
+//! Warm Standby Replication
+//!
+//! A second instance started with `STANDBY_PRIMARY_URL` runs as a warm
+//! standby: it prices and detects like the primary but never trades. Every
+//! `REPLICATION_SYNC_MS` it pulls a [`ReplicaSnapshot`] from the primary's
+//! `GET /replication/snapshot` (positions, venue exposure, the day's trades,
+//! circuit breaker and blacklist) and applies it, so the daily loss limit
+//! keeps counting the primary's trades after a failover.
+//!
+//! A standby is promoted with `POST /replication/promote`, or by itself once
+//! no snapshot has arrived for `STANDBY_FAILOVER_SECS` after it has synced
+//! at least once. Replication runs over the bot API rather than gRPC or a
+//! shared database, so a pair of instances needs nothing else to run.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_arb_core::risk::RiskSnapshot;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::blacklist::BlacklistEntry;
+
+/// How recently a standby must have synced for the primary to count as
+/// alive when failover is manual-only
+const PRIMARY_ALIVE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Whether this instance trades or follows a primary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Primary,
+    Standby,
+}
+
+/// State replicated from the primary to a standby
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub risk: RiskSnapshot,
+    pub blacklist: Vec<BlacklistEntry>,
+}
+
+/// Replication state shown on `/status` and `/replication/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplicationStatus {
+    pub role: Role,
+    pub primary_url: Option<String>,
+    /// When the last snapshot was taken (primary) or applied (standby)
+    pub last_sync: Option<DateTime<Utc>>,
+    /// `taken_at` of the last snapshot applied
+    pub snapshot_taken_at: Option<DateTime<Utc>>,
+    pub snapshots_applied: u64,
+    pub last_error: Option<String>,
+    pub promoted_at: Option<DateTime<Utc>>,
+    pub promotion_reason: Option<String>,
+}
+
+struct SyncState {
+    synced: Option<Instant>,
+    latest: Option<ReplicaSnapshot>,
+    status: ReplicationStatus,
+}
+
+/// Role and replication progress of this instance
+pub struct Replication {
+    failover_after: Option<Duration>,
+    standby: AtomicBool,
+    state: Mutex<SyncState>,
+}
+
+impl Replication {
+    /// A standby of `primary_url`, or a primary without one. A standby
+    /// promotes itself after `failover_after` without a snapshot.
+    pub fn new(primary_url: Option<String>, failover_after: Option<Duration>) -> Self {
+        let role = if primary_url.is_some() {
+            Role::Standby
+        } else {
+            Role::Primary
+        };
+        Self {
+            failover_after,
+            standby: AtomicBool::new(role == Role::Standby),
+            state: Mutex::new(SyncState {
+                synced: None,
+                latest: None,
+                status: ReplicationStatus {
+                    role,
+                    primary_url,
+                    last_sync: None,
+                    snapshot_taken_at: None,
+                    snapshots_applied: 0,
+                    last_error: None,
+                    promoted_at: None,
+                    promotion_reason: None,
+                },
+            }),
+        }
+    }
+
+    pub fn from_config(config: &solana_arb_core::config::Config) -> Self {
+        Self::new(
+            config.standby_primary_url.clone(),
+            (config.standby_failover_secs > 0)
+                .then(|| Duration::from_secs(config.standby_failover_secs)),
+        )
+    }
+
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::SeqCst)
+    }
+
+    pub fn primary_url(&self) -> Option<String> {
+        self.lock().status.primary_url.clone()
+    }
+
+    /// Primary: make `snapshot` the one served to standbys
+    pub fn publish(&self, snapshot: ReplicaSnapshot) {
+        let mut state = self.lock();
+        state.status.last_sync = Some(snapshot.taken_at);
+        state.latest = Some(snapshot);
+    }
+
+    /// The snapshot to serve, `None` while this instance is a standby
+    pub fn latest(&self) -> Option<ReplicaSnapshot> {
+        if self.is_standby() {
+            return None;
+        }
+        self.lock().latest.clone()
+    }
+
+    /// Standby: whether `snapshot` should be applied. Snapshots older than
+    /// the last one applied are skipped; either way the primary is alive.
+    pub fn accept(&self, snapshot: &ReplicaSnapshot) -> bool {
+        let mut state = self.lock();
+        state.synced = Some(Instant::now());
+        state.status.last_sync = Some(Utc::now());
+        state.status.last_error = None;
+        if state
+            .status
+            .snapshot_taken_at
+            .is_some_and(|applied| snapshot.taken_at < applied)
+        {
+            return false;
+        }
+        state.status.snapshot_taken_at = Some(snapshot.taken_at);
+        state.status.snapshots_applied += 1;
+        true
+    }
+
+    /// Standby: a sync attempt failed. Returns true for the first failure
+    /// after a successful sync.
+    pub fn record_error(&self, error: &str) -> bool {
+        self.lock().status.last_error.replace(error.to_string()).is_none()
+    }
+
+    /// Whether a standby that has synced before has not heard from the
+    /// primary for the failover timeout
+    pub fn failover_due(&self) -> bool {
+        let Some(after) = self.failover_after else {
+            return false;
+        };
+        self.is_standby() && self.lock().synced.is_some_and(|synced| synced.elapsed() >= after)
+    }
+
+    /// Whether a standby has synced with the primary within the failover
+    /// timeout, so promoting it now would leave two instances trading
+    pub fn primary_alive(&self) -> bool {
+        let window = self.failover_after.unwrap_or(PRIMARY_ALIVE_WINDOW);
+        self.is_standby() && self.lock().synced.is_some_and(|synced| synced.elapsed() < window)
+    }
+
+    /// Take over as primary. Returns false if this already is one.
+    pub fn promote(&self, reason: &str) -> bool {
+        if !self.standby.swap(false, Ordering::SeqCst) {
+            return false;
+        }
+        let mut state = self.lock();
+        state.status.role = Role::Primary;
+        state.status.promoted_at = Some(Utc::now());
+        state.status.promotion_reason = Some(reason.to_string());
+        true
+    }
+
+    pub fn status(&self) -> ReplicationStatus {
+        self.lock().status.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SyncState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Pull the current snapshot from the primary's bot API
+pub async fn fetch_snapshot(
+    client: &reqwest::Client,
+    primary_url: &str,
+    admin_token: Option<&str>,
+) -> Result<ReplicaSnapshot> {
+    let url = format!("{}/replication/snapshot", primary_url.trim_end_matches('/'));
+    let mut request = client.get(&url);
+    if let Some(token) = admin_token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("primary unreachable at {}", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("primary answered {}", response.status());
+    }
+    response
+        .json()
+        .await
+        .context("invalid snapshot from primary")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use solana_arb_core::risk::{RiskManager, TradeOutcome};
+
+    #[tokio::test]
+    async fn test_standby_applies_snapshots_and_fails_over() {
+        let mut primary_risk = RiskManager::default();
+        primary_risk
+            .record_trade(TradeOutcome {
+                timestamp: Utc::now(),
+                pair: "SOL/USDC".to_string(),
+                profit_loss: Decimal::from(-40),
+                was_successful: false,
+            })
+            .await;
+        primary_risk.update_position("SOL/USDC", Decimal::from(250));
+
+        let primary = Replication::new(None, None);
+        assert!(!primary.is_standby());
+        assert!(primary.latest().is_none());
+        primary.publish(ReplicaSnapshot {
+            taken_at: Utc::now(),
+            risk: primary_risk.snapshot().await,
+            blacklist: Vec::new(),
+        });
+
+        // Over the wire and into the standby's risk manager
+        let json = serde_json::to_string(&primary.latest().unwrap()).unwrap();
+        let snapshot: ReplicaSnapshot = serde_json::from_str(&json).unwrap();
+        let standby = Replication::new(
+            Some("http://primary:8080".to_string()),
+            Some(Duration::from_millis(30)),
+        );
+        assert!(standby.is_standby() && standby.latest().is_none());
+        assert!(!standby.failover_due(), "never synced");
+        assert!(standby.accept(&snapshot));
+        assert!(standby.primary_alive());
+        let mut standby_risk = RiskManager::default();
+        standby_risk.restore(snapshot.risk.clone()).await;
+        assert_eq!(standby_risk.daily_pnl(), Decimal::from(-40));
+        assert_eq!(standby_risk.total_exposure(), Decimal::from(250));
+
+        // A stale snapshot still counts as contact but is not applied
+        let stale = ReplicaSnapshot {
+            taken_at: snapshot.taken_at - chrono::Duration::seconds(5),
+            ..snapshot
+        };
+        assert!(!standby.accept(&stale));
+        assert_eq!(standby.status().snapshots_applied, 1);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(standby.failover_due());
+        assert!(!standby.primary_alive());
+        assert!(standby.promote("primary silent"));
+        assert!(!standby.promote("again"));
+        assert!(!standby.failover_due());
+        let status = standby.status();
+        assert_eq!(status.role, Role::Primary);
+        assert_eq!(status.promotion_reason.as_deref(), Some("primary silent"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Submission Retry Policy
+//!
+//! A failed submission is classified by its error before anything is
+//! retried, because retrying the same transaction only helps with some
+//! failures:
+//!
+//! - `blockhash_expired`: the transaction is rebuilt from the same quote,
+//!   which gives it a fresh blockhash.
+//! - `slippage`: the route no longer returns the trade's minimum output.
+//!   The edge is gone; the trade is not retried.
+//! - `price_moved`: the pools the route crosses changed under it. A fresh
+//!   quote is taken and the trade is rebuilt from it, but only if the new
+//!   quote still meets the original minimum output.
+//! - `program_error`: any other program failure fails the same way again.
+//!   The trade stops at once with `failure_kind: "program_error"` and a
+//!   critical alert.
+//! - `throttled` and `transient` (timeouts, connection errors): the same
+//!   transaction is resent after exponential backoff.
+//!
+//! Every failure is counted in `arb_submit_failures_total{cause, action}`.
+
+use serde::Serialize;
+
+/// Why a submission failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCause {
+    BlockhashExpired,
+    Slippage,
+    PriceMoved,
+    ProgramError,
+    Throttled,
+    Transient,
+}
+
+/// What the executor does about a failed submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryAction {
+    /// Rebuild from the same quote with a fresh blockhash
+    Rebuild,
+    /// Rebuild from a fresh quote
+    Requote,
+    /// Resend the same transaction after a backoff
+    Backoff,
+    /// Give up without retrying
+    Abort,
+    /// Give up and alert
+    Escalate,
+}
+
+/// Error text of blockhashes that expired or were never seen
+const BLOCKHASH_MARKERS: &[&str] = &[
+    "BlockhashNotFound",
+    "Blockhash not found",
+    "blockhash expired",
+    "block height exceeded",
+    "TransactionExpired",
+];
+
+/// Jupiter's own minimum output check: `SlippageToleranceExceeded`, custom
+/// error 6001
+const SLIPPAGE_MARKERS: &[&str] = &[
+    "SlippageToleranceExceeded",
+    "Slippage tolerance exceeded",
+    "custom program error: 0x1771",
+    "Custom(6001)",
+];
+
+/// Pool state the route was built against changed before it landed
+const PRICE_MOVED_MARKERS: &[&str] = &[
+    "price moved",
+    "TickArraySequenceInvalid",
+    "InvalidTickArraySequence",
+    "TickArrayIndexOutofBounds",
+    "stale quote",
+];
+
+/// Any other instruction failure; simulation errors carry the
+/// `TransactionError` debug form
+const PROGRAM_ERROR_MARKERS: &[&str] = &[
+    "custom program error",
+    "InstructionError",
+    "InsufficientFunds",
+    "insufficient funds",
+];
+
+impl FailureCause {
+    /// Classify a submission error by its text, outermost context included
+    pub fn classify(error: &dyn std::fmt::Display) -> Self {
+        let text = format!("{:#}", error);
+        let has = |markers: &[&str]| markers.iter().any(|m| text.contains(m));
+        if has(BLOCKHASH_MARKERS) {
+            Self::BlockhashExpired
+        } else if has(SLIPPAGE_MARKERS) {
+            Self::Slippage
+        } else if has(PRICE_MOVED_MARKERS) {
+            Self::PriceMoved
+        } else if has(PROGRAM_ERROR_MARKERS) {
+            Self::ProgramError
+        } else if text.contains("429") || text.contains("Too Many Requests") {
+            Self::Throttled
+        } else {
+            Self::Transient
+        }
+    }
+
+    pub fn action(self) -> RetryAction {
+        match self {
+            Self::BlockhashExpired => RetryAction::Rebuild,
+            Self::Slippage => RetryAction::Abort,
+            Self::PriceMoved => RetryAction::Requote,
+            Self::ProgramError => RetryAction::Escalate,
+            Self::Throttled | Self::Transient => RetryAction::Backoff,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BlockhashExpired => "blockhash_expired",
+            Self::Slippage => "slippage",
+            Self::PriceMoved => "price_moved",
+            Self::ProgramError => "program_error",
+            Self::Throttled => "throttled",
+            Self::Transient => "transient",
+        }
+    }
+}
+
+impl RetryAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Rebuild => "rebuild",
+            Self::Requote => "requote",
+            Self::Backoff => "backoff",
+            Self::Abort => "abort",
+            Self::Escalate => "escalate",
+        }
+    }
+
+    /// Whether the trade ends here
+    pub fn is_final(self) -> bool {
+        matches!(self, Self::Abort | Self::Escalate)
+    }
+}
+
+/// A submission a program failed, escalated rather than retried
+#[derive(Debug, Clone)]
+pub struct ProgramFailure {
+    pub error: String,
+}
+
+impl std::fmt::Display for ProgramFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Program error, not retried: {}", self.error)
+    }
+}
+
+impl std::error::Error for ProgramFailure {}
+
+/// Whether `error` is (or wraps) a [`ProgramFailure`]
+pub fn is_program_failure(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ProgramFailure>().is_some()
+}
+
+/// Whether a fresh quote still delivers the minimum output the original
+/// one accepted, so re-quoting never loosens the trade's loss bound
+pub fn requote_acceptable(original: &serde_json::Value, fresh: &serde_json::Value) -> bool {
+    let atoms = |quote: &serde_json::Value, field: &str| {
+        quote.get(field)?.as_str()?.parse::<u64>().ok()
+    };
+    match (atoms(original, "otherAmountThreshold"), atoms(fresh, "otherAmountThreshold")) {
+        (Some(min_out), Some(fresh_min_out)) => fresh_min_out >= min_out,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use serde_json::json;
+
+    #[test]
+    fn test_failures_map_to_cause_specific_actions() {
+        let cases = [
+            (
+                anyhow!("RPC response error -32002: Transaction simulation failed: Blockhash not found"),
+                FailureCause::BlockhashExpired,
+                RetryAction::Rebuild,
+            ),
+            (
+                anyhow!("Simulation failed: InstructionError(3, Custom(6001))"),
+                FailureCause::Slippage,
+                RetryAction::Abort,
+            ),
+            (
+                anyhow!("Program log: AnchorError: TickArraySequenceInvalidIndex"),
+                FailureCause::PriceMoved,
+                RetryAction::Requote,
+            ),
+            (
+                anyhow!("Simulation failed: InstructionError(2, Custom(1))"),
+                FailureCause::ProgramError,
+                RetryAction::Escalate,
+            ),
+            (anyhow!("HTTP status client error (429 Too Many Requests)"), FailureCause::Throttled, RetryAction::Backoff),
+            (anyhow!("operation timed out"), FailureCause::Transient, RetryAction::Backoff),
+        ];
+        for (error, cause, action) in cases {
+            assert_eq!(FailureCause::classify(&error), cause, "{}", error);
+            assert_eq!(cause.action(), action);
+        }
+        // Context wrapping the RPC error does not hide it
+        let wrapped = anyhow!("0x1771").context("custom program error: 0x1771");
+        assert_eq!(FailureCause::classify(&wrapped), FailureCause::Slippage);
+        assert!(RetryAction::Escalate.is_final() && !RetryAction::Requote.is_final());
+        let escalated = anyhow::Error::new(ProgramFailure { error: "Custom(1)".to_string() });
+        assert!(is_program_failure(&escalated.context("Submission failed")));
+
+        let original = json!({ "otherAmountThreshold": "1000" });
+        assert!(requote_acceptable(&original, &json!({ "otherAmountThreshold": "1000" })));
+        assert!(!requote_acceptable(&original, &json!({ "otherAmountThreshold": "999" })));
+        assert!(!requote_acceptable(&original, &json!({})));
+    }
+}
@@ -0,0 +1,353 @@
+//! Daily Risk Report
+//!
+//! During the day the bot journals what the end-of-day numbers cannot
+//! show: total exposure sampled every status round, circuit breaker state
+//! changes and breached risk limits. Entries are appended to
+//! `RISK_REPORT_DIR/journal-<date>.jsonl`, so a restart keeps the morning.
+//!
+//! When the day closes, the journal and the daily risk digest are rendered
+//! to `RISK_REPORT_DIR/risk-<date>.html`: the exposure timeline, the VaR
+//! forecast against the realized P&L, breaker activations, limit
+//! utilization and the day's largest losing trade. The page carries a print
+//! stylesheet, so a PDF is a browser "print to PDF" away. The daily digest
+//! alert links the report.
+
+use crate::status_page::{escape, replace};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::risk::{DailyRiskDigest, MoneyLimits};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Something the report needs that the end-of-day digest does not keep
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEvent {
+    Exposure { total: Decimal, limit: Decimal },
+    Breaker { state: String },
+    LimitBreached { limit_type: String, current: f64, max: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Append-only journal, one file per UTC day
+#[derive(Debug, Clone)]
+pub struct RiskJournal {
+    dir: PathBuf,
+}
+
+impl RiskJournal {
+    /// `None` when `dir` is empty (reports disabled)
+    pub fn new(dir: &str) -> Option<Self> {
+        (!dir.is_empty()).then(|| Self { dir: PathBuf::from(dir) })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, day: NaiveDate) -> PathBuf {
+        self.dir.join(format!("journal-{}.jsonl", day))
+    }
+
+    pub fn record(&self, event: JournalEvent) -> std::io::Result<()> {
+        let entry = JournalEntry { at: Utc::now(), event };
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(entry.at.date_naive()))?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+    }
+
+    /// The day's entries, oldest first. Unreadable lines are skipped.
+    pub fn read(&self, day: NaiveDate) -> Vec<JournalEntry> {
+        fs::read_to_string(self.path(day))
+            .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// One closed day's risk report
+#[derive(Debug, Clone)]
+pub struct RiskReport {
+    pub digest: DailyRiskDigest,
+    pub limits: MoneyLimits,
+    pub journal: Vec<JournalEntry>,
+}
+
+impl RiskReport {
+    fn exposure(&self) -> Vec<(DateTime<Utc>, Decimal, Decimal)> {
+        self.journal
+            .iter()
+            .filter_map(|e| match &e.event {
+                JournalEvent::Exposure { total, limit } => Some((e.at, *total, *limit)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Times the circuit breaker opened
+    pub fn breaker_activations(&self) -> Vec<DateTime<Utc>> {
+        self.journal
+            .iter()
+            .filter(|e| matches!(&e.event, JournalEvent::Breaker { state } if state == "Open"))
+            .map(|e| e.at)
+            .collect()
+    }
+
+    /// Peak sampled exposure as a percentage of its limit at the time
+    pub fn peak_exposure_pct(&self) -> Option<Decimal> {
+        self.exposure()
+            .into_iter()
+            .filter(|(_, _, limit)| *limit > Decimal::ZERO)
+            .map(|(_, total, limit)| (total / limit * Decimal::from(100)).round_dp(1))
+            .max()
+    }
+
+    /// The day's loss as a percentage of the daily loss limit
+    pub fn daily_loss_pct(&self) -> Option<Decimal> {
+        (self.limits.max_daily_loss > Decimal::ZERO).then(|| {
+            (-self.digest.daily_pnl).max(Decimal::ZERO) / self.limits.max_daily_loss * Decimal::from(100)
+        })
+        .map(|pct| pct.round_dp(1))
+    }
+
+    pub fn to_html(&self) -> String {
+        let digest = &self.digest;
+        let pct = |p: Option<Decimal>| p.map_or("-".to_string(), |p| format!("{}%", p));
+
+        let var_rows = [
+            ("Forecast for the day", digest.day_forecast.map_or("-".to_string(), usd)),
+            ("Realized P&amp;L", usd(digest.daily_pnl)),
+            (
+                "Outcome",
+                match digest.var_breached {
+                    Some(true) => "Loss exceeded the forecast".to_string(),
+                    Some(false) => "Forecast held".to_string(),
+                    None => "No forecast to backtest".to_string(),
+                },
+            ),
+            ("Forecast for the next day", usd(digest.var_forecast.var)),
+            (
+                "Backtest",
+                format!("{}/{} breaches", digest.backtest.breaches, digest.backtest.observations),
+            ),
+        ];
+
+        let breaches: Vec<String> = self
+            .journal
+            .iter()
+            .filter_map(|e| match &e.event {
+                JournalEvent::LimitBreached { limit_type, current, max } => Some(format!(
+                    "<tr><td>{}</td><td>{}</td><td>{:.2} / {:.2}</td></tr>\n",
+                    e.at.format("%H:%M:%S"),
+                    escape(limit_type),
+                    current,
+                    max
+                )),
+                _ => None,
+            })
+            .collect();
+        let limit_rows = [
+            ("Peak exposure of limit", pct(self.peak_exposure_pct())),
+            ("Daily loss of limit", pct(self.daily_loss_pct())),
+            ("Limit breaches", breaches.len().to_string()),
+        ];
+
+        let activations = self.breaker_activations();
+        let breaker = if activations.is_empty() {
+            "<p>The circuit breaker did not open.</p>".to_string()
+        } else {
+            let times: Vec<String> = activations.iter().map(|t| t.format("%H:%M:%S").to_string()).collect();
+            format!("<p>Opened {} time(s): {}</p>", activations.len(), times.join(", "))
+        };
+
+        let worst = match &digest.worst_trade {
+            Some(t) => format!(
+                "<p>{} at {}: {}</p>",
+                escape(&t.pair),
+                t.timestamp.format("%H:%M:%S"),
+                usd(t.profit_loss)
+            ),
+            None => "<p>No losing trades.</p>".to_string(),
+        };
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Risk report {date}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em auto; max-width: 48em; }}
+th {{ text-align: left; padding-right: 2em; }}
+td {{ padding-right: 2em; }}
+svg {{ border: 1px solid #ccc; }}
+@media print {{ body {{ margin: 0; max-width: none; }} h2 {{ break-after: avoid; }} }}
+</style>
+</head>
+<body>
+<h1>Risk report {date}</h1>
+<p>Trades: {trades} | P&amp;L: {pnl}</p>
+<h2>Exposure</h2>
+{timeline}
+<h2>VaR vs realized</h2>
+<table>
+{var_rows}</table>
+<h2>Circuit breaker</h2>
+{breaker}
+<h2>Limit utilization</h2>
+<table>
+{limit_rows}</table>
+{breaches}
+<h2>Largest drawdown trade</h2>
+{worst}
+</body>
+</html>
+"#,
+            date = digest.date,
+            trades = digest.trades,
+            pnl = usd(digest.daily_pnl),
+            timeline = self.timeline_svg(),
+            var_rows = rows(&var_rows),
+            breaker = breaker,
+            limit_rows = rows(&limit_rows),
+            breaches = if breaches.is_empty() {
+                String::new()
+            } else {
+                format!("<table>\n<tr><th>Time</th><th>Limit</th><th>Current / max</th></tr>\n{}</table>", breaches.concat())
+            },
+            worst = worst,
+        )
+    }
+
+    /// Exposure (solid) against its limit (dashed) over the day
+    fn timeline_svg(&self) -> String {
+        let samples = self.exposure();
+        let Some(top) = samples.iter().map(|(_, total, limit)| (*total).max(*limit)).max() else {
+            return "<p>No exposure samples were journaled.</p>".to_string();
+        };
+        let top = top.max(Decimal::ONE);
+        let (width, height) = (Decimal::from(600), Decimal::from(150));
+        let day_start = self.digest.date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let point = |at: DateTime<Utc>, value: Decimal| {
+            let x = Decimal::from((at - day_start).num_seconds().clamp(0, 86_400)) / Decimal::from(86_400) * width;
+            let y = height - value / top * height;
+            format!("{},{}", x.round_dp(1), y.round_dp(1))
+        };
+        let line = |values: Vec<String>| values.join(" ");
+        format!(
+            r##"<svg viewBox="0 0 600 150" width="600" height="150">
+<polyline fill="none" stroke="#c00" stroke-dasharray="4" points="{limit}"/>
+<polyline fill="none" stroke="#036" points="{total}"/>
+</svg>
+<p>Peak {peak} against a scale of {top}, 00:00 to 24:00 UTC.</p>"##,
+            limit = line(samples.iter().map(|(at, _, limit)| point(*at, *limit)).collect()),
+            total = line(samples.iter().map(|(at, total, _)| point(*at, *total)).collect()),
+            peak = usd(samples.iter().map(|(_, total, _)| *total).max().unwrap_or_default()),
+            top = usd(top),
+        )
+    }
+
+    /// Write `risk-<date>.html` to `dir`, returning its path
+    pub fn write(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("risk-{}.html", self.digest.date));
+        replace(&path, &self.to_html())?;
+        Ok(path)
+    }
+}
+
+fn usd(amount: Decimal) -> String {
+    format!("${:.2}", amount)
+}
+
+fn rows(rows: &[(&str, String)]) -> String {
+    rows.iter()
+        .map(|(name, value)| format!("<tr><th>{}</th><td>{}</td></tr>\n", name, escape(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::risk::var::{VarBacktest, VarEstimate, VarMethod};
+    use solana_arb_core::risk::TradeOutcome;
+
+    #[test]
+    fn test_report_renders_journaled_day() {
+        let dir = std::env::temp_dir().join(format!("risk-report-{}", solana_arb_core::Uuid::new_v4()));
+        let journal = RiskJournal::new(dir.to_str().unwrap()).unwrap();
+        assert!(RiskJournal::new("").is_none());
+
+        for event in [
+            JournalEvent::Exposure { total: Decimal::from(400), limit: Decimal::from(1000) },
+            JournalEvent::Breaker { state: "Open".into() },
+            JournalEvent::LimitBreached { limit_type: "daily_loss".into(), current: 120.0, max: 100.0 },
+            JournalEvent::Breaker { state: "Closed".into() },
+            JournalEvent::Exposure { total: Decimal::from(850), limit: Decimal::from(1000) },
+        ] {
+            journal.record(event).unwrap();
+        }
+        let today = Utc::now().date_naive();
+        let entries = journal.read(today);
+        assert_eq!(entries.len(), 5);
+
+        let estimate = VarEstimate {
+            method: VarMethod::Historical,
+            confidence: 0.95,
+            horizon_periods: 1,
+            var: Decimal::from(90),
+            expected_shortfall: Decimal::from(110),
+            scenarios: 100,
+        };
+        let report = RiskReport {
+            digest: DailyRiskDigest {
+                date: today,
+                trades: 12,
+                daily_pnl: Decimal::from(-50),
+                worst_trade: Some(TradeOutcome {
+                    timestamp: Utc::now(),
+                    pair: "SOL/USDC".into(),
+                    profit_loss: Decimal::from(-35),
+                    was_successful: true,
+                }),
+                day_forecast: Some(Decimal::from(80)),
+                var_forecast: estimate.clone(),
+                estimates: vec![estimate],
+                var_breached: Some(false),
+                backtest: VarBacktest::new(10).summary(0.95),
+            },
+            limits: MoneyLimits {
+                max_position_size: Decimal::from(100),
+                max_total_exposure: Decimal::from(1000),
+                max_daily_loss: Decimal::from(200),
+                max_venue_exposure: Decimal::from(500),
+                canary_size: Decimal::from(10),
+                unwind_step_size: Decimal::from(50),
+            },
+            journal: entries,
+        };
+        assert_eq!(report.breaker_activations().len(), 1);
+        assert_eq!(report.peak_exposure_pct(), Some(Decimal::new(850, 1)));
+        assert_eq!(report.daily_loss_pct(), Some(Decimal::new(250, 1)));
+
+        let path = report.write(&dir).unwrap();
+        assert_eq!(path, dir.join(format!("risk-{}.html", today)));
+        let html = fs::read_to_string(&path).unwrap();
+        assert!(html.contains("<th>Forecast for the day</th><td>$80.00</td>"));
+        assert!(html.contains("Opened 1 time(s)"));
+        assert!(html.contains("daily_loss"));
+        assert!(html.contains("SOL/USDC at"));
+        assert!(html.contains("<polyline"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+//! Route Repair
+//!
+//! A standard trade is a single swap from the pair's quote token into its
+//! base token, submitted outside any atomic bundle. When its submission
+//! fails, the swap may still land, or land in part, and leave base tokens
+//! in the wallet that the trade's record says were never bought.
+//!
+//! With `ROUTE_REPAIR_ENABLED=true` the bot reads the wallet's base balance
+//! before each live standard trade. After one fails past the checks that
+//! stop it before submission, it waits `ROUTE_REPAIR_SETTLE_SECS` and reads
+//! the balance again. An increase worth at least `ROUTE_REPAIR_MIN_USD` is
+//! sold back to the quote token along Jupiter's best-paying route. The sale
+//! is recorded in the history with `trade_type: "REPAIR"` and alerted,
+//! whether it succeeds or not.
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use solana_arb_core::config::Config;
+use solana_arb_core::TradeResult;
+use std::time::Duration;
+
+/// When and how much to repair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairPolicy {
+    /// Wait before the wallet is checked, so a late landing is seen
+    pub settle: Duration,
+    pub min_usd: Decimal,
+}
+
+impl RepairPolicy {
+    /// `None` when repairs are disabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config.route_repair_enabled.then(|| Self {
+            settle: Duration::from_secs(config.route_repair_settle_secs),
+            min_usd: Decimal::from_f64(config.route_repair_min_usd).unwrap_or(Decimal::ONE),
+        })
+    }
+}
+
+/// Base token a failed trade left in the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stranded {
+    pub base_amount: Decimal,
+    /// At the current mid price
+    pub value_usd: Decimal,
+    /// `value_usd` less what the amount cost at the trade's buy price
+    pub pnl_usd: Decimal,
+}
+
+impl Stranded {
+    /// The base balance gained between `before` and `after`, if it is worth
+    /// at least `min_usd` at `price`
+    pub fn find(
+        before: Decimal,
+        after: Decimal,
+        price: Decimal,
+        buy_price: Decimal,
+        min_usd: Decimal,
+    ) -> Option<Self> {
+        let base_amount = after - before;
+        let value_usd = base_amount * price;
+        (base_amount > Decimal::ZERO && value_usd >= min_usd).then(|| Self {
+            base_amount,
+            value_usd: value_usd.round_dp(6),
+            pnl_usd: (base_amount * (price - buy_price)).round_dp(6),
+        })
+    }
+}
+
+/// Whether a failed trade may have moved funds. `None` is an executor
+/// error; results with a failure kind stopped before submission.
+pub fn may_be_stranded(result: Option<&TradeResult>) -> bool {
+    match result {
+        Some(result) => !result.success && result.failure_kind.is_none(),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::{FailureKind, Uuid};
+
+    #[test]
+    fn test_failed_trade_leaving_base_token_is_repaired() {
+        let policy = RepairPolicy::from_config(&Config {
+            route_repair_enabled: true,
+            ..Config::default()
+        })
+        .unwrap();
+        assert_eq!(policy.settle, Duration::from_secs(10));
+        assert_eq!(RepairPolicy::from_config(&Config::default()), None);
+
+        // 0.5 SOL landed from a trade bought at $100; it now sells at $99
+        let stranded = Stranded::find(
+            Decimal::new(2, 0),
+            Decimal::new(25, 1),
+            Decimal::from(99),
+            Decimal::from(100),
+            policy.min_usd,
+        )
+        .unwrap();
+        assert_eq!(stranded.base_amount, Decimal::new(5, 1));
+        assert_eq!(stranded.value_usd, Decimal::new(495, 1));
+        assert_eq!(stranded.pnl_usd, Decimal::new(-5, 1));
+
+        // Dust, an unchanged balance and a drop are left alone
+        let dust = Decimal::new(2000001, 6);
+        assert_eq!(Stranded::find(Decimal::from(2), dust, Decimal::from(99), Decimal::from(100), policy.min_usd), None);
+        assert_eq!(Stranded::find(Decimal::ONE, Decimal::ONE, Decimal::from(99), Decimal::from(100), policy.min_usd), None);
+        assert_eq!(Stranded::find(Decimal::ONE, Decimal::ZERO, Decimal::from(99), Decimal::from(100), policy.min_usd), None);
+
+        let mut result = TradeResult {
+            opportunity_id: Uuid::new_v4(),
+            signature: None,
+            success: false,
+            actual_profit: Decimal::ZERO,
+            executed_at: chrono::Utc::now(),
+            error: Some("Submission failed: blockhash expired".into()),
+            simulated_profit: None,
+            failure_kind: None,
+        };
+        assert!(may_be_stranded(Some(&result)));
+        assert!(may_be_stranded(None));
+        result.failure_kind = Some(FailureKind::ComplianceVeto);
+        assert!(!may_be_stranded(Some(&result)));
+        result.failure_kind = None;
+        result.success = true;
+        assert!(!may_be_stranded(Some(&result)));
+    }
+}
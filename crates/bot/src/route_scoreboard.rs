@@ -0,0 +1,254 @@
+//! Route Scoreboard
+//!
+//! Detection quality per (buy DEX, sell DEX) route: how often a route is
+//! detected, how often it is executed and won, and how much of the detected
+//! edge is actually realized. Routes whose realized edge keeps collapsing are
+//! deprioritized for a while: their opportunities are only taken when no
+//! other route has one.
+
+use crate::config_manager::RoutingConfig;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::{ArbitrageOpportunity, DexType};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Executions kept per route for the edge capture average
+const CAPTURE_WINDOW: usize = 20;
+
+#[derive(Debug, Default)]
+struct RouteStats {
+    detections: u64,
+    executions: u64,
+    wins: u64,
+    detected_edge_sum_pct: f64,
+    realized_edge_sum_pct: f64,
+    /// Realized / detected edge of recent executions
+    captures: VecDeque<f64>,
+    deprioritized_until: Option<Instant>,
+}
+
+impl RouteStats {
+    fn edge_capture(&self) -> Option<f64> {
+        (!self.captures.is_empty())
+            .then(|| self.captures.iter().sum::<f64>() / self.captures.len() as f64)
+    }
+}
+
+/// One row of the scoreboard
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteScore {
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub detections: u64,
+    pub executions: u64,
+    /// Executions per detection
+    pub execution_rate: f64,
+    /// Profitable executions per execution
+    pub win_rate: f64,
+    pub avg_detected_edge_pct: f64,
+    pub avg_realized_edge_pct: f64,
+    /// Average realized / detected edge over recent executions
+    pub edge_capture: Option<f64>,
+    pub deprioritized: bool,
+}
+
+/// Detection and execution statistics for every route seen
+#[derive(Debug)]
+pub struct RouteScoreboard {
+    config: RoutingConfig,
+    routes: HashMap<(DexType, DexType), RouteStats>,
+}
+
+impl RouteScoreboard {
+    pub fn new(config: RoutingConfig) -> Self {
+        Self {
+            config,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Replace the thresholds (hot reload)
+    pub fn set_config(&mut self, config: RoutingConfig) {
+        self.config = config;
+    }
+
+    pub fn record_detections(&mut self, opportunities: &[ArbitrageOpportunity]) {
+        for opp in opportunities {
+            self.routes
+                .entry((opp.buy_dex, opp.sell_dex))
+                .or_default()
+                .detections += 1;
+        }
+    }
+
+    /// Record an executed opportunity and the P&L realized on `size`.
+    /// Failed executions realize nothing.
+    pub fn record_execution(&mut self, opp: &ArbitrageOpportunity, size: Decimal, profit: Decimal) {
+        if size <= Decimal::ZERO {
+            return;
+        }
+        let detected = opp.net_profit_pct.to_f64().unwrap_or(0.0);
+        let realized = (profit / size * Decimal::from(100)).to_f64().unwrap_or(0.0);
+
+        let stats = self.routes.entry((opp.buy_dex, opp.sell_dex)).or_default();
+        stats.executions += 1;
+        if profit > Decimal::ZERO {
+            stats.wins += 1;
+        }
+        stats.detected_edge_sum_pct += detected;
+        stats.realized_edge_sum_pct += realized;
+        if detected > 0.0 {
+            stats.captures.push_back(realized / detected);
+            if stats.captures.len() > CAPTURE_WINDOW {
+                stats.captures.pop_front();
+            }
+        }
+
+        let collapsed = stats.captures.len() >= self.config.edge_capture_min_samples
+            && stats
+                .edge_capture()
+                .is_some_and(|capture| capture < self.config.min_edge_capture);
+        if collapsed && stats.deprioritized_until.is_none() {
+            stats.deprioritized_until =
+                Some(Instant::now() + Duration::from_secs(self.config.route_deprioritize_secs));
+            warn!(
+                "📉 Deprioritizing {} → {} for {}s: realizing {:.0}% of detected edge",
+                opp.buy_dex,
+                opp.sell_dex,
+                self.config.route_deprioritize_secs,
+                stats.edge_capture().unwrap_or(0.0) * 100.0
+            );
+        }
+    }
+
+    pub fn is_deprioritized(&self, buy_dex: DexType, sell_dex: DexType) -> bool {
+        self.routes
+            .get(&(buy_dex, sell_dex))
+            .and_then(|stats| stats.deprioritized_until)
+            .is_some_and(|until| until > Instant::now())
+    }
+
+    /// Give routes whose deprioritization has expired a fresh start
+    pub fn expire_deprioritizations(&mut self) {
+        let now = Instant::now();
+        for ((buy_dex, sell_dex), stats) in self.routes.iter_mut() {
+            if stats.deprioritized_until.is_some_and(|until| until <= now) {
+                info!("⏱️ {} → {} no longer deprioritized", buy_dex, sell_dex);
+                stats.deprioritized_until = None;
+                stats.captures.clear();
+            }
+        }
+    }
+
+    /// Move opportunities on deprioritized routes behind all others,
+    /// otherwise keeping their order
+    pub fn prioritize(&self, opportunities: &mut [ArbitrageOpportunity]) {
+        opportunities.sort_by_key(|opp| self.is_deprioritized(opp.buy_dex, opp.sell_dex));
+    }
+
+    /// Every route, most detected first
+    pub fn scores(&self) -> Vec<RouteScore> {
+        let mut scores: Vec<RouteScore> = self
+            .routes
+            .iter()
+            .map(|((buy_dex, sell_dex), stats)| {
+                let per_execution = |sum: f64| {
+                    if stats.executions == 0 {
+                        0.0
+                    } else {
+                        sum / stats.executions as f64
+                    }
+                };
+                RouteScore {
+                    buy_dex: buy_dex.display_name().to_string(),
+                    sell_dex: sell_dex.display_name().to_string(),
+                    detections: stats.detections,
+                    executions: stats.executions,
+                    execution_rate: if stats.detections == 0 {
+                        0.0
+                    } else {
+                        stats.executions as f64 / stats.detections as f64
+                    },
+                    win_rate: per_execution(stats.wins as f64),
+                    avg_detected_edge_pct: per_execution(stats.detected_edge_sum_pct),
+                    avg_realized_edge_pct: per_execution(stats.realized_edge_sum_pct),
+                    edge_capture: stats.edge_capture(),
+                    deprioritized: self.is_deprioritized(*buy_dex, *sell_dex),
+                }
+            })
+            .collect();
+        scores.sort_by(|a, b| {
+            b.detections
+                .cmp(&a.detections)
+                .then_with(|| (&a.buy_dex, &a.sell_dex).cmp(&(&b.buy_dex, &b.sell_dex)))
+        });
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use chrono::Utc;
+    use solana_arb_core::{TokenPair, Uuid};
+
+    fn opp(buy_dex: DexType, sell_dex: DexType, edge_pct: i64) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex,
+            sell_dex,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::from(edge_pct),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        }
+    }
+
+    #[test]
+    fn test_scores_and_deprioritization() {
+        let mut board = RouteScoreboard::new(RoutingConfig {
+            edge_capture_min_samples: 3,
+            ..RoutingConfig::default()
+        });
+        let good = opp(DexType::Raydium, DexType::Orca, 1);
+        let bad = opp(DexType::Orca, DexType::Raydium, 1);
+        board.record_detections(&[good.clone(), good.clone(), bad.clone()]);
+
+        // 1% detected, 1% realized vs. 0.1% realized
+        board.record_execution(&good, Decimal::from(100), Decimal::ONE);
+        for _ in 0..2 {
+            board.record_execution(&bad, Decimal::from(100), Decimal::new(1, 1));
+            assert!(!board.is_deprioritized(DexType::Orca, DexType::Raydium));
+        }
+        board.record_execution(&bad, Decimal::ZERO, Decimal::ZERO);
+        board.record_execution(&bad, Decimal::from(100), Decimal::new(1, 1));
+        assert!(board.is_deprioritized(DexType::Orca, DexType::Raydium));
+        assert!(!board.is_deprioritized(DexType::Raydium, DexType::Orca));
+
+        let scores = board.scores();
+        assert_eq!(scores[0].buy_dex, "Raydium");
+        assert_eq!(scores[0].execution_rate, 0.5);
+        assert_eq!(scores[0].win_rate, 1.0);
+        assert_eq!(scores[0].edge_capture, Some(1.0));
+        assert_eq!(scores[1].executions, 3);
+        assert!(scores[1].deprioritized);
+        assert!((scores[1].avg_realized_edge_pct - 0.1).abs() < 1e-9);
+
+        let mut opportunities = vec![bad.clone(), good.clone()];
+        board.prioritize(&mut opportunities);
+        assert_eq!(opportunities[0].buy_dex, DexType::Raydium);
+    }
+}
@@ -0,0 +1,334 @@
+//! Jupiter Route Filtering
+//!
+//! Keeps Jupiter quotes away from venues that are blacklisted in config or
+//! that keep failing. Venues with too many consecutive failed trades are
+//! excluded for a cooldown period, then given another chance. Quoted routes
+//! are checked against the venues the opportunity was detected on.
+
+use crate::config_manager::RoutingConfig;
+use serde::Serialize;
+use solana_arb_core::DexType;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Jupiter AMM labels that belong to a venue
+pub fn jupiter_labels(dex: DexType) -> &'static [&'static str] {
+    match dex {
+        DexType::Raydium => &["Raydium", "Raydium CLMM", "Raydium CP"],
+        DexType::Orca => &["Whirlpool", "Orca V2"],
+        DexType::Lifinity => &["Lifinity V2"],
+        DexType::Meteora => &["Meteora", "Meteora DLMM"],
+        DexType::Phoenix => &["Phoenix"],
+        DexType::Sanctum => &["Sanctum", "Sanctum Infinity"],
+        // Jupiter is the aggregator itself, not an AMM
+        DexType::Jupiter => &[],
+    }
+}
+
+/// Effective `excludeDexes` / `onlyDexes` for Jupiter quotes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RouteFilter {
+    pub exclude_dexes: Vec<String>,
+    pub only_dexes: Vec<String>,
+}
+
+impl RouteFilter {
+    /// Query string suffix for the Jupiter `/quote` endpoint (empty if no filter)
+    pub fn query_params(&self) -> String {
+        let mut params = String::new();
+        if !self.exclude_dexes.is_empty() {
+            params.push_str("&excludeDexes=");
+            params.push_str(&encode_list(&self.exclude_dexes));
+        }
+        if !self.only_dexes.is_empty() {
+            params.push_str("&onlyDexes=");
+            params.push_str(&encode_list(&self.only_dexes));
+        }
+        params
+    }
+}
+
+fn encode_list(labels: &[String]) -> String {
+    labels
+        .iter()
+        .map(|l| l.replace(' ', "+"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Venue a Jupiter AMM label belongs to
+pub fn venue_for_label(label: &str) -> Option<DexType> {
+    DexType::all()
+        .iter()
+        .copied()
+        .find(|dex| jupiter_labels(*dex).contains(&label))
+}
+
+/// A quoted route that does not cross the venues an opportunity was detected on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteMismatch {
+    pub entry: DexType,
+    pub exit: DexType,
+    /// AMM labels of the quoted route plan, in order
+    pub route: Vec<String>,
+}
+
+impl fmt::Display for RouteMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "route [{}] does not cross {} → {}",
+            self.route.join(", "),
+            self.entry,
+            self.exit
+        )
+    }
+}
+
+/// One step of a Jupiter route plan
+struct RouteHop<'a> {
+    label: &'a str,
+    input_mint: Option<&'a str>,
+    output_mint: Option<&'a str>,
+}
+
+/// Check a Jupiter quote's `routePlan` against the detected venues.
+///
+/// Hops out of the input token must be on `entry`, hops back into it on
+/// `exit`, and any other hop on one of the two. `DexType::Jupiter` accepts
+/// any venue. A quote whose route plan is missing or unreadable cannot be
+/// verified and counts as a mismatch.
+pub fn verify_route(
+    quote: &serde_json::Value,
+    entry: DexType,
+    exit: DexType,
+) -> Result<(), RouteMismatch> {
+    let input_mint = quote.get("inputMint").and_then(|m| m.as_str());
+    let hops: Option<Vec<RouteHop>> = quote
+        .get("routePlan")
+        .and_then(|plan| plan.as_array())
+        .and_then(|plan| {
+            plan.iter()
+                .map(|step| {
+                    let info = step.get("swapInfo")?;
+                    let mint = |key: &str| info.get(key).and_then(|m| m.as_str());
+                    Some(RouteHop {
+                        label: info.get("label")?.as_str()?,
+                        input_mint: mint("inputMint"),
+                        output_mint: mint("outputMint"),
+                    })
+                })
+                .collect()
+        });
+    let hops = hops.unwrap_or_default();
+
+    let on = |venue: DexType, label: &str| {
+        venue == DexType::Jupiter || venue_for_label(label) == Some(venue)
+    };
+    let verified = !hops.is_empty()
+        && hops.iter().all(|hop| {
+            if input_mint.is_some() && hop.input_mint == input_mint {
+                on(entry, hop.label)
+            } else if input_mint.is_some() && hop.output_mint == input_mint {
+                on(exit, hop.label)
+            } else {
+                on(entry, hop.label) || on(exit, hop.label)
+            }
+        });
+
+    if verified {
+        Ok(())
+    } else {
+        Err(RouteMismatch {
+            entry,
+            exit,
+            route: hops.iter().map(|hop| hop.label.to_string()).collect(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct VenueHealth {
+    consecutive_failures: u32,
+    excluded_until: Option<Instant>,
+}
+
+/// Per-venue health plus configured exclusions
+#[derive(Debug)]
+pub struct RoutePolicy {
+    config: RoutingConfig,
+    venues: HashMap<DexType, VenueHealth>,
+}
+
+impl RoutePolicy {
+    pub fn new(config: RoutingConfig) -> Self {
+        Self {
+            config,
+            venues: HashMap::new(),
+        }
+    }
+
+    /// Replace the configured lists and thresholds (hot reload)
+    pub fn set_config(&mut self, config: RoutingConfig) {
+        self.config = config;
+    }
+
+    /// Record a trade outcome on a venue. Returns `true` if the venue was
+    /// just excluded.
+    pub fn record_result(&mut self, dex: DexType, success: bool) -> bool {
+        let health = self.venues.entry(dex).or_default();
+        if success {
+            if health.consecutive_failures > 0 || health.excluded_until.is_some() {
+                info!("✅ {} healthy again, re-enabling in Jupiter routes", dex);
+            }
+            *health = VenueHealth::default();
+            return false;
+        }
+
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.config.max_venue_failures
+            && health.excluded_until.is_none()
+        {
+            let cooldown = Duration::from_secs(self.config.venue_cooldown_secs);
+            health.excluded_until = Some(Instant::now() + cooldown);
+            warn!(
+                "🚫 Excluding {} from Jupiter routes for {}s after {} consecutive failures",
+                dex, self.config.venue_cooldown_secs, health.consecutive_failures
+            );
+            return true;
+        }
+        false
+    }
+
+    /// Venues currently excluded for poor health
+    pub fn unhealthy_venues(&self) -> Vec<DexType> {
+        let now = Instant::now();
+        let mut venues: Vec<DexType> = self
+            .venues
+            .iter()
+            .filter(|(_, h)| h.excluded_until.is_some_and(|until| until > now))
+            .map(|(dex, _)| *dex)
+            .collect();
+        venues.sort_by_key(|d| d.display_name());
+        venues
+    }
+
+    /// Drop exclusions whose cooldown has passed so the venue gets a fresh start
+    pub fn expire_cooldowns(&mut self) {
+        let now = Instant::now();
+        for (dex, health) in self.venues.iter_mut() {
+            if health.excluded_until.is_some_and(|until| until <= now) {
+                info!("⏱️ {} exclusion cooldown expired", dex);
+                *health = VenueHealth::default();
+            }
+        }
+    }
+
+    /// Effective filter: configured exclusions plus unhealthy venues
+    pub fn route_filter(&self) -> RouteFilter {
+        let mut exclude: BTreeSet<String> = self.config.exclude_dexes.iter().cloned().collect();
+        for dex in self.unhealthy_venues() {
+            exclude.extend(jupiter_labels(dex).iter().map(|l| l.to_string()));
+        }
+
+        // Never widen a whitelist: if every whitelisted venue is excluded,
+        // keep the configured list and let quotes fail rather than route anywhere
+        let mut only_dexes: Vec<String> = self
+            .config
+            .only_dexes
+            .iter()
+            .filter(|label| !exclude.contains(*label))
+            .cloned()
+            .collect();
+        if only_dexes.is_empty() {
+            only_dexes = self.config.only_dexes.clone();
+        }
+
+        RouteFilter {
+            exclude_dexes: exclude.into_iter().collect(),
+            only_dexes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RoutingConfig {
+        RoutingConfig {
+            exclude_dexes: vec!["Saber".to_string()],
+            only_dexes: vec![],
+            max_venue_failures: 2,
+            venue_cooldown_secs: 600,
+            ..RoutingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_failing_venue_is_excluded_until_success() {
+        let mut policy = RoutePolicy::new(config());
+        assert!(!policy.record_result(DexType::Orca, false));
+        assert!(policy.record_result(DexType::Orca, false));
+        assert_eq!(policy.unhealthy_venues(), vec![DexType::Orca]);
+
+        let filter = policy.route_filter();
+        assert_eq!(filter.exclude_dexes, vec!["Orca V2", "Saber", "Whirlpool"]);
+        assert_eq!(
+            filter.query_params(),
+            "&excludeDexes=Orca+V2,Saber,Whirlpool"
+        );
+
+        policy.record_result(DexType::Orca, true);
+        assert!(policy.unhealthy_venues().is_empty());
+        assert_eq!(policy.route_filter().exclude_dexes, vec!["Saber"]);
+    }
+
+    #[test]
+    fn test_only_dexes_drop_excluded_labels() {
+        let mut policy = RoutePolicy::new(RoutingConfig {
+            only_dexes: vec!["Whirlpool".to_string(), "Phoenix".to_string()],
+            ..config()
+        });
+        policy.record_result(DexType::Orca, false);
+        policy.record_result(DexType::Orca, false);
+
+        let filter = policy.route_filter();
+        assert_eq!(filter.only_dexes, vec!["Phoenix"]);
+        assert!(filter.query_params().ends_with("&onlyDexes=Phoenix"));
+        assert_eq!(RouteFilter::default().query_params(), "");
+    }
+
+    #[test]
+    fn test_verify_route_against_detected_venues() {
+        let quote = |hops: &[(&str, &str, &str)]| {
+            serde_json::json!({
+                "inputMint": "USDC",
+                "routePlan": hops
+                    .iter()
+                    .map(|(label, from, to)| serde_json::json!({
+                        "swapInfo": { "label": label, "inputMint": from, "outputMint": to },
+                        "percent": 100
+                    }))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // Buy on Raydium, sell back on Orca
+        let round_trip = quote(&[("Raydium CLMM", "USDC", "SOL"), ("Whirlpool", "SOL", "USDC")]);
+        assert!(verify_route(&round_trip, DexType::Raydium, DexType::Orca).is_ok());
+        assert!(verify_route(&round_trip, DexType::Jupiter, DexType::Orca).is_ok());
+
+        // Legs swapped, or routed through a venue that was never detected
+        let mismatch = verify_route(&round_trip, DexType::Orca, DexType::Raydium).unwrap_err();
+        assert_eq!(mismatch.route, vec!["Raydium CLMM", "Whirlpool"]);
+        let via_saber = quote(&[("Raydium", "USDC", "USDT"), ("Saber", "USDT", "SOL")]);
+        assert!(verify_route(&via_saber, DexType::Raydium, DexType::Orca).is_err());
+
+        assert!(verify_route(&quote(&[]), DexType::Raydium, DexType::Orca).is_err());
+        assert!(verify_route(&serde_json::json!({}), DexType::Jupiter, DexType::Jupiter).is_err());
+        assert_eq!(venue_for_label("Meteora DLMM"), Some(DexType::Meteora));
+    }
+}
@@ -34,7 +34,9 @@ pub async fn run_preflight_checks(
     // I will modify the signature to accept wallet_pubkey.
     
     // 3. Check if in dry-run mode
-    if config.dry_run {
+    if config.watch_only {
+        warnings.push("⚠️ Running in WATCH-ONLY mode (no wallet, no execution)".to_string());
+    } else if config.dry_run {
         warnings.push("⚠️ Running in DRY-RUN mode (no real trades)".to_string());
     }
     
@@ -0,0 +1,411 @@
+//! Session Archives
+//!
+//! A session archive packs a window of a bot run into one zip that can be
+//! attached to a bug report and replayed on another machine:
+//!
+//! - `manifest.json`: format version, window, code revision and counts
+//! - `config.json`: the settings replay depends on (spread guard, price age
+//!   limit) and the dynamic trading config. Keys, RPC URLs and tokens come
+//!   from the environment and are never included.
+//! - `prices.jsonl`: the archived price ticks of the window
+//! - `opportunities.jsonl`: what detection found in those ticks when the
+//!   archive was exported
+//! - `trades.jsonl`: trade history records of the window
+//!
+//! Importing an archive replays its prices with its own settings and reports
+//! the ticks where detection now finds something different, which makes a
+//! "the bot should have traded here" report reproducible. The prices can
+//! also be unpacked as a snapshot directory for `replay` and `POST /replay`.
+
+use crate::replay::{self, PriceSnapshots, ReplaySettings, SnapshotTick};
+use crate::strategy_profile::StrategySettings;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use solana_arb_core::config::Config;
+use solana_arb_core::history::rotation::{self, DateRange};
+use solana_arb_core::history::TradeRecord;
+use solana_arb_core::pricing::spread_guard::SpreadGuard;
+use solana_arb_core::session::git_revision;
+use solana_arb_core::ArbitrageOpportunity;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bumped on incompatible changes to the archive layout
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+/// What an archive holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub git_rev: Option<String>,
+    pub ticks: usize,
+    pub opportunities: usize,
+    pub trades: usize,
+}
+
+/// Configuration snapshot needed to replay an archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    pub max_spread_pct: f64,
+    pub max_spread_pct_by_venue: String,
+    pub max_price_age_seconds: i64,
+    /// Archives from before the quote skew check replay without it
+    #[serde(default)]
+    pub max_quote_skew_ms: u64,
+    /// Archives from before strategy profiles replay the standard profile
+    #[serde(default)]
+    pub strategies: StrategySettings,
+    /// Contents of `config/trading_config.json`, if it could be read
+    #[serde(default)]
+    pub trading_config: Option<serde_json::Value>,
+}
+
+impl ArchiveConfig {
+    pub fn from_config(config: &Config, trading_config: Option<serde_json::Value>) -> Self {
+        Self {
+            max_spread_pct: config.max_spread_pct,
+            max_spread_pct_by_venue: config.max_spread_pct_by_venue.clone(),
+            max_price_age_seconds: config.max_price_age_seconds,
+            max_quote_skew_ms: config.max_quote_skew_ms,
+            strategies: StrategySettings::from_config(config),
+            trading_config,
+        }
+    }
+
+    /// The live filters the exporting bot ran with
+    pub fn replay_settings(&self) -> ReplaySettings {
+        ReplaySettings {
+            spread_guard: SpreadGuard::new(self.max_spread_pct)
+                .with_venue_limits(&self.max_spread_pct_by_venue)
+                .unwrap_or_else(|_| SpreadGuard::new(self.max_spread_pct)),
+            max_price_age_seconds: self.max_price_age_seconds,
+            max_quote_skew_ms: self.max_quote_skew_ms,
+            strategies: self.strategies.clone(),
+        }
+    }
+}
+
+/// An opportunity found in an archived tick
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedOpportunity {
+    /// Time of the tick
+    pub at: DateTime<Utc>,
+    /// `detector` or the strategy name
+    pub source: String,
+    pub opportunity: ArbitrageOpportunity,
+}
+
+impl ArchivedOpportunity {
+    /// What identifies the opportunity across replays; prices and IDs are
+    /// left out
+    fn key(&self) -> String {
+        format!(
+            "{} {} {}->{}",
+            self.source, self.opportunity.pair, self.opportunity.buy_dex, self.opportunity.sell_dex
+        )
+    }
+}
+
+/// A tick where replaying finds different opportunities than recorded
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TickDifference {
+    pub at: DateTime<Utc>,
+    /// Recorded, but not found by the replay
+    pub missing: Vec<String>,
+    /// Found by the replay, but not recorded
+    pub unexpected: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct SessionArchive {
+    pub manifest: ArchiveManifest,
+    pub config: ArchiveConfig,
+    pub ticks: Vec<SnapshotTick>,
+    pub opportunities: Vec<ArchivedOpportunity>,
+    pub trades: Vec<TradeRecord>,
+}
+
+impl SessionArchive {
+    /// Collect the window from `from` to `to`: archived prices, the
+    /// opportunities detection finds in them and the trades recorded in the
+    /// history file `history`
+    pub async fn collect(
+        snapshots: &PriceSnapshots,
+        history: &Path,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        config: ArchiveConfig,
+    ) -> Result<Self> {
+        if from > to {
+            anyhow::bail!("window starts after it ends");
+        }
+        let mut ticks = Vec::new();
+        for date in from.date_naive().iter_days().take_while(|date| *date <= to.date_naive()) {
+            let path = snapshots.path_for(date);
+            if path.exists() {
+                ticks.extend(replay::load_ticks(&path, Some(from), Some(to))?);
+            }
+        }
+
+        let range = DateRange {
+            from: Some(from),
+            to: Some(to),
+        };
+        let mut trades = Vec::new();
+        let mut bad_line = None;
+        rotation::for_each_line(history, &range, |line| {
+            if line.trim().is_empty() {
+                return;
+            }
+            match serde_json::from_str::<TradeRecord>(line) {
+                Ok(record) => {
+                    let at = DateTime::parse_from_rfc3339(&record.timestamp).map(|t| t.to_utc());
+                    if at.is_ok_and(|at| range.contains(at)) {
+                        trades.push(record);
+                    }
+                }
+                Err(e) => {
+                    bad_line.get_or_insert(e);
+                }
+            }
+        })
+        .with_context(|| format!("cannot read {}", history.display()))?;
+        if let Some(e) = bad_line {
+            tracing::warn!("Skipped unreadable records in {}: {}", history.display(), e);
+        }
+
+        let opportunities = detect(&ticks, &config.replay_settings()).await;
+        Ok(Self {
+            manifest: ArchiveManifest {
+                format_version: ARCHIVE_FORMAT_VERSION,
+                created_at: Utc::now(),
+                from,
+                to,
+                git_rev: git_revision(),
+                ticks: ticks.len(),
+                opportunities: opportunities.len(),
+                trades: trades.len(),
+            },
+            config,
+            ticks,
+            opportunities,
+            trades,
+        })
+    }
+
+    /// `session-YYYYMMDDTHHMMSSZ.zip`, after the start of the window
+    pub fn file_name(&self) -> String {
+        format!("session-{}.zip", self.manifest.from.format("%Y%m%dT%H%M%SZ"))
+    }
+
+    pub fn write_zip(&self, path: &Path) -> Result<()> {
+        let files = [
+            ("manifest.json", serde_json::to_string_pretty(&self.manifest)?),
+            ("config.json", serde_json::to_string_pretty(&self.config)?),
+            ("prices.jsonl", json_lines(&self.ticks)?),
+            ("opportunities.jsonl", json_lines(&self.opportunities)?),
+            ("trades.jsonl", json_lines(&self.trades)?),
+        ];
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        for (name, contents) in files {
+            zip.start_file(name, options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    pub fn read_zip(path: &Path) -> Result<Self> {
+        let file =
+            std::fs::File::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+        let mut zip = zip::ZipArchive::new(file)
+            .with_context(|| format!("{} is not a session archive", path.display()))?;
+        let mut read = |name: &str| -> Result<String> {
+            let mut contents = String::new();
+            zip.by_name(name)
+                .with_context(|| format!("archive has no {}", name))?
+                .read_to_string(&mut contents)?;
+            Ok(contents)
+        };
+
+        let manifest: ArchiveManifest = serde_json::from_str(&read("manifest.json")?)?;
+        if manifest.format_version > ARCHIVE_FORMAT_VERSION {
+            anyhow::bail!(
+                "archive format {} is newer than this build supports ({})",
+                manifest.format_version,
+                ARCHIVE_FORMAT_VERSION
+            );
+        }
+        Ok(Self {
+            config: serde_json::from_str(&read("config.json")?)?,
+            ticks: parse_lines(&read("prices.jsonl")?, "prices.jsonl")?,
+            opportunities: parse_lines(&read("opportunities.jsonl")?, "opportunities.jsonl")?,
+            trades: parse_lines(&read("trades.jsonl")?, "trades.jsonl")?,
+            manifest,
+        })
+    }
+
+    /// Replay the archived prices with the archived settings and compare
+    /// with the recorded opportunities, tick by tick
+    pub async fn verify(&self) -> Vec<TickDifference> {
+        let mut recorded: BTreeMap<DateTime<Utc>, BTreeSet<String>> = BTreeMap::new();
+        for opp in &self.opportunities {
+            recorded.entry(opp.at).or_default().insert(opp.key());
+        }
+        let mut replayed: BTreeMap<DateTime<Utc>, BTreeSet<String>> = BTreeMap::new();
+        for opp in detect(&self.ticks, &self.config.replay_settings()).await {
+            replayed.entry(opp.at).or_default().insert(opp.key());
+        }
+
+        let empty = BTreeSet::new();
+        let times: BTreeSet<_> = recorded.keys().chain(replayed.keys()).copied().collect();
+        times
+            .into_iter()
+            .filter_map(|at| {
+                let before = recorded.get(&at).unwrap_or(&empty);
+                let after = replayed.get(&at).unwrap_or(&empty);
+                (before != after).then(|| TickDifference {
+                    at,
+                    missing: before.difference(after).cloned().collect(),
+                    unexpected: after.difference(before).cloned().collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// Write the archived prices to `dir` as daily snapshot files,
+    /// replacing files of the same days, and return their paths
+    pub fn unpack_prices(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let snapshots = PriceSnapshots::new(dir);
+        let mut days: BTreeMap<NaiveDate, Vec<&SnapshotTick>> = BTreeMap::new();
+        for tick in &self.ticks {
+            days.entry(tick.at.date_naive()).or_default().push(tick);
+        }
+
+        std::fs::create_dir_all(dir)?;
+        let mut paths = Vec::new();
+        for (date, ticks) in days {
+            let path = snapshots.path_for(date);
+            std::fs::write(&path, json_lines(&ticks)?)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// Opportunities detection finds in `ticks`
+async fn detect(ticks: &[SnapshotTick], settings: &ReplaySettings) -> Vec<ArchivedOpportunity> {
+    replay::replay(ticks, settings)
+        .await
+        .into_iter()
+        .flat_map(|tick| {
+            tick.opportunities.into_iter().map(move |o| ArchivedOpportunity {
+                at: tick.at,
+                source: o.source,
+                opportunity: o.opportunity,
+            })
+        })
+        .collect()
+}
+
+fn json_lines<T: Serialize>(items: &[T]) -> serde_json::Result<String> {
+    items
+        .iter()
+        .map(|item| serde_json::to_string(item).map(|line| line + "\n"))
+        .collect()
+}
+
+fn parse_lines<T: serde::de::DeserializeOwned>(content: &str, name: &str) -> Result<Vec<T>> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).with_context(|| format!("{} line {}", name, index + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal::Decimal;
+    use solana_arb_core::{DexType, PriceData, TokenPair, Uuid};
+
+    fn price(dex: DexType, quote: i64, at: DateTime<Utc>) -> PriceData {
+        let quote = Decimal::from(quote);
+        let mut price = PriceData::new(dex, TokenPair::new("SOL", "USDC"), quote, quote);
+        price.timestamp = at;
+        price
+    }
+
+    #[tokio::test]
+    async fn test_export_import_and_verify_archive() {
+        let dir = std::env::temp_dir().join(format!("arb_session_archive_{}", Uuid::new_v4()));
+        let snapshots = PriceSnapshots::new(dir.join("snapshots"));
+        let t0 = "2026-03-02T23:59:59Z".parse::<DateTime<Utc>>().unwrap();
+        let t1 = t0 + Duration::seconds(1);
+
+        // The window spans midnight: one tick on each day's file
+        snapshots
+            .record(t0, &[price(DexType::Raydium, 100, t0), price(DexType::Orca, 100, t0)])
+            .await
+            .unwrap();
+        snapshots.record(t1, &[price(DexType::Orca, 102, t1)]).await.unwrap();
+
+        let history = dir.join("history-live.jsonl");
+        let record = |at: DateTime<Utc>| {
+            format!(
+                "{{\"timestamp\":\"{}\",\"session_id\":\"S\",\"trade_type\":\"REAL\",\"pair\":\"SOL/USDC\",\
+                 \"buy_dex\":\"Raydium\",\"sell_dex\":\"Orca\",\"size_usd\":\"100\",\"profit_usd\":\"1\",\
+                 \"profit_pct\":\"1\",\"tx_signature\":null,\"success\":true,\"error\":null}}\n",
+                at.to_rfc3339()
+            )
+        };
+        std::fs::write(&history, record(t1) + &record(t1 + Duration::hours(1))).unwrap();
+
+        let config = ArchiveConfig::from_config(&Config::default(), None);
+        let archive = SessionArchive::collect(&snapshots, &history, t0, t1, config)
+            .await
+            .unwrap();
+        assert_eq!(archive.manifest.ticks, 2);
+        assert_eq!(archive.manifest.trades, 1);
+        assert!(archive
+            .opportunities
+            .iter()
+            .any(|o| o.at == t1 && o.source == "detector"));
+
+        let path = dir.join(archive.file_name());
+        archive.write_zip(&path).unwrap();
+        let mut imported = SessionArchive::read_zip(&path).unwrap();
+        assert_eq!(imported.manifest, archive.manifest);
+        assert_eq!(imported.config, archive.config);
+        assert_eq!(imported.ticks.len(), 2);
+        assert!(imported.verify().await.is_empty());
+
+        // A replay that no longer finds the recorded opportunity is reported
+        imported.config.max_price_age_seconds = 0;
+        let differences = imported.verify().await;
+        assert!(differences
+            .iter()
+            .any(|d| d.at == t1 && d.missing.iter().any(|k| k.starts_with("detector "))));
+
+        let unpacked = imported.unpack_prices(&dir.join("imported")).unwrap();
+        assert_eq!(unpacked.len(), 2);
+        assert_eq!(replay::load_ticks(&unpacked[1], None, None).unwrap().len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,220 @@
+//! Simulation Balance Deltas
+//!
+//! A passing `simulateTransaction` only says the transaction would land, not
+//! that it makes money. Before simulating we snapshot the payer's token
+//! accounts (and its SOL balance), ask the RPC to return the same accounts as
+//! they would look after the transaction, and diff the two. The net change,
+//! valued in the quote token, is the simulated profit used to gate submission.
+
+use rust_decimal::Decimal;
+use solana_arb_core::tokens::from_atoms;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::state::Account as TokenAccount;
+use std::collections::BTreeMap;
+
+/// Lamports and data of an account at one point in time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountState {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// Balance change of the payer in a single mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDelta {
+    pub mint: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+}
+
+impl BalanceDelta {
+    /// Signed change in atomic units
+    pub fn delta(&self) -> i128 {
+        i128::from(self.post) - i128::from(self.pre)
+    }
+}
+
+/// How to value a mint's delta in the quote token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintValuation {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Price of one token in the quote token
+    pub price: Decimal,
+}
+
+/// Mint and amount of an SPL token account owned by `owner`.
+///
+/// Token-2022 accounts carry extensions after the base layout, so only the
+/// first `TokenAccount::LEN` bytes are read.
+fn token_balance(owner: &Pubkey, data: &[u8]) -> Option<(Pubkey, u64)> {
+    let base = data.get(..TokenAccount::LEN)?;
+    let account = TokenAccount::unpack_from_slice(base).ok()?;
+    (account.owner == *owner).then_some((account.mint, account.amount))
+}
+
+/// Balance held in one account: the payer's lamports count as native SOL,
+/// token accounts count if the payer owns them
+fn balance_of(owner: &Pubkey, address: &Pubkey, state: &AccountState) -> Option<(Pubkey, u64)> {
+    if address == owner {
+        return Some((spl_token::native_mint::id(), state.lamports));
+    }
+    token_balance(owner, &state.data)
+}
+
+/// Net balance change per mint for `owner` across `addresses`.
+///
+/// `pre` and `post` line up with `addresses`; `None` means the account does
+/// not exist at that point (e.g. created or closed by the transaction) and
+/// counts as a zero balance. Accounts owned by someone else are ignored.
+pub fn balance_deltas(
+    owner: &Pubkey,
+    addresses: &[Pubkey],
+    pre: &[Option<AccountState>],
+    post: &[Option<AccountState>],
+) -> Vec<BalanceDelta> {
+    let mut by_mint: BTreeMap<Pubkey, BalanceDelta> = BTreeMap::new();
+
+    for (i, address) in addresses.iter().enumerate() {
+        let before = pre
+            .get(i)
+            .and_then(|s| s.as_ref())
+            .and_then(|s| balance_of(owner, address, s));
+        let after = post
+            .get(i)
+            .and_then(|s| s.as_ref())
+            .and_then(|s| balance_of(owner, address, s));
+
+        let mint = match before.or(after) {
+            Some((mint, _)) => mint,
+            None => continue,
+        };
+        let entry = by_mint.entry(mint).or_insert(BalanceDelta {
+            mint,
+            pre: 0,
+            post: 0,
+        });
+        entry.pre += before.map(|(_, amount)| amount).unwrap_or(0);
+        entry.post += after.map(|(_, amount)| amount).unwrap_or(0);
+    }
+
+    by_mint.into_values().collect()
+}
+
+/// Value the deltas in the quote token.
+///
+/// Returns `None` if a non-zero delta is in a mint with no valuation, since
+/// the profit cannot be trusted then.
+pub fn simulated_profit(deltas: &[BalanceDelta], valuations: &[MintValuation]) -> Option<Decimal> {
+    let mut total = Decimal::ZERO;
+    for delta in deltas.iter().filter(|d| d.delta() != 0) {
+        let valuation = valuations.iter().find(|v| v.mint == delta.mint)?;
+        let amount = from_atoms(delta.delta().unsigned_abs() as u64, valuation.decimals);
+        let value = amount * valuation.price;
+        if delta.delta() < 0 {
+            total -= value;
+        } else {
+            total += value;
+        }
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> AccountState {
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut data = vec![0u8; TokenAccount::LEN];
+        account.pack_into_slice(&mut data);
+        AccountState {
+            lamports: 2_039_280,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_deltas_per_mint() {
+        let payer = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let ray = Pubkey::new_unique();
+        let (usdc_ata, ray_ata, foreign) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let addresses = [payer, usdc_ata, ray_ata, foreign];
+
+        let pre = [
+            Some(AccountState {
+                lamports: 1_000_000_000,
+                data: vec![],
+            }),
+            Some(token_account(usdc, payer, 100_000_000)),
+            None,
+            Some(token_account(usdc, Pubkey::new_unique(), 5)),
+        ];
+        let post = [
+            Some(AccountState {
+                lamports: 999_995_000,
+                data: vec![],
+            }),
+            Some(token_account(usdc, payer, 100_250_000)),
+            Some(token_account(ray, payer, 0)),
+            Some(token_account(usdc, Pubkey::new_unique(), 500)),
+        ];
+
+        let deltas = balance_deltas(&payer, &addresses, &pre, &post);
+        assert_eq!(deltas.len(), 3);
+        let delta_of = |mint: Pubkey| deltas.iter().find(|d| d.mint == mint).unwrap().delta();
+        assert_eq!(delta_of(usdc), 250_000);
+        assert_eq!(delta_of(ray), 0);
+        assert_eq!(delta_of(spl_token::native_mint::id()), -5_000);
+    }
+
+    #[test]
+    fn test_simulated_profit_valuation() {
+        let usdc = Pubkey::new_unique();
+        let sol = spl_token::native_mint::id();
+        let deltas = vec![
+            BalanceDelta {
+                mint: usdc,
+                pre: 100_000_000,
+                post: 100_250_000,
+            },
+            BalanceDelta {
+                mint: sol,
+                pre: 1_000_000_000,
+                post: 999_995_000,
+            },
+        ];
+        let valuations = vec![
+            MintValuation {
+                mint: usdc,
+                decimals: 6,
+                price: Decimal::ONE,
+            },
+            MintValuation {
+                mint: sol,
+                decimals: 9,
+                price: Decimal::from(100),
+            },
+        ];
+
+        // +0.25 USDC - 0.000005 SOL * 100
+        assert_eq!(
+            simulated_profit(&deltas, &valuations),
+            Some(Decimal::new(2495, 4))
+        );
+        // Unknown mint with a non-zero delta
+        assert_eq!(simulated_profit(&deltas, &valuations[..1]), None);
+    }
+}
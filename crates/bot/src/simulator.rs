@@ -0,0 +1,161 @@
+//! Opportunity Simulator
+//!
+//! Replays canned price ticks through the detector, path finder and built-in
+//! strategies the way the main loop does, without the network or the clock,
+//! and reduces what they find to a golden record: no ids or detection times,
+//! decimals rounded, sorted. The `simulate` binary compares each fixture's
+//! record with its golden file, so a change in detection logic shows up as a
+//! failing diff in CI instead of in production.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::{
+    arbitrage::ArbitrageDetector, pathfinding::PathFinder, ArbitrageOpportunity, PriceData,
+};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Canned price data, one entry per tick
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub ticks: Vec<Vec<PriceData>>,
+}
+
+impl Fixture {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// One detected opportunity, reduced to what detection logic decides
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GoldenOpportunity {
+    pub tick: usize,
+    /// `detector`, `path`, or the strategy name
+    pub source: String,
+    /// Pair symbol, or the token cycle of a path
+    pub pair: String,
+    /// Buy and sell venue, or each hop's venue for a path
+    pub venues: Vec<String>,
+    pub net_profit_pct: String,
+}
+
+impl std::fmt::Display for GoldenOpportunity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "tick {} {} {} via {} at {}%",
+            self.tick,
+            self.source,
+            self.pair,
+            self.venues.join(" > "),
+            self.net_profit_pct
+        )
+    }
+}
+
+fn rounded(value: Decimal) -> String {
+    value.round_dp(6).normalize().to_string()
+}
+
+fn golden(tick: usize, source: &str, opp: &ArbitrageOpportunity) -> GoldenOpportunity {
+    GoldenOpportunity {
+        tick,
+        source: source.to_string(),
+        pair: opp.pair.symbol(),
+        venues: vec![opp.buy_dex.to_string(), opp.sell_dex.to_string()],
+        net_profit_pct: rounded(opp.net_profit_pct),
+    }
+}
+
+/// Run a fixture through detection and return the sorted golden record
+pub async fn simulate(fixture: &Fixture) -> Vec<GoldenOpportunity> {
+    let mut detector = ArbitrageDetector::default();
+    let mut path_finder = PathFinder::new(4);
+    let strategies = crate::default_strategies();
+
+    let mut record = Vec::new();
+    for (tick, prices) in fixture.ticks.iter().enumerate() {
+        detector.update_prices(prices.clone());
+        path_finder.clear();
+        for price in prices {
+            path_finder.add_price(price);
+        }
+        for strategy in &strategies {
+            for price in prices {
+                let _ = strategy.update_state(price).await;
+            }
+        }
+
+        // A set: the same path may be found from several start tokens
+        let mut found = BTreeSet::new();
+        for opp in detector.find_all_opportunities() {
+            found.insert(golden(tick, "detector", &opp));
+        }
+        for path in path_finder.find_all_profitable_paths() {
+            let mut tokens: Vec<&str> = path.edges.iter().map(|e| e.from_token.as_str()).collect();
+            tokens.extend(path.edges.last().map(|e| e.to_token.as_str()));
+            found.insert(GoldenOpportunity {
+                tick,
+                source: "path".to_string(),
+                pair: tokens.join(">"),
+                venues: path.edges.iter().map(|e| e.dex.to_string()).collect(),
+                net_profit_pct: rounded(path.profit_percentage()),
+            });
+        }
+        for strategy in &strategies {
+            if let Ok(opps) = strategy.analyze(prices).await {
+                for opp in &opps {
+                    found.insert(golden(tick, strategy.name(), opp));
+                }
+            }
+        }
+        record.extend(found);
+    }
+    record
+}
+
+/// Differences between a golden record and a fresh one, one line each:
+/// `- ` for expected opportunities no longer found, `+ ` for new ones
+pub fn diff(expected: &[GoldenOpportunity], actual: &[GoldenOpportunity]) -> Vec<String> {
+    let expected: BTreeSet<_> = expected.iter().collect();
+    let actual: BTreeSet<_> = actual.iter().collect();
+    let missing = expected.difference(&actual).map(|o| format!("- {}", o));
+    let unexpected = actual.difference(&expected).map(|o| format!("+ {}", o));
+    missing.chain(unexpected).collect()
+}
+
+/// Fixture files (`fixtures/*.json`) under a simulator directory, sorted
+pub fn fixtures(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir.join("fixtures"))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Golden file for a fixture: `golden/<name>.json` next to `fixtures/`
+pub fn golden_path(fixture: &Path) -> PathBuf {
+    let name = fixture.file_name().unwrap_or_default();
+    fixture
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or(Path::new("."))
+        .join("golden")
+        .join(name)
+}
+
+pub fn load_golden(path: &Path) -> anyhow::Result<Vec<GoldenOpportunity>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_golden(path: &Path, record: &[GoldenOpportunity]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(record)? + "\n")?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Slippage Size Feedback
+//!
+//! Shrinks the size of trades on routes whose realized slippage keeps
+//! exceeding the modeled one. For each executed trade the slippage error is
+//! the edge lost against the plan minus the price impact the quote modeled,
+//! both in bps of the trade size. When the average error over a route's
+//! recent trades is above `routing.slippage_error_tolerance_bps`, the
+//! route's size scale steps down (to at most `routing.min_route_size_scale`
+//! of the position limit); once it is back within tolerance, the scale steps
+//! back up to 1.
+
+use crate::config_manager::RoutingConfig;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::{ArbitrageOpportunity, DexType};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+/// Slippage errors kept per route
+const ERROR_WINDOW: usize = 20;
+/// Scale applied per adjustment while a route misses its model
+const SHRINK_FACTOR: f64 = 0.8;
+/// Scale applied per adjustment once a route is back within tolerance
+const GROW_FACTOR: f64 = 1.1;
+
+/// Route sizes are kept per pair and venues
+type RouteKey = (String, DexType, DexType);
+
+#[derive(Debug)]
+struct RouteFeedback {
+    /// Realized minus modeled slippage of recent trades (bps)
+    errors: VecDeque<f64>,
+    /// Fraction of the position limit the route may trade
+    scale: f64,
+}
+
+impl Default for RouteFeedback {
+    fn default() -> Self {
+        Self {
+            errors: VecDeque::new(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl RouteFeedback {
+    fn mean_error_bps(&self) -> Option<f64> {
+        (!self.errors.is_empty())
+            .then(|| self.errors.iter().sum::<f64>() / self.errors.len() as f64)
+    }
+}
+
+/// Size scale of one route, as shown on `/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSizeScale {
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub samples: usize,
+    /// Average realized minus modeled slippage (bps)
+    pub mean_error_bps: f64,
+    /// Fraction of the position limit the route may trade
+    pub scale: f64,
+}
+
+/// Per-route size limits driven by realized slippage
+#[derive(Debug)]
+pub struct SizeFeedback {
+    config: Mutex<RoutingConfig>,
+    routes: Mutex<HashMap<RouteKey, RouteFeedback>>,
+}
+
+impl SizeFeedback {
+    pub fn new(config: RoutingConfig) -> Self {
+        Self {
+            config: Mutex::new(config),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the thresholds (hot reload)
+    pub fn set_config(&self, config: RoutingConfig) {
+        *self.config.lock().unwrap_or_else(|e| e.into_inner()) = config;
+    }
+
+    /// Record a successful trade of `size` that realized `shortfall_usd`
+    /// less than planned, on a route whose quote modeled `modeled_pct` of
+    /// price impact
+    pub fn record(
+        &self,
+        opp: &ArbitrageOpportunity,
+        size: Decimal,
+        shortfall_usd: Decimal,
+        modeled_pct: Decimal,
+    ) {
+        if size <= Decimal::ZERO {
+            return;
+        }
+        let realized_bps = (shortfall_usd / size * Decimal::from(10_000)).to_f64().unwrap_or(0.0);
+        let modeled_bps = (modeled_pct * Decimal::from(100)).to_f64().unwrap_or(0.0);
+        let config = self.config.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let route = routes
+            .entry((opp.pair.symbol(), opp.buy_dex, opp.sell_dex))
+            .or_default();
+        route.errors.push_back(realized_bps - modeled_bps);
+        if route.errors.len() > ERROR_WINDOW {
+            route.errors.pop_front();
+        }
+        if route.errors.len() < config.slippage_feedback_min_samples {
+            return;
+        }
+
+        let mean = route.mean_error_bps().unwrap_or(0.0);
+        let previous = route.scale;
+        route.scale = if mean > config.slippage_error_tolerance_bps {
+            (route.scale * SHRINK_FACTOR).max(config.min_route_size_scale)
+        } else {
+            (route.scale * GROW_FACTOR).min(1.0)
+        };
+        if route.scale < previous {
+            warn!(
+                "📉 {} {} → {} sized down to {:.0}% of the limit: slippage {:.1} bps above model",
+                opp.pair,
+                opp.buy_dex,
+                opp.sell_dex,
+                route.scale * 100.0,
+                mean
+            );
+        } else if route.scale == 1.0 && previous < 1.0 {
+            info!(
+                "📈 {} {} → {} back to full size: slippage within {} bps of model",
+                opp.pair, opp.buy_dex, opp.sell_dex, config.slippage_error_tolerance_bps
+            );
+        }
+    }
+
+    /// `size` capped at the route's share of `limit`
+    pub fn cap(&self, opp: &ArbitrageOpportunity, size: Decimal, limit: Decimal) -> Decimal {
+        let routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let scale = routes
+            .get(&(opp.pair.symbol(), opp.buy_dex, opp.sell_dex))
+            .map_or(1.0, |route| route.scale);
+        if scale >= 1.0 {
+            return size;
+        }
+        let max = limit * Decimal::from_f64(scale).unwrap_or(Decimal::ONE);
+        size.min(max.round_dp(2))
+    }
+
+    /// Routes trading below full size, smallest scale first
+    pub fn status(&self) -> Vec<RouteSizeScale> {
+        let routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut scaled: Vec<RouteSizeScale> = routes
+            .iter()
+            .filter(|(_, route)| route.scale < 1.0)
+            .map(|((pair, buy_dex, sell_dex), route)| RouteSizeScale {
+                pair: pair.clone(),
+                buy_dex: buy_dex.display_name().to_string(),
+                sell_dex: sell_dex.display_name().to_string(),
+                samples: route.errors.len(),
+                mean_error_bps: route.mean_error_bps().unwrap_or(0.0),
+                scale: route.scale,
+            })
+            .collect();
+        scaled.sort_by(|a, b| a.scale.total_cmp(&b.scale));
+        scaled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use solana_arb_core::{OpportunitySource, TokenPair, Uuid};
+
+    fn opp() -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Orca,
+            sell_dex: DexType::Raydium,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::ONE,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        }
+    }
+
+    #[test]
+    fn test_route_shrinks_while_slippage_beats_model_and_recovers() {
+        let feedback = SizeFeedback::new(RoutingConfig::default());
+        let opp = opp();
+        let size = Decimal::from(1000);
+        let limit = Decimal::from(1000);
+
+        // Losing $5 on $1000 (50 bps) against 10 bps modeled impact
+        for _ in 0..10 {
+            feedback.record(&opp, size, Decimal::from(5), Decimal::new(1, 1));
+        }
+        let capped = feedback.cap(&opp, size, limit);
+        assert!(capped < size);
+        assert!(capped >= limit * Decimal::new(25, 2));
+        assert_eq!(feedback.status().len(), 1);
+
+        // Fills matching the model push the window back within tolerance
+        for _ in 0..40 {
+            feedback.record(&opp, size, Decimal::ONE, Decimal::new(1, 1));
+        }
+        assert_eq!(feedback.cap(&opp, size, limit), size);
+        assert!(feedback.status().is_empty());
+    }
+
+    #[test]
+    fn test_too_few_samples_leave_size_alone() {
+        let feedback = SizeFeedback::new(RoutingConfig::default());
+        let opp = opp();
+        for _ in 0..4 {
+            feedback.record(&opp, Decimal::from(1000), Decimal::from(50), Decimal::ZERO);
+        }
+        assert_eq!(feedback.cap(&opp, Decimal::from(800), Decimal::from(1000)), Decimal::from(800));
+    }
+}
@@ -0,0 +1,467 @@
+//! Signer Spending Guard
+//!
+//! Last line of defense before a transaction is signed, independent of the
+//! risk manager. The guard works out how much of each token the transaction
+//! takes out of the wallet and refuses to sign if that exceeds a hard
+//! per-transaction cap or would push the last hour's total over the hourly
+//! cap. Mints without a cap are refused outright.
+//!
+//! Outflow per mint is the larger of:
+//! - transfers, approvals and burns the payer authorizes in top-level System
+//!   and SPL Token instructions, and
+//! - the payer's net balance decrease in a simulation of the same message,
+//!   which also catches transfers made by other programs (e.g. Jupiter
+//!   routes) through CPI.
+//!
+//! Caps come from `SIGNER_MAX_TX_OUTFLOW` and `SIGNER_MAX_HOURLY_OUTFLOW`,
+//! formatted `SYMBOL:amount,...` in whole tokens.
+
+use crate::simulation::BalanceDelta;
+use anyhow::{anyhow, Result};
+use solana_arb_core::tokens::{from_atoms, Rounding, TokenRegistry};
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::instruction::TokenInstruction;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+/// Token-2022 shares the SPL Token instruction layout for the variants decoded here
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PEnBqCXEpPxuEb";
+
+const DEFAULT_TX_CAPS: &str =
+    "SOL:10,USDC:2500,USDT:2500,RAY:1500,ORCA:1500,JUP:3000,BONK:100000000";
+const DEFAULT_HOURLY_CAPS: &str =
+    "SOL:100,USDC:25000,USDT:25000,RAY:15000,ORCA:15000,JUP:30000,BONK:1000000000";
+
+const WINDOW: Duration = Duration::from_secs(3600);
+
+/// Hard caps for one mint, in atomic units
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintCap {
+    pub symbol: String,
+    pub decimals: u8,
+    pub per_tx: u64,
+    pub per_hour: u64,
+}
+
+/// Caps per mint
+#[derive(Debug, Clone, Default)]
+pub struct SpendingLimits {
+    caps: BTreeMap<Pubkey, MintCap>,
+}
+
+impl SpendingLimits {
+    /// Limits from `SIGNER_MAX_TX_OUTFLOW` / `SIGNER_MAX_HOURLY_OUTFLOW`,
+    /// falling back to the defaults for any variable that is unset or invalid
+    pub fn from_env(registry: &TokenRegistry) -> Self {
+        let read = |var: &str, default: &str| {
+            let value = std::env::var(var).unwrap_or_else(|_| default.to_string());
+            parse_caps(&value, registry).unwrap_or_else(|e| {
+                warn!("Invalid {}: {}. Using defaults.", var, e);
+                parse_caps(default, registry).unwrap_or_default()
+            })
+        };
+        Self::from_caps(
+            registry,
+            &read("SIGNER_MAX_TX_OUTFLOW", DEFAULT_TX_CAPS),
+            &read("SIGNER_MAX_HOURLY_OUTFLOW", DEFAULT_HOURLY_CAPS),
+        )
+    }
+
+    /// Combine per-transaction and hourly caps (atomic units, keyed by
+    /// symbol). A mint capped in only one of them is unlimited in the other.
+    pub fn from_caps(
+        registry: &TokenRegistry,
+        per_tx: &HashMap<String, u64>,
+        per_hour: &HashMap<String, u64>,
+    ) -> Self {
+        let mut caps = BTreeMap::new();
+        for symbol in per_tx.keys().chain(per_hour.keys()) {
+            let Some(info) = registry.get(symbol) else {
+                continue;
+            };
+            let Ok(mint) = Pubkey::from_str(&info.mint) else {
+                continue;
+            };
+            caps.insert(
+                mint,
+                MintCap {
+                    symbol: info.symbol.clone(),
+                    decimals: info.decimals,
+                    per_tx: per_tx.get(symbol).copied().unwrap_or(u64::MAX),
+                    per_hour: per_hour.get(symbol).copied().unwrap_or(u64::MAX),
+                },
+            );
+        }
+        Self { caps }
+    }
+
+    pub fn cap(&self, mint: &Pubkey) -> Option<&MintCap> {
+        self.caps.get(mint)
+    }
+}
+
+/// Parse `SYMBOL:amount,...` (whole tokens) into atomic units per symbol
+fn parse_caps(value: &str, registry: &TokenRegistry) -> Result<HashMap<String, u64>> {
+    let mut caps = HashMap::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (symbol, amount) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expected SYMBOL:amount, got '{}'", entry))?;
+        let symbol = symbol.trim();
+        let amount = rust_decimal::Decimal::from_str(amount.trim())
+            .map_err(|e| anyhow!("invalid amount for {}: {}", symbol, e))?;
+        let atoms = registry
+            .to_atoms(symbol, amount, Rounding::Down)
+            .map_err(|e| anyhow!("{}", e))?;
+        let symbol = registry
+            .get(symbol)
+            .map(|info| info.symbol.clone())
+            .unwrap_or_else(|| symbol.to_string());
+        caps.insert(symbol, atoms);
+    }
+    Ok(caps)
+}
+
+/// A signed transaction counted against the hourly caps
+#[derive(Debug, Clone)]
+struct Spend {
+    at: Instant,
+    message_hash: Hash,
+    outflows: BTreeMap<Pubkey, u64>,
+}
+
+/// Enforces [`SpendingLimits`] at signing time
+#[derive(Debug)]
+pub struct SpendingGuard {
+    limits: SpendingLimits,
+    spends: Mutex<VecDeque<Spend>>,
+}
+
+impl SpendingGuard {
+    pub fn new(limits: SpendingLimits) -> Self {
+        Self {
+            limits,
+            spends: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Accounts whose balances the simulation must return: the payer itself
+    /// (native SOL) and its associated token account for every capped mint
+    pub fn watched_accounts(&self, owner: &Pubkey) -> Vec<Pubkey> {
+        let mut accounts = vec![*owner];
+        accounts.extend(
+            self.limits
+                .caps
+                .keys()
+                .map(|mint| get_associated_token_address(owner, mint)),
+        );
+        accounts
+    }
+
+    /// Check `message` before `owner` signs it and count it against the
+    /// hourly caps. Re-signing the same message (e.g. a retry) is not
+    /// counted twice.
+    pub fn authorize(
+        &self,
+        message: &VersionedMessage,
+        owner: &Pubkey,
+        simulated: &[BalanceDelta],
+    ) -> Result<()> {
+        self.authorize_at(message, owner, simulated, Instant::now())
+    }
+
+    fn authorize_at(
+        &self,
+        message: &VersionedMessage,
+        owner: &Pubkey,
+        simulated: &[BalanceDelta],
+        now: Instant,
+    ) -> Result<()> {
+        let mut outflows = decode_outflows(message, owner, &self.limits)?;
+        for delta in simulated.iter().filter(|d| d.delta() < 0) {
+            let spent = delta.pre - delta.post;
+            let entry = outflows.entry(delta.mint).or_insert(0);
+            *entry = (*entry).max(spent);
+        }
+        outflows.retain(|_, amount| *amount > 0);
+
+        let message_hash = message.hash();
+        let mut spends = self.spends.lock().unwrap_or_else(|e| e.into_inner());
+        while spends
+            .front()
+            .is_some_and(|s| now.duration_since(s.at) >= WINDOW)
+        {
+            spends.pop_front();
+        }
+        if spends.iter().any(|s| s.message_hash == message_hash) {
+            return Ok(());
+        }
+
+        for (mint, &amount) in &outflows {
+            let cap = self.limits.cap(mint).ok_or_else(|| {
+                refuse(format!("outflow of {} atoms of uncapped mint {}", amount, mint))
+            })?;
+            let show = |atoms: u64| format!("{} {}", from_atoms(atoms, cap.decimals), cap.symbol);
+
+            if amount > cap.per_tx {
+                return Err(refuse(format!(
+                    "outflow {} exceeds per-transaction cap {}",
+                    show(amount),
+                    show(cap.per_tx)
+                )));
+            }
+            let hour_total: u64 = spends
+                .iter()
+                .filter_map(|s| s.outflows.get(mint))
+                .sum();
+            if hour_total.saturating_add(amount) > cap.per_hour {
+                return Err(refuse(format!(
+                    "outflow {} would bring the last hour to {}, over the hourly cap {}",
+                    show(amount),
+                    show(hour_total.saturating_add(amount)),
+                    show(cap.per_hour)
+                )));
+            }
+        }
+
+        spends.push_back(Spend {
+            at: now,
+            message_hash,
+            outflows,
+        });
+        Ok(())
+    }
+}
+
+fn refuse(reason: String) -> anyhow::Error {
+    error!("🛑 Spending guard refused to sign: {}", reason);
+    anyhow!("spending guard refused to sign: {}", reason)
+}
+
+/// Outflows `owner` authorizes in the top-level instructions of `message`.
+///
+/// Transfers into the owner's own watched accounts (e.g. wrapping SOL) are
+/// not outflows. A token instruction the owner authorizes on an account
+/// whose mint cannot be determined is refused.
+pub fn decode_outflows(
+    message: &VersionedMessage,
+    owner: &Pubkey,
+    limits: &SpendingLimits,
+) -> Result<BTreeMap<Pubkey, u64>> {
+    let keys = message.static_account_keys();
+    let native_mint = spl_token::native_mint::id();
+    let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID)?;
+
+    // The owner's watched token accounts and their mints
+    let own_accounts: HashMap<Pubkey, Pubkey> = limits
+        .caps
+        .keys()
+        .map(|mint| (get_associated_token_address(owner, mint), *mint))
+        .collect();
+    let is_own = |key: Option<&Pubkey>| key.is_some_and(|k| k == owner || own_accounts.contains_key(k));
+
+    let mut outflows: BTreeMap<Pubkey, u64> = BTreeMap::new();
+    for ix in message.instructions() {
+        let Some(program_id) = keys.get(ix.program_id_index as usize) else {
+            continue;
+        };
+        // Accounts loaded from lookup tables have no static key; signers
+        // (and so the owner) are always static
+        let account = |i: usize| ix.accounts.get(i).and_then(|&idx| keys.get(idx as usize));
+
+        if *program_id == system_program::id() {
+            let lamports = match bincode::deserialize::<SystemInstruction>(&ix.data) {
+                Ok(SystemInstruction::Transfer { lamports }) if !is_own(account(1)) => lamports,
+                Ok(SystemInstruction::CreateAccount { lamports, .. }) => lamports,
+                _ => continue,
+            };
+            if account(0) == Some(owner) {
+                add_outflow(&mut outflows, native_mint, lamports)?;
+            }
+            continue;
+        }
+
+        if *program_id != spl_token::id() && *program_id != token_2022 {
+            continue;
+        }
+        // (amount, source index, mint index, destination index, authority index)
+        let (amount, source, mint, destination, authority) =
+            match TokenInstruction::unpack(&ix.data) {
+                Ok(TokenInstruction::Transfer { amount }) => (amount, 0, None, Some(1), 2),
+                Ok(TokenInstruction::TransferChecked { amount, .. }) => {
+                    (amount, 0, Some(1), Some(2), 3)
+                }
+                Ok(TokenInstruction::Approve { amount }) => (amount, 0, None, None, 2),
+                Ok(TokenInstruction::ApproveChecked { amount, .. }) => {
+                    (amount, 0, Some(1), None, 3)
+                }
+                Ok(TokenInstruction::Burn { amount }) => (amount, 0, None, None, 2),
+                Ok(TokenInstruction::BurnChecked { amount, .. }) => (amount, 0, Some(1), None, 2),
+                _ => continue,
+            };
+        if account(authority) != Some(owner) {
+            continue;
+        }
+        if destination.is_some_and(|d| is_own(account(d))) {
+            continue;
+        }
+
+        let mint = match mint {
+            Some(i) => account(i).copied(),
+            None => account(source).and_then(|s| own_accounts.get(s).copied()),
+        }
+        .ok_or_else(|| {
+            anyhow!("spending guard refused to sign: token outflow from an account with unknown mint")
+        })?;
+        add_outflow(&mut outflows, mint, amount)?;
+    }
+    Ok(outflows)
+}
+
+/// Add `amount` to the outflow of `mint`. A total past `u64::MAX` cannot be
+/// checked against any cap and is refused.
+fn add_outflow(outflows: &mut BTreeMap<Pubkey, u64>, mint: Pubkey, amount: u64) -> Result<()> {
+    let total = outflows.entry(mint).or_insert(0);
+    *total = total
+        .checked_add(amount)
+        .ok_or_else(|| refuse(format!("outflows of mint {} overflow u64", mint)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::message::Message;
+    use solana_sdk::system_instruction;
+
+    fn usdc() -> Pubkey {
+        Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap()
+    }
+
+    fn guard() -> SpendingGuard {
+        let registry = TokenRegistry::mainnet();
+        SpendingGuard::new(SpendingLimits::from_caps(
+            &registry,
+            &parse_caps("SOL:1,USDC:100", &registry).unwrap(),
+            &parse_caps("USDC:250", &registry).unwrap(),
+        ))
+    }
+
+    fn message(instructions: &[Instruction], payer: &Pubkey) -> VersionedMessage {
+        VersionedMessage::Legacy(Message::new(instructions, Some(payer)))
+    }
+
+    fn usdc_transfer(owner: &Pubkey, to: &Pubkey, amount: u64) -> Instruction {
+        let source = get_associated_token_address(owner, &usdc());
+        spl_token::instruction::transfer(&spl_token::id(), &source, to, owner, &[], amount).unwrap()
+    }
+
+    #[test]
+    fn test_decodes_outflows_but_not_self_transfers() {
+        let owner = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let wsol = get_associated_token_address(&owner, &spl_token::native_mint::id());
+        let limits = guard().limits;
+
+        let msg = message(
+            &[
+                system_instruction::transfer(&owner, &wsol, 500),
+                system_instruction::transfer(&owner, &stranger, 7),
+                usdc_transfer(&owner, &stranger, 40_000_000),
+            ],
+            &owner,
+        );
+        let outflows = decode_outflows(&msg, &owner, &limits).unwrap();
+        assert_eq!(outflows[&spl_token::native_mint::id()], 7);
+        assert_eq!(outflows[&usdc()], 40_000_000);
+
+        // A token account the guard can't map to a mint is refused
+        let unknown = Pubkey::new_unique();
+        let ix = spl_token::instruction::transfer(&spl_token::id(), &unknown, &stranger, &owner, &[], 1)
+            .unwrap();
+        assert!(decode_outflows(&message(&[ix], &owner), &owner, &limits).is_err());
+    }
+
+    #[test]
+    fn test_overflowing_outflows_are_refused() {
+        let guard = guard();
+        let owner = Pubkey::new_unique();
+        let source = get_associated_token_address(&owner, &usdc());
+        let approve = |delegate: &Pubkey| {
+            spl_token::instruction::approve(&spl_token::id(), &source, delegate, &owner, &[], 1 << 63)
+                .unwrap()
+        };
+
+        // Two approvals of 2^63 would wrap to 0 and look like no outflow
+        let msg = message(&[approve(&Pubkey::new_unique()), approve(&Pubkey::new_unique())], &owner);
+        assert!(decode_outflows(&msg, &owner, &guard.limits).is_err());
+        assert!(guard.authorize_at(&msg, &owner, &[], Instant::now()).is_err());
+    }
+
+    #[test]
+    fn test_enforces_tx_and_hourly_caps() {
+        let guard = guard();
+        let owner = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let now = Instant::now();
+
+        // 101 USDC > 100 per transaction
+        let big = message(&[usdc_transfer(&owner, &stranger, 101_000_000)], &owner);
+        assert!(guard.authorize_at(&big, &owner, &[], now).is_err());
+
+        // 90 + 90 fit in 250/hour, a third does not
+        for amount in [90_000_000, 90_000_001] {
+            let msg = message(&[usdc_transfer(&owner, &stranger, amount)], &owner);
+            guard.authorize_at(&msg, &owner, &[], now).unwrap();
+            // Retrying the same message is not counted again
+            guard.authorize_at(&msg, &owner, &[], now).unwrap();
+        }
+        let third = message(&[usdc_transfer(&owner, &stranger, 90_000_002)], &owner);
+        assert!(guard.authorize_at(&third, &owner, &[], now).is_err());
+        // ...until the first spends leave the window
+        guard
+            .authorize_at(&third, &owner, &[], now + WINDOW + Duration::from_secs(1))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_simulated_outflows_and_uncapped_mints() {
+        let guard = guard();
+        let owner = Pubkey::new_unique();
+        let msg = message(&[Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![])], &owner);
+
+        // A CPI spend only visible in the simulation
+        let spent = |mint: Pubkey, amount: u64| BalanceDelta {
+            mint,
+            pre: amount,
+            post: 0,
+        };
+        assert!(guard
+            .authorize_at(&msg, &owner, &[spent(usdc(), 150_000_000)], Instant::now())
+            .is_err());
+
+        let ray = Pubkey::from_str("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R").unwrap();
+        assert!(guard
+            .authorize_at(&msg, &owner, &[spent(ray, 1)], Instant::now())
+            .is_err());
+
+        // Balance increases are not outflows
+        let received = BalanceDelta {
+            mint: ray,
+            pre: 0,
+            post: 5,
+        };
+        guard
+            .authorize_at(&msg, &owner, &[received], Instant::now())
+            .unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+//! Public Status Page
+//!
+//! With `STATUS_PAGE_ENABLED=true` the bot writes a small static bundle to
+//! `STATUS_PAGE_DIR` every `STATUS_PAGE_INTERVAL_SECS`: `status.json` and an
+//! `index.html` rendering it. Both are safe to host publicly. They carry
+//! whether the bot is up, its uptime, the circuit breaker state, trade
+//! counts, and only the sign of the day's P&L. Sizes, balances, exposure,
+//! venues and addresses are left out.
+//!
+//! Files are replaced atomically, so a web server never serves a partial one.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Sign of a P&L, without its amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PnlSign {
+    Positive,
+    Flat,
+    Negative,
+}
+
+impl PnlSign {
+    pub fn of(pnl: Decimal) -> Self {
+        if pnl > Decimal::ZERO {
+            Self::Positive
+        } else if pnl < Decimal::ZERO {
+            Self::Negative
+        } else {
+            Self::Flat
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Positive => "Up",
+            Self::Flat => "Flat",
+            Self::Negative => "Down",
+        }
+    }
+}
+
+/// Everything the public bundle shows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublicStatus {
+    pub generated_at: DateTime<Utc>,
+    /// "live", "dry-run" or "watch-only"
+    pub mode: String,
+    pub running: bool,
+    pub paused: bool,
+    pub uptime_seconds: u64,
+    /// "Closed" or "Open"
+    pub circuit_breaker: String,
+    pub daily_pnl: PnlSign,
+    pub trades_today: usize,
+    /// Since the bot started
+    pub trades_successful: u64,
+    pub trades_failed: u64,
+    /// Seconds until the bundle is next refreshed
+    pub refresh_secs: u64,
+}
+
+impl PublicStatus {
+    /// A single-page rendering that reloads itself at the refresh interval
+    pub fn to_html(&self, title: &str) -> String {
+        let state = if !self.running {
+            "Stopped"
+        } else if self.paused {
+            "Paused"
+        } else {
+            "Running"
+        };
+        let rows = [
+            ("Status", state.to_string()),
+            ("Mode", self.mode.clone()),
+            ("Uptime", uptime(self.uptime_seconds)),
+            ("Circuit breaker", self.circuit_breaker.clone()),
+            ("P&amp;L today", self.daily_pnl.label().to_string()),
+            ("Trades today", self.trades_today.to_string()),
+            ("Successful trades", self.trades_successful.to_string()),
+            ("Failed trades", self.trades_failed.to_string()),
+        ];
+        let rows: String = rows
+            .iter()
+            .map(|(name, value)| format!("<tr><th>{}</th><td>{}</td></tr>\n", name, escape(value)))
+            .collect();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta http-equiv="refresh" content="{refresh}">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em auto; max-width: 32em; }}
+th {{ text-align: left; padding-right: 2em; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+{rows}</table>
+<p>Updated {updated} (<a href="status.json">JSON</a>)</p>
+</body>
+</html>
+"#,
+            refresh = self.refresh_secs.max(1),
+            title = escape(title),
+            rows = rows,
+            updated = self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        )
+    }
+
+    /// Replace `status.json` and `index.html` in `dir`
+    pub fn write(&self, dir: &Path, title: &str) -> std::io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self)?;
+        replace(&dir.join("status.json"), &json)?;
+        replace(&dir.join("index.html"), &self.to_html(title))
+    }
+}
+
+pub(crate) fn replace(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+fn uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else {
+        format!("{}h {}m", hours, minutes)
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_shows_pnl_sign_only() {
+        let status = PublicStatus {
+            generated_at: Utc::now(),
+            mode: "live".into(),
+            running: true,
+            paused: false,
+            uptime_seconds: 93_780,
+            circuit_breaker: "Closed".into(),
+            daily_pnl: PnlSign::of(Decimal::new(-12345, 2)),
+            trades_today: 7,
+            trades_successful: 40,
+            trades_failed: 2,
+            refresh_secs: 60,
+        };
+        assert_eq!(status.daily_pnl, PnlSign::Negative);
+        assert_eq!(PnlSign::of(Decimal::ZERO), PnlSign::Flat);
+
+        let dir = std::env::temp_dir().join(format!("status-page-{}", solana_arb_core::Uuid::new_v4()));
+        status.write(&dir, "Desk <A>").unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("status.json")).unwrap()).unwrap();
+        assert_eq!(json["daily_pnl"], "negative");
+        assert_eq!(json["trades_today"], 7);
+        let html = fs::read_to_string(dir.join("index.html")).unwrap();
+        assert!(html.contains("<title>Desk &lt;A&gt;</title>"));
+        assert!(html.contains("<td>Down</td>") && html.contains("<td>1d 2h</td>"));
+        assert!(!html.contains("123.45") && !json.to_string().contains("123.45"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,225 @@
+//! Strategy Profiles
+//!
+//! `STRATEGY_PROFILE` picks which signals the bot trades on:
+//!
+//! - `standard`: the spread detector plus the statistical and latency
+//!   strategies
+//! - `conservative`: only [`CrossedMarket`], one venue's bid strictly above
+//!   another venue's ask by more than both swap fees plus
+//!   `CROSSED_MARKET_MARGIN_BPS`. The detector and the statistical signals
+//!   are skipped, so it trades rarely and only on edges visible in the
+//!   current quotes. Meant as the starting point for new users.
+//!
+//! With `IMBALANCE_STRATEGY_ENABLED=true` the standard profile also runs
+//! [`OrderBookImbalance`] on the Phoenix books. Replays and the opportunity
+//! simulator have no books and leave it out.
+//!
+//! Strategies that need a price history first (the statistical strategy
+//! needs a full window) are skipped until they report ready. Their warm-up
+//! progress is shown under `strategies` in `/status`.
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::config::Config;
+use solana_arb_dex_plugins::PhoenixProvider;
+use solana_arb_strategies::{
+    CrossedMarket, ImbalanceConfig, LatencyArbitrage, OrderBookImbalance, StatisticalArbitrage, Strategy,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyProfile {
+    #[default]
+    Standard,
+    Conservative,
+}
+
+impl std::str::FromStr for StrategyProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "standard" => Ok(Self::Standard),
+            "conservative" => Ok(Self::Conservative),
+            other => Err(format!("unknown strategy profile '{}'", other)),
+        }
+    }
+}
+
+/// Warm-up of one running strategy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WarmUpStatus {
+    pub name: String,
+    pub ready: bool,
+    /// Share of the warm-up done, 0 to 1
+    pub progress: f64,
+}
+
+impl WarmUpStatus {
+    pub async fn of(strategy: &dyn Strategy) -> Self {
+        Self {
+            name: strategy.name().to_string(),
+            ready: strategy.is_ready().await,
+            progress: (strategy.warm_up_progress().await * 100.0).round() / 100.0,
+        }
+    }
+}
+
+/// The profile and what its strategies are built with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategySettings {
+    pub profile: StrategyProfile,
+    /// Edge a crossed market must clear beyond both venues' fees
+    pub crossed_market_margin_bps: Decimal,
+    /// Window (ticks) of the statistical strategy
+    #[serde(default = "default_statistical_window")]
+    pub statistical_window: usize,
+    /// Z-score the statistical strategy fires at
+    #[serde(default = "default_statistical_z_score")]
+    pub statistical_z_score: Decimal,
+    /// Order book imbalance settings, when that strategy is enabled
+    #[serde(default)]
+    pub imbalance: Option<ImbalanceConfig>,
+}
+
+fn default_statistical_window() -> usize {
+    20
+}
+
+fn default_statistical_z_score() -> Decimal {
+    Decimal::new(20, 1)
+}
+
+impl Default for StrategySettings {
+    fn default() -> Self {
+        Self {
+            profile: StrategyProfile::Standard,
+            crossed_market_margin_bps: Decimal::from(10),
+            statistical_window: default_statistical_window(),
+            statistical_z_score: default_statistical_z_score(),
+            imbalance: None,
+        }
+    }
+}
+
+impl StrategySettings {
+    /// An unknown profile falls back to `standard` with a warning
+    pub fn from_config(config: &Config) -> Self {
+        let profile = config.strategy_profile.parse().unwrap_or_else(|e| {
+            warn!("{} - using the standard profile", e);
+            StrategyProfile::Standard
+        });
+        Self {
+            profile,
+            crossed_market_margin_bps: Decimal::from_f64(config.crossed_market_margin_bps)
+                .filter(|bps| *bps >= Decimal::ZERO)
+                .unwrap_or_else(|| Self::default().crossed_market_margin_bps),
+            statistical_window: config.stat_arb_window.max(2),
+            statistical_z_score: Decimal::from_f64(config.stat_arb_z_score)
+                .filter(|z| *z > Decimal::ZERO)
+                .unwrap_or_else(default_statistical_z_score),
+            imbalance: config.imbalance_strategy_enabled.then(|| {
+                let defaults = ImbalanceConfig::default();
+                let decimal = |value: f64, default: Decimal| {
+                    Decimal::from_f64(value)
+                        .filter(|v| *v >= Decimal::ZERO)
+                        .unwrap_or(default)
+                };
+                ImbalanceConfig {
+                    levels: config.imbalance_levels.max(1),
+                    threshold: decimal(config.imbalance_threshold, defaults.threshold).min(Decimal::ONE),
+                    max_drift_bps: decimal(config.imbalance_max_drift_bps, defaults.max_drift_bps),
+                    margin_bps: decimal(config.imbalance_margin_bps, defaults.margin_bps),
+                }
+            }),
+        }
+    }
+
+    pub fn strategies(&self) -> Vec<Box<dyn Strategy>> {
+        match self.profile {
+            StrategyProfile::Standard => vec![
+                Box::new(StatisticalArbitrage::new(
+                    self.statistical_window,
+                    self.statistical_z_score,
+                )),
+                Box::new(LatencyArbitrage::new()),
+            ],
+            StrategyProfile::Conservative => {
+                vec![Box::new(CrossedMarket::new(self.crossed_market_margin_bps))]
+            }
+        }
+    }
+
+    /// [`Self::strategies`] plus the strategies that read `phoenix`'s books
+    pub fn strategies_with_books(&self, phoenix: &Arc<PhoenixProvider>) -> Vec<Box<dyn Strategy>> {
+        let mut strategies = self.strategies();
+        if let (StrategyProfile::Standard, Some(config)) = (self.profile, self.imbalance) {
+            strategies.push(Box::new(OrderBookImbalance::new(phoenix.clone(), config)));
+        }
+        strategies
+    }
+
+    /// Whether the spread detector's opportunities are traded
+    pub fn uses_detector(&self) -> bool {
+        self.profile == StrategyProfile::Standard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conservative_profile_runs_only_crossed_markets() {
+        let standard = StrategySettings::from_config(&Config::default());
+        assert_eq!(standard, StrategySettings::default());
+        assert!(standard.uses_detector());
+        assert_eq!(standard.strategies().len(), 2);
+
+        let config = Config {
+            strategy_profile: " Conservative".into(),
+            crossed_market_margin_bps: 25.0,
+            ..Config::default()
+        };
+        let conservative = StrategySettings::from_config(&config);
+        assert_eq!(conservative.profile, StrategyProfile::Conservative);
+        assert_eq!(conservative.crossed_market_margin_bps, Decimal::from(25));
+        assert!(!conservative.uses_detector());
+        let names: Vec<_> = conservative.strategies().iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["Crossed Market"]);
+
+        let warm_up = WarmUpStatus::of(standard.strategies()[0].as_ref()).await;
+        assert_eq!((warm_up.ready, warm_up.progress), (false, 0.0));
+        let crossed = WarmUpStatus::of(conservative.strategies()[0].as_ref()).await;
+        assert_eq!((crossed.ready, crossed.progress), (true, 1.0));
+
+        let phoenix = Arc::new(PhoenixProvider::new());
+        assert_eq!(standard.strategies_with_books(&phoenix).len(), 2);
+        let imbalance = StrategySettings::from_config(&Config {
+            imbalance_strategy_enabled: true,
+            imbalance_threshold: 1.5,
+            ..Config::default()
+        });
+        assert_eq!(imbalance.imbalance.unwrap().threshold, Decimal::ONE);
+        let names: Vec<_> = imbalance.strategies_with_books(&phoenix).iter().map(|s| s.name()).collect();
+        assert_eq!(names.last(), Some(&"Order Book Imbalance"));
+        assert_eq!(imbalance.strategies().len(), 2);
+
+        let typo = Config {
+            strategy_profile: "conservitive".into(),
+            ..Config::default()
+        };
+        assert_eq!(StrategySettings::from_config(&typo).profile, StrategyProfile::Standard);
+
+        let tuned = StrategySettings::from_config(&Config {
+            stat_arb_window: 50,
+            stat_arb_z_score: -1.0,
+            ..Config::default()
+        });
+        assert_eq!(tuned.statistical_window, 50);
+        assert_eq!(tuned.statistical_z_score, Decimal::new(20, 1));
+    }
+}
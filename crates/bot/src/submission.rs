@@ -0,0 +1,302 @@
+//! Submission Coordination
+//!
+//! Trades from one wallet run concurrently, and two of them writing the same
+//! token account (the wallet's USDC account, a pool's vault) can land in the
+//! same slot with the same blockhash and fail on account locks. Before a
+//! transaction is sent, the executor takes a permit for the accounts it
+//! touches. Submissions whose accounts overlap run one after another in the
+//! order they asked; disjoint ones run in parallel. Overlap follows the
+//! runtime's locking rules: a written account conflicts with any other use,
+//! read-only accounts may be shared.
+//!
+//! The fee payer is left out, since every transaction of the wallet pays
+//! from it. Accounts loaded from lookup tables are compared by table and
+//! index, not by address.
+
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::sync::Notify;
+
+/// An account a transaction locks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockKey {
+    Account(Pubkey),
+    /// Entry of an address lookup table
+    Lookup(Pubkey, u8),
+}
+
+/// The accounts a transaction writes and reads
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountSet {
+    pub writable: HashSet<LockKey>,
+    pub readonly: HashSet<LockKey>,
+}
+
+impl AccountSet {
+    /// Accounts of `message`, without the fee payer and the programs it calls
+    pub fn from_message(message: &VersionedMessage, payer: &Pubkey) -> Self {
+        let mut accounts = Self::default();
+        let programs: HashSet<u8> = message
+            .instructions()
+            .iter()
+            .map(|ix| ix.program_id_index)
+            .collect();
+        for (index, key) in message.static_account_keys().iter().enumerate() {
+            if key == payer || programs.contains(&(index as u8)) {
+                continue;
+            }
+            if message.is_maybe_writable(index) {
+                accounts.writable.insert(LockKey::Account(*key));
+            } else {
+                accounts.readonly.insert(LockKey::Account(*key));
+            }
+        }
+        for lookup in message.address_table_lookups().unwrap_or_default() {
+            let key = |index: &u8| LockKey::Lookup(lookup.account_key, *index);
+            accounts.writable.extend(lookup.writable_indexes.iter().map(key));
+            accounts.readonly.extend(lookup.readonly_indexes.iter().map(key));
+        }
+        accounts
+    }
+
+    fn conflicts_with(&self, other: &AccountSet) -> bool {
+        !self.writable.is_disjoint(&other.writable)
+            || !self.writable.is_disjoint(&other.readonly)
+            || !self.readonly.is_disjoint(&other.writable)
+    }
+}
+
+/// Waits on the coordinator since the last [`SubmissionCoordinator::take_stats`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubmissionStats {
+    /// Seconds each contended submission waited for its accounts
+    pub waits_secs: Vec<f64>,
+    /// Most submissions waiting at once
+    pub peak_waiting: usize,
+}
+
+#[derive(Debug, Default)]
+struct Locks {
+    writes: HashSet<LockKey>,
+    reads: HashMap<LockKey, usize>,
+    /// Submissions waiting, by arrival
+    queue: BTreeMap<u64, AccountSet>,
+    next_ticket: u64,
+    stats: SubmissionStats,
+}
+
+impl Locks {
+    fn held_conflict(&self, accounts: &AccountSet) -> bool {
+        accounts
+            .writable
+            .iter()
+            .any(|k| self.writes.contains(k) || self.reads.contains_key(k))
+            || accounts.readonly.iter().any(|k| self.writes.contains(k))
+    }
+
+    /// Whether a held permit, or a submission queued before `ticket`,
+    /// conflicts with `accounts`
+    fn blocked(&self, accounts: &AccountSet, ticket: u64) -> bool {
+        self.held_conflict(accounts)
+            || self
+                .queue
+                .range(..ticket)
+                .any(|(_, earlier)| earlier.conflicts_with(accounts))
+    }
+
+    fn hold(&mut self, accounts: &AccountSet) {
+        self.writes.extend(accounts.writable.iter().copied());
+        for key in &accounts.readonly {
+            *self.reads.entry(*key).or_default() += 1;
+        }
+    }
+
+    fn release(&mut self, accounts: &AccountSet) {
+        for key in &accounts.writable {
+            self.writes.remove(key);
+        }
+        for key in &accounts.readonly {
+            if let Some(count) = self.reads.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    self.reads.remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// Serializes one wallet's submissions that touch the same accounts
+#[derive(Debug, Default)]
+pub struct SubmissionCoordinator {
+    locks: Mutex<Locks>,
+    released: Notify,
+}
+
+impl SubmissionCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no held permit and no earlier waiting submission conflicts
+    /// with `accounts`, then hold them until the permit is dropped. A
+    /// cancelled wait gives up its place in the queue.
+    pub async fn acquire(self: &Arc<Self>, accounts: AccountSet) -> SubmissionPermit {
+        let ticket = {
+            let mut locks = self.lock();
+            if !locks.blocked(&accounts, u64::MAX) {
+                locks.hold(&accounts);
+                return self.permit(accounts);
+            }
+            let ticket = locks.next_ticket;
+            locks.next_ticket += 1;
+            locks.queue.insert(ticket, accounts);
+            locks.stats.peak_waiting = locks.stats.peak_waiting.max(locks.queue.len());
+            ticket
+        };
+        let mut place = QueuePlace {
+            coordinator: self,
+            ticket: Some(ticket),
+        };
+
+        let started = Instant::now();
+        loop {
+            let released = self.released.notified();
+            {
+                let mut locks = self.lock();
+                let free = locks
+                    .queue
+                    .get(&ticket)
+                    .is_some_and(|accounts| !locks.blocked(accounts, ticket));
+                if free {
+                    let accounts = locks.queue.remove(&ticket).unwrap_or_default();
+                    place.ticket = None;
+                    locks.hold(&accounts);
+                    locks.stats.waits_secs.push(started.elapsed().as_secs_f64());
+                    drop(locks);
+                    // Later submissions may have waited on this one's place
+                    self.released.notify_waiters();
+                    return self.permit(accounts);
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Submissions waiting for their accounts right now
+    pub fn waiting(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn take_stats(&self) -> SubmissionStats {
+        let mut locks = self.lock();
+        let waiting = locks.queue.len();
+        let mut stats = std::mem::take(&mut locks.stats);
+        stats.peak_waiting = stats.peak_waiting.max(waiting);
+        locks.stats.peak_waiting = waiting;
+        stats
+    }
+
+    fn permit(self: &Arc<Self>, accounts: AccountSet) -> SubmissionPermit {
+        SubmissionPermit {
+            coordinator: self.clone(),
+            accounts,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Locks> {
+        self.locks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Accounts held for one submission; released on drop
+pub struct SubmissionPermit {
+    coordinator: Arc<SubmissionCoordinator>,
+    accounts: AccountSet,
+}
+
+/// A queued submission; leaves the queue if the wait is dropped
+struct QueuePlace<'a> {
+    coordinator: &'a SubmissionCoordinator,
+    ticket: Option<u64>,
+}
+
+impl Drop for QueuePlace<'_> {
+    fn drop(&mut self) {
+        if let Some(ticket) = self.ticket {
+            self.coordinator.lock().queue.remove(&ticket);
+            self.coordinator.released.notify_waiters();
+        }
+    }
+}
+
+impl Drop for SubmissionPermit {
+    fn drop(&mut self) {
+        self.coordinator.lock().release(&self.accounts);
+        self.coordinator.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn set(writable: &[u8], readonly: &[u8]) -> AccountSet {
+        let key = |b: &u8| LockKey::Account(Pubkey::new_from_array([*b; 32]));
+        AccountSet {
+            writable: writable.iter().map(key).collect(),
+            readonly: readonly.iter().map(key).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_submissions_wait_in_order() {
+        let coordinator = Arc::new(SubmissionCoordinator::new());
+        let first = coordinator.acquire(set(&[1], &[9])).await;
+        // Disjoint writes and a shared read go ahead at once
+        let disjoint = coordinator.acquire(set(&[2], &[9])).await;
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let spawn = |name: &'static str, accounts: AccountSet| {
+            let coordinator = coordinator.clone();
+            let order = order.clone();
+            tokio::spawn(async move {
+                let _permit = coordinator.acquire(accounts).await;
+                order.lock().unwrap().push(name);
+            })
+        };
+        let second = spawn("second", set(&[1, 3], &[]));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // Free on its own, but queued behind `second`, which wants account 3
+        let third = spawn("third", set(&[3], &[]));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(coordinator.waiting(), 2);
+        assert!(order.lock().unwrap().is_empty());
+
+        drop(disjoint);
+        drop(first);
+        second.await.unwrap();
+        third.await.unwrap();
+        assert_eq!(*order.lock().unwrap(), ["second", "third"]);
+
+        let stats = coordinator.take_stats();
+        assert_eq!(stats.waits_secs.len(), 2);
+        assert_eq!(stats.peak_waiting, 2);
+        assert_eq!(coordinator.take_stats(), SubmissionStats::default());
+
+        // A wait that is given up leaves the queue
+        let held = coordinator.acquire(set(&[5], &[])).await;
+        let gave_up = tokio::time::timeout(
+            Duration::from_millis(20),
+            coordinator.acquire(set(&[5], &[])),
+        );
+        assert!(gave_up.await.is_err());
+        assert_eq!(coordinator.waiting(), 0);
+        drop(held);
+        drop(coordinator.acquire(set(&[3, 5], &[])).await);
+    }
+}
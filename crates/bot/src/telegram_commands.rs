@@ -0,0 +1,265 @@
+//! Telegram Bot Commands
+//!
+//! With `TELEGRAM_BOT_TOKEN` set, the bot long-polls the Telegram Bot API for
+//! commands and answers in the chat they came from. Only the chats listed in
+//! `TELEGRAM_COMMAND_CHAT_IDS` are obeyed; messages from any other chat are
+//! logged and dropped. `/pause` and `/resume` act on the same
+//! [`TradingControl`](crate::control::TradingControl) as the REST API.
+//!
+//! | Command | Reply |
+//! |---|---|
+//! | `/status` | mode, pause, circuit breaker, exposure, trades in flight |
+//! | `/pause [reason]` | pauses trading |
+//! | `/resume` | resumes trading |
+//! | `/pnl` | the day's P&L and trade count |
+//! | `/lasttrades [N]` | the last N recorded trades (default 5, at most 20) |
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use solana_arb_core::config::Config;
+use solana_arb_core::history::{rotation, TradeRecord, COMMITMENT_UPDATE};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::time::Duration;
+use tracing::warn;
+
+/// How long one `getUpdates` call waits for a message
+const LONG_POLL_SECS: u64 = 30;
+const DEFAULT_LAST_TRADES: usize = 5;
+pub const MAX_LAST_TRADES: usize = 20;
+
+pub const HELP: &str = "/status - mode, pause and exposure\n/pause [reason] - pause trading\n/resume - resume trading\n/pnl - today's P&L\n/lasttrades [N] - the last N trades";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Status,
+    Pause(Option<String>),
+    Resume,
+    Pnl,
+    LastTrades(usize),
+    Help,
+}
+
+impl Command {
+    /// Parse `/command[@botname] [args]`; `None` for anything else
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (head, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let name = head.strip_prefix('/')?;
+        let name = name.split_once('@').map_or(name, |(name, _)| name);
+        let args = args.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "status" => Some(Command::Status),
+            "pause" => Some(Command::Pause((!args.is_empty()).then(|| args.to_string()))),
+            "resume" => Some(Command::Resume),
+            "pnl" => Some(Command::Pnl),
+            "lasttrades" => {
+                let n = args.parse().unwrap_or(DEFAULT_LAST_TRADES);
+                Some(Command::LastTrades(n.clamp(1, MAX_LAST_TRADES)))
+            }
+            "help" | "start" => Some(Command::Help),
+            _ => None,
+        }
+    }
+}
+
+/// A command from an allowed chat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatCommand {
+    pub chat_id: i64,
+    pub command: Command,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdatesResponse {
+    ok: bool,
+    #[serde(default)]
+    result: Vec<Update>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    #[serde(default)]
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+/// Comma-separated chat ids; unparsable entries are skipped with a warning
+pub fn parse_chat_ids(list: &str) -> HashSet<i64> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .filter_map(|id| match id.parse() {
+            Ok(id) => Some(id),
+            Err(_) => {
+                warn!("Ignoring invalid Telegram chat id '{}'", id);
+                None
+            }
+        })
+        .collect()
+}
+
+pub struct TelegramCommands {
+    client: Client,
+    api_url: String,
+    allowed_chats: HashSet<i64>,
+    /// Id after the last update seen, so each is handled once
+    offset: i64,
+}
+
+impl TelegramCommands {
+    pub fn new(bot_token: &str, allowed_chats: HashSet<i64>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(LONG_POLL_SECS + 10))
+                .build()
+                .unwrap_or_default(),
+            api_url: format!("https://api.telegram.org/bot{}", bot_token),
+            allowed_chats,
+            offset: 0,
+        }
+    }
+
+    /// `None` without a bot token, or without any chat allowed to use it
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let token = config.telegram_bot_token.as_deref()?;
+        let allowed = parse_chat_ids(&config.telegram_command_chat_ids);
+        if allowed.is_empty() {
+            warn!("TELEGRAM_BOT_TOKEN is set but TELEGRAM_COMMAND_CHAT_IDS is empty - Telegram commands are off");
+            return None;
+        }
+        Some(Self::new(token, allowed))
+    }
+
+    /// Wait for the next batch of messages and return the commands among
+    /// them from allowed chats
+    pub async fn poll(&mut self) -> Result<Vec<ChatCommand>> {
+        let response: UpdatesResponse = self
+            .client
+            .get(format!("{}/getUpdates", self.api_url))
+            .query(&[
+                ("offset", self.offset.to_string()),
+                ("timeout", LONG_POLL_SECS.to_string()),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        if !response.ok {
+            return Err(anyhow!(
+                "getUpdates failed: {}",
+                response.description.unwrap_or_default()
+            ));
+        }
+        Ok(self.accept(response.result))
+    }
+
+    fn accept(&mut self, updates: Vec<Update>) -> Vec<ChatCommand> {
+        let mut commands = Vec::new();
+        for update in updates {
+            self.offset = self.offset.max(update.update_id + 1);
+            let Some(message) = update.message else {
+                continue;
+            };
+            let Some(command) = message.text.as_deref().and_then(Command::parse) else {
+                continue;
+            };
+            if !self.allowed_chats.contains(&message.chat.id) {
+                warn!("Ignoring Telegram command from unlisted chat {}", message.chat.id);
+                continue;
+            }
+            commands.push(ChatCommand {
+                chat_id: message.chat.id,
+                command,
+            });
+        }
+        commands
+    }
+
+    pub async fn reply(&self, chat_id: i64, text: &str) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/sendMessage", self.api_url))
+            .json(&json!({ "chat_id": chat_id, "text": text }))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("sendMessage failed: {}", response.text().await?));
+        }
+        Ok(())
+    }
+}
+
+/// The last `n` trades in the history file, oldest first
+pub fn last_trades(history: &Path, n: usize) -> std::io::Result<Vec<TradeRecord>> {
+    let mut trades = VecDeque::with_capacity(n + 1);
+    rotation::for_each_line(history, &rotation::DateRange::all(), |line| {
+        let Ok(record) = serde_json::from_str::<TradeRecord>(line) else {
+            return;
+        };
+        if record.trade_type == COMMITMENT_UPDATE {
+            return;
+        }
+        trades.push_back(record);
+        if trades.len() > n {
+            trades.pop_front();
+        }
+    })?;
+    Ok(trades.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands_and_drop_unlisted_chats() {
+        assert_eq!(Command::parse("/status"), Some(Command::Status));
+        assert_eq!(Command::parse("/Status@arb_bot"), Some(Command::Status));
+        assert_eq!(
+            Command::parse("/pause  rpc is flaky "),
+            Some(Command::Pause(Some("rpc is flaky".into())))
+        );
+        assert_eq!(Command::parse("/pause"), Some(Command::Pause(None)));
+        assert_eq!(Command::parse("/lasttrades 3"), Some(Command::LastTrades(3)));
+        assert_eq!(Command::parse("/lasttrades"), Some(Command::LastTrades(DEFAULT_LAST_TRADES)));
+        assert_eq!(Command::parse("/lasttrades 500"), Some(Command::LastTrades(MAX_LAST_TRADES)));
+        assert_eq!(Command::parse("status"), None);
+        assert_eq!(Command::parse("/withdraw all"), None);
+
+        assert_eq!(parse_chat_ids(" 42, -100123,bad,"), HashSet::from([42, -100123]));
+
+        let mut commands = TelegramCommands::new("token", HashSet::from([42]));
+        let updates: UpdatesResponse = serde_json::from_value(json!({
+            "ok": true,
+            "result": [
+                { "update_id": 7, "message": { "chat": { "id": 42 }, "text": "/pnl" } },
+                { "update_id": 8, "message": { "chat": { "id": 99 }, "text": "/pause" } },
+                { "update_id": 9, "message": { "chat": { "id": 42 }, "text": "hello" } },
+                { "update_id": 10 }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            commands.accept(updates.result),
+            vec![ChatCommand { chat_id: 42, command: Command::Pnl }]
+        );
+        assert_eq!(commands.offset, 11);
+    }
+}
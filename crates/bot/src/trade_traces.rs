@@ -0,0 +1,404 @@
+//! Trade Traces
+//!
+//! When realized profit differs from what was detected, the answer is in one
+//! of four places: the plan (detected prices and size), the Jupiter quote,
+//! the pre-submission simulation, or what happened on-chain. Each stage is
+//! recorded against the opportunity ID as the trade moves through it, so the
+//! API can put them side by side. Only the most recent trades are kept.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use solana_arb_core::memory::MemoryUsage;
+use solana_arb_core::tokens::TokenRegistry;
+use solana_arb_core::{ArbitrageOpportunity, TradeResult, Uuid};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// Number of trades kept
+const TRACE_CAPACITY: usize = 500;
+
+/// What the detector saw and the size the bot chose
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedTrade {
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub buy_price: Decimal,
+    pub sell_price: Decimal,
+    pub size_usd: Decimal,
+    pub net_profit_pct: Decimal,
+    pub expected_profit_usd: Decimal,
+    /// DEX fees implied by the gap between gross and net spread
+    pub dex_fees_usd: Decimal,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl PlannedTrade {
+    pub fn new(opp: &ArbitrageOpportunity, size_usd: Decimal) -> Self {
+        let hundred = Decimal::from(100);
+        Self {
+            pair: opp.pair.symbol(),
+            buy_dex: opp.buy_dex.display_name().to_string(),
+            sell_dex: opp.sell_dex.display_name().to_string(),
+            buy_price: opp.buy_price,
+            sell_price: opp.sell_price,
+            size_usd,
+            net_profit_pct: opp.net_profit_pct,
+            expected_profit_usd: size_usd * opp.net_profit_pct / hundred,
+            dex_fees_usd: size_usd * (opp.gross_profit_pct - opp.net_profit_pct) / hundred,
+            detected_at: opp.detected_at,
+        }
+    }
+}
+
+/// The Jupiter quote the transaction was built from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuoteSnapshot {
+    pub input_token: String,
+    pub output_token: String,
+    pub in_amount: Decimal,
+    pub out_amount: Decimal,
+    /// Least output accepted under the slippage tolerance
+    pub min_out_amount: Option<Decimal>,
+    /// Quote token per base token implied by the amounts
+    pub implied_price: Option<Decimal>,
+    pub price_impact_pct: Option<Decimal>,
+    /// AMM labels of the route, in order
+    pub route: Vec<String>,
+}
+
+impl QuoteSnapshot {
+    /// Summarize a Jupiter v6 quote. `None` if its mints or amounts are
+    /// missing or unknown to the registry.
+    pub fn from_quote(
+        quote: &serde_json::Value,
+        registry: &TokenRegistry,
+        opp: &ArbitrageOpportunity,
+    ) -> Option<Self> {
+        let field = |key: &str| quote.get(key).and_then(|v| v.as_str());
+        let input = registry.get(field("inputMint")?)?;
+        let output = registry.get(field("outputMint")?)?;
+        let amount = |key: &str, decimals: u8| {
+            field(key)
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(|atoms| solana_arb_core::tokens::from_atoms(atoms, decimals))
+        };
+        let in_amount = amount("inAmount", input.decimals)?;
+        let out_amount = amount("outAmount", output.decimals)?;
+
+        let implied_price = if input.symbol == opp.pair.quote {
+            (out_amount > Decimal::ZERO).then(|| in_amount / out_amount)
+        } else {
+            (in_amount > Decimal::ZERO).then(|| out_amount / in_amount)
+        };
+        let route = quote
+            .get("routePlan")
+            .and_then(|plan| plan.as_array())
+            .map(|plan| {
+                plan.iter()
+                    .filter_map(|step| step.pointer("/swapInfo/label")?.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            input_token: input.symbol.clone(),
+            output_token: output.symbol.clone(),
+            in_amount,
+            out_amount,
+            min_out_amount: amount("otherAmountThreshold", output.decimals),
+            implied_price: implied_price.map(|p| p.round_dp(9)),
+            price_impact_pct: field("priceImpactPct").and_then(|v| v.parse().ok()),
+            route,
+        })
+    }
+}
+
+/// What the executor reported
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionSnapshot {
+    pub success: bool,
+    pub signature: Option<String>,
+    /// Net payer balance change from the pre-submission simulation
+    pub simulated_profit_usd: Option<Decimal>,
+    pub actual_profit_usd: Decimal,
+    /// Share of a separately paid transaction fee (batched trades)
+    pub network_fee_usd: Decimal,
+    pub priority_fee_micro_lamports: Option<u64>,
+    pub error: Option<String>,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Final commitment of a live trade
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OnchainOutcome {
+    /// "finalized", "failed" or "reorged"
+    pub status: String,
+    pub error: Option<String>,
+    pub resolved_at: DateTime<Utc>,
+}
+
+/// Every recorded stage of one trade
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TradeTrace {
+    pub opportunity_id: Uuid,
+    pub plan: Option<PlannedTrade>,
+    pub quote: Option<QuoteSnapshot>,
+    /// Loss in USD at the quote's least accepted output
+    pub worst_case_loss_usd: Option<Decimal>,
+    pub execution: Option<ExecutionSnapshot>,
+    /// Program logs of the pre-submission simulation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub simulation_logs: Vec<String>,
+    /// `None` while a live trade awaits finalization, and for simulations
+    pub onchain: Option<OnchainOutcome>,
+}
+
+/// Differences between stages, in USD. Positive means better than planned.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TradeDiff {
+    #[serde(flatten)]
+    pub trace: TradeTrace,
+    pub expected_profit_usd: Option<Decimal>,
+    pub simulated_vs_expected_usd: Option<Decimal>,
+    pub realized_vs_expected_usd: Option<Decimal>,
+    pub realized_vs_simulated_usd: Option<Decimal>,
+    /// Realized profit above the worst-case bound; negative means the
+    /// bound did not hold
+    pub realized_vs_worst_case_usd: Option<Decimal>,
+    /// Quote price against the detected price of the quoted leg, in percent
+    pub quote_price_slippage_pct: Option<Decimal>,
+}
+
+impl TradeTrace {
+    /// Profit kept after separately paid fees; zero unless the trade
+    /// succeeded and was not rolled back
+    fn realized_profit(&self) -> Option<Decimal> {
+        let execution = self.execution.as_ref()?;
+        let reverted = self
+            .onchain
+            .as_ref()
+            .is_some_and(|o| o.status != "finalized");
+        Some(if execution.success && !reverted {
+            execution.actual_profit_usd - execution.network_fee_usd
+        } else {
+            Decimal::ZERO
+        })
+    }
+
+    pub fn diff(&self) -> TradeDiff {
+        let expected = self.plan.as_ref().map(|p| p.expected_profit_usd);
+        let simulated = self.execution.as_ref().and_then(|e| e.simulated_profit_usd);
+        let realized = self.realized_profit();
+        let delta = |a: Option<Decimal>, b: Option<Decimal>| Some((a? - b?).round_dp(6));
+        let worst_case_profit = self.worst_case_loss_usd.map(|loss| -loss);
+
+        // The standard route buys the base token, so its quote prices the
+        // buy leg; a flash loan sells first, so its quote prices the sell leg
+        let quote_price_slippage_pct = self.plan.as_ref().zip(self.quote.as_ref()).and_then(
+            |(plan, quote)| {
+                let price = quote.implied_price?;
+                let buying = quote.output_token != plan.pair.split('/').nth(1)?;
+                let (detected, better) = if buying {
+                    (plan.buy_price, plan.buy_price - price)
+                } else {
+                    (plan.sell_price, price - plan.sell_price)
+                };
+                (detected > Decimal::ZERO)
+                    .then(|| (better / detected * Decimal::from(100)).round_dp(4))
+            },
+        );
+
+        TradeDiff {
+            trace: self.clone(),
+            expected_profit_usd: expected,
+            simulated_vs_expected_usd: delta(simulated, expected),
+            realized_vs_expected_usd: delta(realized, expected),
+            realized_vs_simulated_usd: delta(realized, simulated),
+            realized_vs_worst_case_usd: delta(realized, worst_case_profit),
+            quote_price_slippage_pct,
+        }
+    }
+}
+
+/// Bounded store of recent trade traces, shared by the bot and executor
+#[derive(Debug, Default)]
+pub struct TradeTraces {
+    traces: RwLock<(HashMap<Uuid, TradeTrace>, VecDeque<Uuid>)>,
+}
+
+impl TradeTraces {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Approximate memory held by the kept traces (their strings excluded
+    /// but for simulation logs, which can run to kilobytes)
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let guard = self.traces.read().unwrap_or_else(|e| e.into_inner());
+        let logs: usize = guard
+            .0
+            .values()
+            .flat_map(|t| &t.simulation_logs)
+            .map(|line| size_of::<String>() + line.capacity())
+            .sum();
+        MemoryUsage::of::<(Uuid, TradeTrace)>(guard.0.len(), size_of::<Uuid>()).with_bytes(logs)
+    }
+
+    fn update(&self, id: Uuid, apply: impl FnOnce(&mut TradeTrace)) {
+        let mut guard = self.traces.write().unwrap_or_else(|e| e.into_inner());
+        let (traces, order) = &mut *guard;
+        if !traces.contains_key(&id) {
+            if order.len() >= TRACE_CAPACITY {
+                if let Some(oldest) = order.pop_front() {
+                    traces.remove(&oldest);
+                }
+            }
+            order.push_back(id);
+        }
+        apply(traces.entry(id).or_insert_with(|| TradeTrace {
+            opportunity_id: id,
+            ..Default::default()
+        }));
+    }
+
+    pub fn record_plan(&self, opp: &ArbitrageOpportunity, size_usd: Decimal) {
+        let plan = PlannedTrade::new(opp, size_usd);
+        self.update(opp.id, |trace| trace.plan = Some(plan));
+    }
+
+    pub fn record_quote(&self, id: Uuid, quote: QuoteSnapshot) {
+        self.update(id, |trace| trace.quote = Some(quote));
+    }
+
+    pub fn record_worst_case(&self, id: Uuid, loss_usd: Decimal) {
+        self.update(id, |trace| trace.worst_case_loss_usd = Some(loss_usd));
+    }
+
+    pub fn record_simulation(&self, id: Uuid, logs: Vec<String>) {
+        self.update(id, |trace| trace.simulation_logs = logs);
+    }
+
+    pub fn record_execution(
+        &self,
+        outcome: &TradeResult,
+        network_fee_usd: Decimal,
+        priority_fee_micro_lamports: Option<u64>,
+    ) {
+        let execution = ExecutionSnapshot {
+            success: outcome.success,
+            signature: outcome.signature.clone(),
+            simulated_profit_usd: outcome.simulated_profit,
+            actual_profit_usd: outcome.actual_profit,
+            network_fee_usd,
+            priority_fee_micro_lamports,
+            error: outcome.error.clone(),
+            executed_at: outcome.executed_at,
+        };
+        self.update(outcome.opportunity_id, |trace| trace.execution = Some(execution));
+    }
+
+    /// Record the final commitment of a trade tracked by its opportunity ID.
+    /// Trades no longer in the store are ignored.
+    pub fn record_onchain(&self, id: &str, status: &str, error: Option<&str>) {
+        let Ok(id) = id.parse::<Uuid>() else {
+            return;
+        };
+        let mut guard = self.traces.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(trace) = guard.0.get_mut(&id) {
+            trace.onchain = Some(OnchainOutcome {
+                status: status.to_string(),
+                error: error.map(str::to_string),
+                resolved_at: Utc::now(),
+            });
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<TradeTrace> {
+        self.traces
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .0
+            .get(&id)
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use solana_arb_core::{DexType, TokenPair};
+
+    #[test]
+    fn test_trace_diff_across_stages() {
+        let opp = ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(102),
+            gross_profit_pct: Decimal::from(2),
+            net_profit_pct: Decimal::from(1),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let traces = TradeTraces::new();
+        traces.record_plan(&opp, Decimal::from(1000));
+
+        // 1,000 USDC in for 9.95 SOL out: an entry price of ~100.5025
+        let quote = serde_json::json!({
+            "inputMint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            "outputMint": "So11111111111111111111111111111111111111112",
+            "inAmount": "1000000000",
+            "outAmount": "9950000000",
+            "otherAmountThreshold": "9900250000",
+            "priceImpactPct": "0.01",
+            "routePlan": [{ "swapInfo": { "label": "Raydium" } }, { "swapInfo": { "label": "Whirlpool" } }]
+        });
+        let snapshot = QuoteSnapshot::from_quote(&quote, &TokenRegistry::mainnet(), &opp).unwrap();
+        assert_eq!(snapshot.out_amount, Decimal::new(995, 2));
+        assert_eq!(snapshot.route, vec!["Raydium", "Whirlpool"]);
+        traces.record_quote(opp.id, snapshot);
+        traces.record_worst_case(opp.id, Decimal::from(5));
+
+        traces.record_execution(
+            &TradeResult {
+                opportunity_id: opp.id,
+                signature: Some("sig".to_string()),
+                success: true,
+                actual_profit: Decimal::from(6),
+                executed_at: Utc::now(),
+                error: None,
+                simulated_profit: Some(Decimal::from(7)),
+                failure_kind: None,
+            },
+            Decimal::ZERO,
+            Some(50_000),
+        );
+        traces.record_onchain(&opp.id.to_string(), "finalized", None);
+
+        let diff = traces.get(opp.id).unwrap().diff();
+        assert_eq!(diff.expected_profit_usd, Some(Decimal::from(10)));
+        assert_eq!(diff.trace.plan.as_ref().unwrap().dex_fees_usd, Decimal::from(10));
+        assert_eq!(diff.simulated_vs_expected_usd, Some(Decimal::from(-3)));
+        assert_eq!(diff.realized_vs_expected_usd, Some(Decimal::from(-4)));
+        assert_eq!(diff.realized_vs_simulated_usd, Some(Decimal::from(-1)));
+        assert_eq!(diff.realized_vs_worst_case_usd, Some(Decimal::from(11)));
+        assert_eq!(diff.quote_price_slippage_pct, Some(Decimal::new(-5025, 4)));
+
+        // A failed transaction realizes nothing
+        traces.record_onchain(&opp.id.to_string(), "failed", Some("custom program error"));
+        let diff = traces.get(opp.id).unwrap().diff();
+        assert_eq!(diff.realized_vs_expected_usd, Some(Decimal::from(-10)));
+    }
+}
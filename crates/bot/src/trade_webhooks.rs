@@ -0,0 +1,381 @@
+//! Trade Webhooks
+//!
+//! Posts one signed record per completed live trade to an accounting
+//! endpoint. Amounts are decimal strings in USD and timestamps are RFC 3339
+//! UTC, so tax and bookkeeping tools can ingest the body without knowing the
+//! bot's types. Every record is journaled before delivery; failed deliveries
+//! are kept for a manual retry, and a whole day can be replayed from the
+//! journal. Receivers should deduplicate on `trade_id`.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use solana_arb_core::memory::MemoryUsage;
+use solana_arb_core::{ArbitrageOpportunity, TradeResult};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+/// Header carrying `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`
+pub const SIGNATURE_HEADER: &str = "X-Arb-Signature";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Failed webhooks kept for a manual retry; older ones are dropped and can
+/// still be replayed from the journal
+const FAILED_KEPT: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct TradeWebhookConfig {
+    pub url: String,
+    /// Signing secret; without one, webhooks are sent unsigned
+    pub secret: Option<String>,
+    pub max_attempts: u32,
+    /// JSONL journal of every emitted webhook, for replay
+    pub journal_path: PathBuf,
+}
+
+/// Costs between the gross edge and the realized profit, in USD
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeCosts {
+    /// DEX swap fees and slippage allowance priced into the opportunity
+    pub dex_fees_usd: String,
+    /// This trade's share of the transaction fee
+    pub network_fee_usd: String,
+    pub total_usd: String,
+}
+
+/// Normalized webhook body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeWebhookPayload {
+    pub event: String,
+    pub trade_id: String,
+    pub session_id: String,
+    pub signature: Option<String>,
+    pub pair: String,
+    pub base: String,
+    pub quote: String,
+    pub buy_venue: String,
+    pub sell_venue: String,
+    pub buy_price: String,
+    pub sell_price: String,
+    pub size_usd: String,
+    pub gross_profit_usd: String,
+    pub costs: TradeCosts,
+    pub net_profit_usd: String,
+    pub detected_at: String,
+    pub executed_at: String,
+}
+
+fn decimal(value: Decimal) -> String {
+    value.round_dp(6).normalize().to_string()
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+impl TradeWebhookPayload {
+    /// Build the record for a completed trade. `fee_usd` is the trade's share
+    /// of a transaction fee accounted separately (batched trades).
+    pub fn new(
+        opp: &ArbitrageOpportunity,
+        session_id: &str,
+        size: Decimal,
+        outcome: &TradeResult,
+        fee_usd: Decimal,
+    ) -> Self {
+        let dex_fees = size * (opp.gross_profit_pct - opp.net_profit_pct) / Decimal::from(100);
+        let net_profit = outcome.actual_profit - fee_usd;
+        let total_costs = dex_fees + fee_usd;
+
+        Self {
+            event: "trade.completed".to_string(),
+            trade_id: opp.id.to_string(),
+            session_id: session_id.to_string(),
+            signature: outcome.signature.clone(),
+            pair: opp.pair.symbol(),
+            base: opp.pair.base.clone(),
+            quote: opp.pair.quote.clone(),
+            buy_venue: opp.buy_dex.to_string(),
+            sell_venue: opp.sell_dex.to_string(),
+            buy_price: decimal(opp.buy_price),
+            sell_price: decimal(opp.sell_price),
+            size_usd: decimal(size),
+            gross_profit_usd: decimal(net_profit + total_costs),
+            costs: TradeCosts {
+                dex_fees_usd: decimal(dex_fees),
+                network_fee_usd: decimal(fee_usd),
+                total_usd: decimal(total_costs),
+            },
+            net_profit_usd: decimal(net_profit),
+            detected_at: timestamp(opp.detected_at),
+            executed_at: timestamp(outcome.executed_at),
+        }
+    }
+
+    fn executed_on(&self) -> Option<NaiveDate> {
+        DateTime::parse_from_rfc3339(&self.executed_at)
+            .ok()
+            .map(|at| at.with_timezone(&Utc).date_naive())
+    }
+}
+
+/// Signature header value for a body sent at `unix_time`
+pub fn sign(secret: &str, unix_time: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.{}", unix_time, body).as_bytes());
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("t={},v1={}", unix_time, digest)
+}
+
+/// Journaled webhooks for trades executed on `date` (UTC)
+pub fn journal_entries_on(journal: &str, date: NaiveDate) -> Vec<TradeWebhookPayload> {
+    journal
+        .lines()
+        .filter_map(|line| serde_json::from_str::<TradeWebhookPayload>(line).ok())
+        .filter(|payload| payload.executed_on() == Some(date))
+        .collect()
+}
+
+/// Delivery counters for the status endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeWebhookStatus {
+    pub url: String,
+    pub signed: bool,
+    pub delivered: u64,
+    pub failed: Vec<String>,
+}
+
+/// Journals, signs and delivers trade webhooks in the background
+pub struct TradeWebhooks {
+    config: TradeWebhookConfig,
+    client: Client,
+    journal_lock: tokio::sync::Mutex<()>,
+    /// Webhooks that exhausted their attempts, awaiting a manual retry
+    failed: Mutex<Vec<TradeWebhookPayload>>,
+    delivered: AtomicU64,
+}
+
+impl TradeWebhooks {
+    pub fn new(config: TradeWebhookConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            client: Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            journal_lock: tokio::sync::Mutex::new(()),
+            failed: Mutex::new(Vec::new()),
+            delivered: AtomicU64::new(0),
+        })
+    }
+
+    /// Journal and deliver a webhook without blocking the caller
+    pub fn emit(self: &Arc<Self>, payload: TradeWebhookPayload) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = this.journal(&payload).await {
+                warn!("Failed to journal trade webhook {}: {}", payload.trade_id, e);
+            }
+            this.deliver(payload).await;
+        });
+    }
+
+    /// Re-send every webhook that exhausted its attempts. Returns how many
+    /// were queued.
+    pub fn retry_failed(self: &Arc<Self>) -> usize {
+        let failed = std::mem::take(&mut *self.failed.lock().unwrap());
+        let count = failed.len();
+        for payload in failed {
+            let this = self.clone();
+            tokio::spawn(async move { this.deliver(payload).await });
+        }
+        count
+    }
+
+    /// Re-send every journaled webhook for trades executed on `date` (UTC).
+    /// Returns how many were queued.
+    pub async fn replay(self: &Arc<Self>, date: NaiveDate) -> std::io::Result<usize> {
+        let journal = match tokio::fs::read_to_string(&self.config.journal_path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let entries = journal_entries_on(&journal, date);
+        let count = entries.len();
+        for payload in entries {
+            let this = self.clone();
+            tokio::spawn(async move { this.deliver(payload).await });
+        }
+        Ok(count)
+    }
+
+    pub fn status(&self) -> TradeWebhookStatus {
+        TradeWebhookStatus {
+            url: self.config.url.clone(),
+            signed: self.config.secret.is_some(),
+            delivered: self.delivered.load(Ordering::Relaxed),
+            failed: self
+                .failed
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|p| p.trade_id.clone())
+                .collect(),
+        }
+    }
+
+    async fn journal(&self, payload: &TradeWebhookPayload) -> anyhow::Result<()> {
+        let line = serde_json::to_string(payload)? + "\n";
+        let _guard = self.journal_lock.lock().await;
+        if let Some(parent) = self.config.journal_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.journal_path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    /// POST with exponential backoff; parks the webhook in `failed` when
+    /// every attempt fails
+    async fn deliver(&self, payload: TradeWebhookPayload) {
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize trade webhook {}: {}", payload.trade_id, e);
+                return;
+            }
+        };
+
+        let mut backoff = Duration::from_secs(1);
+        let attempts = self.config.max_attempts.max(1);
+        for attempt in 1..=attempts {
+            match self.post(&body).await {
+                Ok(()) => {
+                    self.delivered.fetch_add(1, Ordering::Relaxed);
+                    debug!("Delivered trade webhook {}", payload.trade_id);
+                    return;
+                }
+                Err(e) if attempt < attempts => {
+                    debug!(
+                        "Trade webhook {} attempt {}/{} failed: {}",
+                        payload.trade_id, attempt, attempts, e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                Err(e) => {
+                    warn!(
+                        "Trade webhook {} failed after {} attempts: {}",
+                        payload.trade_id, attempts, e
+                    );
+                }
+            }
+        }
+        let mut failed = self.failed.lock().unwrap();
+        if failed.len() >= FAILED_KEPT {
+            let dropped = failed.remove(0);
+            warn!(
+                "Dropping failed trade webhook {} from the retry queue (limit {}); replay it from the journal",
+                dropped.trade_id, FAILED_KEPT
+            );
+        }
+        failed.push(payload);
+    }
+
+    /// Approximate memory held by webhooks awaiting a manual retry
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::of::<TradeWebhookPayload>(self.failed.lock().unwrap().len(), 0)
+    }
+
+    async fn post(&self, body: &str) -> anyhow::Result<()> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(secret) = &self.config.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, Utc::now().timestamp(), body));
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("endpoint returned {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::OpportunitySource;
+    use solana_arb_core::{DexType, TokenPair, Uuid};
+
+    #[test]
+    fn test_payload_costs_and_signature() {
+        let opp = ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::new(10, 1),
+            net_profit_pct: Decimal::new(6, 1),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let outcome = TradeResult {
+            opportunity_id: opp.id,
+            signature: Some("sig".to_string()),
+            success: true,
+            actual_profit: Decimal::new(3, 0),
+            executed_at: "2026-03-01T12:00:00Z".parse().unwrap(),
+            error: None,
+            simulated_profit: None,
+            failure_kind: None,
+        };
+
+        // $500 at 1.0% gross / 0.6% net: $2 of DEX fees, $0.5 network fee
+        let payload =
+            TradeWebhookPayload::new(&opp, "S1", Decimal::from(500), &outcome, Decimal::new(5, 1));
+        assert_eq!(payload.costs.dex_fees_usd, "2");
+        assert_eq!(payload.costs.total_usd, "2.5");
+        assert_eq!(payload.net_profit_usd, "2.5");
+        assert_eq!(payload.gross_profit_usd, "5");
+        assert_eq!(payload.executed_at, "2026-03-01T12:00:00.000Z");
+
+        let journal = format!("{}\nnot json\n", serde_json::to_string(&payload).unwrap());
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        assert_eq!(journal_entries_on(&journal, date), vec![payload]);
+        assert!(journal_entries_on(&journal, date.succ_opt().unwrap()).is_empty());
+
+        let header = sign("secret", 1_700_000_000, "{}");
+        assert!(header.starts_with("t=1700000000,v1="));
+        assert_eq!(header.len(), "t=1700000000,v1=".len() + 64);
+        assert_ne!(header, sign("other", 1_700_000_000, "{}"));
+    }
+}
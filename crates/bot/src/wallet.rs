@@ -70,7 +70,24 @@ impl Wallet {
         self.keypair.as_ref()
     }
 
-    fn parse_keypair(value: &str) -> Result<Keypair> {
+    /// Drops the keypair, leaving a wallet that cannot sign.
+    pub fn revoke(&mut self) {
+        self.keypair = None;
+    }
+
+    /// Restores a keypair dropped by [`Self::revoke`].
+    pub fn restore(&mut self, keypair: Keypair) {
+        self.pubkey = keypair.pubkey().to_string();
+        self.keypair = Some(keypair);
+    }
+
+    /// The `PRIVATE_KEY` keypair, if set and valid
+    pub fn env_keypair() -> Option<Keypair> {
+        let pk = env::var("PRIVATE_KEY").ok().filter(|pk| !pk.is_empty())?;
+        Self::parse_keypair(&pk).ok()
+    }
+
+    pub(crate) fn parse_keypair(value: &str) -> Result<Keypair> {
         if value.trim_start().starts_with('[') {
             let bytes: Vec<u8> = serde_json::from_str(value)?;
             return Keypair::from_bytes(&bytes)
@@ -0,0 +1,97 @@
+//! Standing wSOL Balance
+//!
+//! With `wrapAndUnwrapSol` on, every Jupiter swap with a SOL leg wraps SOL
+//! into a temporary token account and closes it again: extra instructions,
+//! compute and rent churn on every trade. In wSOL mode the wallet keeps a
+//! standing balance in its wrapped SOL token account instead, swaps read and
+//! write that account directly, and SOL is only unwrapped on shutdown or
+//! when the fee reserve needs native SOL back.
+
+use anyhow::{anyhow, Result};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+use spl_associated_token_account::get_associated_token_address;
+
+/// The wallet's wrapped SOL token account
+pub fn wsol_account(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, &spl_token::native_mint::id())
+}
+
+/// Move `lamports` of native SOL into the wSOL account, creating it if needed
+pub fn wrap_instructions(owner: &Pubkey, lamports: u64) -> Result<Vec<Instruction>> {
+    let account = wsol_account(owner);
+    Ok(vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            owner,
+            owner,
+            &spl_token::native_mint::id(),
+            &spl_token::id(),
+        ),
+        system_instruction::transfer(owner, &account, lamports),
+        spl_token::instruction::sync_native(&spl_token::id(), &account)
+            .map_err(|e| anyhow!("Failed to build sync_native: {}", e))?,
+    ])
+}
+
+/// Close the wSOL account, returning its whole balance (and rent) as native SOL
+pub fn unwrap_instructions(owner: &Pubkey) -> Result<Vec<Instruction>> {
+    close_instructions(owner, &wsol_account(owner))
+}
+
+/// Close any wSOL token account of `owner`, e.g. a temporary one a swap
+/// left open, returning its balance and rent as native SOL
+pub fn close_instructions(owner: &Pubkey, account: &Pubkey) -> Result<Vec<Instruction>> {
+    Ok(vec![spl_token::instruction::close_account(&spl_token::id(), account, owner, owner, &[])
+        .map_err(|e| anyhow!("Failed to build close_account: {}", e))?])
+}
+
+/// Unwrap `release` lamports of a `wsol_balance` standing balance: close the
+/// account, then wrap the remainder again. SPL Token cannot unwrap part of
+/// a balance, so the round trip happens in one transaction.
+pub fn release_instructions(
+    owner: &Pubkey,
+    wsol_balance: u64,
+    release: u64,
+) -> Result<Vec<Instruction>> {
+    let mut instructions = unwrap_instructions(owner)?;
+    let keep = wsol_balance.saturating_sub(release);
+    if keep > 0 {
+        instructions.extend(wrap_instructions(owner, keep)?);
+    }
+    Ok(instructions)
+}
+
+/// Lamports to wrap to bring the standing balance up to `target`, keeping
+/// at least `native_floor` lamports unwrapped for fees. Zero when nothing
+/// can or needs to be wrapped.
+pub fn wrap_amount(wsol_balance: u64, native_balance: u64, target: u64, native_floor: u64) -> u64 {
+    let missing = target.saturating_sub(wsol_balance);
+    missing.min(native_balance.saturating_sub(native_floor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_amount_and_release() {
+        const SOL: u64 = 1_000_000_000;
+        // Wrap up to the target
+        assert_eq!(wrap_amount(SOL / 2, 2 * SOL, SOL, SOL / 5), SOL / 2);
+        // Never below the native floor
+        assert_eq!(wrap_amount(0, SOL / 2, SOL, SOL / 5), 3 * SOL / 10);
+        assert_eq!(wrap_amount(2 * SOL, SOL, SOL, SOL / 5), 0);
+
+        let owner = Pubkey::new_unique();
+        // Releasing everything only closes the account
+        assert_eq!(release_instructions(&owner, SOL, SOL).unwrap().len(), 1);
+        let partial = release_instructions(&owner, SOL, SOL / 4).unwrap();
+        assert_eq!(partial.len(), 4);
+        assert_eq!(partial[0].program_id, spl_token::id());
+        assert_eq!(
+            partial[2],
+            system_instruction::transfer(&owner, &wsol_account(&owner), 3 * SOL / 4)
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use solana_arb_bot::simulator::{self, Fixture};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn test_detection_matches_golden_files() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/simulator");
+    let fixtures = simulator::fixtures(&dir).unwrap();
+    assert!(!fixtures.is_empty());
+
+    for path in fixtures {
+        let record = simulator::simulate(&Fixture::load(&path).unwrap()).await;
+        let expected = simulator::load_golden(&simulator::golden_path(&path)).unwrap();
+        let diff = simulator::diff(&expected, &record);
+        assert!(
+            diff.is_empty(),
+            "{} differs from its golden file (rerun `cargo run --bin simulate -- --update` if intended):\n{}",
+            path.display(),
+            diff.join("\n")
+        );
+    }
+}
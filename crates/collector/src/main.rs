@@ -73,6 +73,7 @@ async fn main() -> Result<()> {
     let arb_config = ArbitrageConfig {
         min_profit_threshold: rust_decimal::Decimal::try_from(config.min_profit_threshold)
             .unwrap_or_default(),
+        max_quote_skew_ms: config.max_quote_skew_ms,
         ..Default::default()
     };
     let detector = Arc::new(RwLock::new(ArbitrageDetector::new(arb_config)));
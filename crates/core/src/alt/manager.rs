@@ -2,6 +2,7 @@ use anyhow::{anyhow, Result};
 use solana_rpc_client::rpc_client::RpcClient;
 use solana_sdk::address_lookup_table::{
     instruction::{create_lookup_table, extend_lookup_table},
+    state::AddressLookupTable,
     AddressLookupTableAccount,
 };
 use solana_sdk::commitment_config::CommitmentConfig;
@@ -13,12 +14,26 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::info;
 
+use crate::rpc_batch::RpcBatcher;
+
+/// Whether new transactions can still use a lookup table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AltLiveness {
+    Active,
+    /// Deactivation started; the table can no longer be used and will be
+    /// closable once the cool-down ends
+    Deactivated,
+    /// The account no longer exists
+    Closed,
+}
+
 /// Manages Address Lookup Tables (ALTs) for efficient transaction packing
 #[allow(dead_code)]
 pub struct AltManager {
     rpc_client: Arc<RpcClient>,
     lookup_tables: RwLock<HashMap<String, Pubkey>>,
     cache: RwLock<HashMap<Pubkey, AddressLookupTableAccount>>,
+    batcher: Option<Arc<RpcBatcher>>,
 }
 
 impl AltManager {
@@ -32,9 +47,17 @@ impl AltManager {
             rpc_client,
             lookup_tables: RwLock::new(HashMap::new()),
             cache: RwLock::new(HashMap::new()),
+            batcher: None,
         }
     }
 
+    /// Fetch tables through `batcher`, so the tables of one transaction
+    /// are read with a single `getMultipleAccounts`
+    pub fn with_batcher(mut self, batcher: Arc<RpcBatcher>) -> Self {
+        self.batcher = Some(batcher);
+        self
+    }
+
     /// Create a new Address Lookup Table
     pub async fn create_alt(
         &self,
@@ -82,11 +105,22 @@ impl AltManager {
             }
         }
 
-        // Fetch from RPC
-        // This requires blocking call or spawn_blocking
-        // Placeholder for now
-
-        Err(anyhow!("ALT fetching not fully implemented in this phase"))
+        let batcher = self
+            .batcher
+            .as_ref()
+            .ok_or_else(|| anyhow!("ALT fetching needs an RPC batcher"))?;
+        let account = batcher
+            .get_account(address)
+            .await?
+            .ok_or_else(|| anyhow!("lookup table {} not found", address))?;
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| anyhow!("invalid lookup table {}: {}", address, e))?;
+        let table = AddressLookupTableAccount {
+            key: *address,
+            addresses: table.addresses.to_vec(),
+        };
+        self.cache.write().await.insert(*address, table.clone());
+        Ok(table)
     }
 
     pub async fn extend_alt(
@@ -113,13 +147,34 @@ impl AltManager {
         Ok(())
     }
 
-    pub async fn get_tables(&self, addresses: &[Pubkey]) -> Result<Vec<AddressLookupTableAccount>> {
-        let mut tables = Vec::new();
-        for addr in addresses {
-            // Sequential fetch for now
-            tables.push(self.get_alt(addr).await?);
+    /// Look up whether `address` is still usable, dropping a table that is
+    /// not from the cache
+    pub async fn liveness(&self, address: &Pubkey) -> Result<AltLiveness> {
+        let batcher = self
+            .batcher
+            .as_ref()
+            .ok_or_else(|| anyhow!("ALT fetching needs an RPC batcher"))?;
+        let liveness = match batcher.get_account(address).await? {
+            None => AltLiveness::Closed,
+            Some(account) => {
+                let table = AddressLookupTable::deserialize(&account.data)
+                    .map_err(|e| anyhow!("invalid lookup table {}: {}", address, e))?;
+                if table.meta.deactivation_slot == u64::MAX {
+                    AltLiveness::Active
+                } else {
+                    AltLiveness::Deactivated
+                }
+            }
+        };
+        if liveness != AltLiveness::Active {
+            self.cache.write().await.remove(address);
         }
-        Ok(tables)
+        Ok(liveness)
+    }
+
+    pub async fn get_tables(&self, addresses: &[Pubkey]) -> Result<Vec<AddressLookupTableAccount>> {
+        // Fetched concurrently so the batcher coalesces them
+        futures_util::future::try_join_all(addresses.iter().map(|addr| self.get_alt(addr))).await
     }
 }
 
@@ -1,3 +1,3 @@
 pub mod manager;
 
-pub use manager::AltManager;
+pub use manager::{AltLiveness, AltManager};
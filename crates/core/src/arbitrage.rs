@@ -1,19 +1,37 @@
 //! Arbitrage Detection Engine
 //!
 //! This module identifies arbitrage opportunities by comparing prices
-//! across different DEXs for the same trading pair.
+//! across different DEXs for the same trading pair. Two quotes taken more
+//! than `max_quote_skew_ms` apart are not paired: one of them no longer
+//! describes the market the other does, and the edge is likely phantom.
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
-
-use crate::{ArbitrageConfig, ArbitrageOpportunity, DexType, PriceData, TokenPair, Uuid};
+use std::sync::Mutex;
+
+use crate::memory::MemoryUsage;
+use crate::{ArbitrageConfig, ArbitrageOpportunity, DexType, OpportunitySource, PriceData, TokenPair, Uuid};
+
+/// Quote skews kept between [`ArbitrageDetector::take_quote_skew`] calls
+const MAX_SKEW_SAMPLES: usize = 10_000;
+
+/// Skew between the quotes of profitable venue pairings, since the last
+/// [`ArbitrageDetector::take_quote_skew`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuoteSkewStats {
+    /// Milliseconds between the two quotes, one sample per pairing
+    pub samples_ms: Vec<f64>,
+    /// Pairings dropped for exceeding `max_quote_skew_ms`
+    pub rejected: u64,
+}
 
 /// Arbitrage detector that compares prices across DEXs
 pub struct ArbitrageDetector {
     config: ArbitrageConfig,
     /// Cache of latest prices by (pair, dex)
     price_cache: HashMap<(TokenPair, DexType), PriceData>,
+    quote_skew: Mutex<QuoteSkewStats>,
 }
 
 impl ArbitrageDetector {
@@ -21,11 +39,14 @@ impl ArbitrageDetector {
         Self {
             config,
             price_cache: HashMap::new(),
+            quote_skew: Mutex::new(QuoteSkewStats::default()),
         }
     }
 
-    /// Update the price cache with new price data
+    /// Update the price cache with new price data, kept under the pair's
+    /// canonical orientation
     pub fn update_price(&mut self, price: PriceData) {
+        let price = price.normalized();
         let key = (price.pair.clone(), price.dex);
         self.price_cache.insert(key, price);
     }
@@ -37,15 +58,27 @@ impl ArbitrageDetector {
         }
     }
 
-    /// Find all arbitrage opportunities for a given pair
+    /// Drop the cached price of `pair` on `dex`
+    pub fn remove_price(&mut self, pair: &TokenPair, dex: DexType) {
+        self.price_cache.remove(&(pair.canonical(), dex));
+    }
+
+    /// Find all arbitrage opportunities for a given pair, in its canonical
+    /// orientation
     pub fn find_opportunities(&self, pair: &TokenPair) -> Vec<ArbitrageOpportunity> {
         let mut opportunities = Vec::new();
+        let pair = &pair.canonical();
 
         // Get all prices for this pair from different DEXs
-        let prices: Vec<_> = [DexType::Raydium, DexType::Orca, DexType::Jupiter]
-            .iter()
-            .filter_map(|dex| self.price_cache.get(&(pair.clone(), *dex)))
-            .collect();
+        let prices: Vec<_> = [
+            DexType::Raydium,
+            DexType::Orca,
+            DexType::Jupiter,
+            DexType::Sanctum,
+        ]
+        .iter()
+        .filter_map(|dex| self.price_cache.get(&(pair.clone(), *dex)))
+        .collect();
 
         // Compare all pairs of DEXs
         for i in 0..prices.len() {
@@ -64,16 +97,45 @@ impl ArbitrageDetector {
         }
 
         // Sort by profit percentage (descending)
-        opportunities.sort_by(|a, b| b.net_profit_pct.cmp(&a.net_profit_pct));
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.net_profit_pct));
         opportunities
     }
 
+    /// Price buying `pair` on `buy_dex` and selling it on `sell_dex` at the
+    /// cached quotes, whatever the edge. `None` without a quote from both.
+    pub fn price_route(
+        &self,
+        pair: &TokenPair,
+        buy_dex: DexType,
+        sell_dex: DexType,
+    ) -> Option<ArbitrageOpportunity> {
+        let pair = pair.canonical();
+        let buy_from = self.price_cache.get(&(pair.clone(), buy_dex))?;
+        let sell_to = self.price_cache.get(&(pair, sell_dex))?;
+        Self::route_opportunity(buy_from, sell_to)
+    }
+
     /// Check if there's an arbitrage opportunity between two prices
     fn check_opportunity(
         &self,
         buy_from: &PriceData,
         sell_to: &PriceData,
     ) -> Option<ArbitrageOpportunity> {
+        let opp = Self::route_opportunity(buy_from, sell_to)?;
+
+        // Only return if profitable after fees and above threshold
+        if opp.net_profit_pct > self.config.min_profit_threshold {
+            if !self.quotes_in_sync(buy_from, sell_to) {
+                return None;
+            }
+            Some(opp)
+        } else {
+            None
+        }
+    }
+
+    /// Opportunity of buying at `buy_from`'s ask and selling at `sell_to`'s bid
+    fn route_opportunity(buy_from: &PriceData, sell_to: &PriceData) -> Option<ArbitrageOpportunity> {
         // Buy at ask price from buy_from, sell at bid price to sell_to
         let buy_price = buy_from.ask;
         let sell_price = sell_to.bid;
@@ -93,25 +155,45 @@ impl ArbitrageDetector {
         // Net profit after fees
         let net_profit_pct = gross_profit_pct - total_fee_pct;
 
-        // Only return if profitable after fees and above threshold
-        if net_profit_pct > self.config.min_profit_threshold {
-            Some(ArbitrageOpportunity {
-                id: Uuid::new_v4(),
-                pair: buy_from.pair.clone(),
-                buy_dex: buy_from.dex,
-                sell_dex: sell_to.dex,
-                buy_price,
-                sell_price,
-                gross_profit_pct,
-                net_profit_pct,
-                estimated_profit_usd: None,
-                recommended_size: None,
-                detected_at: Utc::now(),
-                expired_at: None,
-            })
-        } else {
-            None
+        Some(ArbitrageOpportunity {
+            id: Uuid::new_v4(),
+            pair: buy_from.pair.clone(),
+            buy_dex: buy_from.dex,
+            sell_dex: sell_to.dex,
+            buy_price,
+            sell_price,
+            gross_profit_pct,
+            net_profit_pct,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: Some(buy_from.timestamp),
+            sell_quote_at: Some(sell_to.timestamp),
+            source: OpportunitySource::Detector,
+            strategy: None,
+        })
+    }
+
+    /// Record the skew between two quotes of a profitable pairing and
+    /// whether it is within `max_quote_skew_ms`
+    fn quotes_in_sync(&self, a: &PriceData, b: &PriceData) -> bool {
+        let skew_ms = (a.timestamp - b.timestamp).num_milliseconds().unsigned_abs();
+        let in_sync =
+            self.config.max_quote_skew_ms == 0 || skew_ms <= self.config.max_quote_skew_ms;
+        let mut stats = self.quote_skew.lock().unwrap_or_else(|e| e.into_inner());
+        if stats.samples_ms.len() < MAX_SKEW_SAMPLES {
+            stats.samples_ms.push(skew_ms as f64);
+        }
+        if !in_sync {
+            stats.rejected += 1;
         }
+        in_sync
+    }
+
+    /// Quote skew samples and rejections since the previous call
+    pub fn take_quote_skew(&self) -> QuoteSkewStats {
+        std::mem::take(&mut *self.quote_skew.lock().unwrap_or_else(|e| e.into_inner()))
     }
 
     /// Find all profitable opportunities across all cached pairs
@@ -132,7 +214,7 @@ impl ArbitrageDetector {
         }
 
         // Sort by profit
-        all_opportunities.sort_by(|a, b| b.net_profit_pct.cmp(&a.net_profit_pct));
+        all_opportunities.sort_by_key(|o| std::cmp::Reverse(o.net_profit_pct));
         all_opportunities
     }
 
@@ -141,9 +223,30 @@ impl ArbitrageDetector {
         &self.price_cache
     }
 
+    /// Approximate memory held by the price cache
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let strings: usize = self
+            .price_cache
+            .iter()
+            .map(|((pair, _), price)| {
+                pair.base.capacity()
+                    + pair.quote.capacity()
+                    + price.pair.base.capacity()
+                    + price.pair.quote.capacity()
+            })
+            .sum();
+        MemoryUsage::of::<((TokenPair, DexType), PriceData)>(self.price_cache.len(), 0)
+            .with_bytes(strings)
+    }
+
     /// Clear old prices from cache
     pub fn clear_stale_prices(&mut self, max_age_seconds: i64) {
-        let now = Utc::now();
+        self.clear_prices_older_than(Utc::now(), max_age_seconds);
+    }
+
+    /// Clear prices that were at least `max_age_seconds` old at `now`, e.g.
+    /// the time of an archived tick being replayed
+    pub fn clear_prices_older_than(&mut self, now: DateTime<Utc>, max_age_seconds: i64) {
         self.price_cache
             .retain(|_, price| (now - price.timestamp).num_seconds() < max_age_seconds);
     }
@@ -249,4 +352,53 @@ mod tests {
         assert_eq!(best.sell_dex, DexType::Orca);
         assert!(best.net_profit_pct > Decimal::ZERO);
     }
+
+    #[test]
+    fn test_skewed_quotes_are_not_paired() {
+        let mut detector = ArbitrageDetector::new(ArbitrageConfig {
+            min_profit_threshold: Decimal::new(1, 2),
+            max_quote_skew_ms: 500,
+            ..Default::default()
+        });
+        let pair = TokenPair::new("SOL", "USDC");
+        let now = Utc::now();
+        let mut stale = create_test_price(DexType::Raydium, pair.clone(), 99.90, 100.00);
+        stale.timestamp = now - chrono::Duration::seconds(2);
+        let mut fresh = create_test_price(DexType::Orca, pair.clone(), 101.50, 101.60);
+        fresh.timestamp = now;
+        detector.update_price(stale.clone());
+        detector.update_price(fresh);
+
+        // A 2s-old Raydium quote against a fresh Orca one
+        assert!(detector.find_opportunities(&pair).is_empty());
+        let stats = detector.take_quote_skew();
+        assert_eq!((stats.samples_ms, stats.rejected), (vec![2000.0], 1));
+
+        stale.timestamp = now - chrono::Duration::milliseconds(300);
+        detector.update_price(stale);
+        let opportunities = detector.find_opportunities(&pair);
+        assert_eq!(opportunities.len(), 1);
+        assert_eq!(
+            opportunities[0].quote_skew(),
+            Some(chrono::Duration::milliseconds(300))
+        );
+        assert_eq!(detector.take_quote_skew().rejected, 0);
+    }
+
+    #[test]
+    fn test_price_route_ignores_threshold() {
+        let mut detector = ArbitrageDetector::default();
+        let pair = TokenPair::new("SOL", "USDC");
+        detector.update_price(create_test_price(DexType::Raydium, pair.clone(), 99.90, 100.00));
+        detector.update_price(create_test_price(DexType::Orca, pair.clone(), 100.05, 100.15));
+
+        // Only Raydium -> Orca clears the threshold, but the losing direction is priced too
+        let found = detector.find_opportunities(&pair);
+        assert!(found.iter().all(|opp| opp.buy_dex == DexType::Raydium));
+        let opp = detector.price_route(&pair, DexType::Orca, DexType::Raydium).unwrap();
+        assert_eq!(opp.buy_price, Decimal::try_from(100.15).unwrap());
+        assert_eq!(opp.sell_price, Decimal::try_from(99.90).unwrap());
+        assert!(opp.net_profit_pct < Decimal::ZERO);
+        assert!(detector.price_route(&pair, DexType::Orca, DexType::Jupiter).is_none());
+    }
 }
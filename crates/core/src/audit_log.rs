@@ -1,11 +1,15 @@
 //! Append-only audit logging for trade execution and security events
 //!
 //! Provides a tamper-evident trade log for compliance, debugging,
-//! and post-incident analysis.
+//! and post-incident analysis. Events are hash-chained (and optionally
+//! signed), see [`crate::hash_chain`].
 
+use crate::hash_chain::{self, HashChain};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
@@ -30,6 +34,7 @@ pub struct AuditEvent {
 pub struct AuditLogger {
     file: Mutex<tokio::fs::File>,
     path: PathBuf,
+    chain: HashChain,
 }
 
 impl AuditLogger {
@@ -50,17 +55,27 @@ impl AuditLogger {
 
         Ok(Self {
             file: Mutex::new(file),
+            chain: HashChain::resume(hash_chain::last_link(&path)),
             path,
         })
     }
 
+    /// Sign every event with `signer`
+    pub fn with_signer(mut self, signer: Arc<Keypair>) -> Self {
+        self.chain = self.chain.with_signer(signer);
+        self
+    }
+
     /// Log a raw audit event
     pub async fn log(&self, event: AuditEvent) -> std::io::Result<()> {
-        let mut json = serde_json::to_string(&event)
+        // Sealed under the file lock so lines land in chain order
+        let mut file = self.file.lock().await;
+        let mut json = self
+            .chain
+            .seal(&event)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
         json.push('\n');
 
-        let mut file = self.file.lock().await;
         file.write_all(json.as_bytes()).await?;
         file.flush().await?;
 
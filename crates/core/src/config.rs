@@ -15,6 +15,21 @@ pub struct Config {
     pub min_profit_threshold: f64,
     /// Maximum age of price data before it is considered stale (seconds)
     pub max_price_age_seconds: i64,
+    /// Largest gap (ms) between two venues' quotes paired into an
+    /// opportunity; 0 disables the check
+    pub max_quote_skew_ms: u64,
+    /// Maximum bid/ask spread (percent of mid) of a usable price; 0 disables
+    pub max_spread_pct: f64,
+    /// Per-venue spread limits overriding `max_spread_pct` (`dex:pct,...`)
+    pub max_spread_pct_by_venue: String,
+    /// Project fast venues' quotes along their drift before judging edge
+    pub price_projection_enabled: bool,
+    /// Venues whose quotes are projected (comma-separated DEX names)
+    pub price_projection_venues: String,
+    /// Weight of the newest price change in the drift average (0 to 1)
+    pub price_projection_alpha: f64,
+    /// Largest projected price move, in bps
+    pub price_projection_max_bps: f64,
     /// API server port
     pub api_port: u16,
     /// Log level
@@ -25,6 +40,12 @@ pub struct Config {
     pub compute_unit_limit: u32,
     /// RPC commitment level (processed, confirmed, finalized)
     pub rpc_commitment: String,
+    /// Commitment of balance and account reads (empty: `rpc_commitment`)
+    pub rpc_commitment_reads: String,
+    /// Commitment of blockhashes, simulations and submission confirmation (empty: `rpc_commitment`)
+    pub rpc_commitment_submission: String,
+    /// Commitment live trades are tracked to before their P&L is settled
+    pub rpc_commitment_accounting: String,
     /// Slippage tolerance in basis points (50 = 0.5%)
     pub slippage_bps: u64,
     /// Maximum retry attempts for failed transactions
@@ -35,8 +56,34 @@ pub struct Config {
     pub jito_block_engine_url: String,
     /// Jito tip amount in lamports
     pub jito_tip_lamports: u64,
+    /// Jupiter v6 API base URL (public or self-hosted)
+    pub jupiter_api_url: String,
+    /// Probe the public Jupiter API alongside a self-hosted one to compare latency
+    pub jupiter_compare_public: bool,
+    /// Seconds between Jupiter endpoint health probes
+    pub jupiter_probe_interval_secs: u64,
+    /// Tune RPC / Jupiter rate limiter budgets from 429s and latency
+    pub rate_limit_autotune: bool,
+    /// Seconds between rate limit tuning steps
+    pub rate_limit_tune_interval_secs: u64,
+    /// Average response latency above which budgets are lowered
+    pub rate_limit_target_latency_ms: u64,
+    /// RPC requests per second: starting budget and tuning bounds
+    pub rpc_rate_limit: usize,
+    pub rpc_rate_limit_floor: usize,
+    pub rpc_rate_limit_ceiling: usize,
+    /// Public Jupiter API requests per second: starting budget and tuning bounds
+    pub jupiter_rate_limit: usize,
+    pub jupiter_rate_limit_floor: usize,
+    pub jupiter_rate_limit_ceiling: usize,
+    /// Share rate limit budgets with other processes through `redis_url`
+    pub shared_rate_limits: bool,
+    /// Prefix of the Redis keys shared budgets are counted under
+    pub rate_limit_redis_prefix: String,
     /// Dry run mode
     pub dry_run: bool,
+    /// Watch-only mode: detection, analytics and API without a wallet or executor
+    pub watch_only: bool,
     /// Maximum daily loss allowed before pausing
     pub max_daily_loss: f64,
     /// Maximum consecutive losses before pausing
@@ -49,6 +96,20 @@ pub struct Config {
     pub telegram_webhook_url: Option<String>,
     /// Discord webhook URL for alerts
     pub discord_webhook_url: Option<String>,
+    /// Mask dollar amounts and token quantities in Telegram alerts
+    pub telegram_alert_redact: bool,
+    /// Base64 X25519 public key to encrypt Telegram alerts to
+    pub telegram_alert_public_key: Option<String>,
+    /// Mask dollar amounts and token quantities in Discord alerts
+    pub discord_alert_redact: bool,
+    /// Base64 X25519 public key to encrypt Discord alerts to
+    pub discord_alert_public_key: Option<String>,
+    /// Accounting endpoint that receives a signed webhook per completed live trade
+    pub trade_webhook_url: Option<String>,
+    /// HMAC-SHA256 secret for signing trade webhooks
+    pub trade_webhook_secret: Option<String>,
+    /// Delivery attempts per trade webhook before giving up
+    pub trade_webhook_max_attempts: u32,
     /// Polling interval in milliseconds
     pub poll_interval_ms: u64,
     /// Maximum concurrent trades
@@ -57,6 +118,296 @@ pub struct Config {
     pub enable_metrics: bool,
     /// Metrics server port
     pub metrics_port: u16,
+    /// Snapshot metrics to a local timeseries file for offline analysis
+    pub metrics_recorder_enabled: bool,
+    /// Seconds between metrics snapshots
+    pub metrics_recorder_interval_secs: u64,
+    /// Days of snapshots to keep
+    pub metrics_recorder_retention_days: u32,
+    /// Path of the metrics timeseries file (JSON lines)
+    pub metrics_recorder_path: String,
+    /// Maximum exposure routed through a single DEX venue (accounting currency)
+    pub max_venue_exposure: f64,
+    /// Trade size (accounting currency) used while canary mode is active
+    pub canary_size_usd: f64,
+    /// Successful canary trades required before full sizes are restored
+    pub canary_required_successes: u32,
+    /// gRPC endpoint of a remote strategy service (empty disables it)
+    pub remote_strategy_url: String,
+    /// Deadline for each remote strategy call (ms)
+    pub remote_strategy_timeout_ms: u64,
+    /// Largest housekeeping trade (accounting currency) used to unwind exposure above a lowered limit
+    pub unwind_step_usd: f64,
+    /// Seconds between housekeeping trades while unwinding
+    pub unwind_interval_secs: u64,
+    /// Sell base tokens a failed standard trade left in the wallet back to the quote token
+    pub route_repair_enabled: bool,
+    /// Seconds a failed trade is given to land before the wallet is checked
+    pub route_repair_settle_secs: u64,
+    /// Smallest stranded balance (USD) worth a repair trade
+    pub route_repair_min_usd: f64,
+    /// Quarantine the wallet on signs of a leaked key
+    pub quarantine_enabled: bool,
+    /// Seconds between scans of the wallet's signatures and balance
+    pub quarantine_check_secs: u64,
+    /// SOL that may leave the wallet between two scans while the bot signs
+    /// nothing
+    pub quarantine_max_outflow_sol: f64,
+    /// File an active quarantine is persisted to
+    pub quarantine_state_path: String,
+    /// Seconds before capital committed to an unconfirmed trade is released anyway
+    pub capital_commitment_ttl_secs: u64,
+    /// Share (%) of each order book level assumed queued ahead of dry-run fills
+    pub paper_queue_ahead_pct: f64,
+    /// Ticks of spread index history forming the regime baseline
+    pub regime_window_ticks: usize,
+    /// Spread index / baseline at or above which the market counts as dislocated
+    pub regime_dislocation_ratio: f64,
+    /// Multiplier on the minimum profit while the market is dislocated
+    pub regime_edge_multiplier: f64,
+    /// Trades are blocked while the wallet holds less SOL than this
+    pub fee_reserve_min_sol: f64,
+    /// SOL balance a fee reserve top-up aims for
+    pub fee_reserve_target_sol: f64,
+    /// Swap USDC to SOL automatically when the fee reserve runs low
+    pub fee_reserve_auto_topup: bool,
+    /// Largest single fee reserve top-up (USD)
+    pub fee_reserve_max_topup_usd: f64,
+    /// Seconds between fee reserve balance checks
+    pub fee_reserve_check_secs: u64,
+    /// Rotate the trade history file once it reaches this many MB (0: never by size)
+    pub history_rotate_max_mb: u64,
+    /// Rotate the trade history file once its first record is this many hours old (0: never by age)
+    pub history_rotate_max_age_hours: u64,
+    /// Sign every audit event and trade record with the wallet key
+    pub sign_records: bool,
+    /// Milliseconds balance and account lookups are queued to be sent as
+    /// one batched RPC request
+    pub rpc_batch_window_ms: u64,
+    /// Drift Gateway base URL for perp hedging (empty: live hedging off)
+    pub drift_gateway_url: String,
+    /// Spot base tokens to Drift perp market indexes (`SOL:0,...`)
+    pub drift_perp_markets: String,
+    /// Seconds between hedge checks
+    pub hedge_interval_secs: u64,
+    /// Bot API URL of the primary; when set this instance runs as a warm
+    /// standby replicating its risk state
+    pub standby_primary_url: Option<String>,
+    /// Milliseconds between replication snapshots
+    pub replication_sync_ms: u64,
+    /// Seconds without a snapshot from the primary before a standby
+    /// promotes itself (0: manual promotion only)
+    pub standby_failover_secs: u64,
+    /// Scan Raydium and Orca pool registries for new pairs
+    pub pool_discovery_enabled: bool,
+    /// Seconds between pool discovery scans
+    pub pool_discovery_interval_secs: u64,
+    /// Tokens discovered pairs may use (`SYMBOL` or `SYMBOL:MINT`, comma separated)
+    pub discovery_tokens: String,
+    /// Liquidity (USD) a pool needs to count towards a proposed pair
+    pub discovery_min_liquidity_usd: f64,
+    /// Escalate critical alert incidents unacknowledged for this many minutes (0: never)
+    pub incident_escalate_minutes: u64,
+    /// Webhook that also receives escalated incidents
+    pub incident_escalation_webhook_url: Option<String>,
+    /// Mask dollar amounts and token quantities in escalation webhook posts
+    pub escalation_alert_redact: bool,
+    /// Base64 X25519 public key to encrypt escalation webhook posts to
+    pub escalation_alert_public_key: Option<String>,
+    /// Milliseconds quote, swap instructions and build may take before a
+    /// trade is abandoned (0 disables the deadline)
+    pub execution_deadline_ms: u64,
+    /// Keep a standing wSOL balance and trade SOL legs through it
+    pub wsol_mode: bool,
+    /// Standing wSOL balance kept in wSOL mode
+    pub wsol_target_sol: f64,
+    /// Largest share (0-1) of a lending reserve's available liquidity one flash loan may borrow
+    pub flash_loan_max_reserve_fraction: f64,
+    /// Seconds between flash loan reserve liquidity refreshes
+    pub flash_loan_liquidity_refresh_secs: u64,
+    /// Events the event bus buffers for its slowest subscriber
+    pub event_bus_capacity: usize,
+    /// Overflow policy per event subscriber, as `name=policy` pairs
+    pub event_bus_policies: String,
+    /// Directory for the spill files of `spill` event subscribers
+    pub event_bus_spill_dir: String,
+    /// Quote Kamino Lend alongside Solend and borrow from the cheaper one
+    pub kamino_flash_loans_enabled: bool,
+    /// Kamino lending market holding the flash loan reserve
+    pub kamino_lending_market: String,
+    /// Kamino USDC reserve lent from
+    pub kamino_usdc_reserve: String,
+    /// Flash loan fee of the Kamino reserve, in bps
+    pub kamino_flash_loan_fee_bps: u64,
+    /// Quote marginfi alongside the other flash loan providers
+    pub marginfi_flash_loans_enabled: bool,
+    /// marginfi group the banks belong to
+    pub marginfi_group: String,
+    /// The wallet's marginfi account loans are taken on
+    pub marginfi_account: String,
+    /// Banks lent from, as `TOKEN=bank` pairs
+    pub marginfi_banks: String,
+    /// Flash loan fee charged by the marginfi banks, in bps
+    pub marginfi_flash_loan_fee_bps: u64,
+    /// Maximum swaps per batch transaction
+    pub batch_max_legs: usize,
+    /// Only opportunities at or below this size (USD) are batched
+    pub batch_max_leg_size_usd: f64,
+    /// Largest size (USD) a multi-hop path is traded at
+    pub path_max_size_usd: f64,
+    /// VaR method: "parametric", "historical" or "monte_carlo"
+    pub var_method: String,
+    /// VaR confidence level (e.g. 0.95)
+    pub var_confidence: f64,
+    /// VaR horizon in price update periods
+    pub var_horizon_periods: usize,
+    /// Simulated paths for Monte Carlo VaR
+    pub var_monte_carlo_paths: usize,
+    /// Directory end-of-day risk reports and their journal are written to
+    /// (empty: no reports)
+    pub risk_report_dir: String,
+    /// Directory the fetched prices of every tick are archived to, for
+    /// replay (`None`: not archived)
+    pub price_snapshot_dir: Option<String>,
+    /// Deduplicate past days of the price archive, write OHLC bars and
+    /// apply retention
+    pub price_compaction_enabled: bool,
+    /// Seconds between compaction runs
+    pub price_compaction_interval_secs: u64,
+    /// Days raw price ticks are kept (0: forever)
+    pub price_raw_retention_days: u32,
+    /// Days 1-second bars are kept (0: forever)
+    pub price_bars_1s_retention_days: u32,
+    /// Days 1-minute bars are kept (0: forever)
+    pub price_bars_1m_retention_days: u32,
+    /// Symbols or mints every traded route must stay within (empty: any)
+    pub compliance_token_allowlist: String,
+    /// Programs a transaction may call besides the base programs (empty: any)
+    pub compliance_program_allowlist: String,
+    /// Cap on the notional of one transaction in USD (0: none)
+    pub compliance_max_notional_usd: f64,
+    /// Per-pair notional caps (`PAIR:usd,...`)
+    pub compliance_pair_notional_usd: String,
+    /// External compliance service consulted before every live trade
+    pub compliance_hook_url: Option<String>,
+    /// Milliseconds the external service has to answer
+    pub compliance_hook_timeout_ms: u64,
+    /// Allow trades when the external service does not answer
+    pub compliance_hook_fail_open: bool,
+    /// Trade liquid staking tokens (mSOL, jitoSOL, bSOL) against SOL, with
+    /// Sanctum quoting their intrinsic rate
+    pub lst_pairs_enabled: bool,
+    /// Sanctum API base URL
+    pub sanctum_api_url: String,
+    /// Fee (bps) either side of the intrinsic rate Sanctum is quoted at
+    pub sanctum_fee_bps: u64,
+    /// Candidates simulated each tick before executing the best passing one
+    /// (0 or 1: execute the top opportunity unsimulated)
+    pub preflight_top_k: usize,
+    /// Candidate simulations in flight at once
+    pub preflight_concurrency: usize,
+    /// Binary search steps sizing a trade against quoted price impact, two
+    /// round-trip quotes each (0: size without quotes)
+    pub size_search_steps: u32,
+    /// Smallest trade size (USD) the search considers
+    pub size_search_min_usd: f64,
+    /// Seconds a route's sampled impact curve is reused
+    pub impact_curve_ttl_secs: u64,
+    /// Port of the bot's health and admin API
+    pub health_port: u16,
+    /// Address the health and admin API binds to
+    pub health_bind_addr: String,
+    /// Address the metrics server binds to
+    pub metrics_bind_addr: String,
+    /// Ports after a taken health or metrics port tried before giving up
+    pub port_fallback_range: u16,
+    /// Loss (USD, after fees) of a live trade that triggers a post-mortem
+    /// bundle (0: never)
+    pub postmortem_loss_usd: f64,
+    /// Directory post-mortem bundles are written to
+    pub postmortem_dir: String,
+    /// Seconds of price ticks and events either side of a losing trade
+    /// included in its post-mortem
+    pub postmortem_window_secs: u64,
+    /// Currency money limits are configured in and reports, metrics and
+    /// alerts show amounts in (ISO code, e.g. "EUR")
+    pub accounting_currency: String,
+    /// Pyth feed quoting the accounting currency in USD (empty: the known
+    /// feed for the currency)
+    pub fx_feed_id: String,
+    /// Pyth Hermes endpoint the exchange rate is read from
+    pub fx_oracle_url: String,
+    /// Seconds between exchange rate refreshes
+    pub fx_refresh_secs: u64,
+    /// Age of the exchange rate (seconds) after which it is alerted as stale
+    pub fx_max_age_secs: u64,
+    /// How long a Jupiter quote is reused (ms); 0 disables the quote cache
+    pub quote_cache_ttl_ms: u64,
+    /// Width (%) of the trade size buckets estimates share cached quotes in
+    pub quote_cache_bucket_pct: f64,
+    /// Telegram bot token commands are polled with; unset disables commands
+    pub telegram_bot_token: Option<String>,
+    /// Comma-separated Telegram chat ids allowed to send commands
+    pub telegram_command_chat_ids: String,
+    /// Strategy profile: "standard" (detector and statistical strategies)
+    /// or "conservative" (crossed markets only)
+    pub strategy_profile: String,
+    /// Edge (bps) a crossed market must clear beyond both venues' fees
+    pub crossed_market_margin_bps: f64,
+    /// Ticks of price ratios the statistical strategy averages over
+    pub stat_arb_window: usize,
+    /// Z-score of the ratio at which the statistical strategy fires
+    pub stat_arb_z_score: f64,
+    /// Trade Phoenix order book imbalance against lagging AMMs (standard profile)
+    pub imbalance_strategy_enabled: bool,
+    /// Book levels per side summed into the imbalance
+    pub imbalance_levels: usize,
+    /// Smallest imbalance (0 to 1) that predicts a drift
+    pub imbalance_threshold: f64,
+    /// Predicted drift (bps) of a fully one-sided book
+    pub imbalance_max_drift_bps: f64,
+    /// Edge (bps) an imbalance trade must clear beyond both venues' fees
+    pub imbalance_margin_bps: f64,
+    /// Minimum time (ms) between the starts of two price fetches
+    pub price_poll_interval_ms: u64,
+    /// A price stream silent this long (ms) falls back to polling its pair
+    pub price_stream_stale_ms: u64,
+    /// Solana RPC pubsub endpoint (empty: derived from `solana_rpc_url`)
+    pub solana_ws_url: String,
+    /// Pools streamed from their accounts, as `dex:BASE/QUOTE=address`
+    /// entries in the pool's token order (Orca and Meteora)
+    pub stream_pool_accounts: String,
+    /// Mirror trade history, traded opportunities and risk events into
+    /// Postgres (`DATABASE_URL`)
+    pub trade_store_enabled: bool,
+    /// Check wallet balances, wSOL accounts, open transactions and lookup
+    /// tables against the chain before live trading starts
+    pub reconcile_on_startup: bool,
+    /// Log of signed transactions not yet seen confirmed
+    pub intent_wal_path: String,
+    /// Unix socket the bot publishes its trading events on, for the API
+    /// server's WebSocket clients (`None`: not published)
+    pub event_socket_path: Option<String>,
+    /// Batches each trading pipeline queue holds before holding back the
+    /// stage feeding it
+    pub pipeline_queue_depth: usize,
+    /// Time limit (ms) of one venue's price request
+    pub dex_timeout_ms: u64,
+    /// Per-venue request limits as dex:ms pairs, e.g. "phoenix:800"
+    pub dex_timeout_ms_by_venue: String,
+    /// Time limit (ms) of a whole price fetch; venues still running are
+    /// left out of the batch
+    pub price_fetch_budget_ms: u64,
+    /// Send a second price request when the first outlives the venue's p95
+    pub dex_hedged_requests: bool,
+    /// Write a public status bundle (no sizes or balances)
+    pub status_page_enabled: bool,
+    /// Directory the status bundle is written to
+    pub status_page_dir: String,
+    /// Seconds between status bundle refreshes
+    pub status_page_interval_secs: u64,
+    /// Heading of the status page
+    pub status_page_title: String,
 }
 
 impl Config {
@@ -78,6 +429,28 @@ impl Config {
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
+            max_quote_skew_ms: env::var("MAX_QUOTE_SKEW_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            max_spread_pct: env::var("MAX_SPREAD_PCT")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            max_spread_pct_by_venue: env::var("MAX_SPREAD_PCT_BY_VENUE").unwrap_or_default(),
+            price_projection_enabled: env::var("PRICE_PROJECTION_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            price_projection_venues: env::var("PRICE_PROJECTION_VENUES")
+                .unwrap_or_else(|_| "phoenix".to_string()),
+            price_projection_alpha: env::var("PRICE_PROJECTION_ALPHA")
+                .unwrap_or_else(|_| "0.2".to_string())
+                .parse()
+                .unwrap_or(0.2),
+            price_projection_max_bps: env::var("PRICE_PROJECTION_MAX_BPS")
+                .unwrap_or_else(|_| "25".to_string())
+                .parse()
+                .unwrap_or(25.0),
             api_port: env::var("API_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
@@ -92,6 +465,10 @@ impl Config {
                 .parse()
                 .unwrap_or(200000),
             rpc_commitment: env::var("RPC_COMMITMENT").unwrap_or_else(|_| "confirmed".to_string()),
+            rpc_commitment_reads: env::var("RPC_COMMITMENT_READS").unwrap_or_default(),
+            rpc_commitment_submission: env::var("RPC_COMMITMENT_SUBMISSION").unwrap_or_default(),
+            rpc_commitment_accounting: env::var("RPC_COMMITMENT_ACCOUNTING")
+                .unwrap_or_else(|_| "finalized".to_string()),
             slippage_bps: env::var("SLIPPAGE_BPS")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()
@@ -109,9 +486,61 @@ impl Config {
                 .unwrap_or_else(|_| "10000".to_string())
                 .parse()
                 .unwrap_or(10000),
+            jupiter_api_url: env::var("JUPITER_API_URL")
+                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6".to_string()),
+            jupiter_compare_public: env::var("JUPITER_COMPARE_PUBLIC")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
+            jupiter_probe_interval_secs: env::var("JUPITER_PROBE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            rate_limit_autotune: env::var("RATE_LIMIT_AUTOTUNE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
+            rate_limit_tune_interval_secs: env::var("RATE_LIMIT_TUNE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            rate_limit_target_latency_ms: env::var("RATE_LIMIT_TARGET_LATENCY_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            rpc_rate_limit: env::var("RPC_RATE_LIMIT")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            rpc_rate_limit_floor: env::var("RPC_RATE_LIMIT_FLOOR")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap_or(2),
+            rpc_rate_limit_ceiling: env::var("RPC_RATE_LIMIT_CEILING")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            jupiter_rate_limit: env::var("JUPITER_RATE_LIMIT")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            jupiter_rate_limit_floor: env::var("JUPITER_RATE_LIMIT_FLOOR")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            jupiter_rate_limit_ceiling: env::var("JUPITER_RATE_LIMIT_CEILING")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            shared_rate_limits: env::var("SHARED_RATE_LIMITS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            rate_limit_redis_prefix: env::var("RATE_LIMIT_REDIS_PREFIX")
+                .unwrap_or_else(|_| "arb:ratelimit".to_string()),
             dry_run: env::var("DRY_RUN")
                 .map(|v| v == "true" || v == "1")
                 .unwrap_or(true),
+            watch_only: env::var("WATCH_ONLY")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
             max_daily_loss: env::var("MAX_DAILY_LOSS")
                 .unwrap_or_else(|_| "500.0".to_string())
                 .parse()
@@ -129,6 +558,20 @@ impl Config {
                 .unwrap_or(300),
             telegram_webhook_url: env::var("TELEGRAM_WEBHOOK_URL").ok(),
             discord_webhook_url: env::var("DISCORD_WEBHOOK_URL").ok(),
+            telegram_alert_redact: env::var("TELEGRAM_ALERT_REDACT")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            telegram_alert_public_key: env::var("TELEGRAM_ALERT_PUBLIC_KEY").ok(),
+            discord_alert_redact: env::var("DISCORD_ALERT_REDACT")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            discord_alert_public_key: env::var("DISCORD_ALERT_PUBLIC_KEY").ok(),
+            trade_webhook_url: env::var("TRADE_WEBHOOK_URL").ok().filter(|v| !v.is_empty()),
+            trade_webhook_secret: env::var("TRADE_WEBHOOK_SECRET").ok().filter(|v| !v.is_empty()),
+            trade_webhook_max_attempts: env::var("TRADE_WEBHOOK_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
             poll_interval_ms: env::var("POLL_INTERVAL_MS")
                 .unwrap_or_else(|_| "500".to_string())
                 .parse()
@@ -144,6 +587,437 @@ impl Config {
                 .unwrap_or_else(|_| "9090".to_string())
                 .parse()
                 .unwrap_or(9090),
+            metrics_recorder_enabled: env::var("METRICS_RECORDER_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            metrics_recorder_interval_secs: env::var("METRICS_RECORDER_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            metrics_recorder_retention_days: env::var("METRICS_RECORDER_RETENTION_DAYS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .unwrap_or(7),
+            metrics_recorder_path: env::var("METRICS_RECORDER_PATH")
+                .unwrap_or_else(|_| "data/metrics_timeseries.jsonl".to_string()),
+            max_venue_exposure: env::var("MAX_VENUE_EXPOSURE")
+                .unwrap_or_else(|_| "2500.0".to_string())
+                .parse()
+                .unwrap_or(2500.0),
+            canary_size_usd: env::var("CANARY_SIZE_USD")
+                .unwrap_or_else(|_| "50.0".to_string())
+                .parse()
+                .unwrap_or(50.0),
+            canary_required_successes: env::var("CANARY_REQUIRED_SUCCESSES")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            remote_strategy_url: env::var("REMOTE_STRATEGY_URL").unwrap_or_default(),
+            remote_strategy_timeout_ms: env::var("REMOTE_STRATEGY_TIMEOUT_MS")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .unwrap_or(200),
+            paper_queue_ahead_pct: env::var("PAPER_QUEUE_AHEAD_PCT")
+                .unwrap_or_else(|_| "25.0".to_string())
+                .parse()
+                .unwrap_or(25.0),
+            regime_window_ticks: env::var("REGIME_WINDOW_TICKS")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .unwrap_or(600),
+            regime_dislocation_ratio: env::var("REGIME_DISLOCATION_RATIO")
+                .unwrap_or_else(|_| "3.0".to_string())
+                .parse()
+                .unwrap_or(3.0),
+            regime_edge_multiplier: env::var("REGIME_EDGE_MULTIPLIER")
+                .unwrap_or_else(|_| "2.0".to_string())
+                .parse()
+                .unwrap_or(2.0),
+            fee_reserve_min_sol: env::var("FEE_RESERVE_MIN_SOL")
+                .unwrap_or_else(|_| "0.05".to_string())
+                .parse()
+                .unwrap_or(0.05),
+            fee_reserve_target_sol: env::var("FEE_RESERVE_TARGET_SOL")
+                .unwrap_or_else(|_| "0.2".to_string())
+                .parse()
+                .unwrap_or(0.2),
+            fee_reserve_auto_topup: env::var("FEE_RESERVE_AUTO_TOPUP")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            fee_reserve_max_topup_usd: env::var("FEE_RESERVE_MAX_TOPUP_USD")
+                .unwrap_or_else(|_| "25.0".to_string())
+                .parse()
+                .unwrap_or(25.0),
+            fee_reserve_check_secs: env::var("FEE_RESERVE_CHECK_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            history_rotate_max_mb: env::var("HISTORY_ROTATE_MAX_MB")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            history_rotate_max_age_hours: env::var("HISTORY_ROTATE_MAX_AGE_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            sign_records: env::var("SIGN_RECORDS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            rpc_batch_window_ms: env::var("RPC_BATCH_WINDOW_MS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            drift_gateway_url: env::var("DRIFT_GATEWAY_URL").unwrap_or_default(),
+            drift_perp_markets: env::var("DRIFT_PERP_MARKETS").unwrap_or_else(|_| "SOL:0".to_string()),
+            hedge_interval_secs: env::var("HEDGE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            standby_primary_url: env::var("STANDBY_PRIMARY_URL").ok().filter(|v| !v.is_empty()),
+            replication_sync_ms: env::var("REPLICATION_SYNC_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            standby_failover_secs: env::var("STANDBY_FAILOVER_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            pool_discovery_enabled: env::var("POOL_DISCOVERY_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            pool_discovery_interval_secs: env::var("POOL_DISCOVERY_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            discovery_tokens: env::var("DISCOVERY_TOKENS")
+                .unwrap_or_else(|_| "RAY,ORCA,JUP,BONK,WIF,PYTH".to_string()),
+            discovery_min_liquidity_usd: env::var("DISCOVERY_MIN_LIQUIDITY_USD")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()
+                .unwrap_or(100_000.0),
+            incident_escalate_minutes: env::var("INCIDENT_ESCALATE_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+            incident_escalation_webhook_url: env::var("INCIDENT_ESCALATION_WEBHOOK_URL")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            escalation_alert_redact: env::var("ESCALATION_ALERT_REDACT")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            escalation_alert_public_key: env::var("ESCALATION_ALERT_PUBLIC_KEY").ok(),
+            execution_deadline_ms: env::var("EXECUTION_DEADLINE_MS")
+                .unwrap_or_else(|_| "800".to_string())
+                .parse()
+                .unwrap_or(800),
+            wsol_mode: env::var("WSOL_MODE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            wsol_target_sol: env::var("WSOL_TARGET_SOL")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            flash_loan_max_reserve_fraction: env::var("FLASH_LOAN_MAX_RESERVE_FRACTION")
+                .unwrap_or_else(|_| "0.1".to_string())
+                .parse()
+                .unwrap_or(0.1),
+            flash_loan_liquidity_refresh_secs: env::var("FLASH_LOAN_LIQUIDITY_REFRESH_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            event_bus_capacity: env::var("EVENT_BUS_CAPACITY")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            event_bus_policies: env::var("EVENT_BUS_POLICIES").unwrap_or_default(),
+            event_bus_spill_dir: env::var("EVENT_BUS_SPILL_DIR")
+                .unwrap_or_else(|_| "data/event_spill".to_string()),
+            kamino_flash_loans_enabled: env::var("KAMINO_FLASH_LOANS_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            kamino_lending_market: env::var("KAMINO_LENDING_MARKET")
+                .unwrap_or_else(|_| "7u3HeHxYDLhnCoErrtycNokbQYbWGzLs6JSDqGAv5PfF".to_string()),
+            kamino_usdc_reserve: env::var("KAMINO_USDC_RESERVE")
+                .unwrap_or_else(|_| "D6q6wuQSrifJKZYpR1M8R4YawnLDtDsMmWM1NbBmgJ59".to_string()),
+            kamino_flash_loan_fee_bps: env::var("KAMINO_FLASH_LOAN_FEE_BPS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            marginfi_flash_loans_enabled: env::var("MARGINFI_FLASH_LOANS_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            marginfi_group: env::var("MARGINFI_GROUP")
+                .unwrap_or_else(|_| "4qp6Fx6tnZkY5Wropq9wUYgtFxXKwE6viZxFHg3rdAG8".to_string()),
+            marginfi_account: env::var("MARGINFI_ACCOUNT").unwrap_or_default(),
+            marginfi_banks: env::var("MARGINFI_BANKS")
+                .unwrap_or_else(|_| "USDC=2s37akK2eyBbp8DZgCm7RtsaEz8eJP3Nxd4urLHQv7yB".to_string()),
+            marginfi_flash_loan_fee_bps: env::var("MARGINFI_FLASH_LOAN_FEE_BPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            unwind_step_usd: env::var("UNWIND_STEP_USD")
+                .unwrap_or_else(|_| "250.0".to_string())
+                .parse()
+                .unwrap_or(250.0),
+            capital_commitment_ttl_secs: env::var("CAPITAL_COMMITMENT_TTL_SECS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .unwrap_or(90),
+            unwind_interval_secs: env::var("UNWIND_INTERVAL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            route_repair_enabled: env::var("ROUTE_REPAIR_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            route_repair_settle_secs: env::var("ROUTE_REPAIR_SETTLE_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            route_repair_min_usd: env::var("ROUTE_REPAIR_MIN_USD")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            quarantine_enabled: env::var("QUARANTINE_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            quarantine_check_secs: env::var("QUARANTINE_CHECK_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            quarantine_max_outflow_sol: env::var("QUARANTINE_MAX_OUTFLOW_SOL")
+                .unwrap_or_else(|_| "0.5".to_string())
+                .parse()
+                .unwrap_or(0.5),
+            quarantine_state_path: env::var("QUARANTINE_STATE_PATH")
+                .unwrap_or_else(|_| "data/quarantine.json".to_string()),
+            batch_max_legs: env::var("BATCH_MAX_LEGS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            batch_max_leg_size_usd: env::var("BATCH_MAX_LEG_SIZE_USD")
+                .unwrap_or_else(|_| "100.0".to_string())
+                .parse()
+                .unwrap_or(100.0),
+            path_max_size_usd: env::var("PATH_MAX_SIZE_USD")
+                .unwrap_or_else(|_| "100.0".to_string())
+                .parse()
+                .unwrap_or(100.0),
+            var_method: env::var("VAR_METHOD").unwrap_or_else(|_| "historical".to_string()),
+            var_confidence: env::var("VAR_CONFIDENCE")
+                .unwrap_or_else(|_| "0.95".to_string())
+                .parse()
+                .unwrap_or(0.95),
+            var_horizon_periods: env::var("VAR_HORIZON_PERIODS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap_or(1),
+            var_monte_carlo_paths: env::var("VAR_MONTE_CARLO_PATHS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            risk_report_dir: env::var("RISK_REPORT_DIR")
+                .unwrap_or_else(|_| "data/reports".to_string()),
+            price_snapshot_dir: env::var("PRICE_SNAPSHOT_DIR")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            price_compaction_enabled: env::var("PRICE_COMPACTION_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            price_compaction_interval_secs: env::var("PRICE_COMPACTION_INTERVAL_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            price_raw_retention_days: env::var("PRICE_RAW_RETENTION_DAYS")
+                .unwrap_or_else(|_| "7".to_string())
+                .parse()
+                .unwrap_or(7),
+            price_bars_1s_retention_days: env::var("PRICE_BARS_1S_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            price_bars_1m_retention_days: env::var("PRICE_BARS_1M_RETENTION_DAYS")
+                .unwrap_or_else(|_| "365".to_string())
+                .parse()
+                .unwrap_or(365),
+            compliance_token_allowlist: env::var("COMPLIANCE_TOKEN_ALLOWLIST").unwrap_or_default(),
+            compliance_program_allowlist: env::var("COMPLIANCE_PROGRAM_ALLOWLIST")
+                .unwrap_or_default(),
+            compliance_max_notional_usd: env::var("COMPLIANCE_MAX_NOTIONAL_USD")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0.0),
+            compliance_pair_notional_usd: env::var("COMPLIANCE_PAIR_NOTIONAL_USD")
+                .unwrap_or_default(),
+            compliance_hook_url: env::var("COMPLIANCE_HOOK_URL")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            compliance_hook_timeout_ms: env::var("COMPLIANCE_HOOK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "250".to_string())
+                .parse()
+                .unwrap_or(250),
+            compliance_hook_fail_open: env::var("COMPLIANCE_HOOK_FAIL_OPEN")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            lst_pairs_enabled: env::var("LST_PAIRS_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            sanctum_api_url: env::var("SANCTUM_API_URL")
+                .unwrap_or_else(|_| "https://extra-api.sanctum.so".to_string()),
+            sanctum_fee_bps: env::var("SANCTUM_FEE_BPS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            preflight_top_k: env::var("PREFLIGHT_TOP_K")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            preflight_concurrency: env::var("PREFLIGHT_CONCURRENCY")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            size_search_steps: env::var("SIZE_SEARCH_STEPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            size_search_min_usd: env::var("SIZE_SEARCH_MIN_USD")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50.0),
+            impact_curve_ttl_secs: env::var("IMPACT_CURVE_TTL_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            health_port: env::var("HEALTH_PORT")
+                .unwrap_or_else(|_| "8080".to_string())
+                .parse()
+                .unwrap_or(8080),
+            health_bind_addr: env::var("HEALTH_BIND_ADDR")
+                .unwrap_or_else(|_| "0.0.0.0".to_string()),
+            metrics_bind_addr: env::var("METRICS_BIND_ADDR")
+                .unwrap_or_else(|_| "0.0.0.0".to_string()),
+            port_fallback_range: env::var("PORT_FALLBACK_RANGE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            postmortem_loss_usd: env::var("POSTMORTEM_LOSS_USD")
+                .unwrap_or_else(|_| "25".to_string())
+                .parse()
+                .unwrap_or(25.0),
+            postmortem_dir: env::var("POSTMORTEM_DIR")
+                .unwrap_or_else(|_| "data/postmortems".to_string()),
+            postmortem_window_secs: env::var("POSTMORTEM_WINDOW_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            accounting_currency: env::var("ACCOUNTING_CURRENCY")
+                .unwrap_or_else(|_| "USD".to_string())
+                .trim()
+                .to_uppercase(),
+            fx_feed_id: env::var("FX_FEED_ID").unwrap_or_default(),
+            fx_oracle_url: env::var("FX_ORACLE_URL")
+                .unwrap_or_else(|_| "https://hermes.pyth.network".to_string()),
+            fx_refresh_secs: env::var("FX_REFRESH_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            fx_max_age_secs: env::var("FX_MAX_AGE_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .unwrap_or(900),
+            quote_cache_ttl_ms: env::var("QUOTE_CACHE_TTL_MS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+            quote_cache_bucket_pct: env::var("QUOTE_CACHE_BUCKET_PCT")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()
+                .unwrap_or(1.0),
+            telegram_bot_token: env::var("TELEGRAM_BOT_TOKEN")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            telegram_command_chat_ids: env::var("TELEGRAM_COMMAND_CHAT_IDS").unwrap_or_default(),
+            strategy_profile: env::var("STRATEGY_PROFILE")
+                .unwrap_or_else(|_| "standard".to_string()),
+            crossed_market_margin_bps: env::var("CROSSED_MARKET_MARGIN_BPS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10.0),
+            stat_arb_window: env::var("STAT_ARB_WINDOW")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+            stat_arb_z_score: env::var("STAT_ARB_Z_SCORE")
+                .unwrap_or_else(|_| "2.0".to_string())
+                .parse()
+                .unwrap_or(2.0),
+            imbalance_strategy_enabled: env::var("IMBALANCE_STRATEGY_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            imbalance_levels: env::var("IMBALANCE_LEVELS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            imbalance_threshold: env::var("IMBALANCE_THRESHOLD")
+                .unwrap_or_else(|_| "0.3".to_string())
+                .parse()
+                .unwrap_or(0.3),
+            imbalance_max_drift_bps: env::var("IMBALANCE_MAX_DRIFT_BPS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30.0),
+            imbalance_margin_bps: env::var("IMBALANCE_MARGIN_BPS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10.0),
+            price_poll_interval_ms: env::var("PRICE_POLL_INTERVAL_MS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+            price_stream_stale_ms: env::var("PRICE_STREAM_STALE_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .unwrap_or(5000),
+            solana_ws_url: env::var("SOLANA_WS_URL").unwrap_or_default(),
+            stream_pool_accounts: env::var("STREAM_POOL_ACCOUNTS").unwrap_or_default(),
+            trade_store_enabled: env::var("TRADE_STORE_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            reconcile_on_startup: env::var("RECONCILE_ON_STARTUP")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
+            intent_wal_path: env::var("INTENT_WAL_PATH")
+                .unwrap_or_else(|_| "data/intents.jsonl".to_string()),
+            event_socket_path: env::var("EVENT_SOCKET_PATH")
+                .ok()
+                .filter(|v| !v.is_empty()),
+            pipeline_queue_depth: env::var("PIPELINE_QUEUE_DEPTH")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .unwrap_or(8),
+            dex_timeout_ms: env::var("DEX_TIMEOUT_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()
+                .unwrap_or(2000),
+            dex_timeout_ms_by_venue: env::var("DEX_TIMEOUT_MS_BY_VENUE").unwrap_or_default(),
+            price_fetch_budget_ms: env::var("PRICE_FETCH_BUDGET_MS")
+                .unwrap_or_else(|_| "3000".to_string())
+                .parse()
+                .unwrap_or(3000),
+            dex_hedged_requests: env::var("DEX_HEDGED_REQUESTS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            status_page_enabled: env::var("STATUS_PAGE_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            status_page_dir: env::var("STATUS_PAGE_DIR")
+                .unwrap_or_else(|_| "data/status-page".to_string()),
+            status_page_interval_secs: env::var("STATUS_PAGE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .unwrap_or(60),
+            status_page_title: env::var("STATUS_PAGE_TITLE")
+                .unwrap_or_else(|_| "Arbitrage Bot Status".to_string()),
         })
     }
 }
@@ -156,27 +1030,193 @@ impl Default for Config {
             solana_rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
             min_profit_threshold: 0.5,
             max_price_age_seconds: 5,
+            max_quote_skew_ms: 1000,
+            max_spread_pct: 1.0,
+            max_spread_pct_by_venue: String::new(),
+            price_projection_enabled: false,
+            price_projection_venues: "phoenix".to_string(),
+            price_projection_alpha: 0.2,
+            price_projection_max_bps: 25.0,
             api_port: 8080,
             log_level: "info".to_string(),
             priority_fee_micro_lamports: 50000,
             compute_unit_limit: 200000,
             rpc_commitment: "confirmed".to_string(),
+            rpc_commitment_reads: String::new(),
+            rpc_commitment_submission: String::new(),
+            rpc_commitment_accounting: "finalized".to_string(),
             slippage_bps: 50,
             max_retries: 3,
             use_jito: false,
             jito_block_engine_url: "https://mainnet.block-engine.jito.wtf".to_string(),
             jito_tip_lamports: 10000,
+            jupiter_api_url: "https://quote-api.jup.ag/v6".to_string(),
+            jupiter_compare_public: true,
+            jupiter_probe_interval_secs: 60,
+            rate_limit_autotune: true,
+            rate_limit_tune_interval_secs: 60,
+            rate_limit_target_latency_ms: 1000,
+            rpc_rate_limit: 10,
+            rpc_rate_limit_floor: 2,
+            rpc_rate_limit_ceiling: 50,
+            jupiter_rate_limit: 5,
+            jupiter_rate_limit_floor: 1,
+            jupiter_rate_limit_ceiling: 10,
+            shared_rate_limits: false,
+            rate_limit_redis_prefix: "arb:ratelimit".to_string(),
             dry_run: true,
+            watch_only: false,
             max_daily_loss: 500.0,
             max_consecutive_losses: 5,
             circuit_breaker_enabled: true,
             circuit_breaker_timeout_seconds: 300,
             telegram_webhook_url: None,
             discord_webhook_url: None,
+            telegram_alert_redact: false,
+            telegram_alert_public_key: None,
+            discord_alert_redact: false,
+            discord_alert_public_key: None,
+            trade_webhook_url: None,
+            trade_webhook_secret: None,
+            trade_webhook_max_attempts: 5,
             poll_interval_ms: 500,
             max_concurrent_trades: 1,
             enable_metrics: true,
             metrics_port: 9090,
+            metrics_recorder_enabled: false,
+            metrics_recorder_interval_secs: 60,
+            metrics_recorder_retention_days: 7,
+            metrics_recorder_path: "data/metrics_timeseries.jsonl".to_string(),
+            max_venue_exposure: 2500.0,
+            canary_size_usd: 50.0,
+            canary_required_successes: 3,
+            remote_strategy_url: String::new(),
+            remote_strategy_timeout_ms: 200,
+            unwind_step_usd: 250.0,
+            unwind_interval_secs: 30,
+            route_repair_enabled: false,
+            route_repair_settle_secs: 10,
+            route_repair_min_usd: 1.0,
+            quarantine_enabled: false,
+            quarantine_check_secs: 30,
+            quarantine_max_outflow_sol: 0.5,
+            quarantine_state_path: "data/quarantine.json".to_string(),
+            capital_commitment_ttl_secs: 90,
+            paper_queue_ahead_pct: 25.0,
+            regime_window_ticks: 600,
+            regime_dislocation_ratio: 3.0,
+            regime_edge_multiplier: 2.0,
+            fee_reserve_min_sol: 0.05,
+            fee_reserve_target_sol: 0.2,
+            fee_reserve_auto_topup: false,
+            fee_reserve_max_topup_usd: 25.0,
+            fee_reserve_check_secs: 60,
+            history_rotate_max_mb: 50,
+            history_rotate_max_age_hours: 24,
+            sign_records: false,
+            rpc_batch_window_ms: 5,
+            drift_gateway_url: String::new(),
+            drift_perp_markets: "SOL:0".to_string(),
+            hedge_interval_secs: 30,
+            standby_primary_url: None,
+            replication_sync_ms: 1000,
+            standby_failover_secs: 10,
+            pool_discovery_enabled: false,
+            pool_discovery_interval_secs: 3600,
+            discovery_tokens: "RAY,ORCA,JUP,BONK,WIF,PYTH".to_string(),
+            discovery_min_liquidity_usd: 100_000.0,
+            incident_escalate_minutes: 15,
+            incident_escalation_webhook_url: None,
+            escalation_alert_redact: false,
+            escalation_alert_public_key: None,
+            execution_deadline_ms: 800,
+            wsol_mode: false,
+            wsol_target_sol: 1.0,
+            flash_loan_max_reserve_fraction: 0.1,
+            flash_loan_liquidity_refresh_secs: 30,
+            event_bus_capacity: 1000,
+            event_bus_policies: String::new(),
+            event_bus_spill_dir: "data/event_spill".to_string(),
+            kamino_flash_loans_enabled: false,
+            kamino_lending_market: "7u3HeHxYDLhnCoErrtycNokbQYbWGzLs6JSDqGAv5PfF".to_string(),
+            kamino_usdc_reserve: "D6q6wuQSrifJKZYpR1M8R4YawnLDtDsMmWM1NbBmgJ59".to_string(),
+            kamino_flash_loan_fee_bps: 1,
+            marginfi_flash_loans_enabled: false,
+            marginfi_group: "4qp6Fx6tnZkY5Wropq9wUYgtFxXKwE6viZxFHg3rdAG8".to_string(),
+            marginfi_account: String::new(),
+            marginfi_banks: "USDC=2s37akK2eyBbp8DZgCm7RtsaEz8eJP3Nxd4urLHQv7yB".to_string(),
+            marginfi_flash_loan_fee_bps: 0,
+            batch_max_legs: 3,
+            batch_max_leg_size_usd: 100.0,
+            path_max_size_usd: 100.0,
+            var_method: "historical".to_string(),
+            var_confidence: 0.95,
+            var_horizon_periods: 1,
+            var_monte_carlo_paths: 10000,
+            risk_report_dir: "data/reports".to_string(),
+            price_snapshot_dir: None,
+            price_compaction_enabled: false,
+            price_compaction_interval_secs: 3600,
+            price_raw_retention_days: 7,
+            price_bars_1s_retention_days: 30,
+            price_bars_1m_retention_days: 365,
+            compliance_token_allowlist: String::new(),
+            compliance_program_allowlist: String::new(),
+            compliance_max_notional_usd: 0.0,
+            compliance_pair_notional_usd: String::new(),
+            compliance_hook_url: None,
+            compliance_hook_timeout_ms: 250,
+            compliance_hook_fail_open: false,
+            lst_pairs_enabled: false,
+            sanctum_api_url: "https://extra-api.sanctum.so".to_string(),
+            sanctum_fee_bps: 10,
+            preflight_top_k: 0,
+            preflight_concurrency: 3,
+            size_search_steps: 0,
+            size_search_min_usd: 50.0,
+            impact_curve_ttl_secs: 30,
+            health_port: 8080,
+            health_bind_addr: "0.0.0.0".to_string(),
+            metrics_bind_addr: "0.0.0.0".to_string(),
+            port_fallback_range: 10,
+            postmortem_loss_usd: 25.0,
+            postmortem_dir: "data/postmortems".to_string(),
+            postmortem_window_secs: 60,
+            accounting_currency: "USD".to_string(),
+            fx_feed_id: String::new(),
+            fx_oracle_url: "https://hermes.pyth.network".to_string(),
+            fx_refresh_secs: 60,
+            fx_max_age_secs: 900,
+            quote_cache_ttl_ms: 500,
+            quote_cache_bucket_pct: 1.0,
+            telegram_bot_token: None,
+            telegram_command_chat_ids: String::new(),
+            strategy_profile: "standard".to_string(),
+            crossed_market_margin_bps: 10.0,
+            stat_arb_window: 20,
+            stat_arb_z_score: 2.0,
+            imbalance_strategy_enabled: false,
+            imbalance_levels: 5,
+            imbalance_threshold: 0.3,
+            imbalance_max_drift_bps: 30.0,
+            imbalance_margin_bps: 10.0,
+            price_poll_interval_ms: 500,
+            price_stream_stale_ms: 5000,
+            solana_ws_url: String::new(),
+            stream_pool_accounts: String::new(),
+            trade_store_enabled: false,
+            reconcile_on_startup: true,
+            intent_wal_path: "data/intents.jsonl".to_string(),
+            event_socket_path: None,
+            pipeline_queue_depth: 8,
+            dex_timeout_ms: 2000,
+            dex_timeout_ms_by_venue: String::new(),
+            price_fetch_budget_ms: 3000,
+            dex_hedged_requests: false,
+            status_page_enabled: false,
+            status_page_dir: "data/status-page".to_string(),
+            status_page_interval_secs: 60,
+            status_page_title: "Arbitrage Bot Status".to_string(),
         }
     }
 }
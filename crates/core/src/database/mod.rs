@@ -1,2 +1,4 @@
 #[cfg(feature = "db")]
 pub mod timescale;
+#[cfg(feature = "db")]
+pub mod trade_store;
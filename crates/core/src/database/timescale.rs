@@ -1,9 +1,11 @@
 use crate::risk::TradeOutcome;
-use crate::types::ArbitrageOpportunity;
+use crate::session::{Session, SessionKpis};
+use crate::types::{ArbitrageOpportunity, DexType, PriceData, TokenPair};
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod, Runtime};
-use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
 use tokio_postgres::NoTls;
 use uuid::Uuid;
 
@@ -65,6 +67,8 @@ impl TimescaleClient {
         trade: &TradeOutcome,
         opp_id: Option<Uuid>,
         signature: &str,
+        session_id: Option<&str>,
+        execution_time_ms: u64,
     ) -> Result<()> {
         let client = self.pool.get().await?;
         let opp_id = opp_id.unwrap_or_else(Uuid::new_v4);
@@ -74,8 +78,8 @@ impl TimescaleClient {
             .prepare(
                 "INSERT INTO trades 
             (time, trade_id, opportunity_id, signature, actual_profit, 
-             execution_time_ms, slippage_bps, gas_used, priority_fee, status)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+             execution_time_ms, slippage_bps, gas_used, priority_fee, status, session_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
             )
             .await?;
 
@@ -88,7 +92,7 @@ impl TimescaleClient {
                     &opp_id,
                     &signature,
                     &trade.profit_loss.to_f64().unwrap_or(0.0),
-                    &0i32,   // execution_time_ms
+                    &(execution_time_ms.min(i32::MAX as u64) as i32),
                     &0.0f64, // slippage_bps
                     &0i64,   // gas_used
                     &0i64,   // priority_fee
@@ -97,10 +101,142 @@ impl TimescaleClient {
                     } else {
                         "failed"
                     },
+                    &session_id,
                 ],
             )
             .await?;
 
         Ok(())
     }
+
+    pub async fn insert_session(&self, session: &Session) -> Result<()> {
+        let client = self.pool.get().await?;
+        client
+            .execute(
+                "INSERT INTO sessions (session_id, started_at, ended_at, mode, config_snapshot, git_rev)
+            VALUES ($1, $2, $3, $4, CAST($5 AS TEXT)::jsonb, $6)",
+                &[
+                    &session.id,
+                    &session.started_at,
+                    &session.ended_at,
+                    &session.mode,
+                    &session.config_snapshot.to_string(),
+                    &session.git_rev,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn end_session(&self, session_id: &str, ended_at: DateTime<Utc>) -> Result<()> {
+        let client = self.pool.get().await?;
+        client
+            .execute(
+                "UPDATE sessions SET ended_at = $2 WHERE session_id = $1",
+                &[&session_id, &ended_at],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Most recent sessions first
+    pub async fn list_sessions(&self, limit: i64) -> Result<Vec<Session>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT session_id, started_at, ended_at, mode, config_snapshot::text, git_rev
+            FROM sessions ORDER BY started_at DESC LIMIT $1",
+                &[&limit],
+            )
+            .await?;
+        rows.iter().map(session_from_row).collect()
+    }
+
+    pub async fn get_session(&self, session_id: &str) -> Result<Option<Session>> {
+        let client = self.pool.get().await?;
+        let row = client
+            .query_opt(
+                "SELECT session_id, started_at, ended_at, mode, config_snapshot::text, git_rev
+            FROM sessions WHERE session_id = $1",
+                &[&session_id],
+            )
+            .await?;
+        row.as_ref().map(session_from_row).transpose()
+    }
+
+    /// Win rate, P&L and latency over the trades tagged with `session_id`
+    pub async fn session_kpis(&self, session_id: &str) -> Result<SessionKpis> {
+        let client = self.pool.get().await?;
+        let row = client
+            .query_one(
+                "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE status = 'success'),
+                    COALESCE(SUM(actual_profit), 0),
+                    AVG(execution_time_ms)::DOUBLE PRECISION
+            FROM trades WHERE session_id = $1",
+                &[&session_id],
+            )
+            .await?;
+
+        let trades: i64 = row.get(0);
+        let successful: i64 = row.get(1);
+        Ok(SessionKpis::new(
+            trades as u64,
+            successful as u64,
+            row.get(2),
+            row.get(3),
+        ))
+    }
+
+    /// Prices recorded in `price_data` from `from` up to and including `to`,
+    /// oldest first. Rows of venues this build does not know are skipped.
+    pub async fn price_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PriceData>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT dex, base_token, quote_token,
+                    bid_price::DOUBLE PRECISION, ask_price::DOUBLE PRECISION,
+                    volume_24h::DOUBLE PRECISION, liquidity::DOUBLE PRECISION, timestamp
+            FROM price_data WHERE timestamp >= $1 AND timestamp <= $2
+            ORDER BY timestamp",
+                &[&from, &to],
+            )
+            .await?;
+
+        let decimal = |value: f64| Decimal::from_f64(value).unwrap_or_default();
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let dex: String = row.get(0);
+                let dex: DexType =
+                    serde_json::from_value(serde_json::Value::String(dex.to_lowercase())).ok()?;
+                let mut price = PriceData::new(
+                    dex,
+                    TokenPair::new(row.get::<_, String>(1), row.get::<_, String>(2)),
+                    decimal(row.get(3)),
+                    decimal(row.get(4)),
+                );
+                price.volume_24h = row.get::<_, Option<f64>>(5).map(decimal);
+                price.liquidity = row.get::<_, Option<f64>>(6).map(decimal);
+                price.timestamp = row.get(7);
+                Some(price)
+            })
+            .collect())
+    }
+}
+
+fn session_from_row(row: &tokio_postgres::Row) -> Result<Session> {
+    let snapshot: String = row.get(4);
+    Ok(Session {
+        id: row.get(0),
+        started_at: row.get(1),
+        ended_at: row.get(2),
+        mode: row.get(3),
+        config_snapshot: serde_json::from_str(&snapshot)?,
+        git_rev: row.get(5),
+    })
 }
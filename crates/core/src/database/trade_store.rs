@@ -0,0 +1,231 @@
+//! Postgres Trade Store
+//!
+//! Keeps the trade history, the opportunities that reached execution and
+//! risk events in Postgres, so they can be queried without parsing the
+//! JSONL history files. The history file stays the record of truth: the
+//! store is a [`HistorySink`] fed by [`HistoryRecorder`], and a write that
+//! fails is logged and dropped rather than holding up the trading loop.
+//!
+//! [`HistoryRecorder`]: crate::history::HistoryRecorder
+
+use crate::events::TradingEvent;
+use crate::history::{HistorySink, TradeRecord};
+use crate::types::ArbitrageOpportunity;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::{ManagerConfig, Pool, RecyclingMethod, Runtime};
+use tokio_postgres::NoTls;
+use uuid::Uuid;
+
+/// Schema of the store, applied by [`TradeStore::migrate`]
+const SCHEMA: &str = include_str!("../../../../migrations/003_create_trade_store.sql");
+
+#[derive(Clone)]
+pub struct TradeStore {
+    pool: Pool,
+    session_id: String,
+}
+
+impl TradeStore {
+    pub async fn new(database_url: &str, session_id: &str) -> Result<Self> {
+        let pg_config: tokio_postgres::Config = database_url.parse()?;
+        let mgr_config = ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        };
+        let mgr = deadpool_postgres::Manager::from_config(pg_config, NoTls, mgr_config);
+        let pool = Pool::builder(mgr)
+            .max_size(8)
+            .runtime(Runtime::Tokio1)
+            .build()?;
+        Ok(Self {
+            pool,
+            session_id: session_id.to_string(),
+        })
+    }
+
+    /// Create the store's tables and indexes if they do not exist yet
+    pub async fn migrate(&self) -> Result<()> {
+        let client = self.pool.get().await?;
+        client.batch_execute(SCHEMA).await?;
+        Ok(())
+    }
+
+    pub async fn insert_trade_record(&self, record: &TradeRecord) -> Result<()> {
+        let client = self.pool.get().await?;
+        let time = DateTime::parse_from_rfc3339(&record.timestamp)
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+        let failure_kind = record
+            .failure_kind
+            .and_then(|kind| serde_json::to_value(kind).ok())
+            .and_then(|kind| kind.as_str().map(str::to_string));
+        client
+            .execute(
+                "INSERT INTO trade_records
+            (id, time, session_id, trade_type, pair, buy_dex, sell_dex, size_usd, profit_usd,
+             profit_pct, signature, success, error, failure_kind, commitment, source, record)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, CAST($8 AS TEXT)::NUMERIC, CAST($9 AS TEXT)::NUMERIC,
+             CAST($10 AS TEXT)::NUMERIC, $11, $12, $13, $14, $15, $16, CAST($17 AS TEXT)::jsonb)
+            ON CONFLICT (id) DO NOTHING",
+                &[
+                    &record.id.unwrap_or_else(Uuid::new_v4),
+                    &time,
+                    &record.session_id,
+                    &record.trade_type,
+                    &record.pair,
+                    &record.buy_dex,
+                    &record.sell_dex,
+                    &record.size_usd,
+                    &record.profit_usd,
+                    &record.profit_pct,
+                    &record.tx_signature,
+                    &record.success,
+                    &record.error,
+                    &failure_kind,
+                    &record.commitment,
+                    &record.source,
+                    &serde_json::to_string(record)?,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn insert_opportunity(&self, opp: &ArbitrageOpportunity) -> Result<()> {
+        let client = self.pool.get().await?;
+        client
+            .execute(
+                "INSERT INTO opportunity_records
+            (id, detected_at, pair, buy_dex, sell_dex, buy_price, sell_price, gross_profit_pct,
+             net_profit_pct, source)
+            VALUES ($1, $2, $3, $4, $5, CAST($6 AS TEXT)::NUMERIC, CAST($7 AS TEXT)::NUMERIC,
+             CAST($8 AS TEXT)::NUMERIC, CAST($9 AS TEXT)::NUMERIC, $10)
+            ON CONFLICT (id) DO NOTHING",
+                &[
+                    &opp.id,
+                    &opp.detected_at,
+                    &opp.pair.symbol(),
+                    &opp.buy_dex.display_name(),
+                    &opp.sell_dex.display_name(),
+                    &opp.buy_price.to_string(),
+                    &opp.sell_price.to_string(),
+                    &opp.gross_profit_pct.to_string(),
+                    &opp.net_profit_pct.to_string(),
+                    &opp.source_label(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Record `event` if it is a risk event; other events are ignored
+    pub async fn insert_risk_event(&self, event: &TradingEvent) -> Result<()> {
+        let Some(kind) = risk_event_kind(event) else {
+            return Ok(());
+        };
+        // Events serialize as `{"Variant": {...fields}}`
+        let detail = match serde_json::to_value(event)? {
+            serde_json::Value::Object(mut variant) => variant.remove(kind).unwrap_or_default(),
+            other => other,
+        };
+        let client = self.pool.get().await?;
+        client
+            .execute(
+                "INSERT INTO risk_events (time, session_id, kind, detail)
+            VALUES ($1, $2, $3, CAST($4 AS TEXT)::jsonb)",
+                &[&Utc::now(), &self.session_id, &kind, &detail.to_string()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Trade records between `from` and `to`, newest first
+    pub async fn list_trade_records(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TradeRecord>> {
+        let client = self.pool.get().await?;
+        let rows = client
+            .query(
+                "SELECT record::text FROM trade_records
+            WHERE ($1::TIMESTAMPTZ IS NULL OR time >= $1) AND ($2::TIMESTAMPTZ IS NULL OR time < $2)
+            ORDER BY time DESC LIMIT $3 OFFSET $4",
+                &[&from, &to, &limit, &offset],
+            )
+            .await?;
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(row.get::<_, &str>(0))?))
+            .collect()
+    }
+}
+
+impl HistorySink for TradeStore {
+    fn record(&self, record: &TradeRecord) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let store = self.clone();
+        let record = record.clone();
+        runtime.spawn(async move {
+            if let Err(e) = store.insert_trade_record(&record).await {
+                tracing::warn!("Failed to write trade record to the trade store: {}", e);
+            }
+        });
+    }
+}
+
+/// Name a risk event is stored under, `None` for other events
+pub fn risk_event_kind(event: &TradingEvent) -> Option<&'static str> {
+    Some(match event {
+        TradingEvent::CircuitBreakerStateChanged { .. } => "CircuitBreakerStateChanged",
+        TradingEvent::RiskLimitBreached { .. } => "RiskLimitBreached",
+        TradingEvent::CanaryStarted { .. } => "CanaryStarted",
+        TradingEvent::CanaryCompleted { .. } => "CanaryCompleted",
+        TradingEvent::UnwindStarted { .. } => "UnwindStarted",
+        TradingEvent::UnwindCompleted { .. } => "UnwindCompleted",
+        TradingEvent::TradeRejected { .. } => "TradeRejected",
+        TradingEvent::TradeStuck { .. } => "TradeStuck",
+        TradingEvent::ChainReorg { .. } => "ChainReorg",
+        TradingEvent::EmergencyStop { .. } => "EmergencyStop",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_is_idempotent() {
+        for statement in SCHEMA.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let statement = statement
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if statement.trim().is_empty() {
+                continue;
+            }
+            assert!(statement.contains("IF NOT EXISTS"), "not idempotent: {}", statement);
+        }
+    }
+
+    #[test]
+    fn test_only_risk_events_are_stored() {
+        let breach = TradingEvent::RiskLimitBreached {
+            limit_type: "daily_loss".to_string(),
+            current: 120.0,
+            max: 100.0,
+        };
+        assert_eq!(risk_event_kind(&breach), Some("RiskLimitBreached"));
+        // The kind is the variant's serialized name, which the detail is taken from
+        assert!(serde_json::to_value(&breach).unwrap().get("RiskLimitBreached").is_some());
+        assert_eq!(
+            risk_event_kind(&TradingEvent::SystemStarted { mode: "live".to_string() }),
+            None
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Provider Conformance
+//!
+//! A shared test harness every `DexProvider`, built-in or plugin, is expected
+//! to pass. The provider is pointed at a local mock HTTP server and checked
+//! for:
+//!
+//! - pair normalization: prices come back for the pair as requested, quote
+//!   per base, even when the venue lists it the other way round
+//! - staleness stamping: `timestamp` is taken when the response arrives
+//! - error typing: upstream failures surface as fetch errors, not panics or
+//!   configuration errors
+//! - timeouts: a stalled upstream fails the request within a bound
+//! - `subscribe()` semantics: the stream survives upstream errors, delivers
+//!   prices once the upstream recovers, and stops polling when dropped
+//!
+//! A plugin implements [`ConformanceTarget`] in its tests and asserts on
+//! [`run_conformance`]'s report.
+
+use crate::{ArbitrageError, DexType, TokenPair};
+use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::error::TryRecvError;
+
+use super::DexProvider;
+
+/// What the mock server answers every request with
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    /// Wait before answering, to simulate a slow or stalled upstream
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn status(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            ..Self::ok(body)
+        }
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// Minimal HTTP/1.1 server on localhost answering every path with the
+/// current [`MockResponse`]
+pub struct MockServer {
+    url: String,
+    response: Arc<Mutex<MockResponse>>,
+    requests: Arc<AtomicUsize>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl MockServer {
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let response = Arc::new(Mutex::new(MockResponse::status(503, "")));
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let handle = {
+            let response = response.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    let response = response.clone();
+                    let requests = requests.clone();
+                    tokio::spawn(async move {
+                        // Read the request head; bodies are not inspected
+                        let mut head = Vec::new();
+                        let mut buf = [0u8; 1024];
+                        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match socket.read(&mut buf).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => head.extend_from_slice(&buf[..n]),
+                            }
+                        }
+                        requests.fetch_add(1, Ordering::SeqCst);
+
+                        let MockResponse { status, body, delay } =
+                            response.lock().unwrap().clone();
+                        tokio::time::sleep(delay).await;
+                        let reply = format!(
+                            "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            status,
+                            body.len(),
+                            body
+                        );
+                        let _ = socket.write_all(reply.as_bytes()).await;
+                        let _ = socket.shutdown().await;
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            url,
+            response,
+            requests,
+            handle,
+        })
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:40123`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn respond(&self, response: MockResponse) {
+        *self.response.lock().unwrap() = response;
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// A provider under test and the upstream format it speaks
+pub trait ConformanceTarget: Send + Sync {
+    /// The provider, configured to call `base_url` instead of its real API
+    fn provider(&self, base_url: &str) -> Arc<dyn DexProvider>;
+
+    /// Upstream response quoting `pair` at `price` (quote per base)
+    fn price_body(&self, pair: &TokenPair, price: f64) -> String;
+
+    /// Upstream response listing `pair` in the reverse orientation (quote
+    /// first) at `price` quote per base, for venues that list pairs either
+    /// way. `None` skips the reversed-pair check.
+    fn reversed_price_body(&self, _pair: &TokenPair, _price: f64) -> Option<String> {
+        None
+    }
+}
+
+/// Bounds the harness holds providers to
+#[derive(Debug, Clone)]
+pub struct ConformanceConfig {
+    pub pair: TokenPair,
+    /// A pair the provider is not asked for, quoted to test "pair missing"
+    pub other_pair: TokenPair,
+    pub price: f64,
+    /// Largest allowed gap between the mid price and the quoted price (%)
+    pub max_mid_deviation_pct: f64,
+    /// A request against a stalled upstream must fail within this
+    pub max_request_time: Duration,
+    /// First streamed price must arrive within this of the upstream recovering
+    pub stream_timeout: Duration,
+    /// How long a dropped stream may keep its last poll in flight
+    pub stream_settle: Duration,
+}
+
+impl Default for ConformanceConfig {
+    fn default() -> Self {
+        Self {
+            pair: TokenPair::new("SOL", "USDC"),
+            other_pair: TokenPair::new("BONK", "USDC"),
+            price: 150.0,
+            max_mid_deviation_pct: 1.0,
+            max_request_time: Duration::from_secs(2),
+            stream_timeout: Duration::from_secs(5),
+            stream_settle: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Whether an error is one a price fetch may fail with: network, HTTP,
+/// decoding, rate limit or missing price. Configuration and unknown errors
+/// are not, since callers treat those as permanent.
+pub fn is_fetch_error(error: &ArbitrageError) -> bool {
+    matches!(
+        error,
+        ArbitrageError::DexConnection(_)
+            | ArbitrageError::RpcError(_)
+            | ArbitrageError::RpcTimeout { .. }
+            | ArbitrageError::RpcRateLimit(_)
+            | ArbitrageError::PriceFetch(_)
+            | ArbitrageError::PriceFetchDetailed { .. }
+            | ArbitrageError::PriceNotAvailable(_)
+            | ArbitrageError::Http(_)
+            | ArbitrageError::Serialization(_)
+            | ArbitrageError::RateLimited(_)
+    )
+}
+
+/// Outcome of one conformance check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConformanceReport {
+    pub dex: Option<DexType>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.error.is_none())
+    }
+
+    /// Panic with every failed check, for use in tests
+    pub fn assert_passed(&self) {
+        assert!(self.passed(), "{}", self);
+    }
+}
+
+impl std::fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.dex {
+            Some(dex) => writeln!(f, "{} provider conformance:", dex)?,
+            None => writeln!(f, "provider conformance:")?,
+        }
+        for check in &self.checks {
+            match &check.error {
+                None => writeln!(f, "  ok   {}", check.name)?,
+                Some(e) => writeln!(f, "  FAIL {}: {}", check.name, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run every check against `target`, each with a fresh mock server
+pub async fn run_conformance<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+) -> ConformanceReport {
+    let mut report = ConformanceReport {
+        dex: None,
+        checks: Vec::new(),
+    };
+
+    macro_rules! check {
+        ($name:literal, $check:ident) => {{
+            let error = match MockServer::start().await {
+                Ok(server) => {
+                    let provider = target.provider(server.url());
+                    report.dex = Some(provider.dex_type());
+                    $check(target, config, &server, provider).await.err()
+                }
+                Err(e) => Some(format!("mock server failed to start: {}", e)),
+            };
+            report.checks.push(CheckResult { name: $name, error });
+        }};
+    }
+
+    check!("pair_normalization", check_pair_normalization);
+    check!("staleness_stamping", check_staleness_stamping);
+    check!("error_typing", check_error_typing);
+    check!("timeout", check_timeout);
+    check!("subscribe_stream", check_subscribe_stream);
+    report
+}
+
+type CheckOutcome = Result<(), String>;
+
+fn check_price(
+    price: &crate::PriceData,
+    provider: &dyn DexProvider,
+    config: &ConformanceConfig,
+) -> CheckOutcome {
+    if price.pair != config.pair {
+        return Err(format!("asked for {}, got {}", config.pair, price.pair));
+    }
+    if price.dex != provider.dex_type() {
+        return Err(format!(
+            "stamped {} on a {} price",
+            price.dex,
+            provider.dex_type()
+        ));
+    }
+    if price.bid <= rust_decimal::Decimal::ZERO || price.bid > price.ask {
+        return Err(format!("bad quote: bid {} ask {}", price.bid, price.ask));
+    }
+    let mid = price.mid_price.to_f64().unwrap_or(0.0);
+    let deviation_pct = (mid - config.price).abs() / config.price * 100.0;
+    if deviation_pct > config.max_mid_deviation_pct {
+        return Err(format!(
+            "mid {} is {:.2}% away from the quoted {}",
+            mid, deviation_pct, config.price
+        ));
+    }
+    Ok(())
+}
+
+async fn check_pair_normalization<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+    server: &MockServer,
+    provider: Arc<dyn DexProvider>,
+) -> CheckOutcome {
+    server.respond(MockResponse::ok(target.price_body(&config.pair, config.price)));
+    let price = provider
+        .get_price(&config.pair)
+        .await
+        .map_err(|e| format!("get_price failed: {}", e))?;
+    check_price(&price, provider.as_ref(), config)?;
+
+    if let Some(body) = target.reversed_price_body(&config.pair, config.price) {
+        server.respond(MockResponse::ok(body));
+        let price = provider
+            .get_price(&config.pair)
+            .await
+            .map_err(|e| format!("get_price on a reversed listing failed: {}", e))?;
+        check_price(&price, provider.as_ref(), config)
+            .map_err(|e| format!("reversed listing: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn check_staleness_stamping<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+    server: &MockServer,
+    provider: Arc<dyn DexProvider>,
+) -> CheckOutcome {
+    let delay = Duration::from_millis(100);
+    server.respond(MockResponse::ok(target.price_body(&config.pair, config.price)).delayed(delay));
+
+    let before = Utc::now();
+    let price = provider
+        .get_price(&config.pair)
+        .await
+        .map_err(|e| format!("get_price failed: {}", e))?;
+    let after = Utc::now();
+
+    let earliest = before + chrono::Duration::from_std(delay).unwrap_or_default();
+    if price.timestamp < earliest || price.timestamp > after {
+        return Err(format!(
+            "timestamp {} is not the response time (between {} and {})",
+            price.timestamp, earliest, after
+        ));
+    }
+    Ok(())
+}
+
+async fn check_error_typing<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+    server: &MockServer,
+    provider: Arc<dyn DexProvider>,
+) -> CheckOutcome {
+    let cases = [
+        ("HTTP 500", MockResponse::status(500, "internal error")),
+        ("malformed body", MockResponse::ok("{\"unexpected\": [")),
+        (
+            "pair missing",
+            MockResponse::ok(target.price_body(&config.other_pair, config.price)),
+        ),
+    ];
+    for (case, response) in cases {
+        server.respond(response);
+        match provider.get_price(&config.pair).await {
+            Ok(price) => return Err(format!("{}: returned a price {}", case, price.mid_price)),
+            Err(e) if !is_fetch_error(&e) => {
+                return Err(format!("{}: not a fetch error: {:?}", case, e))
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(())
+}
+
+async fn check_timeout<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+    server: &MockServer,
+    provider: Arc<dyn DexProvider>,
+) -> CheckOutcome {
+    let stall = config.max_request_time * 2;
+    server.respond(MockResponse::ok(target.price_body(&config.pair, config.price)).delayed(stall));
+
+    let start = Instant::now();
+    let limit = config.max_request_time + Duration::from_millis(500);
+    let result = tokio::time::timeout(limit, provider.get_price(&config.pair)).await;
+    match result {
+        Err(_) => Err(format!(
+            "still waiting after {:?} on a stalled upstream",
+            limit
+        )),
+        Ok(Ok(_)) => Err("returned a price from a stalled upstream".to_string()),
+        Ok(Err(e)) if !is_fetch_error(&e) => Err(format!("not a fetch error: {:?}", e)),
+        Ok(Err(_)) if start.elapsed() > config.max_request_time => Err(format!(
+            "took {:?}, limit {:?}",
+            start.elapsed(),
+            config.max_request_time
+        )),
+        Ok(Err(_)) => Ok(()),
+    }
+}
+
+async fn check_subscribe_stream<T: ConformanceTarget + ?Sized>(
+    target: &T,
+    config: &ConformanceConfig,
+    server: &MockServer,
+    provider: Arc<dyn DexProvider>,
+) -> CheckOutcome {
+    server.respond(MockResponse::status(500, "internal error"));
+    let mut stream = provider
+        .subscribe(vec![config.pair.clone()])
+        .await
+        .map_err(|e| format!("subscribe failed: {}", e))?;
+
+    // Upstream errors must neither end the stream nor produce prices
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    match stream.try_recv() {
+        Err(TryRecvError::Empty) => {}
+        Err(TryRecvError::Disconnected) => {
+            return Err("stream closed on an upstream error".to_string())
+        }
+        Ok(price) => {
+            return Err(format!(
+                "streamed a price {} while upstream was failing",
+                price.mid_price
+            ))
+        }
+    }
+    if server.requests() == 0 {
+        return Err("stream never polled the upstream".to_string());
+    }
+
+    server.respond(MockResponse::ok(target.price_body(&config.pair, config.price)));
+    match tokio::time::timeout(config.stream_timeout, stream.recv()).await {
+        Err(_) => return Err(format!("no price within {:?}", config.stream_timeout)),
+        Ok(None) => return Err("stream closed before delivering a price".to_string()),
+        Ok(Some(price)) => {
+            check_price(&price, provider.as_ref(), config).map_err(|e| format!("streamed: {}", e))?
+        }
+    }
+
+    // Dropping the receiver must stop the poller
+    drop(stream);
+    tokio::time::sleep(config.stream_settle).await;
+    let polled = server.requests();
+    tokio::time::sleep(config.stream_settle).await;
+    if server.requests() > polled {
+        return Err(format!(
+            "kept polling after the stream was dropped ({} more requests)",
+            server.requests() - polled
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::PriceStream;
+    use async_trait::async_trait;
+    use crate::{ArbitrageResult, PriceData};
+    use rust_decimal::Decimal;
+
+    /// Answers every request with its configured price and never touches
+    /// the network: passes everything but the timeout and stream polling
+    struct StaticProvider;
+
+    #[async_trait]
+    impl DexProvider for StaticProvider {
+        fn dex_type(&self) -> DexType {
+            DexType::Raydium
+        }
+
+        async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
+            Ok(PriceData::new(
+                DexType::Raydium,
+                pair.clone(),
+                Decimal::from(150),
+                Decimal::from(150),
+            ))
+        }
+
+        async fn subscribe(&self, _pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
+            Err(ArbitrageError::Unknown("no stream".to_string()))
+        }
+
+        async fn health_check(&self) -> ArbitrageResult<bool> {
+            Ok(true)
+        }
+    }
+
+    struct StaticTarget;
+
+    impl ConformanceTarget for StaticTarget {
+        fn provider(&self, _base_url: &str) -> Arc<dyn DexProvider> {
+            Arc::new(StaticProvider)
+        }
+
+        fn price_body(&self, _pair: &TokenPair, price: f64) -> String {
+            price.to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_harness_flags_nonconforming_provider() {
+        let report = run_conformance(&StaticTarget, &ConformanceConfig::default()).await;
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| c.error.is_some())
+            .map(|c| c.name)
+            .collect();
+        // It never fetches: stamped before the response, ignores upstream
+        // errors and stalls, and cannot stream
+        assert_eq!(
+            failed,
+            vec!["staleness_stamping", "error_typing", "timeout", "subscribe_stream"]
+        );
+        assert!(!report.passed());
+        assert!(report.to_string().contains("ok   pair_normalization"));
+    }
+}
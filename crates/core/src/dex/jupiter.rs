@@ -7,9 +7,11 @@ use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 use super::{DexProvider, PriceStream};
+use crate::rate_limiter::RateLimiter;
 use crate::{ArbitrageError, ArbitrageResult, DexType, PriceData, TokenPair};
 
 const JUPITER_PRICE_API: &str = "https://price.jup.ag/v6/price";
@@ -17,8 +19,12 @@ const JUPITER_PRICE_API: &str = "https://price.jup.ag/v6/price";
 /// Jupiter DEX provider implementation
 pub struct JupiterProvider {
     client: reqwest::Client,
+    /// API endpoint, overridable for tests
+    api_url: String,
     /// Token symbol to mint address mapping
     token_mints: HashMap<String, String>,
+    /// Limiter every request waits on, if any
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -71,10 +77,38 @@ impl JupiterProvider {
             "ORCA".to_string(),
             "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE".to_string(),
         );
+        token_mints.insert(
+            "mSOL".to_string(),
+            "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So".to_string(),
+        );
+        token_mints.insert(
+            "jitoSOL".to_string(),
+            "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn".to_string(),
+        );
+        token_mints.insert(
+            "bSOL".to_string(),
+            "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1".to_string(),
+        );
 
         Self {
             client: crate::http::pool::create_optimized_client(),
+            api_url: JUPITER_PRICE_API.to_string(),
             token_mints,
+            rate_limiter: None,
+        }
+    }
+
+    /// Provider whose requests all wait on `limiter`
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Provider that calls `api_url` instead of the public Jupiter API
+    pub fn with_api_url(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            ..Self::new()
         }
     }
 
@@ -112,9 +146,12 @@ impl DexProvider for JupiterProvider {
 
         let url = format!(
             "{}?ids={}&vsToken={}",
-            JUPITER_PRICE_API, base_mint, quote_mint
+            self.api_url, base_mint, quote_mint
         );
 
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
         let response: JupiterPriceResponse = self.client.get(&url).send().await?.json().await?;
 
         let token_price = response
@@ -136,7 +173,9 @@ impl DexProvider for JupiterProvider {
     async fn subscribe(&self, pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
         let (tx, rx) = mpsc::channel(100);
         let client = self.client.clone();
+        let api_url = self.api_url.clone();
         let token_mints = self.token_mints.clone();
+        let rate_limiter = self.rate_limiter.clone();
 
         tokio::spawn(async move {
             loop {
@@ -152,9 +191,12 @@ impl DexProvider for JupiterProvider {
 
                     let url = format!(
                         "{}?ids={}&vsToken={}",
-                        JUPITER_PRICE_API, base_mint, quote_mint
+                        api_url, base_mint, quote_mint
                     );
 
+                    if let Some(limiter) = &rate_limiter {
+                        limiter.acquire().await;
+                    }
                     if let Ok(response) = client.get(&url).send().await {
                         if let Ok(data) = response.json::<JupiterPriceResponse>().await {
                             if let Some(token_price) = data.data.get(base_mint) {
@@ -186,7 +228,7 @@ impl DexProvider for JupiterProvider {
     async fn health_check(&self) -> ArbitrageResult<bool> {
         let url = format!(
             "{}?ids=So11111111111111111111111111111111111111112",
-            JUPITER_PRICE_API
+            self.api_url
         );
         let response = self.client.get(&url).send().await?;
         Ok(response.status().is_success())
@@ -196,6 +238,34 @@ impl DexProvider for JupiterProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::conformance::{run_conformance, ConformanceConfig, ConformanceTarget};
+    use std::sync::Arc;
+
+    struct Jupiter;
+
+    impl ConformanceTarget for Jupiter {
+        fn provider(&self, base_url: &str) -> Arc<dyn DexProvider> {
+            Arc::new(JupiterProvider::with_api_url(base_url))
+        }
+
+        fn price_body(&self, pair: &TokenPair, price: f64) -> String {
+            let mint = JupiterProvider::new()
+                .get_mint(&pair.base)
+                .cloned()
+                .unwrap_or_default();
+            serde_json::json!({
+                "data": { mint.clone(): { "id": mint, "mintSymbol": pair.base, "price": price } }
+            })
+            .to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jupiter_conformance() {
+        run_conformance(&Jupiter, &ConformanceConfig::default())
+            .await
+            .assert_passed();
+    }
 
     #[tokio::test]
     #[ignore] // Requires network access - run with: cargo test -- --ignored
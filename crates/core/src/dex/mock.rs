@@ -0,0 +1,325 @@
+//! Scriptable Mock DEX
+//!
+//! A `DexProvider` for tests that plays back a JSON scenario of per-venue
+//! price steps (see `crates/bot/tests/scenarios`) instead of calling a venue.
+//! Providers built from one scenario advance together on a shared [`MockClock`].
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{DexProvider, PriceStream};
+use crate::{ArbitrageError, ArbitrageResult, DexType, PriceData, TokenPair};
+
+/// How often a subscription checks the clock for a new tick
+const SUBSCRIBE_POLL: Duration = Duration::from_millis(5);
+
+/// One scripted segment of a price series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Step {
+    /// Keep the current price
+    Hold { ticks: usize },
+    /// Move linearly to `to`, reaching it on the last tick
+    Ramp { to: Decimal, ticks: usize },
+    /// Jump by `pct` percent for `ticks`, then return to the previous price
+    Spike { pct: Decimal, ticks: usize },
+    /// Quote an explicit bid and ask, which may cross
+    Crossed { bid: Decimal, ask: Decimal, ticks: usize },
+    /// Fail every request
+    Outage { ticks: usize },
+}
+
+/// Scripted prices of one pair on one venue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Series {
+    pub dex: DexType,
+    /// "BASE/QUOTE"
+    pub pair: String,
+    /// Starting mid price
+    pub price: Decimal,
+    /// Bid/ask spread around the mid price, in percent
+    #[serde(default)]
+    pub spread_pct: Decimal,
+    pub steps: Vec<Step>,
+}
+
+/// What a venue quotes on one tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+    Quote { bid: Decimal, ask: Decimal },
+    Outage,
+}
+
+impl Series {
+    pub fn token_pair(&self) -> ArbitrageResult<TokenPair> {
+        match self.pair.split_once('/') {
+            Some((base, quote)) if !base.is_empty() && !quote.is_empty() => {
+                Ok(TokenPair::new(base, quote))
+            }
+            _ => Err(ArbitrageError::Config(format!(
+                "scenario pair must look like BASE/QUOTE, got {}",
+                self.pair
+            ))),
+        }
+    }
+
+    /// Expand the steps into one frame per tick
+    pub fn frames(&self) -> Vec<Frame> {
+        let half_spread = self.spread_pct / Decimal::from(200);
+        let quote = |mid: Decimal| Frame::Quote {
+            bid: mid * (Decimal::ONE - half_spread),
+            ask: mid * (Decimal::ONE + half_spread),
+        };
+        let mut price = self.price;
+        let mut frames = Vec::new();
+        for step in &self.steps {
+            match *step {
+                Step::Hold { ticks } => frames.extend((0..ticks).map(|_| quote(price))),
+                Step::Ramp { to, ticks } => {
+                    let from = price;
+                    for i in 1..=ticks {
+                        price = from + (to - from) * Decimal::from(i) / Decimal::from(ticks);
+                        frames.push(quote(price));
+                    }
+                }
+                Step::Spike { pct, ticks } => {
+                    let spiked = price * (Decimal::ONE + pct / Decimal::from(100));
+                    frames.extend((0..ticks).map(|_| quote(spiked)));
+                }
+                Step::Crossed { bid, ask, ticks } => {
+                    frames.extend((0..ticks).map(|_| Frame::Quote { bid, ask }));
+                }
+                Step::Outage { ticks } => frames.extend((0..ticks).map(|_| Frame::Outage)),
+            }
+        }
+        if frames.is_empty() {
+            frames.push(quote(price));
+        }
+        frames
+    }
+}
+
+/// A named set of series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub series: Vec<Series>,
+}
+
+impl Scenario {
+    /// Load a scenario from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> ArbitrageResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(json: &str) -> ArbitrageResult<Self> {
+        let scenario: Self = serde_json::from_str(json)?;
+        for series in &scenario.series {
+            series.token_pair()?;
+        }
+        Ok(scenario)
+    }
+
+    /// Ticks until every series has played all of its steps
+    pub fn ticks(&self) -> usize {
+        self.series.iter().map(|s| s.frames().len()).max().unwrap_or(0)
+    }
+
+    /// Pairs quoted anywhere in the scenario
+    pub fn pairs(&self) -> Vec<TokenPair> {
+        let mut pairs: Vec<TokenPair> = Vec::new();
+        for pair in self.series.iter().filter_map(|s| s.token_pair().ok()) {
+            if !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+        }
+        pairs
+    }
+
+    /// One provider per venue, all driven by `clock`
+    pub fn providers(&self, clock: &MockClock) -> Vec<Arc<MockDexProvider>> {
+        let mut venues: Vec<MockDexProvider> = Vec::new();
+        for series in &self.series {
+            let Ok(pair) = series.token_pair() else {
+                continue;
+            };
+            let index = match venues.iter().position(|p| p.dex == series.dex) {
+                Some(index) => index,
+                None => {
+                    venues.push(MockDexProvider::new(series.dex, clock.clone()));
+                    venues.len() - 1
+                }
+            };
+            venues[index].frames.insert(pair, series.frames());
+        }
+        venues.into_iter().map(Arc::new).collect()
+    }
+}
+
+/// Tick counter shared by every provider of a scenario
+#[derive(Debug, Clone, Default)]
+pub struct MockClock(Arc<AtomicUsize>);
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tick(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Move to the next tick, returning it
+    pub fn advance(&self) -> usize {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub fn set(&self, tick: usize) {
+        self.0.store(tick, Ordering::SeqCst);
+    }
+}
+
+/// A venue playing back scripted frames
+#[derive(Debug, Clone)]
+pub struct MockDexProvider {
+    dex: DexType,
+    clock: MockClock,
+    frames: HashMap<TokenPair, Vec<Frame>>,
+}
+
+impl MockDexProvider {
+    pub fn new(dex: DexType, clock: MockClock) -> Self {
+        Self {
+            dex,
+            clock,
+            frames: HashMap::new(),
+        }
+    }
+
+    /// Script `frames` for `pair`
+    pub fn with_frames(mut self, pair: TokenPair, frames: Vec<Frame>) -> Self {
+        self.frames.insert(pair, frames);
+        self
+    }
+
+    /// The frame `pair` shows at the current tick
+    pub fn frame(&self, pair: &TokenPair) -> Option<Frame> {
+        let frames = self.frames.get(pair)?;
+        frames
+            .get(self.clock.tick())
+            .or_else(|| frames.last())
+            .copied()
+    }
+}
+
+#[async_trait]
+impl DexProvider for MockDexProvider {
+    fn dex_type(&self) -> DexType {
+        self.dex
+    }
+
+    async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
+        match self.frame(pair) {
+            Some(Frame::Quote { bid, ask }) => Ok(PriceData::new(self.dex, pair.clone(), bid, ask)),
+            Some(Frame::Outage) => Err(ArbitrageError::DexConnection(format!(
+                "{:?} scripted outage at tick {}",
+                self.dex,
+                self.clock.tick()
+            ))),
+            None => Err(ArbitrageError::PriceNotAvailable(pair.to_string())),
+        }
+    }
+
+    /// Sends every pair's price whenever the clock moves to a new tick,
+    /// skipping pairs in an outage
+    async fn subscribe(&self, pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
+        let (tx, rx) = mpsc::channel(100);
+        let provider = self.clone();
+        tokio::spawn(async move {
+            let mut last_tick = None;
+            while !tx.is_closed() {
+                let tick = provider.clock.tick();
+                if last_tick != Some(tick) {
+                    last_tick = Some(tick);
+                    for pair in &pairs {
+                        if let Ok(price) = provider.get_price(pair).await {
+                            if tx.send(price).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+                tokio::time::sleep(SUBSCRIBE_POLL).await;
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn health_check(&self) -> ArbitrageResult<bool> {
+        Ok(!self.frames.keys().any(|pair| self.frame(pair) == Some(Frame::Outage)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scripted_series_plays_back_per_tick() {
+        let scenario = Scenario::parse(
+            r#"{
+                "name": "all steps",
+                "series": [{
+                    "dex": "orca", "pair": "SOL/USDC", "price": 100, "spread_pct": 2,
+                    "steps": [
+                        { "kind": "ramp", "to": 110, "ticks": 2 },
+                        { "kind": "spike", "pct": -10, "ticks": 1 },
+                        { "kind": "crossed", "bid": 112, "ask": 111, "ticks": 1 },
+                        { "kind": "outage", "ticks": 1 },
+                        { "kind": "hold", "ticks": 1 }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(scenario.ticks(), 6);
+
+        let clock = MockClock::new();
+        let providers = scenario.providers(&clock);
+        assert_eq!(providers.len(), 1);
+        let orca = &providers[0];
+        let pair = TokenPair::new("SOL", "USDC");
+        let mid = |price: PriceData| price.mid_price;
+
+        assert_eq!(mid(orca.get_price(&pair).await.unwrap()), Decimal::from(105));
+        clock.advance();
+        let price = orca.get_price(&pair).await.unwrap();
+        assert_eq!((price.bid, price.ask), (Decimal::new(1089, 1), Decimal::new(1111, 1)));
+        clock.advance();
+        assert_eq!(mid(orca.get_price(&pair).await.unwrap()), Decimal::from(99));
+        clock.advance();
+        let crossed = orca.get_price(&pair).await.unwrap();
+        assert!(crossed.bid > crossed.ask);
+        clock.advance();
+        assert!(matches!(
+            orca.get_price(&pair).await,
+            Err(ArbitrageError::DexConnection(_))
+        ));
+        assert!(!orca.health_check().await.unwrap());
+        // The spike is over: back to the ramp's end, and held past the script
+        clock.set(100);
+        assert_eq!(mid(orca.get_price(&pair).await.unwrap()), Decimal::from(110));
+        assert!(orca.health_check().await.unwrap());
+        assert!(orca.get_price(&TokenPair::new("BONK", "USDC")).await.is_err());
+
+        let bad_pair = r#"{"name": "x", "series": [{"dex": "orca", "pair": "SOL", "price": 1, "steps": []}]}"#;
+        assert!(Scenario::parse(bad_pair).is_err());
+    }
+}
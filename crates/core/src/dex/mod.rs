@@ -3,8 +3,11 @@
 //! This module contains the trait definition and implementations for
 //! connecting to various Solana DEXs and fetching price data.
 
+pub mod conformance;
 #[cfg(feature = "http")]
 pub mod jupiter;
+#[cfg(feature = "testing")]
+pub mod mock;
 #[cfg(feature = "http")]
 pub mod orca;
 #[cfg(feature = "http")]
@@ -8,6 +8,7 @@ use serde::Deserialize;
 use tokio::sync::mpsc;
 
 use super::{DexProvider, PriceStream};
+use crate::discovery::{DiscoveredPool, PoolToken};
 use crate::{ArbitrageError, ArbitrageResult, DexType, PriceData, TokenPair};
 
 const ORCA_WHIRLPOOL_API: &str = "https://api.mainnet.orca.so/v1/whirlpool/list";
@@ -15,6 +16,8 @@ const ORCA_WHIRLPOOL_API: &str = "https://api.mainnet.orca.so/v1/whirlpool/list"
 /// Orca DEX provider implementation
 pub struct OrcaProvider {
     client: reqwest::Client,
+    /// API endpoint, overridable for tests
+    api_url: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +49,46 @@ impl OrcaProvider {
     pub fn new() -> Self {
         Self {
             client: crate::http::pool::create_optimized_client(),
+            api_url: ORCA_WHIRLPOOL_API.to_string(),
+        }
+    }
+
+    /// Every whirlpool Orca lists, for pool discovery
+    pub async fn list_pools(&self) -> ArbitrageResult<Vec<DiscoveredPool>> {
+        let response: OrcaWhirlpoolList = self
+            .client
+            .get(&self.api_url)
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(response
+            .whirlpools
+            .into_iter()
+            .map(|pool| DiscoveredPool {
+                dex: DexType::Orca,
+                address: pool.address,
+                token_a: PoolToken {
+                    symbol: pool.token_a.symbol,
+                    mint: pool.token_a.mint,
+                },
+                token_b: PoolToken {
+                    symbol: pool.token_b.symbol,
+                    mint: pool.token_b.mint,
+                },
+                liquidity_usd: pool
+                    .tvl
+                    .and_then(|tvl| Decimal::try_from(tvl).ok())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Provider that calls `api_url` instead of the public Orca API
+    pub fn with_api_url(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            ..Self::new()
         }
     }
 }
@@ -65,7 +108,7 @@ impl DexProvider for OrcaProvider {
     async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
         let response: OrcaWhirlpoolList = self
             .client
-            .get(ORCA_WHIRLPOOL_API)
+            .get(&self.api_url)
             .send()
             .await?
             .json()
@@ -110,10 +153,11 @@ impl DexProvider for OrcaProvider {
     async fn subscribe(&self, pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
         let (tx, rx) = mpsc::channel(100);
         let client = self.client.clone();
+        let api_url = self.api_url.clone();
 
         tokio::spawn(async move {
             loop {
-                if let Ok(response) = client.get(ORCA_WHIRLPOOL_API).send().await {
+                if let Ok(response) = client.get(&api_url).send().await {
                     if let Ok(data) = response.json::<OrcaWhirlpoolList>().await {
                         for pair in &pairs {
                             if let Some(whirlpool) = data.whirlpools.iter().find(|w| {
@@ -158,7 +202,49 @@ impl DexProvider for OrcaProvider {
     }
 
     async fn health_check(&self) -> ArbitrageResult<bool> {
-        let response = self.client.get(ORCA_WHIRLPOOL_API).send().await?;
+        let response = self.client.get(&self.api_url).send().await?;
         Ok(response.status().is_success())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::conformance::{run_conformance, ConformanceConfig, ConformanceTarget};
+    use std::sync::Arc;
+
+    struct Orca;
+
+    impl ConformanceTarget for Orca {
+        fn provider(&self, base_url: &str) -> Arc<dyn DexProvider> {
+            Arc::new(OrcaProvider::with_api_url(base_url))
+        }
+
+        fn price_body(&self, pair: &TokenPair, price: f64) -> String {
+            let token = |symbol: &str| serde_json::json!({ "mint": symbol, "symbol": symbol, "decimals": 6 });
+            serde_json::json!({
+                "whirlpools": [{
+                    "address": "pool",
+                    "tokenA": token(&pair.base),
+                    "tokenB": token(&pair.quote),
+                    "price": price,
+                    "volume24h": 1_000_000.0,
+                    "tvl": 5_000_000.0,
+                }]
+            })
+            .to_string()
+        }
+
+        fn reversed_price_body(&self, pair: &TokenPair, price: f64) -> Option<String> {
+            let reversed = TokenPair::new(pair.quote.clone(), pair.base.clone());
+            Some(self.price_body(&reversed, 1.0 / price))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orca_conformance() {
+        run_conformance(&Orca, &ConformanceConfig::default())
+            .await
+            .assert_passed();
+    }
+}
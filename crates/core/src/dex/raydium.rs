@@ -9,6 +9,7 @@ use serde::Deserialize;
 use tokio::sync::mpsc;
 
 use super::{DexProvider, PriceStream};
+use crate::discovery::{DiscoveredPool, PoolToken};
 use crate::{ArbitrageError, ArbitrageResult, DexType, PriceData, TokenPair};
 
 const RAYDIUM_API: &str = "https://api.raydium.io/v2/main/pairs";
@@ -16,6 +17,8 @@ const RAYDIUM_API: &str = "https://api.raydium.io/v2/main/pairs";
 /// Raydium DEX provider implementation
 pub struct RaydiumProvider {
     client: reqwest::Client,
+    /// API endpoint, overridable for tests
+    api_url: String,
 }
 
 #[allow(dead_code)]
@@ -36,11 +39,43 @@ impl RaydiumProvider {
     pub fn new() -> Self {
         Self {
             client: crate::http::pool::create_optimized_client(),
+            api_url: RAYDIUM_API.to_string(),
         }
     }
 
+    /// Provider that calls `api_url` instead of the public Raydium API
+    pub fn with_api_url(api_url: impl Into<String>) -> Self {
+        Self {
+            api_url: api_url.into(),
+            ..Self::new()
+        }
+    }
+
+    /// Every pair Raydium lists, for pool discovery
+    pub async fn list_pools(&self) -> ArbitrageResult<Vec<DiscoveredPool>> {
+        let pairs: Vec<RaydiumPair> = self.client.get(&self.api_url).send().await?.json().await?;
+        Ok(pairs
+            .into_iter()
+            .filter_map(|pair| {
+                let (base, quote) = Self::parse_pair_name(&pair.name)?;
+                Some(DiscoveredPool {
+                    dex: DexType::Raydium,
+                    address: pair.amm_id,
+                    token_a: PoolToken {
+                        symbol: base,
+                        mint: pair.base_mint,
+                    },
+                    token_b: PoolToken {
+                        symbol: quote,
+                        mint: pair.quote_mint,
+                    },
+                    liquidity_usd: Decimal::try_from(pair.liquidity).unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
     /// Parse a pair name into base and quote tokens
-    #[allow(dead_code)]
     fn parse_pair_name(name: &str) -> Option<(String, String)> {
         let parts: Vec<&str> = name.split('-').collect();
         if parts.len() == 2 {
@@ -64,7 +99,7 @@ impl DexProvider for RaydiumProvider {
     }
 
     async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
-        let pairs: Vec<RaydiumPair> = self.client.get(RAYDIUM_API).send().await?.json().await?;
+        let pairs: Vec<RaydiumPair> = self.client.get(&self.api_url).send().await?.json().await?;
 
         let target_name = format!("{}-{}", pair.base, pair.quote);
         let reverse_name = format!("{}-{}", pair.quote, pair.base);
@@ -100,10 +135,11 @@ impl DexProvider for RaydiumProvider {
     async fn subscribe(&self, pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
         let (tx, rx) = mpsc::channel(100);
         let client = self.client.clone();
+        let api_url = self.api_url.clone();
 
         tokio::spawn(async move {
             loop {
-                if let Ok(response) = client.get(RAYDIUM_API).send().await {
+                if let Ok(response) = client.get(&api_url).send().await {
                     if let Ok(all_pairs) = response.json::<Vec<RaydiumPair>>().await {
                         for pair in &pairs {
                             let target_name = format!("{}-{}", pair.base, pair.quote);
@@ -147,7 +183,48 @@ impl DexProvider for RaydiumProvider {
     }
 
     async fn health_check(&self) -> ArbitrageResult<bool> {
-        let response = self.client.get(RAYDIUM_API).send().await?;
+        let response = self.client.get(&self.api_url).send().await?;
         Ok(response.status().is_success())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::conformance::{run_conformance, ConformanceConfig, ConformanceTarget};
+    use std::sync::Arc;
+
+    struct Raydium;
+
+    impl ConformanceTarget for Raydium {
+        fn provider(&self, base_url: &str) -> Arc<dyn DexProvider> {
+            Arc::new(RaydiumProvider::with_api_url(base_url))
+        }
+
+        fn price_body(&self, pair: &TokenPair, price: f64) -> String {
+            serde_json::json!([{
+                "name": format!("{}-{}", pair.base, pair.quote),
+                "ammId": "amm",
+                "lpMint": "lp",
+                "baseMint": "base",
+                "quoteMint": "quote",
+                "price": price,
+                "volume24h": 1_000_000.0,
+                "liquidity": 5_000_000.0,
+            }])
+            .to_string()
+        }
+
+        fn reversed_price_body(&self, pair: &TokenPair, price: f64) -> Option<String> {
+            let reversed = TokenPair::new(pair.quote.clone(), pair.base.clone());
+            Some(self.price_body(&reversed, 1.0 / price))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_raydium_conformance() {
+        run_conformance(&Raydium, &ConformanceConfig::default())
+            .await
+            .assert_passed();
+    }
+}
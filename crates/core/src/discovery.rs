@@ -0,0 +1,558 @@
+//! Pool Discovery
+//!
+//! Scans DEX pool registries (Raydium pairs, Orca whirlpools) for pools
+//! between whitelisted tokens and proposes new tradable pairs. A pair is
+//! proposed once pools on at least `min_venues` DEXs each hold
+//! `min_liquidity_usd`, since an arbitrage needs two venues to trade between.
+//!
+//! Every proposal is checked by the [`TokenScreener`] against the on-chain
+//! mint accounts before it can be enabled. Approved proposals are enabled
+//! right away when auto-listing is on, otherwise by an operator. Proposals
+//! and their status are persisted so enabled pairs survive a restart.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::account::Account;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::tokens::TokenInfo;
+use crate::{DexType, TokenPair};
+
+/// SPL Token program, owner of screened mints
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// Token-2022 program; its mints may carry transfer fees or hooks
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Length of an SPL `Mint` account
+const MINT_LEN: usize = 82;
+
+/// One side of a pool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolToken {
+    pub symbol: String,
+    pub mint: String,
+}
+
+/// A pool listed by a DEX registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveredPool {
+    pub dex: DexType,
+    pub address: String,
+    pub token_a: PoolToken,
+    pub token_b: PoolToken,
+    pub liquidity_usd: Decimal,
+}
+
+/// A token pairs may be built from. Without a mint, any mint listed under
+/// the symbol matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhitelistedToken {
+    pub symbol: String,
+    pub mint: Option<String>,
+}
+
+/// What makes a pool set worth proposing
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    pub tokens: Vec<WhitelistedToken>,
+    /// Quote tokens, most preferred first. They are whitelisted as well.
+    pub quote_tokens: Vec<String>,
+    /// Liquidity a pool needs to count
+    pub min_liquidity_usd: Decimal,
+    /// DEXs that must each have a qualifying pool
+    pub min_venues: usize,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            tokens: Vec::new(),
+            quote_tokens: vec!["USDC".to_string(), "SOL".to_string()],
+            min_liquidity_usd: Decimal::from(100_000),
+            min_venues: 2,
+        }
+    }
+}
+
+impl DiscoveryConfig {
+    /// Parse a `SYMBOL[:MINT],...` whitelist
+    pub fn parse_tokens(spec: &str) -> Vec<WhitelistedToken> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once(':') {
+                Some((symbol, mint)) => WhitelistedToken {
+                    symbol: symbol.trim().to_uppercase(),
+                    mint: Some(mint.trim().to_string()),
+                },
+                None => WhitelistedToken {
+                    symbol: entry.to_uppercase(),
+                    mint: None,
+                },
+            })
+            .collect()
+    }
+
+    fn quote_rank(&self, token: &PoolToken) -> Option<usize> {
+        self.quote_tokens
+            .iter()
+            .position(|quote| quote.eq_ignore_ascii_case(&token.symbol))
+            .filter(|_| self.mint_allowed(token))
+    }
+
+    fn is_whitelisted(&self, token: &PoolToken) -> bool {
+        let listed = self.quote_rank(token).is_some()
+            || self.tokens.iter().any(|t| t.symbol.eq_ignore_ascii_case(&token.symbol));
+        listed && self.mint_allowed(token)
+    }
+
+    /// A symbol pinned to a mint only matches that mint
+    fn mint_allowed(&self, token: &PoolToken) -> bool {
+        self.tokens
+            .iter()
+            .filter(|t| t.symbol.eq_ignore_ascii_case(&token.symbol))
+            .all(|t| t.mint.as_deref().is_none_or(|mint| mint == token.mint))
+    }
+
+    /// `(base, quote)` of a pool between whitelisted tokens
+    fn orient<'a>(&self, pool: &'a DiscoveredPool) -> Option<(&'a PoolToken, &'a PoolToken)> {
+        if !self.is_whitelisted(&pool.token_a) || !self.is_whitelisted(&pool.token_b) {
+            return None;
+        }
+        match (self.quote_rank(&pool.token_a), self.quote_rank(&pool.token_b)) {
+            (Some(a), Some(b)) if b < a => Some((&pool.token_a, &pool.token_b)),
+            (Some(_), _) => Some((&pool.token_b, &pool.token_a)),
+            (None, Some(_)) => Some((&pool.token_a, &pool.token_b)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Outcome of screening a mint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum ScreenVerdict {
+    Approved { decimals: u8 },
+    Rejected { reason: String },
+}
+
+/// Checks a token's mint account before its pairs may trade
+#[derive(Debug, Clone, Default)]
+pub struct TokenScreener {
+    /// Mints allowed to keep a freeze authority (e.g. USDC)
+    trusted_mints: HashSet<String>,
+}
+
+impl TokenScreener {
+    pub fn new(trusted_mints: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            trusted_mints: trusted_mints.into_iter().collect(),
+        }
+    }
+
+    /// Screen `mint` given its account, `None` if it does not exist
+    pub fn screen(&self, mint: &str, account: Option<&Account>) -> ScreenVerdict {
+        let reject = |reason: &str| ScreenVerdict::Rejected {
+            reason: reason.to_string(),
+        };
+        let Some(account) = account else {
+            return reject("mint account not found");
+        };
+        match account.owner.to_string().as_str() {
+            TOKEN_PROGRAM_ID => {}
+            TOKEN_2022_PROGRAM_ID => return reject("Token-2022 mint (transfer fees or hooks possible)"),
+            _ => return reject("not an SPL token mint"),
+        }
+        let data = &account.data;
+        if data.len() < MINT_LEN || data[45] == 0 {
+            return reject("mint not initialized");
+        }
+        let freeze_authority = data[46..50] != [0, 0, 0, 0];
+        if freeze_authority && !self.trusted_mints.contains(mint) {
+            return reject("mint has a freeze authority");
+        }
+        ScreenVerdict::Approved { decimals: data[44] }
+    }
+}
+
+/// Where a proposal stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Waiting for its mints to be screened
+    Screening,
+    /// Screened and waiting for an operator
+    Proposed,
+    Enabled,
+    Rejected,
+}
+
+/// A pair found by discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairProposal {
+    pub pair: TokenPair,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub venues: Vec<DexType>,
+    pub pools: Vec<DiscoveredPool>,
+    /// Liquidity of the qualifying pools
+    pub liquidity_usd: Decimal,
+    pub status: ProposalStatus,
+    pub reason: Option<String>,
+    pub base_decimals: Option<u8>,
+    pub quote_decimals: Option<u8>,
+    pub first_seen: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PairProposal {
+    /// Token metadata of an enabled pair
+    pub fn tokens(&self) -> Option<[TokenInfo; 2]> {
+        Some([
+            TokenInfo::new(&self.pair.base, &self.base_mint, self.base_decimals?),
+            TokenInfo::new(&self.pair.quote, &self.quote_mint, self.quote_decimals?),
+        ])
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedDiscovery {
+    proposals: Vec<PairProposal>,
+}
+
+/// Proposals from past scans
+#[derive(Debug)]
+pub struct PoolDiscovery {
+    config: DiscoveryConfig,
+    path: PathBuf,
+    proposals: BTreeMap<String, PairProposal>,
+    last_scan: Option<DateTime<Utc>>,
+}
+
+impl PoolDiscovery {
+    /// Load saved proposals from `path`. A missing or unreadable file starts
+    /// with none.
+    pub fn load(path: impl Into<PathBuf>, config: DiscoveryConfig) -> Self {
+        let path = path.into();
+        let saved: SavedDiscovery = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            config,
+            path,
+            proposals: saved
+                .proposals
+                .into_iter()
+                .map(|p| (p.pair.symbol(), p))
+                .collect(),
+            last_scan: None,
+        }
+    }
+
+    pub fn last_scan(&self) -> Option<DateTime<Utc>> {
+        self.last_scan
+    }
+
+    pub fn proposals(&self) -> Vec<PairProposal> {
+        self.proposals.values().cloned().collect()
+    }
+
+    pub fn get(&self, pair: &str) -> Option<&PairProposal> {
+        self.proposals.get(pair)
+    }
+
+    /// Enabled pairs, for the trading loop
+    pub fn enabled(&self) -> Vec<PairProposal> {
+        self.proposals
+            .values()
+            .filter(|p| p.status == ProposalStatus::Enabled)
+            .cloned()
+            .collect()
+    }
+
+    /// Take in the pools of a scan. Returns the pairs waiting to be screened:
+    /// new ones and those whose screening has not succeeded yet. Pairs in
+    /// `active` are never proposed.
+    pub fn update(&mut self, pools: &[DiscoveredPool], active: &[TokenPair]) -> Vec<String> {
+        let now = Utc::now();
+        self.last_scan = Some(now);
+
+        let mut groups: HashMap<(String, String), (TokenPair, Vec<DiscoveredPool>)> = HashMap::new();
+        for pool in pools {
+            if pool.liquidity_usd < self.config.min_liquidity_usd {
+                continue;
+            }
+            let Some((base, quote)) = self.config.orient(pool) else {
+                continue;
+            };
+            groups
+                .entry((base.mint.clone(), quote.mint.clone()))
+                .or_insert_with(|| {
+                    (
+                        TokenPair::new(base.symbol.to_uppercase(), quote.symbol.to_uppercase()),
+                        Vec::new(),
+                    )
+                })
+                .1
+                .push(pool.clone());
+        }
+
+        // Impostor tokens reuse symbols, so per symbol pair keep the mints
+        // with the most liquidity
+        let mut best: BTreeMap<String, PairProposal> = BTreeMap::new();
+        for ((base_mint, quote_mint), (pair, pools)) in groups {
+            let mut venues: Vec<DexType> = pools.iter().map(|p| p.dex).collect();
+            venues.sort_by_key(|dex| dex.display_name());
+            venues.dedup();
+            if venues.len() < self.config.min_venues || active.contains(&pair) {
+                continue;
+            }
+            let liquidity_usd = pools.iter().map(|p| p.liquidity_usd).sum();
+            let key = pair.symbol();
+            if best.get(&key).is_some_and(|b| b.liquidity_usd >= liquidity_usd) {
+                continue;
+            }
+            best.insert(
+                key,
+                PairProposal {
+                    pair,
+                    base_mint,
+                    quote_mint,
+                    venues,
+                    pools,
+                    liquidity_usd,
+                    status: ProposalStatus::Screening,
+                    reason: None,
+                    base_decimals: None,
+                    quote_decimals: None,
+                    first_seen: now,
+                    updated_at: now,
+                },
+            );
+        }
+
+        for (key, found) in best {
+            match self.proposals.get_mut(&key) {
+                // Same mints: refresh the pools, keep the decision
+                Some(existing)
+                    if existing.base_mint == found.base_mint && existing.quote_mint == found.quote_mint =>
+                {
+                    existing.venues = found.venues;
+                    existing.pools = found.pools;
+                    existing.liquidity_usd = found.liquidity_usd;
+                    existing.updated_at = now;
+                }
+                // Left alone once enabled; a mint switch needs a fresh look
+                Some(existing) if existing.status == ProposalStatus::Enabled => {}
+                _ => {
+                    self.proposals.insert(key, found);
+                }
+            }
+        }
+        self.proposals
+            .iter()
+            .filter(|(_, p)| p.status == ProposalStatus::Screening)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Record the screening of a proposal's mints. An approved proposal is
+    /// enabled when `auto_enable` is set, otherwise it waits for an operator.
+    pub fn apply_screen(
+        &mut self,
+        pair: &str,
+        base: ScreenVerdict,
+        quote: ScreenVerdict,
+        auto_enable: bool,
+    ) -> Option<ProposalStatus> {
+        let proposal = self.proposals.get_mut(pair)?;
+        proposal.updated_at = Utc::now();
+        match (base, quote) {
+            (ScreenVerdict::Approved { decimals: base }, ScreenVerdict::Approved { decimals: quote }) => {
+                proposal.base_decimals = Some(base);
+                proposal.quote_decimals = Some(quote);
+                proposal.reason = None;
+                proposal.status = if auto_enable {
+                    ProposalStatus::Enabled
+                } else {
+                    ProposalStatus::Proposed
+                };
+            }
+            (ScreenVerdict::Rejected { reason }, _) => {
+                proposal.status = ProposalStatus::Rejected;
+                proposal.reason = Some(format!("{}: {}", proposal.pair.base, reason));
+            }
+            (_, ScreenVerdict::Rejected { reason }) => {
+                proposal.status = ProposalStatus::Rejected;
+                proposal.reason = Some(format!("{}: {}", proposal.pair.quote, reason));
+            }
+        }
+        Some(proposal.status)
+    }
+
+    /// Operator approval of a screened proposal
+    pub fn enable(&mut self, pair: &str) -> Result<PairProposal, String> {
+        let proposal = self
+            .proposals
+            .get_mut(pair)
+            .ok_or_else(|| format!("{} has not been proposed", pair))?;
+        match proposal.status {
+            ProposalStatus::Proposed | ProposalStatus::Enabled => {
+                proposal.status = ProposalStatus::Enabled;
+                proposal.updated_at = Utc::now();
+                Ok(proposal.clone())
+            }
+            ProposalStatus::Screening => Err(format!("{} has not been screened yet", pair)),
+            ProposalStatus::Rejected => Err(format!(
+                "{} was rejected by the token screener ({})",
+                pair,
+                proposal.reason.as_deref().unwrap_or("no reason")
+            )),
+        }
+    }
+
+    /// Stop trading an enabled pair. It stays known, so later scans do not
+    /// propose it again.
+    pub fn disable(&mut self, pair: &str) -> Result<PairProposal, String> {
+        let proposal = self
+            .proposals
+            .get_mut(pair)
+            .filter(|p| p.status == ProposalStatus::Enabled)
+            .ok_or_else(|| format!("{} is not enabled", pair))?;
+        proposal.status = ProposalStatus::Proposed;
+        proposal.updated_at = Utc::now();
+        Ok(proposal.clone())
+    }
+
+    /// Write the proposals to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let saved = SavedDiscovery {
+            proposals: self.proposals(),
+        };
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    fn token(symbol: &str, mint: &str) -> PoolToken {
+        PoolToken {
+            symbol: symbol.to_string(),
+            mint: mint.to_string(),
+        }
+    }
+
+    fn pool(dex: DexType, a: PoolToken, b: PoolToken, liquidity: i64) -> DiscoveredPool {
+        DiscoveredPool {
+            dex,
+            address: format!("{:?}-{}-{}", dex, a.symbol, b.symbol),
+            token_a: a,
+            token_b: b,
+            liquidity_usd: Decimal::from(liquidity),
+        }
+    }
+
+    fn mint_account(owner: &str, decimals: u8, freeze: bool) -> Account {
+        let mut data = vec![0u8; MINT_LEN];
+        data[44] = decimals;
+        data[45] = 1;
+        if freeze {
+            data[46] = 1;
+        }
+        Account {
+            lamports: 1_461_600,
+            data,
+            owner: Pubkey::from_str(owner).unwrap(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_discovery_proposes_screens_and_enables() {
+        let config = DiscoveryConfig {
+            tokens: DiscoveryConfig::parse_tokens("BONK, wif:WifMint, JUP"),
+            ..DiscoveryConfig::default()
+        };
+        let usdc = token("USDC", "UsdcMint");
+        let bonk = token("BONK", "BonkMint");
+        let pools = vec![
+            // BONK/USDC on two venues, one listed reversed
+            pool(DexType::Raydium, bonk.clone(), usdc.clone(), 500_000),
+            pool(DexType::Orca, usdc.clone(), bonk.clone(), 200_000),
+            // An impostor BONK with less liquidity
+            pool(DexType::Raydium, token("BONK", "FakeBonk"), usdc.clone(), 150_000),
+            pool(DexType::Orca, token("BONK", "FakeBonk"), usdc.clone(), 150_000),
+            // WIF is pinned to another mint
+            pool(DexType::Raydium, token("WIF", "OtherWif"), usdc.clone(), 900_000),
+            pool(DexType::Orca, token("WIF", "OtherWif"), usdc.clone(), 900_000),
+            // JUP only has one deep pool
+            pool(DexType::Raydium, token("JUP", "JupMint"), usdc.clone(), 900_000),
+            pool(DexType::Orca, token("JUP", "JupMint"), usdc.clone(), 5_000),
+            // Not whitelisted
+            pool(DexType::Raydium, token("SCAM", "ScamMint"), usdc.clone(), 900_000),
+            pool(DexType::Orca, token("SCAM", "ScamMint"), usdc.clone(), 900_000),
+            // SOL/USDC is already traded
+            pool(DexType::Raydium, token("SOL", "SolMint"), usdc.clone(), 900_000),
+            pool(DexType::Orca, usdc.clone(), token("SOL", "SolMint"), 900_000),
+        ];
+
+        let path = std::env::temp_dir().join(format!("discovery-{}.json", uuid::Uuid::new_v4()));
+        let mut discovery = PoolDiscovery::load(&path, config.clone());
+        let active = [TokenPair::new("SOL", "USDC")];
+        let new = discovery.update(&pools, &active);
+        assert_eq!(new, vec!["BONK/USDC".to_string()]);
+        let proposal = discovery.get("BONK/USDC").unwrap();
+        assert_eq!(proposal.base_mint, "BonkMint");
+        assert_eq!(proposal.liquidity_usd, Decimal::from(700_000));
+        assert_eq!(proposal.status, ProposalStatus::Screening);
+        assert!(discovery.enable("BONK/USDC").is_err(), "not screened yet");
+
+        // A rescan keeps one proposal, still waiting to be screened
+        assert_eq!(discovery.update(&pools, &active), new);
+        assert_eq!(discovery.proposals().len(), 1);
+
+        let screener = TokenScreener::new(["UsdcMint".to_string()]);
+        let bonk_verdict = screener.screen("BonkMint", Some(&mint_account(TOKEN_PROGRAM_ID, 5, false)));
+        let usdc_verdict = screener.screen("UsdcMint", Some(&mint_account(TOKEN_PROGRAM_ID, 6, true)));
+        assert_eq!(bonk_verdict, ScreenVerdict::Approved { decimals: 5 });
+        assert_eq!(usdc_verdict, ScreenVerdict::Approved { decimals: 6 });
+        assert!(matches!(
+            screener.screen("X", Some(&mint_account(TOKEN_PROGRAM_ID, 6, true))),
+            ScreenVerdict::Rejected { .. }
+        ));
+        assert!(matches!(
+            screener.screen("X", Some(&mint_account(TOKEN_2022_PROGRAM_ID, 6, false))),
+            ScreenVerdict::Rejected { .. }
+        ));
+        assert!(matches!(screener.screen("X", None), ScreenVerdict::Rejected { .. }));
+
+        assert_eq!(
+            discovery.apply_screen("BONK/USDC", bonk_verdict, usdc_verdict, false),
+            Some(ProposalStatus::Proposed)
+        );
+        assert!(discovery.enabled().is_empty());
+        discovery.enable("BONK/USDC").unwrap();
+        assert!(discovery.update(&pools, &active).is_empty());
+        let tokens = discovery.enabled()[0].tokens().unwrap();
+        assert_eq!(tokens[0], TokenInfo::new("BONK", "BonkMint", 5));
+        discovery.save().unwrap();
+
+        // Enabled pairs survive a restart
+        let reloaded = PoolDiscovery::load(&path, config);
+        assert_eq!(reloaded.enabled().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -55,6 +55,9 @@ pub enum ArbitrageError {
     #[error("Transaction error: {0}")]
     Transaction(String),
 
+    #[error("Invalid token amount: {0}")]
+    InvalidAmount(String),
+
     // ── Flash Loan Errors ───────────────────────────────────────────
     #[error("Flash loan amount {amount} exceeds maximum {max}")]
     FlashLoanAmountExceeded { amount: u64, max: u64 },
@@ -115,6 +118,9 @@ pub enum ArbitrageError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("IPC codec error: {0}")]
+    Codec(String),
+
     #[error("HTTP request error: {0}")]
     Http(String),
 
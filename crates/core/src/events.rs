@@ -2,9 +2,31 @@
 //!
 //! Provides a publish-subscribe event bus for trading events, allowing
 //! components to communicate without direct dependencies.
+//!
+//! The bus is a bounded broadcast channel: a subscriber that falls more
+//! than its capacity behind loses the oldest events. Named subscriptions
+//! are listed in a registry with how far behind each one is and what it
+//! has lost, and each follows an overflow policy:
+//!
+//! - `drop_oldest` skips what it missed and carries on (the channel's own
+//!   behaviour);
+//! - `disconnect` ends the subscription at its first loss, for consumers
+//!   that cannot work from a gapped stream;
+//! - `spill` drains the channel into a queue that keeps `capacity` events in
+//!   memory and writes the rest to a JSONL file, so nothing is lost to a
+//!   slow consumer.
 
+use crate::{ArbitrageError, ArbitrageResult};
 use serde::{Deserialize, Serialize};
-use tokio::sync::broadcast;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tracing::warn;
 
 /// Trading system events
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +64,23 @@ pub enum TradingEvent {
     /// Trade was rejected by risk management
     TradeRejected { id: String, reason: String },
 
+    /// Trade has not reached a terminal state within the expected time
+    TradeStuck {
+        id: String,
+        pair: String,
+        elapsed_secs: u64,
+    },
+
+    /// A confirmed trade was dropped by a fork before finalization
+    ChainReorg {
+        id: String,
+        pair: String,
+        signature: String,
+        /// Recorded P&L (USD) that was reverted
+        reverted_pnl: f64,
+        reason: String,
+    },
+
     // ── Risk Events ─────────────────────────────────────────────────
     /// Circuit breaker state changed
     CircuitBreakerStateChanged {
@@ -56,6 +95,21 @@ pub enum TradingEvent {
         max: f64,
     },
 
+    /// Canary mode armed after an execution-affecting config change
+    CanaryStarted {
+        reason: String,
+        required_successes: u32,
+    },
+
+    /// Canary mode completed and full trade sizes restored
+    CanaryCompleted { successes: u32 },
+
+    /// Exposure exceeds a lowered limit; housekeeping trades will reduce it
+    UnwindStarted { limit: f64, overage: f64 },
+
+    /// Exposure is back within the limit
+    UnwindCompleted { reduced: f64, steps: u32 },
+
     // ── System Events ───────────────────────────────────────────────
     /// System started successfully
     SystemStarted { mode: String },
@@ -66,6 +120,10 @@ pub enum TradingEvent {
     /// Emergency stop triggered
     EmergencyStop { reason: String },
 
+    /// The dynamic config was reloaded; `changes` lists the execution
+    /// changes, empty when none
+    ConfigReloaded { changes: Vec<String> },
+
     /// Health check status
     HealthCheck {
         uptime_secs: u64,
@@ -74,16 +132,131 @@ pub enum TradingEvent {
     },
 }
 
+impl TradingEvent {
+    /// Pair the event is about, for events about one
+    pub fn pair(&self) -> Option<&str> {
+        match self {
+            TradingEvent::PriceUpdate { pair, .. }
+            | TradingEvent::TradeExecuted { pair, .. }
+            | TradingEvent::TradeStuck { pair, .. }
+            | TradingEvent::ChainReorg { pair, .. } => Some(pair),
+            _ => None,
+        }
+    }
+}
+
+/// What a subscription does when it falls too far behind the bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Skip the missed events and carry on
+    #[default]
+    DropOldest,
+    /// End the subscription at the first missed event
+    Disconnect,
+    /// Keep up with the bus and queue the excess on disk
+    Spill,
+}
+
+impl std::str::FromStr for OverflowPolicy {
+    type Err = ArbitrageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "drop_oldest" | "drop-oldest" => Ok(Self::DropOldest),
+            "disconnect" => Ok(Self::Disconnect),
+            "spill" | "spill_to_disk" | "spill-to-disk" => Ok(Self::Spill),
+            other => Err(ArbitrageError::Config(format!("unknown overflow policy '{}'", other))),
+        }
+    }
+}
+
+/// Parse `name=policy` pairs, comma-separated
+pub fn parse_overflow_policies(spec: &str) -> ArbitrageResult<HashMap<String, OverflowPolicy>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, policy) = entry
+                .split_once('=')
+                .ok_or_else(|| ArbitrageError::Config(format!("expected name=policy, got '{}'", entry)))?;
+            Ok((name.trim().to_string(), policy.parse()?))
+        })
+        .collect()
+}
+
+/// One named subscription, as listed by [`EventBus::subscribers`]
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriberInfo {
+    pub name: String,
+    pub policy: OverflowPolicy,
+    /// Events published since it subscribed that it has neither received nor lost
+    pub lag: u64,
+    pub received: u64,
+    /// Events it will never see
+    pub dropped: u64,
+    /// Events written to its spill file so far
+    pub spilled: u64,
+    pub disconnected: bool,
+}
+
+#[derive(Debug)]
+struct SubscriberStats {
+    name: String,
+    policy: OverflowPolicy,
+    /// Bus sequence when it subscribed
+    start: u64,
+    received: AtomicU64,
+    dropped: AtomicU64,
+    spilled: AtomicU64,
+    disconnected: AtomicBool,
+}
+
+type Registry = Arc<Mutex<HashMap<u64, Arc<SubscriberStats>>>>;
+
 /// Broadcast-based event bus for zero-copy event distribution
 pub struct EventBus {
     tx: broadcast::Sender<TradingEvent>,
+    capacity: usize,
+    /// Events published so far
+    sequence: Arc<AtomicU64>,
+    policies: HashMap<String, OverflowPolicy>,
+    spill_dir: Option<PathBuf>,
+    registry: Registry,
+    next_id: AtomicU64,
 }
 
 impl EventBus {
     /// Create a new event bus with the given channel capacity
     pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         let (tx, _) = broadcast::channel(capacity);
-        Self { tx }
+        Self {
+            tx,
+            capacity,
+            sequence: Arc::new(AtomicU64::new(0)),
+            policies: HashMap::new(),
+            spill_dir: None,
+            registry: Arc::default(),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Overflow policies of named subscribers; others drop the oldest events
+    pub fn with_policies(mut self, policies: HashMap<String, OverflowPolicy>) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Directory for the spill files of `spill` subscribers. Without one
+    /// they drop the oldest events instead.
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
     }
 
     /// Publish an event to all subscribers
@@ -91,6 +264,7 @@ impl EventBus {
     /// Returns the number of active subscribers that received the event.
     /// If no subscribers are listening, the event is silently dropped.
     pub fn publish(&self, event: TradingEvent) -> usize {
+        self.sequence.fetch_add(1, Ordering::Relaxed);
         self.tx.send(event).unwrap_or(0)
     }
 
@@ -99,10 +273,88 @@ impl EventBus {
         self.tx.subscribe()
     }
 
+    /// Create a subscription listed in the registry as `name`, following the
+    /// overflow policy configured for that name
+    pub fn subscribe_as(&self, name: &str) -> Subscription {
+        let policy = self.policies.get(name).copied().unwrap_or_default();
+        self.subscribe_with(name, policy)
+    }
+
+    /// Create a named subscription with an explicit overflow policy
+    pub fn subscribe_with(&self, name: &str, policy: OverflowPolicy) -> Subscription {
+        let rx = self.tx.subscribe();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let policy = match (policy, &self.spill_dir) {
+            (OverflowPolicy::Spill, None) => {
+                warn!("Event subscriber {} cannot spill without a spill directory; dropping oldest", name);
+                OverflowPolicy::DropOldest
+            }
+            _ => policy,
+        };
+        let stats = Arc::new(SubscriberStats {
+            name: name.to_string(),
+            policy,
+            start: self.sequence.load(Ordering::Relaxed),
+            received: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            spilled: AtomicU64::new(0),
+            disconnected: AtomicBool::new(false),
+        });
+        let source = match (policy, &self.spill_dir) {
+            (OverflowPolicy::Spill, Some(dir)) => {
+                let path = dir.join(format!("{}-{}.jsonl", name, id));
+                let queue = Arc::new(SpillQueue::new(path, self.capacity));
+                let forwarder = tokio::spawn(queue.clone().forward(rx, stats.clone()));
+                Source::Spill(queue, forwarder)
+            }
+            _ => Source::Direct(rx),
+        };
+        lock(&self.registry).insert(id, stats.clone());
+        Subscription {
+            id,
+            source,
+            stats,
+            registry: self.registry.clone(),
+        }
+    }
+
+    /// Named subscriptions and how far behind each one is
+    pub fn subscribers(&self) -> Vec<SubscriberInfo> {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        let mut subscribers: Vec<SubscriberInfo> = lock(&self.registry)
+            .values()
+            .map(|stats| {
+                let received = stats.received.load(Ordering::Relaxed);
+                let dropped = stats.dropped.load(Ordering::Relaxed);
+                let disconnected = stats.disconnected.load(Ordering::Relaxed);
+                SubscriberInfo {
+                    name: stats.name.clone(),
+                    policy: stats.policy,
+                    lag: if disconnected {
+                        0
+                    } else {
+                        sequence.saturating_sub(stats.start + received + dropped)
+                    },
+                    received,
+                    dropped,
+                    spilled: stats.spilled.load(Ordering::Relaxed),
+                    disconnected,
+                }
+            })
+            .collect();
+        subscribers.sort_by(|a, b| a.name.cmp(&b.name));
+        subscribers
+    }
+
     /// Get the number of active subscribers
     pub fn subscriber_count(&self) -> usize {
         self.tx.receiver_count()
     }
+
+    /// Approximate memory held by events not yet seen by every subscriber
+    pub fn memory_usage(&self) -> crate::memory::MemoryUsage {
+        crate::memory::MemoryUsage::of::<TradingEvent>(self.tx.len(), 0)
+    }
 }
 
 impl Default for EventBus {
@@ -111,6 +363,196 @@ impl Default for EventBus {
     }
 }
 
+enum Source {
+    Direct(broadcast::Receiver<TradingEvent>),
+    Spill(Arc<SpillQueue>, JoinHandle<()>),
+}
+
+/// A named subscription; leaves the registry when dropped
+pub struct Subscription {
+    id: u64,
+    source: Source,
+    stats: Arc<SubscriberStats>,
+    registry: Registry,
+}
+
+impl Subscription {
+    /// Next event, or `None` once the bus is gone or, under `disconnect`,
+    /// the subscription fell behind
+    pub async fn recv(&mut self) -> Option<TradingEvent> {
+        let event = match &mut self.source {
+            Source::Direct(rx) => loop {
+                if self.stats.disconnected.load(Ordering::Relaxed) {
+                    return None;
+                }
+                match rx.recv().await {
+                    Ok(event) => break event,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        self.stats.dropped.fetch_add(missed, Ordering::Relaxed);
+                        if self.stats.policy == OverflowPolicy::Disconnect {
+                            warn!("Event subscriber {} lost {} events and was disconnected", self.stats.name, missed);
+                            self.stats.disconnected.store(true, Ordering::Relaxed);
+                            return None;
+                        }
+                        warn!("Event subscriber {} fell behind and lost {} events", self.stats.name, missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+            Source::Spill(queue, _) => queue.pop().await?,
+        };
+        self.stats.received.fetch_add(1, Ordering::Relaxed);
+        Some(event)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.stats.name
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        lock(&self.registry).remove(&self.id);
+        if let Source::Spill(queue, forwarder) = &self.source {
+            forwarder.abort();
+            queue.discard();
+        }
+    }
+}
+
+struct SpillState {
+    memory: VecDeque<TradingEvent>,
+    /// Events in the spill file not yet read back
+    on_disk: usize,
+    read_offset: u64,
+    closed: bool,
+}
+
+/// Queue of events keeping `memory_cap` in memory and the rest on disk, in
+/// order
+struct SpillQueue {
+    path: PathBuf,
+    memory_cap: usize,
+    state: Mutex<SpillState>,
+    ready: Notify,
+}
+
+impl SpillQueue {
+    fn new(path: PathBuf, memory_cap: usize) -> Self {
+        Self {
+            path,
+            memory_cap: memory_cap.max(1),
+            state: Mutex::new(SpillState {
+                memory: VecDeque::new(),
+                on_disk: 0,
+                read_offset: 0,
+                closed: false,
+            }),
+            ready: Notify::new(),
+        }
+    }
+
+    /// Move events off the bus as fast as they arrive
+    async fn forward(self: Arc<Self>, mut rx: broadcast::Receiver<TradingEvent>, stats: Arc<SubscriberStats>) {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if let Err(e) = self.push(event, &stats) {
+                        stats.dropped.fetch_add(1, Ordering::Relaxed);
+                        warn!("Event subscriber {} failed to spill an event: {}", stats.name, e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    stats.dropped.fetch_add(missed, Ordering::Relaxed);
+                    warn!("Event subscriber {} lost {} events before spilling them", stats.name, missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+        lock(&self.state).closed = true;
+        self.ready.notify_one();
+    }
+
+    fn push(&self, event: TradingEvent, stats: &SubscriberStats) -> std::io::Result<()> {
+        let mut state = lock(&self.state);
+        if state.on_disk == 0 && state.memory.len() < self.memory_cap {
+            state.memory.push_back(event);
+        } else {
+            if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            writeln!(file, "{}", serde_json::to_string(&event)?)?;
+            state.on_disk += 1;
+            stats.spilled.fetch_add(1, Ordering::Relaxed);
+        }
+        drop(state);
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    async fn pop(&self) -> Option<TradingEvent> {
+        loop {
+            {
+                let mut state = lock(&self.state);
+                if state.memory.is_empty() && state.on_disk > 0 {
+                    if let Err(e) = self.refill(&mut state) {
+                        warn!("Failed to read spilled events from {}: {}", self.path.display(), e);
+                        state.on_disk = 0;
+                        state.read_offset = 0;
+                        let _ = fs::remove_file(&self.path);
+                    }
+                }
+                if let Some(event) = state.memory.pop_front() {
+                    return Some(event);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// Read the next `memory_cap` spilled events back into memory
+    fn refill(&self, state: &mut SpillState) -> std::io::Result<()> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(state.read_offset))?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        while state.on_disk > 0 && state.memory.len() < self.memory_cap {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                // The file is shorter than counted; what is missing is lost
+                state.on_disk = 0;
+                break;
+            }
+            state.read_offset += read as u64;
+            state.on_disk -= 1;
+            match serde_json::from_str(line.trim_end()) {
+                Ok(event) => state.memory.push_back(event),
+                Err(e) => warn!("Skipping unreadable spilled event: {}", e),
+            }
+        }
+        if state.on_disk == 0 {
+            state.read_offset = 0;
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+
+    fn discard(&self) {
+        if lock(&self.state).on_disk > 0 {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +631,50 @@ mod tests {
         drop(_rx1);
         assert_eq!(bus.subscriber_count(), 1);
     }
+
+    #[tokio::test]
+    async fn test_slow_subscribers_follow_their_overflow_policy() {
+        let policies = parse_overflow_policies("journal=spill, strict=disconnect").unwrap();
+        assert!(parse_overflow_policies("journal=keep").is_err());
+        let dir = std::env::temp_dir().join(format!("event_spill_{}", uuid::Uuid::new_v4()));
+        let bus = EventBus::new(4).with_policies(policies).with_spill_dir(&dir);
+        let mut lossy = bus.subscribe_as("logger");
+        let mut strict = bus.subscribe_as("strict");
+        let mut journal = bus.subscribe_as("journal");
+        tokio::task::yield_now().await;
+
+        for i in 0..10u64 {
+            bus.publish(TradingEvent::CanaryCompleted { successes: i as u32 });
+            // Let the spill forwarder keep up with the bus
+            tokio::task::yield_now().await;
+        }
+        let lag = |name: &str| bus.subscribers().into_iter().find(|s| s.name == name).unwrap();
+        assert_eq!(lag("logger").lag, 10);
+        assert!(lag("journal").spilled >= 6);
+
+        // Drop-oldest resumes at the oldest event still buffered
+        match lossy.recv().await {
+            Some(TradingEvent::CanaryCompleted { successes }) => assert_eq!(successes, 6),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(lag("logger").dropped, 6);
+        assert_eq!(lag("logger").lag, 3);
+
+        assert!(strict.recv().await.is_none());
+        assert!(lag("strict").disconnected);
+
+        // The spilled subscriber sees every event, in order
+        for i in 0..10u32 {
+            match journal.recv().await {
+                Some(TradingEvent::CanaryCompleted { successes }) => assert_eq!(successes, i),
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        assert_eq!((lag("journal").lag, lag("journal").dropped), (0, 0));
+        assert!(!dir.join("journal-2.jsonl").exists());
+
+        drop(lossy);
+        assert_eq!(bus.subscribers().len(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,252 @@
+//! Accounting Currency
+//!
+//! Trades settle in USD stablecoins, so positions, P&L and risk checks are
+//! tracked in USD. Operators can keep their books in another currency
+//! (`ACCOUNTING_CURRENCY`, e.g. EUR): configured money limits are read in
+//! it, and reports, metrics and alerts show amounts in it. Conversion uses an
+//! [`FxRate`] refreshed from a Pyth price feed quoting the currency in USD.
+//!
+//! The bot writes each refreshed rate to [`FX_RATE_PATH`] so the API server
+//! converts reports at the same rate the bot applies to its limits.
+
+use crate::error::{ArbitrageError, ArbitrageResult};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+pub const USD: &str = "USD";
+
+/// Where the bot publishes the rate it converts with
+pub const FX_RATE_PATH: &str = "data/fx-rate.json";
+
+/// Pyth feed quoting `currency` in USD, for currencies with a known feed.
+/// Others need `FX_FEED_ID`.
+pub fn default_feed_id(currency: &str) -> Option<&'static str> {
+    match currency {
+        "EUR" => Some("0xa995d00bb36a63cef7fd2c287dc105fc8f3d93779f062f09551b0af3e81ec30b"),
+        _ => None,
+    }
+}
+
+/// USD value of one unit of the accounting currency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxRate {
+    /// ISO code, e.g. "EUR"
+    pub currency: String,
+    /// USD per unit of `currency` (1 for USD)
+    pub usd_per_unit: Decimal,
+    /// Publish time of the oracle price
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for FxRate {
+    fn default() -> Self {
+        Self {
+            currency: USD.to_string(),
+            usd_per_unit: Decimal::ONE,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+impl FxRate {
+    pub fn is_usd(&self) -> bool {
+        self.currency == USD
+    }
+
+    /// Convert a USD amount to the accounting currency
+    pub fn from_usd(&self, usd: Decimal) -> Decimal {
+        if self.usd_per_unit.is_zero() {
+            return usd;
+        }
+        usd / self.usd_per_unit
+    }
+
+    /// Convert an amount in the accounting currency to USD
+    pub fn to_usd(&self, amount: Decimal) -> Decimal {
+        amount * self.usd_per_unit
+    }
+
+    pub fn from_usd_f64(&self, usd: f64) -> f64 {
+        match self.usd_per_unit.to_f64() {
+            Some(rate) if rate > 0.0 => usd / rate,
+            _ => usd,
+        }
+    }
+
+    /// A USD amount shown in the accounting currency: "$12.34", or
+    /// "11.37 EUR"
+    pub fn format(&self, usd: f64) -> String {
+        if self.is_usd() {
+            format!("${:.2}", usd)
+        } else {
+            format!("{:.2} {}", self.from_usd_f64(usd), self.currency)
+        }
+    }
+
+    /// Seconds since the oracle published the rate
+    pub fn age_secs(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.updated_at).num_seconds()
+    }
+
+    /// Report amounts converted to the accounting currency, from
+    /// `(field, usd)` pairs as reports hold them (`None` when accounting in
+    /// USD). Amounts that do not parse are left out.
+    pub fn accounting(&self, amounts: &[(&str, &str)]) -> Option<AccountingAmounts> {
+        if self.is_usd() {
+            return None;
+        }
+        Some(AccountingAmounts {
+            currency: self.currency.clone(),
+            usd_per_unit: self.usd_per_unit.normalize().to_string(),
+            rate_at: self.updated_at,
+            amounts: amounts
+                .iter()
+                .filter_map(|(field, usd)| {
+                    let usd = Decimal::from_str(usd).ok()?;
+                    Some((field.to_string(), format!("{:.2}", self.from_usd(usd))))
+                })
+                .collect(),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> ArbitrageResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> ArbitrageResult<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Report amounts in the accounting currency, next to the USD ones
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AccountingAmounts {
+    pub currency: String,
+    /// USD per unit of `currency` the amounts were converted at
+    pub usd_per_unit: String,
+    pub rate_at: DateTime<Utc>,
+    /// Converted amounts, keyed by the USD field name without `_usd`
+    pub amounts: BTreeMap<String, String>,
+}
+
+/// Latest rate, shared by everything that converts amounts
+#[derive(Debug, Clone, Default)]
+pub struct SharedFxRate(Arc<RwLock<FxRate>>);
+
+impl SharedFxRate {
+    pub fn new(rate: FxRate) -> Self {
+        Self(Arc::new(RwLock::new(rate)))
+    }
+
+    pub fn get(&self) -> FxRate {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set(&self, rate: FxRate) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = rate;
+    }
+
+    /// Shorthand for [`FxRate::format`] at the current rate
+    pub fn format(&self, usd: f64) -> String {
+        self.get().format(usd)
+    }
+}
+
+/// Rate from a Pyth Hermes `/v2/updates/price/latest` response for a feed
+/// quoting `currency` in USD
+pub fn parse_pyth_rate(currency: &str, body: &serde_json::Value) -> ArbitrageResult<FxRate> {
+    let invalid = |reason: &str| ArbitrageError::PriceFetchDetailed {
+        pair: format!("{}/{}", currency, USD),
+        reason: reason.to_string(),
+    };
+    let price = body["parsed"]
+        .get(0)
+        .map(|feed| &feed["price"])
+        .ok_or_else(|| invalid("no parsed price in oracle response"))?;
+    let mantissa = price["price"]
+        .as_str()
+        .and_then(|p| p.parse::<i64>().ok())
+        .ok_or_else(|| invalid("missing price"))?;
+    let expo = price["expo"].as_i64().ok_or_else(|| invalid("missing exponent"))?;
+    if !(-28..=0).contains(&expo) || mantissa <= 0 {
+        return Err(invalid("price out of range"));
+    }
+    let updated_at = price["publish_time"]
+        .as_i64()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .ok_or_else(|| invalid("missing publish time"))?;
+
+    Ok(FxRate {
+        currency: currency.to_string(),
+        usd_per_unit: Decimal::new(mantissa, (-expo) as u32),
+        updated_at,
+    })
+}
+
+/// Fetch the latest rate for `currency` from a Pyth Hermes endpoint
+#[cfg(feature = "http")]
+pub async fn fetch_pyth_rate(
+    client: &reqwest::Client,
+    hermes_url: &str,
+    feed_id: &str,
+    currency: &str,
+) -> ArbitrageResult<FxRate> {
+    let url = format!(
+        "{}/v2/updates/price/latest?ids[]={}",
+        hermes_url.trim_end_matches('/'),
+        feed_id
+    );
+    let body: serde_json::Value = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    parse_pyth_rate(currency, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pyth_rate_converts_and_formats() {
+        let body = json!({
+            "parsed": [{
+                "id": "a995d00bb36a63cef7fd2c287dc105fc8f3d93779f062f09551b0af3e81ec30b",
+                "price": { "price": "108000", "conf": "12", "expo": -5, "publish_time": 1_700_000_000 }
+            }]
+        });
+        let rate = parse_pyth_rate("EUR", &body).unwrap();
+        assert_eq!(rate.usd_per_unit, Decimal::new(108, 2));
+        assert_eq!(rate.updated_at.timestamp(), 1_700_000_000);
+
+        assert_eq!(rate.from_usd(Decimal::from(108)), Decimal::from(100));
+        assert_eq!(rate.to_usd(Decimal::from(1000)), Decimal::from(1080));
+        assert_eq!(rate.format(54.0), "50.00 EUR");
+        assert_eq!(FxRate::default().format(54.0), "$54.00");
+
+        let converted = rate
+            .accounting(&[("total_profit", "21.60"), ("total_volume", "n/a")])
+            .unwrap();
+        assert_eq!(converted.usd_per_unit, "1.08");
+        assert_eq!(converted.amounts.len(), 1);
+        assert_eq!(converted.amounts["total_profit"], "20.00");
+        assert!(FxRate::default().accounting(&[("total_profit", "1")]).is_none());
+
+        assert!(parse_pyth_rate("EUR", &json!({ "parsed": [] })).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Hash-Chained JSONL Logs
+//!
+//! The audit log and the trade history are append-only JSONL files. To make
+//! edits, deletions and reordering detectable, every record written through
+//! a [`HashChain`] carries a `chain` object:
+//!
+//! ```json
+//! {"timestamp": "...", ..., "chain": {"seq": 7, "prev": "<hash of record 6>", "hash": "<hash>"}}
+//! ```
+//!
+//! `hash` is the SHA-256 of the previous record's hash followed by the
+//! record itself (without `chain`) as canonical JSON (object keys sorted,
+//! no whitespace). With a signer, the hash is also signed with its Ed25519
+//! key (`sig`, `signer`), so a rewritten chain cannot be passed off as the
+//! bot's. [`verify`] replays a file, rotated history segments included.
+//!
+//! Records written before chaining have no `chain` and are only accepted
+//! ahead of the first chained one.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::hash::{hashv, Hash};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::history::rotation::{self, DateRange};
+
+/// `prev` of the first record of a chain
+pub const GENESIS: &str = "genesis";
+
+/// Key of the chain object in every record
+const CHAIN_FIELD: &str = "chain";
+
+/// Position of a record in its chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    pub seq: u64,
+    pub prev: String,
+    pub hash: String,
+    /// Ed25519 signature of the hash, base58
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+    /// Public key that made `sig`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+}
+
+/// The chain link of a JSONL line, if it has one
+pub fn link_of(line: &str) -> Option<ChainLink> {
+    let mut value: Value = serde_json::from_str(line).ok()?;
+    serde_json::from_value(value.as_object_mut()?.remove(CHAIN_FIELD)?).ok()
+}
+
+/// The last chain link in a file
+pub fn last_link(path: &Path) -> Option<ChainLink> {
+    let file = File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| link_of(&line))
+        .last()
+}
+
+/// Writer side of a chain: links and optionally signs each new record
+pub struct HashChain {
+    head: Mutex<Option<ChainLink>>,
+    signer: Option<Arc<Keypair>>,
+}
+
+impl HashChain {
+    /// Continue after `head`, or start a new chain
+    pub fn resume(head: Option<ChainLink>) -> Self {
+        Self {
+            head: Mutex::new(head),
+            signer: None,
+        }
+    }
+
+    /// Sign every new record with `signer`
+    pub fn with_signer(mut self, signer: Arc<Keypair>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// The JSON line for `record`, linked to the previous one. Callers
+    /// write lines in the order they were sealed.
+    pub fn seal(&self, record: &impl Serialize) -> serde_json::Result<String> {
+        let mut value = serde_json::to_value(record)?;
+        let mut head = self.head.lock().unwrap_or_else(|e| e.into_inner());
+        let (seq, prev) = match head.as_ref() {
+            Some(link) => (link.seq + 1, link.hash.clone()),
+            None => (0, GENESIS.to_string()),
+        };
+        let hash = link_hash(&prev, &value);
+        let link = ChainLink {
+            seq,
+            prev,
+            hash: hash.to_string(),
+            sig: self
+                .signer
+                .as_ref()
+                .map(|key| key.sign_message(hash.as_ref()).to_string()),
+            signer: self.signer.as_ref().map(|key| key.pubkey().to_string()),
+        };
+        if let Some(object) = value.as_object_mut() {
+            object.insert(CHAIN_FIELD.to_string(), serde_json::to_value(&link)?);
+        }
+        *head = Some(link);
+        serde_json::to_string(&value)
+    }
+}
+
+fn link_hash(prev: &str, record: &Value) -> Hash {
+    let mut body = String::new();
+    canonical(record, &mut body);
+    hashv(&[prev.as_bytes(), body.as_bytes()])
+}
+
+/// Compact JSON with object keys sorted, independent of map ordering
+fn canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Result of replaying a chain
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChainReport {
+    /// Chained records checked
+    pub records: usize,
+    /// Records from before chaining, ahead of the first chained one
+    pub unchained: usize,
+    pub signed: usize,
+    /// Sequence number the chain starts at; not 0 if older records
+    /// (e.g. archived history segments) are missing
+    pub first_seq: Option<u64>,
+    pub head: Option<String>,
+    /// First record that does not verify; nothing after it was checked
+    pub broken: Option<ChainBreak>,
+}
+
+impl ChainReport {
+    pub fn is_intact(&self) -> bool {
+        self.broken.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainBreak {
+    /// 1-based line across all segments and the active file
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Replay the chain of `path` (and its rotated segments, if any). With
+/// `signer`, every record must carry a valid signature by that key.
+pub fn verify(path: &Path, signer: Option<&Pubkey>) -> io::Result<ChainReport> {
+    let mut report = ChainReport::default();
+    let mut head: Option<ChainLink> = None;
+    let mut line_no = 0;
+    rotation::for_each_line(path, &DateRange::all(), |line| {
+        line_no += 1;
+        if report.broken.is_some() || line.trim().is_empty() {
+            return;
+        }
+        if let Err(reason) = check_line(line, &mut head, &mut report, signer) {
+            report.broken = Some(ChainBreak {
+                line: line_no,
+                reason,
+            });
+        }
+    })?;
+    report.head = head.map(|link| link.hash);
+    Ok(report)
+}
+
+fn check_line(
+    line: &str,
+    head: &mut Option<ChainLink>,
+    report: &mut ChainReport,
+    signer: Option<&Pubkey>,
+) -> Result<(), String> {
+    let mut value: Value = serde_json::from_str(line).map_err(|e| format!("not JSON: {}", e))?;
+    let chain = value.as_object_mut().and_then(|o| o.remove(CHAIN_FIELD));
+    let Some(chain) = chain else {
+        if head.is_some() {
+            return Err("record without a chain link after chaining started".to_string());
+        }
+        report.unchained += 1;
+        return Ok(());
+    };
+    let link: ChainLink = serde_json::from_value(chain).map_err(|e| format!("bad chain link: {}", e))?;
+
+    match head.as_ref() {
+        Some(prev) if link.seq != prev.seq + 1 || link.prev != prev.hash => {
+            return Err(format!(
+                "seq {} does not follow seq {} (record removed, reordered or inserted)",
+                link.seq, prev.seq
+            ));
+        }
+        Some(_) => {}
+        None if link.seq == 0 && link.prev != GENESIS => {
+            return Err("first record does not start at genesis".to_string());
+        }
+        None => report.first_seq = Some(link.seq),
+    }
+
+    let hash = link_hash(&link.prev, &value);
+    if hash.to_string() != link.hash {
+        return Err(format!("seq {} was modified: hash mismatch", link.seq));
+    }
+
+    match (&link.sig, &link.signer) {
+        (Some(sig), Some(key)) => {
+            let key = Pubkey::from_str(key).map_err(|_| format!("seq {}: bad signer", link.seq))?;
+            if signer.is_some_and(|expected| *expected != key) {
+                return Err(format!("seq {} was signed by {}, not the expected key", link.seq, key));
+            }
+            let sig = Signature::from_str(sig).map_err(|_| format!("seq {}: bad signature", link.seq))?;
+            if !sig.verify(key.as_ref(), hash.as_ref()) {
+                return Err(format!("seq {}: signature does not match", link.seq));
+            }
+            report.signed += 1;
+        }
+        _ if signer.is_some() => return Err(format!("seq {} is not signed", link.seq)),
+        _ => {}
+    }
+
+    report.records += 1;
+    *head = Some(link);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_chain_detects_edits_and_forged_signatures() {
+        let dir = std::env::temp_dir().join(format!("hash-chain-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+        let key = Arc::new(Keypair::new());
+
+        let mut lines = vec![json!({"timestamp": "2026-03-01T00:00:00Z", "legacy": true}).to_string()];
+        let chain = HashChain::resume(None).with_signer(key.clone());
+        for i in 0..3 {
+            lines.push(chain.seal(&json!({"timestamp": "2026-03-01T00:00:00Z", "profit": i, "b": [1, {"z": 1, "a": 2}]})).unwrap());
+        }
+        let write = |lines: &[String]| std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        write(&lines);
+
+        let report = verify(&path, Some(&key.pubkey())).unwrap();
+        assert!(report.is_intact(), "{:?}", report.broken);
+        assert_eq!((report.records, report.unchained, report.signed), (3, 1, 3));
+        assert_eq!(report.first_seq, Some(0));
+        assert_eq!(last_link(&path).unwrap().hash, report.head.clone().unwrap());
+
+        // A resumed chain continues where the file ends
+        let resumed = HashChain::resume(last_link(&path)).with_signer(key.clone());
+        lines.push(resumed.seal(&json!({"profit": 3})).unwrap());
+        write(&lines);
+        assert_eq!(verify(&path, None).unwrap().records, 4);
+
+        let broken_at = |lines: &[String]| {
+            write(lines);
+            verify(&path, None).unwrap().broken.map(|b| b.line)
+        };
+        let mut edited = lines.clone();
+        edited[2] = edited[2].replace("\"profit\":1", "\"profit\":100");
+        assert_eq!(broken_at(&edited), Some(3));
+        let mut removed = lines.clone();
+        removed.remove(2);
+        assert_eq!(broken_at(&removed), Some(3));
+        let mut unchained = lines.clone();
+        unchained.push(json!({"profit": 4}).to_string());
+        assert_eq!(broken_at(&unchained), Some(6));
+
+        // Rechaining everything with another key is caught by the expected signer
+        let forger = HashChain::resume(None).with_signer(Arc::new(Keypair::new()));
+        let forged: Vec<String> = (0..2).map(|i| forger.seal(&json!({"profit": i})).unwrap()).collect();
+        write(&forged);
+        assert!(verify(&path, None).unwrap().is_intact());
+        assert!(!verify(&path, Some(&key.pubkey())).unwrap().is_intact());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
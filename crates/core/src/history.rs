@@ -1,16 +1,45 @@
-use crate::types::ArbitrageOpportunity;
-use chrono::Utc;
+pub mod rotation;
+
+use crate::fx::{AccountingAmounts, FxRate};
+use crate::hash_chain::{self, HashChain};
+use crate::types::{ArbitrageOpportunity, FailureKind};
+use chrono::{DateTime, Utc};
+use rotation::{DateRange, RotationPolicy, SegmentIndex};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Schema version written into every new `TradeRecord`.
+/// Records written before versioning deserialize as version 0. Version 2
+/// added the `COMMITMENT_UPDATE` and `REPAIR` records, which older binaries
+/// would count as trades; its other fields are optional and read as absent
+/// from version 1 records.
+pub const HISTORY_SCHEMA_VERSION: u32 = 2;
+
+/// `trade_type` of records that update the commitment of an earlier trade
+pub const COMMITMENT_UPDATE: &str = "COMMITMENT_UPDATE";
+
+/// `trade_type` of trades that sold what a failed trade left in the wallet
+pub const REPAIR: &str = "REPAIR";
+
+/// Commitment of a trade rolled back by a fork
+pub const COMMITMENT_REORGED: &str = "reorged";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TradeRecord {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Identifies the record across sinks; records written before ids have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<uuid::Uuid>,
     pub timestamp: String,
     pub session_id: String,
-    pub trade_type: String, // "SIMULATION" or "REAL"
+    pub trade_type: String, // "SIMULATION", "REAL" or "REPAIR"
     pub pair: String,
     pub buy_dex: String,
     pub sell_dex: String,
@@ -20,11 +49,57 @@ pub struct TradeRecord {
     pub tx_signature: Option<String>,
     pub success: bool,
     pub error: Option<String>,
+    /// Jupiter AMM labels excluded from routing when the trade was planned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_dexes: Vec<String>,
+    /// Net payer balance change predicted by the pre-submission simulation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_profit_usd: Option<String>,
+    /// Commitment the transaction had reached when recorded
+    /// ("confirmed", "finalized" or "reorged")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+    /// Category of a failed trade, if tracked separately (e.g. "route_mismatch")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_kind: Option<FailureKind>,
+    /// What produced the traded opportunity: "detector" or the strategy's
+    /// name. Records written before attribution have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Later commitment of a real trade, appended as a [`COMMITMENT_UPDATE`] record
+#[derive(Debug, Clone)]
+pub struct CommitmentUpdate {
+    pub pair: String,
+    pub buy_dex: String,
+    pub sell_dex: String,
+    pub size_usd: Decimal,
+    /// P&L recorded for the original trade
+    pub profit_usd: Decimal,
+    pub signature: String,
+    pub commitment: String,
+    pub reason: Option<String>,
+}
+
+/// Another destination of every record written to the history file, such
+/// as a database. Recording must not block the trading loop.
+pub trait HistorySink: Send + Sync {
+    fn record(&self, record: &TradeRecord);
 }
 
 pub struct HistoryRecorder {
     file_path: String,
     session_id: String,
+    excluded_dexes: RwLock<Vec<String>>,
+    commitment_level: String,
+    rotation: Option<RotationPolicy>,
+    /// When the active file's first record was written, once known. Held
+    /// while appending so rotation and writes do not interleave.
+    active_since: Mutex<Option<DateTime<Utc>>>,
+    /// Links every record to the previous one, across rotations
+    chain: HashChain,
+    sinks: RwLock<Vec<Arc<dyn HistorySink>>>,
 }
 
 impl HistoryRecorder {
@@ -37,9 +112,43 @@ impl HistoryRecorder {
         Self {
             file_path: file_path.to_string(),
             session_id: session_id.to_string(),
+            excluded_dexes: RwLock::new(Vec::new()),
+            commitment_level: "confirmed".to_string(),
+            rotation: None,
+            active_since: Mutex::new(None),
+            chain: HashChain::resume(chain_head(Path::new(file_path))),
+            sinks: RwLock::new(Vec::new()),
         }
     }
 
+    /// Sign every record with `signer`
+    pub fn with_signer(mut self, signer: Arc<Keypair>) -> Self {
+        self.chain = self.chain.with_signer(signer);
+        self
+    }
+
+    /// Rotate the file into compressed segments per `policy`
+    pub fn with_rotation(mut self, policy: RotationPolicy) -> Self {
+        self.rotation = Some(policy);
+        self
+    }
+
+    /// Commitment level real trades are confirmed at (default "confirmed")
+    pub fn with_commitment_level(mut self, level: &str) -> Self {
+        self.commitment_level = level.to_string();
+        self
+    }
+
+    /// Also send every subsequent record to `sink`
+    pub fn add_sink(&self, sink: Arc<dyn HistorySink>) {
+        self.sinks.write().unwrap_or_else(|e| e.into_inner()).push(sink);
+    }
+
+    /// Set the route exclusions recorded with subsequent trades
+    pub fn set_excluded_dexes(&self, excluded: Vec<String>) {
+        *self.excluded_dexes.write().unwrap_or_else(|e| e.into_inner()) = excluded;
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn record_trade(
         &self,
@@ -49,9 +158,13 @@ impl HistoryRecorder {
         success: bool,
         tx_sig: Option<String>,
         error: Option<String>,
+        simulated_profit: Option<Decimal>,
+        failure_kind: Option<FailureKind>,
         is_dry_run: bool,
     ) {
         let record = TradeRecord {
+            schema_version: HISTORY_SCHEMA_VERSION,
+            id: Some(uuid::Uuid::new_v4()),
             timestamp: Utc::now().to_rfc3339(),
             session_id: self.session_id.clone(),
             trade_type: if is_dry_run {
@@ -68,9 +181,103 @@ impl HistoryRecorder {
             tx_signature: tx_sig,
             success,
             error,
+            excluded_dexes: self
+                .excluded_dexes
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            simulated_profit_usd: simulated_profit.map(|p| p.round_dp(4).to_string()),
+            commitment: (!is_dry_run && success).then(|| self.commitment_level.clone()),
+            failure_kind,
+            source: Some(opp.source_label().to_string()),
         };
+        self.append(&record);
+    }
 
-        match serde_json::to_string(&record) {
+    /// Record a repair trade selling `size_usd` of the base token a failed
+    /// trade of `opp` left in the wallet. `profit_usd` is the sale measured
+    /// against the failed trade's buy price.
+    pub fn record_repair(
+        &self,
+        opp: &ArbitrageOpportunity,
+        size_usd: Decimal,
+        profit_usd: Decimal,
+        tx_sig: Option<String>,
+        error: Option<String>,
+    ) {
+        let success = error.is_none();
+        let record = TradeRecord {
+            schema_version: HISTORY_SCHEMA_VERSION,
+            id: Some(uuid::Uuid::new_v4()),
+            timestamp: Utc::now().to_rfc3339(),
+            session_id: self.session_id.clone(),
+            trade_type: REPAIR.to_string(),
+            pair: opp.pair.symbol(),
+            buy_dex: opp.buy_dex.display_name().to_string(),
+            sell_dex: opp.sell_dex.display_name().to_string(),
+            size_usd: size_usd.round_dp(2).to_string(),
+            profit_usd: profit_usd.round_dp(4).to_string(),
+            profit_pct: "0".to_string(),
+            tx_signature: tx_sig,
+            success,
+            error,
+            excluded_dexes: Vec::new(),
+            simulated_profit_usd: None,
+            commitment: success.then(|| self.commitment_level.clone()),
+            failure_kind: None,
+            source: Some(opp.source_label().to_string()),
+        };
+        self.append(&record);
+    }
+
+    /// Record that a real trade reached a new commitment (or was reorged)
+    pub fn record_commitment_update(&self, update: &CommitmentUpdate) {
+        let record = TradeRecord {
+            schema_version: HISTORY_SCHEMA_VERSION,
+            id: Some(uuid::Uuid::new_v4()),
+            timestamp: Utc::now().to_rfc3339(),
+            session_id: self.session_id.clone(),
+            trade_type: COMMITMENT_UPDATE.to_string(),
+            pair: update.pair.clone(),
+            buy_dex: update.buy_dex.clone(),
+            sell_dex: update.sell_dex.clone(),
+            size_usd: update.size_usd.round_dp(2).to_string(),
+            profit_usd: update.profit_usd.round_dp(4).to_string(),
+            profit_pct: "0".to_string(),
+            tx_signature: Some(update.signature.clone()),
+            success: update.commitment != COMMITMENT_REORGED,
+            error: update.reason.clone(),
+            excluded_dexes: Vec::new(),
+            simulated_profit_usd: None,
+            commitment: Some(update.commitment.clone()),
+            failure_kind: None,
+            source: None,
+        };
+        self.append(&record);
+    }
+
+    fn append(&self, record: &TradeRecord) {
+        let mut active_since = self.active_since.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(policy) = &self.rotation {
+            let path = Path::new(&self.file_path);
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if active_since.is_none() {
+                *active_since = rotation::first_timestamp(path);
+            }
+            if policy.due(size, *active_since, Utc::now()) {
+                match rotation::rotate(path) {
+                    Ok(_) => *active_since = None,
+                    Err(e) => eprintln!("Failed to rotate history file {}: {}", self.file_path, e),
+                }
+            }
+        }
+        if active_since.is_none() {
+            *active_since = DateTime::parse_from_rfc3339(&record.timestamp)
+                .ok()
+                .map(|at| at.with_timezone(&Utc));
+        }
+
+        match self.chain.seal(record) {
             Ok(json) => {
                 let open_result = OpenOptions::new()
                     .create(true)
@@ -88,10 +295,28 @@ impl HistoryRecorder {
             }
             Err(e) => eprintln!("Failed to serialize trade record: {}", e),
         }
+        drop(active_since);
+
+        for sink in self.sinks.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            sink.record(record);
+        }
     }
 }
 
+/// Last chain link of a history: its active file, else its newest segment
+fn chain_head(active: &Path) -> Option<hash_chain::ChainLink> {
+    hash_chain::last_link(active).or_else(|| {
+        SegmentIndex::load(active)
+            .ok()?
+            .segments
+            .last()?
+            .chain_head
+            .clone()
+    })
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AnalysisReport {
     pub total_trades: usize,
     pub successful_trades: usize,
@@ -102,54 +327,194 @@ pub struct AnalysisReport {
     pub best_route: Option<String>,
     pub worst_route: Option<String>,
     pub total_volume_usd: String,
+    /// P&L of real trades that reached finalized commitment
+    #[serde(default)]
+    pub finalized_profit_usd: String,
+    /// P&L of real trades not (yet) known to be finalized
+    #[serde(default)]
+    pub unfinalized_profit_usd: String,
+    /// Real trades rolled back by a fork (excluded from the totals above)
+    #[serde(default)]
+    pub reorged_trades: usize,
+    #[serde(default)]
+    pub reverted_profit_usd: String,
+    /// Trades refused because the aggregator route did not cross the
+    /// detected venues (counted in `total_trades` as failures)
+    #[serde(default)]
+    pub route_mismatches: usize,
+    /// P&L (USD) per opportunity source ("detector" or a strategy's name);
+    /// trades recorded before attribution count as "unattributed"
+    #[serde(default)]
+    pub profit_by_source: std::collections::BTreeMap<String, String>,
+    /// The USD amounts above in the accounting currency, when it is not USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accounting: Option<AccountingAmounts>,
+}
+
+impl AnalysisReport {
+    /// Add the USD amounts converted at `rate` (none for USD)
+    pub fn convert(&mut self, rate: &FxRate) {
+        self.accounting = rate.accounting(&[
+            ("total_profit", self.total_profit_usd.as_str()),
+            ("avg_profit", self.avg_profit_usd.as_str()),
+            ("total_volume", self.total_volume_usd.as_str()),
+            ("finalized_profit", self.finalized_profit_usd.as_str()),
+            ("unfinalized_profit", self.unfinalized_profit_usd.as_str()),
+            ("reverted_profit", self.reverted_profit_usd.as_str()),
+        ]);
+    }
+
+    fn empty() -> Self {
+        Self {
+            total_trades: 0,
+            successful_trades: 0,
+            success_rate: 0.0,
+            total_profit_usd: "0.00".to_string(),
+            avg_profit_usd: "0.00".to_string(),
+            best_pair: None,
+            best_route: None,
+            worst_route: None,
+            total_volume_usd: "0.00".to_string(),
+            finalized_profit_usd: "0.00".to_string(),
+            unfinalized_profit_usd: "0.00".to_string(),
+            reorged_trades: 0,
+            reverted_profit_usd: "0.00".to_string(),
+            route_mismatches: 0,
+            profit_by_source: std::collections::BTreeMap::new(),
+            accounting: None,
+        }
+    }
+}
+
+/// A real trade that landed and was not rolled back
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealizedTrade {
+    pub timestamp: DateTime<Utc>,
+    pub pair: String,
+    pub profit_usd: Decimal,
+}
+
+/// P&L of the trades recorded on one UTC day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DailyPnl {
+    /// "YYYY-MM-DD"
+    pub date: String,
+    pub trades: usize,
+    pub successful_trades: usize,
+    pub profit_usd: String,
+    pub volume_usd: String,
+    /// Profit of this day and every earlier day in the range
+    pub cumulative_profit_usd: String,
+}
+
+/// Trade records in `range` across a history file and its rotated
+/// segments, and the latest commitment per signature from update records
+fn read_records(
+    path: &Path,
+    range: &DateRange,
+) -> Result<(Vec<TradeRecord>, std::collections::HashMap<String, String>), std::io::Error> {
+    let mut trades: Vec<TradeRecord> = Vec::new();
+    let mut commitments: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
+
+    rotation::for_each_line(path, range, |line| {
+        if let Ok(record) = serde_json::from_str::<TradeRecord>(line) {
+            if record.trade_type == COMMITMENT_UPDATE {
+                if let (Some(sig), Some(commitment)) = (&record.tx_signature, &record.commitment) {
+                    commitments.insert(sig.clone(), commitment.clone());
+                }
+            } else if range.is_all()
+                || DateTime::parse_from_rfc3339(&record.timestamp)
+                    .is_ok_and(|at| range.contains(at.with_timezone(&Utc)))
+            {
+                trades.push(record);
+            }
+        }
+    })?;
+    Ok((trades, commitments))
+}
+
+fn commitment_of(
+    trade: &TradeRecord,
+    commitments: &std::collections::HashMap<String, String>,
+) -> Option<String> {
+    trade
+        .tx_signature
+        .as_ref()
+        .and_then(|sig| commitments.get(sig))
+        .or(trade.commitment.as_ref())
+        .cloned()
 }
 
 pub struct HistoryAnalyzer;
 
 impl HistoryAnalyzer {
+    /// Successful real and repair trades of a history file and its rotated
+    /// segments in time order, without reorged ones. Simulations and
+    /// failures realize nothing.
+    pub fn realized_trades(file_path: &str) -> Result<Vec<RealizedTrade>, std::io::Error> {
+        let (records, commitments) = read_records(Path::new(file_path), &DateRange::all())?;
+        let mut trades: Vec<RealizedTrade> = records
+            .iter()
+            .filter(|t| (t.trade_type == "REAL" || t.trade_type == REPAIR) && t.success)
+            .filter(|t| commitment_of(t, &commitments).as_deref() != Some(COMMITMENT_REORGED))
+            .filter_map(|t| {
+                Some(RealizedTrade {
+                    timestamp: DateTime::parse_from_rfc3339(&t.timestamp)
+                        .ok()?
+                        .with_timezone(&Utc),
+                    pair: t.pair.clone(),
+                    profit_usd: t.profit_usd.parse().ok()?,
+                })
+            })
+            .collect();
+        trades.sort_by_key(|t| t.timestamp);
+        Ok(trades)
+    }
+
     pub fn analyze(file_path: &str) -> Result<AnalysisReport, std::io::Error> {
-        let path = Path::new(file_path);
-        if !path.exists() {
-            return Ok(AnalysisReport {
-                total_trades: 0,
-                successful_trades: 0,
-                success_rate: 0.0,
-                total_profit_usd: "0.00".to_string(),
-                avg_profit_usd: "0.00".to_string(),
-                best_pair: None,
-                best_route: None,
-                worst_route: None,
-                total_volume_usd: "0.00".to_string(),
-            });
-        }
+        Self::analyze_range(file_path, &DateRange::all())
+    }
 
-        let file = fs::File::open(path)?;
-        let reader = std::io::BufReader::new(file);
-        let mut trades: Vec<TradeRecord> = Vec::new();
+    /// Analyze the trades recorded within `range`, reading only the rotated
+    /// segments that can hold them
+    pub fn analyze_range(file_path: &str, range: &DateRange) -> Result<AnalysisReport, std::io::Error> {
+        let (trades, commitments) = read_records(Path::new(file_path), range)?;
 
-        use std::io::BufRead;
-        for line in reader.lines().map_while(Result::ok) {
-            if let Ok(record) = serde_json::from_str::<TradeRecord>(&line) {
-                trades.push(record);
+        use std::str::FromStr;
+        let profit_of = |t: &TradeRecord| Decimal::from_str(&t.profit_usd).unwrap_or_default();
+        let commitment_of = |t: &TradeRecord| commitment_of(t, &commitments);
+
+        // Reorged trades never happened as far as P&L is concerned
+        let (reorged, trades): (Vec<TradeRecord>, Vec<TradeRecord>) = trades
+            .into_iter()
+            .partition(|t| commitment_of(t).as_deref() == Some(COMMITMENT_REORGED));
+        let reverted_profit: Decimal = reorged.iter().map(profit_of).sum();
+        let mut finalized_profit = Decimal::ZERO;
+        let mut unfinalized_profit = Decimal::ZERO;
+        for trade in &trades {
+            match commitment_of(trade).as_deref() {
+                Some("finalized") => finalized_profit += profit_of(trade),
+                Some(_) => unfinalized_profit += profit_of(trade),
+                None => {}
             }
         }
 
         let total_trades = trades.len();
         if total_trades == 0 {
             return Ok(AnalysisReport {
-                total_trades: 0,
-                successful_trades: 0,
-                success_rate: 0.0,
-                total_profit_usd: "0.00".to_string(),
-                avg_profit_usd: "0.00".to_string(),
-                best_pair: None,
-                best_route: None,
-                worst_route: None,
-                total_volume_usd: "0.00".to_string(),
+                reorged_trades: reorged.len(),
+                reverted_profit_usd: reverted_profit.round_dp(2).to_string(),
+                ..AnalysisReport::empty()
             });
         }
 
         let successful_trades = trades.iter().filter(|t| t.success).count();
+        let route_mismatches = trades
+            .iter()
+            .filter(|t| t.failure_kind == Some(FailureKind::RouteMismatch))
+            .count();
         let success_rate = if total_trades > 0 {
             (successful_trades as f64 / total_trades as f64) * 100.0
         } else {
@@ -162,8 +527,9 @@ impl HistoryAnalyzer {
             std::collections::HashMap::new();
         let mut route_profit: std::collections::HashMap<String, Decimal> =
             std::collections::HashMap::new();
+        let mut source_profit: std::collections::BTreeMap<String, Decimal> =
+            std::collections::BTreeMap::new();
 
-        use std::str::FromStr;
         for trade in &trades {
             if let Ok(profit) = Decimal::from_str(&trade.profit_usd) {
                 total_profit += profit;
@@ -171,6 +537,9 @@ impl HistoryAnalyzer {
 
                 let route = format!("{}->{}", trade.buy_dex, trade.sell_dex);
                 *route_profit.entry(route).or_default() += profit;
+
+                let source = trade.source.as_deref().unwrap_or("unattributed");
+                *source_profit.entry(source.to_string()).or_default() += profit;
             }
             if let Ok(size) = Decimal::from_str(&trade.size_usd) {
                 total_volume += size;
@@ -208,6 +577,168 @@ impl HistoryAnalyzer {
             best_route,
             worst_route,
             total_volume_usd: total_volume.round_dp(2).to_string(),
+            finalized_profit_usd: finalized_profit.round_dp(2).to_string(),
+            unfinalized_profit_usd: unfinalized_profit.round_dp(2).to_string(),
+            reorged_trades: reorged.len(),
+            reverted_profit_usd: reverted_profit.round_dp(2).to_string(),
+            route_mismatches,
+            profit_by_source: source_profit
+                .into_iter()
+                .map(|(source, profit)| (source, profit.round_dp(2).to_string()))
+                .collect(),
+            accounting: None,
         })
     }
+
+    /// Trades recorded within `range`, newest first, each with its latest
+    /// known commitment. Commitment update records are folded in rather
+    /// than listed.
+    pub fn trades(file_path: &str, range: &DateRange) -> Result<Vec<TradeRecord>, std::io::Error> {
+        let (mut trades, commitments) = read_records(Path::new(file_path), range)?;
+        for trade in &mut trades {
+            trade.commitment = commitment_of(trade, &commitments);
+        }
+        // RFC 3339 timestamps in UTC sort chronologically as strings
+        trades.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(trades)
+    }
+
+    /// The trade with record id or transaction signature `id`
+    pub fn find_trade(file_path: &str, id: &str) -> Result<Option<TradeRecord>, std::io::Error> {
+        let trades = Self::trades(file_path, &DateRange::all())?;
+        Ok(trades.into_iter().find(|t| {
+            t.id.is_some_and(|record_id| record_id.to_string() == id)
+                || t.tx_signature.as_deref() == Some(id)
+        }))
+    }
+
+    /// P&L per UTC day of the trades recorded within `range`, oldest day
+    /// first. Reorged trades are left out, as in [`Self::analyze_range`].
+    pub fn daily_pnl(file_path: &str, range: &DateRange) -> Result<Vec<DailyPnl>, std::io::Error> {
+        let (trades, commitments) = read_records(Path::new(file_path), range)?;
+
+        #[derive(Default)]
+        struct Day {
+            trades: usize,
+            successful_trades: usize,
+            profit: Decimal,
+            volume: Decimal,
+        }
+        let mut days: std::collections::BTreeMap<chrono::NaiveDate, Day> =
+            std::collections::BTreeMap::new();
+        for trade in &trades {
+            if commitment_of(trade, &commitments).as_deref() == Some(COMMITMENT_REORGED) {
+                continue;
+            }
+            let Ok(at) = DateTime::parse_from_rfc3339(&trade.timestamp) else {
+                continue;
+            };
+            let day = days.entry(at.with_timezone(&Utc).date_naive()).or_default();
+            day.trades += 1;
+            if trade.success {
+                day.successful_trades += 1;
+            }
+            day.profit += trade.profit_usd.parse::<Decimal>().unwrap_or_default();
+            day.volume += trade.size_usd.parse::<Decimal>().unwrap_or_default();
+        }
+
+        let mut cumulative = Decimal::ZERO;
+        Ok(days
+            .into_iter()
+            .map(|(date, day)| {
+                cumulative += day.profit;
+                DailyPnl {
+                    date: date.format("%Y-%m-%d").to_string(),
+                    trades: day.trades,
+                    successful_trades: day.successful_trades,
+                    profit_usd: day.profit.round_dp(2).to_string(),
+                    volume_usd: day.volume.round_dp(2).to_string(),
+                    cumulative_profit_usd: cumulative.round_dp(2).to_string(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(at: &str, profit: &str, sig: &str, commitment: Option<&str>) -> String {
+        let record = TradeRecord {
+            schema_version: HISTORY_SCHEMA_VERSION,
+            id: Some(uuid::Uuid::new_v4()),
+            timestamp: at.to_string(),
+            session_id: "test".to_string(),
+            trade_type: "REAL".to_string(),
+            pair: "SOL/USDC".to_string(),
+            buy_dex: "Raydium".to_string(),
+            sell_dex: "Orca".to_string(),
+            size_usd: "100".to_string(),
+            profit_usd: profit.to_string(),
+            profit_pct: "0.5".to_string(),
+            tx_signature: Some(sig.to_string()),
+            success: true,
+            error: None,
+            excluded_dexes: Vec::new(),
+            simulated_profit_usd: None,
+            commitment: commitment.map(str::to_string),
+            failure_kind: None,
+            source: None,
+        };
+        serde_json::to_string(&record).unwrap() + "\n"
+    }
+
+    fn update(at: &str, sig: &str, commitment: &str) -> String {
+        format!(
+            "{{\"timestamp\":\"{}\",\"session_id\":\"test\",\"trade_type\":\"{}\",\"pair\":\"SOL/USDC\",\"buy_dex\":\"\",\"sell_dex\":\"\",\"size_usd\":\"0\",\"profit_usd\":\"0\",\"profit_pct\":\"0\",\"tx_signature\":\"{}\",\"success\":true,\"error\":null,\"commitment\":\"{}\"}}\n",
+            at, COMMITMENT_UPDATE, sig, commitment
+        )
+    }
+
+    fn history(lines: &[String]) -> String {
+        let path = std::env::temp_dir().join(format!("history-query-{}.jsonl", uuid::Uuid::new_v4()));
+        fs::write(&path, lines.concat()).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_trades_newest_first_with_latest_commitment() {
+        let path = history(&[
+            trade("2026-03-01T10:00:00Z", "1.5", "sig-a", Some("confirmed")),
+            trade("2026-03-02T10:00:00Z", "2", "sig-b", Some("confirmed")),
+            update("2026-03-02T10:01:00Z", "sig-a", "finalized"),
+        ]);
+
+        let trades = HistoryAnalyzer::trades(&path, &DateRange::all()).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].tx_signature.as_deref(), Some("sig-b"));
+        assert_eq!(trades[1].commitment.as_deref(), Some("finalized"));
+
+        let id = trades[0].id.unwrap().to_string();
+        let found = HistoryAnalyzer::find_trade(&path, &id).unwrap().unwrap();
+        assert_eq!(found.tx_signature.as_deref(), Some("sig-b"));
+        assert!(HistoryAnalyzer::find_trade(&path, "sig-a").unwrap().is_some());
+        assert!(HistoryAnalyzer::find_trade(&path, "sig-z").unwrap().is_none());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_daily_pnl_skips_reorged_trades() {
+        let path = history(&[
+            trade("2026-03-01T10:00:00Z", "1.5", "sig-a", Some("finalized")),
+            trade("2026-03-01T23:00:00Z", "-0.5", "sig-b", Some("confirmed")),
+            trade("2026-03-02T10:00:00Z", "2", "sig-c", Some("confirmed")),
+            trade("2026-03-03T10:00:00Z", "9", "sig-d", Some("confirmed")),
+            update("2026-03-03T10:01:00Z", "sig-d", COMMITMENT_REORGED),
+        ]);
+
+        let days = HistoryAnalyzer::daily_pnl(&path, &DateRange::all()).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].date.as_str(), days[0].trades), ("2026-03-01", 2));
+        assert_eq!(days[0].profit_usd, "1.0");
+        assert_eq!(days[1].volume_usd, "100");
+        assert_eq!(days[1].cumulative_profit_usd, "3.0");
+        fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,295 @@
+//! History Rotation
+//!
+//! A history file rotates once it passes a size or age limit: it is gzipped
+//! into a segment next to it (`history-live.20260301T120000Z.jsonl.gz`) and
+//! listed in an index (`history-live.index.json`) with the time span and
+//! record count of each segment, and a new file is started. Readers stream
+//! the segments oldest first, decompressing as they go, followed by the
+//! active file, and skip segments outside a requested date range.
+
+use crate::hash_chain::{self, ChainLink};
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Commitment updates are appended shortly after their trade, possibly
+/// into the next segment. Segments starting up to this long after a range's
+/// end are still read for them.
+const COMMITMENT_GRACE_HOURS: i64 = 1;
+
+/// When the active history file rotates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Rotate once the file reaches this many bytes (0: never by size)
+    pub max_bytes: u64,
+    /// Rotate once the file's first record is this old
+    pub max_age: Option<Duration>,
+}
+
+impl RotationPolicy {
+    /// `max_mb` megabytes and `max_age_hours` hours, 0 disabling either
+    pub fn new(max_mb: u64, max_age_hours: u64) -> Self {
+        Self {
+            max_bytes: max_mb * 1024 * 1024,
+            max_age: (max_age_hours > 0).then(|| Duration::hours(max_age_hours as i64)),
+        }
+    }
+
+    /// Whether a file of `size` bytes whose first record was written at
+    /// `started` is due for rotation at `now`
+    pub fn due(&self, size: u64, started: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        if size == 0 {
+            return false;
+        }
+        let too_big = self.max_bytes > 0 && size >= self.max_bytes;
+        let too_old = match (self.max_age, started) {
+            (Some(max_age), Some(started)) => now - started >= max_age,
+            _ => false,
+        };
+        too_big || too_old
+    }
+}
+
+/// A rotated, compressed part of a history file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    /// File name, in the directory of the active file
+    pub file: String,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    pub records: usize,
+    /// Chain link of the segment's last record, where a new active file
+    /// continues the chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_head: Option<ChainLink>,
+}
+
+/// Rotated segments of one history file, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentIndex {
+    pub segments: Vec<Segment>,
+}
+
+impl SegmentIndex {
+    /// The index next to `active`; empty if there is none yet
+    pub fn load(active: &Path) -> io::Result<Self> {
+        match fs::read_to_string(index_path(active)) {
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, active: &Path) -> io::Result<()> {
+        let path = index_path(active);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self).map_err(io::Error::other)?)?;
+        fs::rename(tmp, path)
+    }
+}
+
+/// Date range filter; either bound may be open and both are inclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn is_all(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| at >= from) && self.to.is_none_or(|to| at <= to)
+    }
+
+    /// Whether `segment` may hold records in range, or commitment updates
+    /// for them
+    fn wants(&self, segment: &Segment) -> bool {
+        self.from.is_none_or(|from| segment.last >= from)
+            && self
+                .to
+                .is_none_or(|to| segment.first <= to + Duration::hours(COMMITMENT_GRACE_HOURS))
+    }
+}
+
+/// `history-live.jsonl` → `history-live.index.json`
+pub fn index_path(active: &Path) -> PathBuf {
+    active.with_file_name(format!("{}.index.json", stem(active)))
+}
+
+fn stem(active: &Path) -> String {
+    active
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "history".to_string())
+}
+
+fn record_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    #[derive(Deserialize)]
+    struct Stamp {
+        timestamp: String,
+    }
+    let stamp: Stamp = serde_json::from_str(line).ok()?;
+    Some(DateTime::parse_from_rfc3339(&stamp.timestamp).ok()?.with_timezone(&Utc))
+}
+
+/// Timestamp of the first record of a history file
+pub fn first_timestamp(path: &Path) -> Option<DateTime<Utc>> {
+    let file = File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .find_map(|line| record_timestamp(&line))
+}
+
+/// Compress `active` into a new segment, add it to the index and remove
+/// `active`. Returns the segment, or `None` if the file held no records.
+pub fn rotate(active: &Path) -> io::Result<Option<Segment>> {
+    let mut first = None;
+    let mut last = None;
+    let mut records = 0;
+    let mut chain_head = None;
+    match File::open(active) {
+        Ok(file) => {
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                if let Some(at) = record_timestamp(&line) {
+                    first = first.or(Some(at));
+                    last = Some(at);
+                    records += 1;
+                }
+                if let Some(link) = hash_chain::link_of(&line) {
+                    chain_head = Some(link);
+                }
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let (Some(first), Some(last)) = (first, last) else {
+        return Ok(None);
+    };
+
+    let base = format!("{}.{}", stem(active), first.format("%Y%m%dT%H%M%SZ"));
+    let mut name = format!("{}.jsonl.gz", base);
+    let mut n = 1;
+    while active.with_file_name(&name).exists() {
+        n += 1;
+        name = format!("{}-{}.jsonl.gz", base, n);
+    }
+
+    let mut encoder = GzEncoder::new(File::create(active.with_file_name(&name))?, Compression::default());
+    io::copy(&mut File::open(active)?, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+
+    let segment = Segment {
+        file: name,
+        first,
+        last,
+        records,
+        chain_head,
+    };
+    let mut index = SegmentIndex::load(active)?;
+    index.segments.push(segment.clone());
+    index.save(active)?;
+    fs::remove_file(active)?;
+    Ok(Some(segment))
+}
+
+/// Stream every line of the history behind `active`: the rotated segments
+/// `range` may need, oldest first, then the active file. Missing files are
+/// skipped.
+pub fn for_each_line(active: &Path, range: &DateRange, mut f: impl FnMut(&str)) -> io::Result<()> {
+    let mut read = |reader: Box<dyn Read>| -> io::Result<()> {
+        for line in BufReader::new(reader).lines() {
+            f(&line?);
+        }
+        Ok(())
+    };
+
+    for segment in SegmentIndex::load(active)?.segments.iter().filter(|s| range.wants(s)) {
+        match File::open(active.with_file_name(&segment.file)) {
+            Ok(file) => read(Box::new(GzDecoder::new(file)))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                tracing::warn!("History segment {} is listed but missing", segment.file)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    match File::open(active) {
+        Ok(file) => read(Box::new(file)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(at: &str) -> String {
+        format!("{{\"timestamp\":\"{}\",\"trade_type\":\"REAL\"}}\n", at)
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_rotate_and_stream_segments_by_range() {
+        let dir = std::env::temp_dir().join(format!("history-rotation-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let active = dir.join("history-live.jsonl");
+
+        let policy = RotationPolicy::new(1, 24);
+        let started = at("2026-03-01T00:00:00Z");
+        assert!(!policy.due(100, Some(started), started + Duration::hours(23)));
+        assert!(policy.due(100, Some(started), started + Duration::hours(24)));
+        assert!(policy.due(1024 * 1024, None, started));
+        assert!(!policy.due(0, Some(started), started + Duration::days(7)));
+
+        fs::write(&active, line("2026-03-01T00:00:00Z") + &line("2026-03-01T12:00:00Z")).unwrap();
+        let first = rotate(&active).unwrap().unwrap();
+        assert_eq!(first.file, "history-live.20260301T000000Z.jsonl.gz");
+        assert_eq!(first.records, 2);
+        assert!(!active.exists());
+        assert!(rotate(&active).unwrap().is_none());
+
+        fs::write(&active, line("2026-03-05T00:00:00Z")).unwrap();
+        rotate(&active).unwrap().unwrap();
+        fs::write(&active, line("2026-03-09T00:00:00Z")).unwrap();
+        assert_eq!(SegmentIndex::load(&active).unwrap().segments.len(), 2);
+        assert_eq!(first_timestamp(&active), Some(at("2026-03-09T00:00:00Z")));
+
+        let lines = |range: DateRange| {
+            let mut lines = Vec::new();
+            for_each_line(&active, &range, |l| lines.push(l.to_string())).unwrap();
+            lines
+        };
+        assert_eq!(lines(DateRange::all()).len(), 4);
+        // Only the second segment and the active file overlap March 4th on
+        let since = DateRange {
+            from: Some(at("2026-03-04T00:00:00Z")),
+            to: None,
+        };
+        assert_eq!(lines(since), vec![line("2026-03-05T00:00:00Z").trim(), line("2026-03-09T00:00:00Z").trim()]);
+        let until = DateRange {
+            from: None,
+            to: Some(at("2026-03-02T00:00:00Z")),
+        };
+        assert_eq!(lines(until).len(), 3);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
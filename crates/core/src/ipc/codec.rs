@@ -0,0 +1,384 @@
+//! Conversions between domain types and their protobuf representation
+//!
+//! Frames on the wire are a big-endian `u32` length prefix followed by an
+//! encoded [`proto::Envelope`].
+//!
+//! Only the hot-path events (price updates, detected opportunities, executed
+//! and rejected trades) have protobuf messages. Every other [`TradingEvent`]
+//! travels as a `GenericEvent` holding its serde JSON, so readers outside
+//! this crate must parse that JSON to use them.
+
+use chrono::{DateTime, TimeZone, Utc};
+use prost::Message;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::proto::{self, envelope::Body, trading_event::Kind};
+use crate::events::TradingEvent;
+use crate::{
+    ArbitrageError, ArbitrageOpportunity, ArbitrageResult, DexType, OpportunitySource, PriceData, TokenPair,
+};
+
+/// Maximum accepted frame size (guards against corrupt length prefixes)
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+/// A message carried over the IPC channel
+#[derive(Debug, Clone)]
+pub enum IpcMessage {
+    Price(PriceData),
+    Opportunity(ArbitrageOpportunity),
+    Event(TradingEvent),
+}
+
+/// Encode a message into a protobuf envelope (without length prefix)
+pub fn encode_message(message: &IpcMessage) -> Vec<u8> {
+    let body = match message {
+        IpcMessage::Price(price) => Body::Price(price.into()),
+        IpcMessage::Opportunity(opp) => Body::Opportunity(opp.into()),
+        IpcMessage::Event(event) => Body::Event(event.into()),
+    };
+    proto::Envelope { body: Some(body) }.encode_to_vec()
+}
+
+/// Decode a protobuf envelope (without length prefix)
+pub fn decode_message(bytes: &[u8]) -> ArbitrageResult<IpcMessage> {
+    let envelope = proto::Envelope::decode(bytes).map_err(codec_err)?;
+    match envelope.body {
+        Some(Body::Price(price)) => Ok(IpcMessage::Price(price.try_into()?)),
+        Some(Body::Opportunity(opp)) => Ok(IpcMessage::Opportunity(opp.try_into()?)),
+        Some(Body::Event(event)) => Ok(IpcMessage::Event(event.try_into()?)),
+        None => Err(ArbitrageError::Codec("empty envelope".to_string())),
+    }
+}
+
+/// Encode a message as a length-prefixed frame
+pub fn encode_frame(message: &IpcMessage) -> Vec<u8> {
+    let payload = encode_message(message);
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    frame
+}
+
+fn codec_err(e: impl std::fmt::Display) -> ArbitrageError {
+    ArbitrageError::Codec(e.to_string())
+}
+
+fn parse_decimal(field: &str, value: &str) -> ArbitrageResult<Decimal> {
+    Decimal::from_str(value).map_err(|e| ArbitrageError::Codec(format!("{}: {}", field, e)))
+}
+
+fn parse_opt_decimal(field: &str, value: Option<String>) -> ArbitrageResult<Option<Decimal>> {
+    value.map(|v| parse_decimal(field, &v)).transpose()
+}
+
+fn from_millis(ms: i64) -> ArbitrageResult<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms)
+        .single()
+        .ok_or_else(|| ArbitrageError::Codec(format!("invalid timestamp {}", ms)))
+}
+
+impl From<DexType> for proto::Dex {
+    fn from(dex: DexType) -> Self {
+        match dex {
+            DexType::Raydium => proto::Dex::Raydium,
+            DexType::Orca => proto::Dex::Orca,
+            DexType::Jupiter => proto::Dex::Jupiter,
+            DexType::Lifinity => proto::Dex::Lifinity,
+            DexType::Meteora => proto::Dex::Meteora,
+            DexType::Phoenix => proto::Dex::Phoenix,
+            DexType::Sanctum => proto::Dex::Sanctum,
+        }
+    }
+}
+
+impl From<proto::Dex> for DexType {
+    fn from(dex: proto::Dex) -> Self {
+        match dex {
+            proto::Dex::Raydium => DexType::Raydium,
+            proto::Dex::Orca => DexType::Orca,
+            proto::Dex::Jupiter => DexType::Jupiter,
+            proto::Dex::Lifinity => DexType::Lifinity,
+            proto::Dex::Meteora => DexType::Meteora,
+            proto::Dex::Phoenix => DexType::Phoenix,
+            proto::Dex::Sanctum => DexType::Sanctum,
+        }
+    }
+}
+
+impl From<OpportunitySource> for proto::OpportunitySource {
+    fn from(source: OpportunitySource) -> Self {
+        match source {
+            OpportunitySource::Detector => proto::OpportunitySource::Detector,
+            OpportunitySource::Strategy => proto::OpportunitySource::Strategy,
+            OpportunitySource::Manual => proto::OpportunitySource::Manual,
+        }
+    }
+}
+
+impl From<proto::OpportunitySource> for OpportunitySource {
+    fn from(source: proto::OpportunitySource) -> Self {
+        match source {
+            proto::OpportunitySource::Detector => OpportunitySource::Detector,
+            proto::OpportunitySource::Strategy => OpportunitySource::Strategy,
+            proto::OpportunitySource::Manual => OpportunitySource::Manual,
+        }
+    }
+}
+
+fn dex_from_i32(value: i32) -> ArbitrageResult<DexType> {
+    proto::Dex::try_from(value)
+        .map(DexType::from)
+        .map_err(|_| ArbitrageError::Codec(format!("unknown dex {}", value)))
+}
+
+impl From<&PriceData> for proto::PriceData {
+    fn from(price: &PriceData) -> Self {
+        Self {
+            dex: proto::Dex::from(price.dex) as i32,
+            base: price.pair.base.clone(),
+            quote: price.pair.quote.clone(),
+            bid: price.bid.to_string(),
+            ask: price.ask.to_string(),
+            mid_price: price.mid_price.to_string(),
+            volume_24h: price.volume_24h.map(|v| v.to_string()),
+            liquidity: price.liquidity.map(|v| v.to_string()),
+            timestamp_ms: price.timestamp.timestamp_millis(),
+        }
+    }
+}
+
+impl TryFrom<proto::PriceData> for PriceData {
+    type Error = ArbitrageError;
+
+    fn try_from(p: proto::PriceData) -> ArbitrageResult<Self> {
+        Ok(Self {
+            dex: dex_from_i32(p.dex)?,
+            pair: TokenPair::new(p.base, p.quote),
+            bid: parse_decimal("bid", &p.bid)?,
+            ask: parse_decimal("ask", &p.ask)?,
+            mid_price: parse_decimal("mid_price", &p.mid_price)?,
+            volume_24h: parse_opt_decimal("volume_24h", p.volume_24h)?,
+            liquidity: parse_opt_decimal("liquidity", p.liquidity)?,
+            timestamp: from_millis(p.timestamp_ms)?,
+        })
+    }
+}
+
+impl From<&ArbitrageOpportunity> for proto::ArbitrageOpportunity {
+    fn from(opp: &ArbitrageOpportunity) -> Self {
+        Self {
+            id: opp.id.to_string(),
+            base: opp.pair.base.clone(),
+            quote: opp.pair.quote.clone(),
+            buy_dex: proto::Dex::from(opp.buy_dex) as i32,
+            sell_dex: proto::Dex::from(opp.sell_dex) as i32,
+            buy_price: opp.buy_price.to_string(),
+            sell_price: opp.sell_price.to_string(),
+            gross_profit_pct: opp.gross_profit_pct.to_string(),
+            net_profit_pct: opp.net_profit_pct.to_string(),
+            estimated_profit_usd: opp.estimated_profit_usd.map(|v| v.to_string()),
+            recommended_size: opp.recommended_size.map(|v| v.to_string()),
+            detected_at_ms: opp.detected_at.timestamp_millis(),
+            expired_at_ms: opp.expired_at.map(|t| t.timestamp_millis()),
+            buy_quote_at_ms: opp.buy_quote_at.map(|t| t.timestamp_millis()),
+            sell_quote_at_ms: opp.sell_quote_at.map(|t| t.timestamp_millis()),
+            source: proto::OpportunitySource::from(opp.source) as i32,
+            strategy: opp.strategy.clone(),
+        }
+    }
+}
+
+impl TryFrom<proto::ArbitrageOpportunity> for ArbitrageOpportunity {
+    type Error = ArbitrageError;
+
+    fn try_from(p: proto::ArbitrageOpportunity) -> ArbitrageResult<Self> {
+        Ok(Self {
+            id: uuid::Uuid::parse_str(&p.id).map_err(codec_err)?,
+            pair: TokenPair::new(p.base, p.quote),
+            buy_dex: dex_from_i32(p.buy_dex)?,
+            sell_dex: dex_from_i32(p.sell_dex)?,
+            buy_price: parse_decimal("buy_price", &p.buy_price)?,
+            sell_price: parse_decimal("sell_price", &p.sell_price)?,
+            gross_profit_pct: parse_decimal("gross_profit_pct", &p.gross_profit_pct)?,
+            net_profit_pct: parse_decimal("net_profit_pct", &p.net_profit_pct)?,
+            estimated_profit_usd: parse_opt_decimal("estimated_profit_usd", p.estimated_profit_usd)?,
+            recommended_size: parse_opt_decimal("recommended_size", p.recommended_size)?,
+            detected_at: from_millis(p.detected_at_ms)?,
+            expired_at: p.expired_at_ms.map(from_millis).transpose()?,
+            buy_quote_at: p.buy_quote_at_ms.map(from_millis).transpose()?,
+            sell_quote_at: p.sell_quote_at_ms.map(from_millis).transpose()?,
+            source: proto::OpportunitySource::try_from(p.source)
+                .map(OpportunitySource::from)
+                .map_err(|_| ArbitrageError::Codec(format!("unknown opportunity source {}", p.source)))?,
+            strategy: p.strategy,
+        })
+    }
+}
+
+impl From<&TradingEvent> for proto::TradingEvent {
+    fn from(event: &TradingEvent) -> Self {
+        let kind = match event.clone() {
+            TradingEvent::PriceUpdate { pair, price, source, timestamp } => {
+                Kind::PriceUpdate(proto::PriceUpdate { pair, price, source, timestamp })
+            }
+            TradingEvent::OpportunityDetected { id, strategy, expected_profit_bps } => {
+                Kind::OpportunityDetected(proto::OpportunityDetected {
+                    id,
+                    strategy,
+                    expected_profit_bps,
+                })
+            }
+            TradingEvent::TradeExecuted { id, pair, success, profit, execution_time_ms } => {
+                Kind::TradeExecuted(proto::TradeExecuted {
+                    id,
+                    pair,
+                    success,
+                    profit,
+                    execution_time_ms,
+                })
+            }
+            TradingEvent::TradeRejected { id, reason } => {
+                Kind::TradeRejected(proto::TradeRejected { id, reason })
+            }
+            // No protobuf message of their own, see the module docs
+            other => Kind::Generic(proto::GenericEvent {
+                // TradingEvent only holds plain fields, serialization cannot fail
+                json: serde_json::to_string(&other).unwrap_or_default(),
+            }),
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<proto::TradingEvent> for TradingEvent {
+    type Error = ArbitrageError;
+
+    fn try_from(p: proto::TradingEvent) -> ArbitrageResult<Self> {
+        match p.kind {
+            Some(Kind::PriceUpdate(e)) => Ok(TradingEvent::PriceUpdate {
+                pair: e.pair,
+                price: e.price,
+                source: e.source,
+                timestamp: e.timestamp,
+            }),
+            Some(Kind::OpportunityDetected(e)) => Ok(TradingEvent::OpportunityDetected {
+                id: e.id,
+                strategy: e.strategy,
+                expected_profit_bps: e.expected_profit_bps,
+            }),
+            Some(Kind::TradeExecuted(e)) => Ok(TradingEvent::TradeExecuted {
+                id: e.id,
+                pair: e.pair,
+                success: e.success,
+                profit: e.profit,
+                execution_time_ms: e.execution_time_ms,
+            }),
+            Some(Kind::TradeRejected(e)) => Ok(TradingEvent::TradeRejected {
+                id: e.id,
+                reason: e.reason,
+            }),
+            Some(Kind::Generic(e)) => Ok(serde_json::from_str(&e.json)?),
+            None => Err(ArbitrageError::Codec("empty trading event".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_roundtrip_is_exact() {
+        let mut price = PriceData::new(
+            DexType::Orca,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::from_str("100.123456789").unwrap(),
+            Decimal::from_str("100.2").unwrap(),
+        );
+        price.liquidity = Some(Decimal::from(50_000));
+
+        let decoded = match decode_message(&encode_message(&IpcMessage::Price(price.clone()))) {
+            Ok(IpcMessage::Price(p)) => p,
+            other => panic!("unexpected {:?}", other),
+        };
+
+        assert_eq!(decoded.dex, DexType::Orca);
+        assert_eq!(decoded.pair, price.pair);
+        assert_eq!(decoded.bid, price.bid);
+        assert_eq!(decoded.liquidity, price.liquidity);
+        assert_eq!(decoded.volume_24h, None);
+        assert_eq!(
+            decoded.timestamp.timestamp_millis(),
+            price.timestamp.timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn test_event_roundtrip() {
+        let typed = TradingEvent::TradeExecuted {
+            id: "abc".to_string(),
+            pair: "SOL/USDC".to_string(),
+            success: true,
+            profit: 1.5,
+            execution_time_ms: 420,
+        };
+        let generic = TradingEvent::EmergencyStop {
+            reason: "kill switch".to_string(),
+        };
+        match proto::TradingEvent::from(&generic).kind {
+            Some(Kind::Generic(e)) => assert_eq!(e.json, r#"{"EmergencyStop":{"reason":"kill switch"}}"#),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        for event in [typed, generic] {
+            let bytes = encode_message(&IpcMessage::Event(event.clone()));
+            match decode_message(&bytes) {
+                Ok(IpcMessage::Event(decoded)) => {
+                    assert_eq!(
+                        serde_json::to_string(&decoded).unwrap(),
+                        serde_json::to_string(&event).unwrap()
+                    );
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_opportunity_roundtrip_keeps_provenance() {
+        let opp = ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::new(5, 1),
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        }
+        .attributed_to("Statistical Arbitrage");
+
+        let decoded = match decode_message(&encode_message(&IpcMessage::Opportunity(opp.clone()))) {
+            Ok(IpcMessage::Opportunity(o)) => o,
+            other => panic!("unexpected {:?}", other),
+        };
+
+        assert_eq!(decoded.id, opp.id);
+        assert_eq!(decoded.source, OpportunitySource::Strategy);
+        assert_eq!(decoded.source_label(), "Statistical Arbitrage");
+    }
+
+    #[test]
+    fn test_decode_garbage_fails() {
+        assert!(decode_message(&[0xff, 0xff, 0xff]).is_err());
+    }
+}
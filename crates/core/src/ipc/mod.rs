@@ -0,0 +1,9 @@
+//! Protobuf wire format and unix-socket transport for running the fetcher,
+//! detector and executor as separate processes.
+
+pub mod codec;
+pub mod proto;
+#[cfg(unix)]
+pub mod socket;
+
+pub use codec::{decode_message, encode_frame, encode_message, IpcMessage};
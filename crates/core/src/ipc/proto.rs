@@ -0,0 +1,175 @@
+//! Protobuf message definitions mirroring `proto/arb.proto`
+//!
+//! Written by hand with prost derives so the build does not need `protoc`.
+//! Keep field tags in sync with the `.proto` file.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Dex {
+    Raydium = 0,
+    Orca = 1,
+    Jupiter = 2,
+    Lifinity = 3,
+    Meteora = 4,
+    Phoenix = 5,
+    Sanctum = 6,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum OpportunitySource {
+    Detector = 0,
+    Strategy = 1,
+    Manual = 2,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PriceData {
+    #[prost(enumeration = "Dex", tag = "1")]
+    pub dex: i32,
+    #[prost(string, tag = "2")]
+    pub base: String,
+    #[prost(string, tag = "3")]
+    pub quote: String,
+    #[prost(string, tag = "4")]
+    pub bid: String,
+    #[prost(string, tag = "5")]
+    pub ask: String,
+    #[prost(string, tag = "6")]
+    pub mid_price: String,
+    #[prost(string, optional, tag = "7")]
+    pub volume_24h: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub liquidity: Option<String>,
+    #[prost(int64, tag = "9")]
+    pub timestamp_ms: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArbitrageOpportunity {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub base: String,
+    #[prost(string, tag = "3")]
+    pub quote: String,
+    #[prost(enumeration = "Dex", tag = "4")]
+    pub buy_dex: i32,
+    #[prost(enumeration = "Dex", tag = "5")]
+    pub sell_dex: i32,
+    #[prost(string, tag = "6")]
+    pub buy_price: String,
+    #[prost(string, tag = "7")]
+    pub sell_price: String,
+    #[prost(string, tag = "8")]
+    pub gross_profit_pct: String,
+    #[prost(string, tag = "9")]
+    pub net_profit_pct: String,
+    #[prost(string, optional, tag = "10")]
+    pub estimated_profit_usd: Option<String>,
+    #[prost(string, optional, tag = "11")]
+    pub recommended_size: Option<String>,
+    #[prost(int64, tag = "12")]
+    pub detected_at_ms: i64,
+    #[prost(int64, optional, tag = "13")]
+    pub expired_at_ms: Option<i64>,
+    #[prost(int64, optional, tag = "14")]
+    pub buy_quote_at_ms: Option<i64>,
+    #[prost(int64, optional, tag = "15")]
+    pub sell_quote_at_ms: Option<i64>,
+    #[prost(enumeration = "OpportunitySource", tag = "16")]
+    pub source: i32,
+    #[prost(string, optional, tag = "17")]
+    pub strategy: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PriceUpdate {
+    #[prost(string, tag = "1")]
+    pub pair: String,
+    #[prost(double, tag = "2")]
+    pub price: f64,
+    #[prost(string, tag = "3")]
+    pub source: String,
+    #[prost(int64, tag = "4")]
+    pub timestamp: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OpportunityDetected {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub strategy: String,
+    #[prost(double, tag = "3")]
+    pub expected_profit_bps: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TradeExecuted {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub pair: String,
+    #[prost(bool, tag = "3")]
+    pub success: bool,
+    #[prost(double, tag = "4")]
+    pub profit: f64,
+    #[prost(uint64, tag = "5")]
+    pub execution_time_ms: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TradeRejected {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub reason: String,
+}
+
+/// Low-frequency events carried as their JSON representation
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GenericEvent {
+    #[prost(string, tag = "1")]
+    pub json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TradingEvent {
+    #[prost(oneof = "trading_event::Kind", tags = "1, 2, 3, 4, 15")]
+    pub kind: Option<trading_event::Kind>,
+}
+
+pub mod trading_event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Kind {
+        #[prost(message, tag = "1")]
+        PriceUpdate(super::PriceUpdate),
+        #[prost(message, tag = "2")]
+        OpportunityDetected(super::OpportunityDetected),
+        #[prost(message, tag = "3")]
+        TradeExecuted(super::TradeExecuted),
+        #[prost(message, tag = "4")]
+        TradeRejected(super::TradeRejected),
+        #[prost(message, tag = "15")]
+        Generic(super::GenericEvent),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Envelope {
+    #[prost(oneof = "envelope::Body", tags = "1, 2, 3")]
+    pub body: Option<envelope::Body>,
+}
+
+pub mod envelope {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Body {
+        #[prost(message, tag = "1")]
+        Price(super::PriceData),
+        #[prost(message, tag = "2")]
+        Opportunity(super::ArbitrageOpportunity),
+        #[prost(message, tag = "3")]
+        Event(super::TradingEvent),
+    }
+}
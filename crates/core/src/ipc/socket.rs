@@ -0,0 +1,174 @@
+//! Unix domain socket publisher/subscriber for length-prefixed protobuf frames
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use super::codec::{decode_message, encode_frame, IpcMessage, MAX_FRAME_LEN};
+use crate::{ArbitrageError, ArbitrageResult};
+
+/// Publishes messages to every connected subscriber.
+///
+/// Slow subscribers that fall more than `capacity` frames behind skip the
+/// frames they missed rather than blocking the publisher.
+///
+/// Dropping the publisher stops accepting and disconnects every subscriber.
+pub struct IpcPublisher {
+    path: PathBuf,
+    tx: broadcast::Sender<Arc<Vec<u8>>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl IpcPublisher {
+    /// Bind the socket at `path`, replacing any stale socket file
+    pub async fn bind(path: impl AsRef<Path>, capacity: usize) -> ArbitrageResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        let (tx, _) = broadcast::channel::<Arc<Vec<u8>>>(capacity);
+        info!("📡 IPC publisher listening on {:?}", path);
+
+        // A weak handle, so the channel closes when the publisher is dropped
+        let accept_tx = tx.downgrade();
+        let accept_task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let Some(tx) = accept_tx.upgrade() else { break };
+                        tokio::spawn(Self::serve(stream, tx.subscribe()));
+                    }
+                    Err(e) => {
+                        warn!("IPC accept failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { path, tx, accept_task })
+    }
+
+    async fn serve(mut stream: UnixStream, mut rx: broadcast::Receiver<Arc<Vec<u8>>>) {
+        loop {
+            match rx.recv().await {
+                Ok(frame) => {
+                    if stream.write_all(&frame).await.is_err() {
+                        debug!("IPC subscriber disconnected");
+                        return;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("IPC subscriber lagged, skipped {} frames", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Publish a message, returning the number of connected subscribers
+    pub fn publish(&self, message: &IpcMessage) -> usize {
+        self.tx.send(Arc::new(encode_frame(message))).unwrap_or(0)
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for IpcPublisher {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Receives messages from an [`IpcPublisher`]
+pub struct IpcSubscriber {
+    stream: UnixStream,
+}
+
+impl IpcSubscriber {
+    pub async fn connect(path: impl AsRef<Path>) -> ArbitrageResult<Self> {
+        let stream = UnixStream::connect(path.as_ref()).await?;
+        Ok(Self { stream })
+    }
+
+    /// Wait for the next message
+    pub async fn recv(&mut self) -> ArbitrageResult<IpcMessage> {
+        let mut len_buf = [0u8; 4];
+        self.stream.read_exact(&mut len_buf).await?;
+        let len = u32::from_be_bytes(len_buf) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(ArbitrageError::Codec(format!(
+                "frame of {} bytes exceeds limit {}",
+                len, MAX_FRAME_LEN
+            )));
+        }
+
+        let mut payload = vec![0u8; len];
+        self.stream.read_exact(&mut payload).await?;
+        decode_message(&payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DexType, PriceData, TokenPair};
+    use rust_decimal::Decimal;
+
+    #[tokio::test]
+    async fn test_publish_subscribe_over_socket() {
+        let path = std::env::temp_dir().join(format!("arb-ipc-{}.sock", uuid::Uuid::new_v4()));
+        let publisher = IpcPublisher::bind(&path, 16).await.unwrap();
+        let mut subscriber = IpcSubscriber::connect(&path).await.unwrap();
+
+        // Wait until the accept loop has registered the subscriber
+        while publisher.subscriber_count() == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        let price = PriceData::new(
+            DexType::Raydium,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::from(99),
+            Decimal::from(101),
+        );
+        assert_eq!(publisher.publish(&IpcMessage::Price(price)), 1);
+
+        match subscriber.recv().await.unwrap() {
+            IpcMessage::Price(p) => {
+                assert_eq!(p.dex, DexType::Raydium);
+                assert_eq!(p.mid_price, Decimal::from(100));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dropping_publisher_disconnects_subscribers() {
+        let path = std::env::temp_dir().join(format!("arb-ipc-{}.sock", uuid::Uuid::new_v4()));
+        let publisher = IpcPublisher::bind(&path, 16).await.unwrap();
+        let mut subscriber = IpcSubscriber::connect(&path).await.unwrap();
+        while publisher.subscriber_count() == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+
+        drop(publisher);
+        let read = tokio::time::timeout(std::time::Duration::from_secs(5), subscriber.recv()).await;
+        assert!(read.expect("subscriber still connected").is_err());
+        assert!(!path.exists());
+        assert!(IpcSubscriber::connect(&path).await.is_err());
+    }
+}
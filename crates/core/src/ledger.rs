@@ -0,0 +1,284 @@
+//! Investor Ledger
+//!
+//! Pooled capital needs per-investor accounting. The ledger replays the
+//! configured capital events (deposits and withdrawals) together with the
+//! realized trades of a history file, in time order, and attributes each
+//! trade's P&L to the accounts pro-rata to their balances at that moment.
+//! Retained P&L compounds into the balance it was attributed to. The ledger
+//! only reads; it never touches execution.
+
+use crate::error::{ArbitrageError, ArbitrageResult};
+use crate::fx::{AccountingAmounts, FxRate};
+use crate::history::RealizedTrade;
+use chrono::{DateTime, Datelike, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// One investor's capital account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalAccount {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapitalEventKind {
+    Deposit,
+    Withdrawal,
+}
+
+/// Capital moved into or out of an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalEvent {
+    pub account: String,
+    pub kind: CapitalEventKind,
+    pub amount_usd: Decimal,
+    pub at: DateTime<Utc>,
+}
+
+/// One account's activity over a calendar month (UTC)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonthlyStatement {
+    /// "YYYY-MM"
+    pub month: String,
+    pub account: String,
+    pub name: String,
+    pub opening_balance_usd: String,
+    pub deposits_usd: String,
+    pub withdrawals_usd: String,
+    pub pnl_usd: String,
+    pub closing_balance_usd: String,
+    /// Realized trades the account shared in
+    pub trades: usize,
+    /// The USD amounts above in the accounting currency, when it is not USD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounting: Option<AccountingAmounts>,
+}
+
+impl MonthlyStatement {
+    /// Add the USD amounts converted at `rate` (none for USD)
+    pub fn convert(&mut self, rate: &FxRate) {
+        self.accounting = rate.accounting(&[
+            ("opening_balance", self.opening_balance_usd.as_str()),
+            ("deposits", self.deposits_usd.as_str()),
+            ("withdrawals", self.withdrawals_usd.as_str()),
+            ("pnl", self.pnl_usd.as_str()),
+            ("closing_balance", self.closing_balance_usd.as_str()),
+        ]);
+    }
+}
+
+/// Per month and account movements
+#[derive(Debug, Default, Clone, Copy)]
+struct Movements {
+    deposits: Decimal,
+    withdrawals: Decimal,
+    pnl: Decimal,
+    trades: usize,
+}
+
+/// Capital accounts and their deposit/withdrawal history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    pub accounts: Vec<CapitalAccount>,
+    #[serde(default)]
+    pub events: Vec<CapitalEvent>,
+}
+
+impl Ledger {
+    /// Load accounts and events from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> ArbitrageResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let ledger: Self = serde_json::from_str(&content)?;
+        ledger.validate()?;
+        Ok(ledger)
+    }
+
+    fn validate(&self) -> ArbitrageResult<()> {
+        for event in &self.events {
+            if !self.accounts.iter().any(|a| a.id == event.account) {
+                return Err(ArbitrageError::Config(format!(
+                    "capital event for unknown account {}",
+                    event.account
+                )));
+            }
+            if event.amount_usd <= Decimal::ZERO {
+                return Err(ArbitrageError::Config(format!(
+                    "capital event for {} must have a positive amount",
+                    event.account
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replay events and trades and return one statement per account and
+    /// month, from the first month with activity to the last. P&L realized
+    /// while no account holds capital is not attributed.
+    pub fn statements(&self, trades: &[RealizedTrade]) -> ArbitrageResult<Vec<MonthlyStatement>> {
+        enum Item<'a> {
+            Event(&'a CapitalEvent),
+            Trade(&'a RealizedTrade),
+        }
+        let mut items: Vec<(DateTime<Utc>, Item)> = self
+            .events
+            .iter()
+            .map(|e| (e.at, Item::Event(e)))
+            .chain(trades.iter().map(|t| (t.timestamp, Item::Trade(t))))
+            .collect();
+        // Stable: capital events sort ahead of trades at the same instant
+        items.sort_by_key(|(at, _)| *at);
+
+        let mut balances: HashMap<&str, Decimal> = HashMap::new();
+        let mut movements: BTreeMap<(i32, u32), HashMap<&str, Movements>> = BTreeMap::new();
+        for (at, item) in &items {
+            let month = movements.entry((at.year(), at.month())).or_default();
+            match item {
+                Item::Event(event) => {
+                    let balance = balances.entry(event.account.as_str()).or_default();
+                    let moved = month.entry(event.account.as_str()).or_default();
+                    match event.kind {
+                        CapitalEventKind::Deposit => {
+                            *balance += event.amount_usd;
+                            moved.deposits += event.amount_usd;
+                        }
+                        CapitalEventKind::Withdrawal => {
+                            if event.amount_usd > *balance {
+                                return Err(ArbitrageError::Config(format!(
+                                    "withdrawal of {} from {} at {} exceeds its balance of {}",
+                                    event.amount_usd,
+                                    event.account,
+                                    at.to_rfc3339(),
+                                    balance.round_dp(2)
+                                )));
+                            }
+                            *balance -= event.amount_usd;
+                            moved.withdrawals += event.amount_usd;
+                        }
+                    }
+                }
+                Item::Trade(trade) => {
+                    let total: Decimal = balances.values().filter(|b| **b > Decimal::ZERO).sum();
+                    if total <= Decimal::ZERO {
+                        continue;
+                    }
+                    for (account, balance) in balances.iter_mut() {
+                        if *balance <= Decimal::ZERO {
+                            continue;
+                        }
+                        let share = trade.profit_usd * *balance / total;
+                        *balance += share;
+                        let moved = month.entry(account).or_default();
+                        moved.pnl += share;
+                        moved.trades += 1;
+                    }
+                }
+            }
+        }
+
+        let (Some(&first), Some(&last)) = (movements.keys().next(), movements.keys().last()) else {
+            return Ok(Vec::new());
+        };
+        let usd = |value: Decimal| format!("{:.2}", value.round_dp(2));
+        let mut statements = Vec::new();
+        let mut running: HashMap<&str, Decimal> = HashMap::new();
+        let (mut year, mut month) = first;
+        while (year, month) <= last {
+            let moved = movements.get(&(year, month));
+            for account in &self.accounts {
+                let id = account.id.as_str();
+                let m = moved.and_then(|m| m.get(id)).copied();
+                // Accounts appear from their first deposit on
+                let Some(opening) = running.get(id).copied().or(m.map(|_| Decimal::ZERO)) else {
+                    continue;
+                };
+                let m = m.unwrap_or_default();
+                let closing = opening + m.deposits - m.withdrawals + m.pnl;
+                running.insert(id, closing);
+                statements.push(MonthlyStatement {
+                    month: format!("{:04}-{:02}", year, month),
+                    account: account.id.clone(),
+                    name: account.name.clone(),
+                    opening_balance_usd: usd(opening),
+                    deposits_usd: usd(m.deposits),
+                    withdrawals_usd: usd(m.withdrawals),
+                    pnl_usd: usd(m.pnl),
+                    closing_balance_usd: usd(closing),
+                    trades: m.trades,
+                    accounting: None,
+                });
+            }
+            (year, month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        }
+        Ok(statements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn event(account: &str, kind: CapitalEventKind, amount: i64, when: &str) -> CapitalEvent {
+        CapitalEvent {
+            account: account.to_string(),
+            kind,
+            amount_usd: Decimal::from(amount),
+            at: at(when),
+        }
+    }
+
+    fn trade(profit: i64, when: &str) -> RealizedTrade {
+        RealizedTrade {
+            timestamp: at(when),
+            pair: "SOL/USDC".to_string(),
+            profit_usd: Decimal::from(profit),
+        }
+    }
+
+    #[test]
+    fn test_pro_rata_attribution_and_monthly_statements() {
+        use CapitalEventKind::*;
+        let ledger = Ledger {
+            accounts: vec![
+                CapitalAccount { id: "a".into(), name: "Alpha".into() },
+                CapitalAccount { id: "b".into(), name: "Beta".into() },
+            ],
+            events: vec![
+                event("a", Deposit, 3000, "2026-01-01T00:00:00Z"),
+                event("b", Deposit, 1000, "2026-01-01T00:00:00Z"),
+                event("b", Withdrawal, 1010, "2026-03-01T00:00:00Z"),
+            ],
+        };
+        // January: 3:1 split of $40. February: no activity. March: Beta has
+        // withdrawn everything, so Alpha takes all of the $20.
+        let trades = [trade(40, "2026-01-15T12:00:00Z"), trade(20, "2026-03-02T00:00:00Z")];
+        let statements = ledger.statements(&trades).unwrap();
+        assert_eq!(statements.len(), 6);
+
+        let find = |month: &str, account: &str| {
+            statements
+                .iter()
+                .find(|s| s.month == month && s.account == account)
+                .unwrap()
+        };
+        assert_eq!(find("2026-01", "a").pnl_usd, "30.00");
+        assert_eq!(find("2026-01", "b").closing_balance_usd, "1010.00");
+        assert_eq!(find("2026-02", "a").opening_balance_usd, "3030.00");
+        assert_eq!(find("2026-02", "a").trades, 0);
+        assert_eq!(find("2026-03", "b").withdrawals_usd, "1010.00");
+        assert_eq!(find("2026-03", "a").pnl_usd, "20.00");
+        assert_eq!(find("2026-03", "a").closing_balance_usd, "3050.00");
+
+        let mut overdrawn = ledger.clone();
+        overdrawn.events.push(event("a", Withdrawal, 5000, "2026-03-03T00:00:00Z"));
+        assert!(overdrawn.statements(&trades).is_err());
+    }
+}
@@ -9,19 +9,29 @@ pub mod cache;
 pub mod config;
 pub mod database;
 pub mod dex;
+pub mod discovery;
 pub mod error;
 pub mod events;
 pub mod flash_loan;
+pub mod fx;
+pub mod hash_chain;
 pub mod history;
+pub mod ledger;
+pub mod memory;
 pub mod http;
+pub mod ipc;
 pub mod parsers;
 pub mod pathfinding;
 pub mod pricing;
 pub mod rate_limiter;
+pub mod regime;
 pub mod risk;
+pub mod rpc_batch;
 pub mod streaming;
+pub mod tokens;
 pub mod types;
 pub mod secrets;
+pub mod session;
 
 // Phase 8 modules
 pub mod alt;
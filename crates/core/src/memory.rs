@@ -0,0 +1,81 @@
+//! Memory Accounting
+//!
+//! Long-running sessions keep price histories, caches and queues in memory.
+//! Each such component reports an approximate [`MemoryUsage`] (entries held
+//! and bytes they take, counting strings and collection slots but not
+//! allocator overhead), so a component that keeps growing can be told apart
+//! from one that merely sits large. The process resident set size is read
+//! alongside as the ground truth the estimates should add up to.
+
+use serde::Serialize;
+use std::mem::size_of;
+
+/// Approximate memory held by one component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryUsage {
+    /// Items held (prices, samples, records, ...)
+    pub entries: usize,
+    /// Estimated bytes held by those items
+    pub bytes: usize,
+}
+
+impl MemoryUsage {
+    /// `entries` items of `T`, each owning `heap_bytes` more on the heap
+    pub fn of<T>(entries: usize, heap_bytes: usize) -> Self {
+        Self {
+            entries,
+            bytes: entries * (size_of::<T>() + heap_bytes),
+        }
+    }
+
+    /// This usage plus `bytes` not tied to an entry
+    pub fn with_bytes(self, bytes: usize) -> Self {
+        Self {
+            entries: self.entries,
+            bytes: self.bytes + bytes,
+        }
+    }
+}
+
+impl std::ops::Add for MemoryUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            entries: self.entries + other.entries,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+}
+
+impl std::iter::Sum for MemoryUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |a, b| a + b)
+    }
+}
+
+/// Resident set size of this process in bytes (Linux only)
+pub fn resident_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_adds_up() {
+        let prices = MemoryUsage::of::<(u64, u64)>(10, 8);
+        assert_eq!(prices, MemoryUsage { entries: 10, bytes: 240 });
+
+        let total: MemoryUsage = [prices, MemoryUsage::of::<u8>(4, 0).with_bytes(100)]
+            .into_iter()
+            .sum();
+        assert_eq!(total, MemoryUsage { entries: 14, bytes: 344 });
+
+        #[cfg(target_os = "linux")]
+        assert!(resident_bytes().is_some_and(|b| b > 0));
+    }
+}
@@ -6,6 +6,7 @@
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
 
+use crate::memory::MemoryUsage;
 #[cfg(test)]
 #[cfg(test)]
 use crate::TokenPair;
@@ -85,6 +86,18 @@ impl PathFinder {
         }
     }
 
+    /// Approximate memory held by the price graph
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let edges = self.edges.values().map(Vec::len).sum();
+        let strings: usize = self
+            .edges
+            .values()
+            .flatten()
+            .map(|e| e.from_token.capacity() + e.to_token.capacity())
+            .sum();
+        MemoryUsage::of::<TradingEdge>(edges, 0).with_bytes(strings)
+    }
+
     /// Clear all edges and rebuild from fresh price data
     pub fn clear(&mut self) {
         self.edges.clear();
@@ -93,6 +106,14 @@ impl PathFinder {
 
     /// Add a trading edge from price data
     pub fn add_price(&mut self, price: &PriceData) {
+        // Either orientation of a market yields the same two edges
+        let normalized;
+        let price = if price.pair.is_canonical() {
+            price
+        } else {
+            normalized = price.inverted();
+            &normalized
+        };
         let base = price.pair.base.clone();
         let quote = price.pair.quote.clone();
         let fee = price.dex.fee_percentage();
@@ -145,7 +166,7 @@ impl PathFinder {
         );
 
         // Sort by profit (descending)
-        paths.sort_by(|a, b| b.profit_ratio.cmp(&a.profit_ratio));
+        paths.sort_by_key(|p| std::cmp::Reverse(p.profit_ratio));
         paths
     }
 
@@ -226,7 +247,7 @@ impl PathFinder {
                     .all(|(ea, eb)| ea.from_token == eb.from_token && ea.to_token == eb.to_token)
         });
 
-        all_paths.sort_by(|a, b| b.profit_ratio.cmp(&a.profit_ratio));
+        all_paths.sort_by_key(|p| std::cmp::Reverse(p.profit_ratio));
         all_paths
     }
 }
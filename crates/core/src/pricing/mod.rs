@@ -1,2 +1,4 @@
 pub mod hybrid_fetcher;
 pub mod parallel_fetcher;
+pub mod projection;
+pub mod spread_guard;
@@ -1,47 +1,206 @@
+//! Parallel Price Fetching
+//!
+//! Every provider is asked for its prices at once. One slow venue must not
+//! hold up the rest, so each request has a time limit (per venue, with a
+//! default) and the whole fetch has a budget: whatever has not answered
+//! when the budget runs out is left out of the batch.
+//!
+//! With hedging on, a request still running after the venue's p95 latency
+//! gets a second attempt, and the first answer wins. The p95 comes from the
+//! venue's recent successful requests and hedging waits until enough have
+//! been seen.
+
+use crate::config::Config;
 use crate::dex::DexProvider;
-use crate::types::{PriceData, TokenPair};
-use std::sync::Arc;
-use std::time::Instant;
+use crate::error::{ArbitrageError, ArbitrageResult};
+use crate::types::{DexType, PriceData, TokenPair};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::task::JoinSet;
 
+/// Successful request latencies kept per venue for the p95
+const LATENCY_SAMPLES: usize = 100;
+
+/// Latencies seen before a venue's requests are hedged
+const MIN_HEDGE_SAMPLES: usize = 20;
+
+/// Time limits of a fetch
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchTimeouts {
+    /// Limit of one venue's request, unless overridden
+    pub default: Duration,
+    pub per_venue: HashMap<DexType, Duration>,
+    /// Limit of the whole fetch
+    pub budget: Duration,
+    /// Send a second request when the first outlives the venue's p95
+    pub hedge: bool,
+}
+
+impl Default for FetchTimeouts {
+    fn default() -> Self {
+        Self {
+            default: Duration::from_millis(2000),
+            per_venue: HashMap::new(),
+            budget: Duration::from_millis(3000),
+            hedge: false,
+        }
+    }
+}
+
+impl FetchTimeouts {
+    /// `DEX_TIMEOUT_MS`, `PRICE_FETCH_BUDGET_MS` and `DEX_HEDGED_REQUESTS`;
+    /// venue overrides are added with [`Self::with_venue_timeouts`]
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            default: Duration::from_millis(config.dex_timeout_ms),
+            per_venue: HashMap::new(),
+            budget: Duration::from_millis(config.price_fetch_budget_ms),
+            hedge: config.dex_hedged_requests,
+        }
+    }
+
+    /// Override single venues with `dex:ms` pairs, e.g. `phoenix:800`
+    pub fn with_venue_timeouts(mut self, spec: &str) -> ArbitrageResult<Self> {
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, ms) = entry.split_once(':').ok_or_else(|| {
+                ArbitrageError::Config(format!("expected dex:ms, got '{}'", entry))
+            })?;
+            let dex = DexType::all()
+                .iter()
+                .find(|dex| dex.display_name().eq_ignore_ascii_case(name.trim()))
+                .copied()
+                .ok_or_else(|| ArbitrageError::Config(format!("unknown DEX '{}'", name.trim())))?;
+            let ms = u64::from_str(ms.trim())
+                .map_err(|_| ArbitrageError::Config(format!("invalid timeout for {}: '{}'", dex, ms)))?;
+            self.per_venue.insert(dex, Duration::from_millis(ms));
+        }
+        Ok(self)
+    }
+
+    pub fn timeout(&self, dex: DexType) -> Duration {
+        self.per_venue.get(&dex).copied().unwrap_or(self.default)
+    }
+}
+
+/// Timeouts and hedges since the last [`ParallelPriceFetcher::take_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchStats {
+    /// Requests that ran into their venue's timeout or the fetch budget
+    pub timeouts: HashMap<DexType, u64>,
+    /// Second requests sent
+    pub hedges_sent: HashMap<DexType, u64>,
+    /// Second requests that answered first
+    pub hedges_won: HashMap<DexType, u64>,
+}
+
+/// How one venue's request ended
+enum Outcome {
+    Prices { prices: Vec<PriceData>, latency: Duration, hedged: bool, hedge_won: bool },
+    Failed { error: ArbitrageError, hedged: bool },
+    TimedOut { hedged: bool },
+}
+
 pub struct ParallelPriceFetcher {
     dex_providers: Vec<Arc<dyn DexProvider>>,
+    timeouts: FetchTimeouts,
+    latencies: Mutex<HashMap<DexType, VecDeque<Duration>>>,
+    stats: Mutex<FetchStats>,
 }
 
 impl ParallelPriceFetcher {
     pub fn new(providers: Vec<Arc<dyn DexProvider>>) -> Self {
         Self {
             dex_providers: providers,
+            timeouts: FetchTimeouts::default(),
+            latencies: Mutex::new(HashMap::new()),
+            stats: Mutex::new(FetchStats::default()),
         }
     }
 
+    pub fn with_timeouts(mut self, timeouts: FetchTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
     pub async fn fetch_all_prices(&self, pairs: &[TokenPair]) -> Vec<PriceData> {
         let start = Instant::now();
+        let deadline = tokio::time::Instant::now() + self.timeouts.budget;
         let mut join_set = JoinSet::new();
+        let mut pending: Vec<DexType> = Vec::with_capacity(self.dex_providers.len());
 
         // Iterate over providers
         for provider in &self.dex_providers {
             let provider = provider.clone();
             let pairs = pairs.to_vec();
+            let dex = provider.dex_type();
+            let timeout = self.timeouts.timeout(dex);
+            let hedge_after = self.hedge_after(dex);
+            pending.push(dex);
 
             // Spawn concurrent task for each provider
             // We use spawn since we want them to run in parallel
-            join_set.spawn(async move { provider.get_prices(&pairs).await });
+            join_set.spawn(async move {
+                (dex, fetch_one(provider, &pairs, timeout, hedge_after).await)
+            });
         }
 
         let mut all_prices = Vec::new();
 
-        // Collect results
-        while let Some(result) = join_set.join_next().await {
-            match result {
-                Ok(Ok(prices)) => {
-                    all_prices.extend(prices);
-                }
-                Ok(Err(e)) => {
-                    tracing::warn!("Task error in price fetch: {}", e);
+        // Collect results until the budget runs out
+        loop {
+            let result = match tokio::time::timeout_at(deadline, join_set.join_next()).await {
+                Ok(Some(result)) => result,
+                Ok(None) => break,
+                Err(_) => {
+                    tracing::warn!(
+                        budget_ms = self.timeouts.budget.as_millis(),
+                        "Price fetch budget spent, skipping {:?}",
+                        pending
+                    );
+                    for dex in &pending {
+                        *self.lock_stats().timeouts.entry(*dex).or_default() += 1;
+                    }
+                    join_set.abort_all();
+                    break;
                 }
+            };
+            let (dex, outcome) = match result {
+                Ok(done) => done,
                 Err(e) => {
                     tracing::error!("Join error in price fetch: {}", e);
+                    continue;
+                }
+            };
+            pending.retain(|p| *p != dex);
+            let hedged = match &outcome {
+                Outcome::Prices { hedged, .. }
+                | Outcome::Failed { hedged, .. }
+                | Outcome::TimedOut { hedged } => *hedged,
+            };
+            if hedged {
+                *self.lock_stats().hedges_sent.entry(dex).or_default() += 1;
+            }
+            match outcome {
+                Outcome::Prices { prices, latency, hedge_won, .. } => {
+                    if hedge_won {
+                        *self.lock_stats().hedges_won.entry(dex).or_default() += 1;
+                    } else {
+                        self.record_latency(dex, latency);
+                    }
+                    all_prices.extend(prices);
+                }
+                Outcome::Failed { error, .. } => {
+                    tracing::warn!("Task error in price fetch: {}", error);
+                }
+                Outcome::TimedOut { .. } => {
+                    tracing::warn!(
+                        timeout_ms = self.timeouts.timeout(dex).as_millis(),
+                        "{} price request timed out",
+                        dex
+                    );
+                    *self.lock_stats().timeouts.entry(dex).or_default() += 1;
                 }
             }
         }
@@ -54,4 +213,171 @@ impl ParallelPriceFetcher {
 
         all_prices
     }
+
+    /// When a request to `dex` gets a second attempt, if hedging is on and
+    /// enough of its latencies are known
+    pub fn hedge_after(&self, dex: DexType) -> Option<Duration> {
+        if !self.timeouts.hedge {
+            return None;
+        }
+        let latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = latencies.get(&dex).filter(|s| s.len() >= MIN_HEDGE_SAMPLES)?;
+        let mut sorted: Vec<_> = samples.iter().copied().collect();
+        sorted.sort();
+        Some(sorted[(sorted.len() * 95 / 100).min(sorted.len() - 1)])
+    }
+
+    pub fn take_stats(&self) -> FetchStats {
+        std::mem::take(&mut *self.lock_stats())
+    }
+
+    fn record_latency(&self, dex: DexType, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = latencies.entry(dex).or_default();
+        if samples.len() == LATENCY_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    fn lock_stats(&self) -> std::sync::MutexGuard<'_, FetchStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One venue's request within `timeout`, with a second attempt racing the
+/// first after `hedge_after`
+async fn fetch_one(
+    provider: Arc<dyn DexProvider>,
+    pairs: &[TokenPair],
+    timeout: Duration,
+    hedge_after: Option<Duration>,
+) -> Outcome {
+    let started = Instant::now();
+    let mut hedged = false;
+    let request = async {
+        let first = provider.get_prices(pairs);
+        tokio::pin!(first);
+        let Some(delay) = hedge_after else {
+            return (first.await, false);
+        };
+        tokio::select! {
+            result = &mut first => return (result, false),
+            _ = tokio::time::sleep(delay) => {}
+        }
+        hedged = true;
+        tokio::select! {
+            result = &mut first => (result, false),
+            result = provider.get_prices(pairs) => (result, true),
+        }
+    };
+    let result = tokio::time::timeout(timeout, request).await;
+    match result {
+        Ok((Ok(prices), hedge_won)) => Outcome::Prices {
+            prices,
+            latency: started.elapsed(),
+            hedged,
+            hedge_won,
+        },
+        Ok((Err(error), _)) => Outcome::Failed { error, hedged },
+        Err(_) => Outcome::TimedOut { hedged },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::PriceStream;
+    use async_trait::async_trait;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers after the next of its delays, repeating the last
+    struct DelayedProvider {
+        dex: DexType,
+        delays_ms: Vec<u64>,
+        calls: AtomicUsize,
+    }
+
+    impl DelayedProvider {
+        fn new(dex: DexType, delays_ms: &[u64]) -> Arc<Self> {
+            Arc::new(Self {
+                dex,
+                delays_ms: delays_ms.to_vec(),
+                calls: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl DexProvider for DelayedProvider {
+        fn dex_type(&self) -> DexType {
+            self.dex
+        }
+
+        async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let delay = self.delays_ms[call.min(self.delays_ms.len() - 1)];
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Ok(PriceData::new(self.dex, pair.clone(), Decimal::from(150), Decimal::from(150)))
+        }
+
+        async fn subscribe(&self, _pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
+            Err(ArbitrageError::Unknown("no stream".to_string()))
+        }
+
+        async fn health_check(&self) -> ArbitrageResult<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_venues_time_out_and_slow_requests_are_hedged() {
+        let pairs = [TokenPair::new("SOL", "USDC")];
+        let timeouts = FetchTimeouts {
+            default: Duration::from_millis(500),
+            budget: Duration::from_millis(300),
+            ..FetchTimeouts::default()
+        }
+        .with_venue_timeouts("orca:50")
+        .unwrap();
+        assert_eq!(timeouts.timeout(DexType::Orca), Duration::from_millis(50));
+        assert!(FetchTimeouts::default().with_venue_timeouts("nowhere:10").is_err());
+
+        // Orca misses its own limit, Phoenix the fetch budget
+        let fetcher = ParallelPriceFetcher::new(vec![
+            DelayedProvider::new(DexType::Raydium, &[5]),
+            DelayedProvider::new(DexType::Orca, &[200]),
+            DelayedProvider::new(DexType::Phoenix, &[450]),
+        ])
+        .with_timeouts(timeouts);
+        let started = Instant::now();
+        let prices = fetcher.fetch_all_prices(&pairs).await;
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices[0].dex, DexType::Raydium);
+        let stats = fetcher.take_stats();
+        assert_eq!(stats.timeouts.get(&DexType::Orca), Some(&1));
+        assert_eq!(stats.timeouts.get(&DexType::Phoenix), Some(&1));
+        assert!(fetcher.hedge_after(DexType::Raydium).is_none());
+
+        // Once the p95 is known, a request stalling past it is sent again
+        let mut delays = vec![5; MIN_HEDGE_SAMPLES];
+        delays.extend([400, 5]);
+        let fetcher = ParallelPriceFetcher::new(vec![DelayedProvider::new(DexType::Raydium, &delays)])
+            .with_timeouts(FetchTimeouts {
+                hedge: true,
+                ..FetchTimeouts::default()
+            });
+        for _ in 0..MIN_HEDGE_SAMPLES {
+            fetcher.fetch_all_prices(&pairs).await;
+        }
+        assert!(fetcher.hedge_after(DexType::Raydium).is_some());
+        let started = Instant::now();
+        assert_eq!(fetcher.fetch_all_prices(&pairs).await.len(), 1);
+        assert!(started.elapsed() < Duration::from_millis(300));
+        let stats = fetcher.take_stats();
+        assert_eq!(stats.hedges_sent.get(&DexType::Raydium), Some(&1));
+        assert_eq!(stats.hedges_won.get(&DexType::Raydium), Some(&1));
+    }
 }
@@ -0,0 +1,212 @@
+//! Latency-Compensated Price Projection
+//!
+//! A quote from a fast venue (a Phoenix book, a WebSocket feed) is already
+//! old when the trade built on it lands, and during a trend the price has
+//! kept moving in between. Detection prices each leg as it was quoted, so
+//! in trending markets it overestimates the edge a trade will realize.
+//!
+//! The projector keeps an exponentially weighted drift (fractional price
+//! change per second) of each venue and pair's mid price. An opportunity's
+//! legs on projected venues are moved by that drift over the quote's age
+//! plus the execution latency budget, capped at `max_bps`, and its profit
+//! is recomputed with the same fees. Projections only ever lower an
+//! estimate: a drift in the trade's favour is not counted on.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::{ArbitrageError, ArbitrageOpportunity, ArbitrageResult, DexType, PriceData};
+
+/// Price updates needed before a drift is trusted
+const MIN_SAMPLES: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct Drift {
+    mid: Decimal,
+    at: DateTime<Utc>,
+    /// EWMA of the fractional mid change per second
+    rate: f64,
+    samples: u32,
+}
+
+/// Short-horizon drift per venue and pair
+#[derive(Debug, Clone)]
+pub struct PriceProjector {
+    venues: HashSet<DexType>,
+    /// Weight of the newest observation in the drift average
+    alpha: f64,
+    /// Largest projected move, in bps
+    max_bps: Decimal,
+    drifts: HashMap<(DexType, String), Drift>,
+}
+
+impl PriceProjector {
+    /// Projects legs on `venues` (comma-separated DEX names)
+    pub fn new(venues: &str, alpha: f64, max_bps: f64) -> ArbitrageResult<Self> {
+        let venues = venues
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                DexType::all()
+                    .iter()
+                    .find(|dex| dex.display_name().eq_ignore_ascii_case(name))
+                    .copied()
+                    .ok_or_else(|| ArbitrageError::Config(format!("unknown DEX '{}'", name)))
+            })
+            .collect::<ArbitrageResult<_>>()?;
+        Ok(Self {
+            venues,
+            alpha: alpha.clamp(0.01, 1.0),
+            max_bps: Decimal::from_f64(max_bps).unwrap_or_default().max(Decimal::ZERO),
+            drifts: HashMap::new(),
+        })
+    }
+
+    /// Fold a fetched price into its venue and pair's drift
+    pub fn observe(&mut self, price: &PriceData) {
+        if !self.venues.contains(&price.dex) || price.mid_price <= Decimal::ZERO {
+            return;
+        }
+        let key = (price.dex, price.pair.symbol());
+        let Some(drift) = self.drifts.get_mut(&key) else {
+            self.drifts.insert(key, Drift { mid: price.mid_price, at: price.timestamp, rate: 0.0, samples: 0 });
+            return;
+        };
+        let secs = (price.timestamp - drift.at).num_milliseconds() as f64 / 1000.0;
+        // The same quote fetched twice says nothing about drift
+        if secs <= 0.0 {
+            return;
+        }
+        let change = ((price.mid_price - drift.mid) / drift.mid).to_f64().unwrap_or_default();
+        let rate = change / secs;
+        drift.rate = if drift.samples == 0 { rate } else { self.alpha * rate + (1.0 - self.alpha) * drift.rate };
+        drift.samples += 1;
+        drift.mid = price.mid_price;
+        drift.at = price.timestamp;
+    }
+
+    /// Drift of `dex`'s `pair` in bps per second, once enough updates are in
+    pub fn drift_bps_per_sec(&self, dex: DexType, pair: &str) -> Option<f64> {
+        self.drifts
+            .get(&(dex, pair.to_string()))
+            .filter(|d| d.samples >= MIN_SAMPLES)
+            .map(|d| d.rate * 10_000.0)
+    }
+
+    /// Fractional move of `dex`'s `pair` over `horizon`, capped at `max_bps`
+    fn projected_move(&self, dex: DexType, pair: &str, horizon: Duration) -> Option<Decimal> {
+        let bps = self.drift_bps_per_sec(dex, pair)? * horizon.as_secs_f64();
+        let bps = Decimal::from_f64(bps)?.clamp(-self.max_bps, self.max_bps);
+        Some(bps / Decimal::from(10_000))
+    }
+
+    /// `opp` with its legs projected to when a trade started `now` would
+    /// land, `latency` from now. `None` when the projection does not lower
+    /// its net profit.
+    pub fn project(
+        &self,
+        opp: &ArbitrageOpportunity,
+        latency: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<ArbitrageOpportunity> {
+        let pair = opp.pair.symbol();
+        let leg = |dex: DexType, price: Decimal, quoted_at: Option<DateTime<Utc>>| {
+            let age = quoted_at.and_then(|at| (now - at).to_std().ok()).unwrap_or_default();
+            match self.projected_move(dex, &pair, age + latency) {
+                Some(change) => price * (Decimal::ONE + change),
+                None => price,
+            }
+        };
+        let buy_price = leg(opp.buy_dex, opp.buy_price, opp.buy_quote_at);
+        let sell_price = leg(opp.sell_dex, opp.sell_price, opp.sell_quote_at);
+        if buy_price <= Decimal::ZERO {
+            return None;
+        }
+
+        let fees_pct = opp.gross_profit_pct - opp.net_profit_pct;
+        let gross_profit_pct = (sell_price - buy_price) / buy_price * Decimal::from(100);
+        let net_profit_pct = gross_profit_pct - fees_pct;
+        if net_profit_pct >= opp.net_profit_pct {
+            return None;
+        }
+        let estimated_profit_usd = opp.estimated_profit_usd.map(|profit| {
+            if opp.net_profit_pct > Decimal::ZERO {
+                profit * net_profit_pct / opp.net_profit_pct
+            } else {
+                profit
+            }
+        });
+        Some(ArbitrageOpportunity {
+            buy_price,
+            sell_price,
+            gross_profit_pct,
+            net_profit_pct,
+            estimated_profit_usd,
+            ..opp.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OpportunitySource;
+    use crate::TokenPair;
+
+    #[test]
+    fn test_trend_lowers_edge_of_stale_leg() {
+        let pair = TokenPair::new("SOL", "USDC");
+        let mut projector = PriceProjector::new("phoenix", 0.5, 50.0).unwrap();
+        assert!(PriceProjector::new("phoenix,nowhere", 0.5, 50.0).is_err());
+
+        // Phoenix rises 0.1% (10 bps) a second
+        let start = Utc::now() - chrono::Duration::seconds(10);
+        for i in 0..4i64 {
+            let mid = Decimal::from(100) * (Decimal::ONE + Decimal::new(i, 3));
+            let mut price = PriceData::new(DexType::Phoenix, pair.clone(), mid, mid);
+            price.timestamp = start + chrono::Duration::seconds(i);
+            projector.observe(&price);
+        }
+        let drift = projector.drift_bps_per_sec(DexType::Phoenix, "SOL/USDC").unwrap();
+        assert!((drift - 10.0).abs() < 0.1, "{}", drift);
+        assert_eq!(projector.drift_bps_per_sec(DexType::Orca, "SOL/USDC"), None);
+
+        // Buying on Phoenix at a quote 1s old, landing 1s from now: 20 bps dearer
+        let now = Utc::now();
+        let opp = ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: pair.clone(),
+            buy_dex: DexType::Phoenix,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::new(1005, 1),
+            gross_profit_pct: Decimal::new(5, 1),
+            net_profit_pct: Decimal::new(3, 1),
+            estimated_profit_usd: Some(Decimal::from(3)),
+            recommended_size: None,
+            detected_at: now,
+            expired_at: None,
+            buy_quote_at: Some(now - chrono::Duration::seconds(1)),
+            sell_quote_at: Some(now),
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let projected = projector.project(&opp, Duration::from_secs(1), now).unwrap();
+        assert!((projected.buy_price - Decimal::new(1002, 1)).abs() < Decimal::new(1, 3), "{}", projected.buy_price);
+        assert_eq!(projected.sell_price, opp.sell_price);
+        assert!(projected.net_profit_pct < Decimal::new(11, 2) && projected.net_profit_pct > Decimal::new(9, 2));
+        assert!(projected.estimated_profit_usd.unwrap() < Decimal::from(3));
+
+        // Selling on the rising venue is not credited with the drift
+        let reversed = ArbitrageOpportunity {
+            buy_dex: DexType::Orca,
+            sell_dex: DexType::Phoenix,
+            ..opp
+        };
+        assert!(projector.project(&reversed, Duration::from_secs(1), now).is_none());
+    }
+}
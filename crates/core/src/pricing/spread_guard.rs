@@ -0,0 +1,102 @@
+//! Bid/Ask Spread Guard
+//!
+//! A venue quoting a very wide spread (thin pool, stale quote, broken
+//! provider) has a top of book that cannot be traded near the quoted
+//! price. Prices whose spread exceeds the venue's limit are dropped before
+//! they reach detection, so no opportunity is built on them.
+//!
+//! Limits are in percent of the mid price: one default, optionally
+//! overridden per venue with `dex:pct,...` (e.g. `phoenix:2,orca:0.5`).
+//! A limit of 0 disables the guard for that venue. Crossed quotes have a
+//! negative spread and always pass.
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::{ArbitrageError, ArbitrageResult, DexType, PriceData};
+
+/// Maximum bid/ask spread per venue
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpreadGuard {
+    default_max_pct: Option<Decimal>,
+    venue_max_pct: HashMap<DexType, Option<Decimal>>,
+}
+
+impl SpreadGuard {
+    /// Every venue limited to `max_pct` percent (0: unlimited)
+    pub fn new(max_pct: f64) -> Self {
+        Self {
+            default_max_pct: limit(Decimal::from_f64(max_pct).unwrap_or_default()),
+            venue_max_pct: HashMap::new(),
+        }
+    }
+
+    /// Override the limit of single venues with `dex:pct,...`
+    pub fn with_venue_limits(mut self, spec: &str) -> ArbitrageResult<Self> {
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, pct) = entry.split_once(':').ok_or_else(|| {
+                ArbitrageError::Config(format!("expected dex:pct, got '{}'", entry))
+            })?;
+            let dex = DexType::all()
+                .iter()
+                .find(|dex| dex.display_name().eq_ignore_ascii_case(name.trim()))
+                .copied()
+                .ok_or_else(|| ArbitrageError::Config(format!("unknown DEX '{}'", name.trim())))?;
+            let pct = Decimal::from_str(pct.trim())
+                .map_err(|_| ArbitrageError::Config(format!("invalid spread for {}: '{}'", dex, pct)))?;
+            self.venue_max_pct.insert(dex, limit(pct));
+        }
+        Ok(self)
+    }
+
+    /// The spread limit of `dex` in percent, `None` if unlimited
+    pub fn max_spread_pct(&self, dex: DexType) -> Option<Decimal> {
+        self.venue_max_pct
+            .get(&dex)
+            .copied()
+            .unwrap_or(self.default_max_pct)
+    }
+
+    /// Whether `price` is within its venue's spread limit
+    pub fn allows(&self, price: &PriceData) -> bool {
+        self.max_spread_pct(price.dex)
+            .is_none_or(|max| price.spread_percentage() <= max)
+    }
+}
+
+fn limit(pct: Decimal) -> Option<Decimal> {
+    (pct > Decimal::ZERO).then_some(pct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TokenPair;
+
+    #[test]
+    fn test_spread_limits_per_venue() {
+        let pair = TokenPair::new("SOL", "USDC");
+        let quote = |dex, bid: i64, ask: i64| PriceData::new(dex, pair.clone(), Decimal::from(bid), Decimal::from(ask));
+
+        let guard = SpreadGuard::new(1.0)
+            .with_venue_limits("Phoenix:5, orca:0")
+            .unwrap();
+        assert_eq!(guard.max_spread_pct(DexType::Raydium), Some(Decimal::ONE));
+        assert_eq!(guard.max_spread_pct(DexType::Orca), None);
+
+        // 2% wide: too wide by default, fine on Phoenix and on unguarded Orca
+        assert!(!guard.allows(&quote(DexType::Raydium, 99, 101)));
+        assert!(guard.allows(&quote(DexType::Phoenix, 99, 101)));
+        assert!(guard.allows(&quote(DexType::Orca, 90, 110)));
+        assert!(guard.allows(&quote(DexType::Raydium, 1000, 1005)));
+        // Crossed quotes are left to the detector
+        assert!(guard.allows(&quote(DexType::Raydium, 102, 101)));
+
+        assert!(SpreadGuard::new(0.0).allows(&quote(DexType::Jupiter, 50, 150)));
+        assert!(SpreadGuard::new(1.0).with_venue_limits("serum:1").is_err());
+        assert!(SpreadGuard::new(1.0).with_venue_limits("orca").is_err());
+        assert!(SpreadGuard::new(1.0).with_venue_limits("orca:wide").is_err());
+    }
+}
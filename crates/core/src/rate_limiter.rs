@@ -1,18 +1,50 @@
 //! Token-bucket rate limiter for RPC and API calls
 //!
 //! Prevents exceeding rate limits on external services like Solana RPC,
-//! Jupiter API, and Jito block engine.
+//! Jupiter API, and Jito block engine. Callers report response telemetry
+//! (latency, HTTP 429s) so the budget can be tuned at runtime, see [`tuner`].
+//! With the `cache` feature the budget can also be shared with other
+//! processes through Redis, see [`shared`].
 
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+#[cfg(feature = "cache")]
+pub mod shared;
+pub mod tuner;
+
+#[cfg(feature = "cache")]
+use shared::SharedBudget;
+
 /// Rate limiter using a sliding window approach
 #[derive(Debug)]
 pub struct RateLimiter {
     state: Arc<Mutex<RateLimiterState>>,
-    max_requests: usize,
+    max_requests: AtomicUsize,
     window: Duration,
+    telemetry: std::sync::Mutex<Telemetry>,
+    /// Budget shared with other processes, on top of the local one
+    #[cfg(feature = "cache")]
+    shared: Option<SharedBudget>,
+}
+
+/// Responses observed since the telemetry was last taken
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Telemetry {
+    pub responses: u64,
+    /// Responses that were rate limited (HTTP 429)
+    pub throttled: u64,
+    pub total_latency: Duration,
+    /// `acquire` calls that had to wait for a slot
+    pub waits: u64,
+}
+
+impl Telemetry {
+    pub fn avg_latency(&self) -> Option<Duration> {
+        (self.responses > 0).then(|| self.total_latency / self.responses as u32)
+    }
 }
 
 #[derive(Debug)]
@@ -32,11 +64,46 @@ impl RateLimiter {
             state: Arc::new(Mutex::new(RateLimiterState {
                 timestamps: Vec::with_capacity(max_requests),
             })),
-            max_requests,
+            max_requests: AtomicUsize::new(max_requests),
             window,
+            telemetry: std::sync::Mutex::new(Telemetry::default()),
+            #[cfg(feature = "cache")]
+            shared: None,
         }
     }
 
+    /// Also take every request from `budget`, shared with other processes
+    #[cfg(feature = "cache")]
+    pub fn with_shared_budget(mut self, budget: SharedBudget) -> Self {
+        self.shared = Some(budget);
+        self
+    }
+
+    /// Requests allowed per window
+    pub fn max_requests(&self) -> usize {
+        self.max_requests.load(Ordering::Relaxed)
+    }
+
+    /// Change the budget; takes effect for the next acquisition
+    pub fn set_max_requests(&self, max_requests: usize) {
+        self.max_requests.store(max_requests.max(1), Ordering::Relaxed);
+    }
+
+    /// Report the outcome of a request made under this limiter
+    pub fn record_response(&self, latency: Duration, throttled: bool) {
+        let mut telemetry = self.telemetry.lock().unwrap_or_else(|e| e.into_inner());
+        telemetry.responses += 1;
+        telemetry.total_latency += latency;
+        if throttled {
+            telemetry.throttled += 1;
+        }
+    }
+
+    /// Telemetry since the last call, resetting it
+    pub fn take_telemetry(&self) -> Telemetry {
+        std::mem::take(&mut *self.telemetry.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
     /// Create a rate limiter for the given requests per second
     pub fn per_second(requests_per_second: usize) -> Self {
         Self::new(requests_per_second, Duration::from_secs(1))
@@ -44,8 +111,29 @@ impl RateLimiter {
 
     /// Wait until a request slot is available, then acquire it
     ///
-    /// This will block (async) if the rate limit has been reached.
+    /// This will block (async) if the rate limit has been reached, locally
+    /// or in the shared budget.
     pub async fn acquire(&self) {
+        let waited = self.acquire_local().await;
+        #[cfg(feature = "cache")]
+        let waited = {
+            let mut waited = waited;
+            if let Some(shared) = &self.shared {
+                while let Some(wait_time) = shared.take(self.max_requests(), self.window).await {
+                    waited = true;
+                    tokio::time::sleep(wait_time).await;
+                }
+            }
+            waited
+        };
+        if waited {
+            self.telemetry.lock().unwrap_or_else(|e| e.into_inner()).waits += 1;
+        }
+    }
+
+    /// Waits for a local slot; whether it had to wait
+    async fn acquire_local(&self) -> bool {
+        let mut waited = false;
         loop {
             let wait_time = {
                 let mut state = self.state.lock().await;
@@ -56,10 +144,10 @@ impl RateLimiter {
                     .timestamps
                     .retain(|t| now.duration_since(*t) < self.window);
 
-                if state.timestamps.len() < self.max_requests {
+                if state.timestamps.len() < self.max_requests() {
                     // Slot available
                     state.timestamps.push(now);
-                    return;
+                    return waited;
                 }
 
                 // Calculate how long to wait for the oldest request to expire
@@ -75,27 +163,34 @@ impl RateLimiter {
                 }
             };
 
+            waited = true;
             tokio::time::sleep(wait_time).await;
         }
     }
 
     /// Try to acquire a slot without waiting
     ///
-    /// Returns `true` if a slot was acquired, `false` if rate limited.
+    /// Returns `true` if a slot was acquired, `false` if rate limited. A
+    /// local slot taken when the shared budget is spent stays used.
     pub async fn try_acquire(&self) -> bool {
-        let mut state = self.state.lock().await;
-        let now = Instant::now();
+        {
+            let mut state = self.state.lock().await;
+            let now = Instant::now();
 
-        state
-            .timestamps
-            .retain(|t| now.duration_since(*t) < self.window);
+            state
+                .timestamps
+                .retain(|t| now.duration_since(*t) < self.window);
 
-        if state.timestamps.len() < self.max_requests {
+            if state.timestamps.len() >= self.max_requests() {
+                return false;
+            }
             state.timestamps.push(now);
-            true
-        } else {
-            false
         }
+        #[cfg(feature = "cache")]
+        if let Some(shared) = &self.shared {
+            return shared.take(self.max_requests(), self.window).await.is_none();
+        }
+        true
     }
 
     /// Get current request count within the window
@@ -156,4 +251,21 @@ mod tests {
 
         assert!(limiter.try_acquire().await); // Should work now
     }
+
+    #[tokio::test]
+    async fn test_rate_limiter_budget_and_telemetry() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(20));
+        limiter.acquire().await;
+        limiter.acquire().await; // waits for the window
+        limiter.set_max_requests(3);
+        assert!(limiter.try_acquire().await);
+        assert!(limiter.try_acquire().await);
+
+        limiter.record_response(Duration::from_millis(100), false);
+        limiter.record_response(Duration::from_millis(300), true);
+        let telemetry = limiter.take_telemetry();
+        assert_eq!((telemetry.responses, telemetry.throttled, telemetry.waits), (2, 1, 1));
+        assert_eq!(telemetry.avg_latency(), Some(Duration::from_millis(200)));
+        assert_eq!(limiter.take_telemetry(), Telemetry::default());
+    }
 }
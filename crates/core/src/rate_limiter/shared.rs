@@ -0,0 +1,162 @@
+//! Rate limit budgets shared through Redis
+//!
+//! Processes on one host (the bot, the API server) each have their own
+//! limiter, but the upstream counts their requests together. A shared
+//! budget is a fixed-window counter in Redis, keyed by limiter name and
+//! window: every process takes its slots from the same counter, so
+//! together they stay within one budget. The local limiter still applies
+//! first. While Redis is unreachable only the local limit is enforced, and
+//! reconnecting is tried again after a pause.
+
+use crate::config::Config;
+use redis::aio::MultiplexedConnection;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// How long to run on local limits before trying Redis again
+const RECONNECT_AFTER: Duration = Duration::from_secs(30);
+/// Longest wait for a connection or a command before falling back
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// A budget counted in Redis under `<prefix>:<name>`
+pub struct SharedBudget {
+    client: redis::Client,
+    key: String,
+    connection: Mutex<Connection>,
+}
+
+enum Connection {
+    Idle,
+    Open(MultiplexedConnection),
+    /// Redis was unreachable; local limits only until then
+    Failed(Instant),
+}
+
+impl std::fmt::Debug for SharedBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedBudget").field("key", &self.key).finish()
+    }
+}
+
+impl SharedBudget {
+    /// Budget `name` under `prefix` on the Redis at `redis_url`. Nothing is
+    /// connected until the first request.
+    pub fn new(redis_url: &str, prefix: &str, name: &str) -> redis::RedisResult<Self> {
+        Ok(Self {
+            client: redis::Client::open(redis_url)?,
+            key: format!("{}:{}", prefix, name),
+            connection: Mutex::new(Connection::Idle),
+        })
+    }
+
+    /// Budget `name` when `SHARED_RATE_LIMITS` is on and `REDIS_URL` parses
+    pub fn from_config(config: &Config, name: &str) -> Option<Self> {
+        if !config.shared_rate_limits {
+            return None;
+        }
+        match Self::new(&config.redis_url, &config.rate_limit_redis_prefix, name) {
+            Ok(budget) => Some(budget),
+            Err(e) => {
+                warn!("Rate limit {} not shared, invalid REDIS_URL: {}", name, e);
+                None
+            }
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Takes one of `max_requests` slots of the current `window`. Returns
+    /// how long until the next window when the budget is spent, and `None`
+    /// when a slot was taken or Redis is unavailable.
+    pub async fn take(&self, max_requests: usize, window: Duration) -> Option<Duration> {
+        let mut connection = self.connection().await?;
+        let window_ms = window.as_millis().max(1) as u64;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let key = format!("{}:{}", self.key, now_ms / window_ms);
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .incr(&key, 1u64)
+            .pexpire(&key, (window_ms * 2) as i64)
+            .ignore();
+        let counted = pipe.query_async::<_, (u64,)>(&mut connection);
+        match tokio::time::timeout(REDIS_TIMEOUT, counted).await {
+            Ok(Ok((count,))) if count > max_requests as u64 => {
+                Some(Duration::from_millis(window_ms - now_ms % window_ms))
+            }
+            Ok(Ok(_)) => None,
+            Ok(Err(e)) => {
+                self.fail(&e.to_string()).await;
+                None
+            }
+            Err(_) => {
+                self.fail("timed out").await;
+                None
+            }
+        }
+    }
+
+    /// The open connection, connecting if due; `None` runs on local limits
+    async fn connection(&self) -> Option<MultiplexedConnection> {
+        let mut connection = self.connection.lock().await;
+        match &*connection {
+            Connection::Open(open) => return Some(open.clone()),
+            Connection::Failed(at) if at.elapsed() < RECONNECT_AFTER => return None,
+            _ => {}
+        }
+        let connected = tokio::time::timeout(REDIS_TIMEOUT, self.client.get_multiplexed_tokio_connection()).await;
+        match connected {
+            Ok(Ok(open)) => {
+                info!("🔗 Sharing rate limit budget {} through Redis", self.key);
+                *connection = Connection::Open(open.clone());
+                Some(open)
+            }
+            Ok(Err(e)) => {
+                warn!("Redis unavailable for {}, using local limits: {}", self.key, e);
+                *connection = Connection::Failed(Instant::now());
+                None
+            }
+            Err(_) => {
+                warn!("Redis unavailable for {}, using local limits: timed out", self.key);
+                *connection = Connection::Failed(Instant::now());
+                None
+            }
+        }
+    }
+
+    async fn fail(&self, error: &str) {
+        warn!("Shared rate limit {} failed, using local limits: {}", self.key, error);
+        *self.connection.lock().await = Connection::Failed(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limiter::RateLimiter;
+
+    #[tokio::test]
+    async fn test_unreachable_redis_falls_back_to_local_limits() {
+        // Nothing listens on port 1
+        let budget = SharedBudget::new("redis://127.0.0.1:1", "test", "jupiter").unwrap();
+        assert_eq!(budget.key(), "test:jupiter");
+        let limiter = RateLimiter::per_second(2).with_shared_budget(budget);
+
+        assert!(limiter.try_acquire().await);
+        limiter.acquire().await;
+        assert!(!limiter.try_acquire().await);
+        assert_eq!(limiter.take_telemetry().waits, 0);
+
+        let config = Config { shared_rate_limits: false, ..Config::default() };
+        assert!(SharedBudget::from_config(&config, "rpc").is_none());
+        let config = Config { shared_rate_limits: true, redis_url: "not a url".to_string(), ..config };
+        assert!(SharedBudget::from_config(&config, "rpc").is_none());
+        let config = Config { redis_url: "redis://localhost:6379".to_string(), ..config };
+        assert_eq!(SharedBudget::from_config(&config, "rpc").unwrap().key(), "arb:ratelimit:rpc");
+    }
+}
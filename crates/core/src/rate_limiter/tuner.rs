@@ -0,0 +1,135 @@
+//! Rate limit auto-tuning
+//!
+//! Adjusts a limiter's budget from observed telemetry (additive increase,
+//! multiplicative decrease): any HTTP 429 cuts the budget by a quarter, slow
+//! responses take one request off, and a limiter that callers had to wait on
+//! gets one more request per window. Budgets stay within configured bounds
+//! and are persisted so a restart resumes from what was learned.
+
+use super::Telemetry;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Tuning policy for one limiter
+#[derive(Debug, Clone)]
+pub struct BudgetTuner {
+    pub floor: usize,
+    pub ceiling: usize,
+    /// Average latency above which the budget is lowered
+    pub target_latency: Duration,
+    /// Responses needed in an interval before the budget is raised
+    pub min_samples: u64,
+}
+
+impl BudgetTuner {
+    pub fn new(floor: usize, ceiling: usize, target_latency: Duration) -> Self {
+        let floor = floor.max(1);
+        Self {
+            floor,
+            ceiling: ceiling.max(floor),
+            target_latency,
+            min_samples: 10,
+        }
+    }
+
+    pub fn clamp(&self, budget: usize) -> usize {
+        budget.clamp(self.floor, self.ceiling)
+    }
+
+    /// Budget for the next interval given the current one and the telemetry
+    /// observed under it
+    pub fn next_budget(&self, current: usize, telemetry: &Telemetry) -> usize {
+        let next = if telemetry.throttled > 0 {
+            (current * 3 / 4).min(current.saturating_sub(1))
+        } else if telemetry
+            .avg_latency()
+            .is_some_and(|latency| latency > self.target_latency)
+        {
+            current.saturating_sub(1)
+        } else if telemetry.waits > 0 && telemetry.responses >= self.min_samples {
+            current + 1
+        } else {
+            current
+        };
+        self.clamp(next)
+    }
+}
+
+/// Budgets learned per limiter name, saved across restarts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearnedBudgets {
+    pub budgets: BTreeMap<String, usize>,
+}
+
+impl LearnedBudgets {
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.budgets.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, budget: usize) {
+        self.budgets.insert(name.to_string(), budget);
+    }
+
+    /// Load budgets saved with [`LearnedBudgets::save`]
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the budgets to `path` as JSON
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telemetry(responses: u64, throttled: u64, latency_ms: u64, waits: u64) -> Telemetry {
+        Telemetry {
+            responses,
+            throttled,
+            total_latency: Duration::from_millis(latency_ms * responses),
+            waits,
+        }
+    }
+
+    #[test]
+    fn test_next_budget_within_bounds() {
+        let tuner = BudgetTuner::new(2, 12, Duration::from_millis(500));
+
+        // Saturated and healthy: grow by one, up to the ceiling
+        assert_eq!(tuner.next_budget(10, &telemetry(20, 0, 100, 3)), 11);
+        assert_eq!(tuner.next_budget(12, &telemetry(20, 0, 100, 3)), 12);
+        // Not enough demand to learn anything
+        assert_eq!(tuner.next_budget(10, &telemetry(5, 0, 100, 3)), 10);
+        assert_eq!(tuner.next_budget(10, &telemetry(20, 0, 100, 0)), 10);
+        // Slow responses back off gently, 429s sharply, never below the floor
+        assert_eq!(tuner.next_budget(10, &telemetry(20, 0, 800, 3)), 9);
+        assert_eq!(tuner.next_budget(10, &telemetry(20, 1, 100, 3)), 7);
+        assert_eq!(tuner.next_budget(3, &telemetry(20, 1, 100, 0)), 2);
+        assert_eq!(tuner.next_budget(2, &telemetry(20, 5, 100, 0)), 2);
+    }
+
+    #[test]
+    fn test_learned_budgets_roundtrip() {
+        let path = std::env::temp_dir().join(format!("rate_limits_{}.json", uuid::Uuid::new_v4()));
+        let mut budgets = LearnedBudgets::default();
+        budgets.set("rpc", 14);
+        budgets.save(&path).unwrap();
+
+        let loaded = LearnedBudgets::load(&path).unwrap();
+        assert_eq!(loaded.get("rpc"), Some(14));
+        assert_eq!(loaded.get("jupiter"), None);
+        let _ = std::fs::remove_file(path);
+    }
+}
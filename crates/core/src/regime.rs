@@ -0,0 +1,208 @@
+//! Market Regime
+//!
+//! The spread index measures cross-DEX dislocation. For each pair it takes
+//! the gap between the highest and lowest mid price across venues, in basis
+//! points of the average mid. Pairs are then averaged, weighted by notional:
+//! 24h volume, else liquidity, else equally. A jump far above the index's
+//! recent baseline is more often stale or broken data than real opportunity,
+//! so the regime turns `Dislocated` and callers can demand more edge.
+
+use crate::types::PriceData;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Floor for the baseline so a flat market does not make any spread abnormal
+const MIN_BASELINE_BPS: f64 = 1.0;
+
+/// Notional-weighted cross-DEX spread over the tracked pairs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpreadIndex {
+    pub value_bps: f64,
+    /// Pairs quoted by at least two venues
+    pub pairs: usize,
+}
+
+/// Compute the spread index from one tick of prices. Returns `None` when no
+/// pair is quoted by two venues.
+pub fn spread_index(prices: &[PriceData]) -> Option<SpreadIndex> {
+    let mut by_pair: HashMap<String, Vec<&PriceData>> = HashMap::new();
+    for price in prices.iter().filter(|p| p.mid_price > Decimal::ZERO) {
+        by_pair.entry(price.pair.symbol()).or_default().push(price);
+    }
+
+    let mut weighted_sum = 0.0;
+    let mut total_weight = 0.0;
+    let mut pairs = 0;
+    for quotes in by_pair.values().filter(|q| q.len() >= 2) {
+        let mids: Vec<Decimal> = quotes.iter().map(|p| p.mid_price).collect();
+        let (Some(max), Some(min)) = (mids.iter().max(), mids.iter().min()) else {
+            continue;
+        };
+        let avg = mids.iter().sum::<Decimal>() / Decimal::from(mids.len());
+        let spread_bps = ((max - min) / avg * Decimal::from(10_000))
+            .to_f64()
+            .unwrap_or(0.0);
+
+        let notional = |field: fn(&PriceData) -> Option<Decimal>| -> Option<f64> {
+            let total: Decimal = quotes.iter().filter_map(|p| field(p)).sum();
+            total.to_f64().filter(|t| *t > 0.0)
+        };
+        let weight = notional(|p| p.volume_24h)
+            .or_else(|| notional(|p| p.liquidity))
+            .unwrap_or(1.0);
+
+        weighted_sum += spread_bps * weight;
+        total_weight += weight;
+        pairs += 1;
+    }
+
+    (pairs > 0).then(|| SpreadIndex {
+        value_bps: weighted_sum / total_weight,
+        pairs,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketRegime {
+    /// Not enough history for a baseline yet
+    Warming,
+    Normal,
+    /// Index far above its baseline
+    Dislocated,
+}
+
+/// Latest index reading and the regime it implies
+#[derive(Debug, Clone, Serialize)]
+pub struct RegimeSnapshot {
+    pub index_bps: f64,
+    /// Median index over the recent window
+    pub baseline_bps: Option<f64>,
+    /// Index / baseline
+    pub ratio: Option<f64>,
+    pub pairs: usize,
+    pub regime: MarketRegime,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Tracks the spread index against its rolling median
+#[derive(Debug)]
+pub struct RegimeTracker {
+    history: VecDeque<f64>,
+    window: usize,
+    min_samples: usize,
+    /// Index / baseline at or above which the market counts as dislocated
+    dislocation_ratio: f64,
+    latest: Option<RegimeSnapshot>,
+}
+
+impl RegimeTracker {
+    pub fn new(window: usize, dislocation_ratio: f64) -> Self {
+        let window = window.max(1);
+        Self {
+            history: VecDeque::with_capacity(window),
+            window,
+            min_samples: (window / 10).max(1),
+            dislocation_ratio,
+            latest: None,
+        }
+    }
+
+    fn baseline(&self) -> Option<f64> {
+        if self.history.len() < self.min_samples {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.history.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Some(sorted[sorted.len() / 2])
+    }
+
+    /// Record one tick's index. The reading is judged against the baseline
+    /// before it joins the window.
+    pub fn record(&mut self, index: SpreadIndex) -> RegimeSnapshot {
+        let baseline = self.baseline();
+        let ratio = baseline.map(|b| index.value_bps / b.max(MIN_BASELINE_BPS));
+        let regime = match ratio {
+            None => MarketRegime::Warming,
+            Some(r) if r >= self.dislocation_ratio => MarketRegime::Dislocated,
+            Some(_) => MarketRegime::Normal,
+        };
+
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(index.value_bps);
+
+        let snapshot = RegimeSnapshot {
+            index_bps: index.value_bps,
+            baseline_bps: baseline,
+            ratio,
+            pairs: index.pairs,
+            regime,
+            updated_at: Utc::now(),
+        };
+        self.latest = Some(snapshot.clone());
+        snapshot
+    }
+
+    pub fn latest(&self) -> Option<&RegimeSnapshot> {
+        self.latest.as_ref()
+    }
+
+    pub fn regime(&self) -> MarketRegime {
+        self.latest
+            .as_ref()
+            .map_or(MarketRegime::Warming, |s| s.regime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DexType, TokenPair};
+
+    fn price(dex: DexType, pair: &TokenPair, mid: i64, volume: Option<i64>) -> PriceData {
+        let mut price = PriceData::new(dex, pair.clone(), Decimal::from(mid), Decimal::from(mid));
+        price.volume_24h = volume.map(Decimal::from);
+        price
+    }
+
+    #[test]
+    fn test_spread_index_weights_by_notional() {
+        let sol = TokenPair::new("SOL", "USDC");
+        let ray = TokenPair::new("RAY", "USDC");
+        let prices = vec![
+            // 200 bps of 100 on SOL, 3x the notional of RAY's 0 bps
+            price(DexType::Raydium, &sol, 99, Some(150)),
+            price(DexType::Orca, &sol, 101, Some(150)),
+            price(DexType::Raydium, &ray, 2, Some(50)),
+            price(DexType::Orca, &ray, 2, Some(50)),
+            // Single-venue pairs carry no cross-DEX spread
+            price(DexType::Raydium, &TokenPair::new("JUP", "USDC"), 1, Some(1000)),
+        ];
+
+        let index = spread_index(&prices).unwrap();
+        assert_eq!(index.pairs, 2);
+        assert!((index.value_bps - 150.0).abs() < 1e-9);
+        assert!(spread_index(&prices[4..]).is_none());
+    }
+
+    #[test]
+    fn test_regime_flags_jump_over_baseline() {
+        let mut tracker = RegimeTracker::new(10, 3.0);
+        let reading = |value_bps| SpreadIndex { value_bps, pairs: 1 };
+
+        assert_eq!(tracker.record(reading(10.0)).regime, MarketRegime::Warming);
+        for _ in 0..5 {
+            assert_eq!(tracker.record(reading(10.0)).regime, MarketRegime::Normal);
+        }
+        let snapshot = tracker.record(reading(40.0));
+        assert_eq!(snapshot.regime, MarketRegime::Dislocated);
+        assert_eq!(snapshot.baseline_bps, Some(10.0));
+        assert_eq!(tracker.regime(), MarketRegime::Dislocated);
+        assert_eq!(tracker.record(reading(25.0)).regime, MarketRegime::Normal);
+    }
+}
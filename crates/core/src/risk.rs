@@ -3,13 +3,22 @@
 //! Implements position sizing, exposure limits, and circuit breakers
 //! for safe automated trading.
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 use std::sync::Arc;
 use crate::events::{EventBus, TradingEvent};
+use crate::memory::MemoryUsage;
+use crate::DexType;
 
+pub mod canary;
 pub mod circuit_breaker;
+pub mod committed;
+pub mod hedge;
+pub mod impact;
+pub mod returns;
+pub mod unwind;
 pub mod var;
 pub mod volatility;
 
@@ -28,6 +37,26 @@ pub struct RiskConfig {
     pub max_slippage: Decimal,
     /// Cool-down period after a loss (seconds)
     pub loss_cooldown_seconds: i64,
+    /// Maximum exposure routed through any single DEX venue
+    pub max_venue_exposure: Decimal,
+    /// Trade size used while canary mode is active
+    pub canary_size: Decimal,
+    /// Successful canary trades required before full sizes are restored
+    pub canary_required_successes: u32,
+    /// VaR estimation method (falls back to parametric until enough returns are archived)
+    pub var_method: var::VarMethod,
+    /// VaR confidence level
+    pub var_confidence: f64,
+    /// VaR horizon in price update periods
+    pub var_horizon_periods: usize,
+    /// Simulated paths for Monte Carlo VaR
+    pub var_monte_carlo_paths: usize,
+    /// Returns archived per pair for historical / Monte Carlo VaR
+    pub var_archive_len: usize,
+    /// Largest housekeeping trade used to unwind exposure above a lowered limit
+    pub unwind_step_size: Decimal,
+    /// Seconds before capital committed to an unconfirmed trade is released anyway
+    pub commitment_ttl_secs: u64,
 }
 
 impl Default for RiskConfig {
@@ -39,12 +68,47 @@ impl Default for RiskConfig {
             min_profit_threshold: Decimal::new(5, 3), // 0.5% min profit
             max_slippage: Decimal::new(1, 2),       // 1% max slippage
             loss_cooldown_seconds: 300,             // 5 minute cooldown
+            max_venue_exposure: Decimal::from(2500), // $2,500 per venue
+            canary_size: Decimal::from(50),         // $50 canary trades
+            canary_required_successes: 3,           // 3 clean canaries
+            var_method: var::VarMethod::Historical,
+            var_confidence: 0.95,
+            var_horizon_periods: 1,
+            var_monte_carlo_paths: 10_000,
+            var_archive_len: 10_000,
+            unwind_step_size: Decimal::from(250), // $250 housekeeping trades
+            commitment_ttl_secs: 90,              // ~ blockhash lifetime
+        }
+    }
+}
+
+/// The money-denominated limits of a [`RiskConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoneyLimits {
+    pub max_position_size: Decimal,
+    pub max_total_exposure: Decimal,
+    pub max_daily_loss: Decimal,
+    pub max_venue_exposure: Decimal,
+    pub canary_size: Decimal,
+    pub unwind_step_size: Decimal,
+}
+
+impl MoneyLimits {
+    /// Apply `f` to every limit, e.g. a currency conversion
+    pub fn map(self, f: impl Fn(Decimal) -> Decimal) -> Self {
+        Self {
+            max_position_size: f(self.max_position_size),
+            max_total_exposure: f(self.max_total_exposure),
+            max_daily_loss: f(self.max_daily_loss),
+            max_venue_exposure: f(self.max_venue_exposure),
+            canary_size: f(self.canary_size),
+            unwind_step_size: f(self.unwind_step_size),
         }
     }
 }
 
 /// Trade outcome for tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TradeOutcome {
     pub timestamp: DateTime<Utc>,
     pub pair: String,
@@ -57,6 +121,8 @@ pub struct RiskManager {
     config: RiskConfig,
     /// Current open positions by pair
     positions: HashMap<String, Decimal>,
+    /// Current exposure by DEX venue
+    venue_exposure: HashMap<DexType, Decimal>,
     /// Trade history for the current day
     daily_trades: Vec<TradeOutcome>,
     /// Timestamp of last loss
@@ -67,31 +133,80 @@ pub struct RiskManager {
     pub volatility_tracker: volatility::VolatilityTracker,
     /// VaR calculator
     pub var_calculator: var::VarCalculator,
+    /// Archived price returns for historical / Monte Carlo VaR
+    pub return_archive: returns::ReturnArchive,
+    /// Daily VaR forecasts against realized P&L
+    pub var_backtest: var::VarBacktest,
+    /// VaR forecast made at the start of the current day
+    day_var_forecast: Option<Decimal>,
+    /// Canary mode controller
+    pub canary: canary::CanaryController,
+    /// Unwinds exposure above a lowered limit
+    pub unwind: unwind::UnwindScheduler,
+    /// Capital held by trades that have not confirmed yet
+    committed: committed::CommittedCapital,
     /// Event bus for publishing risk events
     event_bus: Option<Arc<EventBus>>,
 }
 
 impl RiskManager {
     pub fn new(config: RiskConfig) -> Self {
+        let canary =
+            canary::CanaryController::new(config.canary_required_successes, config.canary_size);
+        let var_calculator = var::VarCalculator::new(config.var_confidence)
+            .with_horizon(config.var_horizon_periods)
+            .with_monte_carlo_paths(config.var_monte_carlo_paths);
+        let return_archive = returns::ReturnArchive::new(config.var_archive_len);
+        let unwind = unwind::UnwindScheduler::new(config.unwind_step_size);
+        let committed = committed::CommittedCapital::new(std::time::Duration::from_secs(
+            config.commitment_ttl_secs,
+        ));
         Self {
             config,
             positions: HashMap::new(),
+            venue_exposure: HashMap::new(),
             daily_trades: Vec::new(),
             last_loss_time: None,
             circuit_breaker: circuit_breaker::CircuitBreaker::new(3, 5, 300), // 3 failures, 5 successes, 5 min timeout
             volatility_tracker: volatility::VolatilityTracker::new(20), // 20-period moving average
-            var_calculator: var::VarCalculator::new(0.95),              // 95% confidence
+            var_calculator,
+            return_archive,
+            var_backtest: var::VarBacktest::new(250), // ~1 trading year of days
+            day_var_forecast: None,
+            canary,
+            unwind,
+            committed,
             event_bus: None,
         }
     }
 
     pub async fn set_event_bus(&mut self, event_bus: Arc<EventBus>) {
         self.event_bus = Some(event_bus.clone());
+        self.canary.set_event_bus(event_bus.clone());
+        self.unwind.set_event_bus(event_bus.clone());
         self.circuit_breaker.set_event_bus(event_bus).await;
     }
 
     /// Check if a trade is allowed under current risk parameters
-    pub async fn can_trade(&self, _pair: &str, size: Decimal) -> TradeDecision {
+    pub async fn can_trade(&self, pair: &str, size: Decimal) -> TradeDecision {
+        let decision = self.check_limits(pair, size).await;
+
+        // Canary mode caps whatever the limits allowed
+        let approved = match &decision {
+            TradeDecision::Approved { size } => *size,
+            TradeDecision::Reduced { new_size, .. } => *new_size,
+            TradeDecision::Rejected { .. } => return decision,
+        };
+        match self.canary.cap_size(approved) {
+            Some(canary_size) => TradeDecision::Reduced {
+                new_size: canary_size,
+                reason: "Canary mode active - size capped".to_string(),
+            },
+            None => decision,
+        }
+    }
+
+    async fn check_limits(&self, _pair: &str, size: Decimal) -> TradeDecision {
         // Check circuit breaker
         if !self.circuit_breaker.can_execute().await {
             let reason = "Circuit breaker OPEN - trading halted".to_string();
@@ -128,8 +243,8 @@ impl RiskManager {
             };
         }
 
-        // Check total exposure
-        let current_exposure: Decimal = self.positions.values().sum();
+        // Check total exposure, counting capital committed to unconfirmed trades
+        let current_exposure = self.total_exposure() + self.committed.total();
         if current_exposure + size > self.config.max_total_exposure {
             let available = self.config.max_total_exposure - current_exposure;
             if available <= Decimal::ZERO {
@@ -151,6 +266,114 @@ impl RiskManager {
         TradeDecision::Approved { size }
     }
 
+    /// Check a trade routed through the given venues, applying per-venue exposure caps
+    /// on top of the regular `can_trade` checks.
+    pub async fn can_trade_on_venues(
+        &self,
+        pair: &str,
+        size: Decimal,
+        venues: &[DexType],
+    ) -> TradeDecision {
+        let decision = self.can_trade(pair, size).await;
+        let (approved_size, reduced_reason) = match &decision {
+            TradeDecision::Approved { size } => (*size, None),
+            TradeDecision::Reduced { new_size, reason } => (*new_size, Some(reason.clone())),
+            TradeDecision::Rejected { .. } => return decision,
+        };
+
+        // The most loaded venue bounds how much more we can route
+        let available = venues
+            .iter()
+            .map(|venue| self.config.max_venue_exposure - self.venue_exposure_for(*venue))
+            .min()
+            .unwrap_or(approved_size);
+
+        if available <= Decimal::ZERO {
+            let saturated: Vec<String> = venues
+                .iter()
+                .filter(|v| self.venue_exposure_for(**v) >= self.config.max_venue_exposure)
+                .map(|v| v.display_name().to_string())
+                .collect();
+            let reason = format!("Venue exposure limit reached: {}", saturated.join(", "));
+            if let Some(bus) = &self.event_bus {
+                bus.publish(TradingEvent::TradeRejected {
+                    id: "pre-check".to_string(),
+                    reason: reason.clone(),
+                });
+            }
+            return TradeDecision::Rejected { reason };
+        }
+
+        if approved_size > available {
+            return TradeDecision::Reduced {
+                new_size: available,
+                reason: "Size reduced due to venue exposure limit".to_string(),
+            };
+        }
+
+        match reduced_reason {
+            Some(reason) => TradeDecision::Reduced {
+                new_size: approved_size,
+                reason,
+            },
+            None => TradeDecision::Approved {
+                size: approved_size,
+            },
+        }
+    }
+
+    /// Check a trade like `can_trade_on_venues` and, if it is allowed, commit
+    /// the approved size and its venue exposure under `id` in the same step,
+    /// so concurrent approvals cannot oversubscribe the limits. Release with
+    /// [`RiskManager::release_trade`] once the trade confirms or fails.
+    pub async fn reserve_trade(
+        &mut self,
+        id: &str,
+        pair: &str,
+        size: Decimal,
+        venues: &[DexType],
+    ) -> TradeDecision {
+        let decision = self.can_trade_on_venues(pair, size, venues).await;
+        match &decision {
+            TradeDecision::Approved { size } | TradeDecision::Reduced { new_size: size, .. } => {
+                self.commit_trade(id, *size, venues);
+            }
+            TradeDecision::Rejected { .. } => {}
+        }
+        decision
+    }
+
+    /// Commit capital and venue exposure to trade `id` without checking limits
+    pub fn commit_trade(&mut self, id: &str, size: Decimal, venues: &[DexType]) {
+        if let Some(previous) = self.committed.release(id) {
+            self.release_venue_exposure(&previous.venues, previous.size);
+        }
+        self.committed.commit(id, size, venues);
+        self.add_venue_exposure(venues, size);
+    }
+
+    /// Release what trade `id` committed. Returns the released size, or
+    /// `None` if nothing was held (already released or expired).
+    pub fn release_trade(&mut self, id: &str) -> Option<Decimal> {
+        let commitment = self.committed.release(id)?;
+        self.release_venue_exposure(&commitment.venues, commitment.size);
+        Some(commitment.size)
+    }
+
+    /// Release commitments held longer than the TTL, returning them
+    pub fn expire_commitments(&mut self) -> Vec<committed::Commitment> {
+        let expired = self.committed.expire();
+        for commitment in &expired {
+            self.release_venue_exposure(&commitment.venues, commitment.size);
+        }
+        expired
+    }
+
+    /// Capital committed to trades that have not confirmed yet
+    pub fn committed_capital(&self) -> Decimal {
+        self.committed.total()
+    }
+
     /// Calculate optimal position size based on risk parameters and volatility
     pub fn calculate_position_size(
         &self,
@@ -170,18 +393,7 @@ impl RiskManager {
             expected_profit_pct / Decimal::from(2)
         };
 
-        // Adjust for volatility if available
-        if let Some(vol) = self.volatility_tracker.get_volatility(pair) {
-            // If volatility is high (> 1%), reduce size
-            // Simple model: scale = 1 / (1 + volatility_pct)
-            // e.g. vol = 1% -> scale = 1/2 = 0.5
-            // vol = 0.1% -> scale = 1/1.1 = ~0.9
-            let vol_pct = vol * Decimal::from(100);
-            if vol_pct > Decimal::ONE {
-                let vol_scale = Decimal::ONE / vol_pct;
-                profit_factor *= vol_scale;
-            }
-        }
+        profit_factor *= self.volatility_scale(pair);
 
         let calculated = base_size * profit_factor;
 
@@ -191,6 +403,24 @@ impl RiskManager {
             .min(self.config.max_position_size)
     }
 
+    /// Largest position in `pair` the risk limits allow, whatever the edge
+    pub fn position_limit(&self, pair: &str) -> Decimal {
+        self.config.max_position_size * self.volatility_scale(pair)
+    }
+
+    /// Size multiplier for `pair`'s volatility (1 unless it is above 1%)
+    fn volatility_scale(&self, pair: &str) -> Decimal {
+        // If volatility is high (> 1%), reduce size
+        // Simple model: scale = 1 / volatility_pct
+        // e.g. vol = 2% -> scale = 1/2 = 0.5
+        match self.volatility_tracker.get_volatility(pair) {
+            Some(vol) if vol * Decimal::from(100) > Decimal::ONE => {
+                Decimal::ONE / (vol * Decimal::from(100))
+            }
+            _ => Decimal::ONE,
+        }
+    }
+
     /// Record a trade outcome
     pub async fn record_trade(&mut self, outcome: TradeOutcome) {
         if outcome.profit_loss < Decimal::ZERO {
@@ -200,20 +430,83 @@ impl RiskManager {
             self.circuit_breaker.record_success().await;
         }
 
+        self.canary.record_result(outcome.was_successful);
+
         self.daily_trades.push(outcome);
 
         // Check if daily loss limit exceeded
         let daily_pnl: Decimal = self.daily_trades.iter().map(|t| t.profit_loss).sum();
         if daily_pnl < -self.config.max_daily_loss {
-            // Force open circuit breaker
-            // In a real impl, we'd have a specific method for this
-            // For now, we simulate by recording enough failures
-            for _ in 0..3 {
-                self.circuit_breaker.record_failure().await;
+            if let Some(bus) = &self.event_bus {
+                bus.publish(TradingEvent::RiskLimitBreached {
+                    limit_type: "daily_loss".to_string(),
+                    current: (-daily_pnl).to_f64().unwrap_or(0.0),
+                    max: self.config.max_daily_loss.to_f64().unwrap_or(0.0),
+                });
             }
+            self.circuit_breaker.force_open("daily loss limit").await;
         }
     }
 
+    /// Remove a trade recorded with `record_trade` (e.g. rolled back by a
+    /// chain reorg) so it no longer counts toward the daily P&L. Returns the
+    /// removed outcome, or `None` if it is no longer in the current day.
+    pub fn revert_trade(&mut self, pair: &str, timestamp: DateTime<Utc>) -> Option<TradeOutcome> {
+        let index = self
+            .daily_trades
+            .iter()
+            .position(|t| t.pair == pair && t.timestamp == timestamp)?;
+        Some(self.daily_trades.remove(index))
+    }
+
+    pub fn max_total_exposure(&self) -> Decimal {
+        self.config.max_total_exposure
+    }
+
+    /// Change the total exposure limit at runtime. If current positions
+    /// exceed the new limit, an unwind is scheduled and the overage returned.
+    pub fn set_max_total_exposure(&mut self, limit: Decimal) -> Option<Decimal> {
+        self.config.max_total_exposure = limit;
+        self.unwind.schedule(limit, self.total_exposure())
+    }
+
+    pub fn money_limits(&self) -> MoneyLimits {
+        MoneyLimits {
+            max_position_size: self.config.max_position_size,
+            max_total_exposure: self.config.max_total_exposure,
+            max_daily_loss: self.config.max_daily_loss,
+            max_venue_exposure: self.config.max_venue_exposure,
+            canary_size: self.config.canary_size,
+            unwind_step_size: self.config.unwind_step_size,
+        }
+    }
+
+    /// Replace every money limit at once, e.g. after the accounting
+    /// currency's exchange rate moved. As with `set_max_total_exposure`,
+    /// exposure above the new total limit is unwound.
+    pub fn set_money_limits(&mut self, limits: MoneyLimits) -> Option<Decimal> {
+        self.config.max_position_size = limits.max_position_size;
+        self.config.max_daily_loss = limits.max_daily_loss;
+        self.config.max_venue_exposure = limits.max_venue_exposure;
+        self.config.canary_size = limits.canary_size;
+        self.config.unwind_step_size = limits.unwind_step_size;
+        self.canary.set_canary_size(limits.canary_size);
+        self.unwind.set_step_size(limits.unwind_step_size);
+        self.set_max_total_exposure(limits.max_total_exposure)
+    }
+
+    /// Next housekeeping trade of an active unwind
+    pub fn next_unwind_step(&mut self) -> Option<unwind::UnwindStep> {
+        self.unwind.next_step(&self.positions)
+    }
+
+    /// Apply a completed housekeeping trade that reduced `pair` by `reduced` USD
+    pub fn complete_unwind_step(&mut self, pair: &str, reduced: Decimal) {
+        let remaining = self.positions.get(pair).copied().unwrap_or(Decimal::ZERO) - reduced;
+        self.update_position(pair, remaining.max(Decimal::ZERO));
+        self.unwind.complete_step(reduced);
+    }
+
     /// Update position tracking
     pub fn update_position(&mut self, pair: &str, size: Decimal) {
         if size.is_zero() {
@@ -223,6 +516,46 @@ impl RiskManager {
         }
     }
 
+    /// Add exposure to each venue a trade is routed through
+    pub fn add_venue_exposure(&mut self, venues: &[DexType], size: Decimal) {
+        for venue in venues {
+            *self.venue_exposure.entry(*venue).or_insert(Decimal::ZERO) += size;
+        }
+    }
+
+    /// Release exposure previously added with `add_venue_exposure`
+    pub fn release_venue_exposure(&mut self, venues: &[DexType], size: Decimal) {
+        for venue in venues {
+            if let Some(exposure) = self.venue_exposure.get_mut(venue) {
+                *exposure -= size;
+                if *exposure <= Decimal::ZERO {
+                    self.venue_exposure.remove(venue);
+                }
+            }
+        }
+    }
+
+    /// Get current exposure for a single venue
+    pub fn venue_exposure_for(&self, venue: DexType) -> Decimal {
+        self.venue_exposure.get(&venue).copied().unwrap_or(Decimal::ZERO)
+    }
+
+    /// Approximate memory held by today's trade outcomes
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let strings: usize = self.daily_trades.iter().map(|t| t.pair.capacity()).sum();
+        MemoryUsage::of::<TradeOutcome>(self.daily_trades.len(), 0).with_bytes(strings)
+    }
+
+    /// Open positions by pair, in USD
+    pub fn positions(&self) -> &HashMap<String, Decimal> {
+        &self.positions
+    }
+
+    /// Get current exposure breakdown by venue
+    pub fn venue_exposure(&self) -> &HashMap<DexType, Decimal> {
+        &self.venue_exposure
+    }
+
     /// Get current total exposure
     pub fn total_exposure(&self) -> Decimal {
         self.positions.values().sum()
@@ -240,6 +573,71 @@ impl RiskManager {
             let mid_price = (price.bid + price.ask) / Decimal::from(2);
             self.volatility_tracker
                 .update_price(&price.pair.symbol(), mid_price);
+            self.return_archive
+                .record_price(&price.pair.symbol(), mid_price);
+        }
+    }
+
+    /// Portfolio VaR using `method`, falling back to parametric when the
+    /// archive does not have enough returns yet
+    pub fn var_estimate(&self, method: var::VarMethod) -> var::VarEstimate {
+        let pnl = || self.return_archive.portfolio_pnl(&self.positions);
+        let estimate = match method {
+            var::VarMethod::Parametric => None,
+            var::VarMethod::Historical => self.var_calculator.historical(&pnl()),
+            var::VarMethod::MonteCarlo => self
+                .var_calculator
+                .monte_carlo(&pnl(), &mut rand::thread_rng()),
+        };
+        estimate.unwrap_or_else(|| {
+            self.var_calculator
+                .parametric(&self.positions, &self.volatility_tracker)
+        })
+    }
+
+    /// Portfolio VaR using the configured method
+    pub fn portfolio_var(&self) -> var::VarEstimate {
+        self.var_estimate(self.config.var_method)
+    }
+
+    /// Close out the trading day: backtest the VaR forecast made at the start
+    /// of the day against its realized P&L, reset daily statistics and forecast
+    /// VaR for the next day.
+    pub async fn close_day(&mut self, date: NaiveDate) -> DailyRiskDigest {
+        let daily_pnl = self.daily_pnl();
+        let trades = self.daily_trades.len();
+        let worst_trade = self
+            .daily_trades
+            .iter()
+            .filter(|t| t.profit_loss < Decimal::ZERO)
+            .min_by_key(|t| t.profit_loss)
+            .cloned();
+        let day_forecast = self.day_var_forecast;
+        let breached = self
+            .day_var_forecast
+            .map(|forecast| self.var_backtest.record(date, forecast, daily_pnl));
+
+        let estimates = vec![
+            self.var_estimate(var::VarMethod::Parametric),
+            self.var_estimate(var::VarMethod::Historical),
+            self.var_estimate(var::VarMethod::MonteCarlo),
+        ];
+        let var = self.portfolio_var();
+        self.day_var_forecast = Some(var.var);
+        self.reset_daily().await;
+
+        DailyRiskDigest {
+            date,
+            trades,
+            daily_pnl,
+            worst_trade,
+            day_forecast,
+            var_forecast: var,
+            estimates,
+            var_breached: breached,
+            backtest: self
+                .var_backtest
+                .summary(self.var_calculator.confidence_level()),
         }
     }
 
@@ -256,11 +654,35 @@ impl RiskManager {
         !self.circuit_breaker.can_execute().await
     }
 
+    /// State a warm standby needs to take over without losing daily loss
+    /// accounting. In-flight commitments are not included: they settle or
+    /// expire on the primary within their TTL.
+    pub async fn snapshot(&self) -> RiskSnapshot {
+        let (circuit_open, consecutive_failures) = self.circuit_breaker.snapshot().await;
+        RiskSnapshot {
+            positions: self.positions.clone(),
+            venue_exposure: self.venue_exposure.clone(),
+            daily_trades: self.daily_trades.clone(),
+            last_loss_time: self.last_loss_time,
+            circuit_open,
+            consecutive_failures,
+        }
+    }
+
+    /// Replace positions, exposure and the day's trades with `snapshot`
+    pub async fn restore(&mut self, snapshot: RiskSnapshot) {
+        self.positions = snapshot.positions;
+        self.venue_exposure = snapshot.venue_exposure;
+        self.daily_trades = snapshot.daily_trades;
+        self.last_loss_time = snapshot.last_loss_time;
+        self.circuit_breaker
+            .restore(snapshot.circuit_open, snapshot.consecutive_failures)
+            .await;
+    }
+
     /// Get current risk status
     pub async fn status(&self) -> RiskStatus {
-        let var = self
-            .var_calculator
-            .calculate_portfolio_var(&self.positions, &self.volatility_tracker);
+        let var = self.portfolio_var().var;
 
         RiskStatus {
             total_exposure: self.total_exposure(),
@@ -269,10 +691,45 @@ impl RiskManager {
             trades_today: self.daily_trades.len(),
             is_paused: self.is_paused().await,
             positions: self.positions.clone(),
+            venue_exposure: self.venue_exposure.clone(),
+            canary: self.canary.status(),
+            unwind: self.unwind.status(self.total_exposure()),
+            committed: self.committed.status(),
         }
     }
 }
 
+/// End-of-day risk summary for the daily digest
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyRiskDigest {
+    pub date: NaiveDate,
+    pub trades: usize,
+    pub daily_pnl: Decimal,
+    /// The day's largest losing trade
+    pub worst_trade: Option<TradeOutcome>,
+    /// VaR forecast made for the closed day (`None` on the first day)
+    pub day_forecast: Option<Decimal>,
+    /// VaR forecast (configured method) for the next day
+    pub var_forecast: var::VarEstimate,
+    /// Estimates from every method, for comparison
+    pub estimates: Vec<var::VarEstimate>,
+    /// Whether the day's loss exceeded the forecast made for it (`None` on
+    /// the first day, when there was no forecast)
+    pub var_breached: Option<bool>,
+    pub backtest: var::VarBacktestSummary,
+}
+
+/// Replicated risk state, see [`RiskManager::snapshot`]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct RiskSnapshot {
+    pub positions: HashMap<String, Decimal>,
+    pub venue_exposure: HashMap<DexType, Decimal>,
+    pub daily_trades: Vec<TradeOutcome>,
+    pub last_loss_time: Option<DateTime<Utc>>,
+    pub circuit_open: bool,
+    pub consecutive_failures: usize,
+}
+
 /// Decision from risk manager
 #[derive(Debug, Clone)]
 pub enum TradeDecision {
@@ -290,6 +747,10 @@ pub struct RiskStatus {
     pub trades_today: usize,
     pub is_paused: bool,
     pub positions: HashMap<String, Decimal>,
+    pub venue_exposure: HashMap<DexType, Decimal>,
+    pub canary: canary::CanaryStatus,
+    pub unwind: Option<unwind::UnwindStatus>,
+    pub committed: committed::CommittedStatus,
 }
 
 impl Default for RiskManager {
@@ -350,4 +811,165 @@ mod tests {
 
         assert_eq!(manager.total_exposure(), Decimal::from(1500));
     }
+
+    #[tokio::test]
+    async fn test_close_day_backtests_var_forecast() {
+        let mut manager = RiskManager::default();
+        manager.update_position("SOL/USDC", Decimal::from(1000));
+        for i in 0..60 {
+            manager
+                .return_archive
+                .record_return("SOL/USDC", if i % 2 == 0 { 0.01 } else { -0.01 });
+        }
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        // First day has no forecast to backtest
+        let digest = manager.close_day(day).await;
+        assert_eq!(digest.var_breached, None);
+        assert_eq!(digest.var_forecast.method, var::VarMethod::Historical);
+        assert_eq!(digest.var_forecast.var, Decimal::from(10));
+        assert_eq!(digest.estimates.len(), 3);
+
+        manager.record_trade(TradeOutcome {
+            timestamp: Utc::now(),
+            pair: "SOL/USDC".to_string(),
+            profit_loss: Decimal::from(-25),
+            was_successful: false,
+        }).await;
+        let digest = manager.close_day(day.succ_opt().unwrap()).await;
+        assert_eq!(digest.var_breached, Some(true));
+        assert_eq!(digest.daily_pnl, Decimal::from(-25));
+        assert_eq!(digest.backtest.breaches, 1);
+        assert_eq!(manager.daily_pnl(), Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_revert_trade_removes_pnl() {
+        let mut manager = RiskManager::default();
+        let timestamp = Utc::now();
+        for (pair, pnl) in [("SOL/USDC", 12), ("RAY/USDC", 3)] {
+            manager.record_trade(TradeOutcome {
+                timestamp,
+                pair: pair.to_string(),
+                profit_loss: Decimal::from(pnl),
+                was_successful: true,
+            }).await;
+        }
+
+        let reverted = manager.revert_trade("SOL/USDC", timestamp).unwrap();
+        assert_eq!(reverted.profit_loss, Decimal::from(12));
+        assert_eq!(manager.daily_pnl(), Decimal::from(3));
+        assert!(manager.revert_trade("SOL/USDC", timestamp).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lowered_exposure_limit_unwinds() {
+        let mut manager = RiskManager::default();
+        manager.update_position("SOL/USDC", Decimal::from(1500));
+        manager.update_position("RAY/USDC", Decimal::from(500));
+
+        assert_eq!(manager.set_max_total_exposure(Decimal::from(3000)), None);
+        assert_eq!(
+            manager.set_max_total_exposure(Decimal::from(1600)),
+            Some(Decimal::from(400))
+        );
+        assert!(matches!(
+            manager.can_trade("SOL/USDC", Decimal::from(100)).await,
+            TradeDecision::Rejected { .. }
+        ));
+
+        while let Some(step) = manager.next_unwind_step() {
+            assert_eq!(step.pair, "SOL/USDC");
+            manager.complete_unwind_step(&step.pair, step.size);
+        }
+        assert_eq!(manager.total_exposure(), Decimal::from(1600));
+        assert!(manager.status().await.unwind.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_venue_exposure_cap() {
+        let config = RiskConfig {
+            max_venue_exposure: Decimal::from(1200),
+            ..Default::default()
+        };
+        let mut manager = RiskManager::new(config);
+        let route = [DexType::Raydium, DexType::Orca];
+
+        manager.add_venue_exposure(&route, Decimal::from(800));
+        assert_eq!(manager.venue_exposure_for(DexType::Raydium), Decimal::from(800));
+
+        // Raydium has only $400 of headroom left
+        let decision = manager
+            .can_trade_on_venues("SOL/USDC", Decimal::from(500), &[DexType::Raydium, DexType::Jupiter])
+            .await;
+        assert!(matches!(decision, TradeDecision::Reduced { new_size, .. } if new_size == Decimal::from(400)));
+
+        // Untouched venues are unaffected
+        let decision = manager
+            .can_trade_on_venues("SOL/USDC", Decimal::from(500), &[DexType::Jupiter, DexType::Meteora])
+            .await;
+        assert!(matches!(decision, TradeDecision::Approved { .. }));
+
+        manager.add_venue_exposure(&[DexType::Orca], Decimal::from(400));
+        let decision = manager
+            .can_trade_on_venues("SOL/USDC", Decimal::from(100), &route)
+            .await;
+        assert!(matches!(decision, TradeDecision::Rejected { .. }));
+
+        manager.release_venue_exposure(&route, Decimal::from(800));
+        assert_eq!(manager.venue_exposure_for(DexType::Raydium), Decimal::ZERO);
+        assert_eq!(manager.venue_exposure_for(DexType::Orca), Decimal::from(400));
+    }
+
+    #[tokio::test]
+    async fn test_committed_capital_counts_against_exposure() {
+        let mut manager = RiskManager::new(RiskConfig {
+            max_total_exposure: Decimal::from(1500),
+            ..Default::default()
+        });
+        let route = [DexType::Raydium, DexType::Orca];
+
+        // Concurrent approvals share the limit while neither has confirmed
+        let first = manager.reserve_trade("a", "SOL/USDC", Decimal::from(1000), &route).await;
+        assert!(matches!(first, TradeDecision::Approved { .. }));
+        let second = manager.reserve_trade("b", "RAY/USDC", Decimal::from(1000), &route).await;
+        assert!(matches!(second, TradeDecision::Reduced { new_size, .. } if new_size == Decimal::from(500)));
+        assert_eq!(manager.committed_capital(), Decimal::from(1500));
+        assert_eq!(manager.venue_exposure_for(DexType::Raydium), Decimal::from(1500));
+
+        let third = manager.reserve_trade("c", "SOL/USDC", Decimal::from(100), &route).await;
+        assert!(matches!(third, TradeDecision::Rejected { .. }));
+
+        assert_eq!(manager.release_trade("a"), Some(Decimal::from(1000)));
+        assert_eq!(manager.release_trade("a"), None);
+        assert_eq!(manager.committed_capital(), Decimal::from(500));
+        assert_eq!(manager.venue_exposure_for(DexType::Orca), Decimal::from(500));
+        assert_eq!(manager.status().await.committed.pending, 1);
+        assert!(manager.expire_commitments().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_canary_caps_size_until_restored() {
+        let config = RiskConfig {
+            canary_size: Decimal::from(50),
+            canary_required_successes: 1,
+            ..Default::default()
+        };
+        let mut manager = RiskManager::new(config);
+        manager.canary.arm("slippage changed");
+
+        let decision = manager.can_trade("SOL/USDC", Decimal::from(500)).await;
+        assert!(matches!(decision, TradeDecision::Reduced { new_size, .. } if new_size == Decimal::from(50)));
+
+        manager.record_trade(TradeOutcome {
+            timestamp: Utc::now(),
+            pair: "SOL/USDC".to_string(),
+            profit_loss: Decimal::ONE,
+            was_successful: true,
+        }).await;
+
+        let decision = manager.can_trade("SOL/USDC", Decimal::from(500)).await;
+        assert!(matches!(decision, TradeDecision::Approved { .. }));
+        assert!(!manager.status().await.canary.active);
+    }
 }
@@ -0,0 +1,173 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::Arc;
+use crate::events::{EventBus, TradingEvent};
+
+/// Canary mode state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanaryState {
+    Inactive,                     // Full sizes allowed
+    Active { successes: u32 },    // Trading at canary size until enough successes
+}
+
+/// Snapshot of the canary controller for status reporting
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStatus {
+    pub active: bool,
+    pub successes: u32,
+    pub required_successes: u32,
+    pub canary_size: Decimal,
+    pub reason: Option<String>,
+}
+
+/// Forces small canary trades after execution-affecting config changes.
+///
+/// Once armed, every approved size is capped at `canary_size` until
+/// `required_successes` consecutive canary trades succeed. A failed canary
+/// resets the count.
+pub struct CanaryController {
+    state: CanaryState,
+    required_successes: u32,
+    canary_size: Decimal,
+    reason: Option<String>,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+impl CanaryController {
+    pub fn new(required_successes: u32, canary_size: Decimal) -> Self {
+        Self {
+            state: CanaryState::Inactive,
+            required_successes: required_successes.max(1),
+            canary_size,
+            reason: None,
+            event_bus: None,
+        }
+    }
+
+    pub fn set_event_bus(&mut self, bus: Arc<EventBus>) {
+        self.event_bus = Some(bus);
+    }
+
+    pub fn set_canary_size(&mut self, canary_size: Decimal) {
+        self.canary_size = canary_size;
+    }
+
+    /// Enter canary mode (or restart it if already active)
+    pub fn arm(&mut self, reason: &str) {
+        self.state = CanaryState::Active { successes: 0 };
+        self.reason = Some(reason.to_string());
+        tracing::warn!(
+            "🐤 Canary mode ARMED ({}) - next {} trades capped at ${}",
+            reason, self.required_successes, self.canary_size
+        );
+
+        if let Some(bus) = &self.event_bus {
+            bus.publish(TradingEvent::CanaryStarted {
+                reason: reason.to_string(),
+                required_successes: self.required_successes,
+            });
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, CanaryState::Active { .. })
+    }
+
+    pub fn state(&self) -> &CanaryState {
+        &self.state
+    }
+
+    /// Cap a size to the canary size while canary mode is active
+    pub fn cap_size(&self, size: Decimal) -> Option<Decimal> {
+        if self.is_active() && size > self.canary_size {
+            Some(self.canary_size)
+        } else {
+            None
+        }
+    }
+
+    /// Record the outcome of a trade taken while canary mode is active
+    pub fn record_result(&mut self, success: bool) {
+        let CanaryState::Active { successes } = self.state else {
+            return;
+        };
+
+        if !success {
+            tracing::warn!("🐤 Canary trade failed - restarting canary count");
+            self.state = CanaryState::Active { successes: 0 };
+            return;
+        }
+
+        let successes = successes + 1;
+        if successes >= self.required_successes {
+            self.state = CanaryState::Inactive;
+            self.reason = None;
+            tracing::info!("🐤 Canary mode COMPLETE - full sizes restored");
+
+            if let Some(bus) = &self.event_bus {
+                bus.publish(TradingEvent::CanaryCompleted { successes });
+            }
+        } else {
+            self.state = CanaryState::Active { successes };
+        }
+    }
+
+    pub fn status(&self) -> CanaryStatus {
+        let successes = match self.state {
+            CanaryState::Active { successes } => successes,
+            CanaryState::Inactive => 0,
+        };
+
+        CanaryStatus {
+            active: self.is_active(),
+            successes,
+            required_successes: self.required_successes,
+            canary_size: self.canary_size,
+            reason: self.reason.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canary_lifecycle() {
+        let mut canary = CanaryController::new(2, Decimal::from(25));
+        assert_eq!(canary.cap_size(Decimal::from(500)), None);
+
+        canary.arm("slippage changed");
+        assert_eq!(canary.cap_size(Decimal::from(500)), Some(Decimal::from(25)));
+        assert_eq!(canary.cap_size(Decimal::from(10)), None);
+
+        canary.record_result(true);
+        assert!(canary.is_active());
+
+        // A failure restarts the count
+        canary.record_result(false);
+        assert_eq!(*canary.state(), CanaryState::Active { successes: 0 });
+
+        canary.record_result(true);
+        canary.record_result(true);
+        assert!(!canary.is_active());
+        assert_eq!(canary.cap_size(Decimal::from(500)), None);
+    }
+
+    #[tokio::test]
+    async fn test_canary_events() {
+        let bus = Arc::new(EventBus::new(16));
+        let mut rx = bus.subscribe();
+        let mut canary = CanaryController::new(1, Decimal::from(25));
+        canary.set_event_bus(bus);
+
+        canary.arm("config reload");
+        canary.record_result(true);
+
+        assert!(matches!(rx.recv().await.unwrap(), TradingEvent::CanaryStarted { .. }));
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            TradingEvent::CanaryCompleted { successes: 1 }
+        ));
+    }
+}
@@ -91,6 +91,40 @@ impl CircuitBreaker {
         }
     }
 
+    /// Open the breaker immediately, regardless of the failure count
+    pub async fn force_open(&self, reason: &str) {
+        *self.last_failure_time.write().await = Some(Instant::now());
+        let mut state = self.state.write().await;
+        let old_state = format!("{:?}", *state);
+        *state = CircuitState::Open;
+        tracing::error!("Circuit breaker OPEN - trading halted ({})", reason);
+
+        if let Some(bus) = self.event_bus.read().await.as_ref() {
+            bus.publish(TradingEvent::CircuitBreakerStateChanged {
+                old_state,
+                new_state: "Open".to_string(),
+            });
+        }
+    }
+
+    /// Whether the breaker is open, and the current failure streak
+    pub async fn snapshot(&self) -> (bool, usize) {
+        let open = matches!(*self.state.read().await, CircuitState::Open);
+        (open, *self.consecutive_failures.read().await)
+    }
+
+    /// Take over a replicated state; opening here restarts the timeout
+    pub async fn restore(&self, open: bool, consecutive_failures: usize) {
+        *self.consecutive_failures.write().await = consecutive_failures;
+        let is_open = matches!(*self.state.read().await, CircuitState::Open);
+        if open && !is_open {
+            self.force_open("replicated from primary").await;
+        } else if !open && is_open {
+            *self.state.write().await = CircuitState::Closed;
+            tracing::info!("Circuit breaker CLOSED - replicated from primary");
+        }
+    }
+
     pub async fn can_execute(&self) -> bool {
         let mut state = self.state.write().await;
 
@@ -0,0 +1,122 @@
+//! Committed capital
+//!
+//! Capital approved for a trade is spoken for until the transaction confirms
+//! or fails, even though no position exists yet. Each approval commits its
+//! size (and venue exposure) under the trade's id; it counts against the
+//! exposure limit until released. Commitments older than the TTL, roughly a
+//! blockhash lifetime, are dropped so a hung execution cannot lock capital
+//! forever.
+
+use crate::types::DexType;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Capital held for one in-flight trade
+#[derive(Debug, Clone)]
+pub struct Commitment {
+    pub id: String,
+    pub size: Decimal,
+    pub venues: Vec<DexType>,
+    pub committed_at: Instant,
+}
+
+/// Snapshot for status reporting
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommittedStatus {
+    pub total: Decimal,
+    pub pending: usize,
+    /// Age of the oldest commitment in seconds
+    pub oldest_secs: Option<u64>,
+}
+
+/// Capital committed to trades that have not confirmed yet
+#[derive(Debug)]
+pub struct CommittedCapital {
+    ttl: Duration,
+    commitments: HashMap<String, Commitment>,
+}
+
+impl CommittedCapital {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            commitments: HashMap::new(),
+        }
+    }
+
+    /// Commit `size` to trade `id`. Committing an id again replaces it.
+    pub fn commit(&mut self, id: &str, size: Decimal, venues: &[DexType]) {
+        self.commitments.insert(
+            id.to_string(),
+            Commitment {
+                id: id.to_string(),
+                size,
+                venues: venues.to_vec(),
+                committed_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Release the commitment of trade `id`, if it is still held
+    pub fn release(&mut self, id: &str) -> Option<Commitment> {
+        self.commitments.remove(id)
+    }
+
+    /// Remove and return commitments older than the TTL
+    pub fn expire(&mut self) -> Vec<Commitment> {
+        let ttl = self.ttl;
+        let expired: Vec<String> = self
+            .commitments
+            .values()
+            .filter(|c| c.committed_at.elapsed() >= ttl)
+            .map(|c| c.id.clone())
+            .collect();
+        expired
+            .iter()
+            .filter_map(|id| self.commitments.remove(id))
+            .collect()
+    }
+
+    pub fn total(&self) -> Decimal {
+        self.commitments.values().map(|c| c.size).sum()
+    }
+
+    pub fn status(&self) -> CommittedStatus {
+        CommittedStatus {
+            total: self.total(),
+            pending: self.commitments.len(),
+            oldest_secs: self
+                .commitments
+                .values()
+                .map(|c| c.committed_at.elapsed().as_secs())
+                .max(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_release_and_expire() {
+        let mut committed = CommittedCapital::new(Duration::from_secs(60));
+        committed.commit("a", Decimal::from(300), &[DexType::Raydium]);
+        committed.commit("b", Decimal::from(200), &[DexType::Orca]);
+        committed.commit("b", Decimal::from(250), &[DexType::Orca]);
+        assert_eq!(committed.total(), Decimal::from(550));
+        assert_eq!(committed.status().pending, 2);
+
+        assert_eq!(committed.release("a").unwrap().size, Decimal::from(300));
+        assert!(committed.release("a").is_none());
+        assert!(committed.expire().is_empty());
+
+        let mut expiring = CommittedCapital::new(Duration::ZERO);
+        expiring.commit("c", Decimal::from(100), &[]);
+        let expired = expiring.expire();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expiring.total(), Decimal::ZERO);
+    }
+}
@@ -0,0 +1,379 @@
+//! Perp hedging of spot inventory
+//!
+//! Spot inventory the bot is left holding is normally worked off with spot
+//! trades. When that is expensive (the pair's spot rebalance costs more than
+//! `min_spot_cost_bps`), its delta can be hedged with a short perp position
+//! instead. [`HedgeManager`] plans the perp orders that keep each hedge at its
+//! target, and unwinds hedges automatically once the spot position is gone,
+//! hedging is switched off or the hedge budget is spent.
+//!
+//! Hedging has its own risk budget, separate from the spot limits: a cap on
+//! the summed hedge notional, a cap on the share of perp collateral used as
+//! margin, and a loss limit after which every hedge is closed and none is
+//! opened until the next day.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Hedge targets and risk budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeConfig {
+    /// Share of each spot position left unhedged (0: delta neutral)
+    pub target_delta: Decimal,
+    /// Hedges within this many USD of their target are left alone
+    pub rebalance_threshold: Decimal,
+    /// Only spot positions costing more than this to rebalance are hedged (bps)
+    pub min_spot_cost_bps: Decimal,
+    /// Cap on the summed notional of all hedges (USD)
+    pub max_notional: Decimal,
+    /// Cap on margin used as a share of perp collateral (0..1)
+    pub max_margin_usage: Decimal,
+    /// Leverage the margin requirement is estimated with
+    pub leverage: Decimal,
+    /// Realized plus unrealized hedge loss (USD) that closes all hedges for the day
+    pub max_loss: Decimal,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            target_delta: Decimal::ZERO,
+            rebalance_threshold: Decimal::from(25),
+            min_spot_cost_bps: Decimal::from(30),
+            max_notional: Decimal::from(1000),
+            max_margin_usage: Decimal::new(5, 1),
+            leverage: Decimal::from(2),
+            max_loss: Decimal::from(100),
+        }
+    }
+}
+
+/// Spot inventory in one base token
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotExposure {
+    /// Base token symbol, also the perp market (e.g. `SOL`)
+    pub market: String,
+    /// Long spot inventory in USD
+    pub spot_usd: Decimal,
+    pub price: Decimal,
+    /// Estimated cost of working the inventory off on spot
+    pub rebalance_cost_bps: Decimal,
+}
+
+/// Collateral and margin of the perp account
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct MarginAccount {
+    pub collateral: Decimal,
+    pub margin_used: Decimal,
+}
+
+impl MarginAccount {
+    /// Share of collateral used as margin
+    pub fn usage(&self) -> Decimal {
+        if self.collateral <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        self.margin_used / self.collateral
+    }
+}
+
+/// A perp order planned by the hedger
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HedgeOrder {
+    pub market: String,
+    /// Signed base amount: negative sells (adds to a short)
+    pub base_amount: Decimal,
+    pub reduce_only: bool,
+    pub reason: String,
+}
+
+/// An open perp hedge
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PerpHedge {
+    pub market: String,
+    /// Signed base amount, negative for a short
+    pub base_amount: Decimal,
+    pub entry_price: Decimal,
+}
+
+/// Hedges and budget for status reporting
+#[derive(Debug, Clone, Serialize)]
+pub struct HedgeStatus {
+    pub hedges: Vec<PerpHedge>,
+    pub notional: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub max_notional: Decimal,
+    /// Why hedging is stopped for the day, if it is
+    pub halted: Option<String>,
+}
+
+/// Plans perp hedges for spot inventory within the hedge budget
+pub struct HedgeManager {
+    config: HedgeConfig,
+    hedges: HashMap<String, PerpHedge>,
+    realized_pnl: Decimal,
+    halted: Option<String>,
+}
+
+impl HedgeManager {
+    pub fn new(config: HedgeConfig) -> Self {
+        Self {
+            config,
+            hedges: HashMap::new(),
+            realized_pnl: Decimal::ZERO,
+            halted: None,
+        }
+    }
+
+    pub fn set_config(&mut self, config: HedgeConfig) {
+        self.config = config;
+    }
+
+    pub fn has_hedges(&self) -> bool {
+        !self.hedges.is_empty()
+    }
+
+    /// Mark-to-market P&L of the open hedges at `prices` (by market)
+    pub fn unrealized_pnl(&self, prices: &HashMap<String, Decimal>) -> Decimal {
+        self.hedges
+            .values()
+            .filter_map(|h| prices.get(&h.market).map(|p| h.base_amount * (*p - h.entry_price)))
+            .sum()
+    }
+
+    /// The orders that bring every hedge to its target. With `enabled`
+    /// false, or once the loss limit is hit, all hedges are closed.
+    pub fn plan(
+        &mut self,
+        exposures: &[SpotExposure],
+        account: &MarginAccount,
+        enabled: bool,
+    ) -> Vec<HedgeOrder> {
+        let prices: HashMap<String, Decimal> = exposures
+            .iter()
+            .map(|e| (e.market.clone(), e.price))
+            .collect();
+        let pnl = self.realized_pnl + self.unrealized_pnl(&prices);
+        if self.halted.is_none() && self.config.max_loss > Decimal::ZERO && pnl <= -self.config.max_loss {
+            self.halted = Some(format!(
+                "hedge loss ${} reached the ${} budget",
+                (-pnl).round_dp(2),
+                self.config.max_loss
+            ));
+        }
+        let stop = if enabled {
+            self.halted.clone()
+        } else {
+            Some("hedging disabled".to_string())
+        };
+        if let Some(reason) = stop {
+            return self.close_all(&reason);
+        }
+
+        let mut sorted: Vec<&SpotExposure> = exposures.iter().filter(|e| e.price > Decimal::ZERO).collect();
+        sorted.sort_by_key(|e| std::cmp::Reverse(e.spot_usd));
+
+        let leverage = self.config.leverage.max(Decimal::ONE);
+        let mut budget = self.config.max_notional;
+        let mut margin_room = self.config.max_margin_usage * account.collateral - account.margin_used;
+        let mut orders = Vec::new();
+        for exposure in sorted {
+            let current_base = self.hedges.get(&exposure.market).map_or(Decimal::ZERO, |h| h.base_amount);
+            let current = -current_base * exposure.price;
+
+            let mut target = (exposure.spot_usd * (Decimal::ONE - self.config.target_delta)).max(Decimal::ZERO);
+            // Cheap to rebalance on spot: keep what is hedged, add nothing
+            if exposure.rebalance_cost_bps < self.config.min_spot_cost_bps {
+                target = target.min(current);
+            }
+            target = target.min(budget.max(Decimal::ZERO));
+            // Margin over the limit is freed from the largest hedges first
+            if margin_room < Decimal::ZERO {
+                target = target.min((current + margin_room * leverage).max(Decimal::ZERO));
+            } else if target > current {
+                target = target.min(current + margin_room.max(Decimal::ZERO) * leverage);
+            }
+            budget -= target;
+            margin_room -= (target - current) / leverage;
+
+            let change = target - current;
+            if change.abs() < self.config.rebalance_threshold && !(target.is_zero() && current > Decimal::ZERO) {
+                continue;
+            }
+            let reason = if exposure.spot_usd <= Decimal::ZERO {
+                "spot position closed"
+            } else if target.is_zero() {
+                "over the hedge budget"
+            } else if current.is_zero() {
+                "open"
+            } else if change > Decimal::ZERO {
+                "increase to target"
+            } else {
+                "reduce to target"
+            };
+            orders.push(HedgeOrder {
+                market: exposure.market.clone(),
+                base_amount: -change / exposure.price,
+                reduce_only: change < Decimal::ZERO,
+                reason: reason.to_string(),
+            });
+        }
+
+        // No spot inventory reported for the market: close its hedge
+        for hedge in self.hedges.values() {
+            if !exposures.iter().any(|e| e.market == hedge.market) {
+                orders.push(close_order(hedge, "spot position closed"));
+            }
+        }
+        orders
+    }
+
+    fn close_all(&self, reason: &str) -> Vec<HedgeOrder> {
+        self.hedges.values().map(|h| close_order(h, reason)).collect()
+    }
+
+    /// Apply a filled order at `price`, realizing P&L on the closed part
+    pub fn record_fill(&mut self, order: &HedgeOrder, price: Decimal) {
+        let hedge = self
+            .hedges
+            .entry(order.market.clone())
+            .or_insert_with(|| PerpHedge {
+                market: order.market.clone(),
+                base_amount: Decimal::ZERO,
+                entry_price: price,
+            });
+        let before = hedge.base_amount;
+        let after = before + order.base_amount;
+        if before.is_zero() || before.is_sign_negative() == order.base_amount.is_sign_negative() {
+            hedge.entry_price = (before.abs() * hedge.entry_price + order.base_amount.abs() * price)
+                / after.abs();
+        } else {
+            let closed = order.base_amount.abs().min(before.abs());
+            let closed_signed = if before.is_sign_negative() { -closed } else { closed };
+            self.realized_pnl += closed_signed * (price - hedge.entry_price);
+            if after.abs() > Decimal::ZERO && after.is_sign_negative() != before.is_sign_negative() {
+                hedge.entry_price = price;
+            }
+        }
+        hedge.base_amount = after;
+        if after.is_zero() {
+            self.hedges.remove(&order.market);
+        }
+    }
+
+    pub fn status(&self, prices: &HashMap<String, Decimal>) -> HedgeStatus {
+        let mut hedges: Vec<PerpHedge> = self.hedges.values().cloned().collect();
+        hedges.sort_by(|a, b| a.market.cmp(&b.market));
+        HedgeStatus {
+            notional: hedges
+                .iter()
+                .map(|h| h.base_amount.abs() * prices.get(&h.market).copied().unwrap_or(h.entry_price))
+                .sum(),
+            hedges,
+            realized_pnl: self.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl(prices),
+            max_notional: self.config.max_notional,
+            halted: self.halted.clone(),
+        }
+    }
+
+    /// Start a new day: reset realized P&L and lift a loss halt
+    pub fn reset_daily(&mut self) {
+        self.realized_pnl = Decimal::ZERO;
+        self.halted = None;
+    }
+}
+
+fn close_order(hedge: &PerpHedge, reason: &str) -> HedgeOrder {
+    HedgeOrder {
+        market: hedge.market.clone(),
+        base_amount: -hedge.base_amount,
+        reduce_only: true,
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol(spot_usd: i64, price: i64, cost_bps: i64) -> SpotExposure {
+        SpotExposure {
+            market: "SOL".to_string(),
+            spot_usd: Decimal::from(spot_usd),
+            price: Decimal::from(price),
+            rebalance_cost_bps: Decimal::from(cost_bps),
+        }
+    }
+
+    fn fill(manager: &mut HedgeManager, orders: &[HedgeOrder], price: i64) {
+        for order in orders {
+            manager.record_fill(order, Decimal::from(price));
+        }
+    }
+
+    #[test]
+    fn test_hedge_targets_budget_and_unwind() {
+        let account = MarginAccount {
+            collateral: Decimal::from(1000),
+            margin_used: Decimal::ZERO,
+        };
+        let mut manager = HedgeManager::new(HedgeConfig::default());
+
+        // Cheap to rebalance on spot: no hedge
+        assert!(manager.plan(&[sol(500, 100, 10)], &account, true).is_empty());
+
+        // Expensive: short the full $500 (5 SOL)
+        let orders = manager.plan(&[sol(500, 100, 50)], &account, true);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].base_amount, Decimal::from(-5));
+        assert!(!orders[0].reduce_only);
+        fill(&mut manager, &orders, 100);
+
+        // Within the rebalance threshold: nothing to do
+        assert!(manager.plan(&[sol(520, 100, 50)], &account, true).is_empty());
+
+        // The notional budget caps the hedge at $1000
+        let orders = manager.plan(&[sol(3000, 100, 50)], &account, true);
+        assert_eq!(orders[0].base_amount, Decimal::from(-5));
+        fill(&mut manager, &orders, 100);
+
+        // Margin at the limit ($500 of $1000) frees $400 of the hedge
+        let loaded = MarginAccount {
+            collateral: Decimal::from(1000),
+            margin_used: Decimal::from(700),
+        };
+        let orders = manager.plan(&[sol(3000, 100, 50)], &loaded, true);
+        assert_eq!(orders[0].base_amount, Decimal::from(4));
+        assert!(orders[0].reduce_only);
+        fill(&mut manager, &orders, 100);
+
+        // Spot sold down: the hedge follows and realizes its P&L
+        let orders = manager.plan(&[sol(0, 90, 50)], &account, true);
+        assert_eq!(orders[0].base_amount, Decimal::from(6));
+        assert_eq!(orders[0].reason, "spot position closed");
+        fill(&mut manager, &orders, 90);
+        assert!(!manager.has_hedges());
+        assert_eq!(manager.status(&HashMap::new()).realized_pnl, Decimal::from(60));
+
+        // A loss past the budget closes everything and stops hedging for the day
+        let orders = manager.plan(&[sol(1000, 100, 50)], &account, true);
+        fill(&mut manager, &orders, 100);
+        let orders = manager.plan(&[sol(1000, 125, 50)], &account, true);
+        assert_eq!(orders[0].base_amount, Decimal::from(10));
+        assert!(manager.status(&HashMap::new()).halted.is_some());
+        fill(&mut manager, &orders, 125);
+        assert!(manager.plan(&[sol(1000, 125, 50)], &account, true).is_empty());
+        manager.reset_daily();
+        assert_eq!(manager.plan(&[sol(1000, 125, 50)], &account, true).len(), 1);
+
+        // Switching hedging off closes open hedges
+        let orders = manager.plan(&[sol(1000, 125, 50)], &account, true);
+        fill(&mut manager, &orders, 125);
+        let orders = manager.plan(&[sol(1000, 125, 50)], &account, false);
+        assert_eq!(orders[0].reason, "hedging disabled");
+        assert_eq!(orders[0].base_amount, Decimal::from(8));
+    }
+}
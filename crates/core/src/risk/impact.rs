@@ -0,0 +1,212 @@
+//! Impact-aware trade sizing
+//!
+//! The detector's edge is a top-of-book spread: it holds for a small trade
+//! and shrinks as the size eats into the pools. The expected profit of a
+//! trade of size `S` is `S * (edge - impact(S)) / 100`, where `impact(S)` is
+//! the round-trip price impact (%) quoted for that size. It rises with `S`
+//! and then falls once impact overtakes the edge, so the size that
+//! maximizes it is found by binary search on the sign of its slope: each
+//! step quotes two nearby sizes and keeps the half the profit rises toward.
+//!
+//! The sampled points form the route's impact curve. Curves are cached for a
+//! short TTL so the quotes are not repeated every tick, and the best size is
+//! re-read from the curve with each opportunity's current edge.
+
+use crate::memory::MemoryUsage;
+use crate::types::{ArbitrageOpportunity, DexType};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Expected profit (quote currency) of trading `size` at `edge_pct` less
+/// `impact_pct`
+pub fn expected_profit(edge_pct: Decimal, size: Decimal, impact_pct: Decimal) -> Decimal {
+    size * (edge_pct - impact_pct) / Decimal::from(100)
+}
+
+/// Quoted round-trip price impact of one route at the sizes sampled
+#[derive(Debug, Clone)]
+pub struct ImpactCurve {
+    /// (size, impact %) sorted by size
+    points: Vec<(Decimal, Decimal)>,
+    sampled_at: Instant,
+}
+
+impl ImpactCurve {
+    pub fn new(mut points: Vec<(Decimal, Decimal)>) -> Self {
+        points.sort_by_key(|(size, _)| *size);
+        points.dedup_by(|a, b| a.0 == b.0);
+        Self {
+            points,
+            sampled_at: Instant::now(),
+        }
+    }
+
+    pub fn points(&self) -> &[(Decimal, Decimal)] {
+        &self.points
+    }
+
+    /// Sampled size up to `max_size` with the highest expected profit at
+    /// `edge_pct` (the smallest on a tie); `None` if none was sampled
+    pub fn best_size(&self, edge_pct: Decimal, max_size: Decimal) -> Option<Decimal> {
+        self.points
+            .iter()
+            .filter(|(size, _)| *size <= max_size)
+            .fold(None, |best: Option<(Decimal, Decimal)>, &(size, impact)| {
+                let profit = expected_profit(edge_pct, size, impact);
+                match best {
+                    Some((_, top)) if top >= profit => best,
+                    _ => Some((size, profit)),
+                }
+            })
+            .map(|(size, _)| size)
+    }
+}
+
+/// Binary search `min_size..=max_size` for the size with the highest
+/// expected profit at `edge_pct`, quoting `impact_at` twice per step.
+/// Fails with the first quote that fails.
+pub async fn search<F, Fut, E>(
+    edge_pct: Decimal,
+    min_size: Decimal,
+    max_size: Decimal,
+    steps: u32,
+    mut impact_at: F,
+) -> Result<ImpactCurve, E>
+where
+    F: FnMut(Decimal) -> Fut,
+    Fut: Future<Output = Result<Decimal, E>>,
+{
+    let (mut lo, mut hi) = (min_size.min(max_size), max_size);
+    let mut points = Vec::new();
+    for _ in 0..steps {
+        let mid = (lo + hi) / Decimal::TWO;
+        let probe = (mid + (hi - lo) / Decimal::from(10)).min(hi);
+        let at_mid = impact_at(mid).await?;
+        let at_probe = impact_at(probe).await?;
+        points.extend([(mid, at_mid), (probe, at_probe)]);
+
+        if expected_profit(edge_pct, probe, at_probe) > expected_profit(edge_pct, mid, at_mid) {
+            lo = mid;
+        } else {
+            hi = probe;
+        }
+    }
+    Ok(ImpactCurve::new(points))
+}
+
+/// Route an impact curve is cached under: pair and venues
+type RouteKey = (String, DexType, DexType);
+
+fn route_key(opp: &ArbitrageOpportunity) -> RouteKey {
+    (opp.pair.symbol(), opp.buy_dex, opp.sell_dex)
+}
+
+/// Impact curves per route, each kept for `ttl`
+#[derive(Debug)]
+pub struct ImpactCurveCache {
+    ttl: Duration,
+    curves: Mutex<HashMap<RouteKey, ImpactCurve>>,
+}
+
+impl ImpactCurveCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            curves: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fresh curve for `opp`'s route, dropping it if it has expired
+    pub fn get(&self, opp: &ArbitrageOpportunity) -> Option<ImpactCurve> {
+        let mut curves = self.curves.lock().unwrap_or_else(|e| e.into_inner());
+        let key = route_key(opp);
+        match curves.get(&key) {
+            Some(curve) if curve.sampled_at.elapsed() < self.ttl => Some(curve.clone()),
+            Some(_) => {
+                curves.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, opp: &ArbitrageOpportunity, curve: ImpactCurve) {
+        let mut curves = self.curves.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop expired routes so pairs that stop trading do not linger
+        curves.retain(|_, curve| curve.sampled_at.elapsed() < self.ttl);
+        curves.insert(route_key(opp), curve);
+    }
+
+    /// Approximate memory held by the cached curves
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let curves = self.curves.lock().unwrap_or_else(|e| e.into_inner());
+        curves
+            .iter()
+            .map(|((pair, _, _), curve)| {
+                MemoryUsage::of::<(RouteKey, ImpactCurve)>(1, pair.capacity())
+                    + MemoryUsage::of::<(Decimal, Decimal)>(curve.points.len(), 0)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OpportunitySource;
+    use crate::types::TokenPair;
+
+    #[tokio::test]
+    async fn test_search_converges_on_profit_maximizing_size() {
+        // 1% edge; impact grows 0.1% per $100, so profit peaks at $500
+        let mut quotes = 0;
+        let curve = search(Decimal::ONE, Decimal::from(10), Decimal::from(2000), 8, |size| {
+            quotes += 1;
+            async move { Ok::<_, ()>(size / Decimal::from(1000)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(quotes, 16);
+
+        let best = curve.best_size(Decimal::ONE, Decimal::from(2000)).unwrap();
+        assert!((best - Decimal::from(500)).abs() < Decimal::from(25), "best {}", best);
+        // Risk limits bound the size; a thinner edge wants a smaller trade
+        assert!(curve.best_size(Decimal::ONE, Decimal::from(450)).unwrap() <= Decimal::from(450));
+        assert!(curve.best_size(Decimal::new(5, 1), Decimal::from(2000)).unwrap() < best);
+        assert_eq!(curve.best_size(Decimal::ONE, Decimal::ONE), None);
+
+        let failed = search(Decimal::ONE, Decimal::ONE, Decimal::TEN, 3, |_| async {
+            Err::<Decimal, _>("quote failed")
+        })
+        .await;
+        assert_eq!(failed.unwrap_err(), "quote failed");
+
+        let opp = ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: TokenPair::new("SOL", "USDC"),
+            buy_dex: DexType::Raydium,
+            sell_dex: DexType::Orca,
+            buy_price: Decimal::from(100),
+            sell_price: Decimal::from(101),
+            gross_profit_pct: Decimal::ONE,
+            net_profit_pct: Decimal::ONE,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: chrono::Utc::now(),
+            expired_at: None,
+            buy_quote_at: None,
+            sell_quote_at: None,
+            source: OpportunitySource::Detector,
+            strategy: None,
+        };
+        let cache = ImpactCurveCache::new(Duration::from_millis(50));
+        cache.insert(&opp, curve);
+        assert!(cache.get(&opp).is_some());
+        assert_eq!(cache.memory_usage().entries, 1 + cache.get(&opp).unwrap().points().len());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(cache.get(&opp).is_none());
+    }
+}
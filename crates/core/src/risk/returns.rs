@@ -0,0 +1,164 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use crate::memory::MemoryUsage;
+
+/// Rolling archive of per-pair price returns used for historical and
+/// Monte Carlo VaR.
+///
+/// Returns are simple period-over-period changes of the mid price, one per
+/// price update. The archive can be saved to disk so estimates survive
+/// restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReturnArchive {
+    /// Maximum returns kept per pair
+    max_len: usize,
+    returns: HashMap<String, VecDeque<f64>>,
+    #[serde(skip)]
+    last_prices: HashMap<String, Decimal>,
+}
+
+impl ReturnArchive {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            max_len: max_len.max(1),
+            returns: HashMap::new(),
+            last_prices: HashMap::new(),
+        }
+    }
+
+    /// Record a new price for `pair`, archiving the return since the last one
+    pub fn record_price(&mut self, pair: &str, price: Decimal) {
+        if let Some(&last) = self.last_prices.get(pair) {
+            if !last.is_zero() {
+                if let Some(ret) = ((price - last) / last).to_f64() {
+                    self.record_return(pair, ret);
+                }
+            }
+        }
+        self.last_prices.insert(pair.to_string(), price);
+    }
+
+    /// Archive a return directly
+    pub fn record_return(&mut self, pair: &str, ret: f64) {
+        let series = self.returns.entry(pair.to_string()).or_default();
+        if series.len() >= self.max_len {
+            series.pop_front();
+        }
+        series.push_back(ret);
+    }
+
+    /// Approximate memory held by the archived returns
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let returns = self.returns.values().map(VecDeque::len).sum();
+        MemoryUsage::of::<f64>(returns, 0)
+    }
+
+    /// Archived returns for `pair`, oldest first
+    pub fn returns(&self, pair: &str) -> Option<&VecDeque<f64>> {
+        self.returns.get(pair)
+    }
+
+    /// Per-period P&L (USD) the current positions would have had over the
+    /// archived returns, oldest first.
+    ///
+    /// Series are aligned on their most recent return and truncated to the
+    /// shortest one, so every scenario uses returns from the same periods.
+    /// Positions on pairs without any history are skipped.
+    pub fn portfolio_pnl(&self, positions: &HashMap<String, Decimal>) -> Vec<f64> {
+        let series: Vec<(f64, &VecDeque<f64>)> = positions
+            .iter()
+            .filter_map(|(pair, size)| {
+                let returns = self.returns.get(pair).filter(|r| !r.is_empty())?;
+                Some((size.to_f64().unwrap_or(0.0), returns))
+            })
+            .collect();
+
+        let len = match series.iter().map(|(_, r)| r.len()).min() {
+            Some(len) => len,
+            None => return Vec::new(),
+        };
+
+        (0..len)
+            .map(|i| {
+                series
+                    .iter()
+                    .map(|(size, returns)| size * returns[returns.len() - len + i])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Load an archive saved with [`ReturnArchive::save`]
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the archive to `path` as JSON
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_returns_and_caps_length() {
+        let mut archive = ReturnArchive::new(2);
+        for price in [100, 110, 99, 99] {
+            archive.record_price("SOL/USDC", Decimal::from(price));
+        }
+        let returns: Vec<f64> = archive.returns("SOL/USDC").unwrap().iter().copied().collect();
+        assert_eq!(returns.len(), 2);
+        assert!((returns[0] + 0.1).abs() < 1e-12);
+        assert_eq!(returns[1], 0.0);
+    }
+
+    #[test]
+    fn test_portfolio_pnl_aligns_latest_returns() {
+        let mut archive = ReturnArchive::new(10);
+        for r in [0.5, 0.01, -0.02] {
+            archive.record_return("SOL/USDC", r);
+        }
+        for r in [0.03, -0.01] {
+            archive.record_return("RAY/USDC", r);
+        }
+        let positions = HashMap::from([
+            ("SOL/USDC".to_string(), Decimal::from(1000)),
+            ("RAY/USDC".to_string(), Decimal::from(100)),
+            ("ORCA/USDC".to_string(), Decimal::from(100)),
+        ]);
+
+        let pnl = archive.portfolio_pnl(&positions);
+        assert_eq!(pnl.len(), 2);
+        assert!((pnl[0] - 13.0).abs() < 1e-9);
+        assert!((pnl[1] + 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "return_archive_{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        let mut archive = ReturnArchive::new(5);
+        archive.record_return("SOL/USDC", 0.01);
+        archive.save(&path).unwrap();
+
+        let loaded = ReturnArchive::load(&path).unwrap();
+        assert_eq!(loaded.returns("SOL/USDC").unwrap().len(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+}
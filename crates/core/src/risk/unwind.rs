@@ -0,0 +1,251 @@
+//! Partial unwind after a limit reduction
+//!
+//! Lowering `max_total_exposure` at runtime can leave existing positions above
+//! the new limit. Rather than closing everything at once, the overage is worked
+//! off with small housekeeping trades: each step reduces the largest position
+//! by at most `step_size` until total exposure is back within the limit.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::events::{EventBus, TradingEvent};
+
+/// One housekeeping trade: reduce `pair` by `size` USD
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnwindStep {
+    pub pair: String,
+    pub size: Decimal,
+}
+
+/// Snapshot of an unwind for status reporting
+#[derive(Debug, Clone, Serialize)]
+pub struct UnwindStatus {
+    pub limit: Decimal,
+    pub initial_overage: Decimal,
+    pub remaining_overage: Decimal,
+    pub reduced: Decimal,
+    pub steps_completed: u32,
+    pub steps_failed: u32,
+    pub in_flight: Option<UnwindStep>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// An unwind in progress
+#[derive(Debug, Clone)]
+struct UnwindPlan {
+    limit: Decimal,
+    initial_overage: Decimal,
+    reduced: Decimal,
+    steps_completed: u32,
+    steps_failed: u32,
+    in_flight: Option<UnwindStep>,
+    started_at: DateTime<Utc>,
+}
+
+/// Schedules housekeeping trades until exposure is within the limit.
+///
+/// Only one step is in flight at a time; the next one is planned from the
+/// positions as they are after the previous step completed or failed.
+pub struct UnwindScheduler {
+    step_size: Decimal,
+    plan: Option<UnwindPlan>,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+impl UnwindScheduler {
+    pub fn new(step_size: Decimal) -> Self {
+        Self {
+            step_size,
+            plan: None,
+            event_bus: None,
+        }
+    }
+
+    pub fn set_event_bus(&mut self, bus: Arc<EventBus>) {
+        self.event_bus = Some(bus);
+    }
+
+    pub fn set_step_size(&mut self, step_size: Decimal) {
+        self.step_size = step_size;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.plan.is_some()
+    }
+
+    /// Start (or re-target) an unwind after the exposure limit changed.
+    /// Returns the overage, or `None` if exposure is already within `limit`.
+    pub fn schedule(&mut self, limit: Decimal, exposure: Decimal) -> Option<Decimal> {
+        let overage = exposure - limit;
+        if overage <= Decimal::ZERO {
+            if self.plan.is_some() {
+                self.finish();
+            }
+            return None;
+        }
+
+        match &mut self.plan {
+            Some(plan) => plan.limit = limit,
+            None => {
+                self.plan = Some(UnwindPlan {
+                    limit,
+                    initial_overage: overage,
+                    reduced: Decimal::ZERO,
+                    steps_completed: 0,
+                    steps_failed: 0,
+                    in_flight: None,
+                    started_at: Utc::now(),
+                });
+            }
+        }
+        tracing::warn!(
+            "📉 Exposure ${} exceeds new limit ${} - unwinding ${} in steps of up to ${}",
+            exposure, limit, overage, self.step_size
+        );
+
+        if let Some(bus) = &self.event_bus {
+            bus.publish(TradingEvent::UnwindStarted {
+                limit: limit.to_f64().unwrap_or(0.0),
+                overage: overage.to_f64().unwrap_or(0.0),
+            });
+        }
+        Some(overage)
+    }
+
+    /// Plan the next housekeeping trade: the largest position, reduced by at
+    /// most the step size and the remaining overage. Returns `None` while a
+    /// step is in flight or when there is nothing to unwind; the unwind
+    /// completes once exposure is within the limit.
+    pub fn next_step(&mut self, positions: &HashMap<String, Decimal>) -> Option<UnwindStep> {
+        let plan = self.plan.as_ref()?;
+        if plan.in_flight.is_some() {
+            return None;
+        }
+
+        let exposure: Decimal = positions.values().sum();
+        let overage = exposure - plan.limit;
+        let largest = positions
+            .iter()
+            .filter(|(_, size)| **size > Decimal::ZERO)
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)));
+        let (pair, position) = match largest {
+            Some(largest) if overage > Decimal::ZERO => largest,
+            _ => {
+                self.finish();
+                return None;
+            }
+        };
+
+        let step = UnwindStep {
+            pair: pair.clone(),
+            size: self.step_size.min(overage).min(*position),
+        };
+        if let Some(plan) = &mut self.plan {
+            plan.in_flight = Some(step.clone());
+        }
+        Some(step)
+    }
+
+    /// Record the in-flight step as done, having reduced its position by `reduced`
+    pub fn complete_step(&mut self, reduced: Decimal) {
+        if let Some(plan) = &mut self.plan {
+            if plan.in_flight.take().is_some() {
+                plan.steps_completed += 1;
+                plan.reduced += reduced;
+            }
+        }
+    }
+
+    /// Record the in-flight step as failed; it will be planned again
+    pub fn fail_step(&mut self) {
+        if let Some(plan) = &mut self.plan {
+            if plan.in_flight.take().is_some() {
+                plan.steps_failed += 1;
+            }
+        }
+    }
+
+    pub fn status(&self, exposure: Decimal) -> Option<UnwindStatus> {
+        self.plan.as_ref().map(|plan| UnwindStatus {
+            limit: plan.limit,
+            initial_overage: plan.initial_overage,
+            remaining_overage: (exposure - plan.limit).max(Decimal::ZERO),
+            reduced: plan.reduced,
+            steps_completed: plan.steps_completed,
+            steps_failed: plan.steps_failed,
+            in_flight: plan.in_flight.clone(),
+            started_at: plan.started_at,
+        })
+    }
+
+    fn finish(&mut self) {
+        let Some(plan) = self.plan.take() else {
+            return;
+        };
+        tracing::info!(
+            "📉 Unwind complete - reduced ${} in {} steps",
+            plan.reduced, plan.steps_completed
+        );
+
+        if let Some(bus) = &self.event_bus {
+            bus.publish(TradingEvent::UnwindCompleted {
+                reduced: plan.reduced.to_f64().unwrap_or(0.0),
+                steps: plan.steps_completed,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(entries: &[(&str, i64)]) -> HashMap<String, Decimal> {
+        entries
+            .iter()
+            .map(|(pair, size)| (pair.to_string(), Decimal::from(*size)))
+            .collect()
+    }
+
+    #[test]
+    fn test_unwind_steps_until_within_limit() {
+        let mut unwind = UnwindScheduler::new(Decimal::from(250));
+        let mut held = positions(&[("SOL/USDC", 700), ("RAY/USDC", 300)]);
+
+        assert_eq!(unwind.schedule(Decimal::from(2000), Decimal::from(1000)), None);
+        assert!(!unwind.is_active());
+        assert_eq!(
+            unwind.schedule(Decimal::from(600), Decimal::from(1000)),
+            Some(Decimal::from(400))
+        );
+
+        // Largest position first, one step in flight at a time
+        let step = unwind.next_step(&held).unwrap();
+        assert_eq!(step.pair, "SOL/USDC");
+        assert_eq!(step.size, Decimal::from(250));
+        assert!(unwind.next_step(&held).is_none());
+
+        // A failed step is retried
+        unwind.fail_step();
+        let step = unwind.next_step(&held).unwrap();
+        *held.get_mut(&step.pair).unwrap() -= step.size;
+        unwind.complete_step(step.size);
+
+        // Capped by the remaining overage
+        let step = unwind.next_step(&held).unwrap();
+        assert_eq!(step.size, Decimal::from(150));
+        *held.get_mut(&step.pair).unwrap() -= step.size;
+        unwind.complete_step(step.size);
+
+        let status = unwind.status(Decimal::from(600)).unwrap();
+        assert_eq!(status.reduced, Decimal::from(400));
+        assert_eq!((status.steps_completed, status.steps_failed), (2, 1));
+        assert_eq!(status.remaining_overage, Decimal::ZERO);
+
+        assert!(unwind.next_step(&held).is_none());
+        assert!(!unwind.is_active());
+    }
+}
@@ -1,14 +1,81 @@
 use crate::risk::volatility::VolatilityTracker;
+use chrono::NaiveDate;
+use rand::Rng;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// Minimum number of scenarios for a historical or Monte Carlo estimate
+pub const MIN_SCENARIOS: usize = 30;
+
+/// Chi-squared critical value (1 degree of freedom, 95%) for the Kupiec test
+const KUPIEC_CRITICAL_VALUE: f64 = 3.841;
+
+/// How VaR is estimated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VarMethod {
+    /// Position value x volatility x z-score
+    Parametric,
+    /// Empirical quantile of P&L replayed over archived returns
+    Historical,
+    /// Quantile of simulated P&L paths drawn from the archived return distribution
+    MonteCarlo,
+}
+
+impl VarMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VarMethod::Parametric => "parametric",
+            VarMethod::Historical => "historical",
+            VarMethod::MonteCarlo => "monte_carlo",
+        }
+    }
+}
+
+impl std::fmt::Display for VarMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VarMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "parametric" => Ok(VarMethod::Parametric),
+            "historical" => Ok(VarMethod::Historical),
+            "monte_carlo" | "montecarlo" => Ok(VarMethod::MonteCarlo),
+            other => Err(format!("unknown VaR method '{}'", other)),
+        }
+    }
+}
+
+/// A VaR / Expected Shortfall estimate, both expressed as positive USD losses
+#[derive(Debug, Clone, Serialize)]
+pub struct VarEstimate {
+    pub method: VarMethod,
+    pub confidence: f64,
+    pub horizon_periods: usize,
+    pub var: Decimal,
+    pub expected_shortfall: Decimal,
+    /// Number of scenarios behind the estimate (0 for parametric)
+    pub scenarios: usize,
+}
 
 /// Value at Risk (VaR) Calculator
-#[allow(dead_code)]
 pub struct VarCalculator {
     /// Confidence level (e.g., 0.95 or 0.99)
     confidence_level: f64,
     /// Z-score corresponding to confidence level
     z_score: f64,
+    /// Horizon in price update periods
+    horizon_periods: usize,
+    /// Number of simulated paths for Monte Carlo VaR
+    monte_carlo_paths: usize,
 }
 
 impl VarCalculator {
@@ -25,9 +92,31 @@ impl VarCalculator {
         Self {
             confidence_level,
             z_score,
+            horizon_periods: 1,
+            monte_carlo_paths: 10_000,
         }
     }
 
+    /// Set the horizon (in price update periods)
+    pub fn with_horizon(mut self, periods: usize) -> Self {
+        self.horizon_periods = periods.max(1);
+        self
+    }
+
+    /// Set the number of Monte Carlo paths
+    pub fn with_monte_carlo_paths(mut self, paths: usize) -> Self {
+        self.monte_carlo_paths = paths.max(MIN_SCENARIOS);
+        self
+    }
+
+    pub fn confidence_level(&self) -> f64 {
+        self.confidence_level
+    }
+
+    pub fn horizon_periods(&self) -> usize {
+        self.horizon_periods
+    }
+
     /// Calculate VaR for a single position
     /// VaR = Position Value * Volatility * Z-Score
     pub fn calculate_var(&self, position_value: Decimal, volatility: Decimal) -> Decimal {
@@ -60,4 +149,276 @@ impl VarCalculator {
 
         total_var
     }
+
+    /// Parametric estimate over the configured horizon.
+    ///
+    /// Scales the one-period VaR by sqrt(horizon) and uses the normal
+    /// Expected Shortfall ratio pdf(z) / (1 - confidence).
+    pub fn parametric(
+        &self,
+        positions: &std::collections::HashMap<String, Decimal>,
+        vol_tracker: &VolatilityTracker,
+    ) -> VarEstimate {
+        let one_period = self
+            .calculate_portfolio_var(positions, vol_tracker)
+            .to_f64()
+            .unwrap_or(0.0);
+        let var = one_period * (self.horizon_periods as f64).sqrt();
+        let pdf = (-self.z_score * self.z_score / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let es = var / self.z_score * pdf / (1.0 - self.confidence_level).max(f64::EPSILON);
+
+        self.estimate(VarMethod::Parametric, var, es, 0)
+    }
+
+    /// Historical-simulation estimate from per-period portfolio P&L.
+    ///
+    /// Multi-period horizons use overlapping sums of consecutive periods.
+    /// Returns `None` with fewer than [`MIN_SCENARIOS`] scenarios.
+    pub fn historical(&self, pnl: &[f64]) -> Option<VarEstimate> {
+        let scenarios: Vec<f64> = pnl
+            .windows(self.horizon_periods)
+            .map(|w| w.iter().sum())
+            .collect();
+        if scenarios.len() < MIN_SCENARIOS {
+            return None;
+        }
+
+        let (var, es) = self.tail(scenarios.iter().map(|p| -p).collect());
+        Some(self.estimate(VarMethod::Historical, var, es, scenarios.len()))
+    }
+
+    /// Monte Carlo estimate: simulate horizon P&L paths from a normal
+    /// distribution fitted to the per-period portfolio P&L.
+    ///
+    /// Fitting the portfolio series (rather than each pair) keeps the
+    /// correlation between pairs observed in the archive.
+    pub fn monte_carlo<R: Rng>(&self, pnl: &[f64], rng: &mut R) -> Option<VarEstimate> {
+        if pnl.len() < MIN_SCENARIOS {
+            return None;
+        }
+        let n = pnl.len() as f64;
+        let mean = pnl.iter().sum::<f64>() / n;
+        let std_dev = (pnl.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+
+        let losses: Vec<f64> = (0..self.monte_carlo_paths)
+            .map(|_| {
+                let path: f64 = (0..self.horizon_periods)
+                    .map(|_| mean + std_dev * standard_normal(rng))
+                    .sum();
+                -path
+            })
+            .collect();
+
+        let (var, es) = self.tail(losses);
+        Some(self.estimate(VarMethod::MonteCarlo, var, es, self.monte_carlo_paths))
+    }
+
+    /// VaR and Expected Shortfall from a set of scenario losses
+    fn tail(&self, mut losses: Vec<f64>) -> (f64, f64) {
+        losses.sort_by(|a, b| a.total_cmp(b));
+        let n = losses.len();
+        let idx = ((self.confidence_level * n as f64).ceil() as usize)
+            .saturating_sub(1)
+            .min(n - 1);
+        let var = losses[idx];
+        let tail = &losses[idx..];
+        let es = tail.iter().sum::<f64>() / tail.len() as f64;
+        (var, es)
+    }
+
+    fn estimate(&self, method: VarMethod, var: f64, es: f64, scenarios: usize) -> VarEstimate {
+        let var = var.max(0.0);
+        VarEstimate {
+            method,
+            confidence: self.confidence_level,
+            horizon_periods: self.horizon_periods,
+            var: Decimal::try_from(var).unwrap_or(Decimal::ZERO).round_dp(2),
+            expected_shortfall: Decimal::try_from(es.max(var))
+                .unwrap_or(Decimal::ZERO)
+                .round_dp(2),
+            scenarios,
+        }
+    }
+}
+
+/// Standard normal draw (Box-Muller)
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// One day's VaR forecast against the realized P&L
+#[derive(Debug, Clone, Serialize)]
+pub struct VarBacktestRecord {
+    pub date: NaiveDate,
+    pub var: Decimal,
+    pub pnl: Decimal,
+    pub breached: bool,
+}
+
+/// Summary of VaR breaches over the backtest window
+#[derive(Debug, Clone, Serialize)]
+pub struct VarBacktestSummary {
+    pub observations: usize,
+    pub breaches: usize,
+    pub breach_rate: f64,
+    pub expected_rate: f64,
+    /// Kupiec proportion-of-failures likelihood ratio
+    pub kupiec_lr: f64,
+    /// Whether the breach rate is inconsistent with the confidence level (95% test)
+    pub rejected: bool,
+}
+
+/// Rolling record of daily VaR forecasts and realized P&L
+#[derive(Debug, Clone)]
+pub struct VarBacktest {
+    max_len: usize,
+    records: VecDeque<VarBacktestRecord>,
+}
+
+impl VarBacktest {
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            max_len: max_len.max(1),
+            records: VecDeque::new(),
+        }
+    }
+
+    /// Record a day's forecast and realized P&L. Returns `true` on a breach
+    /// (loss larger than the forecast VaR).
+    pub fn record(&mut self, date: NaiveDate, var: Decimal, pnl: Decimal) -> bool {
+        let breached = -pnl > var;
+        if self.records.len() >= self.max_len {
+            self.records.pop_front();
+        }
+        self.records.push_back(VarBacktestRecord {
+            date,
+            var,
+            pnl,
+            breached,
+        });
+        breached
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &VarBacktestRecord> {
+        self.records.iter()
+    }
+
+    /// Breach statistics for a VaR at `confidence`
+    pub fn summary(&self, confidence: f64) -> VarBacktestSummary {
+        let n = self.records.len();
+        let x = self.records.iter().filter(|r| r.breached).count();
+        let p = (1.0 - confidence).clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+        let rate = if n == 0 { 0.0 } else { x as f64 / n as f64 };
+
+        let kupiec_lr = if n == 0 {
+            0.0
+        } else {
+            let (n, x) = (n as f64, x as f64);
+            let log_l0 = (n - x) * (1.0 - p).ln() + x * p.ln();
+            let log_l1 = xlogy(n - x, 1.0 - rate) + xlogy(x, rate);
+            (-2.0 * (log_l0 - log_l1)).max(0.0)
+        };
+
+        VarBacktestSummary {
+            observations: n,
+            breaches: x,
+            breach_rate: rate,
+            expected_rate: p,
+            kupiec_lr,
+            rejected: kupiec_lr > KUPIEC_CRITICAL_VALUE,
+        }
+    }
+}
+
+/// `x * ln(y)`, defined as 0 when `x` is 0
+fn xlogy(x: f64, y: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x * y.ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// 100 P&L observations: -1, -2, ..., -100
+    fn losses() -> Vec<f64> {
+        (1..=100).map(|i| -(i as f64)).collect()
+    }
+
+    #[test]
+    fn test_historical_quantile_and_shortfall() {
+        let calc = VarCalculator::new(0.95);
+        let est = calc.historical(&losses()).unwrap();
+        assert_eq!(est.var, Decimal::from(95));
+        // Mean of the 95..=100 tail
+        assert_eq!(est.expected_shortfall, Decimal::new(975, 1));
+        assert_eq!(est.scenarios, 100);
+
+        assert!(calc.historical(&losses()[..10]).is_none());
+    }
+
+    #[test]
+    fn test_historical_horizon_uses_overlapping_sums() {
+        let calc = VarCalculator::new(0.95).with_horizon(2);
+        let est = calc.historical(&vec![-1.0; 50]).unwrap();
+        assert_eq!(est.var, Decimal::from(2));
+        assert_eq!(est.scenarios, 49);
+    }
+
+    #[test]
+    fn test_monte_carlo_matches_normal_quantile() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // Alternating +/-10 P&L: mean 0, std ~10
+        let pnl: Vec<f64> = (0..200).map(|i| if i % 2 == 0 { 10.0 } else { -10.0 }).collect();
+        let calc = VarCalculator::new(0.95).with_monte_carlo_paths(20_000);
+        let est = calc.monte_carlo(&pnl, &mut rng).unwrap();
+
+        let var = est.var.to_f64().unwrap();
+        assert!((var - 16.45).abs() < 1.0, "var = {}", var);
+        assert!(est.expected_shortfall > est.var);
+
+        // Horizon of 4 periods scales by ~sqrt(4)
+        let calc = calc.with_horizon(4);
+        let var4 = calc.monte_carlo(&pnl, &mut rng).unwrap().var.to_f64().unwrap();
+        assert!((var4 / var - 2.0).abs() < 0.15, "ratio = {}", var4 / var);
+    }
+
+    #[test]
+    fn test_method_parsing() {
+        assert_eq!("historical".parse::<VarMethod>(), Ok(VarMethod::Historical));
+        assert_eq!("Monte_Carlo".parse::<VarMethod>(), Ok(VarMethod::MonteCarlo));
+        assert!("garch".parse::<VarMethod>().is_err());
+    }
+
+    #[test]
+    fn test_backtest_breaches_and_kupiec() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut backtest = VarBacktest::new(250);
+        assert!(backtest.record(day, Decimal::from(10), Decimal::from(-11)));
+        assert!(!backtest.record(day, Decimal::from(10), Decimal::from(-10)));
+
+        // 4 breaches in 100 days is consistent with 95% VaR
+        for i in 0..98 {
+            let pnl = if i < 3 { -15 } else { 1 };
+            backtest.record(day, Decimal::from(10), Decimal::from(pnl));
+        }
+        let summary = backtest.summary(0.95);
+        assert_eq!((summary.observations, summary.breaches), (100, 4));
+        assert!(!summary.rejected);
+
+        // 20 breaches in 100 days is not
+        let mut bad = VarBacktest::new(250);
+        for i in 0..100 {
+            let pnl = if i < 20 { -20 } else { 0 };
+            bad.record(day, Decimal::from(10), Decimal::from(pnl));
+        }
+        assert!(bad.summary(0.95).rejected);
+    }
 }
@@ -0,0 +1,341 @@
+//! RPC Request Batching
+//!
+//! Balance and account lookups from different tasks (the wallet balance
+//! check, the fee reserve monitor, flash loan reserves, lookup tables) add
+//! up to many small RPC calls. [`RpcBatcher`] queues requests for a few
+//! milliseconds and sends everything queued as one HTTP request: account and
+//! balance lookups are merged into `getMultipleAccounts` calls of up to 100
+//! keys, and any other call rides along in the same JSON-RPC batch.
+//!
+//! Lookups are made at the batcher's commitment, if it has one, else at the
+//! RPC node's default.
+//!
+//! The batcher runs on a background task, so it has to be created inside a
+//! tokio runtime.
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Value};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Keys per `getMultipleAccounts` call (RPC node limit)
+const MAX_ACCOUNTS_PER_CALL: usize = 100;
+
+/// One queued lookup
+#[derive(Debug, Clone, PartialEq)]
+enum Request {
+    Account(Pubkey),
+    Call { method: String, params: Value },
+}
+
+struct Pending {
+    request: Request,
+    reply: oneshot::Sender<Result<Value, String>>,
+}
+
+/// Requests queued and HTTP requests sent, for status reporting
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct BatchStats {
+    pub requests: u64,
+    pub http_calls: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    requests: AtomicU64,
+    http_calls: AtomicU64,
+}
+
+/// Coalesces RPC requests made within `window` into one HTTP request
+pub struct RpcBatcher {
+    queue: mpsc::UnboundedSender<Pending>,
+    counters: Arc<Counters>,
+    commitment: Option<CommitmentConfig>,
+}
+
+impl RpcBatcher {
+    pub fn new(rpc_url: &str, window: Duration) -> Self {
+        Self::start(rpc_url, window, None)
+    }
+
+    /// A batcher making every lookup at `commitment`
+    pub fn new_with_commitment(rpc_url: &str, window: Duration, commitment: CommitmentConfig) -> Self {
+        Self::start(rpc_url, window, Some(commitment))
+    }
+
+    fn start(rpc_url: &str, window: Duration, commitment: Option<CommitmentConfig>) -> Self {
+        let (queue, rx) = mpsc::unbounded_channel();
+        let counters = Arc::new(Counters::default());
+        tokio::spawn(run(rpc_url.to_string(), window, commitment, rx, counters.clone()));
+        Self {
+            queue,
+            counters,
+            commitment,
+        }
+    }
+
+    /// The account at `pubkey`, `None` if it does not exist
+    pub async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>> {
+        parse_account(&self.submit(Request::Account(*pubkey)).await?)
+    }
+
+    /// Several accounts, fetched in as few calls as possible
+    pub async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        futures_util::future::try_join_all(pubkeys.iter().map(|key| self.get_account(key))).await
+    }
+
+    /// Lamports held by `pubkey` (0 if the account does not exist)
+    pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
+        Ok(self.get_account(pubkey).await?.map_or(0, |account| account.lamports))
+    }
+
+    /// UI amount of an SPL token account
+    pub async fn get_token_balance(&self, token_account: &Pubkey) -> Result<Decimal> {
+        let params = match self.commitment {
+            Some(commitment) => json!([token_account.to_string(), commitment]),
+            None => json!([token_account.to_string()]),
+        };
+        let result = self.call("getTokenAccountBalance", params).await?;
+        let amount = result["value"]["uiAmountString"]
+            .as_str()
+            .ok_or_else(|| anyhow!("no token balance for {}", token_account))?;
+        Decimal::from_str(amount).context("invalid token balance")
+    }
+
+    /// Any other JSON-RPC method; returns its `result`
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        self.submit(Request::Call {
+            method: method.to_string(),
+            params,
+        })
+        .await
+    }
+
+    pub fn stats(&self) -> BatchStats {
+        BatchStats {
+            requests: self.counters.requests.load(Ordering::Relaxed),
+            http_calls: self.counters.http_calls.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn submit(&self, request: Request) -> Result<Value> {
+        let (reply, rx) = oneshot::channel();
+        self.queue
+            .send(Pending { request, reply })
+            .map_err(|_| anyhow!("RPC batcher stopped"))?;
+        rx.await
+            .map_err(|_| anyhow!("RPC batcher dropped the request"))?
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+impl std::fmt::Debug for RpcBatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcBatcher").field("stats", &self.stats()).finish()
+    }
+}
+
+async fn run(
+    rpc_url: String,
+    window: Duration,
+    commitment: Option<CommitmentConfig>,
+    mut rx: mpsc::UnboundedReceiver<Pending>,
+    counters: Arc<Counters>,
+) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default();
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = tokio::time::Instant::now() + window;
+        while let Ok(Some(pending)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+            batch.push(pending);
+        }
+        counters.requests.fetch_add(batch.len() as u64, Ordering::Relaxed);
+        counters.http_calls.fetch_add(1, Ordering::Relaxed);
+        // Sent in the background so the next window starts right away
+        tokio::spawn(dispatch(client.clone(), rpc_url.clone(), commitment, batch));
+    }
+}
+
+async fn dispatch(
+    client: reqwest::Client,
+    rpc_url: String,
+    commitment: Option<CommitmentConfig>,
+    batch: Vec<Pending>,
+) {
+    let (requests, replies): (Vec<Request>, Vec<_>) =
+        batch.into_iter().map(|p| (p.request, p.reply)).unzip();
+    let (body, layout) = build_batch(&requests, commitment);
+
+    let response = async {
+        let response = client.post(&rpc_url).json(&body).send().await?;
+        response.error_for_status()?.json::<Value>().await
+    }
+    .await;
+    let results = match response {
+        Ok(response) => split_response(&layout, &response),
+        Err(e) => vec![Err(format!("RPC batch failed: {}", e)); requests.len()],
+    };
+    for (reply, result) in replies.into_iter().zip(results) {
+        let _ = reply.send(result);
+    }
+}
+
+/// Where each request's result is found in the batch response
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Slot {
+    /// Call id and index into its `value` array
+    Account(usize, usize),
+    Call(usize),
+}
+
+/// The JSON-RPC batch body for `requests`, and where each answer will be.
+/// Account lookups are made at `commitment` when given.
+fn build_batch(requests: &[Request], commitment: Option<CommitmentConfig>) -> (Value, Vec<Slot>) {
+    let mut keys: Vec<Pubkey> = Vec::new();
+    for request in requests {
+        if let Request::Account(key) = request {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+    }
+    let mut body: Vec<Value> = keys
+        .chunks(MAX_ACCOUNTS_PER_CALL)
+        .enumerate()
+        .map(|(id, chunk)| {
+            let chunk: Vec<String> = chunk.iter().map(Pubkey::to_string).collect();
+            let mut options = json!({"encoding": "base64"});
+            if let Some(commitment) = commitment {
+                options["commitment"] = json!(commitment.commitment);
+            }
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "getMultipleAccounts",
+                "params": [chunk, options],
+            })
+        })
+        .collect();
+
+    let layout = requests
+        .iter()
+        .map(|request| match request {
+            Request::Account(key) => {
+                let position = keys.iter().position(|k| k == key).unwrap_or_default();
+                Slot::Account(position / MAX_ACCOUNTS_PER_CALL, position % MAX_ACCOUNTS_PER_CALL)
+            }
+            Request::Call { method, params } => {
+                let id = body.len();
+                body.push(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+                Slot::Call(id)
+            }
+        })
+        .collect();
+    (Value::Array(body), layout)
+}
+
+/// Each request's result (or error) from a batch response
+fn split_response(layout: &[Slot], response: &Value) -> Vec<Result<Value, String>> {
+    let by_id = |id: usize| -> Result<&Value, String> {
+        let entry = response
+            .as_array()
+            .and_then(|entries| entries.iter().find(|e| e["id"].as_u64() == Some(id as u64)))
+            .ok_or_else(|| "missing from RPC batch response".to_string())?;
+        if let Some(error) = entry.get("error") {
+            return Err(format!("RPC error: {}", error["message"].as_str().unwrap_or("unknown")));
+        }
+        Ok(&entry["result"])
+    };
+    layout
+        .iter()
+        .map(|slot| match *slot {
+            Slot::Account(id, index) => by_id(id).map(|result| result["value"][index].clone()),
+            Slot::Call(id) => by_id(id).cloned(),
+        })
+        .collect()
+}
+
+/// An account from a base64 `getMultipleAccounts` entry (`null`: no account)
+fn parse_account(value: &Value) -> Result<Option<Account>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let data = value["data"][0]
+        .as_str()
+        .ok_or_else(|| anyhow!("account without base64 data"))?;
+    Ok(Some(Account {
+        lamports: value["lamports"].as_u64().unwrap_or_default(),
+        data: base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .context("invalid account data")?,
+        owner: Pubkey::from_str(value["owner"].as_str().unwrap_or_default())
+            .map_err(|_| anyhow!("invalid account owner"))?,
+        executable: value["executable"].as_bool().unwrap_or_default(),
+        rent_epoch: value["rentEpoch"].as_u64().unwrap_or_default(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_layout_and_response_split() {
+        let keys: Vec<Pubkey> = (0..102).map(|_| Pubkey::new_unique()).collect();
+        let mut requests: Vec<Request> = keys.iter().copied().map(Request::Account).collect();
+        requests.push(Request::Account(keys[5]));
+        requests.push(Request::Call {
+            method: "getTokenAccountBalance".to_string(),
+            params: json!(["x"]),
+        });
+
+        let (body, layout) = build_batch(&requests, Some(CommitmentConfig::processed()));
+        let calls = body.as_array().unwrap();
+        // 102 unique keys: two getMultipleAccounts calls plus the other call
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0]["params"][0].as_array().unwrap().len(), 100);
+        assert_eq!(calls[1]["params"][0].as_array().unwrap().len(), 2);
+        assert_eq!(calls[1]["params"][1]["commitment"], json!("processed"));
+        assert_eq!(calls[2]["method"], json!("getTokenAccountBalance"));
+        assert_eq!(layout[101], Slot::Account(1, 1));
+        assert_eq!(layout[102], Slot::Account(0, 5));
+        assert_eq!(layout[103], Slot::Call(2));
+
+        let account = json!({
+            "lamports": 42,
+            "owner": Pubkey::default().to_string(),
+            "data": ["AQID", "base64"],
+            "executable": false,
+            "rentEpoch": 0,
+        });
+        let mut first_chunk = vec![Value::Null; 100];
+        first_chunk[5] = account;
+        let response = json!([
+            {"id": 2, "error": {"code": -32602, "message": "could not find account"}},
+            {"id": 0, "result": {"value": first_chunk}},
+            {"id": 1, "result": {"value": [null, null]}},
+        ]);
+        let results = split_response(&layout, &response);
+        let fetched = parse_account(results[102].as_ref().unwrap()).unwrap().unwrap();
+        assert_eq!((fetched.lamports, fetched.data), (42, vec![1, 2, 3]));
+        assert!(parse_account(results[101].as_ref().unwrap()).unwrap().is_none());
+        assert_eq!(results[103], Err("RPC error: could not find account".to_string()));
+
+        // A response missing a call fails only the requests in it
+        let partial = split_response(&layout, &json!([{"id": 0, "result": {"value": []}}]));
+        assert!(partial[101].is_err());
+        assert!(partial[0].is_ok());
+    }
+}
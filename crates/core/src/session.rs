@@ -0,0 +1,150 @@
+//! Trading Sessions
+//!
+//! A session is one run of the bot: when it started and stopped, the mode it
+//! ran in, the configuration it ran with and the code revision. Trades are
+//! tagged with their session so KPIs can be compared between runs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One run of the bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// "live", "dry-run" or "watch-only"
+    pub mode: String,
+    /// Configuration the session was started with
+    pub config_snapshot: serde_json::Value,
+    pub git_rev: Option<String>,
+}
+
+impl Session {
+    /// A session starting now
+    pub fn start(id: &str, mode: &str, config_snapshot: serde_json::Value) -> Self {
+        Self {
+            id: id.to_string(),
+            started_at: Utc::now(),
+            ended_at: None,
+            mode: mode.to_string(),
+            config_snapshot,
+            git_rev: git_revision(),
+        }
+    }
+
+    /// Session length in seconds, `None` while it is still running
+    pub fn duration_secs(&self) -> Option<i64> {
+        self.ended_at.map(|end| (end - self.started_at).num_seconds())
+    }
+}
+
+/// Code revision from `GIT_REV` (set at build or deploy time), falling back
+/// to `git rev-parse` in the working directory
+pub fn git_revision() -> Option<String> {
+    let configured = option_env!("GIT_REV")
+        .map(str::to_string)
+        .or_else(|| std::env::var("GIT_REV").ok());
+    if let Some(rev) = configured.filter(|r| !r.is_empty()) {
+        return Some(rev);
+    }
+
+    std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|rev| rev.trim().to_string())
+        .filter(|rev| !rev.is_empty())
+}
+
+/// Key performance indicators for a session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionKpis {
+    pub trades: u64,
+    pub successful_trades: u64,
+    /// Successful trades / trades (0 with no trades)
+    pub win_rate: f64,
+    pub total_pnl_usd: f64,
+    /// Mean execution latency, `None` with no trades
+    pub avg_latency_ms: Option<f64>,
+}
+
+impl SessionKpis {
+    pub fn new(
+        trades: u64,
+        successful_trades: u64,
+        total_pnl_usd: f64,
+        avg_latency_ms: Option<f64>,
+    ) -> Self {
+        let win_rate = if trades == 0 {
+            0.0
+        } else {
+            successful_trades as f64 / trades as f64
+        };
+        Self {
+            trades,
+            successful_trades,
+            win_rate,
+            total_pnl_usd,
+            avg_latency_ms,
+        }
+    }
+}
+
+/// KPIs of two sessions side by side; deltas are `b - a`
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionComparison {
+    pub a: SessionKpis,
+    pub b: SessionKpis,
+    pub win_rate_delta: f64,
+    pub pnl_delta_usd: f64,
+    /// `None` unless both sessions have latency data
+    pub avg_latency_delta_ms: Option<f64>,
+}
+
+impl SessionComparison {
+    pub fn new(a: SessionKpis, b: SessionKpis) -> Self {
+        let avg_latency_delta_ms = match (a.avg_latency_ms, b.avg_latency_ms) {
+            (Some(a), Some(b)) => Some(b - a),
+            _ => None,
+        };
+        Self {
+            win_rate_delta: b.win_rate - a.win_rate,
+            pnl_delta_usd: b.total_pnl_usd - a.total_pnl_usd,
+            avg_latency_delta_ms,
+            a,
+            b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kpis_and_comparison() {
+        let a = SessionKpis::new(10, 6, 12.5, Some(420.0));
+        let b = SessionKpis::new(4, 3, 20.0, Some(380.0));
+        assert_eq!(a.win_rate, 0.6);
+        assert_eq!(SessionKpis::new(0, 0, 0.0, None).win_rate, 0.0);
+
+        let cmp = SessionComparison::new(a, b);
+        assert!((cmp.win_rate_delta - 0.15).abs() < 1e-12);
+        assert_eq!(cmp.pnl_delta_usd, 7.5);
+        assert_eq!(cmp.avg_latency_delta_ms, Some(-40.0));
+
+        let empty = SessionComparison::new(SessionKpis::default(), SessionKpis::new(1, 1, 1.0, Some(5.0)));
+        assert_eq!(empty.avg_latency_delta_ms, None);
+    }
+
+    #[test]
+    fn test_session_duration() {
+        let mut session = Session::start("SESSION-1", "dry-run", serde_json::json!({}));
+        assert_eq!(session.duration_secs(), None);
+        session.ended_at = Some(session.started_at + chrono::Duration::seconds(90));
+        assert_eq!(session.duration_secs(), Some(90));
+    }
+}
@@ -0,0 +1,181 @@
+//! Streaming Price Aggregator
+//!
+//! Collects every streamed price (WebSocket streams through
+//! [`WebSocketManager`](super::ws_manager), or a provider's [`PriceStream`])
+//! into one cache of the latest price per pair and venue. Consumers wait for
+//! the next updates instead of polling on a fixed interval.
+//!
+//! Each expected stream is registered with [`StreamingPriceAggregator::expect`].
+//! A pair counts as streamed while every stream expected for it has delivered
+//! within `stale_after`; the rest are [`stale_pairs`] for the caller to poll
+//! until their streams recover.
+//!
+//! [`stale_pairs`]: StreamingPriceAggregator::stale_pairs
+
+use crate::dex::PriceStream;
+use crate::types::{DexType, PriceData, TokenPair};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Notify};
+
+type StreamKey = (TokenPair, DexType);
+
+/// Latest streamed prices, shared by the consumer task and the aggregator
+#[derive(Default)]
+struct Shared {
+    latest: Mutex<HashMap<StreamKey, (PriceData, Instant)>>,
+    /// Updates not yet taken by [`StreamingPriceAggregator::next_batch`]
+    pending: Mutex<HashMap<StreamKey, PriceData>>,
+    expected: Mutex<HashSet<StreamKey>>,
+    notify: Notify,
+}
+
+/// Shared cache of streamed prices
+pub struct StreamingPriceAggregator {
+    tx: mpsc::Sender<PriceData>,
+    shared: Arc<Shared>,
+    stale_after: Duration,
+}
+
+impl StreamingPriceAggregator {
+    /// Starts consuming updates; a stream silent for `stale_after` falls
+    /// back to polling
+    pub fn spawn(capacity: usize, stale_after: Duration) -> Self {
+        let (tx, mut rx) = mpsc::channel::<PriceData>(capacity.max(1));
+        let shared = Arc::new(Shared::default());
+        let consumer = shared.clone();
+        tokio::spawn(async move {
+            while let Some(price) = rx.recv().await {
+                let key = (price.pair.canonical(), price.dex);
+                consumer
+                    .latest
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(key.clone(), (price.clone(), Instant::now()));
+                consumer
+                    .pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(key, price);
+                consumer.notify.notify_one();
+            }
+        });
+        Self { tx, shared, stale_after }
+    }
+
+    /// Channel streams send their prices into
+    pub fn sender(&self) -> mpsc::Sender<PriceData> {
+        self.tx.clone()
+    }
+
+    /// Forwards a provider's price stream
+    pub fn attach(&self, mut stream: PriceStream) {
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            while let Some(price) = stream.recv().await {
+                if tx.send(price).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Registers a stream of `pair` on `dex`; the pair is polled while it is
+    /// stale
+    pub fn expect(&self, dex: DexType, pair: &TokenPair) {
+        self.shared
+            .expected
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((pair.canonical(), dex));
+    }
+
+    /// Pairs of `pairs` without a stream, or with an expected stream that
+    /// has not delivered within `stale_after`
+    pub fn stale_pairs(&self, pairs: &[TokenPair]) -> Vec<TokenPair> {
+        let expected = self.shared.expected.lock().unwrap_or_else(|e| e.into_inner());
+        let latest = self.shared.latest.lock().unwrap_or_else(|e| e.into_inner());
+        pairs
+            .iter()
+            .filter(|pair| {
+                let pair = pair.canonical();
+                let mut streams = expected.iter().filter(|(p, _)| *p == pair).peekable();
+                streams.peek().is_none()
+                    || streams.any(|key| {
+                        latest
+                            .get(key)
+                            .is_none_or(|(_, at)| at.elapsed() > self.stale_after)
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Waits up to `timeout` for updates and takes every one waiting, one
+    /// (the newest) per pair and venue. Empty when none arrived.
+    pub async fn next_batch(&self, timeout: Duration) -> Vec<PriceData> {
+        let waiting = !self.shared.pending.lock().unwrap_or_else(|e| e.into_inner()).is_empty();
+        if !waiting {
+            let _ = tokio::time::timeout(timeout, self.shared.notify.notified()).await;
+        }
+        self.take_pending()
+    }
+
+    /// Takes the updates waiting, without blocking
+    pub fn take_pending(&self) -> Vec<PriceData> {
+        let mut pending = self.shared.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.drain().map(|(_, price)| price).collect()
+    }
+
+    /// Latest streamed price of `pair` on `dex`
+    pub fn latest(&self, pair: &TokenPair, dex: DexType) -> Option<PriceData> {
+        self.shared
+            .latest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(pair.canonical(), dex))
+            .map(|(price, _)| price.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[tokio::test]
+    async fn test_streamed_updates_batch_and_stale_pairs_fall_back() {
+        let streams = StreamingPriceAggregator::spawn(16, Duration::from_millis(200));
+        let (sol, ray) = (TokenPair::new("SOL", "USDC"), TokenPair::new("RAY", "USDC"));
+        streams.expect(DexType::Jupiter, &sol);
+        streams.expect(DexType::Raydium, &sol);
+        assert_eq!(streams.stale_pairs(&[sol.clone(), ray.clone()]), vec![sol.clone(), ray.clone()]);
+        assert!(streams.next_batch(Duration::from_millis(20)).await.is_empty());
+
+        let price = |dex, bid| PriceData::new(dex, sol.clone(), Decimal::from(bid), Decimal::from(bid + 1));
+        let (stream_tx, stream_rx) = mpsc::channel(4);
+        streams.attach(stream_rx);
+        stream_tx.send(price(DexType::Jupiter, 100)).await.unwrap();
+        let batch = streams.next_batch(Duration::from_secs(1)).await;
+        assert_eq!(batch.iter().map(|p| p.bid).collect::<Vec<_>>(), vec![Decimal::from(100)]);
+
+        // Only the newest update per venue is taken
+        let sender = streams.sender();
+        for (dex, bid) in [(DexType::Jupiter, 101), (DexType::Raydium, 102), (DexType::Jupiter, 103)] {
+            sender.send(price(dex, bid)).await.unwrap();
+        }
+        while streams.latest(&sol, DexType::Jupiter).unwrap().bid != Decimal::from(103) {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let mut bids: Vec<_> = streams.take_pending().iter().map(|p| p.bid).collect();
+        bids.sort();
+        assert_eq!(bids, vec![Decimal::from(102), Decimal::from(103)]);
+        assert_eq!(streams.latest(&sol, DexType::Raydium).unwrap().bid, Decimal::from(102));
+        assert_eq!(streams.stale_pairs(&[sol.clone(), ray.clone()]), vec![ray.clone()]);
+
+        // The SOL streams go quiet; RAY has none and is always polled
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(streams.stale_pairs(&[sol.clone(), ray.clone()]), vec![sol, ray]);
+    }
+}
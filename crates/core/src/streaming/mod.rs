@@ -1,2 +1,5 @@
+pub mod aggregator;
+pub mod ordering;
+pub mod pool_accounts;
 #[cfg(feature = "ws")]
 pub mod ws_manager;
@@ -0,0 +1,338 @@
+//! Stream Tick Ordering
+//!
+//! WebSocket price streams can deliver ticks late or out of order: a
+//! reconnect replays what the server buffered, and relays fan several
+//! upstream connections into one. Strategies keep rolling windows in arrival
+//! order, so a stale tick landing after a newer one corrupts them. Each
+//! venue/pair stream goes through a [`TickOrderer`]:
+//!
+//! - Ticks are held for up to `reorder_window` and released in order of
+//!   their sequence number, or of their timestamp when the venue sends none.
+//!   A tick that continues the sequence is released at once.
+//! - A tick at or before the last one released, or not newer than the last
+//!   snapshot, is dropped as late.
+//! - A jump in the sequence, or no tick for `max_silence`, is reported as a
+//!   [`Gap`]. The caller refreshes the stream from a snapshot and resyncs.
+//!
+//! Late, duplicate and reordered ticks and gaps are counted in
+//! [`StreamStats`], shared by every stream.
+
+use crate::types::PriceData;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderingConfig {
+    /// Longest a tick is held waiting for earlier ones (zero: released at
+    /// once, late ticks are still dropped)
+    pub reorder_window: Duration,
+    /// Ticks held at most; beyond it the earliest is released early
+    pub buffer_capacity: usize,
+    /// Silence after which the stream is refreshed from a snapshot
+    pub max_silence: Duration,
+}
+
+impl Default for OrderingConfig {
+    fn default() -> Self {
+        Self {
+            reorder_window: Duration::from_millis(250),
+            buffer_capacity: 64,
+            max_silence: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Ticks a stream is missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Gap {
+    /// Sequence numbers `expected` up to `received` (exclusive) never arrived
+    Sequence { expected: u64, received: u64 },
+    /// No tick arrived for this long
+    Silence { secs: u64 },
+}
+
+#[derive(Debug, Default)]
+pub struct StreamStats {
+    received: AtomicU64,
+    released: AtomicU64,
+    late: AtomicU64,
+    duplicate: AtomicU64,
+    reordered: AtomicU64,
+    gaps: AtomicU64,
+    missing: AtomicU64,
+    refreshes: AtomicU64,
+}
+
+/// Tick counts across all streams, for status reporting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StreamStatsSnapshot {
+    pub received: u64,
+    pub released: u64,
+    /// Dropped: at or before the last tick released
+    pub late: u64,
+    /// Dropped: same position as a tick already held
+    pub duplicate: u64,
+    /// Arrived before a tick it follows, and put back in order
+    pub reordered: u64,
+    pub gaps: u64,
+    /// Sequence numbers skipped by gaps
+    pub missing: u64,
+    /// Snapshot refreshes after gaps
+    pub refreshes: u64,
+}
+
+impl StreamStats {
+    pub fn snapshot(&self) -> StreamStatsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        StreamStatsSnapshot {
+            received: load(&self.received),
+            released: load(&self.released),
+            late: load(&self.late),
+            duplicate: load(&self.duplicate),
+            reordered: load(&self.reordered),
+            gaps: load(&self.gaps),
+            missing: load(&self.missing),
+            refreshes: load(&self.refreshes),
+        }
+    }
+
+    fn bump(counter: &AtomicU64, by: u64) {
+        counter.fetch_add(by, Ordering::Relaxed);
+    }
+}
+
+/// Ticks released by the orderer, in order, and the gap found releasing them
+#[derive(Debug, Default)]
+pub struct Released {
+    pub ticks: Vec<PriceData>,
+    pub gap: Option<Gap>,
+}
+
+/// Position of a tick: its sequence number if the venue sends one, then its
+/// timestamp
+type Position = (Option<u64>, DateTime<Utc>);
+
+/// Puts one venue/pair stream back in order
+#[derive(Debug)]
+pub struct TickOrderer {
+    config: OrderingConfig,
+    stats: Arc<StreamStats>,
+    /// Held ticks with their arrival
+    buffer: BTreeMap<Position, (Instant, PriceData)>,
+    /// Position of the last tick released
+    last: Option<Position>,
+    /// Ticks at or before the last snapshot are stale
+    snapshot_at: Option<DateTime<Utc>>,
+    last_arrival: Instant,
+    silence_reported: bool,
+}
+
+impl TickOrderer {
+    pub fn new(config: OrderingConfig, stats: Arc<StreamStats>) -> Self {
+        Self {
+            config,
+            stats,
+            buffer: BTreeMap::new(),
+            last: None,
+            snapshot_at: None,
+            last_arrival: Instant::now(),
+            silence_reported: false,
+        }
+    }
+
+    /// Accept a tick as it arrives
+    pub fn push(&mut self, sequence: Option<u64>, price: PriceData, now: Instant) -> Released {
+        StreamStats::bump(&self.stats.received, 1);
+        self.last_arrival = now;
+        self.silence_reported = false;
+
+        let position = (sequence, price.timestamp);
+        if self.last.is_some_and(|last| position <= last)
+            || self.snapshot_at.is_some_and(|at| price.timestamp <= at)
+        {
+            StreamStats::bump(&self.stats.late, 1);
+            return Released::default();
+        }
+        if self.buffer.contains_key(&position) {
+            StreamStats::bump(&self.stats.duplicate, 1);
+            return Released::default();
+        }
+        if self
+            .buffer
+            .last_key_value()
+            .is_some_and(|(held, _)| *held > position)
+        {
+            StreamStats::bump(&self.stats.reordered, 1);
+        }
+        self.buffer.insert(position, (now, price));
+
+        let mut released = Released::default();
+        while let Some((&first, _)) = self.buffer.first_key_value() {
+            let continues = matches!(
+                (first.0, self.last.and_then(|last| last.0)),
+                (Some(next), Some(last)) if next == last + 1
+            );
+            if continues
+                || self.config.reorder_window.is_zero()
+                || self.buffer.len() > self.config.buffer_capacity
+            {
+                self.release_first(&mut released);
+            } else {
+                break;
+            }
+        }
+        released
+    }
+
+    /// Release ticks held for the whole reorder window, with every tick
+    /// ordered before them, and report a silent stream. Call on a timer.
+    pub fn flush(&mut self, now: Instant) -> Released {
+        let mut released = Released::default();
+        let expired = self
+            .buffer
+            .iter()
+            .filter(|(_, (arrived, _))| now.duration_since(*arrived) >= self.config.reorder_window)
+            .map(|(position, _)| *position)
+            .max();
+        if let Some(until) = expired {
+            while self
+                .buffer
+                .first_key_value()
+                .is_some_and(|(position, _)| *position <= until)
+            {
+                self.release_first(&mut released);
+            }
+        }
+
+        let silent = now.duration_since(self.last_arrival);
+        if !self.silence_reported && silent >= self.config.max_silence {
+            self.silence_reported = true;
+            StreamStats::bump(&self.stats.gaps, 1);
+            released.gap.get_or_insert(Gap::Silence {
+                secs: silent.as_secs(),
+            });
+        }
+        released
+    }
+
+    /// Continue from a snapshot fetched after a gap: ticks not newer than
+    /// it are dropped, and the next sequence number is accepted as is
+    pub fn resync(&mut self, snapshot: &PriceData) {
+        StreamStats::bump(&self.stats.refreshes, 1);
+        self.buffer.retain(|(_, at), _| *at > snapshot.timestamp);
+        self.last = None;
+        self.snapshot_at = Some(snapshot.timestamp);
+    }
+
+    fn release_first(&mut self, released: &mut Released) {
+        let Some((position, (_, price))) = self.buffer.pop_first() else {
+            return;
+        };
+        if let (Some(received), Some(Some(last))) = (position.0, self.last.map(|last| last.0)) {
+            if received > last + 1 {
+                StreamStats::bump(&self.stats.gaps, 1);
+                StreamStats::bump(&self.stats.missing, received - last - 1);
+                released.gap.get_or_insert(Gap::Sequence {
+                    expected: last + 1,
+                    received,
+                });
+            }
+        }
+        self.last = Some(position);
+        StreamStats::bump(&self.stats.released, 1);
+        released.ticks.push(price);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DexType, TokenPair};
+    use rust_decimal::Decimal;
+
+    fn tick(mid: i64, at_ms: i64) -> PriceData {
+        let mut price = PriceData::new(
+            DexType::Raydium,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::from(mid),
+            Decimal::from(mid),
+        );
+        price.timestamp = DateTime::from_timestamp_millis(1_700_000_000_000 + at_ms).unwrap();
+        price
+    }
+
+    fn mids(released: &Released) -> Vec<i64> {
+        released
+            .ticks
+            .iter()
+            .map(|p| p.mid_price.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_orders_drops_late_and_reports_gaps() {
+        let stats = Arc::new(StreamStats::default());
+        let config = OrderingConfig {
+            reorder_window: Duration::from_millis(100),
+            buffer_capacity: 8,
+            max_silence: Duration::from_secs(5),
+        };
+        let mut orderer = TickOrderer::new(config, stats.clone());
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Sequenced: 1 is held for the window, 3 arrives before 2
+        assert!(orderer.push(Some(1), tick(1, 0), at(0)).ticks.is_empty());
+        assert_eq!(mids(&orderer.flush(at(100))), vec![1]);
+        assert!(orderer.push(Some(3), tick(3, 20), at(110)).ticks.is_empty());
+        assert_eq!(mids(&orderer.push(Some(2), tick(2, 10), at(120))), vec![2, 3]);
+        assert!(orderer.push(Some(2), tick(2, 10), at(130)).ticks.is_empty());
+
+        // 4 and 5 never arrive: 6 is released after the window with a gap
+        orderer.push(Some(6), tick(6, 50), at(140));
+        let released = orderer.flush(at(240));
+        assert_eq!(mids(&released), vec![6]);
+        assert_eq!(released.gap, Some(Gap::Sequence { expected: 4, received: 6 }));
+
+        // Silence, then a snapshot refresh drops ticks older than it
+        let released = orderer.flush(at(6000));
+        assert!(matches!(released.gap, Some(Gap::Silence { secs: 5 })));
+        assert!(orderer.flush(at(7000)).gap.is_none());
+        orderer.resync(&tick(9, 1000));
+        assert!(orderer.push(Some(7), tick(7, 900), at(7100)).ticks.is_empty());
+        orderer.push(Some(20), tick(20, 1100), at(7100));
+        let released = orderer.flush(at(7200));
+        assert_eq!(mids(&released), vec![20]);
+        assert_eq!(released.gap, None);
+
+        assert_eq!(
+            stats.snapshot(),
+            StreamStatsSnapshot {
+                received: 7,
+                released: 5,
+                late: 2,
+                duplicate: 0,
+                reordered: 1,
+                gaps: 2,
+                missing: 2,
+                refreshes: 1,
+            }
+        );
+
+        // Unsequenced ticks order by timestamp; no window releases at once
+        let mut orderer = TickOrderer::new(
+            OrderingConfig {
+                reorder_window: Duration::ZERO,
+                ..config
+            },
+            stats.clone(),
+        );
+        assert_eq!(mids(&orderer.push(None, tick(2, 10), at(0))), vec![2]);
+        assert!(orderer.push(None, tick(1, 5), at(1)).ticks.is_empty());
+        assert_eq!(stats.snapshot().late, 3);
+    }
+}
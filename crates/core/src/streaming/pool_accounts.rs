@@ -0,0 +1,213 @@
+//! Pool Account Streams
+//!
+//! Venues without a price WebSocket of their own (Orca Whirlpools, Meteora
+//! DLMM) are streamed by subscribing to their pool accounts over the Solana
+//! RPC pubsub endpoint. Every account update carries the pool's state, from
+//! which the spot price is decoded:
+//!
+//! - Whirlpool: `sqrt_price` (Q64.64), price = sqrt_price² · 10^(decimals_a − decimals_b)
+//! - DLMM: `active_id` and `bin_step`, price = (1 + bin_step / 10⁴)^active_id · 10^(decimals_x − decimals_y)
+//!
+//! Bid and ask are both the spot price, like other single-price streams;
+//! swap fees are applied by detection.
+
+use crate::tokens::TokenRegistry;
+use crate::types::{DexType, PriceData, TokenPair};
+use crate::{ArbitrageError, ArbitrageResult};
+use base64::Engine;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde_json::json;
+
+/// Venues streamed from pool accounts
+pub const ACCOUNT_STREAMED_DEXES: &[DexType] = &[DexType::Orca, DexType::Meteora];
+
+/// Offset of `sqrt_price` (u128) in a Whirlpool account
+const WHIRLPOOL_SQRT_PRICE_OFFSET: usize = 65;
+/// Offset of `active_id` (i32) in a DLMM `LbPair` account
+const DLMM_ACTIVE_ID_OFFSET: usize = 76;
+/// Offset of `bin_step` (u16) in a DLMM `LbPair` account
+const DLMM_BIN_STEP_OFFSET: usize = 80;
+
+/// A pool whose account is streamed. `pair` is in the pool's token order:
+/// its base is the pool's first token (Whirlpool A, DLMM X).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolAccount {
+    pub dex: DexType,
+    pub pair: TokenPair,
+    pub address: String,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+}
+
+impl PoolAccount {
+    /// The `accountSubscribe` request for this pool
+    pub fn subscribe_request(&self, commitment: &str) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "accountSubscribe",
+            "params": [self.address, {"encoding": "base64", "commitment": commitment}],
+        })
+        .to_string()
+    }
+
+    /// Decode the spot price from the pool's account data
+    pub fn price(&self, data: &[u8]) -> ArbitrageResult<PriceData> {
+        let raw = match self.dex {
+            DexType::Orca => {
+                let sqrt_price = u128::from_le_bytes(read(data, WHIRLPOOL_SQRT_PRICE_OFFSET)?);
+                let sqrt = sqrt_price as f64 / 2f64.powi(64);
+                sqrt * sqrt
+            }
+            DexType::Meteora => {
+                let active_id = i32::from_le_bytes(read(data, DLMM_ACTIVE_ID_OFFSET)?);
+                let bin_step = u16::from_le_bytes(read(data, DLMM_BIN_STEP_OFFSET)?);
+                (1.0 + f64::from(bin_step) / 10_000.0).powi(active_id)
+            }
+            other => return Err(ArbitrageError::Config(format!("{} pools are not streamed", other))),
+        };
+        let scale = 10f64.powi(i32::from(self.base_decimals) - i32::from(self.quote_decimals));
+        let price = Decimal::from_f64(raw * scale)
+            .filter(|price| *price > Decimal::ZERO)
+            .ok_or_else(|| ArbitrageError::PriceFetch(format!("no price in {} pool {}", self.dex, self.address)))?;
+        Ok(PriceData::new(self.dex, self.pair.clone(), price, price))
+    }
+
+    /// The price carried by an `accountNotification`, `Ok(None)` for other
+    /// messages (subscription acks)
+    pub fn parse_notification(&self, text: &str) -> Result<Option<PriceData>, String> {
+        let json: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        if let Some(error) = json.get("error") {
+            return Err(format!("RPC error: {}", error));
+        }
+        if json.get("method").and_then(|m| m.as_str()) != Some("accountNotification") {
+            return Ok(None);
+        }
+        let encoded = json
+            .pointer("/params/result/value/data/0")
+            .and_then(|d| d.as_str())
+            .ok_or_else(|| "notification without account data".to_string())?;
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Invalid account data: {}", e))?;
+        self.price(&data).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> ArbitrageResult<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| ArbitrageError::PriceFetch(format!("pool account too short ({} bytes)", data.len())))
+}
+
+/// Parse `dex:BASE/QUOTE=address,...`, taking decimals from `tokens`
+pub fn parse_pool_accounts(spec: &str, tokens: &TokenRegistry) -> ArbitrageResult<Vec<PoolAccount>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(|entry| {
+            let invalid = || ArbitrageError::Config(format!("expected dex:BASE/QUOTE=address, got '{}'", entry));
+            let (name, rest) = entry.split_once(':').ok_or_else(invalid)?;
+            let (pair, address) = rest.split_once('=').ok_or_else(invalid)?;
+            let (base, quote) = pair.split_once('/').ok_or_else(invalid)?;
+            let dex = ACCOUNT_STREAMED_DEXES
+                .iter()
+                .find(|dex| dex.display_name().eq_ignore_ascii_case(name.trim()))
+                .copied()
+                .ok_or_else(|| ArbitrageError::Config(format!("'{}' pools are not streamed", name.trim())))?;
+            let decimals = |token: &str| {
+                tokens
+                    .decimals(token)
+                    .ok_or_else(|| ArbitrageError::Config(format!("unknown token '{}'", token)))
+            };
+            Ok(PoolAccount {
+                dex,
+                pair: TokenPair::new(base.trim(), quote.trim()),
+                address: address.trim().to_string(),
+                base_decimals: decimals(base.trim())?,
+                quote_decimals: decimals(quote.trim())?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(dex: DexType) -> PoolAccount {
+        PoolAccount {
+            dex,
+            pair: TokenPair::new("SOL", "USDC"),
+            address: "pool".to_string(),
+            base_decimals: 9,
+            quote_decimals: 6,
+        }
+    }
+
+    #[test]
+    fn test_whirlpool_price_from_sqrt_price() {
+        // 150 USDC per SOL is 0.15 atoms per lamport
+        let sqrt_price = (0.15f64.sqrt() * 2f64.powi(64)) as u128;
+        let mut data = vec![0u8; 653];
+        data[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+
+        let price = pool(DexType::Orca).price(&data).unwrap();
+        assert!((price.mid_price - Decimal::from(150)).abs() < Decimal::new(1, 6));
+        assert_eq!(price.bid, price.ask);
+        assert!(pool(DexType::Orca).price(&data[..70]).is_err());
+    }
+
+    #[test]
+    fn test_dlmm_price_from_active_bin() {
+        // 1.0025^-2578 ≈ 0.0016, or 1.6 USDC per SOL after decimals
+        let mut data = vec![0u8; 904];
+        data[76..80].copy_from_slice(&(-2578i32).to_le_bytes());
+        data[80..82].copy_from_slice(&25u16.to_le_bytes());
+
+        let price = pool(DexType::Meteora).price(&data).unwrap();
+        let expected = 1.0025f64.powi(-2578) * 1000.0;
+        assert!((price.mid_price - Decimal::from_f64(expected).unwrap()).abs() < Decimal::new(1, 6));
+    }
+
+    #[test]
+    fn test_account_notification() {
+        let pool = pool(DexType::Orca);
+        assert!(pool.parse_notification(r#"{"jsonrpc":"2.0","result":23784,"id":1}"#).unwrap().is_none());
+
+        let sqrt_price = (0.15f64.sqrt() * 2f64.powi(64)) as u128;
+        let mut data = vec![0u8; 653];
+        data[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "accountNotification",
+            "params": {
+                "result": {
+                    "context": {"slot": 5199307},
+                    "value": {"data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"]}
+                },
+                "subscription": 23784
+            }
+        });
+        let price = pool.parse_notification(&notification.to_string()).unwrap().unwrap();
+        assert_eq!(price.dex, DexType::Orca);
+        assert!(pool.parse_notification(r#"{"error":{"code":-32602}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_pool_accounts() {
+        let tokens = TokenRegistry::mainnet();
+        let pools = parse_pool_accounts("orca:SOL/USDC=abc, meteora:SOL/USDC=def", &tokens).unwrap();
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[1].dex, DexType::Meteora);
+        assert_eq!((pools[0].base_decimals, pools[0].quote_decimals), (9, 6));
+        assert_eq!(pools[1].address, "def");
+
+        assert!(parse_pool_accounts("raydium:SOL/USDC=abc", &tokens).is_err());
+        assert!(parse_pool_accounts("orca:SOL-USDC=abc", &tokens).is_err());
+        assert!(parse_pool_accounts("orca:NOPE/USDC=abc", &tokens).is_err());
+        assert!(parse_pool_accounts("", &tokens).unwrap().is_empty());
+    }
+}
@@ -1,8 +1,17 @@
+use crate::dex::DexProvider;
+use crate::streaming::ordering::{
+    Gap, OrderingConfig, Released, StreamStats, StreamStatsSnapshot, TickOrderer,
+};
+use crate::streaming::pool_accounts::PoolAccount;
 use crate::types::{DexType, PriceData, TokenPair};
+use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use rust_decimal::Decimal;
 use serde_json::json;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
@@ -10,14 +19,40 @@ pub struct WebSocketManager {
     price_tx: mpsc::Sender<PriceData>,
     reconnect_delay_ms: u64,
     max_reconnect_attempts: u32,
+    ordering: OrderingConfig,
+    stream_stats: Arc<StreamStats>,
+    /// HTTP providers a stream is refreshed from after a gap
+    snapshot_sources: HashMap<DexType, Arc<dyn DexProvider>>,
+    /// Solana RPC pubsub endpoint pool accounts are subscribed on
+    rpc_ws_url: Option<String>,
+    account_commitment: String,
+    /// Pools streamed from their accounts, by venue and canonical pair
+    pool_accounts: HashMap<(DexType, TokenPair), PoolAccount>,
+}
+
+/// Where a stream's prices come from and how its messages are read
+enum StreamSource {
+    /// The venue's own price WebSocket
+    Venue { url: String },
+    /// A pool account subscription on the RPC pubsub endpoint
+    Account { url: String, pool: PoolAccount },
 }
 
 impl WebSocketManager {
+    /// Venues with a price stream
+    pub const STREAMED_DEXES: &'static [DexType] = &[DexType::Jupiter, DexType::Raydium];
+
     pub fn new(price_tx: mpsc::Sender<PriceData>) -> Self {
         Self {
             price_tx,
             reconnect_delay_ms: 1000,
             max_reconnect_attempts: 10,
+            ordering: OrderingConfig::default(),
+            stream_stats: Arc::new(StreamStats::default()),
+            snapshot_sources: HashMap::new(),
+            rpc_ws_url: None,
+            account_commitment: "confirmed".to_string(),
+            pool_accounts: HashMap::new(),
         }
     }
 
@@ -27,10 +62,58 @@ impl WebSocketManager {
         self
     }
 
+    /// Reordering window, buffer size and silence limit of every stream
+    pub fn with_ordering(mut self, ordering: OrderingConfig) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Refresh streams of `provider`'s DEX from it when they have a gap
+    pub fn with_snapshot_source(mut self, provider: Arc<dyn DexProvider>) -> Self {
+        self.snapshot_sources.insert(provider.dex_type(), provider);
+        self
+    }
+
+    /// Stream `pools` from their accounts over the RPC pubsub endpoint
+    /// `rpc_ws_url`, at `commitment`
+    pub fn with_pool_accounts(mut self, rpc_ws_url: &str, commitment: &str, pools: Vec<PoolAccount>) -> Self {
+        self.rpc_ws_url = Some(rpc_ws_url.to_string());
+        self.account_commitment = commitment.to_string();
+        self.pool_accounts = pools
+            .into_iter()
+            .map(|pool| ((pool.dex, pool.pair.canonical()), pool))
+            .collect();
+        self
+    }
+
+    /// Venues `pair` can be streamed from: those with a price WebSocket,
+    /// and those with a configured pool account
+    pub fn streamed_dexes(&self, pair: &TokenPair) -> Vec<DexType> {
+        let mut dexes = Self::STREAMED_DEXES.to_vec();
+        if self.rpc_ws_url.is_some() {
+            let canonical = pair.canonical();
+            dexes.extend(
+                self.pool_accounts
+                    .keys()
+                    .filter(|(_, pool_pair)| *pool_pair == canonical)
+                    .map(|(dex, _)| *dex),
+            );
+        }
+        dexes
+    }
+
+    /// Late, reordered and dropped tick counts across all streams
+    pub fn stream_stats(&self) -> StreamStatsSnapshot {
+        self.stream_stats.snapshot()
+    }
+
     /// Start a WebSocket subscription with automatic reconnection on disconnect.
+    /// Ordering carries over reconnects, so ticks the server replays are
+    /// dropped as late.
     pub async fn start_with_reconnection(&self, dex: DexType, pair: TokenPair) {
         let mut attempt = 0u32;
         let mut delay = self.reconnect_delay_ms;
+        let mut orderer = TickOrderer::new(self.ordering, self.stream_stats.clone());
 
         loop {
             tracing::info!(
@@ -41,7 +124,7 @@ impl WebSocketManager {
                 dex
             );
 
-            self.subscribe_to_pair(dex, pair.clone()).await;
+            self.run_stream(dex, &pair, &mut orderer).await;
 
             attempt += 1;
             if attempt >= self.max_reconnect_attempts {
@@ -67,12 +150,31 @@ impl WebSocketManager {
     }
 
     pub async fn subscribe_to_pair(&self, dex: DexType, pair: TokenPair) {
-        let url = match dex {
-            DexType::Jupiter => "wss://quote-api.jup.ag/v6/quote-ws".to_string(),
-            DexType::Raydium => {
-                format!("wss://api.raydium.io/v2/main/price/{}", pair.symbol())
-            }
-            _ => return,
+        let mut orderer = TickOrderer::new(self.ordering, self.stream_stats.clone());
+        self.run_stream(dex, &pair, &mut orderer).await;
+    }
+
+    fn stream_source(&self, dex: DexType, pair: &TokenPair) -> Option<StreamSource> {
+        match dex {
+            DexType::Jupiter => Some(StreamSource::Venue {
+                url: "wss://quote-api.jup.ag/v6/quote-ws".to_string(),
+            }),
+            DexType::Raydium => Some(StreamSource::Venue {
+                url: format!("wss://api.raydium.io/v2/main/price/{}", pair.symbol()),
+            }),
+            _ => Some(StreamSource::Account {
+                url: self.rpc_ws_url.clone()?,
+                pool: self.pool_accounts.get(&(dex, pair.canonical()))?.clone(),
+            }),
+        }
+    }
+
+    async fn run_stream(&self, dex: DexType, pair: &TokenPair, orderer: &mut TickOrderer) {
+        let Some(source) = self.stream_source(dex, pair) else {
+            return;
+        };
+        let url = match &source {
+            StreamSource::Venue { url } | StreamSource::Account { url, .. } => url,
         };
 
         let result = connect_async(url.as_str()).await;
@@ -86,77 +188,88 @@ impl WebSocketManager {
                 ) = ws_stream.split();
 
                 // Send subscribe message
-                let subscribe_msg = json!({
-                    "method": "subscribe",
-                    "params": [pair.symbol()]
-                });
-                if let Err(e) = write.send(Message::Text(subscribe_msg.to_string())).await {
+                let subscribe_msg = match &source {
+                    StreamSource::Venue { .. } => json!({
+                        "method": "subscribe",
+                        "params": [pair.symbol()]
+                    })
+                    .to_string(),
+                    StreamSource::Account { pool, .. } => pool.subscribe_request(&self.account_commitment),
+                };
+                if let Err(e) = write.send(Message::Text(subscribe_msg)).await {
                     tracing::error!("Failed to send subscribe message: {}", e);
                     return;
                 }
 
-                let price_tx = self.price_tx.clone();
-                let pair_clone = pair.clone();
+                // Held ticks are released, and silence detected, on this timer
+                let mut flush = tokio::time::interval(
+                    (self.ordering.reorder_window / 2).max(Duration::from_millis(10)),
+                );
 
                 // Process messages until disconnect
-                while let Some(msg_result) = read.next().await {
-                    match msg_result {
-                        Ok(Message::Text(text)) => {
-                            match Self::parse_price_message(&text, dex, &pair_clone) {
-                                Ok(Some(price_data)) => {
-                                    if let Err(e) = price_tx.send(price_data).await {
-                                        tracing::error!(
-                                            "Failed to send price update through channel: {}",
+                loop {
+                    let released = tokio::select! {
+                        _ = flush.tick() => orderer.flush(Instant::now()),
+                        msg_result = read.next() => match msg_result {
+                            Some(Ok(Message::Text(text))) => {
+                                match Self::parse_source_message(&source, &text, dex, pair) {
+                                    Ok(Some((sequence, price_data))) => {
+                                        orderer.push(sequence, price_data, Instant::now())
+                                    }
+                                    Ok(None) => {
+                                        // Non-price message (heartbeat, ack, etc.) – ignore
+                                        continue;
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!(
+                                            "Failed to parse WS message for {} on {:?}: {}",
+                                            pair,
+                                            dex,
                                             e
                                         );
-                                        break;
+                                        continue;
                                     }
                                 }
-                                Ok(None) => {
-                                    // Non-price message (heartbeat, ack, etc.) – ignore
-                                }
-                                Err(e) => {
-                                    tracing::warn!(
-                                        "Failed to parse WS message for {} on {:?}: {}",
-                                        pair_clone,
-                                        dex,
-                                        e
-                                    );
+                            }
+                            Some(Ok(Message::Ping(payload))) => {
+                                tracing::trace!("Received ping, sending pong");
+                                if let Err(e) = write.send(Message::Pong(payload)).await {
+                                    tracing::warn!("Failed to send pong: {}", e);
+                                    break;
                                 }
+                                continue;
+                            }
+                            Some(Ok(Message::Close(frame))) => {
+                                tracing::info!(
+                                    "WS closed by server for {} on {:?}: {:?}",
+                                    pair,
+                                    dex,
+                                    frame
+                                );
+                                break;
+                            }
+                            Some(Ok(_)) => {
+                                // Binary, Pong, Frame – ignore
+                                continue;
                             }
-                        }
-                        Ok(Message::Ping(payload)) => {
-                            tracing::trace!("Received ping, sending pong");
-                            if let Err(e) = write.send(Message::Pong(payload)).await {
-                                tracing::warn!("Failed to send pong: {}", e);
+                            Some(Err(e)) => {
+                                tracing::error!(
+                                    "WS read error for {} on {:?}: {}",
+                                    pair,
+                                    dex,
+                                    e
+                                );
                                 break;
                             }
-                        }
-                        Ok(Message::Close(frame)) => {
-                            tracing::info!(
-                                "WS closed by server for {} on {:?}: {:?}",
-                                pair_clone,
-                                dex,
-                                frame
-                            );
-                            break;
-                        }
-                        Ok(_) => {
-                            // Binary, Pong, Frame – ignore
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                "WS read error for {} on {:?}: {}",
-                                pair_clone,
-                                dex,
-                                e
-                            );
-                            break;
-                        }
+                            None => break,
+                        },
+                    };
+                    if !self.deliver(released, dex, pair, orderer).await {
+                        break;
                     }
                 }
 
-                tracing::warn!("WS disconnected for {} on {:?}", pair_clone, dex);
+                tracing::warn!("WS disconnected for {} on {:?}", pair, dex);
             }
             Err(e) => {
                 tracing::warn!("Failed to connect to WS for {} on {:?}: {}", pair, dex, e);
@@ -164,16 +277,121 @@ impl WebSocketManager {
         }
     }
 
+    /// Send released ticks downstream, then refresh the stream from a
+    /// snapshot if they revealed a gap. False once the channel is closed.
+    async fn deliver(
+        &self,
+        released: Released,
+        dex: DexType,
+        pair: &TokenPair,
+        orderer: &mut TickOrderer,
+    ) -> bool {
+        let mut prices = released.ticks;
+        if let Some(gap) = released.gap {
+            prices.extend(self.refresh_snapshot(dex, pair, gap, orderer).await);
+        }
+        for price_data in prices {
+            if let Err(e) = self.price_tx.send(price_data).await {
+                tracing::error!("Failed to send price update through channel: {}", e);
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn refresh_snapshot(
+        &self,
+        dex: DexType,
+        pair: &TokenPair,
+        gap: Gap,
+        orderer: &mut TickOrderer,
+    ) -> Option<PriceData> {
+        let Some(provider) = self.snapshot_sources.get(&dex) else {
+            tracing::warn!("Gap in WS stream for {} on {:?}: {:?}", pair, dex, gap);
+            return None;
+        };
+        tracing::warn!(
+            "Gap in WS stream for {} on {:?}: {:?}. Refreshing from snapshot",
+            pair,
+            dex,
+            gap
+        );
+        match provider.get_price(pair).await {
+            Ok(snapshot) => {
+                orderer.resync(&snapshot);
+                Some(snapshot)
+            }
+            Err(e) => {
+                tracing::warn!("Snapshot refresh for {} on {:?} failed: {}", pair, dex, e);
+                None
+            }
+        }
+    }
+
+    /// Parse a message of `source`. Account updates carry no usable
+    /// sequence (slots skip), so they are ordered by arrival, and are
+    /// turned to `pair`'s orientation.
+    fn parse_source_message(
+        source: &StreamSource,
+        text: &str,
+        dex: DexType,
+        pair: &TokenPair,
+    ) -> Result<Option<(Option<u64>, PriceData)>, String> {
+        match source {
+            StreamSource::Venue { .. } => Self::parse_stream_message(text, dex, pair),
+            StreamSource::Account { pool, .. } => Ok(pool.parse_notification(text)?.map(|price| {
+                let price = if price.pair == *pair { price } else { price.inverted() };
+                (None, price)
+            })),
+        }
+    }
+
+    /// Parse a stream message into its sequence number (`seq`, `sequence`
+    /// or `slot`, if any) and price, taking the price's timestamp from
+    /// `timestamp` or `ts` (epoch milliseconds or RFC 3339) when present.
+    /// `Ok(None)` for non-price messages.
+    fn parse_stream_message(
+        text: &str,
+        dex: DexType,
+        pair: &TokenPair,
+    ) -> Result<Option<(Option<u64>, PriceData)>, String> {
+        let json: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        let Some(mut price) = Self::price_from_json(&json, dex, pair)? else {
+            return Ok(None);
+        };
+        let data_obj = json.get("data").unwrap_or(&json);
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| data_obj.get(*name).or_else(|| json.get(*name)))
+        };
+
+        let sequence = field(&["seq", "sequence", "slot"]).and_then(|v| {
+            v.as_u64()
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        });
+        if let Some(at) = field(&["timestamp", "ts"]).and_then(parse_timestamp) {
+            price.timestamp = at;
+        }
+        Ok(Some((sequence, price)))
+    }
+
     /// Parse a WebSocket text message into a `PriceData`, returning `Ok(None)` for
     /// non-price messages (heartbeats, subscription acks, etc.).
-    fn parse_price_message(
+    pub fn parse_price_message(
         text: &str,
         dex: DexType,
         pair: &TokenPair,
     ) -> Result<Option<PriceData>, String> {
-        let json: serde_json::Value =
-            serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        Ok(Self::parse_stream_message(text, dex, pair)?.map(|(_, price)| price))
+    }
 
+    fn price_from_json(
+        json: &serde_json::Value,
+        dex: DexType,
+        pair: &TokenPair,
+    ) -> Result<Option<PriceData>, String> {
         // Check for heartbeat / subscription ack messages
         if json.get("type").and_then(|t| t.as_str()) == Some("heartbeat")
             || json.get("type").and_then(|t| t.as_str()) == Some("subscribed")
@@ -196,7 +414,7 @@ impl WebSocketManager {
         //   Generic: { "data": { "bid": ..., "ask": ... } }
 
         // Pattern 1: explicit bid/ask at top level or inside "data"
-        let data_obj = json.get("data").unwrap_or(&json);
+        let data_obj = json.get("data").unwrap_or(json);
 
         if let (Some(bid_val), Some(ask_val)) = (data_obj.get("bid"), data_obj.get("ask")) {
             let bid = parse_decimal_value(bid_val)
@@ -234,6 +452,17 @@ impl WebSocketManager {
     }
 }
 
+/// Parse epoch milliseconds or an RFC 3339 string.
+fn parse_timestamp(val: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match val {
+        serde_json::Value::Number(n) => n.as_i64().and_then(DateTime::from_timestamp_millis),
+        serde_json::Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|at| at.with_timezone(&Utc)),
+        _ => None,
+    }
+}
+
 /// Parse a JSON value that might be a number or a string containing a number.
 fn parse_decimal_value(val: &serde_json::Value) -> Option<Decimal> {
     match val {
@@ -340,6 +569,27 @@ mod tests {
         assert_eq!(price.mid_price, Decimal::from_str("42.5").unwrap());
     }
 
+    #[test]
+    fn test_parse_stream_position() {
+        let msg = r#"{"data": {"bid": 99, "ask": 101, "seq": 42}, "ts": 1700000000123}"#;
+        let pair = TokenPair::new("SOL", "USDC");
+        let (sequence, price) = WebSocketManager::parse_stream_message(msg, DexType::Jupiter, &pair)
+            .unwrap()
+            .expect("Should produce PriceData");
+        assert_eq!(sequence, Some(42));
+        assert_eq!(price.timestamp.timestamp_millis(), 1_700_000_000_123);
+
+        let msg = r#"{"price": "42.5", "slot": "250000000", "timestamp": "2026-03-02T14:32:00Z"}"#;
+        let (sequence, price) = WebSocketManager::parse_stream_message(msg, DexType::Raydium, &pair)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sequence, Some(250_000_000));
+        assert_eq!(price.timestamp.to_rfc3339(), "2026-03-02T14:32:00+00:00");
+        assert!(WebSocketManager::parse_stream_message(r#"{"type": "heartbeat"}"#, DexType::Jupiter, &pair)
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn test_parse_decimal_value_number() {
         let val = serde_json::json!(42.5);
@@ -48,6 +48,38 @@ mod types_tests {
         let price = make_price(DexType::Orca, "SOL", "USDC", 100.0, 102.0);
         assert_eq!(price.mid_price, Decimal::from(101));
     }
+
+    #[test]
+    fn test_pair_normalization_and_inverse_quotes() {
+        let canonical = TokenPair::new("BONK", "SOL");
+        assert!(canonical.is_canonical());
+        assert!(!TokenPair::new("SOL", "BONK").is_canonical());
+        assert_eq!(TokenPair::new("SOL", "BONK").canonical(), canonical);
+        assert!(canonical.is_inverse_of(&TokenPair::new("SOL", "BONK")));
+        // Stablecoins quote SOL; unranked tokens are ordered by name
+        assert_eq!(TokenPair::new("USDC", "SOL").canonical().symbol(), "SOL/USDC");
+        assert_eq!(TokenPair::new("USDC", "USDT").canonical().symbol(), "USDT/USDC");
+        assert_eq!(TokenPair::new("RAY", "JUP").canonical().symbol(), "JUP/RAY");
+
+        // 50,000 BONK per SOL bid, 50,000.5 ask
+        let mut price = make_price(DexType::Orca, "SOL", "BONK", 50_000.0, 50_000.5);
+        price.volume_24h = Some(Decimal::from(1_000_000_000));
+        let inverted = price.clone().normalized();
+        assert_eq!(inverted.pair, canonical);
+        assert_eq!(inverted.bid, Decimal::ONE / Decimal::new(500005, 1));
+        assert_eq!(inverted.ask, Decimal::ONE / Decimal::from(50_000));
+        assert!(inverted.bid < inverted.ask);
+        // 1bn BONK traded is about 20,000 SOL
+        let volume = inverted.volume_24h.unwrap();
+        assert!((volume - Decimal::from(20_000)).abs() < Decimal::ONE, "{}", volume);
+
+        // Inverting twice restores the quote, up to division rounding
+        let restored = inverted.inverted();
+        assert_eq!(restored.pair, price.pair);
+        assert!((restored.bid - price.bid).abs() < Decimal::new(1, 9));
+        assert!((restored.ask - price.ask).abs() < Decimal::new(1, 9));
+        assert_eq!(price.clone().normalized().normalized().pair, canonical);
+    }
 }
 
 #[cfg(test)]
@@ -170,7 +202,7 @@ mod arbitrage_tests {
         let mut detector = create_detector_with_low_threshold();
 
         let mut raydium_price = make_price(DexType::Raydium, "SOL", "USDC", 99.9, 100.0);
-        raydium_price.timestamp = raydium_price.timestamp - Duration::seconds(10);
+        raydium_price.timestamp -= Duration::seconds(10);
         detector.update_price(raydium_price);
 
         detector.update_price(make_price(DexType::Orca, "SOL", "USDC", 102.0, 102.1));
@@ -186,6 +218,27 @@ mod arbitrage_tests {
             "Stale prices should not contribute to opportunities"
         );
     }
+
+    #[test]
+    fn test_inverse_quotes_share_one_market() {
+        let mut detector = create_detector_with_low_threshold();
+        // Raydium quotes SOL in BONK, Orca BONK in SOL; Orca's BONK is 2% dearer
+        detector.update_price(make_price(DexType::Raydium, "SOL", "BONK", 50_000.0, 50_000.0));
+        detector.update_price(make_price(DexType::Orca, "BONK", "SOL", 0.0000204, 0.0000204));
+        assert_eq!(detector.get_prices().len(), 2);
+        assert!(detector.get_prices().keys().all(|(pair, _)| pair.symbol() == "BONK/SOL"));
+
+        // Either orientation finds the same canonical opportunity
+        for pair in [TokenPair::new("SOL", "BONK"), TokenPair::new("BONK", "SOL")] {
+            let opportunities = detector.find_opportunities(&pair);
+            let best = opportunities.first().expect("inverse quotes should be compared");
+            assert_eq!(best.pair.symbol(), "BONK/SOL");
+            assert_eq!((best.buy_dex, best.sell_dex), (DexType::Raydium, DexType::Orca));
+        }
+
+        detector.remove_price(&TokenPair::new("SOL", "BONK"), DexType::Raydium);
+        assert!(detector.find_opportunities(&TokenPair::new("BONK", "SOL")).is_empty());
+    }
 }
 
 #[cfg(test)]
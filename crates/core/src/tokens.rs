@@ -0,0 +1,284 @@
+//! Token registry and decimal-accurate amount conversion
+//!
+//! Converts between human-readable token amounts and on-chain atomic units
+//! using each mint's real decimals instead of guessing.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::HashMap;
+
+use crate::{ArbitrageError, ArbitrageResult};
+
+/// How to round when an amount has more precision than the mint supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero - never spend more than requested
+    Down,
+    /// Away from zero - never repay less than owed
+    Up,
+    /// Half away from zero
+    Nearest,
+}
+
+impl From<Rounding> for RoundingStrategy {
+    fn from(rounding: Rounding) -> Self {
+        match rounding {
+            Rounding::Down => RoundingStrategy::ToZero,
+            Rounding::Up => RoundingStrategy::AwayFromZero,
+            Rounding::Nearest => RoundingStrategy::MidpointAwayFromZero,
+        }
+    }
+}
+
+/// Convert a token amount to atomic units
+pub fn to_atoms(amount: Decimal, decimals: u8, rounding: Rounding) -> ArbitrageResult<u64> {
+    if amount.is_sign_negative() && !amount.is_zero() {
+        return Err(ArbitrageError::InvalidAmount(format!(
+            "negative amount {}",
+            amount
+        )));
+    }
+
+    let scale = Decimal::from(10u64.pow(decimals as u32));
+    let atoms = amount
+        .checked_mul(scale)
+        .ok_or_else(|| ArbitrageError::InvalidAmount(format!("{} overflows", amount)))?
+        .round_dp_with_strategy(0, rounding.into());
+
+    atoms
+        .to_u64()
+        .ok_or_else(|| ArbitrageError::InvalidAmount(format!("{} atoms do not fit in u64", atoms)))
+}
+
+/// Convert atomic units to a token amount
+pub fn from_atoms(atoms: u64, decimals: u8) -> Decimal {
+    Decimal::from_i128_with_scale(atoms as i128, decimals as u32)
+}
+
+/// Apply a basis-point fee to an atomic amount, rounding as requested
+pub fn bps_of_atoms(atoms: u64, bps: u64, rounding: Rounding) -> u64 {
+    let fee = Decimal::from(atoms) * Decimal::from(bps) / Decimal::from(10_000);
+    fee.round_dp_with_strategy(0, rounding.into())
+        .to_u64()
+        .unwrap_or(u64::MAX)
+}
+
+/// Metadata for a single token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub mint: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    pub fn new(symbol: &str, mint: &str, decimals: u8) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            mint: mint.to_string(),
+            decimals,
+        }
+    }
+}
+
+/// Lookup of token metadata by symbol or mint
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    by_symbol: HashMap<String, TokenInfo>,
+    symbol_by_mint: HashMap<String, String>,
+}
+
+impl TokenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the mainnet tokens the bot trades
+    pub fn mainnet() -> Self {
+        let mut registry = Self::new();
+        registry.register(TokenInfo::new(
+            "SOL",
+            "So11111111111111111111111111111111111111112",
+            9,
+        ));
+        registry.register(TokenInfo::new(
+            "USDC",
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            6,
+        ));
+        registry.register(TokenInfo::new(
+            "USDT",
+            "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+            6,
+        ));
+        registry.register(TokenInfo::new(
+            "RAY",
+            "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R",
+            6,
+        ));
+        registry.register(TokenInfo::new(
+            "ORCA",
+            "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE",
+            6,
+        ));
+        registry.register(TokenInfo::new(
+            "JUP",
+            "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+            6,
+        ));
+        registry.register(TokenInfo::new(
+            "BONK",
+            "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+            5,
+        ));
+        // Liquid staking tokens, quoted against SOL by Sanctum
+        registry.register(TokenInfo::new(
+            "mSOL",
+            "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",
+            9,
+        ));
+        registry.register(TokenInfo::new(
+            "jitoSOL",
+            "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn",
+            9,
+        ));
+        registry.register(TokenInfo::new(
+            "bSOL",
+            "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1",
+            9,
+        ));
+        registry
+    }
+
+    /// Add or replace a token
+    pub fn register(&mut self, info: TokenInfo) {
+        if let Some(old) = self.by_symbol.get(&info.symbol) {
+            self.symbol_by_mint.remove(&old.mint);
+        }
+        self.symbol_by_mint
+            .insert(info.mint.clone(), info.symbol.clone());
+        self.by_symbol.insert(info.symbol.clone(), info);
+    }
+
+    /// Look up a token by symbol or mint address
+    pub fn get(&self, symbol_or_mint: &str) -> Option<&TokenInfo> {
+        self.by_symbol.get(symbol_or_mint).or_else(|| {
+            self.symbol_by_mint
+                .get(symbol_or_mint)
+                .and_then(|symbol| self.by_symbol.get(symbol))
+        })
+    }
+
+    fn require(&self, token: &str) -> ArbitrageResult<&TokenInfo> {
+        self.get(token)
+            .ok_or_else(|| ArbitrageError::InvalidAmount(format!("unknown token {}", token)))
+    }
+
+    pub fn mint(&self, token: &str) -> Option<&str> {
+        self.get(token).map(|info| info.mint.as_str())
+    }
+
+    pub fn decimals(&self, token: &str) -> Option<u8> {
+        self.get(token).map(|info| info.decimals)
+    }
+
+    /// Convert an amount of `token` to atomic units
+    pub fn to_atoms(
+        &self,
+        token: &str,
+        amount: Decimal,
+        rounding: Rounding,
+    ) -> ArbitrageResult<u64> {
+        to_atoms(amount, self.require(token)?.decimals, rounding)
+    }
+
+    /// Convert atomic units of `token` to an amount
+    pub fn from_atoms(&self, token: &str, atoms: u64) -> ArbitrageResult<Decimal> {
+        Ok(from_atoms(atoms, self.require(token)?.decimals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry = TokenRegistry::mainnet();
+        assert_eq!(registry.decimals("USDC"), Some(6));
+        assert_eq!(
+            registry.decimals("So11111111111111111111111111111111111111112"),
+            Some(9)
+        );
+        assert_eq!(registry.decimals("BONK"), Some(5));
+        assert!(registry.get("NOPE").is_none());
+        assert!(registry
+            .to_atoms("NOPE", Decimal::ONE, Rounding::Down)
+            .is_err());
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let amount = Decimal::from_str("1.0000005").unwrap();
+        assert_eq!(to_atoms(amount, 6, Rounding::Down).unwrap(), 1_000_000);
+        assert_eq!(to_atoms(amount, 6, Rounding::Up).unwrap(), 1_000_001);
+        assert_eq!(to_atoms(amount, 6, Rounding::Nearest).unwrap(), 1_000_001);
+        assert_eq!(
+            to_atoms(
+                Decimal::from_str("1.0000004").unwrap(),
+                6,
+                Rounding::Nearest
+            )
+            .unwrap(),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn test_invalid_amounts() {
+        assert!(to_atoms(Decimal::from(-1), 6, Rounding::Down).is_err());
+        assert!(to_atoms(Decimal::from(u64::MAX), 9, Rounding::Down).is_err());
+        assert_eq!(to_atoms(Decimal::ZERO, 9, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bps_fee_rounding() {
+        // 3 bps of 1_001 atoms = 0.3003 atoms
+        assert_eq!(bps_of_atoms(1_001, 3, Rounding::Down), 0);
+        assert_eq!(bps_of_atoms(1_001, 3, Rounding::Up), 1);
+    }
+
+    #[test]
+    fn test_property_atoms_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1_000 {
+            let decimals: u8 = rng.gen_range(0..=9);
+            let atoms: u64 = rng.gen_range(0..=1_000_000_000_000_000);
+            let amount = from_atoms(atoms, decimals);
+            for rounding in [Rounding::Down, Rounding::Up, Rounding::Nearest] {
+                assert_eq!(to_atoms(amount, decimals, rounding).unwrap(), atoms);
+            }
+        }
+    }
+
+    #[test]
+    fn test_property_rounding_brackets_exact_value() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..1_000 {
+            let decimals: u8 = rng.gen_range(0..=9);
+            // Up to 12 fractional digits, more precision than any mint
+            let amount = Decimal::new(rng.gen_range(0..=i64::MAX / 1_000), 12);
+            let down = to_atoms(amount, decimals, Rounding::Down).unwrap();
+            let up = to_atoms(amount, decimals, Rounding::Up).unwrap();
+            let nearest = to_atoms(amount, decimals, Rounding::Nearest).unwrap();
+
+            assert!(from_atoms(down, decimals) <= amount);
+            assert!(from_atoms(up, decimals) >= amount);
+            assert!(up - down <= 1);
+            assert!(nearest == down || nearest == up);
+        }
+    }
+}
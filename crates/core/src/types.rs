@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 /// Supported DEX types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum DexType {
     Raydium,
     Orca,
@@ -14,6 +15,7 @@ pub enum DexType {
     Lifinity,
     Meteora,
     Phoenix,
+    Sanctum,
 }
 
 impl DexType {
@@ -26,6 +28,7 @@ impl DexType {
             DexType::Lifinity => Decimal::new(10, 4), // 0.10% (approx)
             DexType::Meteora => Decimal::new(10, 4),  // Dynamic, varies
             DexType::Phoenix => Decimal::new(5, 4),   // 0.05% (maker/taker varies)
+            DexType::Sanctum => Decimal::new(0, 4),   // In the quoted LST rate
         }
     }
 
@@ -38,6 +41,7 @@ impl DexType {
             DexType::Lifinity => "Lifinity",
             DexType::Meteora => "Meteora",
             DexType::Phoenix => "Phoenix",
+            DexType::Sanctum => "Sanctum",
         }
     }
 
@@ -50,6 +54,7 @@ impl DexType {
             DexType::Lifinity,
             DexType::Meteora,
             DexType::Phoenix,
+            DexType::Sanctum,
         ];
         ALL
     }
@@ -61,8 +66,24 @@ impl std::fmt::Display for DexType {
     }
 }
 
+/// Tokens that price others, most preferred first, by symbol and mint.
+/// A canonical pair is quoted in the better ranked of its tokens.
+const QUOTE_PRIORITY: &[(&str, &str)] = &[
+    ("USDC", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    ("USDT", "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+    ("SOL", "So11111111111111111111111111111111111111112"),
+];
+
+fn quote_rank(token: &str) -> usize {
+    QUOTE_PRIORITY
+        .iter()
+        .position(|(symbol, mint)| token.eq_ignore_ascii_case(symbol) || token == *mint)
+        .unwrap_or(QUOTE_PRIORITY.len())
+}
+
 /// Represents a trading pair of tokens
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TokenPair {
     /// Base token mint address or symbol
     pub base: String,
@@ -82,6 +103,33 @@ impl TokenPair {
     pub fn symbol(&self) -> String {
         format!("{}/{}", self.base, self.quote)
     }
+
+    /// The same market with base and quote swapped
+    pub fn inverse(&self) -> Self {
+        Self::new(self.quote.clone(), self.base.clone())
+    }
+
+    /// Whether this is the orientation every component keys the market by:
+    /// quoted in USDC, then USDT, then SOL; otherwise in the
+    /// alphabetically later token. BONK/SOL is canonical, SOL/BONK is not.
+    pub fn is_canonical(&self) -> bool {
+        let (base, quote) = (quote_rank(&self.base), quote_rank(&self.quote));
+        quote < base || (quote == base && self.base <= self.quote)
+    }
+
+    /// This pair in its canonical orientation
+    pub fn canonical(&self) -> Self {
+        if self.is_canonical() {
+            self.clone()
+        } else {
+            self.inverse()
+        }
+    }
+
+    /// Whether `other` is this market the other way round
+    pub fn is_inverse_of(&self, other: &TokenPair) -> bool {
+        self.base == other.quote && self.quote == other.base
+    }
 }
 
 impl std::fmt::Display for TokenPair {
@@ -92,6 +140,7 @@ impl std::fmt::Display for TokenPair {
 
 /// Price data from a DEX
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PriceData {
     /// The DEX this price is from
     pub dex: DexType,
@@ -126,6 +175,41 @@ impl PriceData {
         }
     }
 
+    /// The same quote for the inverse pair: the inverse's bid is one over
+    /// this ask and its ask one over this bid. Volume moves to the new
+    /// quote token; liquidity is a depth, not a price, and is kept.
+    pub fn inverted(&self) -> Self {
+        let invert = |price: Decimal| {
+            if price.is_zero() {
+                Decimal::ZERO
+            } else {
+                Decimal::ONE / price
+            }
+        };
+        let (bid, ask) = (invert(self.ask), invert(self.bid));
+        Self {
+            dex: self.dex,
+            pair: self.pair.inverse(),
+            bid,
+            ask,
+            mid_price: (bid + ask) / Decimal::from(2),
+            volume_24h: self
+                .volume_24h
+                .map(|volume| if self.mid_price.is_zero() { volume } else { volume / self.mid_price }),
+            liquidity: self.liquidity,
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// This quote for the canonical orientation of its pair
+    pub fn normalized(self) -> Self {
+        if self.pair.is_canonical() {
+            self
+        } else {
+            self.inverted()
+        }
+    }
+
     /// Spread as a percentage
     pub fn spread_percentage(&self) -> Decimal {
         if self.mid_price.is_zero() {
@@ -137,6 +221,7 @@ impl PriceData {
 
 /// An arbitrage opportunity between two DEXs
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ArbitrageOpportunity {
     /// Unique identifier
     pub id: uuid::Uuid,
@@ -162,6 +247,18 @@ pub struct ArbitrageOpportunity {
     pub detected_at: DateTime<Utc>,
     /// When this opportunity expired (filled or price changed)
     pub expired_at: Option<DateTime<Utc>>,
+    /// When the buy venue's quote was taken, if it was a venue quote
+    #[serde(default)]
+    pub buy_quote_at: Option<DateTime<Utc>>,
+    /// When the sell venue's quote was taken, if it was a venue quote
+    #[serde(default)]
+    pub sell_quote_at: Option<DateTime<Utc>>,
+    /// What produced this opportunity
+    #[serde(default)]
+    pub source: OpportunitySource,
+    /// Name of the strategy that produced it, when `source` is a strategy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
 }
 
 impl ArbitrageOpportunity {
@@ -175,6 +272,51 @@ impl ArbitrageOpportunity {
         let end = self.expired_at.unwrap_or_else(Utc::now);
         end - self.detected_at
     }
+
+    /// Time between the two venues' quotes, when both are known
+    pub fn quote_skew(&self) -> Option<chrono::Duration> {
+        Some((self.buy_quote_at? - self.sell_quote_at?).abs())
+    }
+
+    /// Attribute this opportunity to the strategy named `name`
+    pub fn attributed_to(mut self, name: &str) -> Self {
+        self.source = OpportunitySource::Strategy;
+        self.strategy = Some(name.to_string());
+        self
+    }
+
+    /// Label attributing this opportunity in metrics, history and events:
+    /// the strategy's name, "detector" or "manual"
+    pub fn source_label(&self) -> &str {
+        match (self.source, &self.strategy) {
+            (OpportunitySource::Strategy, Some(name)) => name,
+            (source, _) => source.as_str(),
+        }
+    }
+}
+
+/// What produced an opportunity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum OpportunitySource {
+    /// The cross-venue spread detector
+    #[default]
+    Detector,
+    /// A strategy, named in the opportunity's `strategy`
+    Strategy,
+    /// An operator, through the manual trade command
+    Manual,
+}
+
+impl OpportunitySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Detector => "detector",
+            Self::Strategy => "strategy",
+            Self::Manual => "manual",
+        }
+    }
 }
 
 /// Configuration for arbitrage detection
@@ -188,6 +330,10 @@ pub struct ArbitrageConfig {
     pub slippage_tolerance: Decimal,
     /// Solana transaction fee in SOL
     pub solana_tx_fee: Decimal,
+    /// Largest gap between the two venues' quotes (ms) an opportunity may
+    /// be built from; 0 disables the check
+    #[serde(default)]
+    pub max_quote_skew_ms: u64,
 }
 
 impl Default for ArbitrageConfig {
@@ -197,10 +343,31 @@ impl Default for ArbitrageConfig {
             max_position_size: Decimal::from(1000),    // $1,000
             slippage_tolerance: Decimal::new(100, 4),  // 1%
             solana_tx_fee: Decimal::new(5, 6),         // 0.000005 SOL
+            max_quote_skew_ms: 1000,
         }
     }
 }
 
+/// Failure categories tracked separately from ordinary execution errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The aggregator route did not cross the venues the opportunity was detected on
+    RouteMismatch,
+    /// Quote, swap instructions and build ran past the execution deadline;
+    /// nothing was sent
+    AbortedDeadline,
+    /// A pre-trade compliance hook vetoed the transaction; nothing was sent
+    ComplianceVeto,
+    /// The quote's worst accepted output lost more than the per-trade cap;
+    /// nothing was sent
+    MaxLossExceeded,
+    /// A program failed the transaction in a way a retry would repeat; it
+    /// was not retried and was escalated
+    ProgramError,
+}
+
 /// Trade execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeResult {
@@ -216,6 +383,12 @@ pub struct TradeResult {
     pub executed_at: DateTime<Utc>,
     /// Error message if failed
     pub error: Option<String>,
+    /// Net payer balance change from the pre-submission simulation, in quote currency
+    #[serde(default)]
+    pub simulated_profit: Option<Decimal>,
+    /// Category of the failure, if it is one tracked separately
+    #[serde(default)]
+    pub failure_kind: Option<FailureKind>,
 }
 
 // Re-export uuid for convenience
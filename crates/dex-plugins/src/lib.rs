@@ -1,7 +1,9 @@
 pub mod lifinity;
 pub mod meteora;
 pub mod phoenix;
+pub mod sanctum;
 
 pub use lifinity::LifinityProvider;
 pub use meteora::MeteoraProvider;
 pub use phoenix::PhoenixProvider;
+pub use sanctum::SanctumProvider;
@@ -1,14 +1,43 @@
 use async_trait::async_trait;
+use rust_decimal::Decimal;
 use solana_arb_core::{
     dex::DexProvider,
     error::ArbitrageError,
     types::{DexType, PriceData, TokenPair},
     ArbitrageResult,
 };
+use std::collections::HashMap;
+use std::sync::RwLock;
 use tokio::sync::mpsc;
 
+/// One price level of a Phoenix order book
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookLevel {
+    pub price: Decimal,
+    /// Displayed size in base units
+    pub size: Decimal,
+}
+
+/// Order book snapshot, best levels first on both sides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderBook {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|l| l.price)
+    }
+
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|l| l.price)
+    }
+}
+
 pub struct PhoenixProvider {
-    // Placeholder
+    /// Latest book snapshot per pair symbol
+    books: RwLock<HashMap<String, OrderBook>>,
 }
 
 impl Default for PhoenixProvider {
@@ -19,7 +48,21 @@ impl Default for PhoenixProvider {
 
 impl PhoenixProvider {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            books: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the book snapshot for `pair`
+    pub fn update_book(&self, pair: &TokenPair, book: OrderBook) {
+        if let Ok(mut books) = self.books.write() {
+            books.insert(pair.symbol(), book);
+        }
+    }
+
+    /// Latest book snapshot for `pair`, if one has been received
+    pub fn book(&self, pair: &TokenPair) -> Option<OrderBook> {
+        self.books.read().ok()?.get(&pair.symbol()).cloned()
     }
 }
 
@@ -29,10 +72,13 @@ impl DexProvider for PhoenixProvider {
         DexType::Phoenix
     }
 
-    async fn get_price(&self, _pair: &TokenPair) -> ArbitrageResult<PriceData> {
-        Err(ArbitrageError::PriceFetch(
-            "Phoenix price fetching not implemented".to_string(),
-        ))
+    async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
+        self.book(pair)
+            .and_then(|book| Some((book.best_bid()?, book.best_ask()?)))
+            .map(|(bid, ask)| PriceData::new(DexType::Phoenix, pair.clone(), bid, ask))
+            .ok_or_else(|| {
+                ArbitrageError::PriceFetch(format!("No Phoenix order book for {}", pair))
+            })
     }
 
     async fn subscribe(
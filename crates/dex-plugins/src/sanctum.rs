@@ -0,0 +1,287 @@
+//! Sanctum Liquid Staking Token Provider
+//!
+//! Quotes liquid staking tokens (mSOL, jitoSOL, bSOL) at their intrinsic
+//! exchange rate: the SOL a token redeems for from its stake pool, as
+//! reported by Sanctum's sol-value API. Sanctum swaps LSTs against SOL and
+//! each other at that rate less a small fee, so the provider quotes bid and
+//! ask `fee_bps` either side of it. The fee is baked into the quote, which
+//! is why `DexType::Sanctum` carries no further trading fee.
+//!
+//! Pairs may be LST/SOL, SOL/LST or LST/LST; anything else is not priced.
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use solana_arb_core::{
+    dex::{DexProvider, PriceStream},
+    error::ArbitrageError,
+    types::{DexType, PriceData, TokenPair},
+    ArbitrageResult,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tokio::sync::mpsc;
+
+pub const SANCTUM_API_URL: &str = "https://extra-api.sanctum.so";
+
+/// Liquid staking tokens quoted by default, as (symbol, mint)
+pub const LIQUID_STAKING_TOKENS: &[(&str, &str)] = &[
+    ("mSOL", "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So"),
+    ("jitoSOL", "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn"),
+    ("bSOL", "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1"),
+];
+
+/// Lamports per SOL; sol values are quoted per whole (10^9 atomic) LST
+const LAMPORTS_PER_SOL: i64 = 1_000_000_000;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SolValueResponse {
+    /// Mint to lamports of SOL one token redeems for
+    sol_values: HashMap<String, String>,
+}
+
+pub struct SanctumProvider {
+    client: reqwest::Client,
+    api_url: String,
+    /// Swap fee either side of the intrinsic rate, in basis points
+    fee_bps: u64,
+    /// LST symbol to mint address
+    token_mints: HashMap<String, String>,
+}
+
+impl Default for SanctumProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SanctumProvider {
+    pub fn new() -> Self {
+        Self::with_api_url(SANCTUM_API_URL, 10)
+    }
+
+    /// Provider that calls `api_url` and quotes `fee_bps` around the
+    /// intrinsic rate
+    pub fn with_api_url(api_url: impl Into<String>, fee_bps: u64) -> Self {
+        Self {
+            client: solana_arb_core::http::pool::create_optimized_client(),
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            fee_bps,
+            token_mints: LIQUID_STAKING_TOKENS
+                .iter()
+                .map(|(symbol, mint)| (symbol.to_string(), mint.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Add a liquid staking token (9 decimals, priced by Sanctum)
+    pub fn add_token(&mut self, symbol: String, mint: String) {
+        self.token_mints.insert(symbol, mint);
+    }
+
+    /// Whether `pair` is one this provider quotes
+    pub fn supports(&self, pair: &TokenPair) -> bool {
+        lst_legs(&self.token_mints, pair).is_some()
+    }
+}
+
+/// Mints of the LST legs of `pair` (`None` for a SOL leg), or `None` if
+/// the pair is not LST/SOL, SOL/LST or LST/LST
+fn lst_legs<'a>(
+    token_mints: &'a HashMap<String, String>,
+    pair: &TokenPair,
+) -> Option<(Option<&'a String>, Option<&'a String>)> {
+    let leg = |symbol: &str| match token_mints.get(symbol) {
+        Some(mint) => Some(Some(mint)),
+        None if symbol == "SOL" => Some(None),
+        None => None,
+    };
+    match (leg(&pair.base)?, leg(&pair.quote)?) {
+        (None, None) => None,
+        legs => Some(legs),
+    }
+}
+
+async fn fetch_price(
+    client: &reqwest::Client,
+    api_url: &str,
+    token_mints: &HashMap<String, String>,
+    fee_bps: u64,
+    pair: &TokenPair,
+) -> ArbitrageResult<PriceData> {
+    let (base, quote) = lst_legs(token_mints, pair).ok_or_else(|| {
+        ArbitrageError::PriceNotAvailable(format!("Sanctum does not quote {}", pair))
+    })?;
+
+    let query: Vec<String> = [base, quote]
+        .into_iter()
+        .flatten()
+        .map(|mint| format!("lst={}", mint))
+        .collect();
+    let url = format!("{}/v1/sol-value/current?{}", api_url, query.join("&"));
+    let response: SolValueResponse = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    // SOL per unit of each leg; SOL itself is worth one
+    let sol_value = |mint: Option<&String>| -> ArbitrageResult<Decimal> {
+        let Some(mint) = mint else {
+            return Ok(Decimal::ONE);
+        };
+        let lamports = response.sol_values.get(mint).ok_or_else(|| {
+            ArbitrageError::PriceNotAvailable(format!("No Sanctum sol value for {}", mint))
+        })?;
+        let lamports = Decimal::from_str(lamports)
+            .map_err(|e| ArbitrageError::PriceFetch(format!("Invalid sol value: {}", e)))?;
+        if lamports <= Decimal::ZERO {
+            return Err(ArbitrageError::PriceFetch(format!(
+                "Invalid sol value {} for {}",
+                lamports, mint
+            )));
+        }
+        Ok(lamports / Decimal::from(LAMPORTS_PER_SOL))
+    };
+    let rate = sol_value(base)? / sol_value(quote)?;
+
+    let fee = rate * Decimal::new(fee_bps as i64, 4);
+    Ok(PriceData::new(
+        DexType::Sanctum,
+        pair.clone(),
+        rate - fee,
+        rate + fee,
+    ))
+}
+
+#[async_trait]
+impl DexProvider for SanctumProvider {
+    fn dex_type(&self) -> DexType {
+        DexType::Sanctum
+    }
+
+    async fn get_price(&self, pair: &TokenPair) -> ArbitrageResult<PriceData> {
+        fetch_price(
+            &self.client,
+            &self.api_url,
+            &self.token_mints,
+            self.fee_bps,
+            pair,
+        )
+        .await
+    }
+
+    async fn subscribe(&self, pairs: Vec<TokenPair>) -> ArbitrageResult<PriceStream> {
+        let (tx, rx) = mpsc::channel(100);
+        let client = self.client.clone();
+        let api_url = self.api_url.clone();
+        let token_mints = self.token_mints.clone();
+        let fee_bps = self.fee_bps;
+        let pairs: Vec<_> = pairs.into_iter().filter(|p| self.supports(p)).collect();
+
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                for pair in &pairs {
+                    if let Ok(price) =
+                        fetch_price(&client, &api_url, &token_mints, fee_bps, pair).await
+                    {
+                        if tx.send(price).await.is_err() {
+                            return; // Channel closed
+                        }
+                    }
+                }
+
+                // Intrinsic rates move once per epoch; poll gently
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+            }
+        });
+
+        Ok(rx)
+    }
+
+    async fn health_check(&self) -> ArbitrageResult<bool> {
+        let url = format!(
+            "{}/v1/sol-value/current?lst={}",
+            self.api_url, LIQUID_STAKING_TOKENS[0].1
+        );
+        let response = self.client.get(&url).send().await?;
+        Ok(response.status().is_success())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::dex::conformance::{
+        run_conformance, ConformanceConfig, ConformanceTarget, MockResponse, MockServer,
+    };
+    use std::sync::Arc;
+
+    struct Sanctum;
+
+    fn mint(symbol: &str) -> &'static str {
+        LIQUID_STAKING_TOKENS
+            .iter()
+            .find(|(s, _)| *s == symbol)
+            .map(|(_, mint)| *mint)
+            .unwrap_or_default()
+    }
+
+    impl ConformanceTarget for Sanctum {
+        fn provider(&self, base_url: &str) -> Arc<dyn DexProvider> {
+            Arc::new(SanctumProvider::with_api_url(base_url, 10))
+        }
+
+        fn price_body(&self, pair: &TokenPair, price: f64) -> String {
+            let lamports = (price * LAMPORTS_PER_SOL as f64).round() as u64;
+            serde_json::json!({
+                "solValues": { mint(&pair.base): lamports.to_string() },
+                "errs": {}
+            })
+            .to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sanctum_conformance_and_intrinsic_rate() {
+        let config = ConformanceConfig {
+            pair: TokenPair::new("mSOL", "SOL"),
+            other_pair: TokenPair::new("jitoSOL", "SOL"),
+            price: 1.25,
+            ..ConformanceConfig::default()
+        };
+        run_conformance(&Sanctum, &config).await.assert_passed();
+
+        // SOL/LST inverts the rate and LST/LST crosses it through SOL
+        let server = MockServer::start().await.unwrap();
+        server.respond(MockResponse::ok(
+            serde_json::json!({
+                "solValues": {
+                    mint("mSOL"): "1250000000",
+                    mint("jitoSOL"): "1125000000"
+                }
+            })
+            .to_string(),
+        ));
+        let provider = SanctumProvider::with_api_url(server.url(), 10);
+        let price = provider
+            .get_price(&TokenPair::new("SOL", "mSOL"))
+            .await
+            .unwrap();
+        assert_eq!(price.mid_price, Decimal::new(8, 1));
+        assert_eq!(price.bid, Decimal::new(7992, 4));
+        let price = provider
+            .get_price(&TokenPair::new("jitoSOL", "mSOL"))
+            .await
+            .unwrap();
+        assert_eq!(price.mid_price, Decimal::new(9, 1));
+        assert!(!provider.supports(&TokenPair::new("SOL", "USDC")));
+        assert!(provider
+            .get_price(&TokenPair::new("SOL", "USDC"))
+            .await
+            .is_err());
+    }
+}
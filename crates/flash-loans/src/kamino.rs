@@ -0,0 +1,268 @@
+//! Kamino Lend flash loans
+//!
+//! Kamino's flash borrow and repay are Anchor instructions on the lending
+//! program. Both name the reserve's supply and fee vaults, which live in
+//! the reserve account rather than in config: they are learned the first
+//! time the reserve is read (the flash liquidity monitor does this) and
+//! building a loan fails until then. The fee is a per-reserve setting, so
+//! it is configured to match the reserve in use.
+
+use super::liquidity::{kamino_layout, parse_kamino_reserve};
+use super::{FlashLoanProvider, FlashLoanQuote, ReserveLiquidity};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+use std::sync::RwLock;
+use tracing::info;
+
+/// Anchor discriminator of `flash_borrow_reserve_liquidity`
+const FLASH_BORROW: [u8; 8] = [135, 231, 52, 167, 7, 52, 212, 193];
+/// Anchor discriminator of `flash_repay_reserve_liquidity`
+const FLASH_REPAY: [u8; 8] = [185, 117, 0, 203, 96, 245, 180, 186];
+
+/// Supply and fee vaults of the reserve
+#[derive(Debug, Clone, Copy)]
+struct Vaults {
+    supply: Pubkey,
+    fee: Pubkey,
+}
+
+/// Kamino Lend flash loan implementation for a single reserve
+pub struct KaminoFlashLoan {
+    program_id: Pubkey,
+    lending_market: Pubkey,
+    lending_market_authority: Pubkey,
+    reserve: Pubkey,
+    liquidity_mint: Pubkey,
+    /// Wallet that receives and repays the loan
+    owner: Pubkey,
+    fee_bps: u64,
+    /// Position of the borrow in the transaction; the repay must name it
+    borrow_instruction_index: u8,
+    vaults: RwLock<Option<Vaults>>,
+}
+
+impl KaminoFlashLoan {
+    pub const PROTOCOL_NAME: &'static str = "Kamino";
+
+    // Mainnet program ID
+    pub const KAMINO_PROGRAM_ID: &'static str = "KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD";
+
+    /// Borrow index when the borrow follows the compute budget limit and
+    /// price instructions and the destination ATA creation
+    pub const DEFAULT_BORROW_INSTRUCTION_INDEX: u8 = 3;
+
+    pub fn new(lending_market: Pubkey, reserve: Pubkey, liquidity_mint: Pubkey, owner: Pubkey, fee_bps: u64) -> Self {
+        let program_id = Pubkey::from_str(Self::KAMINO_PROGRAM_ID).unwrap();
+        let (lending_market_authority, _) =
+            Pubkey::find_program_address(&[b"lma", lending_market.as_ref()], &program_id);
+        Self {
+            program_id,
+            lending_market,
+            lending_market_authority,
+            reserve,
+            liquidity_mint,
+            owner,
+            fee_bps,
+            borrow_instruction_index: Self::DEFAULT_BORROW_INSTRUCTION_INDEX,
+            vaults: RwLock::new(None),
+        }
+    }
+
+    pub fn with_borrow_instruction_index(mut self, index: u8) -> Self {
+        self.borrow_instruction_index = index;
+        self
+    }
+
+    fn vaults(&self) -> Result<Vaults> {
+        self.vaults
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .ok_or_else(|| anyhow!("Kamino reserve {} has not been read yet", self.reserve))
+    }
+
+    fn check_mint(&self, token_mint: &Pubkey) -> Result<()> {
+        if *token_mint != self.liquidity_mint {
+            return Err(anyhow!("Kamino reserve {} does not lend {}", self.reserve, token_mint));
+        }
+        Ok(())
+    }
+
+    /// Accounts shared by borrow and repay; `vault` is the reserve side of
+    /// the transfer and `user` the wallet's token account
+    fn accounts(&self, vault: Pubkey, user: Pubkey, fee: Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.owner, true),
+            AccountMeta::new_readonly(self.lending_market_authority, false),
+            AccountMeta::new_readonly(self.lending_market, false),
+            AccountMeta::new(self.reserve, false),
+            AccountMeta::new_readonly(self.liquidity_mint, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(user, false),
+            AccountMeta::new(fee, false),
+            // No referrer: Anchor reads the program ID as an absent optional account
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new_readonly(self.program_id, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ]
+    }
+}
+
+#[async_trait]
+impl FlashLoanProvider for KaminoFlashLoan {
+    fn name(&self) -> &'static str {
+        Self::PROTOCOL_NAME
+    }
+
+    fn calculate_fee(&self, borrow_amount: u64) -> u64 {
+        (borrow_amount as u128 * self.fee_bps as u128 / 10000) as u64
+    }
+
+    fn borrow_instruction(&self, borrow_amount: u64, token_mint: &Pubkey) -> Result<Instruction> {
+        self.check_mint(token_mint)?;
+        let vaults = self.vaults()?;
+        info!("Creating Kamino borrow instruction for amount: {}", borrow_amount);
+
+        let mut data = FLASH_BORROW.to_vec();
+        data.extend_from_slice(&borrow_amount.to_le_bytes());
+        let user = get_associated_token_address(&self.owner, token_mint);
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts(vaults.supply, user, vaults.fee),
+            data,
+        })
+    }
+
+    /// The program adds the fee itself, so the repay names the borrowed amount
+    fn repay_instruction(&self, borrow_amount: u64, token_mint: &Pubkey) -> Result<Instruction> {
+        self.check_mint(token_mint)?;
+        let vaults = self.vaults()?;
+        info!(
+            "Creating Kamino repay instruction for amount: {}",
+            borrow_amount + self.calculate_fee(borrow_amount)
+        );
+
+        let mut data = FLASH_REPAY.to_vec();
+        data.extend_from_slice(&borrow_amount.to_le_bytes());
+        data.push(self.borrow_instruction_index);
+        let user = get_associated_token_address(&self.owner, token_mint);
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts(vaults.supply, user, vaults.fee),
+            data,
+        })
+    }
+
+    fn reserve_account(&self, token_mint: &Pubkey) -> Option<Pubkey> {
+        (*token_mint == self.liquidity_mint).then_some(self.reserve)
+    }
+
+    /// Also records the reserve's vaults for building loans
+    fn parse_reserve_liquidity(&self, data: &[u8]) -> Result<ReserveLiquidity> {
+        let liquidity = parse_kamino_reserve(data)?;
+        if liquidity.mint == self.liquidity_mint {
+            let key_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]);
+            if let (Ok(supply), Ok(fee)) = (key_at(kamino_layout::SUPPLY_VAULT), key_at(kamino_layout::FEE_VAULT)) {
+                *self.vaults.write().unwrap_or_else(|e| e.into_inner()) = Some(Vaults { supply, fee });
+            }
+        }
+        Ok(liquidity)
+    }
+
+    async fn get_quote(&self, token_mint: Pubkey, amount: Decimal) -> Result<FlashLoanQuote> {
+        self.check_mint(&token_mint)?;
+        let amount_u64 = amount
+            .to_u64()
+            .ok_or_else(|| anyhow!("Invalid amount for flash loan"))?;
+        Ok(FlashLoanQuote {
+            fee: Decimal::from(self.calculate_fee(amount_u64)),
+            provider: Self::PROTOCOL_NAME.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reserve account data holding `mint` with the given vaults
+    fn reserve_data(mint: &Pubkey, supply: &Pubkey, fee: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; kamino_layout::MIN_LEN];
+        data[..8].copy_from_slice(&kamino_layout::DISCRIMINATOR);
+        data[kamino_layout::MINT..kamino_layout::MINT + 32].copy_from_slice(mint.as_ref());
+        data[kamino_layout::SUPPLY_VAULT..kamino_layout::SUPPLY_VAULT + 32].copy_from_slice(supply.as_ref());
+        data[kamino_layout::FEE_VAULT..kamino_layout::FEE_VAULT + 32].copy_from_slice(fee.as_ref());
+        data[kamino_layout::DECIMALS] = 6;
+        data
+    }
+
+    /// `(pubkey, is_signer, is_writable)` of each account meta
+    fn metas(ix: &Instruction) -> Vec<(Pubkey, bool, bool)> {
+        ix.accounts.iter().map(|m| (m.pubkey, m.is_signer, m.is_writable)).collect()
+    }
+
+    #[test]
+    fn test_instructions_match_the_klend_idl() {
+        let (market, reserve, mint, owner) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (supply, fee_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let kamino = KaminoFlashLoan::new(market, reserve, mint, owner, 9).with_borrow_instruction_index(2);
+
+        // Vaults are only known once the reserve has been read
+        assert!(kamino.borrow_instruction(1_000_000, &mint).is_err());
+        kamino.parse_reserve_liquidity(&reserve_data(&mint, &supply, &fee_vault)).unwrap();
+        assert!(kamino.borrow_instruction(1_000_000, &Pubkey::new_unique()).is_err());
+
+        let program = Pubkey::from_str("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD").unwrap();
+        let (authority, _) = Pubkey::find_program_address(&[b"lma", market.as_ref()], &program);
+        let user = get_associated_token_address(&owner, &mint);
+        // Account order of both instructions in the klend IDL: user_transfer_authority,
+        // lending_market_authority, lending_market, reserve, reserve_liquidity_mint,
+        // the reserve's supply vault, the user's token account,
+        // reserve_liquidity_fee_receiver, referrer_token_state, referrer_account,
+        // sysvar_info, token_program
+        let expected = vec![
+            (owner, true, false),
+            (authority, false, false),
+            (market, false, false),
+            (reserve, false, true),
+            (mint, false, false),
+            (supply, false, true),
+            (user, false, true),
+            (fee_vault, false, true),
+            (program, false, false),
+            (program, false, false),
+            (sysvar::instructions::id(), false, false),
+            (spl_token::id(), false, false),
+        ];
+
+        // sha256("global:flash_borrow_reserve_liquidity")[..8], then liquidity_amount: u64
+        let borrow = kamino.borrow_instruction(1_000_000, &mint).unwrap();
+        assert_eq!(borrow.program_id, program);
+        assert_eq!(
+            borrow.data,
+            [&[0x87, 0xe7, 0x34, 0xa7, 0x07, 0x34, 0xd4, 0xc1][..], &1_000_000u64.to_le_bytes()].concat()
+        );
+        assert_eq!(metas(&borrow), expected);
+
+        // sha256("global:flash_repay_reserve_liquidity")[..8], then
+        // liquidity_amount: u64 and borrow_instruction_index: u8
+        let repay = kamino.repay_instruction(1_000_000, &mint).unwrap();
+        assert_eq!(repay.program_id, program);
+        assert_eq!(
+            repay.data,
+            [&[0xb9, 0x75, 0x00, 0xcb, 0x60, 0xf5, 0xb4, 0xba][..], &1_000_000u64.to_le_bytes(), &[2]].concat()
+        );
+        assert_eq!(metas(&repay), expected);
+        assert_eq!(kamino.calculate_fee(1_000_000), 900);
+    }
+}
@@ -1,4 +1,8 @@
+pub mod kamino;
+pub mod liquidity;
+pub mod marginfi;
 pub mod metrics;
+pub mod router;
 pub mod safety;
 pub mod solend;
 
@@ -8,6 +12,9 @@ use rust_decimal::Decimal;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 
+pub use liquidity::ReserveLiquidity;
+pub use router::FlashLoanRouter;
+
 #[async_trait]
 pub trait FlashLoanProvider: Send + Sync {
     /// Name of the provider (e.g., "Solend", "Mango")
@@ -24,6 +31,23 @@ pub trait FlashLoanProvider: Send + Sync {
 
     /// Get a flash loan quote (fee and expected overhead)
     async fn get_quote(&self, token_mint: Pubkey, amount: Decimal) -> Result<FlashLoanQuote>;
+
+    /// Reserve account lending `token_mint`, if this provider knows one
+    fn reserve_account(&self, _token_mint: &Pubkey) -> Option<Pubkey> {
+        None
+    }
+
+    /// Read the liquidity of a reserve from its account data
+    fn parse_reserve_liquidity(&self, _data: &[u8]) -> Result<ReserveLiquidity> {
+        Err(anyhow::anyhow!("{} does not expose reserve liquidity", self.name()))
+    }
+
+    /// Instructions that open and close the loan, placed before the borrow
+    /// and after the repay; `end_index` is the position of the closing one
+    /// in the transaction. Most providers need none.
+    fn loan_bounds(&self, _end_index: u64) -> Option<(Instruction, Instruction)> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,204 @@
+//! Reserve liquidity
+//!
+//! How much a flash loan can borrow depends on the lending reserve, not the
+//! protocol: the reserve's available liquidity shrinks as utilization rises,
+//! and borrowing a large share of what is left pushes utilization (and the
+//! fees it drives) up further. Providers expose their reserve accounts so the
+//! bot can read live liquidity and keep each loan to a fraction of it.
+
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+/// Liquidity of one lending reserve, in atoms of its token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReserveLiquidity {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Liquidity that can be borrowed right now
+    pub available: u64,
+    /// Liquidity currently lent out
+    pub borrowed: u64,
+}
+
+impl ReserveLiquidity {
+    /// Borrowed share of total liquidity, 0–1
+    pub fn utilization(&self) -> Decimal {
+        let total = u128::from(self.available) + u128::from(self.borrowed);
+        if total == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.borrowed) / Decimal::from(total)
+    }
+
+    /// `atoms` of the reserve's token in whole tokens
+    pub fn tokens(&self, atoms: u64) -> Decimal {
+        Decimal::from(atoms) / Decimal::from(10u64.pow(self.decimals as u32))
+    }
+
+    /// Largest loan, in whole tokens, that takes at most `fraction` of the
+    /// available liquidity
+    pub fn max_borrow(&self, fraction: Decimal) -> Decimal {
+        let fraction = fraction.clamp(Decimal::ZERO, Decimal::ONE);
+        self.tokens(self.available) * fraction
+    }
+}
+
+/// Byte offsets in a Solend (SPL token-lending) `Reserve` account
+mod solend_layout {
+    pub const MINT: usize = 42;
+    pub const DECIMALS: usize = 74;
+    pub const AVAILABLE: usize = 171;
+    /// Borrowed amount as a WAD (18 decimal) fixed-point u128
+    pub const BORROWED_WADS: usize = 179;
+    pub const MIN_LEN: usize = 195;
+}
+
+/// Parse the liquidity section of a Solend reserve account
+pub fn parse_solend_reserve(data: &[u8]) -> Result<ReserveLiquidity> {
+    use solend_layout::*;
+
+    if data.len() < MIN_LEN {
+        return Err(anyhow!(
+            "Solend reserve account too short: {} bytes",
+            data.len()
+        ));
+    }
+    let u64_at = |offset: usize| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    let mut wads = [0u8; 16];
+    wads.copy_from_slice(&data[BORROWED_WADS..BORROWED_WADS + 16]);
+    let borrowed = u128::from_le_bytes(wads) / 10u128.pow(18);
+
+    Ok(ReserveLiquidity {
+        mint: Pubkey::try_from(&data[MINT..MINT + 32])
+            .map_err(|_| anyhow!("Invalid reserve mint"))?,
+        decimals: data[DECIMALS],
+        available: u64_at(AVAILABLE),
+        borrowed: u64::try_from(borrowed).unwrap_or(u64::MAX),
+    })
+}
+
+/// Byte offsets in a Kamino Lend `Reserve` account (Anchor, zero-copy)
+pub(crate) mod kamino_layout {
+    /// Anchor discriminator of the `Reserve` account
+    pub const DISCRIMINATOR: [u8; 8] = [43, 242, 204, 202, 26, 247, 59, 127];
+    pub const MINT: usize = 128;
+    pub const SUPPLY_VAULT: usize = 160;
+    pub const FEE_VAULT: usize = 192;
+    pub const AVAILABLE: usize = 224;
+    /// Borrowed amount as a fixed-point u128 with 60 fractional bits
+    pub const BORROWED_SF: usize = 232;
+    pub const DECIMALS: usize = 272;
+    pub const MIN_LEN: usize = 280;
+}
+
+/// Parse the liquidity section of a Kamino Lend reserve account
+pub fn parse_kamino_reserve(data: &[u8]) -> Result<ReserveLiquidity> {
+    use kamino_layout::*;
+
+    if data.len() < MIN_LEN {
+        return Err(anyhow!(
+            "Kamino reserve account too short: {} bytes",
+            data.len()
+        ));
+    }
+    if data[..8] != DISCRIMINATOR {
+        return Err(anyhow!("Not a Kamino reserve account"));
+    }
+    let u64_at = |offset: usize| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    let mut sf = [0u8; 16];
+    sf.copy_from_slice(&data[BORROWED_SF..BORROWED_SF + 16]);
+    let borrowed = u128::from_le_bytes(sf) >> 60;
+
+    Ok(ReserveLiquidity {
+        mint: Pubkey::try_from(&data[MINT..MINT + 32])
+            .map_err(|_| anyhow!("Invalid reserve mint"))?,
+        decimals: u8::try_from(u64_at(DECIMALS)).map_err(|_| anyhow!("Invalid reserve decimals"))?,
+        available: u64_at(AVAILABLE),
+        borrowed: u64::try_from(borrowed).unwrap_or(u64::MAX),
+    })
+}
+
+/// Byte offsets in a marginfi v2 `Bank` account (Anchor, zero-copy)
+pub(crate) mod marginfi_layout {
+    /// Anchor discriminator of the `Bank` account
+    pub const DISCRIMINATOR: [u8; 8] = [142, 49, 166, 242, 50, 66, 97, 188];
+    pub const MINT: usize = 8;
+    pub const DECIMALS: usize = 40;
+    /// Share values and share totals are I80F48 fixed-point i128s
+    pub const ASSET_SHARE_VALUE: usize = 80;
+    pub const LIABILITY_SHARE_VALUE: usize = 96;
+    pub const TOTAL_LIABILITY_SHARES: usize = 256;
+    pub const TOTAL_ASSET_SHARES: usize = 272;
+    pub const MIN_LEN: usize = 288;
+}
+
+/// Parse the liquidity of a marginfi bank account: deposits less
+/// borrows are available, borrows are lent out
+pub fn parse_marginfi_bank(data: &[u8]) -> Result<ReserveLiquidity> {
+    use marginfi_layout::*;
+
+    if data.len() < MIN_LEN {
+        return Err(anyhow!(
+            "Marginfi bank account too short: {} bytes",
+            data.len()
+        ));
+    }
+    if data[..8] != DISCRIMINATOR {
+        return Err(anyhow!("Not a marginfi bank account"));
+    }
+    let i80f48_at = |offset: usize| {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&data[offset..offset + 16]);
+        i128::from_le_bytes(bytes)
+    };
+    // Whole shares times share value keeps the product inside an i128
+    let amount = |shares: usize, value: usize| {
+        let atoms = ((i80f48_at(shares) >> 48).saturating_mul(i80f48_at(value))) >> 48;
+        u64::try_from(atoms.max(0)).unwrap_or(u64::MAX)
+    };
+    let deposits = amount(TOTAL_ASSET_SHARES, ASSET_SHARE_VALUE);
+    let borrowed = amount(TOTAL_LIABILITY_SHARES, LIABILITY_SHARE_VALUE);
+
+    Ok(ReserveLiquidity {
+        mint: Pubkey::try_from(&data[MINT..MINT + 32])
+            .map_err(|_| anyhow!("Invalid bank mint"))?,
+        decimals: data[DECIMALS],
+        available: deposits.saturating_sub(borrowed),
+        borrowed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_solend_reserve() {
+        let mint = Pubkey::new_from_array([7; 32]);
+        let mut data = vec![0u8; 600];
+        data[solend_layout::MINT..solend_layout::MINT + 32].copy_from_slice(mint.as_ref());
+        data[solend_layout::DECIMALS] = 6;
+        data[solend_layout::AVAILABLE..solend_layout::AVAILABLE + 8]
+            .copy_from_slice(&3_000_000_000u64.to_le_bytes());
+        let wads = 1_000_000_000u128 * 10u128.pow(18);
+        data[solend_layout::BORROWED_WADS..solend_layout::BORROWED_WADS + 16]
+            .copy_from_slice(&wads.to_le_bytes());
+
+        let liquidity = parse_solend_reserve(&data).unwrap();
+        assert_eq!(liquidity.mint, mint);
+        assert_eq!(liquidity.borrowed, 1_000_000_000);
+        assert_eq!(liquidity.utilization(), Decimal::new(25, 2));
+        // 10% of 3,000 available tokens
+        assert_eq!(liquidity.max_borrow(Decimal::new(1, 1)), Decimal::from(300));
+        assert!(parse_solend_reserve(&data[..100]).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! marginfi flash loans
+//!
+//! A marginfi flash loan is an ordinary borrow and repay on the wallet's
+//! marginfi account, wrapped in `lending_account_start_flashloan` and
+//! `lending_account_end_flashloan`: while the flash loan is open the
+//! account's health check is deferred to the end instruction, so nothing
+//! needs to be deposited as collateral. The start instruction names the
+//! index of the end one; both are handed out as the provider's loan bounds
+//! and placed around the borrow and repay by the transaction builder.
+//!
+//! Banks are configured per token. The repay closes the whole liability
+//! (`repay_all`), so the account should hold no other borrows: the end
+//! instruction is built without the bank and oracle accounts a health
+//! check over other balances would need.
+
+use super::liquidity::parse_marginfi_bank;
+use super::{FlashLoanProvider, FlashLoanQuote, ReserveLiquidity};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::info;
+
+/// Anchor discriminator of `lending_account_borrow`
+const BORROW: [u8; 8] = [4, 126, 116, 53, 48, 5, 212, 31];
+/// Anchor discriminator of `lending_account_repay`
+const REPAY: [u8; 8] = [79, 209, 172, 177, 222, 51, 173, 151];
+/// Anchor discriminator of `lending_account_start_flashloan`
+const START_FLASHLOAN: [u8; 8] = [14, 131, 33, 220, 81, 186, 180, 107];
+/// Anchor discriminator of `lending_account_end_flashloan`
+const END_FLASHLOAN: [u8; 8] = [105, 124, 201, 106, 153, 2, 8, 156];
+
+/// marginfi v2 flash loans from the configured banks of one group
+pub struct MarginfiFlashLoan {
+    program_id: Pubkey,
+    group: Pubkey,
+    /// The wallet's marginfi account the loan is taken on
+    marginfi_account: Pubkey,
+    /// Wallet that owns the account and receives and repays the loan
+    owner: Pubkey,
+    /// Bank lending each mint
+    banks: HashMap<Pubkey, Pubkey>,
+    fee_bps: u64,
+}
+
+impl MarginfiFlashLoan {
+    pub const PROTOCOL_NAME: &'static str = "Marginfi";
+
+    // Mainnet program ID
+    pub const MARGINFI_PROGRAM_ID: &'static str = "MFv2hWf31Z9kbCa1snEPYctwafyhdvnV7FZnsebVacA";
+
+    pub fn new(group: Pubkey, marginfi_account: Pubkey, owner: Pubkey, fee_bps: u64) -> Self {
+        Self {
+            program_id: Pubkey::from_str(Self::MARGINFI_PROGRAM_ID).unwrap(),
+            group,
+            marginfi_account,
+            owner,
+            banks: HashMap::new(),
+            fee_bps,
+        }
+    }
+
+    /// Lend `mint` from `bank`
+    pub fn with_bank(mut self, mint: Pubkey, bank: Pubkey) -> Self {
+        self.banks.insert(mint, bank);
+        self
+    }
+
+    fn bank(&self, token_mint: &Pubkey) -> Result<Pubkey> {
+        self.banks
+            .get(token_mint)
+            .copied()
+            .ok_or_else(|| anyhow!("No marginfi bank configured for {}", token_mint))
+    }
+
+    fn liquidity_vault(&self, bank: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"liquidity_vault", bank.as_ref()], &self.program_id).0
+    }
+
+    fn liquidity_vault_authority(&self, bank: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"liquidity_vault_auth", bank.as_ref()], &self.program_id).0
+    }
+
+    /// Opens the flash loan; `end_index` is the position of
+    /// [`Self::end_flashloan_instruction`] in the transaction
+    pub fn start_flashloan_instruction(&self, end_index: u64) -> Instruction {
+        let mut data = START_FLASHLOAN.to_vec();
+        data.extend_from_slice(&end_index.to_le_bytes());
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.marginfi_account, false),
+                AccountMeta::new_readonly(self.owner, true),
+                AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// Closes the flash loan and runs the deferred health check
+    pub fn end_flashloan_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.marginfi_account, false),
+                AccountMeta::new_readonly(self.owner, true),
+            ],
+            data: END_FLASHLOAN.to_vec(),
+        }
+    }
+}
+
+#[async_trait]
+impl FlashLoanProvider for MarginfiFlashLoan {
+    fn name(&self) -> &'static str {
+        Self::PROTOCOL_NAME
+    }
+
+    fn calculate_fee(&self, borrow_amount: u64) -> u64 {
+        (borrow_amount as u128 * self.fee_bps as u128 / 10000) as u64
+    }
+
+    fn borrow_instruction(&self, borrow_amount: u64, token_mint: &Pubkey) -> Result<Instruction> {
+        let bank = self.bank(token_mint)?;
+        info!("Creating Marginfi borrow instruction for amount: {}", borrow_amount);
+
+        let mut data = BORROW.to_vec();
+        data.extend_from_slice(&borrow_amount.to_le_bytes());
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(self.group, false),
+                AccountMeta::new(self.marginfi_account, false),
+                AccountMeta::new_readonly(self.owner, true),
+                AccountMeta::new(bank, false),
+                AccountMeta::new(get_associated_token_address(&self.owner, token_mint), false),
+                AccountMeta::new(self.liquidity_vault_authority(&bank), false),
+                AccountMeta::new(self.liquidity_vault(&bank), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data,
+        })
+    }
+
+    /// Repays the whole liability, so any origination fee the bank charges
+    /// is included
+    fn repay_instruction(&self, borrow_amount: u64, token_mint: &Pubkey) -> Result<Instruction> {
+        let bank = self.bank(token_mint)?;
+        let amount = borrow_amount + self.calculate_fee(borrow_amount);
+        info!("Creating Marginfi repay instruction for amount: {}", amount);
+
+        let mut data = REPAY.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        // repay_all: Some(true)
+        data.extend_from_slice(&[1, 1]);
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new_readonly(self.group, false),
+                AccountMeta::new(self.marginfi_account, false),
+                AccountMeta::new_readonly(self.owner, true),
+                AccountMeta::new(bank, false),
+                AccountMeta::new(get_associated_token_address(&self.owner, token_mint), false),
+                AccountMeta::new(self.liquidity_vault(&bank), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data,
+        })
+    }
+
+    fn reserve_account(&self, token_mint: &Pubkey) -> Option<Pubkey> {
+        self.banks.get(token_mint).copied()
+    }
+
+    fn parse_reserve_liquidity(&self, data: &[u8]) -> Result<ReserveLiquidity> {
+        parse_marginfi_bank(data)
+    }
+
+    fn loan_bounds(&self, end_index: u64) -> Option<(Instruction, Instruction)> {
+        Some((self.start_flashloan_instruction(end_index), self.end_flashloan_instruction()))
+    }
+
+    async fn get_quote(&self, token_mint: Pubkey, amount: Decimal) -> Result<FlashLoanQuote> {
+        self.bank(&token_mint)?;
+        let amount_u64 = amount
+            .to_u64()
+            .ok_or_else(|| anyhow!("Invalid amount for flash loan"))?;
+        Ok(FlashLoanQuote {
+            fee: Decimal::from(self.calculate_fee(amount_u64)),
+            provider: Self::PROTOCOL_NAME.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquidity::marginfi_layout;
+
+    #[tokio::test]
+    async fn test_marginfi_loan_from_configured_bank() {
+        let (usdc, sol, bank) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (account, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let marginfi = MarginfiFlashLoan::new(Pubkey::new_unique(), account, owner, 0).with_bank(usdc, bank);
+
+        assert_eq!(marginfi.reserve_account(&usdc), Some(bank));
+        assert!(marginfi.reserve_account(&sol).is_none());
+        assert!(marginfi.get_quote(sol, Decimal::from(100)).await.is_err());
+        assert_eq!(marginfi.get_quote(usdc, Decimal::from(100)).await.unwrap().fee, Decimal::ZERO);
+
+        let borrow = marginfi.borrow_instruction(1_000_000, &usdc).unwrap();
+        assert_eq!(borrow.data[..8], BORROW);
+        assert_eq!(borrow.data[8..], 1_000_000u64.to_le_bytes());
+        assert_eq!((borrow.accounts[1].pubkey, borrow.accounts[3].pubkey), (account, bank));
+        assert!(borrow.accounts[2].is_signer);
+        let repay = marginfi.repay_instruction(1_000_000, &usdc).unwrap();
+        assert_eq!(repay.data[16..], [1, 1]);
+        assert_eq!(repay.accounts[5].pubkey, borrow.accounts[6].pubkey);
+        assert_eq!(marginfi.start_flashloan_instruction(4).data[8..], 4u64.to_le_bytes());
+        assert!(marginfi.borrow_instruction(1_000_000, &sol).is_err());
+
+        // 5,000 USDC deposited at share value 1, 2,000 borrowed at 1.5
+        let mut data = vec![0u8; 1_800];
+        data[..8].copy_from_slice(&marginfi_layout::DISCRIMINATOR);
+        data[marginfi_layout::MINT..marginfi_layout::MINT + 32].copy_from_slice(usdc.as_ref());
+        data[marginfi_layout::DECIMALS] = 6;
+        let mut put = |offset: usize, value: i128| data[offset..offset + 16].copy_from_slice(&value.to_le_bytes());
+        put(marginfi_layout::ASSET_SHARE_VALUE, 1 << 48);
+        put(marginfi_layout::TOTAL_ASSET_SHARES, 5_000_000_000 << 48);
+        put(marginfi_layout::LIABILITY_SHARE_VALUE, 3 << 47);
+        put(marginfi_layout::TOTAL_LIABILITY_SHARES, 1_333_333_333 << 48);
+        let liquidity = marginfi.parse_reserve_liquidity(&data).unwrap();
+        assert_eq!(liquidity.mint, usdc);
+        assert_eq!((liquidity.available, liquidity.borrowed), (3_000_000_001, 1_999_999_999));
+        assert!(marginfi.parse_reserve_liquidity(&data[..200]).is_err());
+    }
+
+    /// `(pubkey, is_signer, is_writable)` of each account meta
+    fn metas(ix: &Instruction) -> Vec<(Pubkey, bool, bool)> {
+        ix.accounts.iter().map(|m| (m.pubkey, m.is_signer, m.is_writable)).collect()
+    }
+
+    #[test]
+    fn test_instructions_match_the_marginfi_idl() {
+        let (group, account, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (usdc, bank) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let marginfi = MarginfiFlashLoan::new(group, account, owner, 10).with_bank(usdc, bank);
+
+        let program = Pubkey::from_str("MFv2hWf31Z9kbCa1snEPYctwafyhdvnV7FZnsebVacA").unwrap();
+        let vault = Pubkey::find_program_address(&[b"liquidity_vault", bank.as_ref()], &program).0;
+        let vault_authority = Pubkey::find_program_address(&[b"liquidity_vault_auth", bank.as_ref()], &program).0;
+        let user = get_associated_token_address(&owner, &usdc);
+
+        // sha256("global:lending_account_borrow")[..8], then amount: u64. Accounts:
+        // marginfi_group, marginfi_account, signer, bank, destination_token_account,
+        // bank_liquidity_vault_authority, bank_liquidity_vault, token_program
+        let borrow = marginfi.borrow_instruction(1_000_000, &usdc).unwrap();
+        assert_eq!(borrow.program_id, program);
+        assert_eq!(
+            borrow.data,
+            [&[0x04, 0x7e, 0x74, 0x35, 0x30, 0x05, 0xd4, 0x1f][..], &1_000_000u64.to_le_bytes()].concat()
+        );
+        assert_eq!(
+            metas(&borrow),
+            vec![
+                (group, false, false),
+                (account, false, true),
+                (owner, true, false),
+                (bank, false, true),
+                (user, false, true),
+                (vault_authority, false, true),
+                (vault, false, true),
+                (spl_token::id(), false, false),
+            ]
+        );
+
+        // sha256("global:lending_account_repay")[..8], then amount: u64 and
+        // repay_all: Option<bool>. Accounts: marginfi_group, marginfi_account,
+        // signer, bank, signer_token_account, bank_liquidity_vault, token_program
+        let repay = marginfi.repay_instruction(1_000_000, &usdc).unwrap();
+        assert_eq!(
+            repay.data,
+            [&[0x4f, 0xd1, 0xac, 0xb1, 0xde, 0x33, 0xad, 0x97][..], &1_001_000u64.to_le_bytes(), &[1, 1]].concat()
+        );
+        assert_eq!(
+            metas(&repay),
+            vec![
+                (group, false, false),
+                (account, false, true),
+                (owner, true, false),
+                (bank, false, true),
+                (user, false, true),
+                (vault, false, true),
+                (spl_token::id(), false, false),
+            ]
+        );
+
+        // sha256("global:lending_account_start_flashloan")[..8], then end_index: u64.
+        // Accounts: marginfi_account, signer, ixs_sysvar
+        let (start, end) = marginfi.loan_bounds(7).unwrap();
+        assert_eq!(
+            start.data,
+            [&[0x0e, 0x83, 0x21, 0xdc, 0x51, 0xba, 0xb4, 0x6b][..], &7u64.to_le_bytes()].concat()
+        );
+        assert_eq!(
+            metas(&start),
+            vec![(account, false, true), (owner, true, false), (sysvar::instructions::id(), false, false)]
+        );
+
+        // sha256("global:lending_account_end_flashloan")[..8], no arguments.
+        // Accounts: marginfi_account, signer
+        assert_eq!(end.data, [0x69, 0x7c, 0xc9, 0x6a, 0x99, 0x02, 0x08, 0x9c]);
+        assert_eq!(metas(&end), vec![(account, false, true), (owner, true, false)]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Flash loan routing
+//!
+//! The bot holds one router over every configured provider. A quote asks
+//! all of them at once and takes the lowest fee among those whose reserve
+//! can lend the amount; a reserve whose liquidity has not been read yet is
+//! given the benefit of the doubt. Borrow and repay instructions come from
+//! one provider: the cheapest, by fee on the amount, that can build both
+//! for the token and has the liquidity.
+
+use super::{FlashLoanProvider, FlashLoanQuote, ReserveLiquidity};
+use anyhow::{anyhow, Result};
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+pub struct FlashLoanRouter {
+    providers: Vec<Box<dyn FlashLoanProvider>>,
+    /// Last read liquidity per provider and mint
+    liquidity: RwLock<HashMap<(&'static str, Pubkey), ReserveLiquidity>>,
+}
+
+impl FlashLoanRouter {
+    pub fn new(providers: Vec<Box<dyn FlashLoanProvider>>) -> Self {
+        Self {
+            providers,
+            liquidity: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Each provider's reserve lending `token_mint`
+    pub fn reserves(&self, token_mint: &Pubkey) -> Vec<(&'static str, Pubkey)> {
+        self.providers
+            .iter()
+            .filter_map(|p| Some((p.name(), p.reserve_account(token_mint)?)))
+            .collect()
+    }
+
+    /// Parse `provider`'s reserve account and keep its liquidity for routing
+    pub fn record_reserve(&self, provider: &str, data: &[u8]) -> Result<ReserveLiquidity> {
+        let provider = self
+            .providers
+            .iter()
+            .find(|p| p.name() == provider)
+            .ok_or_else(|| anyhow!("Unknown flash loan provider {}", provider))?;
+        let liquidity = provider.parse_reserve_liquidity(data)?;
+        self.liquidity
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((provider.name(), liquidity.mint), liquidity);
+        Ok(liquidity)
+    }
+
+    /// Deepest known reserve lending `token_mint`
+    pub fn deepest(&self, token_mint: &Pubkey) -> Option<ReserveLiquidity> {
+        self.liquidity
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|((_, mint), _)| mint == token_mint)
+            .map(|(_, liquidity)| *liquidity)
+            .max_by_key(|liquidity| liquidity.available)
+    }
+
+    /// Whether `provider`'s reserve can lend `tokens(reserve)` whole tokens
+    /// of `token_mint`; a reserve not read yet is assumed to
+    fn can_lend(&self, provider: &'static str, token_mint: &Pubkey, tokens: impl Fn(&ReserveLiquidity) -> Decimal) -> bool {
+        self.liquidity
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(provider, *token_mint))
+            .is_none_or(|liquidity| liquidity.max_borrow(Decimal::ONE) >= tokens(liquidity))
+    }
+
+    /// Lowest-fee quote, from all providers at once, for borrowing `amount`
+    /// whole tokens of `token_mint`
+    pub async fn quote(&self, token_mint: Pubkey, amount: Decimal) -> Result<FlashLoanQuote> {
+        let quotes = join_all(self.providers.iter().map(|p| p.get_quote(token_mint, amount))).await;
+        let mut last_err = None;
+        let mut best: Option<FlashLoanQuote> = None;
+        for (provider, quote) in self.providers.iter().zip(quotes) {
+            match quote {
+                Ok(quote) if !self.can_lend(provider.name(), &token_mint, |_| amount) => {
+                    last_err = Some(anyhow!("{} reserve cannot lend {}", quote.provider, amount));
+                }
+                Ok(quote) if best.as_ref().is_none_or(|b| quote.fee < b.fee) => best = Some(quote),
+                Ok(_) => {}
+                Err(e) => last_err = Some(e),
+            }
+        }
+        best.ok_or_else(|| last_err.unwrap_or_else(|| anyhow!("No flash loan providers configured")))
+    }
+
+    /// Borrow and repay instructions for a loan of `amount` atoms of
+    /// `token_mint`, from the cheapest provider that has the liquidity and
+    /// can build both, with that provider's name
+    pub fn instructions(&self, amount: u64, token_mint: &Pubkey) -> Result<(&'static str, Instruction, Instruction)> {
+        let mut providers: Vec<&dyn FlashLoanProvider> = self.providers.iter().map(|p| p.as_ref()).collect();
+        providers.sort_by_key(|p| p.calculate_fee(amount));
+        providers
+            .into_iter()
+            .filter(|p| self.can_lend(p.name(), token_mint, |liquidity| liquidity.tokens(amount)))
+            .find_map(|p| {
+                let borrow = p.borrow_instruction(amount, token_mint).ok()?;
+                let repay = p.repay_instruction(amount, token_mint).ok()?;
+                Some((p.name(), borrow, repay))
+            })
+            .ok_or_else(|| anyhow!("No flash loan provider can lend {} of {}", amount, token_mint))
+    }
+
+    /// Instructions `provider` opens and closes its loans with, if any;
+    /// `end_index` is the position of the closing one in the transaction
+    pub fn loan_bounds(&self, provider: &str, end_index: u64) -> Option<(Instruction, Instruction)> {
+        self.providers
+            .iter()
+            .find(|p| p.name() == provider)?
+            .loan_bounds(end_index)
+    }
+
+    /// Fee `provider` charges on a loan of `amount` atoms
+    pub fn fee(&self, provider: &str, amount: u64) -> Option<u64> {
+        self.providers
+            .iter()
+            .find(|p| p.name() == provider)
+            .map(|p| p.calculate_fee(amount))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kamino::KaminoFlashLoan;
+    use crate::liquidity::kamino_layout;
+    use crate::solend::SolendFlashLoan;
+
+    #[tokio::test]
+    async fn test_routes_to_cheapest_provider_with_liquidity() {
+        let usdc = Pubkey::new_unique();
+        let sol = Pubkey::new_unique();
+        let kamino_reserve = Pubkey::new_unique();
+        let kamino = KaminoFlashLoan::new(Pubkey::new_unique(), kamino_reserve, usdc, Pubkey::new_unique(), 1);
+        let solend_reserve = Pubkey::new_unique();
+        let router = FlashLoanRouter::new(vec![
+            Box::new(SolendFlashLoan::new(solend_reserve)),
+            Box::new(kamino),
+        ]);
+
+        // Kamino only lends USDC, and builds loans only once its reserve has been read
+        let quote = router.quote(usdc, Decimal::from(1_000_000)).await.unwrap();
+        assert_eq!((quote.provider.as_str(), quote.fee), ("Kamino", Decimal::from(100)));
+        let quote = router.quote(sol, Decimal::from(1_000_000)).await.unwrap();
+        assert_eq!(quote.provider, "Solend");
+        assert_eq!(router.reserves(&usdc), vec![("Solend", solend_reserve), ("Kamino", kamino_reserve)]);
+        assert_eq!(router.reserves(&sol), vec![("Solend", solend_reserve)]);
+        let (provider, borrow, _) = router.instructions(1_000_000, &usdc).unwrap();
+        assert_eq!((provider, borrow.data), ("Solend", vec![15]));
+
+        // 3 USDC available at 6 decimals
+        let supply = Pubkey::new_unique();
+        let mut data = vec![0u8; 600];
+        data[..8].copy_from_slice(&kamino_layout::DISCRIMINATOR);
+        data[kamino_layout::MINT..kamino_layout::MINT + 32].copy_from_slice(usdc.as_ref());
+        data[kamino_layout::SUPPLY_VAULT..kamino_layout::SUPPLY_VAULT + 32].copy_from_slice(supply.as_ref());
+        data[kamino_layout::AVAILABLE..kamino_layout::AVAILABLE + 8].copy_from_slice(&3_000_000u64.to_le_bytes());
+        data[kamino_layout::BORROWED_SF..kamino_layout::BORROWED_SF + 16]
+            .copy_from_slice(&(1_000_000u128 << 60).to_le_bytes());
+        data[kamino_layout::DECIMALS] = 6;
+        let liquidity = router.record_reserve("Kamino", &data).unwrap();
+        assert_eq!((liquidity.mint, liquidity.available, liquidity.borrowed), (usdc, 3_000_000, 1_000_000));
+        assert_eq!(router.deepest(&usdc), Some(liquidity));
+        assert!(router.record_reserve("Kamino", &data[..100]).is_err());
+
+        let (provider, borrow, repay) = router.instructions(1_000_000, &usdc).unwrap();
+        assert_eq!(provider, "Kamino");
+        assert_eq!(borrow.program_id.to_string(), KaminoFlashLoan::KAMINO_PROGRAM_ID);
+        assert_eq!(borrow.accounts[5].pubkey, supply);
+        assert_eq!(repay.data.len(), 17);
+        assert_eq!(repay.data[16], KaminoFlashLoan::DEFAULT_BORROW_INSTRUCTION_INDEX);
+
+        assert_eq!(router.fee("Kamino", 1_000_000), Some(100));
+
+        // Quotes and instructions agree on the liquidity: 3 USDC can be
+        // lent by Kamino, anything beyond goes to Solend
+        assert_eq!(router.instructions(3_000_000, &usdc).unwrap().0, "Kamino");
+        let quote = router.quote(usdc, Decimal::from(5)).await.unwrap();
+        assert_eq!(quote.provider, "Solend");
+        let (provider, borrow, _) = router.instructions(5_000_000, &usdc).unwrap();
+        assert_eq!((provider, borrow.data), ("Solend", vec![15]));
+    }
+}
@@ -110,6 +110,16 @@ impl FlashLoanProvider for SolendFlashLoan {
         })
     }
 
+    /// The configured reserve; callers check the parsed mint, since a
+    /// single-reserve setup lends only that reserve's token
+    fn reserve_account(&self, _token_mint: &Pubkey) -> Option<Pubkey> {
+        Some(self.reserve)
+    }
+
+    fn parse_reserve_liquidity(&self, data: &[u8]) -> Result<super::ReserveLiquidity> {
+        super::liquidity::parse_solend_reserve(data)
+    }
+
     async fn get_quote(
         &self,
         _token_mint: Pubkey,
@@ -0,0 +1,132 @@
+//! Crossed Market Strategy
+//!
+//! The most conservative signal there is: one venue's bid strictly above
+//! another venue's ask, by more than both venues' fees plus a margin for
+//! network fees and slippage. It keeps no history and makes no forecast;
+//! when it fires, the edge is in the quotes of the current tick.
+
+use crate::Strategy;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use solana_arb_core::{
+    types::{ArbitrageOpportunity, OpportunitySource, PriceData, TokenPair},
+    ArbitrageResult,
+};
+use std::collections::HashMap;
+
+pub struct CrossedMarket {
+    /// Edge required beyond venue fees, in percent
+    margin_pct: Decimal,
+}
+
+impl CrossedMarket {
+    /// `margin_bps`: edge required on top of both venues' fees
+    pub fn new(margin_bps: Decimal) -> Self {
+        Self {
+            margin_pct: margin_bps / Decimal::from(100),
+        }
+    }
+
+    fn check(&self, buy: &PriceData, sell: &PriceData) -> Option<ArbitrageOpportunity> {
+        if buy.dex == sell.dex || buy.ask <= Decimal::ZERO || sell.bid <= buy.ask {
+            return None;
+        }
+        let gross_profit_pct = (sell.bid - buy.ask) / buy.ask * Decimal::from(100);
+        let net_profit_pct = gross_profit_pct - fees_pct(buy, sell);
+        if net_profit_pct <= self.margin_pct {
+            return None;
+        }
+        Some(ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: buy.pair.clone(),
+            buy_dex: buy.dex,
+            sell_dex: sell.dex,
+            buy_price: buy.ask,
+            sell_price: sell.bid,
+            gross_profit_pct,
+            net_profit_pct,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: chrono::Utc::now(),
+            expired_at: None,
+            buy_quote_at: Some(buy.timestamp),
+            sell_quote_at: Some(sell.timestamp),
+            source: OpportunitySource::Strategy,
+            strategy: Some(self.name().to_string()),
+        })
+    }
+}
+
+/// Both venues' swap fees, in percent (`fee_percentage` is a fraction)
+fn fees_pct(buy: &PriceData, sell: &PriceData) -> Decimal {
+    (buy.dex.fee_percentage() + sell.dex.fee_percentage()) * Decimal::from(100)
+}
+
+#[async_trait]
+impl Strategy for CrossedMarket {
+    fn name(&self) -> &'static str {
+        "Crossed Market"
+    }
+
+    async fn update_state(&self, _price: &PriceData) -> ArbitrageResult<()> {
+        Ok(())
+    }
+
+    async fn analyze(&self, prices: &[PriceData]) -> ArbitrageResult<Vec<ArbitrageOpportunity>> {
+        let mut by_pair: HashMap<&TokenPair, Vec<&PriceData>> = HashMap::new();
+        for price in prices {
+            by_pair.entry(&price.pair).or_default().push(price);
+        }
+
+        let mut opportunities: Vec<_> = by_pair
+            .values()
+            .flat_map(|quotes| {
+                quotes
+                    .iter()
+                    .flat_map(|buy| quotes.iter().filter_map(|sell| self.check(buy, sell)))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.net_profit_pct));
+        Ok(opportunities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_core::DexType;
+
+    fn quote(dex: DexType, bid: i64, ask: i64) -> PriceData {
+        // Prices in hundredths
+        PriceData::new(
+            dex,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::new(bid, 2),
+            Decimal::new(ask, 2),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_only_crosses_clearing_fees_and_margin_fire() {
+        // Raydium and Orca fees are 0.55% together; the margin adds 0.10%
+        let strategy = CrossedMarket::new(Decimal::from(10));
+
+        // Overlapping books, no cross: nothing
+        let prices = [quote(DexType::Raydium, 9990, 10010), quote(DexType::Orca, 10000, 10020)];
+        assert!(strategy.analyze(&prices).await.unwrap().is_empty());
+
+        // Orca's bid crosses Raydium's ask by 0.60%, short of fees + margin
+        let prices = [quote(DexType::Raydium, 9990, 10000), quote(DexType::Orca, 10060, 10070)];
+        assert!(strategy.analyze(&prices).await.unwrap().is_empty());
+
+        // A wide cross fires once, in the crossed direction only
+        let prices = [quote(DexType::Raydium, 9990, 10000), quote(DexType::Orca, 10150, 10160)];
+        let found = strategy.analyze(&prices).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].buy_dex, found[0].sell_dex), (DexType::Raydium, DexType::Orca));
+        assert_eq!(found[0].gross_profit_pct, Decimal::new(15, 1));
+        assert_eq!(found[0].net_profit_pct, Decimal::new(95, 2));
+        assert!(found[0].buy_quote_at.is_some());
+    }
+}
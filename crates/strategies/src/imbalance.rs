@@ -0,0 +1,211 @@
+//! Order Book Imbalance Strategy
+//!
+//! Resting size on a Phoenix book leans before its price moves: when the
+//! top levels hold far more bid than ask size, the next fills tend to lift
+//! the ask. AMM pools only reprice when someone trades against them, so
+//! they follow that move late.
+//!
+//! The strategy reads the volume imbalance of each Phoenix book,
+//! `(bid size - ask size) / (bid size + ask size)` over its top levels.
+//! Past the threshold it predicts the Phoenix mid drifting in the heavy
+//! side's direction, by up to `max_drift_bps` at a one-sided book. An AMM
+//! quote on the wrong side of the prediction is an opportunity even while
+//! its price matches Phoenix: buy on the AMM and sell on Phoenix ahead of
+//! an upward drift, the other way round ahead of a downward one. The
+//! predicted side is a forecast and carries no quote time.
+
+use crate::Strategy;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_arb_core::{
+    types::{ArbitrageOpportunity, DexType, OpportunitySource, PriceData, TokenPair},
+    ArbitrageResult,
+};
+use solana_arb_dex_plugins::phoenix::{OrderBook, PhoenixProvider};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// When an imbalance is traded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImbalanceConfig {
+    /// Book levels per side summed into the imbalance
+    pub levels: usize,
+    /// Smallest |imbalance| (0 to 1) that predicts a drift
+    pub threshold: Decimal,
+    /// Predicted drift (bps) of a fully one-sided book
+    pub max_drift_bps: Decimal,
+    /// Edge required beyond both venues' fees, in bps
+    pub margin_bps: Decimal,
+}
+
+impl Default for ImbalanceConfig {
+    fn default() -> Self {
+        Self {
+            levels: 5,
+            threshold: Decimal::new(3, 1),
+            max_drift_bps: Decimal::from(30),
+            margin_bps: Decimal::from(10),
+        }
+    }
+}
+
+pub struct OrderBookImbalance {
+    books: Arc<PhoenixProvider>,
+    config: ImbalanceConfig,
+}
+
+impl OrderBookImbalance {
+    pub fn new(books: Arc<PhoenixProvider>, config: ImbalanceConfig) -> Self {
+        Self { books, config }
+    }
+
+    /// The mid price `book` is predicted to drift to, if its imbalance
+    /// clears the threshold
+    fn predicted_mid(&self, book: &OrderBook) -> Option<Decimal> {
+        let imbalance = imbalance(book, self.config.levels)?;
+        if imbalance.abs() < self.config.threshold {
+            return None;
+        }
+        let mid = (book.best_bid()? + book.best_ask()?) / Decimal::from(2);
+        Some(mid * (Decimal::ONE + imbalance * self.config.max_drift_bps / Decimal::from(10_000)))
+    }
+
+    fn check(&self, book: &OrderBook, predicted: Decimal, amm: &PriceData) -> Option<ArbitrageOpportunity> {
+        let mid = (book.best_bid()? + book.best_ask()?) / Decimal::from(2);
+        let (buy_dex, sell_dex, buy_price, sell_price) = if predicted > mid {
+            // Upward drift: the AMM's ask is stale low
+            (amm.dex, DexType::Phoenix, amm.ask, predicted)
+        } else {
+            // Downward drift: the AMM's bid is stale high
+            (DexType::Phoenix, amm.dex, predicted, amm.bid)
+        };
+        if buy_price <= Decimal::ZERO || sell_price <= buy_price {
+            return None;
+        }
+        let gross_profit_pct = (sell_price - buy_price) / buy_price * Decimal::from(100);
+        let fees_pct = (buy_dex.fee_percentage() + sell_dex.fee_percentage()) * Decimal::from(100);
+        let net_profit_pct = gross_profit_pct - fees_pct;
+        if net_profit_pct <= self.config.margin_bps / Decimal::from(100) {
+            return None;
+        }
+        let quoted_at = |dex| (dex == amm.dex).then_some(amm.timestamp);
+        Some(ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: amm.pair.clone(),
+            buy_dex,
+            sell_dex,
+            buy_price,
+            sell_price,
+            gross_profit_pct,
+            net_profit_pct,
+            estimated_profit_usd: None,
+            recommended_size: None,
+            detected_at: chrono::Utc::now(),
+            expired_at: None,
+            buy_quote_at: quoted_at(buy_dex),
+            sell_quote_at: quoted_at(sell_dex),
+            source: OpportunitySource::Strategy,
+            strategy: Some(self.name().to_string()),
+        })
+    }
+}
+
+/// `(bid size - ask size) / (bid size + ask size)` over the top `levels`
+/// of each side, from -1 (all asks) to 1 (all bids). `None` for an empty
+/// book.
+pub fn imbalance(book: &OrderBook, levels: usize) -> Option<Decimal> {
+    let bids: Decimal = book.bids.iter().take(levels).map(|l| l.size).sum();
+    let asks: Decimal = book.asks.iter().take(levels).map(|l| l.size).sum();
+    let total = bids + asks;
+    (total > Decimal::ZERO).then(|| (bids - asks) / total)
+}
+
+#[async_trait]
+impl Strategy for OrderBookImbalance {
+    fn name(&self) -> &'static str {
+        "Order Book Imbalance"
+    }
+
+    async fn update_state(&self, _price: &PriceData) -> ArbitrageResult<()> {
+        Ok(())
+    }
+
+    async fn analyze(&self, prices: &[PriceData]) -> ArbitrageResult<Vec<ArbitrageOpportunity>> {
+        // Jupiter routes through Phoenix itself, so only AMM quotes can lag
+        let mut by_pair: HashMap<&TokenPair, Vec<&PriceData>> = HashMap::new();
+        for price in prices.iter().filter(|p| !matches!(p.dex, DexType::Phoenix | DexType::Jupiter)) {
+            by_pair.entry(&price.pair).or_default().push(price);
+        }
+
+        let mut opportunities = Vec::new();
+        for (pair, quotes) in by_pair {
+            let Some(book) = self.books.book(pair) else {
+                continue;
+            };
+            let Some(predicted) = self.predicted_mid(&book) else {
+                continue;
+            };
+            opportunities.extend(quotes.iter().filter_map(|amm| self.check(&book, predicted, amm)));
+        }
+        opportunities.sort_by_key(|o| std::cmp::Reverse(o.net_profit_pct));
+        Ok(opportunities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_arb_dex_plugins::phoenix::BookLevel;
+
+    fn level(price: i64, size: i64) -> BookLevel {
+        BookLevel {
+            price: Decimal::new(price, 2),
+            size: Decimal::from(size),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lopsided_book_flags_lagging_amm() {
+        let pair = TokenPair::new("SOL", "USDC");
+        let books = Arc::new(PhoenixProvider::new());
+        let strategy = OrderBookImbalance::new(
+            books.clone(),
+            ImbalanceConfig {
+                max_drift_bps: Decimal::from(100),
+                ..ImbalanceConfig::default()
+            },
+        );
+        // Raydium matches the Phoenix mid of 100.00
+        let raydium = [PriceData::new(DexType::Raydium, pair.clone(), Decimal::new(9995, 2), Decimal::new(10005, 2))];
+
+        // 90 bid against 10 ask: imbalance 0.8, a predicted mid of 100.80
+        books.update_book(&pair, OrderBook {
+            bids: vec![level(9999, 60), level(9998, 30)],
+            asks: vec![level(10001, 10)],
+        });
+        assert_eq!(imbalance(&books.book(&pair).unwrap(), 5), Some(Decimal::new(8, 1)));
+        let found = strategy.analyze(&raydium).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].buy_dex, found[0].sell_dex), (DexType::Raydium, DexType::Phoenix));
+        assert_eq!(found[0].sell_price, Decimal::new(1008, 1));
+        assert!(found[0].buy_quote_at.is_some() && found[0].sell_quote_at.is_none());
+
+        // The mirror book sells on Raydium ahead of the fall
+        books.update_book(&pair, OrderBook {
+            bids: vec![level(9999, 10)],
+            asks: vec![level(10001, 60), level(10002, 30)],
+        });
+        let found = strategy.analyze(&raydium).await.unwrap();
+        assert_eq!((found[0].buy_dex, found[0].sell_dex), (DexType::Phoenix, DexType::Raydium));
+
+        // A balanced book predicts nothing, and pairs without a book are skipped
+        books.update_book(&pair, OrderBook {
+            bids: vec![level(9999, 50)],
+            asks: vec![level(10001, 45)],
+        });
+        assert!(strategy.analyze(&raydium).await.unwrap().is_empty());
+        let orca = PriceData::new(DexType::Orca, TokenPair::new("JUP", "USDC"), Decimal::ONE, Decimal::ONE);
+        assert!(strategy.analyze(&[orca]).await.unwrap().is_empty());
+    }
+}
@@ -1,11 +1,15 @@
 use crate::Strategy;
 use async_trait::async_trait;
 use solana_arb_core::{
+    memory::MemoryUsage,
     types::{ArbitrageOpportunity, PriceData},
     ArbitrageResult,
 };
 use tokio::sync::RwLock;
 
+/// Pairs tracked; past this the least recently updated pair is dropped
+const MAX_PAIRS: usize = 512;
+
 pub struct LatencyArbitrage {
     // Track last update time to detect stale prices vs fresh updates
     last_update: RwLock<std::collections::HashMap<String, i64>>,
@@ -33,10 +37,23 @@ impl Strategy for LatencyArbitrage {
 
     async fn update_state(&self, price: &PriceData) -> ArbitrageResult<()> {
         let mut last = self.last_update.write().await;
-        last.insert(price.pair.symbol(), price.timestamp.timestamp_millis());
+        let pair = price.pair.symbol();
+        if !last.contains_key(&pair) && last.len() >= MAX_PAIRS {
+            let stalest = last.iter().min_by_key(|(_, at)| **at).map(|(p, _)| p.clone());
+            if let Some(stalest) = stalest {
+                last.remove(&stalest);
+            }
+        }
+        last.insert(pair, price.timestamp.timestamp_millis());
         Ok(())
     }
 
+    async fn memory_usage(&self) -> MemoryUsage {
+        let last = self.last_update.read().await;
+        let keys: usize = last.keys().map(|pair| pair.capacity()).sum();
+        MemoryUsage::of::<(String, i64)>(last.len(), 0).with_bytes(keys)
+    }
+
     async fn analyze(&self, _prices: &[PriceData]) -> ArbitrageResult<Vec<ArbitrageOpportunity>> {
         // Latency arb logic:
         // Compare timestamps of same pair across different DEXs.
@@ -1,16 +1,24 @@
 use async_trait::async_trait;
 use solana_arb_core::{
+    memory::MemoryUsage,
+    regime::RegimeSnapshot,
     types::{ArbitrageOpportunity, PriceData},
     ArbitrageResult,
 };
 
+pub mod crossed;
+pub mod imbalance;
 pub mod latency;
 pub mod statistical;
 pub mod plugin;
+pub mod remote;
 
+pub use crossed::CrossedMarket;
+pub use imbalance::{ImbalanceConfig, OrderBookImbalance};
 pub use latency::LatencyArbitrage;
 pub use statistical::StatisticalArbitrage;
 pub use plugin::*;
+pub use remote::{RemoteStrategy, RemoteStrategyConfig};
 
 /// Trait for trading strategies
 #[async_trait]
@@ -23,4 +31,24 @@ pub trait Strategy: Send + Sync {
 
     /// Update internal state with new market data (e.g., for moving averages)
     async fn update_state(&self, price: &PriceData) -> ArbitrageResult<()>;
+
+    /// Share of the warm-up done, from 0 to 1. Strategies that need a
+    /// history before their signals are valid report how much they have.
+    async fn warm_up_progress(&self) -> f64 {
+        1.0
+    }
+
+    /// Whether signals are valid yet; the bot skips `analyze` until then
+    async fn is_ready(&self) -> bool {
+        self.warm_up_progress().await >= 1.0
+    }
+
+    /// Called each tick with the latest market regime, so a strategy can
+    /// hold back while cross-DEX dislocation is abnormal
+    fn on_regime(&self, _snapshot: &RegimeSnapshot) {}
+
+    /// Approximate memory held by the strategy's market state
+    async fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::default()
+    }
 }
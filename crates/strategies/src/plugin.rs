@@ -59,13 +59,21 @@ impl StrategyRegistry {
         Ok(())
     }
 
-    /// Run all enabled strategies against the provided price data
+    /// Run all enabled strategies against the provided price data, with
+    /// every pair in its canonical orientation
     pub async fn analyze_all(&self, prices: &[PriceData]) -> Vec<ArbitrageOpportunity> {
+        let normalized: Vec<PriceData>;
+        let prices = if prices.iter().all(|p| p.pair.is_canonical()) {
+            prices
+        } else {
+            normalized = prices.iter().cloned().map(PriceData::normalized).collect();
+            &normalized
+        };
         let plugins = self.plugins.read().await;
         let mut all_opps = Vec::new();
 
         for plugin in plugins.iter() {
-            if plugin.descriptor().enabled {
+            if plugin.descriptor().enabled && plugin.is_ready().await {
                 match plugin.analyze(prices).await {
                     Ok(opps) => all_opps.extend(opps),
                     Err(e) => {
@@ -77,8 +85,16 @@ impl StrategyRegistry {
         all_opps
     }
 
-    /// Update state for all enabled strategies
+    /// Update state for all enabled strategies, with the price's pair in
+    /// its canonical orientation
     pub async fn update_all(&self, price: &PriceData) {
+        let normalized;
+        let price = if price.pair.is_canonical() {
+            price
+        } else {
+            normalized = price.inverted();
+            &normalized
+        };
         let plugins = self.plugins.read().await;
         for plugin in plugins.iter() {
             if plugin.descriptor().enabled {
@@ -98,6 +114,7 @@ impl StrategyRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_arb_core::types::OpportunitySource;
     use solana_arb_core::types::{DexType, TokenPair};
     use rust_decimal::Decimal;
 
@@ -134,6 +151,10 @@ mod tests {
                  recommended_size: Some(Decimal::new(1000, 0)),
                  detected_at: chrono::Utc::now(),
                  expired_at: None,
+                 buy_quote_at: None,
+                 sell_quote_at: None,
+                 source: OpportunitySource::Strategy,
+                 strategy: Some(self.name().to_string()),
              };
              
              Ok(vec![opp])
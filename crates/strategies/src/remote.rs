@@ -0,0 +1,539 @@
+//! Remote Strategy
+//!
+//! Forwards `analyze` / `update_state` to an external gRPC service so
+//! strategies prototyped in other languages (typically Python) can run in
+//! the live pipeline. The schema is `proto/strategy.proto`.
+//!
+//! The bridge is defensive: every call has a timeout, responses must match
+//! the schema version, returned opportunities are validated, and after
+//! repeated failures the service is skipped for a cool-off period so a slow
+//! or broken strategy cannot stall the trading loop.
+
+use crate::{Strategy, StrategyDescriptor, StrategyPlugin};
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use solana_arb_core::{
+    types::{ArbitrageOpportunity, DexType, OpportunitySource, PriceData, TokenPair},
+    ArbitrageError, ArbitrageResult,
+};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+
+/// Schema version of `proto/strategy.proto` this bot speaks
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Opportunities accepted from a single `Analyze` response
+const MAX_OPPORTUNITIES: usize = 100;
+
+/// Consecutive failures before the service is skipped for a while
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// Messages of `proto/strategy.proto`
+pub mod proto {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Price {
+        #[prost(string, tag = "1")]
+        pub dex: String,
+        #[prost(string, tag = "2")]
+        pub base: String,
+        #[prost(string, tag = "3")]
+        pub quote: String,
+        #[prost(string, tag = "4")]
+        pub bid: String,
+        #[prost(string, tag = "5")]
+        pub ask: String,
+        #[prost(string, tag = "6")]
+        pub mid_price: String,
+        #[prost(string, optional, tag = "7")]
+        pub volume_24h: Option<String>,
+        #[prost(string, optional, tag = "8")]
+        pub liquidity: Option<String>,
+        #[prost(int64, tag = "9")]
+        pub timestamp_ms: i64,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Opportunity {
+        #[prost(string, tag = "1")]
+        pub base: String,
+        #[prost(string, tag = "2")]
+        pub quote: String,
+        #[prost(string, tag = "3")]
+        pub buy_dex: String,
+        #[prost(string, tag = "4")]
+        pub sell_dex: String,
+        #[prost(string, tag = "5")]
+        pub buy_price: String,
+        #[prost(string, tag = "6")]
+        pub sell_price: String,
+        #[prost(string, tag = "7")]
+        pub gross_profit_pct: String,
+        #[prost(string, tag = "8")]
+        pub net_profit_pct: String,
+        #[prost(string, optional, tag = "9")]
+        pub estimated_profit_usd: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HandshakeRequest {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HandshakeResponse {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+        #[prost(string, tag = "2")]
+        pub name: String,
+        #[prost(string, tag = "3")]
+        pub version: String,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AnalyzeRequest {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+        #[prost(message, repeated, tag = "2")]
+        pub prices: Vec<Price>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AnalyzeResponse {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+        #[prost(message, repeated, tag = "2")]
+        pub opportunities: Vec<Opportunity>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct UpdateStateRequest {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+        #[prost(message, optional, tag = "2")]
+        pub price: Option<Price>,
+    }
+
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct UpdateStateResponse {
+        #[prost(uint32, tag = "1")]
+        pub schema_version: u32,
+    }
+}
+
+/// Connection settings for a remote strategy
+#[derive(Debug, Clone)]
+pub struct RemoteStrategyConfig {
+    /// gRPC endpoint, e.g. `http://localhost:50051`
+    pub endpoint: String,
+    /// Deadline for each call
+    pub timeout: Duration,
+    /// How long the service is skipped after repeated failures
+    pub cooloff: Duration,
+}
+
+impl RemoteStrategyConfig {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            timeout: Duration::from_millis(200),
+            cooloff: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RemoteHealth {
+    consecutive_failures: u32,
+    skip_until: Option<Instant>,
+    /// Name and version reported by the handshake
+    identity: Option<(String, String)>,
+    /// Schema version of a service we cannot talk to; set for good
+    incompatible_schema: Option<u32>,
+}
+
+/// [`Strategy`] backed by an external `StrategyService`
+pub struct RemoteStrategy {
+    config: RemoteStrategyConfig,
+    channel: Channel,
+    health: Mutex<RemoteHealth>,
+}
+
+impl RemoteStrategy {
+    /// Create the strategy. The connection is made on first use, so an
+    /// unavailable service does not prevent startup.
+    pub fn new(config: RemoteStrategyConfig) -> ArbitrageResult<Self> {
+        let channel = Endpoint::from_shared(config.endpoint.clone())
+            .map_err(|e| remote_error(format!("invalid endpoint {}: {}", config.endpoint, e)))?
+            .connect_timeout(config.timeout)
+            .timeout(config.timeout)
+            .connect_lazy();
+        Ok(Self {
+            config,
+            channel,
+            health: Mutex::new(RemoteHealth::default()),
+        })
+    }
+
+    /// Check that the service speaks our schema version. Returns its name
+    /// and version.
+    pub async fn handshake(&self) -> ArbitrageResult<(String, String)> {
+        let response: proto::HandshakeResponse = self
+            .call(
+                "/arb.strategy.v1.StrategyService/Handshake",
+                proto::HandshakeRequest {
+                    schema_version: SCHEMA_VERSION,
+                },
+            )
+            .await?;
+        self.check_schema(response.schema_version)?;
+
+        let identity = (response.name, response.version);
+        self.lock_health().identity = Some(identity.clone());
+        Ok(identity)
+    }
+
+    fn lock_health(&self) -> std::sync::MutexGuard<'_, RemoteHealth> {
+        self.health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether calls are being skipped, after repeated failures or because
+    /// the service speaks another schema version
+    pub fn is_suspended(&self) -> bool {
+        let health = self.lock_health();
+        health.incompatible_schema.is_some()
+            || health.skip_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Reject responses with another schema version. A mismatch disables the
+    /// strategy until restart: the service has to be upgraded first.
+    fn check_schema(&self, version: u32) -> ArbitrageResult<()> {
+        check_schema(version).inspect_err(|e| {
+            tracing::error!("Remote strategy {} disabled: {}", self.config.endpoint, e);
+            self.lock_health().incompatible_schema = Some(version);
+        })
+    }
+
+    /// Make a unary call with the configured deadline, tracking failures
+    async fn call<Req, Resp>(&self, path: &'static str, request: Req) -> ArbitrageResult<Resp>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        if self.is_suspended() {
+            return Err(remote_error("skipped after repeated failures".to_string()));
+        }
+
+        let mut grpc = tonic::client::Grpc::new(self.channel.clone());
+        let result = tokio::time::timeout(self.config.timeout, async {
+            grpc.ready()
+                .await
+                .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+            grpc.unary(
+                tonic::Request::new(request),
+                PathAndQuery::from_static(path),
+                tonic::codec::ProstCodec::default(),
+            )
+            .await
+        })
+        .await;
+
+        let result = match result {
+            Ok(Ok(response)) => Ok(response.into_inner()),
+            Ok(Err(status)) => Err(remote_error(format!(
+                "{} failed: {}",
+                path,
+                status.message()
+            ))),
+            Err(_) => Err(ArbitrageError::RpcTimeout {
+                timeout_ms: self.config.timeout.as_millis() as u64,
+            }),
+        };
+
+        let mut health = self.lock_health();
+        match &result {
+            Ok(_) => {
+                health.consecutive_failures = 0;
+                health.skip_until = None;
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                if health.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                    health.skip_until = Some(Instant::now() + self.config.cooloff);
+                    tracing::warn!(
+                        "Remote strategy {} failed {} times ({}), skipping for {}s",
+                        self.config.endpoint,
+                        health.consecutive_failures,
+                        e,
+                        self.config.cooloff.as_secs()
+                    );
+                }
+            }
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Strategy for RemoteStrategy {
+    fn name(&self) -> &'static str {
+        "Remote Strategy (gRPC)"
+    }
+
+    async fn analyze(&self, prices: &[PriceData]) -> ArbitrageResult<Vec<ArbitrageOpportunity>> {
+        let response: proto::AnalyzeResponse = self
+            .call(
+                "/arb.strategy.v1.StrategyService/Analyze",
+                proto::AnalyzeRequest {
+                    schema_version: SCHEMA_VERSION,
+                    prices: prices.iter().map(price_to_proto).collect(),
+                },
+            )
+            .await?;
+        self.check_schema(response.schema_version)?;
+
+        if response.opportunities.len() > MAX_OPPORTUNITIES {
+            tracing::warn!(
+                "Remote strategy returned {} opportunities, keeping the first {}",
+                response.opportunities.len(),
+                MAX_OPPORTUNITIES
+            );
+        }
+        Ok(response
+            .opportunities
+            .iter()
+            .take(MAX_OPPORTUNITIES)
+            .filter_map(|opp| match opportunity_from_proto(opp) {
+                Ok(opp) => Some(opp.attributed_to(self.name())),
+                Err(e) => {
+                    tracing::warn!("Dropping remote opportunity: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn update_state(&self, price: &PriceData) -> ArbitrageResult<()> {
+        // Updates missed while suspended are not worth a warning per price
+        if self.is_suspended() {
+            return Ok(());
+        }
+        let response: proto::UpdateStateResponse = self
+            .call(
+                "/arb.strategy.v1.StrategyService/UpdateState",
+                proto::UpdateStateRequest {
+                    schema_version: SCHEMA_VERSION,
+                    price: Some(price_to_proto(price)),
+                },
+            )
+            .await?;
+        self.check_schema(response.schema_version)
+    }
+}
+
+#[async_trait]
+impl StrategyPlugin for RemoteStrategy {
+    fn descriptor(&self) -> StrategyDescriptor {
+        let (name, version) = self
+            .lock_health()
+            .identity
+            .clone()
+            .unwrap_or_else(|| (self.config.endpoint.clone(), "unknown".to_string()));
+        StrategyDescriptor {
+            name,
+            version,
+            description: format!("Remote strategy at {}", self.config.endpoint),
+            enabled: true,
+        }
+    }
+
+    async fn on_load(&self) -> ArbitrageResult<()> {
+        self.handshake().await.map(|_| ())
+    }
+}
+
+fn remote_error(reason: String) -> ArbitrageError {
+    ArbitrageError::StrategyError {
+        strategy: "remote".to_string(),
+        reason,
+    }
+}
+
+fn check_schema(version: u32) -> ArbitrageResult<()> {
+    if version == SCHEMA_VERSION {
+        Ok(())
+    } else {
+        Err(remote_error(format!(
+            "schema version {} does not match ours ({})",
+            version, SCHEMA_VERSION
+        )))
+    }
+}
+
+pub fn price_to_proto(price: &PriceData) -> proto::Price {
+    proto::Price {
+        dex: price.dex.display_name().to_string(),
+        base: price.pair.base.clone(),
+        quote: price.pair.quote.clone(),
+        bid: price.bid.to_string(),
+        ask: price.ask.to_string(),
+        mid_price: price.mid_price.to_string(),
+        volume_24h: price.volume_24h.map(|v| v.to_string()),
+        liquidity: price.liquidity.map(|v| v.to_string()),
+        timestamp_ms: price.timestamp.timestamp_millis(),
+    }
+}
+
+fn parse_dex(name: &str) -> ArbitrageResult<DexType> {
+    DexType::all()
+        .iter()
+        .find(|dex| dex.display_name().eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| ArbitrageError::InvalidOpportunity(format!("unknown DEX '{}'", name)))
+}
+
+fn parse_decimal(field: &str, value: &str) -> ArbitrageResult<Decimal> {
+    Decimal::from_str(value).map_err(|_| {
+        ArbitrageError::InvalidOpportunity(format!("{} '{}' is not a decimal", field, value))
+    })
+}
+
+/// Validate and convert an opportunity returned by the service
+pub fn opportunity_from_proto(opp: &proto::Opportunity) -> ArbitrageResult<ArbitrageOpportunity> {
+    let invalid = |reason: &str| Err(ArbitrageError::InvalidOpportunity(reason.to_string()));
+
+    let buy_dex = parse_dex(&opp.buy_dex)?;
+    let sell_dex = parse_dex(&opp.sell_dex)?;
+    if buy_dex == sell_dex {
+        return invalid("buy and sell DEX are the same");
+    }
+    if opp.base.is_empty() || opp.quote.is_empty() {
+        return invalid("missing token");
+    }
+
+    let buy_price = parse_decimal("buy_price", &opp.buy_price)?;
+    let sell_price = parse_decimal("sell_price", &opp.sell_price)?;
+    let gross_profit_pct = parse_decimal("gross_profit_pct", &opp.gross_profit_pct)?;
+    let net_profit_pct = parse_decimal("net_profit_pct", &opp.net_profit_pct)?;
+    let estimated_profit_usd = opp
+        .estimated_profit_usd
+        .as_deref()
+        .map(|v| parse_decimal("estimated_profit_usd", v))
+        .transpose()?;
+
+    if buy_price <= Decimal::ZERO || sell_price <= Decimal::ZERO {
+        return invalid("prices must be positive");
+    }
+    if net_profit_pct <= Decimal::ZERO || net_profit_pct > gross_profit_pct {
+        return invalid("net profit must be positive and at most the gross profit");
+    }
+
+    Ok(ArbitrageOpportunity {
+        id: uuid::Uuid::new_v4(),
+        pair: TokenPair::new(opp.base.clone(), opp.quote.clone()),
+        buy_dex,
+        sell_dex,
+        buy_price,
+        sell_price,
+        gross_profit_pct,
+        net_profit_pct,
+        estimated_profit_usd,
+        recommended_size: None,
+        detected_at: Utc::now(),
+        expired_at: None,
+        buy_quote_at: None,
+        sell_quote_at: None,
+        source: OpportunitySource::Strategy,
+        strategy: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity() -> proto::Opportunity {
+        proto::Opportunity {
+            base: "SOL".to_string(),
+            quote: "USDC".to_string(),
+            buy_dex: "orca".to_string(),
+            sell_dex: "Raydium".to_string(),
+            buy_price: "100.0".to_string(),
+            sell_price: "101.5".to_string(),
+            gross_profit_pct: "1.5".to_string(),
+            net_profit_pct: "1.0".to_string(),
+            estimated_profit_usd: Some("10".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_opportunity_validation() {
+        let opp = opportunity_from_proto(&opportunity()).unwrap();
+        assert_eq!(opp.buy_dex, DexType::Orca);
+        assert_eq!(opp.sell_dex, DexType::Raydium);
+        assert_eq!(opp.net_profit_pct, Decimal::ONE);
+        assert_eq!(opp.estimated_profit_usd, Some(Decimal::from(10)));
+
+        let mut bad = opportunity();
+        bad.sell_dex = "Uniswap".to_string();
+        assert!(opportunity_from_proto(&bad).is_err());
+
+        let mut bad = opportunity();
+        bad.net_profit_pct = "2.0".to_string();
+        assert!(opportunity_from_proto(&bad).is_err());
+
+        let mut bad = opportunity();
+        bad.buy_price = "NaN".to_string();
+        assert!(opportunity_from_proto(&bad).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_price_round_trip_and_schema() {
+        let price = PriceData::new(
+            DexType::Meteora,
+            TokenPair::new("SOL", "USDC"),
+            Decimal::from(99),
+            Decimal::from(101),
+        );
+        let encoded = price_to_proto(&price);
+        assert_eq!(encoded.dex, "Meteora");
+        assert_eq!(encoded.mid_price, "100");
+        assert_eq!(encoded.timestamp_ms, price.timestamp.timestamp_millis());
+
+        // Messages survive the wire format
+        let bytes = prost::Message::encode_to_vec(&encoded);
+        let decoded: proto::Price = prost::Message::decode(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, encoded);
+
+        assert!(check_schema(SCHEMA_VERSION).is_ok());
+        assert!(check_schema(SCHEMA_VERSION + 1).is_err());
+
+        let strategy =
+            RemoteStrategy::new(RemoteStrategyConfig::new("http://127.0.0.1:9")).unwrap();
+        assert!(strategy.check_schema(SCHEMA_VERSION + 1).is_err());
+        assert!(strategy.is_suspended());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_service_is_skipped() {
+        let strategy = RemoteStrategy::new(RemoteStrategyConfig {
+            timeout: Duration::from_millis(50),
+            ..RemoteStrategyConfig::new("http://127.0.0.1:9")
+        })
+        .unwrap();
+
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            assert!(strategy.analyze(&[]).await.is_err());
+        }
+        assert!(strategy.is_suspended());
+        assert!(matches!(
+            strategy.analyze(&[]).await,
+            Err(ArbitrageError::StrategyError { .. })
+        ));
+        assert!(RemoteStrategy::new(RemoteStrategyConfig::new("not a url")).is_err());
+    }
+}
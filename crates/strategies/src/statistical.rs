@@ -3,18 +3,24 @@ use async_trait::async_trait;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use solana_arb_core::{
-    types::{ArbitrageOpportunity, DexType, PriceData},
+    memory::MemoryUsage,
+    types::{ArbitrageOpportunity, DexType, OpportunitySource, PriceData},
     ArbitrageResult,
 };
 use std::collections::VecDeque;
 use tokio::sync::RwLock;
 
+/// Pairs with a price history; past this the least recently updated pair's
+/// history is dropped, so pairs that stop trading do not pile up
+pub const MAX_PAIRS: usize = 512;
+
 pub struct StatisticalArbitrage {
     // Sliding window of price ratios for pairs
     // Key: Pair symbol, Value: Queue of (price_ratio, timestamp)
     history: RwLock<std::collections::HashMap<String, VecDeque<(Decimal, i64)>>>,
     window_size: usize,
     z_score_threshold: Decimal,
+    max_pairs: usize,
 }
 
 impl StatisticalArbitrage {
@@ -23,9 +29,16 @@ impl StatisticalArbitrage {
             history: RwLock::new(std::collections::HashMap::new()),
             window_size,
             z_score_threshold,
+            max_pairs: MAX_PAIRS,
         }
     }
 
+    /// Keep price histories for at most `max_pairs` pairs
+    pub fn with_max_pairs(mut self, max_pairs: usize) -> Self {
+        self.max_pairs = max_pairs.max(1);
+        self
+    }
+
     fn calculate_z_score(
         &self,
         value: Decimal,
@@ -70,6 +83,16 @@ impl Strategy for StatisticalArbitrage {
         let mut history = self.history.write().await;
         let pair_symbol = price.pair.symbol();
 
+        if !history.contains_key(&pair_symbol) && history.len() >= self.max_pairs {
+            let stalest = history
+                .iter()
+                .min_by_key(|(_, queue)| queue.back().map(|(_, at)| *at))
+                .map(|(pair, _)| pair.clone());
+            if let Some(pair) = stalest {
+                tracing::debug!("Dropping price history of {} (pair limit {})", pair, self.max_pairs);
+                history.remove(&pair);
+            }
+        }
         let entry = history.entry(pair_symbol).or_insert_with(VecDeque::new);
         entry.push_back((price.mid_price, price.timestamp.timestamp()));
 
@@ -80,6 +103,24 @@ impl Strategy for StatisticalArbitrage {
         Ok(())
     }
 
+    /// Ready once one pair has a full window; pairs still short of theirs
+    /// give no signal until they do
+    async fn warm_up_progress(&self) -> f64 {
+        let fullest = self.history.read().await.values().map(VecDeque::len).max();
+        match (fullest, self.window_size) {
+            (_, 0) => 1.0,
+            (Some(len), window) => (len as f64 / window as f64).min(1.0),
+            (None, _) => 0.0,
+        }
+    }
+
+    async fn memory_usage(&self) -> MemoryUsage {
+        let history = self.history.read().await;
+        let samples = history.values().map(VecDeque::len).sum();
+        let keys: usize = history.keys().map(|pair| pair.capacity()).sum();
+        MemoryUsage::of::<(Decimal, i64)>(samples, 0).with_bytes(keys)
+    }
+
     async fn analyze(&self, prices: &[PriceData]) -> ArbitrageResult<Vec<ArbitrageOpportunity>> {
         let history = self.history.read().await;
         let mut opportunities = Vec::new();
@@ -113,6 +154,8 @@ impl Strategy for StatisticalArbitrage {
                             // Price is low: buy on current DEX at ask price, expect to sell at mean
                             (price.dex, DexType::Jupiter, price.ask, mean)
                         };
+                        // Only the current DEX's side is a quote; the other is the mean
+                        let quoted_at = |dex| (dex == price.dex).then_some(price.timestamp);
 
                         // Gross profit as percentage of buy price
                         let gross_profit_pct = if buy_price.is_zero() {
@@ -148,6 +191,10 @@ impl Strategy for StatisticalArbitrage {
                                 recommended_size: Some(recommended_size),
                                 detected_at: chrono::Utc::now(),
                                 expired_at: None,
+                                buy_quote_at: quoted_at(buy_dex),
+                                sell_quote_at: quoted_at(sell_dex),
+                                source: OpportunitySource::Strategy,
+                                strategy: Some(self.name().to_string()),
                             };
 
                             tracing::info!(
@@ -212,9 +259,31 @@ mod tests {
         assert!(z.unwrap() > Decimal::from(2), "Z-score should be > 2 for outlier");
     }
 
+    #[tokio::test]
+    async fn test_history_drops_stalest_pair_past_limit() {
+        let strat = StatisticalArbitrage::new(5, Decimal::from(2)).with_max_pairs(2);
+        for (i, base) in ["SOL", "RAY", "JUP"].iter().enumerate() {
+            let mut price = PriceData::new(
+                DexType::Raydium,
+                TokenPair::new(*base, "USDC"),
+                Decimal::ONE,
+                Decimal::ONE,
+            );
+            price.timestamp = chrono::DateTime::from_timestamp(i as i64, 0).unwrap();
+            strat.update_state(&price).await.unwrap();
+        }
+
+        let history = strat.history.read().await;
+        assert!(!history.contains_key("SOL/USDC"));
+        assert!(history.contains_key("JUP/USDC"));
+        drop(history);
+        assert_eq!(strat.memory_usage().await.entries, 2);
+    }
+
     #[tokio::test]
     async fn test_analyze_creates_opportunity_above_threshold() {
         let strat = StatisticalArbitrage::new(5, Decimal::from(2));
+        assert_eq!(strat.warm_up_progress().await, 0.0);
 
         // Seed history with slightly varying prices so variance is nonzero
         for &v in &[99.0, 100.0, 101.0, 100.5, 99.5] {
@@ -228,6 +297,8 @@ mod tests {
             strat.update_state(&price).await.unwrap();
         }
 
+        assert!(strat.is_ready().await);
+
         // Now present a far-outlier price
         let outlier = PriceData::new(
             DexType::Raydium,
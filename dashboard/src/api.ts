@@ -34,12 +34,12 @@ export const api = {
         if (params?.minProfit) query.set('min_profit', params.minProfit.toString());
         if (params?.limit) query.set('limit', params.limit.toString());
         const queryStr = query.toString();
-        return fetchApi(`/api/opportunities${queryStr ? `?${queryStr}` : ''}`);
+        return fetchApi(`/api/v1/opportunities${queryStr ? `?${queryStr}` : ''}`);
     },
 
     // Get single opportunity
     async getOpportunity(id: string): Promise<ApiResponse<ArbitrageOpportunity>> {
-        return fetchApi(`/api/opportunities/${id}`);
+        return fetchApi(`/api/v1/opportunities/${id}`);
     },
 
     // Get prices
@@ -51,21 +51,21 @@ export const api = {
         if (params?.base) query.set('base', params.base);
         if (params?.quote) query.set('quote', params.quote);
         const queryStr = query.toString();
-        return fetchApi(`/api/prices${queryStr ? `?${queryStr}` : ''}`);
+        return fetchApi(`/api/v1/prices${queryStr ? `?${queryStr}` : ''}`);
     },
 
     // Get pair prices
     async getPairPrices(pair: string): Promise<ApiResponse<PriceData[]>> {
-        return fetchApi(`/api/prices/${pair}`);
+        return fetchApi(`/api/v1/prices/${pair}`);
     },
 
     // Get config
     async getConfig(): Promise<ApiResponse<Config>> {
-        return fetchApi('/api/config');
+        return fetchApi('/api/v1/config');
     },
 
     // Get bot status
     async getStatus(): Promise<ApiResponse<StatusData>> {
-        return fetchApi('/api/status');
+        return fetchApi('/api/v1/status');
     },
 };
@@ -60,4 +60,5 @@ export interface StatusData {
     heartbeat_count: number;
     last_scan_at: string;
     dex_health: DexHealth[];
+    max_price_age_seconds: number;
 }
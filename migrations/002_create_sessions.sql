@@ -0,0 +1,19 @@
+-- Bot sessions: one row per run
+CREATE TABLE IF NOT EXISTS sessions (
+    session_id VARCHAR(64) PRIMARY KEY,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,
+    mode VARCHAR(20) NOT NULL,  -- live, dry-run, watch-only
+    config_snapshot JSONB NOT NULL,
+    git_rev VARCHAR(64)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_started ON sessions (started_at DESC);
+
+-- Tag trades with the session that executed them
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS session_id VARCHAR(64);
+CREATE INDEX IF NOT EXISTS idx_trades_session ON trades (session_id, time DESC);
+
+-- Trades are recorded without a matching opportunities row (and hypertables
+-- cannot be referenced by foreign keys), so drop the reference
+ALTER TABLE trades DROP CONSTRAINT IF EXISTS trades_opportunity_id_fkey;
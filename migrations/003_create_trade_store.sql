@@ -0,0 +1,55 @@
+-- Trade store: the trade history, traded opportunities and risk events,
+-- queryable without parsing the JSONL history files. Applied by the bot at
+-- startup (TradeStore::migrate), so every statement must be idempotent.
+
+-- One row per trade history record, as written to the history file
+CREATE TABLE IF NOT EXISTS trade_records (
+    id UUID PRIMARY KEY,
+    time TIMESTAMPTZ NOT NULL,
+    session_id VARCHAR(64) NOT NULL,
+    trade_type VARCHAR(20) NOT NULL,  -- SIMULATION, REAL, REPAIR, COMMITMENT_UPDATE
+    pair VARCHAR(50) NOT NULL,
+    buy_dex VARCHAR(20) NOT NULL,
+    sell_dex VARCHAR(20) NOT NULL,
+    size_usd NUMERIC NOT NULL,
+    profit_usd NUMERIC NOT NULL,
+    profit_pct NUMERIC NOT NULL,
+    signature VARCHAR(100),
+    success BOOLEAN NOT NULL,
+    error TEXT,
+    failure_kind VARCHAR(30),
+    commitment VARCHAR(20),
+    source VARCHAR(100),
+    record JSONB NOT NULL  -- the full record, including fields not broken out above
+);
+
+CREATE INDEX IF NOT EXISTS idx_trade_records_time ON trade_records (time DESC);
+CREATE INDEX IF NOT EXISTS idx_trade_records_signature ON trade_records (signature);
+CREATE INDEX IF NOT EXISTS idx_trade_records_session ON trade_records (session_id, time DESC);
+
+-- Opportunities that reached execution
+CREATE TABLE IF NOT EXISTS opportunity_records (
+    id UUID PRIMARY KEY,
+    detected_at TIMESTAMPTZ NOT NULL,
+    pair VARCHAR(50) NOT NULL,
+    buy_dex VARCHAR(20) NOT NULL,
+    sell_dex VARCHAR(20) NOT NULL,
+    buy_price NUMERIC NOT NULL,
+    sell_price NUMERIC NOT NULL,
+    gross_profit_pct NUMERIC NOT NULL,
+    net_profit_pct NUMERIC NOT NULL,
+    source VARCHAR(100) NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_opportunity_records_time ON opportunity_records (detected_at DESC);
+
+-- Risk events from the event bus (breaker changes, limit breaches, reorgs, ...)
+CREATE TABLE IF NOT EXISTS risk_events (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMPTZ NOT NULL,
+    session_id VARCHAR(64) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    detail JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_risk_events_time ON risk_events (time DESC);
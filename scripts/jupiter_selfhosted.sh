@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+# Start a self-hosted Jupiter v6 swap API in Docker and wait until it quotes.
+#
+# Usage: JUPITER_RPC_URL=https://your-rpc ./scripts/jupiter_selfhosted.sh
+#
+# JUPITER_IMAGE  image to run (build or pull jupiter-swap-api yourself)
+# JUPITER_PORT   host port to expose (default 8081)
+# JUPITER_RPC_URL  RPC the API loads markets from; use a paid RPC, the public
+#                  one rate-limits the initial market load
+set -euo pipefail
+
+IMAGE="${JUPITER_IMAGE:-jupiter-swap-api:latest}"
+PORT="${JUPITER_PORT:-8081}"
+RPC_URL="${JUPITER_RPC_URL:-https://api.mainnet-beta.solana.com}"
+NAME="solana-arb-jupiter"
+TIMEOUT_SECS="${JUPITER_START_TIMEOUT_SECS:-300}"
+
+if docker ps -a --format '{{.Names}}' | grep -qx "$NAME"; then
+    echo "Removing existing $NAME container"
+    docker rm -f "$NAME" >/dev/null
+fi
+
+echo "Starting $IMAGE on port $PORT"
+docker run -d --name "$NAME" --restart unless-stopped -p "$PORT:8080" "$IMAGE" \
+    --rpc-url "$RPC_URL" --host 0.0.0.0 --port 8080 >/dev/null
+
+# Same probe the bot uses: 1 SOL -> USDC
+PROBE="http://localhost:$PORT/quote?inputMint=So11111111111111111111111111111111111111112&outputMint=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v&amount=1000000000&slippageBps=50"
+
+echo "Waiting for markets to load (up to ${TIMEOUT_SECS}s)..."
+for ((i = 0; i < TIMEOUT_SECS; i += 5)); do
+    if curl -fsS "$PROBE" 2>/dev/null | grep -q outAmount; then
+        echo "Jupiter API ready. Set JUPITER_API_URL=http://localhost:$PORT"
+        exit 0
+    fi
+    sleep 5
+done
+
+echo "Jupiter API did not become ready; check: docker logs $NAME" >&2
+exit 1